@@ -12,7 +12,7 @@ use jsonschema::JSONSchema;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, BytesOrString};
 use smart_default::SmartDefault;
-use strum::{Display, EnumString};
+use strum::{Display, EnumIter, EnumString};
 use utoipa::{schema, ToSchema};
 
 pub type ExtractionGraphId = String;
@@ -25,6 +25,18 @@ pub struct ExtractionGraph {
     pub name: ExtractionGraphName,
     pub namespace: String,
     pub extraction_policies: Vec<ExtractionPolicy>,
+    // Root content of the graph that can have unfinished tasks at the same
+    // time. Content ingested beyond the cap waits until other content finishes
+    #[serde(default)]
+    pub max_active_content: Option<u32>,
+    // Tasks of the graph can wait, so they are placed on the cheapest
+    // executors with room instead of the least loaded ones
+    #[serde(default)]
+    pub latency_tolerant: bool,
+    // How the tasks of a root content are placed across the zones of the
+    // executors
+    #[serde(default)]
+    pub zone_placement: ZonePlacement,
 }
 
 impl From<ExtractionGraph> for indexify_coordinator::ExtractionGraph {
@@ -38,6 +50,9 @@ impl From<ExtractionGraph> for indexify_coordinator::ExtractionGraph {
                 .into_iter()
                 .map(|p| p.into())
                 .collect(),
+            max_active_content: value.max_active_content.unwrap_or_default(),
+            latency_tolerant: value.latency_tolerant,
+            zone_placement: value.zone_placement.to_string(),
         }
     }
 }
@@ -71,10 +86,870 @@ impl ExtractionGraphBuilder {
             name,
             namespace,
             extraction_policies,
+            max_active_content: self.max_active_content.flatten(),
+            latency_tolerant: self.latency_tolerant.unwrap_or_default(),
+            zone_placement: self.zone_placement.unwrap_or_default(),
         })
     }
 }
 
+/// How the tasks of a root content and of the content extracted from it are
+/// placed across the zones executors registered with
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ZonePlacement {
+    /// Zones are ignored
+    #[default]
+    Any,
+    /// Tasks go to the zone with room that ran the fewest tasks of the
+    /// content, so an outage of a zone doesn't stall all of it
+    Spread,
+    /// Tasks stay in the zone the first task of the content went to, which
+    /// avoids moving data across zones. They wait while the executors of the
+    /// zone are full.
+    SameZone,
+}
+
+/// Who changed the definition of an extraction graph and why
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ExtractionGraphChange {
+    pub author: String,
+    pub summary: String,
+    pub created_at: u64,
+}
+
+/// A definition of an extraction graph in the history of the graph
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExtractionGraphVersion {
+    pub version: u64,
+    pub extraction_graph: ExtractionGraph,
+    pub author: String,
+    pub summary: String,
+    pub created_at: u64,
+}
+
+impl From<ExtractionGraphVersion> for indexify_coordinator::ExtractionGraphVersion {
+    fn from(value: ExtractionGraphVersion) -> Self {
+        Self {
+            version: value.version,
+            extraction_graph: Some(value.extraction_graph.into()),
+            author: value.author,
+            created_at: value.created_at,
+            summary: value.summary,
+        }
+    }
+}
+
+/// A parameter of a graph template
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct GraphTemplateParameter {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Value of the parameter when an instantiation doesn't set it. The
+    /// parameter is required without one.
+    #[serde(default)]
+    pub default: Option<serde_json::Value>,
+}
+
+/// Definition of an extraction graph with `{{ parameter }}` placeholders in
+/// its strings, like model names, chunk sizes or output tables, that concrete
+/// graphs are instantiated from
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct GraphTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub parameters: Vec<GraphTemplateParameter>,
+    /// The extraction graph definition. A string that is only a placeholder
+    /// takes the value of the parameter as is, so numbers and objects can be
+    /// parameters too, and placeholders within a string are replaced by the
+    /// value as text.
+    pub graph: serde_json::Value,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub created_at: u64,
+}
+
+enum TemplatePart<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Splits a string into text and the names of its `{{ name }}` placeholders.
+/// An unclosed `{{` is text.
+fn template_parts(mut s: &str) -> Vec<TemplatePart<'_>> {
+    let mut parts = Vec::new();
+    while let Some(start) = s.find("{{") {
+        let Some(len) = s[start + 2..].find("}}") else {
+            break;
+        };
+        if start > 0 {
+            parts.push(TemplatePart::Text(&s[..start]));
+        }
+        parts.push(TemplatePart::Placeholder(
+            s[start + 2..start + 2 + len].trim(),
+        ));
+        s = &s[start + 4 + len..];
+    }
+    if !s.is_empty() {
+        parts.push(TemplatePart::Text(s));
+    }
+    parts
+}
+
+/// Applies `f` to the strings of a JSON value, keys of objects aside
+fn map_template_strings(
+    value: &serde_json::Value,
+    f: &mut impl FnMut(&str) -> Result<serde_json::Value>,
+) -> Result<serde_json::Value> {
+    Ok(match value {
+        serde_json::Value::String(s) => f(s)?,
+        serde_json::Value::Array(values) => serde_json::Value::Array(
+            values
+                .iter()
+                .map(|value| map_template_strings(value, f))
+                .collect::<Result<_>>()?,
+        ),
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(key, value)| Ok((key.clone(), map_template_strings(value, f)?)))
+                .collect::<Result<_>>()?,
+        ),
+        value => value.clone(),
+    })
+}
+
+impl GraphTemplate {
+    /// Checks that the parameters are unique and that every placeholder is a
+    /// parameter and every parameter is used
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty() {
+            return Err(anyhow!("graph template name is empty"));
+        }
+        if !self.graph.is_object() {
+            return Err(anyhow!(
+                "graph of template {} is not an extraction graph definition",
+                self.name
+            ));
+        }
+        let mut parameters = HashSet::new();
+        for parameter in &self.parameters {
+            if parameter.name.is_empty() || !parameters.insert(parameter.name.as_str()) {
+                return Err(anyhow!(
+                    "parameter \"{}\" of graph template {} is empty or declared twice",
+                    parameter.name,
+                    self.name
+                ));
+            }
+        }
+        let mut used = HashSet::new();
+        map_template_strings(&self.graph, &mut |s| {
+            for part in template_parts(s) {
+                if let TemplatePart::Placeholder(name) = part {
+                    if !parameters.contains(name) {
+                        return Err(anyhow!(
+                            "placeholder {{{{ {} }}}} of graph template {} is not a parameter",
+                            name,
+                            self.name
+                        ));
+                    }
+                    used.insert(name.to_string());
+                }
+            }
+            Ok(serde_json::Value::Null)
+        })?;
+        if let Some(unused) = self.parameters.iter().find(|p| !used.contains(&p.name)) {
+            return Err(anyhow!(
+                "parameter {} of graph template {} is not used",
+                unused.name,
+                self.name
+            ));
+        }
+        Ok(())
+    }
+
+    /// The graph definition with the placeholders replaced by `values`, or
+    /// the defaults of the parameters `values` doesn't set
+    pub fn render(&self, values: &HashMap<String, serde_json::Value>) -> Result<serde_json::Value> {
+        if let Some(unknown) = values
+            .keys()
+            .find(|name| !self.parameters.iter().any(|p| &p.name == *name))
+        {
+            return Err(anyhow!(
+                "graph template {} has no parameter {}",
+                self.name,
+                unknown
+            ));
+        }
+        let mut resolved = HashMap::new();
+        for parameter in &self.parameters {
+            let value = values
+                .get(&parameter.name)
+                .or(parameter.default.as_ref())
+                .ok_or_else(|| {
+                    anyhow!(
+                        "parameter {} of graph template {} is required",
+                        parameter.name,
+                        self.name
+                    )
+                })?;
+            resolved.insert(parameter.name.as_str(), value);
+        }
+        let value_of = |name: &str| {
+            resolved.get(name).copied().ok_or_else(|| {
+                anyhow!(
+                    "placeholder {{{{ {} }}}} of graph template {} is not a parameter",
+                    name,
+                    self.name
+                )
+            })
+        };
+        map_template_strings(&self.graph, &mut |s| {
+            let parts = template_parts(s);
+            if let [TemplatePart::Placeholder(name)] = parts.as_slice() {
+                return Ok(value_of(name)?.clone());
+            }
+            let mut rendered = String::new();
+            for part in parts {
+                match part {
+                    TemplatePart::Text(text) => rendered.push_str(text),
+                    TemplatePart::Placeholder(name) => match value_of(name)? {
+                        serde_json::Value::String(value) => rendered.push_str(value),
+                        value => rendered.push_str(&value.to_string()),
+                    },
+                }
+            }
+            Ok(serde_json::Value::String(rendered))
+        })
+    }
+}
+
+impl From<GraphTemplate> for indexify_coordinator::GraphTemplate {
+    fn from(value: GraphTemplate) -> Self {
+        Self {
+            name: value.name,
+            description: value.description,
+            parameters: value
+                .parameters
+                .into_iter()
+                .map(|parameter| indexify_coordinator::GraphTemplateParameter {
+                    name: parameter.name,
+                    description: parameter.description,
+                    default_value: parameter
+                        .default
+                        .map(|value| value.to_string())
+                        .unwrap_or_default(),
+                })
+                .collect(),
+            graph: value.graph.to_string(),
+            author: value.author,
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl TryFrom<indexify_coordinator::GraphTemplate> for GraphTemplate {
+    type Error = anyhow::Error;
+
+    fn try_from(value: indexify_coordinator::GraphTemplate) -> Result<Self> {
+        Ok(Self {
+            name: value.name,
+            description: value.description,
+            parameters: value
+                .parameters
+                .into_iter()
+                .map(|parameter| {
+                    Ok(GraphTemplateParameter {
+                        name: parameter.name,
+                        description: parameter.description,
+                        default: Some(parameter.default_value)
+                            .filter(|value| !value.is_empty())
+                            .map(|value| serde_json::from_str(&value))
+                            .transpose()?,
+                    })
+                })
+                .collect::<Result<_>>()?,
+            graph: serde_json::from_str(&value.graph)?,
+            author: value.author,
+            created_at: value.created_at,
+        })
+    }
+}
+
+/// Resources the tasks of an extraction policy used
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExtractionPolicyUsage {
+    /// Finished tasks that reported their usage
+    pub tasks: u64,
+    pub execution_secs: f64,
+    pub cpu_secs: f64,
+    /// Peak memory of the most memory hungry task
+    pub max_memory_bytes: u64,
+}
+
+impl ExtractionPolicyUsage {
+    pub fn add(&mut self, usage: &TaskUsage) {
+        self.tasks += 1;
+        self.execution_secs += usage.execution_secs;
+        self.cpu_secs += usage.cpu_secs;
+        self.max_memory_bytes = self.max_memory_bytes.max(usage.max_memory_bytes);
+    }
+}
+
+/// Resources used by the tasks of a version of an extraction graph, by
+/// extraction policy. Tasks count towards the version that was current when
+/// they finished.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExtractionGraphUsage {
+    pub namespace: String,
+    pub extraction_graph: ExtractionGraphName,
+    pub version: u64,
+    pub extraction_policies: HashMap<ExtractionPolicyName, ExtractionPolicyUsage>,
+}
+
+impl ExtractionGraphUsage {
+    /// Usage is ordered by version in the state store
+    pub fn key(extraction_graph_id: &str, version: u64) -> String {
+        format!("{}/{:020}", extraction_graph_id, version)
+    }
+}
+
+impl From<ExtractionPolicyUsage> for indexify_coordinator::ExtractionPolicyUsage {
+    fn from(value: ExtractionPolicyUsage) -> Self {
+        Self {
+            tasks: value.tasks,
+            execution_secs: value.execution_secs,
+            cpu_secs: value.cpu_secs,
+            max_memory_bytes: value.max_memory_bytes,
+        }
+    }
+}
+
+impl From<indexify_coordinator::ExtractionPolicyUsage> for ExtractionPolicyUsage {
+    fn from(value: indexify_coordinator::ExtractionPolicyUsage) -> Self {
+        Self {
+            tasks: value.tasks,
+            execution_secs: value.execution_secs,
+            cpu_secs: value.cpu_secs,
+            max_memory_bytes: value.max_memory_bytes,
+        }
+    }
+}
+
+impl From<ExtractionGraphUsage> for indexify_coordinator::ExtractionGraphUsage {
+    fn from(value: ExtractionGraphUsage) -> Self {
+        Self {
+            namespace: value.namespace,
+            extraction_graph: value.extraction_graph,
+            version: value.version,
+            extraction_policies: value
+                .extraction_policies
+                .into_iter()
+                .map(|(name, usage)| (name, usage.into()))
+                .collect(),
+        }
+    }
+}
+
+impl From<indexify_coordinator::ExtractionGraphUsage> for ExtractionGraphUsage {
+    fn from(value: indexify_coordinator::ExtractionGraphUsage) -> Self {
+        Self {
+            namespace: value.namespace,
+            extraction_graph: value.extraction_graph,
+            version: value.version,
+            extraction_policies: value
+                .extraction_policies
+                .into_iter()
+                .map(|(name, usage)| (name, usage.into()))
+                .collect(),
+        }
+    }
+}
+
+/// Time and estimated cost of the tasks of an extraction policy on a content
+/// tree
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExtractionPolicyBreakdown {
+    pub extraction_graph: ExtractionGraphName,
+    pub extraction_policy: ExtractionPolicyName,
+    pub tasks: u64,
+    /// Tasks that are still pending or running
+    pub unfinished_tasks: u64,
+    /// When the first task was created, in seconds since the epoch
+    pub started_at: u64,
+    /// When the last task finished, or the time of the breakdown while tasks
+    /// are unfinished
+    pub finished_at: u64,
+    /// Time the tasks waited for an executor
+    pub queue_secs: u64,
+    /// Time the extractors ran for, as reported by executors, or the time the
+    /// tasks were assigned for when they didn't report it
+    pub execution_secs: f64,
+    /// Execution time priced at the cost per hour of the executors that ran
+    /// the tasks
+    pub estimated_cost: f64,
+    /// Tasks that ran on executors without a cost, or on executors that are
+    /// gone, left out of the estimated cost
+    pub unpriced_tasks: u64,
+}
+
+impl ExtractionPolicyBreakdown {
+    /// Adds a task with its history. `cost_per_hour` is the cost of the
+    /// executor the task was last assigned to.
+    pub fn add(
+        &mut self,
+        task: &Task,
+        transitions: &[TaskTransition],
+        cost_per_hour: impl Fn(&str) -> Option<f64>,
+        now: u64,
+    ) {
+        let mut queue_secs = 0;
+        let mut assigned_secs = 0;
+        let mut executor_id = None;
+        for (transition, next) in transitions.iter().zip(
+            transitions
+                .iter()
+                .skip(1)
+                .map(|next| next.at)
+                .chain(std::iter::once(now)),
+        ) {
+            let secs = next.saturating_sub(transition.at);
+            match transition.state {
+                TaskState::Pending => queue_secs += secs,
+                TaskState::Running => {
+                    assigned_secs += secs;
+                    executor_id = transition.executor_id.as_deref();
+                }
+                TaskState::Finalized => {}
+            }
+        }
+        let started_at = transitions.first().map_or(now, |transition| transition.at);
+        let finished_at = match transitions.last() {
+            Some(last) if last.state == TaskState::Finalized => last.at,
+            _ => {
+                self.unfinished_tasks += 1;
+                now
+            }
+        };
+        let execution_secs = task
+            .usage
+            .map_or(assigned_secs as f64, |usage| usage.execution_secs);
+
+        self.started_at = if self.tasks == 0 {
+            started_at
+        } else {
+            self.started_at.min(started_at)
+        };
+        self.finished_at = self.finished_at.max(finished_at);
+        self.tasks += 1;
+        self.queue_secs += queue_secs;
+        self.execution_secs += execution_secs;
+        match executor_id
+            .and_then(cost_per_hour)
+            .filter(|cost| *cost > 0.0)
+        {
+            Some(cost_per_hour) => self.estimated_cost += execution_secs * cost_per_hour / 3600.0,
+            None if execution_secs > 0.0 => self.unpriced_tasks += 1,
+            None => {}
+        }
+    }
+}
+
+/// Where the time and cost of an invocation, a root content and the content
+/// extracted from it, went, by extraction policy
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct InvocationBreakdown {
+    pub namespace: String,
+    pub content_id: String,
+    /// When the first task was created, in seconds since the epoch
+    pub started_at: u64,
+    /// When the last task finished, or the time of the breakdown while tasks
+    /// are unfinished
+    pub finished_at: u64,
+    /// Ordered by when their first task was created
+    pub extraction_policies: Vec<ExtractionPolicyBreakdown>,
+}
+
+impl From<ExtractionPolicyBreakdown> for indexify_coordinator::ExtractionPolicyBreakdown {
+    fn from(value: ExtractionPolicyBreakdown) -> Self {
+        Self {
+            extraction_graph: value.extraction_graph,
+            extraction_policy: value.extraction_policy,
+            tasks: value.tasks,
+            unfinished_tasks: value.unfinished_tasks,
+            started_at: value.started_at,
+            finished_at: value.finished_at,
+            queue_secs: value.queue_secs,
+            execution_secs: value.execution_secs,
+            estimated_cost: value.estimated_cost,
+            unpriced_tasks: value.unpriced_tasks,
+        }
+    }
+}
+
+impl From<indexify_coordinator::ExtractionPolicyBreakdown> for ExtractionPolicyBreakdown {
+    fn from(value: indexify_coordinator::ExtractionPolicyBreakdown) -> Self {
+        Self {
+            extraction_graph: value.extraction_graph,
+            extraction_policy: value.extraction_policy,
+            tasks: value.tasks,
+            unfinished_tasks: value.unfinished_tasks,
+            started_at: value.started_at,
+            finished_at: value.finished_at,
+            queue_secs: value.queue_secs,
+            execution_secs: value.execution_secs,
+            estimated_cost: value.estimated_cost,
+            unpriced_tasks: value.unpriced_tasks,
+        }
+    }
+}
+
+impl From<InvocationBreakdown> for indexify_coordinator::InvocationBreakdown {
+    fn from(value: InvocationBreakdown) -> Self {
+        Self {
+            namespace: value.namespace,
+            content_id: value.content_id,
+            started_at: value.started_at,
+            finished_at: value.finished_at,
+            extraction_policies: value
+                .extraction_policies
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+}
+
+impl From<indexify_coordinator::InvocationBreakdown> for InvocationBreakdown {
+    fn from(value: indexify_coordinator::InvocationBreakdown) -> Self {
+        Self {
+            namespace: value.namespace,
+            content_id: value.content_id,
+            started_at: value.started_at,
+            finished_at: value.finished_at,
+            extraction_policies: value
+                .extraction_policies
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+}
+
+/// How the scheduler picks the executor of a task among the executors of its
+/// extractor
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum PlacementStrategy {
+    /// The least loaded executor, or the least loaded executor that recently
+    /// ran the extraction policy of a task with an expensive warm up
+    #[default]
+    PreferWarm,
+    /// The least loaded executor
+    LeastLoaded,
+}
+
+/// Scheduler parameters that can be changed while the cluster runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SchedulerSettings {
+    #[serde(default)]
+    pub placement_strategy: PlacementStrategy,
+    /// Tasks an executor is assigned at most, unlimited when unset. Tasks over
+    /// the limit stay unassigned until executors finish tasks.
+    #[serde(default)]
+    pub max_tasks_per_executor: Option<u32>,
+    /// Share of the free executor slots the tasks of a namespace get when
+    /// tasks of several namespaces wait for them. Namespaces without a weight
+    /// have a weight of 1.
+    #[serde(default)]
+    pub namespace_weights: HashMap<String, u32>,
+    /// Garbage collection tasks that can be pending at once, unlimited when
+    /// unset. Deletions and label updates wait while there are more.
+    #[serde(default)]
+    pub max_pending_gc_tasks: Option<u32>,
+    /// Executor slots held back for the tasks of a namespace or graph during
+    /// a window. They need a limit of tasks per executor to count slots.
+    #[serde(default)]
+    pub capacity_reservations: Vec<CapacityReservation>,
+    /// Factors by which executors of an extractor may exceed the limit of
+    /// tasks per executor while every executor of the extractor is at the
+    /// limit. Extractors without a factor have a factor of 1.
+    #[serde(default)]
+    pub overcommit_factors: HashMap<String, f64>,
+    /// Seconds executors that registered their download bandwidth may take
+    /// to download the input of a task, unlimited when unset. Tasks go to
+    /// faster executors, or wait for one.
+    #[serde(default)]
+    pub max_input_download_secs: Option<u32>,
+    /// Tasks of an extraction policy an executor gets at most in one bundle,
+    /// tasks aren't bundled when unset
+    #[serde(default)]
+    pub max_bundle_tasks: Option<u32>,
+    /// Input size of the tasks of a bundle at most, unlimited when unset.
+    /// Tasks with larger inputs are sent on their own.
+    #[serde(default)]
+    pub max_bundle_bytes: Option<u64>,
+    /// Milliseconds a bundle that isn't full waits for more tasks before it
+    /// is sent anyway
+    #[serde(default)]
+    pub max_bundle_latency_ms: Option<u32>,
+}
+
+impl SchedulerSettings {
+    pub fn namespace_weight(&self, namespace: &str) -> u32 {
+        self.namespace_weights.get(namespace).copied().unwrap_or(1)
+    }
+
+    /// Tasks an executor of an extractor is assigned at most, counting the
+    /// burst capacity above the limit of tasks per executor
+    pub fn burst_tasks_per_executor(&self, extractor: &str) -> Option<u32> {
+        let max_tasks = self.max_tasks_per_executor?;
+        let factor = self
+            .overcommit_factors
+            .get(extractor)
+            .copied()
+            .unwrap_or(1.0);
+        Some((max_tasks as f64 * factor).floor() as u32)
+    }
+
+    pub fn active_reservations(&self, now: u64) -> impl Iterator<Item = &CapacityReservation> {
+        self.capacity_reservations
+            .iter()
+            .filter(move |reservation| reservation.is_active(now))
+    }
+}
+
+/// Slots reserved ahead of a known batch, like a nightly ingest. While the
+/// reservation is active, tasks of other namespaces and graphs only take the
+/// free slots of the executors of its extractors that it doesn't need.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CapacityReservation {
+    pub namespace: String,
+    /// Only the tasks of this graph of the namespace use the slots, those of
+    /// every graph of the namespace do when unset
+    #[serde(default)]
+    pub extraction_graph: Option<String>,
+    pub slots: u32,
+    /// Start and end of the window, in seconds since the epoch
+    pub start: u64,
+    pub end: u64,
+}
+
+impl CapacityReservation {
+    pub fn is_active(&self, now: u64) -> bool {
+        self.start <= now && now < self.end
+    }
+
+    /// Whether the tasks of a graph of a namespace use the reserved slots
+    pub fn matches(&self, namespace: &str, extraction_graph: &str) -> bool {
+        self.namespace == namespace &&
+            self.extraction_graph
+                .as_ref()
+                .map_or(true, |graph| graph == extraction_graph)
+    }
+}
+
+impl From<CapacityReservation> for indexify_coordinator::CapacityReservation {
+    fn from(value: CapacityReservation) -> Self {
+        Self {
+            namespace: value.namespace,
+            extraction_graph: value.extraction_graph.unwrap_or_default(),
+            slots: value.slots,
+            start: value.start,
+            end: value.end,
+        }
+    }
+}
+
+impl From<indexify_coordinator::CapacityReservation> for CapacityReservation {
+    fn from(value: indexify_coordinator::CapacityReservation) -> Self {
+        Self {
+            namespace: value.namespace,
+            extraction_graph: Some(value.extraction_graph).filter(|graph| !graph.is_empty()),
+            slots: value.slots,
+            start: value.start,
+            end: value.end,
+        }
+    }
+}
+
+impl From<SchedulerSettings> for indexify_coordinator::SchedulerSettings {
+    fn from(value: SchedulerSettings) -> Self {
+        Self {
+            placement_strategy: value.placement_strategy.to_string(),
+            max_tasks_per_executor: value.max_tasks_per_executor.unwrap_or(0),
+            namespace_weights: value.namespace_weights,
+            max_pending_gc_tasks: value.max_pending_gc_tasks.unwrap_or(0),
+            capacity_reservations: value
+                .capacity_reservations
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            overcommit_factors: value.overcommit_factors,
+            max_input_download_secs: value.max_input_download_secs.unwrap_or(0),
+            max_bundle_tasks: value.max_bundle_tasks.unwrap_or(0),
+            max_bundle_bytes: value.max_bundle_bytes.unwrap_or(0),
+            max_bundle_latency_ms: value.max_bundle_latency_ms.unwrap_or(0),
+        }
+    }
+}
+
+impl TryFrom<indexify_coordinator::SchedulerSettings> for SchedulerSettings {
+    type Error = anyhow::Error;
+
+    fn try_from(value: indexify_coordinator::SchedulerSettings) -> Result<Self> {
+        let placement_strategy = if value.placement_strategy.is_empty() {
+            PlacementStrategy::default()
+        } else {
+            PlacementStrategy::from_str(&value.placement_strategy)
+                .map_err(|_| anyhow!("invalid placement strategy {}", value.placement_strategy))?
+        };
+        Ok(Self {
+            placement_strategy,
+            max_tasks_per_executor: Some(value.max_tasks_per_executor).filter(|max| *max > 0),
+            namespace_weights: value.namespace_weights,
+            max_pending_gc_tasks: Some(value.max_pending_gc_tasks).filter(|max| *max > 0),
+            capacity_reservations: value
+                .capacity_reservations
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            overcommit_factors: value.overcommit_factors,
+            max_input_download_secs: Some(value.max_input_download_secs).filter(|max| *max > 0),
+            max_bundle_tasks: Some(value.max_bundle_tasks).filter(|max| *max > 0),
+            max_bundle_bytes: Some(value.max_bundle_bytes).filter(|max| *max > 0),
+            max_bundle_latency_ms: Some(value.max_bundle_latency_ms).filter(|max| *max > 0),
+        })
+    }
+}
+
+/// While the cluster is in maintenance the scheduler doesn't create or assign
+/// tasks and garbage collection tasks. Executors still report the outcome of
+/// their tasks and the API keeps serving requests.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct MaintenanceMode {
+    pub enabled: bool,
+    #[serde(default)]
+    pub reason: String,
+    /// When maintenance was last turned on or off, in seconds since the epoch
+    #[serde(default)]
+    pub changed_at: u64,
+}
+
+impl From<MaintenanceMode> for indexify_coordinator::MaintenanceMode {
+    fn from(value: MaintenanceMode) -> Self {
+        Self {
+            enabled: value.enabled,
+            reason: value.reason,
+            changed_at: value.changed_at,
+        }
+    }
+}
+
+impl From<indexify_coordinator::MaintenanceMode> for MaintenanceMode {
+    fn from(value: indexify_coordinator::MaintenanceMode) -> Self {
+        Self {
+            enabled: value.enabled,
+            reason: value.reason,
+            changed_at: value.changed_at,
+        }
+    }
+}
+
+/// Length of the periods usage is metered over, in seconds
+pub const METERING_PERIOD_SECS: u64 = 3600;
+
+/// Usage of a namespace during one metering period. Invocations and stored
+/// bytes count the content ingested into the namespace and extracted from it,
+/// task seconds are the time tasks spent between being assigned and finishing
+/// by extraction policy, and egress bytes are the bytes of content downloaded
+/// from the namespace.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct MeteringRecord {
+    pub namespace: String,
+    /// Start of the period, in seconds since the epoch
+    pub period_start: u64,
+    pub invocations: u64,
+    pub task_seconds: HashMap<String, f64>,
+    pub stored_bytes: u64,
+    pub egress_bytes: u64,
+}
+
+impl MeteringRecord {
+    pub fn new(namespace: &str, period_start: u64) -> Self {
+        Self {
+            namespace: namespace.to_string(),
+            period_start,
+            ..Default::default()
+        }
+    }
+
+    /// Start of the metering period `ts_secs` falls into
+    pub fn period_of(ts_secs: u64) -> u64 {
+        ts_secs - ts_secs % METERING_PERIOD_SECS
+    }
+
+    /// Records are ordered by namespace and period in the state store
+    pub fn key(namespace: &str, period_start: u64) -> String {
+        format!("{}/{:020}", namespace, period_start)
+    }
+}
+
+impl From<MeteringRecord> for indexify_coordinator::MeteringRecord {
+    fn from(value: MeteringRecord) -> Self {
+        Self {
+            namespace: value.namespace,
+            period_start: value.period_start,
+            invocations: value.invocations,
+            task_seconds: value.task_seconds,
+            stored_bytes: value.stored_bytes,
+            egress_bytes: value.egress_bytes,
+        }
+    }
+}
+
+impl From<indexify_coordinator::MeteringRecord> for MeteringRecord {
+    fn from(value: indexify_coordinator::MeteringRecord) -> Self {
+        Self {
+            namespace: value.namespace,
+            period_start: value.period_start,
+            invocations: value.invocations,
+            task_seconds: value.task_seconds,
+            stored_bytes: value.stored_bytes,
+            egress_bytes: value.egress_bytes,
+        }
+    }
+}
+
 pub type IndexName = String;
 pub type IndexId = String;
 
@@ -393,6 +1268,13 @@ pub enum TaskOutcome {
     Unknown,
     Success,
     Failed,
+    /// The task was taken down with the executors that ran it too many times
+    /// and is no longer retried
+    Quarantined,
+    /// The content of the task expired before the task finished
+    Expired,
+    /// The task was cancelled before it finished
+    Cancelled,
 }
 
 impl From<indexify_coordinator::TaskOutcome> for TaskOutcome {
@@ -401,6 +1283,9 @@ impl From<indexify_coordinator::TaskOutcome> for TaskOutcome {
             indexify_coordinator::TaskOutcome::Unknown => TaskOutcome::Unknown,
             indexify_coordinator::TaskOutcome::Success => TaskOutcome::Success,
             indexify_coordinator::TaskOutcome::Failed => TaskOutcome::Failed,
+            indexify_coordinator::TaskOutcome::Quarantined => TaskOutcome::Quarantined,
+            indexify_coordinator::TaskOutcome::Expired => TaskOutcome::Expired,
+            indexify_coordinator::TaskOutcome::Cancelled => TaskOutcome::Cancelled,
         }
     }
 }
@@ -411,6 +1296,9 @@ impl From<TaskOutcome> for indexify_coordinator::TaskOutcome {
             TaskOutcome::Unknown => indexify_coordinator::TaskOutcome::Unknown,
             TaskOutcome::Success => indexify_coordinator::TaskOutcome::Success,
             TaskOutcome::Failed => indexify_coordinator::TaskOutcome::Failed,
+            TaskOutcome::Quarantined => indexify_coordinator::TaskOutcome::Quarantined,
+            TaskOutcome::Expired => indexify_coordinator::TaskOutcome::Expired,
+            TaskOutcome::Cancelled => indexify_coordinator::TaskOutcome::Cancelled,
         }
     }
 }
@@ -439,6 +1327,31 @@ pub struct Task {
     #[schema(value_type = internal_api::TaskOutcome)]
     pub outcome: TaskOutcome,
     pub index_tables: Vec<String>, // list of index tables that this content may be present in
+    /// Executors that were removed while they were running the task
+    #[serde(default)]
+    pub lost_by_executors: Vec<String>,
+    /// Why the task was quarantined
+    #[serde(default)]
+    pub diagnostics: Option<String>,
+    /// Resources the task used, as reported by the executor that ran it
+    #[serde(default)]
+    pub usage: Option<TaskUsage>,
+    /// How far the task got, as last reported by the executor running it
+    #[serde(default)]
+    pub progress: Option<TaskProgress>,
+    /// Thousandths of a GPU device the task takes
+    #[serde(default)]
+    pub gpu_millis: Option<u32>,
+    /// GPU device of the executor the task was packed onto
+    #[serde(default)]
+    pub gpu_device: Option<u32>,
+    /// Whether the task was assigned above the limit of tasks per executor,
+    /// while the executors of its extractor were overcommitted
+    #[serde(default)]
+    pub burst: bool,
+    /// Version of the extraction graph the task was created with
+    #[serde(default)]
+    pub graph_version: Option<u64>,
 }
 
 impl Task {
@@ -456,12 +1369,20 @@ impl Task {
             extractor: "".to_string(),
             extraction_policy_id: extraction_policy.id.to_string(),
             extraction_graph_name: extraction_policy.graph_name,
+            gpu_millis: extraction_policy.gpu_millis,
             output_index_table_mapping: HashMap::new(),
             namespace: content_metadata.namespace.clone(),
             content_metadata: content_metadata.clone(),
             input_params: serde_json::Value::Null,
             outcome: TaskOutcome::Unknown,
             index_tables: Vec::new(),
+            lost_by_executors: Vec::new(),
+            diagnostics: None,
+            usage: None,
+            progress: None,
+            gpu_device: None,
+            burst: false,
+            graph_version: None,
         }
     }
 }
@@ -476,6 +1397,143 @@ impl Display for Task {
     }
 }
 
+/// Resources a task used, reported by the executor when it finishes the
+/// ingest of the task's output
+#[derive(Serialize, Debug, Deserialize, Clone, Copy, PartialEq, ToSchema, Default)]
+pub struct TaskUsage {
+    /// Wall clock time the extractor ran for
+    pub execution_secs: f64,
+    #[serde(default)]
+    pub cpu_secs: f64,
+    /// Peak memory of the extractor
+    #[serde(default)]
+    pub max_memory_bytes: u64,
+}
+
+impl From<TaskUsage> for indexify_coordinator::TaskUsage {
+    fn from(value: TaskUsage) -> Self {
+        Self {
+            execution_secs: value.execution_secs,
+            cpu_secs: value.cpu_secs,
+            max_memory_bytes: value.max_memory_bytes,
+        }
+    }
+}
+
+impl From<indexify_coordinator::TaskUsage> for TaskUsage {
+    fn from(value: indexify_coordinator::TaskUsage) -> Self {
+        Self {
+            execution_secs: value.execution_secs,
+            cpu_secs: value.cpu_secs,
+            max_memory_bytes: value.max_memory_bytes,
+        }
+    }
+}
+
+/// Progress of a running task, reported by its executor in heartbeats
+#[derive(Serialize, Debug, Deserialize, Clone, Copy, PartialEq, ToSchema, Default)]
+pub struct TaskProgress {
+    /// Share of the task that is done, from 0 to 100, when the extractor
+    /// knows how much there is to do
+    #[serde(default)]
+    pub percent: Option<f64>,
+    /// Items, like pages or chunks, the extractor processed so far
+    #[serde(default)]
+    pub items_processed: u64,
+    /// Seconds since the epoch when the coordinator received the report
+    pub updated_at: u64,
+}
+
+impl From<TaskProgress> for indexify_coordinator::TaskProgress {
+    fn from(value: TaskProgress) -> Self {
+        Self {
+            percent: value.percent,
+            items_processed: value.items_processed,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+impl From<indexify_coordinator::TaskProgress> for TaskProgress {
+    fn from(value: indexify_coordinator::TaskProgress) -> Self {
+        Self {
+            percent: value.percent.map(|percent| percent.clamp(0.0, 100.0)),
+            items_processed: value.items_processed,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+/// State of a task in its history
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TaskState {
+    /// Waiting for an executor, after the task was created or taken from
+    /// the executor that held it
+    #[default]
+    Pending,
+    /// Assigned to an executor
+    Running,
+    /// Finished with an outcome
+    Finalized,
+}
+
+/// A state a task moved to
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, ToSchema)]
+pub struct TaskTransition {
+    pub state: TaskState,
+    /// Executor the task was assigned to, or the executor that held it when
+    /// it finished or was taken back
+    #[serde(default)]
+    pub executor_id: Option<String>,
+    /// Outcome of a finalized task
+    #[serde(default)]
+    pub outcome: Option<TaskOutcome>,
+    /// Seconds since the epoch
+    pub at: u64,
+}
+
+impl From<TaskTransition> for indexify_coordinator::TaskTransition {
+    fn from(value: TaskTransition) -> Self {
+        let outcome: indexify_coordinator::TaskOutcome = value.outcome.unwrap_or_default().into();
+        Self {
+            state: value.state.to_string(),
+            executor_id: value.executor_id.unwrap_or_default(),
+            outcome: outcome as i32,
+            at: value.at,
+        }
+    }
+}
+
+impl From<indexify_coordinator::TaskTransition> for TaskTransition {
+    fn from(value: indexify_coordinator::TaskTransition) -> Self {
+        let state: TaskState = value.state.parse().unwrap_or_default();
+        Self {
+            outcome: (state == TaskState::Finalized).then(|| {
+                indexify_coordinator::TaskOutcome::try_from(value.outcome)
+                    .unwrap_or(indexify_coordinator::TaskOutcome::Unknown)
+                    .into()
+            }),
+            state,
+            executor_id: Some(value.executor_id).filter(|id| !id.is_empty()),
+            at: value.at,
+        }
+    }
+}
+
 impl From<Task> for indexify_coordinator::Task {
     fn from(value: Task) -> Self {
         let outcome: indexify_coordinator::TaskOutcome = value.outcome.into();
@@ -490,6 +1548,12 @@ impl From<Task> for indexify_coordinator::Task {
             output_index_mapping: value.output_index_table_mapping,
             outcome: outcome as i32,
             index_tables: value.index_tables,
+            diagnostics: value.diagnostics.unwrap_or_default(),
+            progress: value.progress.map(Into::into),
+            gpu_millis: value.gpu_millis.unwrap_or_default(),
+            gpu_device: value.gpu_device,
+            graph_version: value.graph_version.unwrap_or_default(),
+            attempt: value.lost_by_executors.len() as u32,
         }
     }
 }
@@ -633,6 +1697,149 @@ pub struct ExtractionPolicy {
     // The source of the content this policy will match against. Will either be the graph id or a
     // parent policy id
     pub content_source: ExtractionPolicyContentSource,
+    // JSON schema the metadata extracted by the policy has to match
+    #[serde(default)]
+    pub output_schema: Option<serde_json::Value>,
+    // Named output of the parent policy the content has to be emitted on, any
+    // output matches when unset
+    #[serde(default)]
+    pub source_output: Option<String>,
+    // Most tasks of the policy allocated per second, unlimited when unset
+    #[serde(default)]
+    pub max_tasks_per_second: Option<u32>,
+    // The extractor of the policy is expensive to warm up, so tasks are
+    // placed on executors that recently ran the policy when possible
+    #[serde(default)]
+    pub expensive_warmup: bool,
+    // Executors kept warm for the policy, the most recent executors that ran
+    // it stay preferred even after they were idle for the warm period
+    #[serde(default)]
+    pub min_warm_executors: Option<u32>,
+    // Thousandths of a GPU device each task of the policy takes, so that
+    // tasks of small models share a device
+    #[serde(default)]
+    pub gpu_millis: Option<u32>,
+    // Latency and error rate objectives of the policy's tasks
+    #[serde(default)]
+    pub slo: Option<ExtractionPolicySlo>,
+}
+
+/// Service level objectives of the tasks of an extraction policy. Shares of
+/// tasks are in parts per million.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, Deserialize, Default)]
+pub struct ExtractionPolicySlo {
+    // Tasks finish within this many milliseconds of being created
+    #[serde(default)]
+    pub latency_ms: Option<u64>,
+    // Share of the tasks that have to finish within the latency
+    #[serde(default)]
+    pub latency_target_ppm: u32,
+    // Share of the tasks that are allowed to fail
+    #[serde(default)]
+    pub max_error_rate_ppm: Option<u32>,
+}
+
+impl ExtractionPolicySlo {
+    /// Compliance of `tasks` finished tasks of the policy with the
+    /// objectives, `slow` of them finished past the latency and `failed` of
+    /// them failed
+    pub fn compliance(
+        &self,
+        extraction_policy: &str,
+        tasks: u64,
+        slow: u64,
+        failed: u64,
+    ) -> SloCompliance {
+        let share = |count: u64| {
+            if tasks == 0 {
+                0.0
+            } else {
+                count as f64 / tasks as f64
+            }
+        };
+        //  The burn rate is the share of bad tasks over the share the
+        //  objective allows, the error budget is used up at 1
+        let burn_rate = |bad: f64, allowed_ppm: u32| bad / (allowed_ppm.max(1) as f64 / 1e6);
+        SloCompliance {
+            extraction_policy: extraction_policy.to_string(),
+            tasks,
+            latency_compliance: self.latency_ms.map(|_| 1.0 - share(slow)),
+            error_rate: share(failed),
+            latency_burn_rate: self.latency_ms.map(|_| {
+                burn_rate(
+                    share(slow),
+                    1_000_000u32.saturating_sub(self.latency_target_ppm),
+                )
+            }),
+            error_burn_rate: self
+                .max_error_rate_ppm
+                .map(|max_ppm| burn_rate(share(failed), max_ppm)),
+        }
+    }
+}
+
+impl From<ExtractionPolicySlo> for indexify_coordinator::ExtractionPolicySlo {
+    fn from(value: ExtractionPolicySlo) -> Self {
+        Self {
+            latency_ms: value.latency_ms.unwrap_or_default(),
+            latency_target_ppm: value.latency_target_ppm,
+            max_error_rate_ppm: value.max_error_rate_ppm.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<indexify_coordinator::ExtractionPolicySlo> for ExtractionPolicySlo {
+    fn from(value: indexify_coordinator::ExtractionPolicySlo) -> Self {
+        Self {
+            latency_ms: Some(value.latency_ms).filter(|latency| *latency > 0),
+            latency_target_ppm: value.latency_target_ppm,
+            max_error_rate_ppm: Some(value.max_error_rate_ppm).filter(|max| *max > 0),
+        }
+    }
+}
+
+/// How the tasks an extraction policy finished recently did against its
+/// objectives
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SloCompliance {
+    pub extraction_policy: String,
+    /// Tasks finished within the window compliance is computed over
+    pub tasks: u64,
+    /// Share of the tasks that finished within the latency objective
+    pub latency_compliance: Option<f64>,
+    /// Share of the tasks that failed
+    pub error_rate: f64,
+    /// How fast the tasks use up the error budget of the latency objective,
+    /// it runs out over the window at 1
+    pub latency_burn_rate: Option<f64>,
+    /// How fast the tasks use up the error budget of the error rate objective
+    pub error_burn_rate: Option<f64>,
+}
+
+impl From<SloCompliance> for indexify_coordinator::SloCompliance {
+    fn from(value: SloCompliance) -> Self {
+        Self {
+            extraction_policy: value.extraction_policy,
+            tasks: value.tasks,
+            latency_compliance: value.latency_compliance,
+            error_rate: value.error_rate,
+            latency_burn_rate: value.latency_burn_rate,
+            error_burn_rate: value.error_burn_rate,
+        }
+    }
+}
+
+impl From<indexify_coordinator::SloCompliance> for SloCompliance {
+    fn from(value: indexify_coordinator::SloCompliance) -> Self {
+        Self {
+            extraction_policy: value.extraction_policy,
+            tasks: value.tasks,
+            latency_compliance: value.latency_compliance,
+            error_rate: value.error_rate,
+            latency_burn_rate: value.latency_burn_rate,
+            error_burn_rate: value.error_burn_rate,
+        }
+    }
 }
 
 impl From<ExtractionPolicy> for indexify_coordinator::ExtractionPolicy {
@@ -650,6 +1857,16 @@ impl From<ExtractionPolicy> for indexify_coordinator::ExtractionPolicy {
             content_source: value.content_source.into(),
             graph_name: value.graph_name,
             output_table_mapping: value.output_table_mapping,
+            output_schema: value
+                .output_schema
+                .map(|schema| schema.to_string())
+                .unwrap_or_default(),
+            source_output: value.source_output.unwrap_or_default(),
+            max_tasks_per_second: value.max_tasks_per_second.unwrap_or_default(),
+            expensive_warmup: value.expensive_warmup,
+            min_warm_executors: value.min_warm_executors.unwrap_or_default(),
+            gpu_millis: value.gpu_millis.unwrap_or_default(),
+            slo: value.slo.map(Into::into),
         }
     }
 }
@@ -709,6 +1926,13 @@ impl ExtractionPolicyBuilder {
             input_params: self.input_params.clone().unwrap_or_default(),
             output_table_mapping,
             content_source,
+            output_schema: self.output_schema.clone().flatten(),
+            source_output: self.source_output.clone().flatten(),
+            max_tasks_per_second: self.max_tasks_per_second.flatten(),
+            expensive_warmup: self.expensive_warmup.unwrap_or_default(),
+            min_warm_executors: self.min_warm_executors.flatten(),
+            gpu_millis: self.gpu_millis.flatten(),
+            slo: self.slo.flatten(),
         })
     }
 }
@@ -889,6 +2113,18 @@ pub struct ContentMetadata {
     pub extraction_policy_ids: HashMap<ExtractionPolicyId, u64>, /*  map of completion time for
                                                                   * each extraction policy id */
     pub extraction_graph_names: Vec<ExtractionGraphName>,
+    // Tasks of content with a higher priority are handed to executors first.
+    // Content extracted from it inherits the priority.
+    #[serde(default)]
+    pub priority: i32,
+    // Unix time in seconds after which the unfinished tasks of the content
+    // expire, 0 if they never do. Content extracted from it inherits it.
+    #[serde(default)]
+    pub expires_at: u64,
+    // Named output of the extraction policy the content was emitted on, empty
+    // for the default output and for ingested content
+    #[serde(default)]
+    pub output: String,
 }
 
 impl ContentMetadata {
@@ -935,6 +2171,9 @@ impl From<ContentMetadata> for indexify_coordinator::ContentMetadata {
             hash: value.hash,
             extraction_policy_ids: value.extraction_policy_ids,
             extraction_graph_names: value.extraction_graph_names,
+            priority: value.priority,
+            expires_at: value.expires_at,
+            output: value.output,
         }
     }
 }
@@ -971,6 +2210,9 @@ impl From<indexify_coordinator::ContentMetadata> for ContentMetadata {
             hash: value.hash,
             extraction_policy_ids: value.extraction_policy_ids,
             extraction_graph_names: value.extraction_graph_names,
+            priority: value.priority,
+            expires_at: value.expires_at,
+            output: value.output,
         }
     }
 }
@@ -999,16 +2241,275 @@ impl Default for ContentMetadata {
             tombstoned: false,
             hash: "test_hash".to_string(),
             extraction_graph_names: vec![],
+            priority: 0,
+            expires_at: 0,
+            output: String::new(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Copy of a content and its metadata in the blob store of another region,
+/// recorded once the copy was read back and matched the hash of the content
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ContentReplica {
+    pub content_id: String,
+    pub namespace: NamespaceName,
+    pub region: String,
+    pub url: String,
+    /// Url of the JSON metadata of the content stored next to the copy
+    pub metadata_url: String,
+    pub size_bytes: u64,
+    pub hash: String,
+    pub replicated_at: u64,
+}
+
+impl From<ContentReplica> for indexify_coordinator::ContentReplica {
+    fn from(value: ContentReplica) -> Self {
+        Self {
+            content_id: value.content_id,
+            namespace: value.namespace,
+            region: value.region,
+            url: value.url,
+            metadata_url: value.metadata_url,
+            size_bytes: value.size_bytes,
+            hash: value.hash,
+            replicated_at: value.replicated_at,
+        }
+    }
+}
+
+impl From<indexify_coordinator::ContentReplica> for ContentReplica {
+    fn from(value: indexify_coordinator::ContentReplica) -> Self {
+        Self {
+            content_id: value.content_id,
+            namespace: value.namespace,
+            region: value.region,
+            url: value.url,
+            metadata_url: value.metadata_url,
+            size_bytes: value.size_bytes,
+            hash: value.hash,
+            replicated_at: value.replicated_at,
+        }
+    }
+}
+
+/// Content whose copy in another region may be out of date
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingReplication {
+    pub content_id: String,
+    /// Latest version of the content, none once it's deleted or tombstoned
+    pub content: Option<ContentMetadata>,
+    pub replica: Option<ContentReplica>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct ExecutorMetadata {
     pub id: String,
     pub last_seen: u64,
     pub addr: String,
     pub extractors: Vec<ExtractorDescription>,
+    // What running the executor costs per hour, in whatever unit the
+    // operator registers all executors with
+    #[serde(default)]
+    pub cost_per_hour: Option<f64>,
+    // Multiple of the tasks per executor limit the executor takes, for
+    // executors bigger than the others
+    #[serde(default)]
+    pub capacity_weight: Option<u32>,
+    // Zone or other failure domain the executor runs in
+    #[serde(default)]
+    pub zone: Option<String>,
+    // Largest task input the executor downloads
+    #[serde(default)]
+    pub max_payload_bytes: Option<u64>,
+    // Bandwidth the executor downloads task inputs with
+    #[serde(default)]
+    pub download_bytes_per_sec: Option<u64>,
+    // Set when the executor announced it shuts down once its tasks finished,
+    // it isn't given new tasks
+    #[serde(default)]
+    pub draining: bool,
+    // Resources the executor reported it runs short of in its heartbeats
+    #[serde(default)]
+    pub pressure: Option<ExecutorPressure>,
+    // Thousandths of a device the executor offers on each of its GPUs
+    #[serde(default)]
+    pub gpu_devices: Vec<u32>,
+    // Versions of extraction graphs the executor runs the code of
+    #[serde(default)]
+    pub graph_versions: Vec<GraphVersionRange>,
+}
+
+/// Versions of an extraction graph an executor runs the code of, so that
+/// executors of an older or newer release only get the tasks they can run
+/// while a new release of a graph rolls out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GraphVersionRange {
+    pub namespace: String,
+    pub graph_name: String,
+    /// Oldest version the executor runs, unbounded when unset
+    #[serde(default)]
+    pub min_version: Option<u64>,
+    /// Newest version the executor runs, unbounded when unset
+    #[serde(default)]
+    pub max_version: Option<u64>,
+}
+
+impl GraphVersionRange {
+    pub fn contains(&self, version: u64) -> bool {
+        self.min_version.map_or(true, |min| version >= min) &&
+            self.max_version.map_or(true, |max| version <= max)
+    }
+}
+
+impl From<GraphVersionRange> for indexify_coordinator::GraphVersionRange {
+    fn from(value: GraphVersionRange) -> Self {
+        Self {
+            namespace: value.namespace,
+            graph_name: value.graph_name,
+            min_version: value.min_version.unwrap_or_default(),
+            max_version: value.max_version.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<indexify_coordinator::GraphVersionRange> for GraphVersionRange {
+    fn from(value: indexify_coordinator::GraphVersionRange) -> Self {
+        Self {
+            namespace: value.namespace,
+            graph_name: value.graph_name,
+            min_version: Some(value.min_version).filter(|min| *min > 0),
+            max_version: Some(value.max_version).filter(|max| *max > 0),
+        }
+    }
+}
+
+/// How short of resources an executor is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PressureLevel {
+    /// The executor takes half the tasks it would otherwise take
+    Elevated,
+    /// The executor takes no new tasks until the pressure clears
+    Critical,
+}
+
+/// Pressure an executor reports in its heartbeats, like a disk that fills up
+/// or GPU memory running out. It clears once heartbeats stop reporting it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExecutorPressure {
+    pub level: PressureLevel,
+    /// What the executor runs short of
+    #[serde(default)]
+    pub reason: String,
+    /// Seconds since the epoch when the executor reported the pressure
+    pub since: u64,
+}
+
+impl From<ExecutorPressure> for indexify_coordinator::ExecutorPressure {
+    fn from(value: ExecutorPressure) -> Self {
+        let level = match value.level {
+            PressureLevel::Elevated => indexify_coordinator::PressureLevel::Elevated,
+            PressureLevel::Critical => indexify_coordinator::PressureLevel::Critical,
+        };
+        Self {
+            level: level as i32,
+            reason: value.reason,
+            since: value.since,
+        }
+    }
+}
+
+impl TryFrom<indexify_coordinator::ExecutorPressure> for ExecutorPressure {
+    type Error = anyhow::Error;
+
+    fn try_from(value: indexify_coordinator::ExecutorPressure) -> Result<Self> {
+        let level = match value.level() {
+            indexify_coordinator::PressureLevel::Normal => {
+                return Err(anyhow!("executor reported no pressure"))
+            }
+            indexify_coordinator::PressureLevel::Elevated => PressureLevel::Elevated,
+            indexify_coordinator::PressureLevel::Critical => PressureLevel::Critical,
+        };
+        Ok(Self {
+            level,
+            reason: value.reason,
+            since: value.since,
+        })
+    }
+}
+
+impl ExecutorMetadata {
+    pub fn capacity_weight(&self) -> u32 {
+        self.capacity_weight.unwrap_or(1)
+    }
+
+    /// Whether the executor takes a task whose input is `size_bytes`: the
+    /// input is within its payload limit, and it downloads the input within
+    /// `max_download_secs` when it registered its bandwidth
+    pub fn takes_payload(&self, size_bytes: u64, max_download_secs: Option<u32>) -> bool {
+        if self.max_payload_bytes.map_or(false, |max| size_bytes > max) {
+            return false;
+        }
+        match (self.download_bytes_per_sec, max_download_secs) {
+            (Some(bandwidth), Some(max_secs)) => size_bytes <= bandwidth * max_secs as u64,
+            _ => true,
+        }
+    }
+
+    /// Thousandths of each GPU device of the executor taken by `tasks`, the
+    /// unfinished tasks assigned to it
+    pub fn gpu_occupancy<'a>(&self, tasks: impl IntoIterator<Item = &'a Task>) -> Vec<u32> {
+        let mut occupancy = vec![0; self.gpu_devices.len()];
+        for task in tasks.into_iter().filter(|task| !task.terminal_state()) {
+            if let (Some(millis), Some(device)) = (task.gpu_millis, task.gpu_device) {
+                if let Some(occupied) = occupancy.get_mut(device as usize) {
+                    *occupied += millis;
+                }
+            }
+        }
+        occupancy
+    }
+
+    /// Whether the executor runs `version` of the graph `graph_name`.
+    /// Executors that declared no versions of the graph run all of them.
+    pub fn runs_graph_version(&self, namespace: &str, graph_name: &str, version: u64) -> bool {
+        let mut ranges = self
+            .graph_versions
+            .iter()
+            .filter(|range| range.namespace == namespace && range.graph_name == graph_name)
+            .peekable();
+        ranges.peek().is_none() || ranges.any(|range| range.contains(version))
+    }
+
+    /// GPU device a task taking `millis` thousandths of a device is packed
+    /// onto, given the `occupancy` of the devices. The fullest device the
+    /// task fits on is picked, so that the devices left empty stay free for
+    /// tasks taking a whole device.
+    pub fn pack_gpu(&self, occupancy: &[u32], millis: u32) -> Option<u32> {
+        self.gpu_devices
+            .iter()
+            .enumerate()
+            .map(|(device, capacity)| {
+                let occupied = occupancy.get(device).copied().unwrap_or_default();
+                (device, capacity.saturating_sub(occupied))
+            })
+            .filter(|(_, free)| *free >= millis)
+            .min_by_key(|(device, free)| (*free, *device))
+            .map(|(device, _)| device as u32)
+    }
+}
+
+/// What an executor registers about itself besides its extractors
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ExecutorProfile {
+    pub cost_per_hour: Option<f64>,
+    pub capacity_weight: Option<u32>,
+    pub zone: Option<String>,
+    pub max_payload_bytes: Option<u64>,
+    pub download_bytes_per_sec: Option<u64>,
+    pub gpu_devices: Vec<u32>,
+    pub graph_versions: Vec<GraphVersionRange>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -1076,8 +2577,16 @@ pub enum ChangeType {
     TombstoneContentTree,
     ExecutorAdded,
     ExecutorRemoved,
+    OrphanedAssignmentsReaped,
+    BurstTasksShed,
+    ContentRepaired,
     ContentUpdated,
-    TaskCompleted { root_content_id: ContentMetadataId },
+    TaskCompleted {
+        root_content_id: ContentMetadataId,
+    },
+    RerunExtractionPolicy {
+        extraction_policy_id: ExtractionPolicyId,
+    },
 }
 
 impl fmt::Display for ChangeType {
@@ -1087,12 +2596,24 @@ impl fmt::Display for ChangeType {
             ChangeType::TombstoneContentTree => write!(f, "TombstoneContentTree"),
             ChangeType::ExecutorAdded => write!(f, "ExecutorAdded"),
             ChangeType::ExecutorRemoved => write!(f, "ExecutorRemoved"),
+            ChangeType::OrphanedAssignmentsReaped => write!(f, "OrphanedAssignmentsReaped"),
+            ChangeType::BurstTasksShed => write!(f, "BurstTasksShed"),
+            ChangeType::ContentRepaired => write!(f, "ContentRepaired"),
             ChangeType::ContentUpdated => write!(f, "ContentUpdated"),
             ChangeType::TaskCompleted {
                 root_content_id: content_id,
             } => {
                 write!(f, "TaskCompleted(content_id: {})", content_id)
             }
+            ChangeType::RerunExtractionPolicy {
+                extraction_policy_id,
+            } => {
+                write!(
+                    f,
+                    "RerunExtractionPolicy(extraction_policy_id: {})",
+                    extraction_policy_id
+                )
+            }
         }
     }
 }
@@ -1215,6 +2736,363 @@ impl From<StateChange> for indexify_coordinator::StateChange {
     }
 }
 
+/// Root content with outstanding tasks that stopped making progress
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StuckContent {
+    pub content_id: String,
+    /// Tasks and state changes the content is still waiting for
+    pub outstanding_tasks: u64,
+    /// Unfinished tasks and unprocessed state changes that actually exist for
+    /// the content
+    pub pending_tasks: u64,
+    /// Seconds since a task of the content was last created or finished
+    pub stalled_secs: u64,
+}
+
+impl From<StuckContent> for indexify_coordinator::StuckContent {
+    fn from(value: StuckContent) -> Self {
+        Self {
+            content_id: value.content_id,
+            outstanding_tasks: value.outstanding_tasks,
+            pending_tasks: value.pending_tasks,
+            stalled_secs: value.stalled_secs,
+        }
+    }
+}
+
+/// Why a task is not allocated to an executor
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumIter,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum UnschedulableReason {
+    /// No executor runs the extractor of the task
+    NoExecutor,
+    /// Every executor of the extractor is at its limit of tasks
+    NoCapacity,
+    /// The rate limit of the extraction policy holds the task back
+    RateLimited,
+    /// The cluster is in maintenance
+    Maintenance,
+    /// Executors have room, the task waits for the next allocation
+    #[default]
+    Pending,
+}
+
+/// A task that stayed unallocated past the starvation threshold
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StarvedTask {
+    pub task_id: String,
+    pub namespace: String,
+    pub extraction_graph: String,
+    pub extraction_policy_id: String,
+    pub extractor: String,
+    /// Seconds since the task was last put into its queue
+    pub unallocated_secs: u64,
+    pub reason: UnschedulableReason,
+}
+
+impl From<StarvedTask> for indexify_coordinator::StarvedTask {
+    fn from(value: StarvedTask) -> Self {
+        Self {
+            task_id: value.task_id,
+            namespace: value.namespace,
+            extraction_graph: value.extraction_graph,
+            extraction_policy_id: value.extraction_policy_id,
+            extractor: value.extractor,
+            unallocated_secs: value.unallocated_secs,
+            reason: value.reason.to_string(),
+        }
+    }
+}
+
+/// Why the allocator passed over an executor for a task
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum PlacementRejection {
+    /// The executor is draining or under critical pressure
+    Paused,
+    /// The input of the task is over the payload limit of the executor, or
+    /// takes too long to download at its bandwidth
+    InputTooLarge,
+    /// No GPU device of the executor has room for the task
+    NoGpu,
+    /// The executor doesn't run the version of the graph the task is of
+    GraphVersion,
+    /// The free slots of the executor are held for a capacity reservation
+    Reserved,
+    /// The executor is not in the zone the task is placed in
+    Zone,
+    /// The executor is at its limit of tasks
+    Full,
+    /// Another executor was picked, for its load, cost, zone or because it
+    /// is warm
+    #[default]
+    NotPicked,
+}
+
+/// An executor the allocator considered for a task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PlacementCandidate {
+    pub executor_id: String,
+    /// Why the executor didn't get the task, none for the executor that got
+    /// it
+    pub rejection: Option<PlacementRejection>,
+}
+
+/// A pass of the allocator over an unassigned task. The task wasn't
+/// assigned when no executor is set, and no executor ran its extractor when
+/// there are no candidates either.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SchedulingAttempt {
+    pub attempted_at: u64,
+    pub executor_id: Option<String>,
+    pub candidates: Vec<PlacementCandidate>,
+}
+
+impl From<SchedulingAttempt> for indexify_coordinator::SchedulingAttempt {
+    fn from(value: SchedulingAttempt) -> Self {
+        Self {
+            attempted_at: value.attempted_at,
+            executor_id: value.executor_id.unwrap_or_default(),
+            candidates: value
+                .candidates
+                .into_iter()
+                .map(|candidate| indexify_coordinator::PlacementCandidate {
+                    executor_id: candidate.executor_id,
+                    rejection: candidate
+                        .rejection
+                        .map(|rejection| rejection.to_string())
+                        .unwrap_or_default(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<indexify_coordinator::SchedulingAttempt> for SchedulingAttempt {
+    fn from(value: indexify_coordinator::SchedulingAttempt) -> Self {
+        Self {
+            attempted_at: value.attempted_at,
+            executor_id: Some(value.executor_id).filter(|id| !id.is_empty()),
+            candidates: value
+                .candidates
+                .into_iter()
+                .map(|candidate| PlacementCandidate {
+                    executor_id: candidate.executor_id,
+                    rejection: candidate.rejection.parse().ok(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// What a replay runs again
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    ToSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ReplayKind {
+    /// An extraction policy that completed on a content
+    #[default]
+    ExtractionPolicy,
+    /// The failed tasks of a content tree
+    FailedTasks,
+    /// A quarantined task
+    Requeue,
+}
+
+/// A task a replay runs again
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ReplayTask {
+    pub task_id: String,
+    pub content_id: String,
+    pub extraction_graph: String,
+    pub extraction_policy: String,
+    /// Version of the extraction graph when the replay started
+    pub graph_version: u64,
+    /// Unknown until the task finishes
+    pub outcome: TaskOutcome,
+    /// A later replay that ran the task again before it finished
+    #[serde(default)]
+    pub replaced_by: Option<String>,
+    /// Content the task extracted, recorded as it is created
+    #[serde(default)]
+    pub outputs: Vec<String>,
+}
+
+impl ReplayTask {
+    pub fn finished(&self) -> bool {
+        self.outcome != TaskOutcome::Unknown || self.replaced_by.is_some()
+    }
+}
+
+/// A run of extraction policies on content they already ran on, kept as the
+/// lineage of the outputs it produced
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Replay {
+    pub id: String,
+    pub namespace: String,
+    pub kind: ReplayKind,
+    pub content_id: String,
+    /// Subject of the principal that started the replay, empty when the API
+    /// isn't authenticated
+    pub initiator: String,
+    pub started_at: u64,
+    /// Unset until every task of the replay finished
+    pub finished_at: Option<u64>,
+    pub tasks: Vec<ReplayTask>,
+}
+
+impl Replay {
+    /// Records how a task of the replay ended. The replay finishes with its
+    /// last task.
+    pub fn finish_task(
+        &mut self,
+        task_id: &str,
+        outcome: TaskOutcome,
+        replaced_by: Option<&str>,
+        at: u64,
+    ) {
+        for task in self.tasks.iter_mut().filter(|task| task.task_id == task_id) {
+            task.outcome = outcome;
+            task.replaced_by = replaced_by.map(|id| id.to_string());
+        }
+        if self.finished_at.is_none() && self.tasks.iter().all(|task| task.finished()) {
+            self.finished_at = Some(at);
+        }
+    }
+
+    pub fn failed_tasks(&self) -> usize {
+        self.tasks
+            .iter()
+            .filter(|task| matches!(task.outcome, TaskOutcome::Failed | TaskOutcome::Quarantined))
+            .count()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CancelTasksJobState {
+    Running,
+    Completed,
+    Aborted,
+}
+
+/// A bulk cancellation of the unfinished tasks of a namespace. Its progress is
+/// recorded in the state so it can be read from any coordinator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CancelTasksJob {
+    pub id: String,
+    pub namespace: String,
+    pub state: CancelTasksJobState,
+    pub matched_tasks: u64,
+    pub cancelled_tasks: u64,
+    pub error: Option<String>,
+    pub started_at: u64,
+    /// When the progress of the job was last recorded. A running job that
+    /// isn't updated anymore was left behind by a coordinator that stopped.
+    pub updated_at: u64,
+}
+
+impl From<Replay> for indexify_coordinator::Replay {
+    fn from(value: Replay) -> Self {
+        Self {
+            id: value.id,
+            namespace: value.namespace,
+            kind: value.kind.to_string(),
+            content_id: value.content_id,
+            initiator: value.initiator,
+            started_at: value.started_at,
+            finished_at: value.finished_at.unwrap_or_default(),
+            tasks: value
+                .tasks
+                .into_iter()
+                .map(|task| {
+                    let outcome: indexify_coordinator::TaskOutcome = task.outcome.into();
+                    indexify_coordinator::ReplayTask {
+                        task_id: task.task_id,
+                        content_id: task.content_id,
+                        extraction_graph: task.extraction_graph,
+                        extraction_policy: task.extraction_policy,
+                        graph_version: task.graph_version,
+                        outcome: outcome as i32,
+                        replaced_by: task.replaced_by.unwrap_or_default(),
+                        outputs: task.outputs,
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<indexify_coordinator::Replay> for Replay {
+    fn from(value: indexify_coordinator::Replay) -> Self {
+        Self {
+            id: value.id,
+            namespace: value.namespace,
+            kind: value.kind.parse().unwrap_or_default(),
+            content_id: value.content_id,
+            initiator: value.initiator,
+            started_at: value.started_at,
+            finished_at: Some(value.finished_at).filter(|at| *at > 0),
+            tasks: value
+                .tasks
+                .into_iter()
+                .map(|task| ReplayTask {
+                    task_id: task.task_id,
+                    content_id: task.content_id,
+                    extraction_graph: task.extraction_graph,
+                    extraction_policy: task.extraction_policy,
+                    graph_version: task.graph_version,
+                    outcome: indexify_coordinator::TaskOutcome::try_from(task.outcome)
+                        .unwrap_or(indexify_coordinator::TaskOutcome::Unknown)
+                        .into(),
+                    replaced_by: Some(task.replaced_by).filter(|id| !id.is_empty()),
+                    outputs: task.outputs,
+                })
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct ExtractedEmbeddings {
     pub content_id: String,
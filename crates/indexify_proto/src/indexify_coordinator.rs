@@ -32,6 +32,58 @@ pub struct UpdateTaskRequest {
     pub task_id: ::prost::alloc::string::String,
     #[prost(enumeration = "TaskOutcome", tag = "3")]
     pub outcome: i32,
+    #[prost(string, tag = "4")]
+    pub diagnostics: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "5")]
+    pub usage: ::core::option::Option<TaskUsage>,
+}
+/// Outcomes of the tasks of a bundle, finalized together
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateTasksRequest {
+    #[prost(string, tag = "1")]
+    pub executor_id: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "2")]
+    pub tasks: ::prost::alloc::vec::Vec<FinishedTask>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FinishedTask {
+    #[prost(string, tag = "1")]
+    pub task_id: ::prost::alloc::string::String,
+    #[prost(enumeration = "TaskOutcome", tag = "2")]
+    pub outcome: i32,
+    #[prost(string, tag = "3")]
+    pub diagnostics: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "4")]
+    pub usage: ::core::option::Option<TaskUsage>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateTasksResponse {
+    /// tasks that are no longer assigned to the executor
+    #[prost(string, repeated, tag = "1")]
+    pub rejected_task_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TaskUsage {
+    #[prost(double, tag = "1")]
+    pub execution_secs: f64,
+    #[prost(double, tag = "2")]
+    pub cpu_secs: f64,
+    #[prost(uint64, tag = "3")]
+    pub max_memory_bytes: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TaskProgress {
+    #[prost(double, optional, tag = "1")]
+    pub percent: ::core::option::Option<f64>,
+    #[prost(uint64, tag = "2")]
+    pub items_processed: u64,
+    #[prost(uint64, tag = "3")]
+    pub updated_at: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -63,6 +115,9 @@ pub struct ListTasksRequest {
     pub namespace: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub extraction_policy: ::prost::alloc::string::String,
+    /// Streams only: key of the row to read after, to resume a stream
+    #[prost(string, tag = "3")]
+    pub cursor: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -72,6 +127,38 @@ pub struct ListTasksResponse {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListExecutorTasksRequest {
+    #[prost(string, tag = "1")]
+    pub executor_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListTaskQueueDepthsRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TaskQueueDepth {
+    #[prost(string, tag = "1")]
+    pub extraction_graph: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub extraction_policy: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub unallocated: u64,
+    #[prost(uint64, tag = "4")]
+    pub running: u64,
+    #[prost(uint64, tag = "5")]
+    pub completed_last_hour: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListTaskQueueDepthsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub queue_depths: ::prost::alloc::vec::Vec<TaskQueueDepth>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpdateTaskResponse {}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -179,6 +266,40 @@ pub struct RegisterExecutorRequest {
     pub addr: ::prost::alloc::string::String,
     #[prost(message, repeated, tag = "3")]
     pub extractors: ::prost::alloc::vec::Vec<Extractor>,
+    #[prost(double, tag = "4")]
+    pub cost_per_hour: f64,
+    #[prost(uint32, tag = "5")]
+    pub capacity_weight: u32,
+    /// Zone or other failure domain the executor runs in
+    #[prost(string, tag = "6")]
+    pub zone: ::prost::alloc::string::String,
+    /// Largest task input the executor downloads, unlimited when 0
+    #[prost(uint64, tag = "7")]
+    pub max_payload_bytes: u64,
+    /// Bandwidth the executor downloads task inputs with, unknown when 0
+    #[prost(uint64, tag = "8")]
+    pub download_bytes_per_sec: u64,
+    /// Thousandths of a device the executor offers on each of its GPUs, 1000
+    /// for a whole GPU and less for a partition of one
+    #[prost(uint32, repeated, tag = "9")]
+    pub gpu_device_millis: ::prost::alloc::vec::Vec<u32>,
+    /// Versions of extraction graphs the executor runs the code of, it runs
+    /// every version of graphs it declares none for
+    #[prost(message, repeated, tag = "10")]
+    pub graph_versions: ::prost::alloc::vec::Vec<GraphVersionRange>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GraphVersionRange {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub graph_name: ::prost::alloc::string::String,
+    /// oldest and newest version, unbounded when 0
+    #[prost(uint64, tag = "3")]
+    pub min_version: u64,
+    #[prost(uint64, tag = "4")]
+    pub max_version: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -186,6 +307,28 @@ pub struct RegisterExecutorResponse {
     #[prost(string, tag = "1")]
     pub executor_id: ::prost::alloc::string::String,
 }
+/// An executor shutting down. With drain it finishes its tasks before it is
+/// removed, otherwise its tasks go to other executors right away.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeregisterExecutorRequest {
+    #[prost(string, tag = "1")]
+    pub executor_id: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub drain: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeregisterExecutorResponse {
+    /// Whether the executor was removed, a draining executor is removed once
+    /// its tasks finished
+    #[prost(bool, tag = "1")]
+    pub removed: bool,
+    #[prost(uint64, tag = "2")]
+    pub running_tasks: u64,
+    #[prost(uint64, tag = "3")]
+    pub reassigned_tasks: u64,
+}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RegisterIngestionServerRequest {
@@ -252,6 +395,30 @@ pub struct HeartbeatRequest {
     pub executor_id: ::prost::alloc::string::String,
     #[prost(int64, tag = "2")]
     pub pending_tasks: i64,
+    #[prost(message, repeated, tag = "3")]
+    pub task_progress: ::prost::alloc::vec::Vec<TaskProgressUpdate>,
+    #[prost(message, optional, tag = "4")]
+    pub pressure: ::core::option::Option<ExecutorPressure>,
+}
+/// Resources an executor runs short of, like disk space or GPU memory
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExecutorPressure {
+    #[prost(enumeration = "PressureLevel", tag = "1")]
+    pub level: i32,
+    #[prost(string, tag = "2")]
+    pub reason: ::prost::alloc::string::String,
+    /// when the executor first reported the level
+    #[prost(uint64, tag = "3")]
+    pub since: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TaskProgressUpdate {
+    #[prost(string, tag = "1")]
+    pub task_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub progress: ::core::option::Option<TaskProgress>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -260,6 +427,17 @@ pub struct HeartbeatResponse {
     pub executor_id: ::prost::alloc::string::String,
     #[prost(message, repeated, tag = "2")]
     pub tasks: ::prost::alloc::vec::Vec<Task>,
+    #[prost(message, repeated, tag = "3")]
+    pub bundles: ::prost::alloc::vec::Vec<TaskBundle>,
+}
+/// Tasks of one extraction policy an executor runs one after another
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TaskBundle {
+    #[prost(string, tag = "1")]
+    pub extraction_policy_id: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "2")]
+    pub tasks: ::prost::alloc::vec::Vec<Task>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -289,6 +467,25 @@ pub struct Task {
     /// list of all tables that the content may belong to
     #[prost(string, repeated, tag = "10")]
     pub index_tables: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// why the task was quarantined
+    #[prost(string, tag = "11")]
+    pub diagnostics: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "12")]
+    pub progress: ::core::option::Option<TaskProgress>,
+    /// Thousandths of a GPU device the task takes, none when 0
+    #[prost(uint32, tag = "13")]
+    pub gpu_millis: u32,
+    /// Index of the executor's GPU device the task runs on
+    #[prost(uint32, optional, tag = "14")]
+    pub gpu_device: ::core::option::Option<u32>,
+    /// Version of the extraction graph the task was created with, unknown
+    /// when 0
+    #[prost(uint64, tag = "15")]
+    pub graph_version: u64,
+    /// Times the task was run again after its executor was lost, its output
+    /// is stored under this attempt
+    #[prost(uint32, tag = "16")]
+    pub attempt: u32,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -301,6 +498,58 @@ pub struct ListExtractorsResponse {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListExecutorsRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListExecutorsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub executors: ::prost::alloc::vec::Vec<Executor>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Executor {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub addr: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub last_seen: u64,
+    #[prost(message, repeated, tag = "4")]
+    pub extractors: ::prost::alloc::vec::Vec<Extractor>,
+    #[prost(uint64, tag = "5")]
+    pub running_tasks: u64,
+    #[prost(uint64, tag = "6")]
+    pub burst_tasks: u64,
+    #[prost(double, tag = "7")]
+    pub cost_per_hour: f64,
+    #[prost(uint32, tag = "8")]
+    pub capacity_weight: u32,
+    #[prost(string, tag = "9")]
+    pub zone: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "10")]
+    pub max_payload_bytes: u64,
+    #[prost(uint64, tag = "11")]
+    pub download_bytes_per_sec: u64,
+    #[prost(bool, tag = "12")]
+    pub draining: bool,
+    #[prost(message, optional, tag = "13")]
+    pub pressure: ::core::option::Option<ExecutorPressure>,
+    #[prost(message, repeated, tag = "14")]
+    pub gpu_devices: ::prost::alloc::vec::Vec<GpuDevice>,
+    #[prost(message, repeated, tag = "15")]
+    pub graph_versions: ::prost::alloc::vec::Vec<GraphVersionRange>,
+}
+/// A GPU device of an executor and how much of it tasks take
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GpuDevice {
+    #[prost(uint32, tag = "1")]
+    pub capacity_millis: u32,
+    #[prost(uint32, tag = "2")]
+    pub occupied_millis: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Extractor {
     #[prost(string, tag = "1")]
     pub name: ::prost::alloc::string::String,
@@ -347,6 +596,9 @@ pub struct ListContentRequest {
         ::prost::alloc::string::String,
         ::prost::alloc::string::String,
     >,
+    /// Streams only: key of the row to read after, to resume a stream
+    #[prost(string, tag = "5")]
+    pub cursor: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -412,6 +664,12 @@ pub struct ExtractionGraph {
     pub name: ::prost::alloc::string::String,
     #[prost(message, repeated, tag = "4")]
     pub extraction_policies: ::prost::alloc::vec::Vec<ExtractionPolicy>,
+    #[prost(uint32, tag = "5")]
+    pub max_active_content: u32,
+    #[prost(bool, tag = "6")]
+    pub latency_tolerant: bool,
+    #[prost(string, tag = "7")]
+    pub zone_placement: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -438,6 +696,21 @@ pub struct ExtractionPolicy {
         ::prost::alloc::string::String,
         ::prost::alloc::string::String,
     >,
+    #[prost(string, tag = "9")]
+    pub output_schema: ::prost::alloc::string::String,
+    #[prost(string, tag = "10")]
+    pub source_output: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "11")]
+    pub max_tasks_per_second: u32,
+    #[prost(bool, tag = "12")]
+    pub expensive_warmup: bool,
+    #[prost(uint32, tag = "13")]
+    pub min_warm_executors: u32,
+    /// Thousandths of a GPU device each task of the policy takes, none when 0
+    #[prost(uint32, tag = "14")]
+    pub gpu_millis: u32,
+    #[prost(message, optional, tag = "15")]
+    pub slo: ::core::option::Option<ExtractionPolicySlo>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -459,6 +732,23 @@ pub struct ExtractionPolicyRequest {
     pub content_source: ::prost::alloc::string::String,
     #[prost(int64, tag = "7")]
     pub created_at: i64,
+    #[prost(string, tag = "8")]
+    pub subgraph: ::prost::alloc::string::String,
+    #[prost(string, tag = "9")]
+    pub output_schema: ::prost::alloc::string::String,
+    #[prost(string, tag = "10")]
+    pub source_output: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "11")]
+    pub max_tasks_per_second: u32,
+    #[prost(bool, tag = "12")]
+    pub expensive_warmup: bool,
+    #[prost(uint32, tag = "13")]
+    pub min_warm_executors: u32,
+    /// Thousandths of a GPU device each task of the policy takes, none when 0
+    #[prost(uint32, tag = "14")]
+    pub gpu_millis: u32,
+    #[prost(message, optional, tag = "15")]
+    pub slo: ::core::option::Option<ExtractionPolicySlo>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -469,6 +759,25 @@ pub struct CreateExtractionGraphRequest {
     pub name: ::prost::alloc::string::String,
     #[prost(message, repeated, tag = "3")]
     pub policies: ::prost::alloc::vec::Vec<ExtractionPolicyRequest>,
+    #[prost(uint32, tag = "4")]
+    pub max_active_content: u32,
+    #[prost(string, tag = "5")]
+    pub author: ::prost::alloc::string::String,
+    /// Summary of the change for the history of the graph, generated from the
+    /// difference to the current definition when empty
+    #[prost(string, tag = "6")]
+    pub change_summary: ::prost::alloc::string::String,
+    /// Replace the definition of an existing graph instead of creating it
+    #[prost(bool, tag = "7")]
+    pub replace: bool,
+    #[prost(bool, tag = "8")]
+    pub latency_tolerant: bool,
+    /// any, spread or same_zone, any when empty
+    #[prost(string, tag = "9")]
+    pub zone_placement: ::prost::alloc::string::String,
+    /// Reject the graph when linting it finds warnings
+    #[prost(bool, tag = "10")]
+    pub strict: bool,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -487,6 +796,29 @@ pub struct CreateExtractionGraphResponse {
     >,
     #[prost(message, repeated, tag = "5")]
     pub indexes: ::prost::alloc::vec::Vec<Index>,
+    /// Version of the graph in its history
+    #[prost(uint64, tag = "6")]
+    pub version: u64,
+    /// Warnings of the lint checks of the graph
+    #[prost(message, repeated, tag = "7")]
+    pub warnings: ::prost::alloc::vec::Vec<ExtractionGraphDiagnostic>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExtractionGraphDiagnostic {
+    #[prost(enumeration = "DiagnosticSeverity", tag = "1")]
+    pub severity: i32,
+    /// empty when the diagnostic applies to the whole graph
+    #[prost(string, tag = "2")]
+    pub extraction_policy: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub message: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ValidateExtractionGraphResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub diagnostics: ::prost::alloc::vec::Vec<ExtractionGraphDiagnostic>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -546,6 +878,12 @@ pub struct ContentMetadata {
     pub root_content_id: ::prost::alloc::string::String,
     #[prost(string, repeated, tag = "14")]
     pub extraction_graph_names: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(int32, tag = "15")]
+    pub priority: i32,
+    #[prost(uint64, tag = "16")]
+    pub expires_at: u64,
+    #[prost(string, tag = "17")]
+    pub output: ::prost::alloc::string::String,
 }
 #[derive(serde::Deserialize, serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -723,6 +1061,23 @@ pub struct GetTaskRequest {
 pub struct GetTaskResponse {
     #[prost(message, optional, tag = "1")]
     pub task: ::core::option::Option<Task>,
+    /// states the task moved through, oldest first
+    #[prost(message, repeated, tag = "2")]
+    pub transitions: ::prost::alloc::vec::Vec<TaskTransition>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TaskTransition {
+    /// pending, running or finalized
+    #[prost(string, tag = "1")]
+    pub state: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub executor_id: ::prost::alloc::string::String,
+    /// outcome of a finalized task
+    #[prost(enumeration = "TaskOutcome", tag = "3")]
+    pub outcome: i32,
+    #[prost(uint64, tag = "4")]
+    pub at: u64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -777,94 +1132,1027 @@ pub struct UpdateLabelsRequest {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpdateLabelsResponse {}
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
-#[repr(i32)]
-pub enum TaskOutcome {
-    Unknown = 0,
-    Failed = 1,
-    Success = 2,
+/// cancel all unfinished tasks of a namespace matching the filter
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelTasksRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    /// empty matches every extraction graph
+    #[prost(string, tag = "2")]
+    pub extraction_graph: ::prost::alloc::string::String,
+    #[prost(map = "string, string", tag = "3")]
+    pub labels_eq: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+    /// bounds on the creation time of the content in seconds, 0 is unbounded
+    #[prost(int64, tag = "4")]
+    pub created_after: i64,
+    #[prost(int64, tag = "5")]
+    pub created_before: i64,
 }
-impl TaskOutcome {
-    /// String value of the enum field names used in the ProtoBuf definition.
-    ///
-    /// The values are not transformed in any way and thus are considered stable
-    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
-    pub fn as_str_name(&self) -> &'static str {
-        match self {
-            TaskOutcome::Unknown => "UNKNOWN",
-            TaskOutcome::Failed => "FAILED",
-            TaskOutcome::Success => "SUCCESS",
-        }
-    }
-    /// Creates an enum from field names used in the ProtoBuf definition.
-    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
-        match value {
-            "UNKNOWN" => Some(Self::Unknown),
-            "FAILED" => Some(Self::Failed),
-            "SUCCESS" => Some(Self::Success),
-            _ => None,
-        }
-    }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CancelTasksResponse {
+    #[prost(string, tag = "1")]
+    pub job_id: ::prost::alloc::string::String,
 }
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
-#[repr(i32)]
-pub enum GcTaskType {
-    Delete = 0,
-    UpdateLabels = 1,
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCancelTasksJobRequest {
+    #[prost(string, tag = "1")]
+    pub job_id: ::prost::alloc::string::String,
 }
-impl GcTaskType {
-    /// String value of the enum field names used in the ProtoBuf definition.
-    ///
-    /// The values are not transformed in any way and thus are considered stable
-    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
-    pub fn as_str_name(&self) -> &'static str {
-        match self {
-            GcTaskType::Delete => "Delete",
-            GcTaskType::UpdateLabels => "UpdateLabels",
-        }
-    }
-    /// Creates an enum from field names used in the ProtoBuf definition.
-    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
-        match value {
-            "Delete" => Some(Self::Delete),
-            "UpdateLabels" => Some(Self::UpdateLabels),
-            _ => None,
-        }
-    }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetCancelTasksJobResponse {
+    #[prost(string, tag = "1")]
+    pub job_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(enumeration = "CancelTasksJobState", tag = "3")]
+    pub state: i32,
+    #[prost(uint64, tag = "4")]
+    pub matched_tasks: u64,
+    #[prost(uint64, tag = "5")]
+    pub cancelled_tasks: u64,
+    #[prost(string, tag = "6")]
+    pub error: ::prost::alloc::string::String,
 }
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
-#[repr(i32)]
-pub enum CreateContentStatus {
-    Created = 0,
-    Duplicate = 1,
+/// run an extraction policy that completed on a content again
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RerunExtractionPolicyRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub content_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub extraction_graph: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub extraction_policy: ::prost::alloc::string::String,
+    /// Recorded as the initiator of the replay
+    #[prost(string, tag = "5")]
+    pub initiator: ::prost::alloc::string::String,
 }
-impl CreateContentStatus {
-    /// String value of the enum field names used in the ProtoBuf definition.
-    ///
-    /// The values are not transformed in any way and thus are considered stable
-    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
-    pub fn as_str_name(&self) -> &'static str {
-        match self {
-            CreateContentStatus::Created => "CREATED",
-            CreateContentStatus::Duplicate => "DUPLICATE",
-        }
-    }
-    /// Creates an enum from field names used in the ProtoBuf definition.
-    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
-        match value {
-            "CREATED" => Some(Self::Created),
-            "DUPLICATE" => Some(Self::Duplicate),
-            _ => None,
-        }
-    }
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RerunExtractionPolicyResponse {
+    #[prost(string, tag = "1")]
+    pub replay_id: ::prost::alloc::string::String,
 }
-/// Generated client implementations.
-pub mod coordinator_service_client {
-    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
-    use tonic::codegen::*;
-    use tonic::codegen::http::Uri;
-    #[derive(Debug, Clone)]
-    pub struct CoordinatorServiceClient<T> {
+/// put a quarantined task back into the queue
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RequeueTaskRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub task_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub initiator: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RequeueTaskResponse {
+    #[prost(string, tag = "1")]
+    pub replay_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StuckContent {
+    #[prost(string, tag = "1")]
+    pub content_id: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub outstanding_tasks: u64,
+    #[prost(uint64, tag = "3")]
+    pub pending_tasks: u64,
+    #[prost(uint64, tag = "4")]
+    pub stalled_secs: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListStuckContentRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(uint64, optional, tag = "2")]
+    pub stalled_for_secs: ::core::option::Option<u64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListStuckContentResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub content: ::prost::alloc::vec::Vec<StuckContent>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StarvedTask {
+    #[prost(string, tag = "1")]
+    pub task_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub extraction_graph: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub extraction_policy_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub extractor: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "6")]
+    pub unallocated_secs: u64,
+    #[prost(string, tag = "7")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListStarvedTasksRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(uint64, optional, tag = "2")]
+    pub unallocated_for_secs: ::core::option::Option<u64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListStarvedTasksResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub tasks: ::prost::alloc::vec::Vec<StarvedTask>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PlacementCandidate {
+    #[prost(string, tag = "1")]
+    pub executor_id: ::prost::alloc::string::String,
+    /// Empty for the executor that got the task
+    #[prost(string, tag = "2")]
+    pub rejection: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SchedulingAttempt {
+    #[prost(uint64, tag = "1")]
+    pub attempted_at: u64,
+    /// Empty when the task wasn't assigned
+    #[prost(string, tag = "2")]
+    pub executor_id: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub candidates: ::prost::alloc::vec::Vec<PlacementCandidate>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTaskSchedulingRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub task_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTaskSchedulingResponse {
+    /// Oldest first
+    #[prost(message, repeated, tag = "1")]
+    pub attempts: ::prost::alloc::vec::Vec<SchedulingAttempt>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RepairContentRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub content_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RepairContentResponse {
+    #[prost(uint64, tag = "1")]
+    pub created_tasks: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RerunFailedTasksRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub content_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub initiator: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RerunFailedTasksResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub task_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Empty when no task failed
+    #[prost(string, tag = "2")]
+    pub replay_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReplayTask {
+    #[prost(string, tag = "1")]
+    pub task_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub content_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub extraction_graph: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub extraction_policy: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "5")]
+    pub graph_version: u64,
+    #[prost(enumeration = "TaskOutcome", tag = "6")]
+    pub outcome: i32,
+    #[prost(string, tag = "7")]
+    pub replaced_by: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "8")]
+    pub outputs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Replay {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub kind: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub content_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub initiator: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "6")]
+    pub started_at: u64,
+    /// 0 while tasks of the replay are unfinished
+    #[prost(uint64, tag = "7")]
+    pub finished_at: u64,
+    #[prost(message, repeated, tag = "8")]
+    pub tasks: ::prost::alloc::vec::Vec<ReplayTask>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListReplaysRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    /// Only the replays that ran policies of the graph, when set
+    #[prost(string, tag = "2")]
+    pub extraction_graph: ::prost::alloc::string::String,
+    /// Only the replays of the content, when set
+    #[prost(string, tag = "3")]
+    pub content_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListReplaysResponse {
+    /// Latest first
+    #[prost(message, repeated, tag = "1")]
+    pub replays: ::prost::alloc::vec::Vec<Replay>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetReplayRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub replay_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetReplayResponse {
+    #[prost(message, optional, tag = "1")]
+    pub replay: ::core::option::Option<Replay>,
+    /// Content extracted by the tasks of the replay
+    #[prost(string, repeated, tag = "2")]
+    pub output_content_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// Time and estimated cost of the tasks of an extraction policy on a content
+/// tree
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExtractionPolicyBreakdown {
+    #[prost(string, tag = "1")]
+    pub extraction_graph: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub extraction_policy: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub tasks: u64,
+    #[prost(uint64, tag = "4")]
+    pub unfinished_tasks: u64,
+    #[prost(uint64, tag = "5")]
+    pub started_at: u64,
+    #[prost(uint64, tag = "6")]
+    pub finished_at: u64,
+    #[prost(uint64, tag = "7")]
+    pub queue_secs: u64,
+    #[prost(double, tag = "8")]
+    pub execution_secs: f64,
+    #[prost(double, tag = "9")]
+    pub estimated_cost: f64,
+    #[prost(uint64, tag = "10")]
+    pub unpriced_tasks: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InvocationBreakdown {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub content_id: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub started_at: u64,
+    #[prost(uint64, tag = "4")]
+    pub finished_at: u64,
+    #[prost(message, repeated, tag = "5")]
+    pub extraction_policies: ::prost::alloc::vec::Vec<ExtractionPolicyBreakdown>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetInvocationBreakdownRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub content_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetInvocationBreakdownResponse {
+    #[prost(message, optional, tag = "1")]
+    pub breakdown: ::core::option::Option<InvocationBreakdown>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GraphTemplateParameter {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub description: ::prost::alloc::string::String,
+    /// JSON value, empty when the parameter is required
+    #[prost(string, tag = "3")]
+    pub default_value: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GraphTemplate {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub description: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub parameters: ::prost::alloc::vec::Vec<GraphTemplateParameter>,
+    /// JSON extraction graph definition with placeholders
+    #[prost(string, tag = "4")]
+    pub graph: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub author: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "6")]
+    pub created_at: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetGraphTemplateRequest {
+    #[prost(message, optional, tag = "1")]
+    pub template: ::core::option::Option<GraphTemplate>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetGraphTemplateResponse {
+    #[prost(message, optional, tag = "1")]
+    pub template: ::core::option::Option<GraphTemplate>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListGraphTemplatesRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListGraphTemplatesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub templates: ::prost::alloc::vec::Vec<GraphTemplate>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetGraphTemplateRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetGraphTemplateResponse {
+    #[prost(message, optional, tag = "1")]
+    pub template: ::core::option::Option<GraphTemplate>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteGraphTemplateRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteGraphTemplateResponse {}
+/// Copy of a content in the blob store of another region
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ContentReplica {
+    #[prost(string, tag = "1")]
+    pub content_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub region: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub url: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub metadata_url: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "6")]
+    pub size_bytes: u64,
+    #[prost(string, tag = "7")]
+    pub hash: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "8")]
+    pub replicated_at: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetContentReplicaRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub content_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetContentReplicaResponse {
+    #[prost(message, optional, tag = "1")]
+    pub replica: ::core::option::Option<ContentReplica>,
+}
+/// key value context of the root content of a content
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetContentContextRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub content_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetContentContextResponse {
+    /// JSON encoded values by key
+    #[prost(map = "string, string", tag = "1")]
+    pub context: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetContentContextRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub content_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub key: ::prost::alloc::string::String,
+    /// JSON encoded value, the key is removed when unset
+    #[prost(string, optional, tag = "4")]
+    pub value: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetContentContextResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExtractionGraphVersion {
+    #[prost(uint64, tag = "1")]
+    pub version: u64,
+    #[prost(message, optional, tag = "2")]
+    pub extraction_graph: ::core::option::Option<ExtractionGraph>,
+    #[prost(string, tag = "3")]
+    pub author: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "4")]
+    pub created_at: u64,
+    #[prost(string, tag = "5")]
+    pub summary: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListExtractionGraphVersionsRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub extraction_graph: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListExtractionGraphVersionsResponse {
+    /// oldest first
+    #[prost(message, repeated, tag = "1")]
+    pub versions: ::prost::alloc::vec::Vec<ExtractionGraphVersion>,
+}
+/// Makes a prior definition of a graph its latest version
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RollbackExtractionGraphRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub extraction_graph: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub version: u64,
+    #[prost(string, tag = "4")]
+    pub author: ::prost::alloc::string::String,
+}
+/// Limits of 0 are unset
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SchedulerSettings {
+    #[prost(string, tag = "1")]
+    pub placement_strategy: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub max_tasks_per_executor: u32,
+    #[prost(map = "string, uint32", tag = "3")]
+    pub namespace_weights: ::std::collections::HashMap<::prost::alloc::string::String, u32>,
+    #[prost(uint32, tag = "4")]
+    pub max_pending_gc_tasks: u32,
+    #[prost(message, repeated, tag = "5")]
+    pub capacity_reservations: ::prost::alloc::vec::Vec<CapacityReservation>,
+    #[prost(map = "string, double", tag = "6")]
+    pub overcommit_factors: ::std::collections::HashMap<::prost::alloc::string::String, f64>,
+    #[prost(uint32, tag = "7")]
+    pub max_input_download_secs: u32,
+    #[prost(uint32, tag = "8")]
+    pub max_bundle_tasks: u32,
+    #[prost(uint64, tag = "9")]
+    pub max_bundle_bytes: u64,
+    #[prost(uint32, tag = "10")]
+    pub max_bundle_latency_ms: u32,
+}
+/// An empty extraction_graph reserves the slots for every graph of the
+/// namespace
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CapacityReservation {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub extraction_graph: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "3")]
+    pub slots: u32,
+    #[prost(uint64, tag = "4")]
+    pub start: u64,
+    #[prost(uint64, tag = "5")]
+    pub end: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSchedulerSettingsRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSchedulerSettingsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub settings: ::core::option::Option<SchedulerSettings>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetSchedulerSettingsRequest {
+    #[prost(message, optional, tag = "1")]
+    pub settings: ::core::option::Option<SchedulerSettings>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetSchedulerSettingsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub settings: ::core::option::Option<SchedulerSettings>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SimulatedExecutors {
+    #[prost(uint32, tag = "1")]
+    pub count: u32,
+    #[prost(string, repeated, tag = "2")]
+    pub extractors: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(uint32, optional, tag = "3")]
+    pub capacity_weight: ::core::option::Option<u32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SimulatedInvocations {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub extraction_graph: ::prost::alloc::string::String,
+    #[prost(double, tag = "3")]
+    pub per_hour: f64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SimulateSchedulingRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub executors: ::prost::alloc::vec::Vec<SimulatedExecutors>,
+    #[prost(message, repeated, tag = "2")]
+    pub invocations: ::prost::alloc::vec::Vec<SimulatedInvocations>,
+    #[prost(uint64, tag = "3")]
+    pub horizon_secs: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SimulatedQueue {
+    #[prost(string, tag = "1")]
+    pub extractor: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub executors: u64,
+    #[prost(uint64, tag = "3")]
+    pub unallocated: u64,
+    #[prost(uint64, tag = "4")]
+    pub queued: u64,
+    #[prost(uint64, optional, tag = "5")]
+    pub predicted_queued: ::core::option::Option<u64>,
+    #[prost(uint64, optional, tag = "6")]
+    pub predicted_wait_secs: ::core::option::Option<u64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SimulateSchedulingResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub extractors: ::prost::alloc::vec::Vec<SimulatedQueue>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MaintenanceMode {
+    #[prost(bool, tag = "1")]
+    pub enabled: bool,
+    #[prost(string, tag = "2")]
+    pub reason: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub changed_at: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetMaintenanceModeRequest {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetMaintenanceModeResponse {
+    #[prost(message, optional, tag = "1")]
+    pub maintenance: ::core::option::Option<MaintenanceMode>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetMaintenanceModeRequest {
+    #[prost(bool, tag = "1")]
+    pub enabled: bool,
+    #[prost(string, tag = "2")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetMaintenanceModeResponse {
+    #[prost(message, optional, tag = "1")]
+    pub maintenance: ::core::option::Option<MaintenanceMode>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MeteringRecord {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub period_start: u64,
+    #[prost(uint64, tag = "3")]
+    pub invocations: u64,
+    #[prost(map = "string, double", tag = "4")]
+    pub task_seconds: ::std::collections::HashMap<::prost::alloc::string::String, f64>,
+    #[prost(uint64, tag = "5")]
+    pub stored_bytes: u64,
+    #[prost(uint64, tag = "6")]
+    pub egress_bytes: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecordEgressRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub bytes: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecordEgressResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListMeteringRecordsRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub start: u64,
+    #[prost(uint64, tag = "3")]
+    pub end: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListMeteringRecordsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub records: ::prost::alloc::vec::Vec<MeteringRecord>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExtractionPolicyUsage {
+    #[prost(uint64, tag = "1")]
+    pub tasks: u64,
+    #[prost(double, tag = "2")]
+    pub execution_secs: f64,
+    #[prost(double, tag = "3")]
+    pub cpu_secs: f64,
+    #[prost(uint64, tag = "4")]
+    pub max_memory_bytes: u64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExtractionGraphUsage {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub extraction_graph: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub version: u64,
+    #[prost(map = "string, message", tag = "4")]
+    pub extraction_policies: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ExtractionPolicyUsage,
+    >,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetExtractionGraphUsageRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub extraction_graph: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetExtractionGraphUsageResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub usage: ::prost::alloc::vec::Vec<ExtractionGraphUsage>,
+}
+/// Objectives of the tasks of an extraction policy, shares of tasks are in
+/// parts per million
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExtractionPolicySlo {
+    /// none when 0
+    #[prost(uint64, tag = "1")]
+    pub latency_ms: u64,
+    #[prost(uint32, tag = "2")]
+    pub latency_target_ppm: u32,
+    /// none when 0
+    #[prost(uint32, tag = "3")]
+    pub max_error_rate_ppm: u32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SloCompliance {
+    #[prost(string, tag = "1")]
+    pub extraction_policy: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub tasks: u64,
+    #[prost(double, optional, tag = "3")]
+    pub latency_compliance: ::core::option::Option<f64>,
+    #[prost(double, tag = "4")]
+    pub error_rate: f64,
+    #[prost(double, optional, tag = "5")]
+    pub latency_burn_rate: ::core::option::Option<f64>,
+    #[prost(double, optional, tag = "6")]
+    pub error_burn_rate: ::core::option::Option<f64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSloComplianceRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub extraction_graph: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetSloComplianceResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub compliance: ::prost::alloc::vec::Vec<SloCompliance>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportNamespaceRequest {
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportNamespaceResponse {
+    /// JSON encoded archive of the namespace
+    #[prost(bytes = "vec", tag = "1")]
+    pub archive: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportNamespaceRequest {
+    /// namespace the archive is imported into
+    #[prost(string, tag = "1")]
+    pub namespace: ::prost::alloc::string::String,
+    /// JSON encoded archive of a namespace
+    #[prost(bytes = "vec", tag = "2")]
+    pub archive: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportNamespaceResponse {
+    #[prost(uint64, tag = "1")]
+    pub extraction_graphs: u64,
+    #[prost(uint64, tag = "2")]
+    pub extraction_graph_versions: u64,
+    #[prost(uint64, tag = "3")]
+    pub content: u64,
+    /// new id of the content by its id in the archive
+    #[prost(map = "string, string", tag = "4")]
+    pub content_ids: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum TaskOutcome {
+    Unknown = 0,
+    Failed = 1,
+    Success = 2,
+    Quarantined = 3,
+    Expired = 4,
+    Cancelled = 5,
+}
+impl TaskOutcome {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            TaskOutcome::Unknown => "UNKNOWN",
+            TaskOutcome::Failed => "FAILED",
+            TaskOutcome::Success => "SUCCESS",
+            TaskOutcome::Quarantined => "QUARANTINED",
+            TaskOutcome::Expired => "EXPIRED",
+            TaskOutcome::Cancelled => "CANCELLED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "UNKNOWN" => Some(Self::Unknown),
+            "FAILED" => Some(Self::Failed),
+            "SUCCESS" => Some(Self::Success),
+            "QUARANTINED" => Some(Self::Quarantined),
+            "EXPIRED" => Some(Self::Expired),
+            "CANCELLED" => Some(Self::Cancelled),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum GcTaskType {
+    Delete = 0,
+    UpdateLabels = 1,
+}
+impl GcTaskType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            GcTaskType::Delete => "Delete",
+            GcTaskType::UpdateLabels => "UpdateLabels",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "Delete" => Some(Self::Delete),
+            "UpdateLabels" => Some(Self::UpdateLabels),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum PressureLevel {
+    Normal = 0,
+    Elevated = 1,
+    Critical = 2,
+}
+impl PressureLevel {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            PressureLevel::Normal => "NORMAL",
+            PressureLevel::Elevated => "ELEVATED",
+            PressureLevel::Critical => "CRITICAL",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "NORMAL" => Some(Self::Normal),
+            "ELEVATED" => Some(Self::Elevated),
+            "CRITICAL" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum DiagnosticSeverity {
+    Error = 0,
+    Warning = 1,
+}
+impl DiagnosticSeverity {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            DiagnosticSeverity::Error => "ERROR",
+            DiagnosticSeverity::Warning => "WARNING",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "ERROR" => Some(Self::Error),
+            "WARNING" => Some(Self::Warning),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CreateContentStatus {
+    Created = 0,
+    Duplicate = 1,
+}
+impl CreateContentStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            CreateContentStatus::Created => "CREATED",
+            CreateContentStatus::Duplicate => "DUPLICATE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "CREATED" => Some(Self::Created),
+            "DUPLICATE" => Some(Self::Duplicate),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CancelTasksJobState {
+    Running = 0,
+    Completed = 1,
+    Aborted = 2,
+}
+impl CancelTasksJobState {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            CancelTasksJobState::Running => "RUNNING",
+            CancelTasksJobState::Completed => "COMPLETED",
+            CancelTasksJobState::Aborted => "ABORTED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "RUNNING" => Some(Self::Running),
+            "COMPLETED" => Some(Self::Completed),
+            "ABORTED" => Some(Self::Aborted),
+            _ => None,
+        }
+    }
+}
+/// Generated client implementations.
+pub mod coordinator_service_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    #[derive(Debug, Clone)]
+    pub struct CoordinatorServiceClient<T> {
         inner: tonic::client::Grpc<T>,
     }
     impl CoordinatorServiceClient<tonic::transport::Channel> {
@@ -877,77 +2165,1150 @@ pub mod coordinator_service_client {
             let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
             Ok(Self::new(conn))
         }
-    }
-    impl<T> CoordinatorServiceClient<T>
-    where
-        T: tonic::client::GrpcService<tonic::body::BoxBody>,
-        T::Error: Into<StdError>,
-        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
-        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
-    {
-        pub fn new(inner: T) -> Self {
-            let inner = tonic::client::Grpc::new(inner);
-            Self { inner }
+    }
+    impl<T> CoordinatorServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> CoordinatorServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + Send + Sync,
+        {
+            CoordinatorServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        pub async fn create_content(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateContentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateContentResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/CreateContent",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "CreateContent",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn tombstone_content(
+            &mut self,
+            request: impl tonic::IntoRequest<super::TombstoneContentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::TombstoneContentResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/TombstoneContent",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "TombstoneContent",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_content_metadata(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetContentMetadataRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetContentMetadataResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetContentMetadata",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetContentMetadata",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_content_tree_metadata(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetContentTreeMetadataRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetContentTreeMetadataResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetContentTreeMetadata",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetContentTreeMetadata",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_content(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListContentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListContentResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListContent",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListContent",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn create_extraction_graph(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateExtractionGraphRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateExtractionGraphResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/CreateExtractionGraph",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "CreateExtractionGraph",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn validate_extraction_graph(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateExtractionGraphRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ValidateExtractionGraphResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ValidateExtractionGraph",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ValidateExtractionGraph",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_extraction_policy(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetExtractionPolicyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetExtractionPolicyResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetExtractionPolicy",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetExtractionPolicy",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_extraction_policies(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListExtractionPoliciesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListExtractionPoliciesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListExtractionPolicies",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListExtractionPolicies",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn create_ns(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateNamespaceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateNamespaceResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/CreateNS",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "CreateNS",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_ns(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListNamespaceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListNamespaceResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListNS",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("indexify_coordinator.CoordinatorService", "ListNS"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_ns(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetNamespaceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetNamespaceResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetNS",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("indexify_coordinator.CoordinatorService", "GetNS"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_extractors(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListExtractorsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListExtractorsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListExtractors",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListExtractors",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn register_executor(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RegisterExecutorRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RegisterExecutorResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/RegisterExecutor",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "RegisterExecutor",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn register_ingestion_server(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RegisterIngestionServerRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RegisterIngestionServerResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/RegisterIngestionServer",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "RegisterIngestionServer",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn remove_ingestion_server(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RemoveIngestionServerRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RemoveIngestionServerResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/RemoveIngestionServer",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "RemoveIngestionServer",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn create_gc_tasks(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateGcTasksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateGcTasksResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/CreateGCTasks",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "CreateGCTasks",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn gc_tasks_stream(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<
+                Message = super::GcTaskAcknowledgement,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::CoordinatorCommand>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GCTasksStream",
+            );
+            let mut req = request.into_streaming_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GCTasksStream",
+                    ),
+                );
+            self.inner.streaming(req, path, codec).await
+        }
+        pub async fn heartbeat(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<Message = super::HeartbeatRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::HeartbeatResponse>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/Heartbeat",
+            );
+            let mut req = request.into_streaming_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "Heartbeat",
+                    ),
+                );
+            self.inner.streaming(req, path, codec).await
+        }
+        pub async fn list_indexes(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListIndexesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListIndexesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListIndexes",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListIndexes",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_index(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetIndexRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetIndexResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetIndex",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetIndex",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn update_indexes_state(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateIndexesStateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateIndexesStateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/UpdateIndexesState",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "UpdateIndexesState",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_extractor_coordinates(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetExtractorCoordinatesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetExtractorCoordinatesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetExtractorCoordinates",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetExtractorCoordinates",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn update_task(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateTaskRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateTaskResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/UpdateTask",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "UpdateTask",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_state_changes(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListStateChangesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListStateChangesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListStateChanges",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListStateChanges",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_tasks(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListTasksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListTasksResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListTasks",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListTasks",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_executor_tasks(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListExecutorTasksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListTasksResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListExecutorTasks",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListExecutorTasks",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_task_queue_depths(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListTaskQueueDepthsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListTaskQueueDepthsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListTaskQueueDepths",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListTaskQueueDepths",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_schema(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetSchemaRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetSchemaResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetSchema",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetSchema",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn list_schemas(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetAllSchemaRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetAllSchemaResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListSchemas",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListSchemas",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        pub fn with_origin(inner: T, origin: Uri) -> Self {
-            let inner = tonic::client::Grpc::with_origin(inner, origin);
-            Self { inner }
+        pub async fn get_raft_metrics_snapshot(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetRaftMetricsSnapshotRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RaftMetricsSnapshotResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetRaftMetricsSnapshot",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetRaftMetricsSnapshot",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> CoordinatorServiceClient<InterceptedService<T, F>>
-        where
-            F: tonic::service::Interceptor,
-            T::ResponseBody: Default,
-            T: tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-                Response = http::Response<
-                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
-                >,
-            >,
-            <T as tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-            >>::Error: Into<StdError> + Send + Sync,
-        {
-            CoordinatorServiceClient::new(InterceptedService::new(inner, interceptor))
+        pub async fn get_all_task_assignments(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetAllTaskAssignmentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::TaskAssignments>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetAllTaskAssignments",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetAllTaskAssignments",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        /// Compress requests with the given encoding.
-        ///
-        /// This requires the server to support it otherwise it might respond with an
-        /// error.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.send_compressed(encoding);
-            self
+        pub async fn get_task(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetTaskRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetTaskResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetTask",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("indexify_coordinator.CoordinatorService", "GetTask"),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        /// Enable decompressing responses.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.accept_compressed(encoding);
-            self
+        pub async fn get_ingestion_info(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetIngestionInfoRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetIngestionInfoResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetIngestionInfo",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "GetIngestionInfo",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        /// Limits the maximum size of a decoded message.
-        ///
-        /// Default: `4MB`
-        #[must_use]
-        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
-            self.inner = self.inner.max_decoding_message_size(limit);
-            self
+        pub async fn wait_content_extraction(
+            &mut self,
+            request: impl tonic::IntoRequest<super::WaitContentExtractionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::WaitContentExtractionResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/WaitContentExtraction",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "WaitContentExtraction",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        /// Limits the maximum size of an encoded message.
-        ///
-        /// Default: `usize::MAX`
-        #[must_use]
-        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
-            self.inner = self.inner.max_encoding_message_size(limit);
-            self
+        pub async fn list_active_contents(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListActiveContentsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListActiveContentsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/ListActiveContents",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListActiveContents",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
         }
-        pub async fn create_content(
+        pub async fn update_labels(
             &mut self,
-            request: impl tonic::IntoRequest<super::CreateContentRequest>,
+            request: impl tonic::IntoRequest<super::UpdateLabelsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateContentResponse>,
+            tonic::Response<super::UpdateLabelsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -961,23 +3322,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/CreateContent",
+                "/indexify_coordinator.CoordinatorService/UpdateLabels",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "CreateContent",
+                        "UpdateLabels",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn tombstone_content(
+        pub async fn cancel_tasks(
             &mut self,
-            request: impl tonic::IntoRequest<super::TombstoneContentRequest>,
+            request: impl tonic::IntoRequest<super::CancelTasksRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::TombstoneContentResponse>,
+            tonic::Response<super::CancelTasksResponse>,
             tonic::Status,
         > {
             self.inner
@@ -991,23 +3352,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/TombstoneContent",
+                "/indexify_coordinator.CoordinatorService/CancelTasks",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "TombstoneContent",
+                        "CancelTasks",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_content_metadata(
+        pub async fn get_cancel_tasks_job(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetContentMetadataRequest>,
+            request: impl tonic::IntoRequest<super::GetCancelTasksJobRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetContentMetadataResponse>,
+            tonic::Response<super::GetCancelTasksJobResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1021,23 +3382,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetContentMetadata",
+                "/indexify_coordinator.CoordinatorService/GetCancelTasksJob",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "GetContentMetadata",
+                        "GetCancelTasksJob",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_content_tree_metadata(
+        pub async fn rerun_extraction_policy(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetContentTreeMetadataRequest>,
+            request: impl tonic::IntoRequest<super::RerunExtractionPolicyRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetContentTreeMetadataResponse>,
+            tonic::Response<super::RerunExtractionPolicyResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1051,23 +3412,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetContentTreeMetadata",
+                "/indexify_coordinator.CoordinatorService/RerunExtractionPolicy",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "GetContentTreeMetadata",
+                        "RerunExtractionPolicy",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_content(
+        pub async fn requeue_task(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListContentRequest>,
+            request: impl tonic::IntoRequest<super::RequeueTaskRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListContentResponse>,
+            tonic::Response<super::RequeueTaskResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1081,23 +3442,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListContent",
+                "/indexify_coordinator.CoordinatorService/RequeueTask",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "ListContent",
+                        "RequeueTask",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn create_extraction_graph(
+        pub async fn list_stuck_content(
             &mut self,
-            request: impl tonic::IntoRequest<super::CreateExtractionGraphRequest>,
+            request: impl tonic::IntoRequest<super::ListStuckContentRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateExtractionGraphResponse>,
+            tonic::Response<super::ListStuckContentResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1111,23 +3472,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/CreateExtractionGraph",
+                "/indexify_coordinator.CoordinatorService/ListStuckContent",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "CreateExtractionGraph",
+                        "ListStuckContent",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_extraction_policy(
+        pub async fn repair_content(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetExtractionPolicyRequest>,
+            request: impl tonic::IntoRequest<super::RepairContentRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetExtractionPolicyResponse>,
+            tonic::Response<super::RepairContentResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1141,23 +3502,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetExtractionPolicy",
+                "/indexify_coordinator.CoordinatorService/RepairContent",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "GetExtractionPolicy",
+                        "RepairContent",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_extraction_policies(
+        pub async fn get_content_context(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListExtractionPoliciesRequest>,
+            request: impl tonic::IntoRequest<super::GetContentContextRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListExtractionPoliciesResponse>,
+            tonic::Response<super::GetContentContextResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1171,23 +3532,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListExtractionPolicies",
+                "/indexify_coordinator.CoordinatorService/GetContentContext",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "ListExtractionPolicies",
+                        "GetContentContext",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn create_ns(
+        pub async fn set_content_context(
             &mut self,
-            request: impl tonic::IntoRequest<super::CreateNamespaceRequest>,
+            request: impl tonic::IntoRequest<super::SetContentContextRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateNamespaceResponse>,
+            tonic::Response<super::SetContentContextResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1201,23 +3562,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/CreateNS",
+                "/indexify_coordinator.CoordinatorService/SetContentContext",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "CreateNS",
+                        "SetContentContext",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_ns(
+        pub async fn list_extraction_graph_versions(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListNamespaceRequest>,
+            request: impl tonic::IntoRequest<super::ListExtractionGraphVersionsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListNamespaceResponse>,
+            tonic::Response<super::ListExtractionGraphVersionsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1231,20 +3592,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListNS",
+                "/indexify_coordinator.CoordinatorService/ListExtractionGraphVersions",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("indexify_coordinator.CoordinatorService", "ListNS"),
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "ListExtractionGraphVersions",
+                    ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_ns(
+        pub async fn rollback_extraction_graph(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetNamespaceRequest>,
+            request: impl tonic::IntoRequest<super::RollbackExtractionGraphRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetNamespaceResponse>,
+            tonic::Response<super::CreateExtractionGraphResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1258,20 +3622,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetNS",
+                "/indexify_coordinator.CoordinatorService/RollbackExtractionGraph",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("indexify_coordinator.CoordinatorService", "GetNS"),
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "RollbackExtractionGraph",
+                    ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_extractors(
+        pub async fn get_scheduler_settings(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListExtractorsRequest>,
+            request: impl tonic::IntoRequest<super::GetSchedulerSettingsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListExtractorsResponse>,
+            tonic::Response<super::GetSchedulerSettingsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1285,23 +3652,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListExtractors",
+                "/indexify_coordinator.CoordinatorService/GetSchedulerSettings",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "ListExtractors",
+                        "GetSchedulerSettings",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn register_executor(
+        pub async fn set_scheduler_settings(
             &mut self,
-            request: impl tonic::IntoRequest<super::RegisterExecutorRequest>,
+            request: impl tonic::IntoRequest<super::SetSchedulerSettingsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::RegisterExecutorResponse>,
+            tonic::Response<super::SetSchedulerSettingsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1315,23 +3682,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/RegisterExecutor",
+                "/indexify_coordinator.CoordinatorService/SetSchedulerSettings",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "RegisterExecutor",
+                        "SetSchedulerSettings",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn register_ingestion_server(
+        pub async fn simulate_scheduling(
             &mut self,
-            request: impl tonic::IntoRequest<super::RegisterIngestionServerRequest>,
+            request: impl tonic::IntoRequest<super::SimulateSchedulingRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::RegisterIngestionServerResponse>,
+            tonic::Response<super::SimulateSchedulingResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1345,23 +3712,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/RegisterIngestionServer",
+                "/indexify_coordinator.CoordinatorService/SimulateScheduling",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "RegisterIngestionServer",
+                        "SimulateScheduling",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn remove_ingestion_server(
+        pub async fn get_maintenance_mode(
             &mut self,
-            request: impl tonic::IntoRequest<super::RemoveIngestionServerRequest>,
+            request: impl tonic::IntoRequest<super::GetMaintenanceModeRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::RemoveIngestionServerResponse>,
+            tonic::Response<super::GetMaintenanceModeResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1375,23 +3742,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/RemoveIngestionServer",
+                "/indexify_coordinator.CoordinatorService/GetMaintenanceMode",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "RemoveIngestionServer",
+                        "GetMaintenanceMode",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn create_gc_tasks(
+        pub async fn set_maintenance_mode(
             &mut self,
-            request: impl tonic::IntoRequest<super::CreateGcTasksRequest>,
+            request: impl tonic::IntoRequest<super::SetMaintenanceModeRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::CreateGcTasksResponse>,
+            tonic::Response<super::SetMaintenanceModeResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1405,25 +3772,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/CreateGCTasks",
+                "/indexify_coordinator.CoordinatorService/SetMaintenanceMode",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "CreateGCTasks",
+                        "SetMaintenanceMode",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn gc_tasks_stream(
+        pub async fn rerun_failed_tasks(
             &mut self,
-            request: impl tonic::IntoStreamingRequest<
-                Message = super::GcTaskAcknowledgement,
-            >,
+            request: impl tonic::IntoRequest<super::RerunFailedTasksRequest>,
         ) -> std::result::Result<
-            tonic::Response<tonic::codec::Streaming<super::CoordinatorCommand>>,
+            tonic::Response<super::RerunFailedTasksResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1437,23 +3802,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GCTasksStream",
+                "/indexify_coordinator.CoordinatorService/RerunFailedTasks",
             );
-            let mut req = request.into_streaming_request();
+            let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "GCTasksStream",
+                        "RerunFailedTasks",
                     ),
                 );
-            self.inner.streaming(req, path, codec).await
+            self.inner.unary(req, path, codec).await
         }
-        pub async fn heartbeat(
+        pub async fn record_egress(
             &mut self,
-            request: impl tonic::IntoStreamingRequest<Message = super::HeartbeatRequest>,
+            request: impl tonic::IntoRequest<super::RecordEgressRequest>,
         ) -> std::result::Result<
-            tonic::Response<tonic::codec::Streaming<super::HeartbeatResponse>>,
+            tonic::Response<super::RecordEgressResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1467,23 +3832,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/Heartbeat",
+                "/indexify_coordinator.CoordinatorService/RecordEgress",
             );
-            let mut req = request.into_streaming_request();
+            let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "Heartbeat",
+                        "RecordEgress",
                     ),
                 );
-            self.inner.streaming(req, path, codec).await
+            self.inner.unary(req, path, codec).await
         }
-        pub async fn list_indexes(
+        pub async fn list_metering_records(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListIndexesRequest>,
+            request: impl tonic::IntoRequest<super::ListMeteringRecordsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListIndexesResponse>,
+            tonic::Response<super::ListMeteringRecordsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1497,23 +3862,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListIndexes",
+                "/indexify_coordinator.CoordinatorService/ListMeteringRecords",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "ListIndexes",
+                        "ListMeteringRecords",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_index(
+        pub async fn get_extraction_graph_usage(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetIndexRequest>,
+            request: impl tonic::IntoRequest<super::GetExtractionGraphUsageRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetIndexResponse>,
+            tonic::Response<super::GetExtractionGraphUsageResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1527,23 +3892,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetIndex",
+                "/indexify_coordinator.CoordinatorService/GetExtractionGraphUsage",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "GetIndex",
+                        "GetExtractionGraphUsage",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn update_indexes_state(
+        pub async fn list_executors(
             &mut self,
-            request: impl tonic::IntoRequest<super::UpdateIndexesStateRequest>,
+            request: impl tonic::IntoRequest<super::ListExecutorsRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::UpdateIndexesStateResponse>,
+            tonic::Response<super::ListExecutorsResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1557,23 +3922,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/UpdateIndexesState",
+                "/indexify_coordinator.CoordinatorService/ListExecutors",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "UpdateIndexesState",
+                        "ListExecutors",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_extractor_coordinates(
+        pub async fn list_starved_tasks(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetExtractorCoordinatesRequest>,
+            request: impl tonic::IntoRequest<super::ListStarvedTasksRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetExtractorCoordinatesResponse>,
+            tonic::Response<super::ListStarvedTasksResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1587,23 +3952,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetExtractorCoordinates",
+                "/indexify_coordinator.CoordinatorService/ListStarvedTasks",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "GetExtractorCoordinates",
+                        "ListStarvedTasks",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn update_task(
+        pub async fn export_namespace(
             &mut self,
-            request: impl tonic::IntoRequest<super::UpdateTaskRequest>,
+            request: impl tonic::IntoRequest<super::ExportNamespaceRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::UpdateTaskResponse>,
+            tonic::Response<super::ExportNamespaceResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1617,23 +3982,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/UpdateTask",
+                "/indexify_coordinator.CoordinatorService/ExportNamespace",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "UpdateTask",
+                        "ExportNamespace",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_state_changes(
+        pub async fn import_namespace(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListStateChangesRequest>,
+            request: impl tonic::IntoRequest<super::ImportNamespaceRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListStateChangesResponse>,
+            tonic::Response<super::ImportNamespaceResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1647,23 +4012,53 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListStateChanges",
+                "/indexify_coordinator.CoordinatorService/ImportNamespace",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "ListStateChanges",
+                        "ImportNamespace",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_tasks(
+        pub async fn stream_content(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListContentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::ContentMetadata>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/StreamContent",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "StreamContent",
+                    ),
+                );
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn stream_tasks(
             &mut self,
             request: impl tonic::IntoRequest<super::ListTasksRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListTasksResponse>,
+            tonic::Response<tonic::codec::Streaming<super::Task>>,
             tonic::Status,
         > {
             self.inner
@@ -1677,23 +4072,113 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListTasks",
+                "/indexify_coordinator.CoordinatorService/StreamTasks",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "StreamTasks",
+                    ),
+                );
+            self.inner.server_streaming(req, path, codec).await
+        }
+        pub async fn deregister_executor(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeregisterExecutorRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DeregisterExecutorResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/DeregisterExecutor",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "DeregisterExecutor",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn update_tasks(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateTasksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateTasksResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/UpdateTasks",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "UpdateTasks",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn get_slo_compliance(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetSloComplianceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetSloComplianceResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/indexify_coordinator.CoordinatorService/GetSloCompliance",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "ListTasks",
+                        "GetSloCompliance",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_schema(
+        pub async fn get_task_scheduling(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetSchemaRequest>,
+            request: impl tonic::IntoRequest<super::GetTaskSchedulingRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetSchemaResponse>,
+            tonic::Response<super::GetTaskSchedulingResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1707,23 +4192,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetSchema",
+                "/indexify_coordinator.CoordinatorService/GetTaskScheduling",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "GetSchema",
+                        "GetTaskScheduling",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_schemas(
+        pub async fn list_replays(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetAllSchemaRequest>,
+            request: impl tonic::IntoRequest<super::ListReplaysRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetAllSchemaResponse>,
+            tonic::Response<super::ListReplaysResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1737,23 +4222,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListSchemas",
+                "/indexify_coordinator.CoordinatorService/ListReplays",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "ListSchemas",
+                        "ListReplays",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_raft_metrics_snapshot(
+        pub async fn get_replay(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetRaftMetricsSnapshotRequest>,
+            request: impl tonic::IntoRequest<super::GetReplayRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::RaftMetricsSnapshotResponse>,
+            tonic::Response<super::GetReplayResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1767,23 +4252,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetRaftMetricsSnapshot",
+                "/indexify_coordinator.CoordinatorService/GetReplay",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "GetRaftMetricsSnapshot",
+                        "GetReplay",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_all_task_assignments(
+        pub async fn get_invocation_breakdown(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetAllTaskAssignmentRequest>,
+            request: impl tonic::IntoRequest<super::GetInvocationBreakdownRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::TaskAssignments>,
+            tonic::Response<super::GetInvocationBreakdownResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1797,23 +4282,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetAllTaskAssignments",
+                "/indexify_coordinator.CoordinatorService/GetInvocationBreakdown",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "GetAllTaskAssignments",
+                        "GetInvocationBreakdown",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_task(
+        pub async fn set_graph_template(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetTaskRequest>,
+            request: impl tonic::IntoRequest<super::SetGraphTemplateRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetTaskResponse>,
+            tonic::Response<super::SetGraphTemplateResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1827,20 +4312,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetTask",
+                "/indexify_coordinator.CoordinatorService/SetGraphTemplate",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
-                    GrpcMethod::new("indexify_coordinator.CoordinatorService", "GetTask"),
+                    GrpcMethod::new(
+                        "indexify_coordinator.CoordinatorService",
+                        "SetGraphTemplate",
+                    ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn get_ingestion_info(
+        pub async fn list_graph_templates(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetIngestionInfoRequest>,
+            request: impl tonic::IntoRequest<super::ListGraphTemplatesRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetIngestionInfoResponse>,
+            tonic::Response<super::ListGraphTemplatesResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1854,23 +4342,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/GetIngestionInfo",
+                "/indexify_coordinator.CoordinatorService/ListGraphTemplates",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "GetIngestionInfo",
+                        "ListGraphTemplates",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn wait_content_extraction(
+        pub async fn get_graph_template(
             &mut self,
-            request: impl tonic::IntoRequest<super::WaitContentExtractionRequest>,
+            request: impl tonic::IntoRequest<super::GetGraphTemplateRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::WaitContentExtractionResponse>,
+            tonic::Response<super::GetGraphTemplateResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1884,23 +4372,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/WaitContentExtraction",
+                "/indexify_coordinator.CoordinatorService/GetGraphTemplate",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "WaitContentExtraction",
+                        "GetGraphTemplate",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn list_active_contents(
+        pub async fn delete_graph_template(
             &mut self,
-            request: impl tonic::IntoRequest<super::ListActiveContentsRequest>,
+            request: impl tonic::IntoRequest<super::DeleteGraphTemplateRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListActiveContentsResponse>,
+            tonic::Response<super::DeleteGraphTemplateResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1914,23 +4402,23 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/ListActiveContents",
+                "/indexify_coordinator.CoordinatorService/DeleteGraphTemplate",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "ListActiveContents",
+                        "DeleteGraphTemplate",
                     ),
                 );
             self.inner.unary(req, path, codec).await
         }
-        pub async fn update_labels(
+        pub async fn get_content_replica(
             &mut self,
-            request: impl tonic::IntoRequest<super::UpdateLabelsRequest>,
+            request: impl tonic::IntoRequest<super::GetContentReplicaRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::UpdateLabelsResponse>,
+            tonic::Response<super::GetContentReplicaResponse>,
             tonic::Status,
         > {
             self.inner
@@ -1944,14 +4432,14 @@ pub mod coordinator_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/indexify_coordinator.CoordinatorService/UpdateLabels",
+                "/indexify_coordinator.CoordinatorService/GetContentReplica",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
                         "indexify_coordinator.CoordinatorService",
-                        "UpdateLabels",
+                        "GetContentReplica",
                     ),
                 );
             self.inner.unary(req, path, codec).await
@@ -2007,6 +4495,13 @@ pub mod coordinator_service_server {
             tonic::Response<super::CreateExtractionGraphResponse>,
             tonic::Status,
         >;
+        async fn validate_extraction_graph(
+            &self,
+            request: tonic::Request<super::CreateExtractionGraphRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ValidateExtractionGraphResponse>,
+            tonic::Status,
+        >;
         async fn get_extraction_policy(
             &self,
             request: tonic::Request<super::GetExtractionPolicyRequest>,
@@ -2118,92 +4613,371 @@ pub mod coordinator_service_server {
             &self,
             request: tonic::Request<super::UpdateIndexesStateRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::UpdateIndexesStateResponse>,
+            tonic::Response<super::UpdateIndexesStateResponse>,
+            tonic::Status,
+        >;
+        async fn get_extractor_coordinates(
+            &self,
+            request: tonic::Request<super::GetExtractorCoordinatesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetExtractorCoordinatesResponse>,
+            tonic::Status,
+        >;
+        async fn update_task(
+            &self,
+            request: tonic::Request<super::UpdateTaskRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateTaskResponse>,
+            tonic::Status,
+        >;
+        async fn list_state_changes(
+            &self,
+            request: tonic::Request<super::ListStateChangesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListStateChangesResponse>,
+            tonic::Status,
+        >;
+        async fn list_tasks(
+            &self,
+            request: tonic::Request<super::ListTasksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListTasksResponse>,
+            tonic::Status,
+        >;
+        async fn list_executor_tasks(
+            &self,
+            request: tonic::Request<super::ListExecutorTasksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListTasksResponse>,
+            tonic::Status,
+        >;
+        async fn list_task_queue_depths(
+            &self,
+            request: tonic::Request<super::ListTaskQueueDepthsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListTaskQueueDepthsResponse>,
+            tonic::Status,
+        >;
+        async fn get_schema(
+            &self,
+            request: tonic::Request<super::GetSchemaRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetSchemaResponse>,
+            tonic::Status,
+        >;
+        async fn list_schemas(
+            &self,
+            request: tonic::Request<super::GetAllSchemaRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetAllSchemaResponse>,
+            tonic::Status,
+        >;
+        async fn get_raft_metrics_snapshot(
+            &self,
+            request: tonic::Request<super::GetRaftMetricsSnapshotRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RaftMetricsSnapshotResponse>,
+            tonic::Status,
+        >;
+        async fn get_all_task_assignments(
+            &self,
+            request: tonic::Request<super::GetAllTaskAssignmentRequest>,
+        ) -> std::result::Result<tonic::Response<super::TaskAssignments>, tonic::Status>;
+        async fn get_task(
+            &self,
+            request: tonic::Request<super::GetTaskRequest>,
+        ) -> std::result::Result<tonic::Response<super::GetTaskResponse>, tonic::Status>;
+        async fn get_ingestion_info(
+            &self,
+            request: tonic::Request<super::GetIngestionInfoRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetIngestionInfoResponse>,
+            tonic::Status,
+        >;
+        async fn wait_content_extraction(
+            &self,
+            request: tonic::Request<super::WaitContentExtractionRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::WaitContentExtractionResponse>,
+            tonic::Status,
+        >;
+        async fn list_active_contents(
+            &self,
+            request: tonic::Request<super::ListActiveContentsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListActiveContentsResponse>,
+            tonic::Status,
+        >;
+        async fn update_labels(
+            &self,
+            request: tonic::Request<super::UpdateLabelsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::UpdateLabelsResponse>,
+            tonic::Status,
+        >;
+        async fn cancel_tasks(
+            &self,
+            request: tonic::Request<super::CancelTasksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CancelTasksResponse>,
+            tonic::Status,
+        >;
+        async fn get_cancel_tasks_job(
+            &self,
+            request: tonic::Request<super::GetCancelTasksJobRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetCancelTasksJobResponse>,
+            tonic::Status,
+        >;
+        async fn rerun_extraction_policy(
+            &self,
+            request: tonic::Request<super::RerunExtractionPolicyRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RerunExtractionPolicyResponse>,
+            tonic::Status,
+        >;
+        async fn requeue_task(
+            &self,
+            request: tonic::Request<super::RequeueTaskRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RequeueTaskResponse>,
+            tonic::Status,
+        >;
+        async fn list_stuck_content(
+            &self,
+            request: tonic::Request<super::ListStuckContentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListStuckContentResponse>,
+            tonic::Status,
+        >;
+        async fn repair_content(
+            &self,
+            request: tonic::Request<super::RepairContentRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RepairContentResponse>,
+            tonic::Status,
+        >;
+        async fn get_content_context(
+            &self,
+            request: tonic::Request<super::GetContentContextRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetContentContextResponse>,
+            tonic::Status,
+        >;
+        async fn set_content_context(
+            &self,
+            request: tonic::Request<super::SetContentContextRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SetContentContextResponse>,
+            tonic::Status,
+        >;
+        async fn list_extraction_graph_versions(
+            &self,
+            request: tonic::Request<super::ListExtractionGraphVersionsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListExtractionGraphVersionsResponse>,
+            tonic::Status,
+        >;
+        async fn rollback_extraction_graph(
+            &self,
+            request: tonic::Request<super::RollbackExtractionGraphRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateExtractionGraphResponse>,
+            tonic::Status,
+        >;
+        async fn get_scheduler_settings(
+            &self,
+            request: tonic::Request<super::GetSchedulerSettingsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetSchedulerSettingsResponse>,
+            tonic::Status,
+        >;
+        async fn set_scheduler_settings(
+            &self,
+            request: tonic::Request<super::SetSchedulerSettingsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SetSchedulerSettingsResponse>,
+            tonic::Status,
+        >;
+        async fn simulate_scheduling(
+            &self,
+            request: tonic::Request<super::SimulateSchedulingRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SimulateSchedulingResponse>,
+            tonic::Status,
+        >;
+        async fn get_maintenance_mode(
+            &self,
+            request: tonic::Request<super::GetMaintenanceModeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetMaintenanceModeResponse>,
+            tonic::Status,
+        >;
+        async fn set_maintenance_mode(
+            &self,
+            request: tonic::Request<super::SetMaintenanceModeRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::SetMaintenanceModeResponse>,
+            tonic::Status,
+        >;
+        async fn rerun_failed_tasks(
+            &self,
+            request: tonic::Request<super::RerunFailedTasksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RerunFailedTasksResponse>,
+            tonic::Status,
+        >;
+        async fn record_egress(
+            &self,
+            request: tonic::Request<super::RecordEgressRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::RecordEgressResponse>,
+            tonic::Status,
+        >;
+        async fn list_metering_records(
+            &self,
+            request: tonic::Request<super::ListMeteringRecordsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListMeteringRecordsResponse>,
+            tonic::Status,
+        >;
+        async fn get_extraction_graph_usage(
+            &self,
+            request: tonic::Request<super::GetExtractionGraphUsageRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetExtractionGraphUsageResponse>,
+            tonic::Status,
+        >;
+        async fn list_executors(
+            &self,
+            request: tonic::Request<super::ListExecutorsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListExecutorsResponse>,
+            tonic::Status,
+        >;
+        async fn list_starved_tasks(
+            &self,
+            request: tonic::Request<super::ListStarvedTasksRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListStarvedTasksResponse>,
+            tonic::Status,
+        >;
+        async fn export_namespace(
+            &self,
+            request: tonic::Request<super::ExportNamespaceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ExportNamespaceResponse>,
+            tonic::Status,
+        >;
+        async fn import_namespace(
+            &self,
+            request: tonic::Request<super::ImportNamespaceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ImportNamespaceResponse>,
+            tonic::Status,
+        >;
+        /// Server streaming response type for the StreamContent method.
+        type StreamContentStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::ContentMetadata, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        async fn stream_content(
+            &self,
+            request: tonic::Request<super::ListContentRequest>,
+        ) -> std::result::Result<tonic::Response<Self::StreamContentStream>, tonic::Status>;
+        /// Server streaming response type for the StreamTasks method.
+        type StreamTasksStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::Task, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        async fn stream_tasks(
+            &self,
+            request: tonic::Request<super::ListTasksRequest>,
+        ) -> std::result::Result<tonic::Response<Self::StreamTasksStream>, tonic::Status>;
+        async fn deregister_executor(
+            &self,
+            request: tonic::Request<super::DeregisterExecutorRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::DeregisterExecutorResponse>,
             tonic::Status,
         >;
-        async fn get_extractor_coordinates(
+        async fn update_tasks(
             &self,
-            request: tonic::Request<super::GetExtractorCoordinatesRequest>,
+            request: tonic::Request<super::UpdateTasksRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetExtractorCoordinatesResponse>,
+            tonic::Response<super::UpdateTasksResponse>,
             tonic::Status,
         >;
-        async fn update_task(
+        async fn get_slo_compliance(
             &self,
-            request: tonic::Request<super::UpdateTaskRequest>,
+            request: tonic::Request<super::GetSloComplianceRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::UpdateTaskResponse>,
+            tonic::Response<super::GetSloComplianceResponse>,
             tonic::Status,
         >;
-        async fn list_state_changes(
+        async fn get_task_scheduling(
             &self,
-            request: tonic::Request<super::ListStateChangesRequest>,
+            request: tonic::Request<super::GetTaskSchedulingRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListStateChangesResponse>,
+            tonic::Response<super::GetTaskSchedulingResponse>,
             tonic::Status,
         >;
-        async fn list_tasks(
+        async fn list_replays(
             &self,
-            request: tonic::Request<super::ListTasksRequest>,
+            request: tonic::Request<super::ListReplaysRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListTasksResponse>,
+            tonic::Response<super::ListReplaysResponse>,
             tonic::Status,
         >;
-        async fn get_schema(
+        async fn get_replay(
             &self,
-            request: tonic::Request<super::GetSchemaRequest>,
+            request: tonic::Request<super::GetReplayRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetSchemaResponse>,
+            tonic::Response<super::GetReplayResponse>,
             tonic::Status,
         >;
-        async fn list_schemas(
+        async fn get_invocation_breakdown(
             &self,
-            request: tonic::Request<super::GetAllSchemaRequest>,
+            request: tonic::Request<super::GetInvocationBreakdownRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetAllSchemaResponse>,
+            tonic::Response<super::GetInvocationBreakdownResponse>,
             tonic::Status,
         >;
-        async fn get_raft_metrics_snapshot(
+        async fn set_graph_template(
             &self,
-            request: tonic::Request<super::GetRaftMetricsSnapshotRequest>,
+            request: tonic::Request<super::SetGraphTemplateRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::RaftMetricsSnapshotResponse>,
+            tonic::Response<super::SetGraphTemplateResponse>,
             tonic::Status,
         >;
-        async fn get_all_task_assignments(
-            &self,
-            request: tonic::Request<super::GetAllTaskAssignmentRequest>,
-        ) -> std::result::Result<tonic::Response<super::TaskAssignments>, tonic::Status>;
-        async fn get_task(
-            &self,
-            request: tonic::Request<super::GetTaskRequest>,
-        ) -> std::result::Result<tonic::Response<super::GetTaskResponse>, tonic::Status>;
-        async fn get_ingestion_info(
+        async fn list_graph_templates(
             &self,
-            request: tonic::Request<super::GetIngestionInfoRequest>,
+            request: tonic::Request<super::ListGraphTemplatesRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetIngestionInfoResponse>,
+            tonic::Response<super::ListGraphTemplatesResponse>,
             tonic::Status,
         >;
-        async fn wait_content_extraction(
+        async fn get_graph_template(
             &self,
-            request: tonic::Request<super::WaitContentExtractionRequest>,
+            request: tonic::Request<super::GetGraphTemplateRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::WaitContentExtractionResponse>,
+            tonic::Response<super::GetGraphTemplateResponse>,
             tonic::Status,
         >;
-        async fn list_active_contents(
+        async fn delete_graph_template(
             &self,
-            request: tonic::Request<super::ListActiveContentsRequest>,
+            request: tonic::Request<super::DeleteGraphTemplateRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::ListActiveContentsResponse>,
+            tonic::Response<super::DeleteGraphTemplateResponse>,
             tonic::Status,
         >;
-        async fn update_labels(
+        async fn get_content_replica(
             &self,
-            request: tonic::Request<super::UpdateLabelsRequest>,
+            request: tonic::Request<super::GetContentReplicaRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::UpdateLabelsResponse>,
+            tonic::Response<super::GetContentReplicaResponse>,
             tonic::Status,
         >;
     }
@@ -2288,23 +5062,1824 @@ pub mod coordinator_service_server {
             match req.uri().path() {
                 "/indexify_coordinator.CoordinatorService/CreateContent" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateContentSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct CreateContentSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::CreateContentRequest>
+                    for CreateContentSvc<T> {
+                        type Response = super::CreateContentResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateContentRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::create_content(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateContentSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/TombstoneContent" => {
+                    #[allow(non_camel_case_types)]
+                    struct TombstoneContentSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::TombstoneContentRequest>
+                    for TombstoneContentSvc<T> {
+                        type Response = super::TombstoneContentResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::TombstoneContentRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::tombstone_content(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = TombstoneContentSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetContentMetadata" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetContentMetadataSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetContentMetadataRequest>
+                    for GetContentMetadataSvc<T> {
+                        type Response = super::GetContentMetadataResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetContentMetadataRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_content_metadata(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetContentMetadataSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetContentTreeMetadata" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetContentTreeMetadataSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetContentTreeMetadataRequest>
+                    for GetContentTreeMetadataSvc<T> {
+                        type Response = super::GetContentTreeMetadataResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetContentTreeMetadataRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_content_tree_metadata(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetContentTreeMetadataSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListContent" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListContentSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListContentRequest>
+                    for ListContentSvc<T> {
+                        type Response = super::ListContentResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListContentRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_content(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListContentSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/CreateExtractionGraph" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateExtractionGraphSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::CreateExtractionGraphRequest>
+                    for CreateExtractionGraphSvc<T> {
+                        type Response = super::CreateExtractionGraphResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateExtractionGraphRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::create_extraction_graph(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateExtractionGraphSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ValidateExtractionGraph" => {
+                    #[allow(non_camel_case_types)]
+                    struct ValidateExtractionGraphSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::CreateExtractionGraphRequest>
+                    for ValidateExtractionGraphSvc<T> {
+                        type Response = super::ValidateExtractionGraphResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateExtractionGraphRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::validate_extraction_graph(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ValidateExtractionGraphSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetExtractionPolicy" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetExtractionPolicySvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetExtractionPolicyRequest>
+                    for GetExtractionPolicySvc<T> {
+                        type Response = super::GetExtractionPolicyResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetExtractionPolicyRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_extraction_policy(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetExtractionPolicySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListExtractionPolicies" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListExtractionPoliciesSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListExtractionPoliciesRequest>
+                    for ListExtractionPoliciesSvc<T> {
+                        type Response = super::ListExtractionPoliciesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListExtractionPoliciesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_extraction_policies(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListExtractionPoliciesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/CreateNS" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateNSSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::CreateNamespaceRequest>
+                    for CreateNSSvc<T> {
+                        type Response = super::CreateNamespaceResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateNamespaceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::create_ns(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateNSSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListNS" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListNSSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListNamespaceRequest>
+                    for ListNSSvc<T> {
+                        type Response = super::ListNamespaceResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListNamespaceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_ns(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListNSSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetNS" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetNSSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetNamespaceRequest>
+                    for GetNSSvc<T> {
+                        type Response = super::GetNamespaceResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetNamespaceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_ns(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetNSSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListExtractors" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListExtractorsSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListExtractorsRequest>
+                    for ListExtractorsSvc<T> {
+                        type Response = super::ListExtractorsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListExtractorsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_extractors(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListExtractorsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/RegisterExecutor" => {
+                    #[allow(non_camel_case_types)]
+                    struct RegisterExecutorSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::RegisterExecutorRequest>
+                    for RegisterExecutorSvc<T> {
+                        type Response = super::RegisterExecutorResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RegisterExecutorRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::register_executor(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RegisterExecutorSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/RegisterIngestionServer" => {
+                    #[allow(non_camel_case_types)]
+                    struct RegisterIngestionServerSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::RegisterIngestionServerRequest>
+                    for RegisterIngestionServerSvc<T> {
+                        type Response = super::RegisterIngestionServerResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::RegisterIngestionServerRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::register_ingestion_server(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RegisterIngestionServerSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/RemoveIngestionServer" => {
+                    #[allow(non_camel_case_types)]
+                    struct RemoveIngestionServerSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::RemoveIngestionServerRequest>
+                    for RemoveIngestionServerSvc<T> {
+                        type Response = super::RemoveIngestionServerResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RemoveIngestionServerRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::remove_ingestion_server(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RemoveIngestionServerSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/CreateGCTasks" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateGCTasksSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::CreateGcTasksRequest>
+                    for CreateGCTasksSvc<T> {
+                        type Response = super::CreateGcTasksResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateGcTasksRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::create_gc_tasks(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateGCTasksSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GCTasksStream" => {
+                    #[allow(non_camel_case_types)]
+                    struct GCTasksStreamSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::StreamingService<super::GcTaskAcknowledgement>
+                    for GCTasksStreamSvc<T> {
+                        type Response = super::CoordinatorCommand;
+                        type ResponseStream = T::GCTasksStreamStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                tonic::Streaming<super::GcTaskAcknowledgement>,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::gc_tasks_stream(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GCTasksStreamSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/Heartbeat" => {
+                    #[allow(non_camel_case_types)]
+                    struct HeartbeatSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::StreamingService<super::HeartbeatRequest>
+                    for HeartbeatSvc<T> {
+                        type Response = super::HeartbeatResponse;
+                        type ResponseStream = T::HeartbeatStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                tonic::Streaming<super::HeartbeatRequest>,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::heartbeat(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = HeartbeatSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListIndexes" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListIndexesSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListIndexesRequest>
+                    for ListIndexesSvc<T> {
+                        type Response = super::ListIndexesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListIndexesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_indexes(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListIndexesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetIndex" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetIndexSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetIndexRequest>
+                    for GetIndexSvc<T> {
+                        type Response = super::GetIndexResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetIndexRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_index(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetIndexSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/UpdateIndexesState" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateIndexesStateSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::UpdateIndexesStateRequest>
+                    for UpdateIndexesStateSvc<T> {
+                        type Response = super::UpdateIndexesStateResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateIndexesStateRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::update_indexes_state(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateIndexesStateSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetExtractorCoordinates" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetExtractorCoordinatesSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetExtractorCoordinatesRequest>
+                    for GetExtractorCoordinatesSvc<T> {
+                        type Response = super::GetExtractorCoordinatesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::GetExtractorCoordinatesRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_extractor_coordinates(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetExtractorCoordinatesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/UpdateTask" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateTaskSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::UpdateTaskRequest>
+                    for UpdateTaskSvc<T> {
+                        type Response = super::UpdateTaskResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateTaskRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::update_task(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateTaskSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListStateChanges" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListStateChangesSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListStateChangesRequest>
+                    for ListStateChangesSvc<T> {
+                        type Response = super::ListStateChangesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListStateChangesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_state_changes(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListStateChangesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListTasks" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListTasksSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListTasksRequest>
+                    for ListTasksSvc<T> {
+                        type Response = super::ListTasksResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListTasksRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_tasks(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListTasksSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListExecutorTasks" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListExecutorTasksSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListExecutorTasksRequest>
+                    for ListExecutorTasksSvc<T> {
+                        type Response = super::ListTasksResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListExecutorTasksRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_executor_tasks(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListExecutorTasksSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListTaskQueueDepths" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListTaskQueueDepthsSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListTaskQueueDepthsRequest>
+                    for ListTaskQueueDepthsSvc<T> {
+                        type Response = super::ListTaskQueueDepthsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListTaskQueueDepthsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_task_queue_depths(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListTaskQueueDepthsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetSchema" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSchemaSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetSchemaRequest>
+                    for GetSchemaSvc<T> {
+                        type Response = super::GetSchemaResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetSchemaRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_schema(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetSchemaSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListSchemas" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListSchemasSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetAllSchemaRequest>
+                    for ListSchemasSvc<T> {
+                        type Response = super::GetAllSchemaResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetAllSchemaRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_schemas(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListSchemasSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetRaftMetricsSnapshot" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetRaftMetricsSnapshotSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetRaftMetricsSnapshotRequest>
+                    for GetRaftMetricsSnapshotSvc<T> {
+                        type Response = super::RaftMetricsSnapshotResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetRaftMetricsSnapshotRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_raft_metrics_snapshot(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetRaftMetricsSnapshotSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetAllTaskAssignments" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAllTaskAssignmentsSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetAllTaskAssignmentRequest>
+                    for GetAllTaskAssignmentsSvc<T> {
+                        type Response = super::TaskAssignments;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetAllTaskAssignmentRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_all_task_assignments(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetAllTaskAssignmentsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetTask" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTaskSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetTaskRequest>
+                    for GetTaskSvc<T> {
+                        type Response = super::GetTaskResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetTaskRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_task(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetTaskSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetIngestionInfo" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetIngestionInfoSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetIngestionInfoRequest>
+                    for GetIngestionInfoSvc<T> {
+                        type Response = super::GetIngestionInfoResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetIngestionInfoRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_ingestion_info(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetIngestionInfoSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/WaitContentExtraction" => {
+                    #[allow(non_camel_case_types)]
+                    struct WaitContentExtractionSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::WaitContentExtractionRequest>
+                    for WaitContentExtractionSvc<T> {
+                        type Response = super::WaitContentExtractionResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::WaitContentExtractionRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::wait_content_extraction(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = WaitContentExtractionSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListActiveContents" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListActiveContentsSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListActiveContentsRequest>
+                    for ListActiveContentsSvc<T> {
+                        type Response = super::ListActiveContentsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListActiveContentsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_active_contents(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListActiveContentsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/UpdateLabels" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateLabelsSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::UpdateLabelsRequest>
+                    for UpdateLabelsSvc<T> {
+                        type Response = super::UpdateLabelsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateLabelsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::update_labels(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateLabelsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/CancelTasks" => {
+                    #[allow(non_camel_case_types)]
+                    struct CancelTasksSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::CreateContentRequest>
-                    for CreateContentSvc<T> {
-                        type Response = super::CreateContentResponse;
+                    > tonic::server::UnaryService<super::CancelTasksRequest>
+                    for CancelTasksSvc<T> {
+                        type Response = super::CancelTasksResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateContentRequest>,
+                            request: tonic::Request<super::CancelTasksRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::create_content(&inner, request)
+                                <T as CoordinatorService>::cancel_tasks(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -2317,7 +6892,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = CreateContentSvc(inner);
+                        let method = CancelTasksSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2333,25 +6908,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/TombstoneContent" => {
+                "/indexify_coordinator.CoordinatorService/GetCancelTasksJob" => {
                     #[allow(non_camel_case_types)]
-                    struct TombstoneContentSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetCancelTasksJobSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::TombstoneContentRequest>
-                    for TombstoneContentSvc<T> {
-                        type Response = super::TombstoneContentResponse;
+                    > tonic::server::UnaryService<super::GetCancelTasksJobRequest>
+                    for GetCancelTasksJobSvc<T> {
+                        type Response = super::GetCancelTasksJobResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::TombstoneContentRequest>,
+                            request: tonic::Request<super::GetCancelTasksJobRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::tombstone_content(
+                                <T as CoordinatorService>::get_cancel_tasks_job(
                                         &inner,
                                         request,
                                     )
@@ -2367,7 +6942,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = TombstoneContentSvc(inner);
+                        let method = GetCancelTasksJobSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2383,25 +6958,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetContentMetadata" => {
+                "/indexify_coordinator.CoordinatorService/RerunExtractionPolicy" => {
                     #[allow(non_camel_case_types)]
-                    struct GetContentMetadataSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct RerunExtractionPolicySvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetContentMetadataRequest>
-                    for GetContentMetadataSvc<T> {
-                        type Response = super::GetContentMetadataResponse;
+                    > tonic::server::UnaryService<super::RerunExtractionPolicyRequest>
+                    for RerunExtractionPolicySvc<T> {
+                        type Response = super::RerunExtractionPolicyResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetContentMetadataRequest>,
+                            request: tonic::Request<super::RerunExtractionPolicyRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_content_metadata(
+                                <T as CoordinatorService>::rerun_extraction_policy(
                                         &inner,
                                         request,
                                     )
@@ -2417,7 +6992,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetContentMetadataSvc(inner);
+                        let method = RerunExtractionPolicySvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2433,25 +7008,72 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetContentTreeMetadata" => {
+                "/indexify_coordinator.CoordinatorService/RequeueTask" => {
                     #[allow(non_camel_case_types)]
-                    struct GetContentTreeMetadataSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct RequeueTaskSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetContentTreeMetadataRequest>
-                    for GetContentTreeMetadataSvc<T> {
-                        type Response = super::GetContentTreeMetadataResponse;
+                    > tonic::server::UnaryService<super::RequeueTaskRequest>
+                    for RequeueTaskSvc<T> {
+                        type Response = super::RequeueTaskResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetContentTreeMetadataRequest>,
+                            request: tonic::Request<super::RequeueTaskRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_content_tree_metadata(
+                                <T as CoordinatorService>::requeue_task(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RequeueTaskSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/ListStuckContent" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListStuckContentSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::ListStuckContentRequest>
+                    for ListStuckContentSvc<T> {
+                        type Response = super::ListStuckContentResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListStuckContentRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::list_stuck_content(
                                         &inner,
                                         request,
                                     )
@@ -2467,7 +7089,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetContentTreeMetadataSvc(inner);
+                        let method = ListStuckContentSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2483,25 +7105,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListContent" => {
+                "/indexify_coordinator.CoordinatorService/RepairContent" => {
                     #[allow(non_camel_case_types)]
-                    struct ListContentSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct RepairContentSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ListContentRequest>
-                    for ListContentSvc<T> {
-                        type Response = super::ListContentResponse;
+                    > tonic::server::UnaryService<super::RepairContentRequest>
+                    for RepairContentSvc<T> {
+                        type Response = super::RepairContentResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListContentRequest>,
+                            request: tonic::Request<super::RepairContentRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_content(&inner, request)
+                                <T as CoordinatorService>::repair_content(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -2514,7 +7136,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListContentSvc(inner);
+                        let method = RepairContentSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2530,25 +7152,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/CreateExtractionGraph" => {
+                "/indexify_coordinator.CoordinatorService/GetContentContext" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateExtractionGraphSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetContentContextSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::CreateExtractionGraphRequest>
-                    for CreateExtractionGraphSvc<T> {
-                        type Response = super::CreateExtractionGraphResponse;
+                    > tonic::server::UnaryService<super::GetContentContextRequest>
+                    for GetContentContextSvc<T> {
+                        type Response = super::GetContentContextResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateExtractionGraphRequest>,
+                            request: tonic::Request<super::GetContentContextRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::create_extraction_graph(
+                                <T as CoordinatorService>::get_content_context(
                                         &inner,
                                         request,
                                     )
@@ -2564,7 +7186,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = CreateExtractionGraphSvc(inner);
+                        let method = GetContentContextSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2580,25 +7202,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetExtractionPolicy" => {
+                "/indexify_coordinator.CoordinatorService/SetContentContext" => {
                     #[allow(non_camel_case_types)]
-                    struct GetExtractionPolicySvc<T: CoordinatorService>(pub Arc<T>);
+                    struct SetContentContextSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetExtractionPolicyRequest>
-                    for GetExtractionPolicySvc<T> {
-                        type Response = super::GetExtractionPolicyResponse;
+                    > tonic::server::UnaryService<super::SetContentContextRequest>
+                    for SetContentContextSvc<T> {
+                        type Response = super::SetContentContextResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetExtractionPolicyRequest>,
+                            request: tonic::Request<super::SetContentContextRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_extraction_policy(
+                                <T as CoordinatorService>::set_content_context(
                                         &inner,
                                         request,
                                     )
@@ -2614,7 +7236,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetExtractionPolicySvc(inner);
+                        let method = SetContentContextSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2630,25 +7252,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListExtractionPolicies" => {
+                "/indexify_coordinator.CoordinatorService/ListExtractionGraphVersions" => {
                     #[allow(non_camel_case_types)]
-                    struct ListExtractionPoliciesSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct ListExtractionGraphVersionsSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ListExtractionPoliciesRequest>
-                    for ListExtractionPoliciesSvc<T> {
-                        type Response = super::ListExtractionPoliciesResponse;
+                    > tonic::server::UnaryService<super::ListExtractionGraphVersionsRequest>
+                    for ListExtractionGraphVersionsSvc<T> {
+                        type Response = super::ListExtractionGraphVersionsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListExtractionPoliciesRequest>,
+                            request: tonic::Request<super::ListExtractionGraphVersionsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_extraction_policies(
+                                <T as CoordinatorService>::list_extraction_graph_versions(
                                         &inner,
                                         request,
                                     )
@@ -2664,7 +7286,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListExtractionPoliciesSvc(inner);
+                        let method = ListExtractionGraphVersionsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2680,25 +7302,29 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/CreateNS" => {
+                "/indexify_coordinator.CoordinatorService/RollbackExtractionGraph" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateNSSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct RollbackExtractionGraphSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::CreateNamespaceRequest>
-                    for CreateNSSvc<T> {
-                        type Response = super::CreateNamespaceResponse;
+                    > tonic::server::UnaryService<super::RollbackExtractionGraphRequest>
+                    for RollbackExtractionGraphSvc<T> {
+                        type Response = super::CreateExtractionGraphResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateNamespaceRequest>,
+                            request: tonic::Request<super::RollbackExtractionGraphRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::create_ns(&inner, request).await
+                                <T as CoordinatorService>::rollback_extraction_graph(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -2710,7 +7336,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = CreateNSSvc(inner);
+                        let method = RollbackExtractionGraphSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2726,25 +7352,29 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListNS" => {
+                "/indexify_coordinator.CoordinatorService/GetSchedulerSettings" => {
                     #[allow(non_camel_case_types)]
-                    struct ListNSSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetSchedulerSettingsSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ListNamespaceRequest>
-                    for ListNSSvc<T> {
-                        type Response = super::ListNamespaceResponse;
+                    > tonic::server::UnaryService<super::GetSchedulerSettingsRequest>
+                    for GetSchedulerSettingsSvc<T> {
+                        type Response = super::GetSchedulerSettingsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListNamespaceRequest>,
+                            request: tonic::Request<super::GetSchedulerSettingsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_ns(&inner, request).await
+                                <T as CoordinatorService>::get_scheduler_settings(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -2756,7 +7386,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListNSSvc(inner);
+                        let method = GetSchedulerSettingsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2772,25 +7402,29 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetNS" => {
+                "/indexify_coordinator.CoordinatorService/SetSchedulerSettings" => {
                     #[allow(non_camel_case_types)]
-                    struct GetNSSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct SetSchedulerSettingsSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetNamespaceRequest>
-                    for GetNSSvc<T> {
-                        type Response = super::GetNamespaceResponse;
+                    > tonic::server::UnaryService<super::SetSchedulerSettingsRequest>
+                    for SetSchedulerSettingsSvc<T> {
+                        type Response = super::SetSchedulerSettingsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetNamespaceRequest>,
+                            request: tonic::Request<super::SetSchedulerSettingsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_ns(&inner, request).await
+                                <T as CoordinatorService>::set_scheduler_settings(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -2802,7 +7436,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetNSSvc(inner);
+                        let method = SetSchedulerSettingsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2818,25 +7452,128 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListExtractors" => {
+                "/indexify_coordinator.CoordinatorService/SimulateScheduling" => {
                     #[allow(non_camel_case_types)]
-                    struct ListExtractorsSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct SimulateSchedulingSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ListExtractorsRequest>
-                    for ListExtractorsSvc<T> {
-                        type Response = super::ListExtractorsResponse;
+                    > tonic::server::UnaryService<super::SimulateSchedulingRequest>
+                    for SimulateSchedulingSvc<T> {
+                        type Response = super::SimulateSchedulingResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SimulateSchedulingRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::simulate_scheduling(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SimulateSchedulingSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/GetMaintenanceMode" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetMaintenanceModeSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::GetMaintenanceModeRequest>
+                    for GetMaintenanceModeSvc<T> {
+                        type Response = super::GetMaintenanceModeResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetMaintenanceModeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CoordinatorService>::get_maintenance_mode(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetMaintenanceModeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/indexify_coordinator.CoordinatorService/SetMaintenanceMode" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetMaintenanceModeSvc<T: CoordinatorService>(pub Arc<T>);
+                    impl<
+                        T: CoordinatorService,
+                    > tonic::server::UnaryService<super::SetMaintenanceModeRequest>
+                    for SetMaintenanceModeSvc<T> {
+                        type Response = super::SetMaintenanceModeResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListExtractorsRequest>,
+                            request: tonic::Request<super::SetMaintenanceModeRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_extractors(&inner, request)
+                                <T as CoordinatorService>::set_maintenance_mode(
+                                        &inner,
+                                        request,
+                                    )
                                     .await
                             };
                             Box::pin(fut)
@@ -2849,7 +7586,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListExtractorsSvc(inner);
+                        let method = SetMaintenanceModeSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2865,25 +7602,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/RegisterExecutor" => {
+                "/indexify_coordinator.CoordinatorService/RerunFailedTasks" => {
                     #[allow(non_camel_case_types)]
-                    struct RegisterExecutorSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct RerunFailedTasksSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::RegisterExecutorRequest>
-                    for RegisterExecutorSvc<T> {
-                        type Response = super::RegisterExecutorResponse;
+                    > tonic::server::UnaryService<super::RerunFailedTasksRequest>
+                    for RerunFailedTasksSvc<T> {
+                        type Response = super::RerunFailedTasksResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::RegisterExecutorRequest>,
+                            request: tonic::Request<super::RerunFailedTasksRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::register_executor(
+                                <T as CoordinatorService>::rerun_failed_tasks(
                                         &inner,
                                         request,
                                     )
@@ -2899,7 +7636,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = RegisterExecutorSvc(inner);
+                        let method = RerunFailedTasksSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2915,30 +7652,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/RegisterIngestionServer" => {
+                "/indexify_coordinator.CoordinatorService/RecordEgress" => {
                     #[allow(non_camel_case_types)]
-                    struct RegisterIngestionServerSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct RecordEgressSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::RegisterIngestionServerRequest>
-                    for RegisterIngestionServerSvc<T> {
-                        type Response = super::RegisterIngestionServerResponse;
+                    > tonic::server::UnaryService<super::RecordEgressRequest>
+                    for RecordEgressSvc<T> {
+                        type Response = super::RecordEgressResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<
-                                super::RegisterIngestionServerRequest,
-                            >,
+                            request: tonic::Request<super::RecordEgressRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::register_ingestion_server(
-                                        &inner,
-                                        request,
-                                    )
+                                <T as CoordinatorService>::record_egress(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -2951,7 +7683,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = RegisterIngestionServerSvc(inner);
+                        let method = RecordEgressSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2967,25 +7699,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/RemoveIngestionServer" => {
+                "/indexify_coordinator.CoordinatorService/ListMeteringRecords" => {
                     #[allow(non_camel_case_types)]
-                    struct RemoveIngestionServerSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct ListMeteringRecordsSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::RemoveIngestionServerRequest>
-                    for RemoveIngestionServerSvc<T> {
-                        type Response = super::RemoveIngestionServerResponse;
+                    > tonic::server::UnaryService<super::ListMeteringRecordsRequest>
+                    for ListMeteringRecordsSvc<T> {
+                        type Response = super::ListMeteringRecordsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::RemoveIngestionServerRequest>,
+                            request: tonic::Request<super::ListMeteringRecordsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::remove_ingestion_server(
+                                <T as CoordinatorService>::list_metering_records(
                                         &inner,
                                         request,
                                     )
@@ -3001,7 +7733,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = RemoveIngestionServerSvc(inner);
+                        let method = ListMeteringRecordsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3017,25 +7749,28 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/CreateGCTasks" => {
+                "/indexify_coordinator.CoordinatorService/GetExtractionGraphUsage" => {
                     #[allow(non_camel_case_types)]
-                    struct CreateGCTasksSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetExtractionGraphUsageSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::CreateGcTasksRequest>
-                    for CreateGCTasksSvc<T> {
-                        type Response = super::CreateGcTasksResponse;
+                    > tonic::server::UnaryService<super::GetExtractionGraphUsageRequest>
+                    for GetExtractionGraphUsageSvc<T> {
+                        type Response = super::GetExtractionGraphUsageResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::CreateGcTasksRequest>,
+                            request: tonic::Request<super::GetExtractionGraphUsageRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::create_gc_tasks(&inner, request)
+                                <T as CoordinatorService>::get_extraction_graph_usage(
+                                        &inner,
+                                        request,
+                                    )
                                     .await
                             };
                             Box::pin(fut)
@@ -3048,7 +7783,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = CreateGCTasksSvc(inner);
+                        let method = GetExtractionGraphUsageSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3064,28 +7799,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GCTasksStream" => {
+                "/indexify_coordinator.CoordinatorService/ListExecutors" => {
                     #[allow(non_camel_case_types)]
-                    struct GCTasksStreamSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct ListExecutorsSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::StreamingService<super::GcTaskAcknowledgement>
-                    for GCTasksStreamSvc<T> {
-                        type Response = super::CoordinatorCommand;
-                        type ResponseStream = T::GCTasksStreamStream;
+                    > tonic::server::UnaryService<super::ListExecutorsRequest>
+                    for ListExecutorsSvc<T> {
+                        type Response = super::ListExecutorsResponse;
                         type Future = BoxFuture<
-                            tonic::Response<Self::ResponseStream>,
+                            tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<
-                                tonic::Streaming<super::GcTaskAcknowledgement>,
-                            >,
+                            request: tonic::Request<super::ListExecutorsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::gc_tasks_stream(&inner, request)
+                                <T as CoordinatorService>::list_executors(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -3098,7 +7830,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GCTasksStreamSvc(inner);
+                        let method = ListExecutorsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3109,33 +7841,34 @@ pub mod coordinator_service_server {
                                 max_decoding_message_size,
                                 max_encoding_message_size,
                             );
-                        let res = grpc.streaming(method, req).await;
+                        let res = grpc.unary(method, req).await;
                         Ok(res)
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/Heartbeat" => {
+                "/indexify_coordinator.CoordinatorService/ListStarvedTasks" => {
                     #[allow(non_camel_case_types)]
-                    struct HeartbeatSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct ListStarvedTasksSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::StreamingService<super::HeartbeatRequest>
-                    for HeartbeatSvc<T> {
-                        type Response = super::HeartbeatResponse;
-                        type ResponseStream = T::HeartbeatStream;
+                    > tonic::server::UnaryService<super::ListStarvedTasksRequest>
+                    for ListStarvedTasksSvc<T> {
+                        type Response = super::ListStarvedTasksResponse;
                         type Future = BoxFuture<
-                            tonic::Response<Self::ResponseStream>,
+                            tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<
-                                tonic::Streaming<super::HeartbeatRequest>,
-                            >,
+                            request: tonic::Request<super::ListStarvedTasksRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::heartbeat(&inner, request).await
+                                <T as CoordinatorService>::list_starved_tasks(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -3147,7 +7880,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = HeartbeatSvc(inner);
+                        let method = ListStarvedTasksSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3158,30 +7891,33 @@ pub mod coordinator_service_server {
                                 max_decoding_message_size,
                                 max_encoding_message_size,
                             );
-                        let res = grpc.streaming(method, req).await;
+                        let res = grpc.unary(method, req).await;
                         Ok(res)
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListIndexes" => {
+                "/indexify_coordinator.CoordinatorService/ExportNamespace" => {
                     #[allow(non_camel_case_types)]
-                    struct ListIndexesSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct ExportNamespaceSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ListIndexesRequest>
-                    for ListIndexesSvc<T> {
-                        type Response = super::ListIndexesResponse;
+                    > tonic::server::UnaryService<super::ExportNamespaceRequest>
+                    for ExportNamespaceSvc<T> {
+                        type Response = super::ExportNamespaceResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListIndexesRequest>,
+                            request: tonic::Request<super::ExportNamespaceRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_indexes(&inner, request)
+                                <T as CoordinatorService>::export_namespace(
+                                        &inner,
+                                        request,
+                                    )
                                     .await
                             };
                             Box::pin(fut)
@@ -3194,7 +7930,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListIndexesSvc(inner);
+                        let method = ExportNamespaceSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3210,25 +7946,29 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetIndex" => {
+                "/indexify_coordinator.CoordinatorService/ImportNamespace" => {
                     #[allow(non_camel_case_types)]
-                    struct GetIndexSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct ImportNamespaceSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetIndexRequest>
-                    for GetIndexSvc<T> {
-                        type Response = super::GetIndexResponse;
+                    > tonic::server::UnaryService<super::ImportNamespaceRequest>
+                    for ImportNamespaceSvc<T> {
+                        type Response = super::ImportNamespaceResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetIndexRequest>,
+                            request: tonic::Request<super::ImportNamespaceRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_index(&inner, request).await
+                                <T as CoordinatorService>::import_namespace(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -3240,7 +7980,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetIndexSvc(inner);
+                        let method = ImportNamespaceSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3256,28 +7996,26 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/UpdateIndexesState" => {
+                "/indexify_coordinator.CoordinatorService/StreamContent" => {
                     #[allow(non_camel_case_types)]
-                    struct UpdateIndexesStateSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct StreamContentSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::UpdateIndexesStateRequest>
-                    for UpdateIndexesStateSvc<T> {
-                        type Response = super::UpdateIndexesStateResponse;
+                    > tonic::server::ServerStreamingService<super::ListContentRequest>
+                    for StreamContentSvc<T> {
+                        type Response = super::ContentMetadata;
+                        type ResponseStream = T::StreamContentStream;
                         type Future = BoxFuture<
-                            tonic::Response<Self::Response>,
+                            tonic::Response<Self::ResponseStream>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UpdateIndexesStateRequest>,
+                            request: tonic::Request<super::ListContentRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::update_indexes_state(
-                                        &inner,
-                                        request,
-                                    )
+                                <T as CoordinatorService>::stream_content(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -3290,7 +8028,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = UpdateIndexesStateSvc(inner);
+                        let method = StreamContentSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3301,35 +8039,31 @@ pub mod coordinator_service_server {
                                 max_decoding_message_size,
                                 max_encoding_message_size,
                             );
-                        let res = grpc.unary(method, req).await;
+                        let res = grpc.server_streaming(method, req).await;
                         Ok(res)
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetExtractorCoordinates" => {
+                "/indexify_coordinator.CoordinatorService/StreamTasks" => {
                     #[allow(non_camel_case_types)]
-                    struct GetExtractorCoordinatesSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct StreamTasksSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetExtractorCoordinatesRequest>
-                    for GetExtractorCoordinatesSvc<T> {
-                        type Response = super::GetExtractorCoordinatesResponse;
+                    > tonic::server::ServerStreamingService<super::ListTasksRequest>
+                    for StreamTasksSvc<T> {
+                        type Response = super::Task;
+                        type ResponseStream = T::StreamTasksStream;
                         type Future = BoxFuture<
-                            tonic::Response<Self::Response>,
+                            tonic::Response<Self::ResponseStream>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<
-                                super::GetExtractorCoordinatesRequest,
-                            >,
+                            request: tonic::Request<super::ListTasksRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_extractor_coordinates(
-                                        &inner,
-                                        request,
-                                    )
+                                <T as CoordinatorService>::stream_tasks(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -3342,7 +8076,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetExtractorCoordinatesSvc(inner);
+                        let method = StreamTasksSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3353,30 +8087,33 @@ pub mod coordinator_service_server {
                                 max_decoding_message_size,
                                 max_encoding_message_size,
                             );
-                        let res = grpc.unary(method, req).await;
+                        let res = grpc.server_streaming(method, req).await;
                         Ok(res)
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/UpdateTask" => {
+                "/indexify_coordinator.CoordinatorService/DeregisterExecutor" => {
                     #[allow(non_camel_case_types)]
-                    struct UpdateTaskSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct DeregisterExecutorSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::UpdateTaskRequest>
-                    for UpdateTaskSvc<T> {
-                        type Response = super::UpdateTaskResponse;
+                    > tonic::server::UnaryService<super::DeregisterExecutorRequest>
+                    for DeregisterExecutorSvc<T> {
+                        type Response = super::DeregisterExecutorResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UpdateTaskRequest>,
+                            request: tonic::Request<super::DeregisterExecutorRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::update_task(&inner, request)
+                                <T as CoordinatorService>::deregister_executor(
+                                        &inner,
+                                        request,
+                                    )
                                     .await
                             };
                             Box::pin(fut)
@@ -3389,7 +8126,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = UpdateTaskSvc(inner);
+                        let method = DeregisterExecutorSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3405,28 +8142,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListStateChanges" => {
+                "/indexify_coordinator.CoordinatorService/UpdateTasks" => {
                     #[allow(non_camel_case_types)]
-                    struct ListStateChangesSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct UpdateTasksSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ListStateChangesRequest>
-                    for ListStateChangesSvc<T> {
-                        type Response = super::ListStateChangesResponse;
+                    > tonic::server::UnaryService<super::UpdateTasksRequest>
+                    for UpdateTasksSvc<T> {
+                        type Response = super::UpdateTasksResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListStateChangesRequest>,
+                            request: tonic::Request<super::UpdateTasksRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_state_changes(
-                                        &inner,
-                                        request,
-                                    )
+                                <T as CoordinatorService>::update_tasks(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -3439,7 +8173,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListStateChangesSvc(inner);
+                        let method = UpdateTasksSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3455,25 +8189,29 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListTasks" => {
+                "/indexify_coordinator.CoordinatorService/GetSloCompliance" => {
                     #[allow(non_camel_case_types)]
-                    struct ListTasksSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetSloComplianceSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ListTasksRequest>
-                    for ListTasksSvc<T> {
-                        type Response = super::ListTasksResponse;
+                    > tonic::server::UnaryService<super::GetSloComplianceRequest>
+                    for GetSloComplianceSvc<T> {
+                        type Response = super::GetSloComplianceResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListTasksRequest>,
+                            request: tonic::Request<super::GetSloComplianceRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_tasks(&inner, request).await
+                                <T as CoordinatorService>::get_slo_compliance(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -3485,7 +8223,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListTasksSvc(inner);
+                        let method = GetSloComplianceSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3501,25 +8239,29 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetSchema" => {
+                "/indexify_coordinator.CoordinatorService/GetTaskScheduling" => {
                     #[allow(non_camel_case_types)]
-                    struct GetSchemaSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetTaskSchedulingSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetSchemaRequest>
-                    for GetSchemaSvc<T> {
-                        type Response = super::GetSchemaResponse;
+                    > tonic::server::UnaryService<super::GetTaskSchedulingRequest>
+                    for GetTaskSchedulingSvc<T> {
+                        type Response = super::GetTaskSchedulingResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetSchemaRequest>,
+                            request: tonic::Request<super::GetTaskSchedulingRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_schema(&inner, request).await
+                                <T as CoordinatorService>::get_task_scheduling(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -3531,7 +8273,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetSchemaSvc(inner);
+                        let method = GetTaskSchedulingSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3547,25 +8289,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListSchemas" => {
+                "/indexify_coordinator.CoordinatorService/ListReplays" => {
                     #[allow(non_camel_case_types)]
-                    struct ListSchemasSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct ListReplaysSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetAllSchemaRequest>
-                    for ListSchemasSvc<T> {
-                        type Response = super::GetAllSchemaResponse;
+                    > tonic::server::UnaryService<super::ListReplaysRequest>
+                    for ListReplaysSvc<T> {
+                        type Response = super::ListReplaysResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetAllSchemaRequest>,
+                            request: tonic::Request<super::ListReplaysRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_schemas(&inner, request)
+                                <T as CoordinatorService>::list_replays(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -3578,7 +8320,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListSchemasSvc(inner);
+                        let method = ListReplaysSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3594,28 +8336,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetRaftMetricsSnapshot" => {
+                "/indexify_coordinator.CoordinatorService/GetReplay" => {
                     #[allow(non_camel_case_types)]
-                    struct GetRaftMetricsSnapshotSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetReplaySvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetRaftMetricsSnapshotRequest>
-                    for GetRaftMetricsSnapshotSvc<T> {
-                        type Response = super::RaftMetricsSnapshotResponse;
+                    > tonic::server::UnaryService<super::GetReplayRequest>
+                    for GetReplaySvc<T> {
+                        type Response = super::GetReplayResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetRaftMetricsSnapshotRequest>,
+                            request: tonic::Request<super::GetReplayRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_raft_metrics_snapshot(
-                                        &inner,
-                                        request,
-                                    )
+                                <T as CoordinatorService>::get_replay(&inner, request)
                                     .await
                             };
                             Box::pin(fut)
@@ -3628,7 +8367,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetRaftMetricsSnapshotSvc(inner);
+                        let method = GetReplaySvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3644,25 +8383,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetAllTaskAssignments" => {
+                "/indexify_coordinator.CoordinatorService/GetInvocationBreakdown" => {
                     #[allow(non_camel_case_types)]
-                    struct GetAllTaskAssignmentsSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetInvocationBreakdownSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetAllTaskAssignmentRequest>
-                    for GetAllTaskAssignmentsSvc<T> {
-                        type Response = super::TaskAssignments;
+                    > tonic::server::UnaryService<super::GetInvocationBreakdownRequest>
+                    for GetInvocationBreakdownSvc<T> {
+                        type Response = super::GetInvocationBreakdownResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetAllTaskAssignmentRequest>,
+                            request: tonic::Request<super::GetInvocationBreakdownRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_all_task_assignments(
+                                <T as CoordinatorService>::get_invocation_breakdown(
                                         &inner,
                                         request,
                                     )
@@ -3678,7 +8417,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetAllTaskAssignmentsSvc(inner);
+                        let method = GetInvocationBreakdownSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3694,25 +8433,29 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetTask" => {
+                "/indexify_coordinator.CoordinatorService/SetGraphTemplate" => {
                     #[allow(non_camel_case_types)]
-                    struct GetTaskSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct SetGraphTemplateSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetTaskRequest>
-                    for GetTaskSvc<T> {
-                        type Response = super::GetTaskResponse;
+                    > tonic::server::UnaryService<super::SetGraphTemplateRequest>
+                    for SetGraphTemplateSvc<T> {
+                        type Response = super::SetGraphTemplateResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetTaskRequest>,
+                            request: tonic::Request<super::SetGraphTemplateRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_task(&inner, request).await
+                                <T as CoordinatorService>::set_graph_template(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -3724,7 +8467,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetTaskSvc(inner);
+                        let method = SetGraphTemplateSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3740,25 +8483,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/GetIngestionInfo" => {
+                "/indexify_coordinator.CoordinatorService/ListGraphTemplates" => {
                     #[allow(non_camel_case_types)]
-                    struct GetIngestionInfoSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct ListGraphTemplatesSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::GetIngestionInfoRequest>
-                    for GetIngestionInfoSvc<T> {
-                        type Response = super::GetIngestionInfoResponse;
+                    > tonic::server::UnaryService<super::ListGraphTemplatesRequest>
+                    for ListGraphTemplatesSvc<T> {
+                        type Response = super::ListGraphTemplatesResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetIngestionInfoRequest>,
+                            request: tonic::Request<super::ListGraphTemplatesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::get_ingestion_info(
+                                <T as CoordinatorService>::list_graph_templates(
                                         &inner,
                                         request,
                                     )
@@ -3774,7 +8517,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetIngestionInfoSvc(inner);
+                        let method = ListGraphTemplatesSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3790,25 +8533,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/WaitContentExtraction" => {
+                "/indexify_coordinator.CoordinatorService/GetGraphTemplate" => {
                     #[allow(non_camel_case_types)]
-                    struct WaitContentExtractionSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetGraphTemplateSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::WaitContentExtractionRequest>
-                    for WaitContentExtractionSvc<T> {
-                        type Response = super::WaitContentExtractionResponse;
+                    > tonic::server::UnaryService<super::GetGraphTemplateRequest>
+                    for GetGraphTemplateSvc<T> {
+                        type Response = super::GetGraphTemplateResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::WaitContentExtractionRequest>,
+                            request: tonic::Request<super::GetGraphTemplateRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::wait_content_extraction(
+                                <T as CoordinatorService>::get_graph_template(
                                         &inner,
                                         request,
                                     )
@@ -3824,7 +8567,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = WaitContentExtractionSvc(inner);
+                        let method = GetGraphTemplateSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3840,25 +8583,25 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/ListActiveContents" => {
+                "/indexify_coordinator.CoordinatorService/DeleteGraphTemplate" => {
                     #[allow(non_camel_case_types)]
-                    struct ListActiveContentsSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct DeleteGraphTemplateSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::ListActiveContentsRequest>
-                    for ListActiveContentsSvc<T> {
-                        type Response = super::ListActiveContentsResponse;
+                    > tonic::server::UnaryService<super::DeleteGraphTemplateRequest>
+                    for DeleteGraphTemplateSvc<T> {
+                        type Response = super::DeleteGraphTemplateResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListActiveContentsRequest>,
+                            request: tonic::Request<super::DeleteGraphTemplateRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::list_active_contents(
+                                <T as CoordinatorService>::delete_graph_template(
                                         &inner,
                                         request,
                                     )
@@ -3874,7 +8617,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ListActiveContentsSvc(inner);
+                        let method = DeleteGraphTemplateSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3890,25 +8633,28 @@ pub mod coordinator_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/indexify_coordinator.CoordinatorService/UpdateLabels" => {
+                "/indexify_coordinator.CoordinatorService/GetContentReplica" => {
                     #[allow(non_camel_case_types)]
-                    struct UpdateLabelsSvc<T: CoordinatorService>(pub Arc<T>);
+                    struct GetContentReplicaSvc<T: CoordinatorService>(pub Arc<T>);
                     impl<
                         T: CoordinatorService,
-                    > tonic::server::UnaryService<super::UpdateLabelsRequest>
-                    for UpdateLabelsSvc<T> {
-                        type Response = super::UpdateLabelsResponse;
+                    > tonic::server::UnaryService<super::GetContentReplicaRequest>
+                    for GetContentReplicaSvc<T> {
+                        type Response = super::GetContentReplicaResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UpdateLabelsRequest>,
+                            request: tonic::Request<super::GetContentReplicaRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as CoordinatorService>::update_labels(&inner, request)
+                                <T as CoordinatorService>::get_content_replica(
+                                        &inner,
+                                        request,
+                                    )
                                     .await
                             };
                             Box::pin(fut)
@@ -3921,7 +8667,7 @@ pub mod coordinator_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = UpdateLabelsSvc(inner);
+                        let method = GetContentReplicaSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -0,0 +1,189 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use ipnet::IpNet;
+
+use crate::{api::IndexifyAPIError, server_config::AdminApiConfig};
+
+/// The listener a request came in on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Listener {
+    Public,
+    Admin,
+}
+
+/// Control operations, like re-running or deleting content, managing
+/// executors and tasks and tuning or pausing the scheduler, as opposed to the
+/// data plane of ingesting, reading and searching content
+fn is_admin_route(method: &Method, path: &str) -> bool {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match segments.as_slice() {
+        ["executors", ..] | ["task_assignments"] | ["state_changes"] | ["metrics", "raft"] => true,
+        ["scheduler", ..] | ["maintenance"] | ["metering"] => true,
+        ["debug", "pprof", ..] => true,
+        ["graph_templates", ..] => *method != Method::GET,
+        ["namespaces", _, "content", _, "rerun" | "repair" | "rerun_failed"] => true,
+        ["namespaces", _, "tasks", _, "requeue"] => true,
+        ["namespaces", _, "cancel_tasks", ..] => true,
+        ["namespaces", _, "export" | "import"] => true,
+        ["namespaces", ..] => *method == Method::DELETE,
+        _ => false,
+    }
+}
+
+/// Where admin routes can be called from
+pub struct AdminRoutePolicy {
+    listener: Listener,
+    /// Admin routes are only served on the admin listener when there is one
+    admin_listener: bool,
+    allowed_networks: Vec<IpNet>,
+}
+
+impl AdminRoutePolicy {
+    pub fn new(config: &AdminApiConfig, listener: Listener) -> Result<Self> {
+        let allowed_networks = config
+            .allowed_cidrs
+            .iter()
+            .map(|cidr| {
+                cidr.parse::<IpNet>()
+                    .or_else(|_| cidr.parse::<std::net::IpAddr>().map(IpNet::from))
+                    .map_err(|_| anyhow!("invalid admin api cidr {}", cidr))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            listener,
+            admin_listener: config.listen_port.is_some(),
+            allowed_networks,
+        })
+    }
+
+    fn check(&self, method: &Method, path: &str, remote: &SocketAddr) -> Result<(), StatusCode> {
+        if !is_admin_route(method, path) {
+            return Ok(());
+        }
+        //  The public listener acts as if admin routes don't exist
+        if self.admin_listener && self.listener == Listener::Public {
+            return Err(StatusCode::NOT_FOUND);
+        }
+        let remote = remote.ip().to_canonical();
+        if !self.allowed_networks.is_empty() &&
+            !self
+                .allowed_networks
+                .iter()
+                .any(|network| network.contains(&remote))
+        {
+            return Err(StatusCode::FORBIDDEN);
+        }
+        Ok(())
+    }
+}
+
+pub async fn restrict_admin_routes(
+    State(policy): State<Arc<AdminRoutePolicy>>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Result<Response, IndexifyAPIError> {
+    match policy.check(request.method(), request.uri().path(), &remote) {
+        Ok(()) => Ok(next.run(request).await),
+        Err(StatusCode::NOT_FOUND) => Err(IndexifyAPIError::not_found("not found")),
+        Err(status_code) => Err(IndexifyAPIError::new(
+            status_code,
+            &format!(
+                "{} {} is not allowed from {}",
+                request.method(),
+                request.uri().path(),
+                remote.ip()
+            ),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admin_routes() {
+        assert!(is_admin_route(&Method::GET, "/executors"));
+        assert!(is_admin_route(&Method::GET, "/executors/e1/tasks"));
+        assert!(is_admin_route(
+            &Method::POST,
+            "/namespaces/default/content/c1/rerun"
+        ));
+        assert!(is_admin_route(
+            &Method::DELETE,
+            "/namespaces/default/content/c1"
+        ));
+        assert!(is_admin_route(
+            &Method::POST,
+            "/namespaces/default/cancel_tasks"
+        ));
+        assert!(is_admin_route(&Method::POST, "/namespaces/default/import"));
+        assert!(is_admin_route(&Method::PUT, "/scheduler/settings"));
+        assert!(is_admin_route(&Method::PUT, "/maintenance"));
+        assert!(is_admin_route(&Method::GET, "/metering"));
+        assert!(is_admin_route(&Method::GET, "/debug/pprof/heap"));
+        assert!(is_admin_route(&Method::POST, "/graph_templates"));
+        assert!(!is_admin_route(&Method::GET, "/graph_templates/rag"));
+        assert!(!is_admin_route(
+            &Method::POST,
+            "/namespaces/default/graph_templates/rag/instantiate"
+        ));
+        assert!(!is_admin_route(
+            &Method::GET,
+            "/namespaces/default/content/c1"
+        ));
+        assert!(!is_admin_route(
+            &Method::POST,
+            "/namespaces/default/upload_file"
+        ));
+        assert!(!is_admin_route(&Method::GET, "/"));
+    }
+
+    #[test]
+    fn test_admin_route_policy() {
+        let remote: SocketAddr = "10.0.0.7:41000".parse().unwrap();
+        let outside: SocketAddr = "203.0.113.9:41000".parse().unwrap();
+        let mapped: SocketAddr = "[::ffff:10.0.0.8]:41000".parse().unwrap();
+        let config = AdminApiConfig {
+            allowed_cidrs: vec!["10.0.0.0/8".to_string(), "192.168.1.4".to_string()],
+            listen_port: None,
+        };
+        let policy = AdminRoutePolicy::new(&config, Listener::Public).unwrap();
+        assert_eq!(policy.check(&Method::GET, "/executors", &remote), Ok(()));
+        assert_eq!(policy.check(&Method::GET, "/executors", &mapped), Ok(()));
+        assert_eq!(
+            policy.check(&Method::GET, "/executors", &outside),
+            Err(StatusCode::FORBIDDEN)
+        );
+        assert_eq!(
+            policy.check(&Method::GET, "/namespaces/default/content", &outside),
+            Ok(())
+        );
+
+        let config = AdminApiConfig {
+            allowed_cidrs: vec![],
+            listen_port: Some(8901),
+        };
+        let public = AdminRoutePolicy::new(&config, Listener::Public).unwrap();
+        let admin = AdminRoutePolicy::new(&config, Listener::Admin).unwrap();
+        assert_eq!(
+            public.check(&Method::GET, "/executors", &remote),
+            Err(StatusCode::NOT_FOUND)
+        );
+        assert_eq!(admin.check(&Method::GET, "/executors", &outside), Ok(()));
+
+        let config = AdminApiConfig {
+            allowed_cidrs: vec!["not a cidr".to_string()],
+            listen_port: None,
+        };
+        assert!(AdminRoutePolicy::new(&config, Listener::Public).is_err());
+    }
+}
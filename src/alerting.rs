@@ -0,0 +1,456 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+use crate::{
+    coordinator::Coordinator,
+    server_config::{
+        AlertFormat,
+        AlertMetric,
+        AlertRule,
+        AlertSeverity,
+        AlertWebhook,
+        AlertingConfig,
+    },
+    utils::timestamp_secs,
+    webhooks,
+};
+
+const PAGERDUTY_EVENTS_SOURCE: &str = "indexify-coordinator";
+
+const WEBHOOK_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Attempts to deliver an alert to a webhook, with a doubling delay between
+/// them
+const DELIVERY_ATTEMPTS: u32 = 3;
+const DELIVERY_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Values of the metrics alert rules watch, at a check
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AlertObservation {
+    pub unallocated_tasks: u64,
+    pub executors: u64,
+    pub quarantined_tasks: u64,
+}
+
+impl AlertObservation {
+    fn value(&self, metric: AlertMetric) -> f64 {
+        match metric {
+            AlertMetric::UnallocatedTasks => self.unallocated_tasks as f64,
+            AlertMetric::Executors => self.executors as f64,
+            AlertMetric::QuarantinedTasks => self.quarantined_tasks as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertStatus {
+    Firing,
+    Resolved,
+}
+
+/// A rule that started or stopped firing
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Alert {
+    pub rule: String,
+    pub status: AlertStatus,
+    pub severity: AlertSeverity,
+    pub metric: AlertMetric,
+    pub value: f64,
+    pub threshold: f64,
+    /// When the threshold was first crossed, in seconds since the epoch
+    pub since: u64,
+    pub at: u64,
+    pub summary: String,
+}
+
+#[derive(Debug, Default)]
+struct RuleState {
+    /// When the threshold was first crossed, unset while it isn't
+    breached_since: Option<u64>,
+    firing: bool,
+}
+
+/// Checks the alert rules on the leader and sends an alert to every webhook
+/// when a rule starts or stops firing. Rule state is kept in memory, so a new
+/// leader fires again the rules that are still breached once their duration
+/// passed.
+pub struct Alerter {
+    rules: Vec<AlertRule>,
+    /// Replaced when the secrets they reference are rotated
+    webhooks: Arc<RwLock<Vec<AlertWebhook>>>,
+    states: HashMap<String, RuleState>,
+    /// Observations as old as the longest growth window of the rules
+    history: VecDeque<(u64, AlertObservation)>,
+    /// Alerts waiting to be delivered by the task started at the first check
+    deliveries: Option<mpsc::UnboundedSender<Alert>>,
+}
+
+impl Alerter {
+    pub fn new(config: &AlertingConfig) -> Self {
+        Self {
+            rules: config.rules.clone(),
+            webhooks: Arc::new(RwLock::new(config.webhooks.clone())),
+            states: HashMap::new(),
+            history: VecDeque::new(),
+            deliveries: None,
+        }
+    }
+
+    /// Delivers the alerts with the webhooks of the config resolved with the
+    /// refreshed secrets from then on
+    pub fn apply_refreshed_secrets(&self) {
+        let webhooks = self.webhooks.clone();
+        crate::secrets::on_refresh(move |config| {
+            *webhooks.write().unwrap() = config.alerting.webhooks.clone();
+        });
+    }
+
+    /// Value a rule compares to its threshold, the growth of the metric over
+    /// the window of the rule when it has one
+    fn rule_value(&self, rule: &AlertRule, observation: &AlertObservation, now: u64) -> f64 {
+        let current = observation.value(rule.metric);
+        let Some(window) = rule.growth_over_secs else {
+            return current;
+        };
+        //  The latest observation at least a window old, or the oldest one
+        //  while the coordinator hasn't been leader for a window
+        let start = now.saturating_sub(window);
+        let base = self
+            .history
+            .iter()
+            .rev()
+            .find(|(at, _)| *at <= start)
+            .or(self.history.front())
+            .map_or(current, |(_, past)| past.value(rule.metric));
+        (current - base).max(0.0)
+    }
+
+    /// Checks the rules against an observation, and returns the alerts of the
+    /// rules that started or stopped firing
+    fn evaluate(&mut self, observation: AlertObservation, now: u64) -> Vec<Alert> {
+        let max_window = self
+            .rules
+            .iter()
+            .filter_map(|rule| rule.growth_over_secs)
+            .max()
+            .unwrap_or(0);
+        self.history.push_back((now, observation));
+        while self.history.len() > 1 &&
+            self.history
+                .get(1)
+                .map_or(false, |(at, _)| *at <= now.saturating_sub(max_window))
+        {
+            self.history.pop_front();
+        }
+
+        let mut alerts = Vec::new();
+        for rule in &self.rules {
+            let value = self.rule_value(rule, &observation, now);
+            let (breached, threshold) = match (rule.above, rule.below) {
+                (Some(above), _) => (value > above, above),
+                (None, Some(below)) => (value < below, below),
+                (None, None) => continue,
+            };
+            let state = self.states.entry(rule.name.clone()).or_default();
+            let alert = |status, since, summary| Alert {
+                rule: rule.name.clone(),
+                status,
+                severity: rule.severity,
+                metric: rule.metric,
+                value,
+                threshold,
+                since,
+                at: now,
+                summary,
+            };
+            if breached {
+                let since = *state.breached_since.get_or_insert(now);
+                if !state.firing && now.saturating_sub(since) >= rule.for_secs {
+                    state.firing = true;
+                    alerts.push(alert(
+                        AlertStatus::Firing,
+                        since,
+                        format!(
+                            "{}: {} is {} {}, {} since {}",
+                            rule.name,
+                            rule.metric,
+                            if rule.above.is_some() {
+                                "above"
+                            } else {
+                                "below"
+                            },
+                            threshold,
+                            value,
+                            since
+                        ),
+                    ));
+                }
+            } else if let Some(since) = state.breached_since.take() {
+                if state.firing {
+                    state.firing = false;
+                    alerts.push(alert(
+                        AlertStatus::Resolved,
+                        since,
+                        format!("{}: {} is back at {}", rule.name, rule.metric, value),
+                    ));
+                }
+            }
+        }
+        alerts
+    }
+
+    /// Checks the rules and queues the alerts of the rules that started or
+    /// stopped firing. Alerts are delivered in order by a separate task, so a
+    /// webhook that is slow to answer doesn't hold up the caller.
+    pub async fn check(&mut self, coordinator: &Coordinator) -> Result<()> {
+        let observation = coordinator.alert_observation().await?;
+        for alert in self.evaluate(observation, timestamp_secs()) {
+            match alert.status {
+                AlertStatus::Firing => warn!("alert firing: {}", alert.summary),
+                AlertStatus::Resolved => info!("alert resolved: {}", alert.summary),
+            }
+            if self.webhooks.read().unwrap().is_empty() {
+                continue;
+            }
+            let webhooks = &self.webhooks;
+            let deliveries = self.deliveries.get_or_insert_with(|| {
+                let (tx, rx) = mpsc::unbounded_channel();
+                tokio::spawn(deliver(webhooks.clone(), rx));
+                tx
+            });
+            if deliveries.send(alert).is_err() {
+                error!("alert delivery task stopped");
+                self.deliveries = None;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sends the alerts to every webhook in the order they fired
+async fn deliver(
+    webhooks: Arc<RwLock<Vec<AlertWebhook>>>,
+    mut alerts: mpsc::UnboundedReceiver<Alert>,
+) {
+    let client = match reqwest::Client::builder()
+        .connect_timeout(WEBHOOK_CONNECT_TIMEOUT)
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(err) => {
+            error!("error creating the alert webhook client: {:?}", err);
+            return;
+        }
+    };
+    while let Some(alert) = alerts.recv().await {
+        let webhooks = webhooks.read().unwrap().clone();
+        for webhook in &webhooks {
+            let mut delay = DELIVERY_RETRY_DELAY;
+            for attempt in 1..=DELIVERY_ATTEMPTS {
+                match send(&client, webhook, &alert, attempt).await {
+                    Ok(()) => break,
+                    Err(err) if attempt < DELIVERY_ATTEMPTS => {
+                        warn!(
+                            "error sending alert {} to {}, attempt {}: {:?}",
+                            alert.rule, webhook.url, attempt, err
+                        );
+                        tokio::time::sleep(delay).await;
+                        delay *= 2;
+                    }
+                    Err(err) => error!(
+                        "error sending alert {} to {}: {:?}",
+                        alert.rule, webhook.url, err
+                    ),
+                }
+            }
+        }
+    }
+}
+
+async fn send(
+    client: &reqwest::Client,
+    webhook: &AlertWebhook,
+    alert: &Alert,
+    attempt: u32,
+) -> Result<()> {
+    let body = match webhook.format {
+        AlertFormat::Json => serde_json::to_value(alert)?,
+        AlertFormat::PagerDuty => pagerduty_event(alert, webhook.routing_key.as_deref()),
+    };
+    webhooks::delivery(
+        client.post(&webhook.url),
+        &delivery_id(alert),
+        attempt,
+        webhook.secret.as_deref(),
+        serde_json::to_vec(&body)?,
+    )
+    .send()
+    .await?
+    .error_for_status()?;
+    Ok(())
+}
+
+/// Id of the delivery of an alert, the same for every webhook and attempt
+fn delivery_id(alert: &Alert) -> String {
+    let status = match alert.status {
+        AlertStatus::Firing => "firing",
+        AlertStatus::Resolved => "resolved",
+    };
+    format!("{}-{}-{}", alert.rule, status, alert.at)
+}
+
+/// A PagerDuty Events API v2 event. Alerts of a rule share a dedup key, so a
+/// resolved alert resolves the incident its firing alert opened.
+fn pagerduty_event(alert: &Alert, routing_key: Option<&str>) -> serde_json::Value {
+    let event_action = match alert.status {
+        AlertStatus::Firing => "trigger",
+        AlertStatus::Resolved => "resolve",
+    };
+    json!({
+        "routing_key": routing_key.unwrap_or_default(),
+        "event_action": event_action,
+        "dedup_key": format!("indexify-{}", alert.rule),
+        "payload": {
+            "summary": alert.summary,
+            "source": PAGERDUTY_EVENTS_SOURCE,
+            "severity": alert.severity.to_string(),
+            "custom_details": {
+                "metric": alert.metric.to_string(),
+                "value": alert.value,
+                "threshold": alert.threshold,
+                "since": alert.since,
+            },
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(name: &str, metric: AlertMetric) -> AlertRule {
+        AlertRule {
+            name: name.to_string(),
+            metric,
+            above: None,
+            below: None,
+            for_secs: 0,
+            growth_over_secs: None,
+            severity: AlertSeverity::Warning,
+        }
+    }
+
+    fn observation(
+        unallocated_tasks: u64,
+        executors: u64,
+        quarantined_tasks: u64,
+    ) -> AlertObservation {
+        AlertObservation {
+            unallocated_tasks,
+            executors,
+            quarantined_tasks,
+        }
+    }
+
+    fn statuses(alerts: &[Alert]) -> Vec<(&str, AlertStatus)> {
+        alerts
+            .iter()
+            .map(|alert| (alert.rule.as_str(), alert.status))
+            .collect()
+    }
+
+    #[test]
+    fn test_evaluate_alert_rules() {
+        let config = AlertingConfig {
+            rules: vec![
+                AlertRule {
+                    above: Some(100.0),
+                    for_secs: 60,
+                    ..rule("backlog", AlertMetric::UnallocatedTasks)
+                },
+                AlertRule {
+                    below: Some(2.0),
+                    ..rule("executors", AlertMetric::Executors)
+                },
+                AlertRule {
+                    above: Some(5.0),
+                    growth_over_secs: Some(300),
+                    ..rule("quarantine_growth", AlertMetric::QuarantinedTasks)
+                },
+            ],
+            ..Default::default()
+        };
+        let mut alerter = Alerter::new(&config);
+
+        //  Fewer executors fires right away, the backlog has to last
+        let alerts = alerter.evaluate(observation(200, 1, 10), 1000);
+        assert_eq!(statuses(&alerts), vec![("executors", AlertStatus::Firing)]);
+        assert!(alerter.evaluate(observation(200, 1, 12), 1030).is_empty());
+        let alerts = alerter.evaluate(observation(200, 2, 14), 1060);
+        assert_eq!(
+            statuses(&alerts),
+            vec![
+                ("backlog", AlertStatus::Firing),
+                ("executors", AlertStatus::Resolved)
+            ]
+        );
+        assert_eq!(alerts[0].since, 1000);
+
+        //  Quarantined tasks grew by 6 over the window, not by their total
+        let alerts = alerter.evaluate(observation(50, 2, 16), 1300);
+        assert_eq!(
+            statuses(&alerts),
+            vec![
+                ("backlog", AlertStatus::Resolved),
+                ("quarantine_growth", AlertStatus::Firing)
+            ]
+        );
+        assert_eq!(alerts[1].value, 6.0);
+        let alerts = alerter.evaluate(observation(50, 2, 16), 1700);
+        assert_eq!(
+            statuses(&alerts),
+            vec![("quarantine_growth", AlertStatus::Resolved)]
+        );
+
+        //  A backlog that clears before the duration never fires
+        assert!(alerter.evaluate(observation(200, 2, 16), 1800).is_empty());
+        assert!(alerter.evaluate(observation(0, 2, 16), 1830).is_empty());
+        assert!(alerter.evaluate(observation(200, 2, 16), 1870).is_empty());
+    }
+
+    #[test]
+    fn test_pagerduty_event() {
+        let alert = Alert {
+            rule: "backlog".to_string(),
+            status: AlertStatus::Resolved,
+            severity: AlertSeverity::Critical,
+            metric: AlertMetric::UnallocatedTasks,
+            value: 10.0,
+            threshold: 100.0,
+            since: 1000,
+            at: 1200,
+            summary: "backlog: unallocated_tasks is back at 10".to_string(),
+        };
+        assert_eq!(delivery_id(&alert), "backlog-resolved-1200");
+        let event = pagerduty_event(&alert, Some("key"));
+        assert_eq!(event["event_action"], "resolve");
+        assert_eq!(event["dedup_key"], "indexify-backlog");
+        assert_eq!(event["payload"]["severity"], "critical");
+        assert_eq!(
+            event["payload"]["custom_details"]["metric"],
+            "unallocated_tasks"
+        );
+    }
+}
@@ -22,6 +22,12 @@ pub struct ExtractionGraph {
     #[serde(default)]
     pub namespace: String,
     pub extraction_policies: Vec<ExtractionPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_active_content: Option<u32>,
+    #[serde(default)]
+    pub latency_tolerant: bool,
+    #[serde(default)]
+    pub zone_placement: internal_api::ZonePlacement,
 }
 
 impl From<indexify_coordinator::ExtractionGraph> for ExtractionGraph {
@@ -35,6 +41,9 @@ impl From<indexify_coordinator::ExtractionGraph> for ExtractionGraph {
                 .into_iter()
                 .map(Into::into)
                 .collect(),
+            max_active_content: Some(value.max_active_content).filter(|max| *max > 0),
+            latency_tolerant: value.latency_tolerant,
+            zone_placement: value.zone_placement.parse().unwrap_or_default(),
         }
     }
 }
@@ -49,6 +58,63 @@ pub struct ExtractionPolicy {
     pub input_params: Option<serde_json::Value>,
     pub content_source: Option<String>,
     pub graph_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_output: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tasks_per_second: Option<u32>,
+    #[serde(default)]
+    pub expensive_warmup: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_warm_executors: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slo: Option<ExtractionPolicySlo>,
+}
+
+/// Service level objectives of the tasks of an extraction policy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExtractionPolicySlo {
+    /// Milliseconds within which tasks finish after they are created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    /// Share of the tasks that have to finish within the latency
+    #[serde(default = "default_slo_latency_target")]
+    pub latency_target: f64,
+    /// Share of the tasks that are allowed to fail
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_error_rate: Option<f64>,
+}
+
+fn default_slo_latency_target() -> f64 {
+    0.99
+}
+
+impl From<ExtractionPolicySlo> for indexify_coordinator::ExtractionPolicySlo {
+    fn from(value: ExtractionPolicySlo) -> Self {
+        Self {
+            latency_ms: value.latency_ms.unwrap_or_default(),
+            latency_target_ppm: (value.latency_target * 1e6).round() as u32,
+            max_error_rate_ppm: value
+                .max_error_rate
+                .map(|rate| (rate * 1e6).round() as u32)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl From<indexify_coordinator::ExtractionPolicySlo> for ExtractionPolicySlo {
+    fn from(value: indexify_coordinator::ExtractionPolicySlo) -> Self {
+        Self {
+            latency_ms: Some(value.latency_ms).filter(|latency| *latency > 0),
+            latency_target: value.latency_target_ppm as f64 / 1e6,
+            max_error_rate: Some(value.max_error_rate_ppm)
+                .filter(|max| *max > 0)
+                .map(|max| max as f64 / 1e6),
+        }
+    }
 }
 
 impl From<indexify_coordinator::ExtractionPolicy> for ExtractionPolicy {
@@ -61,6 +127,15 @@ impl From<indexify_coordinator::ExtractionPolicy> for ExtractionPolicy {
             input_params: Some(serde_json::from_str(&value.input_params).unwrap()),
             content_source: Some(value.content_source),
             graph_name: value.graph_name,
+            output_schema: serde_json::from_str(&value.output_schema).ok(),
+            source_output: Some(value.source_output).filter(|output| !output.is_empty()),
+            max_tasks_per_second: Some(value.max_tasks_per_second).filter(|max| *max > 0),
+            expensive_warmup: value.expensive_warmup,
+            min_warm_executors: Some(value.min_warm_executors).filter(|min| *min > 0),
+            gpu: Some(value.gpu_millis)
+                .filter(|millis| *millis > 0)
+                .map(|millis| millis as f64 / 1000.0),
+            slo: value.slo.map(Into::into),
         }
     }
 }
@@ -144,12 +219,51 @@ impl From<vectordbs::IndexDistance> for IndexDistance {
 /// Request payload for creating a new vector index.
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct ExtractionPolicyRequest {
+    #[serde(default)]
     pub extractor: String,
     pub name: String,
     #[serde(default, deserialize_with = "api_utils::deserialize_labels_eq_filter")]
     pub filters_eq: Option<HashMap<String, String>>,
     pub input_params: Option<serde_json::Value>,
     pub content_source: Option<String>,
+    /// Name of an extraction graph in the same namespace whose policies
+    /// replace this one. They are copied into the graph as
+    /// `<name>.<subgraph policy>` when the graph is created, and policies of
+    /// the graph can use either those names or `<name>` as content source.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subgraph: Option<String>,
+    /// JSON schema that the metadata extracted by the policy has to match.
+    /// Tasks whose metadata doesn't match it fail.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<serde_json::Value>,
+    /// Named output of the `content_source` policy to read content from.
+    /// Content emitted on any output of it is read when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_output: Option<String>,
+    /// Most tasks of the policy handed to executors per second, for
+    /// extractors that call rate limited services. Tasks over the limit wait
+    /// unassigned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tasks_per_second: Option<u32>,
+    /// Whether the extractor is expensive to warm up, for example because it
+    /// loads a model. Tasks of the policy then prefer executors that ran it
+    /// recently.
+    #[serde(default)]
+    pub expensive_warmup: bool,
+    /// Executors kept warm for the policy of an expensive extractor. The
+    /// executors that ran the policy last keep getting its tasks after idle
+    /// periods, and tasks spread to more executors until this many ran it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_warm_executors: Option<u32>,
+    /// Share of a GPU each task of the policy takes, like 0.25 for a small
+    /// model. Tasks are packed onto the GPU devices of executors, so several
+    /// of them share a device.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu: Option<f64>,
+    /// Latency and error rate objectives of the tasks of the policy. Their
+    /// compliance is computed over the tasks finished within the last hour.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slo: Option<ExtractionPolicySlo>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
@@ -171,6 +285,12 @@ pub struct TextAddRequest {
     pub documents: Vec<Text>,
     pub sync: Option<bool>,
     pub extraction_graph_names: Vec<internal_api::ExtractionGraphName>,
+    /// Tasks of content with a higher priority are handed to executors
+    /// before the tasks of content with a lower one
+    #[serde(default)]
+    pub priority: i32,
+    /// Seconds after which the unfinished tasks of the texts expire
+    pub ttl_secs: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -241,7 +361,94 @@ impl TryFrom<indexify_coordinator::Extractor> for ExtractorDescription {
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct Executor {
     pub id: String,
+    #[serde(default)]
+    pub addr: String,
+    /// Time of the last heartbeat, in seconds since the epoch
+    #[serde(default)]
+    pub last_seen: u64,
     pub extractors: Vec<ExtractorDescription>,
+    /// Tasks assigned to the executor that haven't finished
+    #[serde(default)]
+    pub running_tasks: u64,
+    /// Running tasks the executor took above the limit of tasks per executor,
+    /// while the executors of an extractor were overcommitted. They are the
+    /// first ones taken back when the executor holds more tasks than it may.
+    #[serde(default)]
+    pub burst_tasks: u64,
+    /// Cost per hour the executor registered with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_per_hour: Option<f64>,
+    /// Multiple of the tasks per executor limit the executor takes
+    #[serde(default)]
+    pub capacity_weight: u32,
+    /// Zone the executor registered with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<String>,
+    /// Largest task input the executor takes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_payload_bytes: Option<u64>,
+    /// Bandwidth the executor registered to download task inputs with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_bytes_per_sec: Option<u64>,
+    /// Whether the executor deregistered and waits for its tasks to finish
+    #[serde(default)]
+    pub draining: bool,
+    /// Resources the executor reported it runs short of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pressure: Option<internal_api::ExecutorPressure>,
+    /// GPU devices of the executor and the share of each taken by its tasks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gpu_devices: Vec<GpuDevice>,
+    /// Versions of extraction graphs the executor declared it runs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub graph_versions: Vec<internal_api::GraphVersionRange>,
+}
+
+/// A GPU device of an executor, in shares of a whole GPU
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct GpuDevice {
+    /// Share of a GPU the device offers, less than 1 for a partition of one
+    pub capacity: f64,
+    /// Share of a GPU taken by the tasks running on the device
+    pub occupied: f64,
+}
+
+impl From<indexify_coordinator::GpuDevice> for GpuDevice {
+    fn from(value: indexify_coordinator::GpuDevice) -> Self {
+        Self {
+            capacity: value.capacity_millis as f64 / 1000.0,
+            occupied: value.occupied_millis as f64 / 1000.0,
+        }
+    }
+}
+
+impl TryFrom<indexify_coordinator::Executor> for Executor {
+    type Error = anyhow::Error;
+
+    fn try_from(value: indexify_coordinator::Executor) -> Result<Self> {
+        Ok(Self {
+            id: value.id,
+            addr: value.addr,
+            last_seen: value.last_seen,
+            extractors: value
+                .extractors
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_>>()?,
+            running_tasks: value.running_tasks,
+            burst_tasks: value.burst_tasks,
+            cost_per_hour: Some(value.cost_per_hour).filter(|cost| *cost > 0.0),
+            capacity_weight: value.capacity_weight.max(1),
+            zone: Some(value.zone).filter(|zone| !zone.is_empty()),
+            max_payload_bytes: Some(value.max_payload_bytes).filter(|max| *max > 0),
+            download_bytes_per_sec: Some(value.download_bytes_per_sec)
+                .filter(|bandwidth| *bandwidth > 0),
+            draining: value.draining,
+            pressure: value.pressure.and_then(|pressure| pressure.try_into().ok()),
+            gpu_devices: value.gpu_devices.into_iter().map(Into::into).collect(),
+            graph_versions: value.graph_versions.into_iter().map(Into::into).collect(),
+        })
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -389,6 +596,39 @@ impl IntoResponse for IndexifyAPIError {
     }
 }
 
+/// Metadata key of the status a stream of rows from the coordinator ends with
+/// on a read error, holding the key of the last row it sent
+pub const STREAM_CURSOR_METADATA_KEY: &str = "x-indexify-cursor";
+
+/// Last line of a stream of JSON lines that failed after it started
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct StreamError {
+    pub error: String,
+    /// Passed as `cursor` to resume the stream after the last line it sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+impl From<tonic::Status> for StreamError {
+    fn from(status: tonic::Status) -> Self {
+        let cursor = status
+            .metadata()
+            .get(STREAM_CURSOR_METADATA_KEY)
+            .and_then(|cursor| cursor.to_str().ok())
+            .map(str::to_string);
+        Self {
+            error: status.message().to_string(),
+            cursor,
+        }
+    }
+}
+
+/// Where a stream starts, from the cursor of the error that ended it
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+pub struct StreamCursor {
+    pub cursor: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
 
 pub struct ListContentResponse {
@@ -415,6 +655,9 @@ pub struct ContentMetadata {
     pub source: String,
     pub size: u64,
     pub hash: String,
+    pub priority: i32,
+    pub expires_at: u64,
+    pub output: String,
 }
 
 impl From<indexify_coordinator::ContentMetadata> for ContentMetadata {
@@ -433,6 +676,9 @@ impl From<indexify_coordinator::ContentMetadata> for ContentMetadata {
             size: value.size_bytes,
             hash: value.hash,
             extraction_graph_names: value.extraction_graph_names,
+            priority: value.priority,
+            expires_at: value.expires_at,
+            output: value.output,
         }
     }
 }
@@ -453,6 +699,9 @@ impl From<indexify_internal_api::ContentMetadata> for ContentMetadata {
             size: value.size_bytes,
             hash: value.hash,
             extraction_graph_names: value.extraction_graph_names,
+            priority: value.priority,
+            expires_at: value.expires_at,
+            output: value.output,
         }
     }
 }
@@ -468,6 +717,9 @@ pub struct Task {
     pub input_params: serde_json::Value,
     pub outcome: i32,
     pub index_tables: Vec<String>,
+    pub diagnostics: Option<String>,
+    /// Latest progress the executor running the task reported
+    pub progress: Option<internal_api::TaskProgress>,
 }
 
 impl From<indexify_coordinator::Task> for Task {
@@ -484,6 +736,8 @@ impl From<indexify_coordinator::Task> for Task {
             input_params: serde_json::Value::String(value.input_params),
             outcome: value.outcome, //  EGTODO: Is it correct to just return i32 for value outcome?
             index_tables: value.index_tables,
+            diagnostics: Some(value.diagnostics).filter(|d| !d.is_empty()),
+            progress: value.progress.map(Into::into),
         }
     }
 }
@@ -590,11 +844,23 @@ pub struct ContentFrame {
     pub bytes: Vec<u8>,
 }
 
+/// Output the executor captured from the extractor while it ran the task.
+/// Frames of a stream are appended to its log.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TaskOutput {
+    pub stream: TaskLogStream,
+    pub bytes: Vec<u8>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct FinishContent {
     pub content_type: String,
     pub features: Vec<Feature>,
     pub labels: HashMap<String, String>,
+    /// Named output the content is emitted on. Policies reading from the
+    /// extraction policy can subscribe to a single output.
+    #[serde(default)]
+    pub output: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -610,6 +876,7 @@ pub enum IngestExtractedContent {
     BeginMultipartContent(BeginMultiPartContent),
     MultipartContentFrame(ContentFrame),
     FinishMultipartContent(FinishContent),
+    TaskOutput(TaskOutput),
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
@@ -634,6 +901,9 @@ pub struct ExtractedFeatures {
 #[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
 pub struct FinishExtractedContentIngest {
     pub num_extracted_content: u64,
+    /// Resources the task used
+    #[serde(default)]
+    pub usage: Option<internal_api::TaskUsage>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -661,6 +931,354 @@ pub struct ListTasksResponse {
     pub tasks: Vec<Task>,
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GetTaskResponse {
+    pub task: Task,
+    /// States the task moved through, oldest first
+    pub transitions: Vec<internal_api::TaskTransition>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TaskSchedulingResponse {
+    /// Most recent placement attempts of the allocator, oldest first
+    pub attempts: Vec<internal_api::SchedulingAttempt>,
+}
+
+#[derive(
+    Display,
+    EnumString,
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    ToSchema,
+)]
+pub enum TaskLogStream {
+    #[serde(rename = "stdout")]
+    #[strum(serialize = "stdout")]
+    #[default]
+    Stdout,
+
+    #[serde(rename = "stderr")]
+    #[strum(serialize = "stderr")]
+    Stderr,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TaskLogParams {
+    /// Attempt of the task, the latest one by default
+    pub attempt: Option<u32>,
+    #[serde(default)]
+    pub stream: TaskLogStream,
+    /// Only return the last `tail` lines of the log
+    pub tail: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListStuckContentParams {
+    /// Also report content whose tasks made no progress for this many
+    /// seconds. Defaults to the coordinator's `stalled_after_secs`.
+    pub stalled_for_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StuckContent {
+    pub content_id: String,
+    pub outstanding_tasks: u64,
+    pub pending_tasks: u64,
+    pub stalled_secs: u64,
+}
+
+impl From<indexify_coordinator::StuckContent> for StuckContent {
+    fn from(value: indexify_coordinator::StuckContent) -> Self {
+        Self {
+            content_id: value.content_id,
+            outstanding_tasks: value.outstanding_tasks,
+            pending_tasks: value.pending_tasks,
+            stalled_secs: value.stalled_secs,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListStuckContentResponse {
+    pub content: Vec<StuckContent>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ListStarvedTasksParams {
+    /// Report tasks that stayed unallocated for this many seconds. Defaults
+    /// to the scheduler's `starvation_threshold_secs`.
+    pub unallocated_for_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct StarvedTask {
+    pub task_id: String,
+    pub extraction_graph: String,
+    pub extraction_policy_id: String,
+    pub extractor: String,
+    pub unallocated_secs: u64,
+    pub reason: internal_api::UnschedulableReason,
+}
+
+impl From<indexify_coordinator::StarvedTask> for StarvedTask {
+    fn from(value: indexify_coordinator::StarvedTask) -> Self {
+        Self {
+            task_id: value.task_id,
+            extraction_graph: value.extraction_graph,
+            extraction_policy_id: value.extraction_policy_id,
+            extractor: value.extractor,
+            unallocated_secs: value.unallocated_secs,
+            reason: value.reason.parse().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListStarvedTasksResponse {
+    pub tasks: Vec<StarvedTask>,
+    /// Number of starved tasks per reason
+    pub reasons: HashMap<String, u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RepairContentResponse {
+    pub created_tasks: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+pub struct ListMeteringRecordsParams {
+    /// Only return the records of this namespace
+    pub namespace: Option<String>,
+    /// Only return the periods starting at or after this time, in seconds
+    /// since the epoch
+    #[serde(default)]
+    pub start: u64,
+    /// Only return the periods starting at or before this time, in seconds
+    /// since the epoch. Defaults to now.
+    pub end: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListMeteringRecordsResponse {
+    pub records: Vec<internal_api::MeteringRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RerunFailedTasksResponse {
+    /// Ids of the failed tasks that run again
+    pub task_ids: Vec<String>,
+    /// Replay of the failed tasks, unset when no task failed
+    pub replay_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReplayStartedResponse {
+    pub replay_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+pub struct ListReplaysParams {
+    /// Only return the replays that ran policies of this extraction graph
+    pub extraction_graph: Option<String>,
+    /// Only return the replays of this content
+    pub content_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListReplaysResponse {
+    /// Latest first
+    pub replays: Vec<internal_api::Replay>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ReplayResponse {
+    pub replay: internal_api::Replay,
+    /// Content extracted by the tasks of the replay
+    pub output_content_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListGraphTemplatesResponse {
+    pub templates: Vec<internal_api::GraphTemplate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InstantiateGraphTemplateRequest {
+    /// Name of the extraction graph created from the template
+    pub name: String,
+    /// Values of the parameters of the template, parameters left out take
+    /// their default
+    #[serde(default)]
+    pub parameters: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InvocationBreakdownResponse {
+    pub breakdown: internal_api::InvocationBreakdown,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ContentContextResponse {
+    pub context: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TaskQueueDepth {
+    pub extraction_graph: String,
+    pub extraction_policy: String,
+    /// Tasks waiting for an executor
+    pub unallocated: u64,
+    /// Tasks assigned to an executor that have not finished
+    pub running: u64,
+    pub completed_last_hour: u64,
+}
+
+impl From<indexify_coordinator::TaskQueueDepth> for TaskQueueDepth {
+    fn from(value: indexify_coordinator::TaskQueueDepth) -> Self {
+        Self {
+            extraction_graph: value.extraction_graph,
+            extraction_policy: value.extraction_policy,
+            unallocated: value.unallocated,
+            running: value.running,
+            completed_last_hour: value.completed_last_hour,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListTaskQueueDepthsResponse {
+    pub queue_depths: Vec<TaskQueueDepth>,
+}
+
+/// A hypothetical change to the cluster the scheduler is simulated with
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SimulateSchedulingRequest {
+    /// Executors added to the cluster
+    #[serde(default)]
+    pub executors: Vec<SimulatedExecutors>,
+    /// Content ingested into extraction graphs on top of the current load
+    #[serde(default)]
+    pub invocations: Vec<SimulatedInvocations>,
+    /// How far ahead queues are predicted, an hour when unset
+    #[serde(default)]
+    pub horizon_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SimulatedExecutors {
+    pub count: u32,
+    /// Names of the extractors the executors run
+    pub extractors: Vec<String>,
+    #[serde(default)]
+    pub capacity_weight: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SimulatedInvocations {
+    pub namespace: String,
+    pub extraction_graph: String,
+    /// Content ingested into the graph per hour
+    pub per_hour: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SimulateSchedulingResponse {
+    pub horizon_secs: u64,
+    pub extractors: Vec<SimulatedQueue>,
+}
+
+/// The predicted queue of the tasks of an extractor
+#[derive(Debug, Serialize, Deserialize, ToSchema, PartialEq)]
+pub struct SimulatedQueue {
+    pub extractor: String,
+    /// Executors of the extractor after the change
+    pub executors: usize,
+    /// Tasks waiting for an executor now
+    pub unallocated: u64,
+    /// Tasks still waiting once the unallocated tasks are placed on the
+    /// executors after the change
+    pub queued: u64,
+    /// Tasks waiting at the end of the horizon. Unset when the executors of
+    /// the extractor finished no task in the last hour, as how long its tasks
+    /// run is unknown
+    pub predicted_queued: Option<u64>,
+    /// How long the tasks of the extractor wait for an executor on average
+    /// over the horizon, counting the tasks still waiting at its end
+    pub predicted_wait_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RerunExtractionPolicyRequest {
+    pub extraction_graph: String,
+    pub extraction_policy: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema)]
+pub struct CancelTasksRequest {
+    pub extraction_graph: Option<String>,
+    #[serde(default)]
+    pub labels_eq: HashMap<String, String>,
+    /// Only cancel tasks of content created at or after this time, in seconds
+    pub created_after: Option<i64>,
+    /// Only cancel tasks of content created before this time, in seconds
+    pub created_before: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CancelTasksResponse {
+    pub job_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+pub enum CancelTasksJobState {
+    #[serde(rename = "running")]
+    Running,
+    #[serde(rename = "completed")]
+    Completed,
+    #[serde(rename = "aborted")]
+    Aborted,
+}
+
+impl From<indexify_coordinator::CancelTasksJobState> for CancelTasksJobState {
+    fn from(value: indexify_coordinator::CancelTasksJobState) -> Self {
+        match value {
+            indexify_coordinator::CancelTasksJobState::Running => CancelTasksJobState::Running,
+            indexify_coordinator::CancelTasksJobState::Completed => CancelTasksJobState::Completed,
+            indexify_coordinator::CancelTasksJobState::Aborted => CancelTasksJobState::Aborted,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CancelTasksJob {
+    pub id: String,
+    pub namespace: String,
+    pub state: CancelTasksJobState,
+    pub matched_tasks: u64,
+    pub cancelled_tasks: u64,
+    pub error: Option<String>,
+}
+
+impl From<indexify_coordinator::GetCancelTasksJobResponse> for CancelTasksJob {
+    fn from(value: indexify_coordinator::GetCancelTasksJobResponse) -> Self {
+        Self {
+            state: value.state().into(),
+            id: value.job_id,
+            namespace: value.namespace,
+            matched_tasks: value.matched_tasks,
+            cancelled_tasks: value.cancelled_tasks,
+            error: Some(value.error).filter(|e| !e.is_empty()),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ListStateChanges {
     pub start_at: Option<u64>,
@@ -718,6 +1336,9 @@ pub struct IngestRemoteFile {
     pub mime_type: String,
     pub labels: HashMap<String, String>,
     pub extraction_graph_names: Vec<String>,
+    #[serde(default)]
+    pub priority: i32,
+    pub ttl_secs: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -730,18 +1351,166 @@ pub struct TaskAssignments {
     pub assignments: HashMap<String, String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UploadFileResponse {
     pub content_id: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ExtractionGraphRequest {
     pub name: String,
     pub extraction_policies: Vec<ExtractionPolicyRequest>,
+    /// Maximum number of ingested content that is extracted at the same time,
+    /// further content is queued until earlier content finishes
+    #[serde(default)]
+    pub max_active_content: Option<u32>,
+    /// Tasks of the graph can wait for cheap executors, they are placed on
+    /// the executors with the lowest cost per hour that have room
+    #[serde(default)]
+    pub latency_tolerant: bool,
+    /// Whether the tasks of a content are spread across the zones of the
+    /// executors or kept in one zone
+    #[serde(default)]
+    pub zone_placement: internal_api::ZonePlacement,
+    /// Summary of the change for the history of the graph, generated from the
+    /// difference to the current definition when unset
+    #[serde(default)]
+    pub change_summary: Option<String>,
+    /// Author of the change when the request isn't authenticated, otherwise
+    /// the authenticated subject is recorded
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Reject the graph when linting it finds warnings, like unreachable
+    /// policies or large fan outs, instead of creating it with them
+    #[serde(default)]
+    pub strict: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ExtractionGraphResponse {
     pub indexes: Vec<String>,
+    /// Version of the graph in its history
+    pub version: u64,
+    /// Warnings of the lint checks of the graph, it was created regardless
+    #[serde(default)]
+    pub warnings: Vec<ExtractionGraphDiagnostic>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ExtractionGraphVersion {
+    pub version: u64,
+    pub extraction_graph: ExtractionGraph,
+    pub author: String,
+    pub created_at: u64,
+    pub summary: String,
+}
+
+impl From<indexify_coordinator::ExtractionGraphVersion> for ExtractionGraphVersion {
+    fn from(value: indexify_coordinator::ExtractionGraphVersion) -> Self {
+        Self {
+            version: value.version,
+            extraction_graph: value.extraction_graph.unwrap_or_default().into(),
+            author: value.author,
+            created_at: value.created_at,
+            summary: value.summary,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ListExtractionGraphVersionsResponse {
+    /// Oldest first
+    pub versions: Vec<ExtractionGraphVersion>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ExtractionGraphUsageResponse {
+    /// Versions of the graph that ran tasks, oldest first
+    pub usage: Vec<internal_api::ExtractionGraphUsage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SloComplianceResponse {
+    /// Extraction policies of the graph that have objectives
+    pub compliance: Vec<internal_api::SloCompliance>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RollbackExtractionGraphRequest {
+    /// Version of the graph to make its latest version
+    pub version: u64,
+    /// Author of the rollback when the request isn't authenticated
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SetMaintenanceModeRequest {
+    pub enabled: bool,
+    /// Why the cluster is in maintenance, shown to other operators
+    #[serde(default)]
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExtractionGraphDiagnostic {
+    pub severity: DiagnosticSeverity,
+    /// Name of the extraction policy the diagnostic applies to, unset for the
+    /// graph as a whole
+    pub extraction_policy: Option<String>,
+    pub message: String,
+}
+
+impl From<indexify_coordinator::ExtractionGraphDiagnostic> for ExtractionGraphDiagnostic {
+    fn from(value: indexify_coordinator::ExtractionGraphDiagnostic) -> Self {
+        let severity = match value.severity() {
+            indexify_coordinator::DiagnosticSeverity::Error => DiagnosticSeverity::Error,
+            indexify_coordinator::DiagnosticSeverity::Warning => DiagnosticSeverity::Warning,
+        };
+        Self {
+            severity,
+            extraction_policy: Some(value.extraction_policy).filter(|name| !name.is_empty()),
+            message: value.message,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ValidateExtractionGraphResponse {
+    /// True when there are no error diagnostics
+    pub valid: bool,
+    pub diagnostics: Vec<ExtractionGraphDiagnostic>,
+}
+
+/// An edge of an extraction graph. An empty `from` is the ingested content.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+pub struct ExtractionGraphEdge {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExtractionPolicyChange {
+    pub name: String,
+    /// Names of the fields that differ, e.g. `extractor` or `input_params`
+    pub changed_fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExtractionGraphDiff {
+    pub added_policies: Vec<String>,
+    pub removed_policies: Vec<String>,
+    pub changed_policies: Vec<ExtractionPolicyChange>,
+    pub added_edges: Vec<ExtractionGraphEdge>,
+    pub removed_edges: Vec<ExtractionGraphEdge>,
+    /// Policies of the proposed graph that would produce new outputs, which are
+    /// the added and changed policies and everything downstream of them
+    pub affected_policies: Vec<String>,
 }
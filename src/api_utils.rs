@@ -1,7 +1,20 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, VecDeque};
 
 use anyhow::{anyhow, Result};
-use serde::Deserialize;
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use jsonschema::JSONSchema;
+use mime::Mime;
+use serde::{Deserialize, Serialize};
+
+use crate::api::{
+    ExtractionGraph,
+    ExtractionGraphDiff,
+    ExtractionGraphEdge,
+    ExtractionGraphRequest,
+    ExtractionPolicyChange,
+    StreamError,
+};
 
 pub fn validate_label_key(key: &str) -> Result<()> {
     let validations = [
@@ -417,3 +430,713 @@ mod test_deserialize_labels_eq_filter {
         }
     }
 }
+
+/// A single range parsed from a `Range: bytes=...` request header. `end` is
+/// inclusive, as in the header.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ByteRange {
+    FromTo { start: u64, end: Option<u64> },
+    Suffix(u64),
+}
+
+impl ByteRange {
+    /// Resolves the range against an object of `len` bytes, returning the
+    /// inclusive start and end offsets, or None if the range is not
+    /// satisfiable.
+    pub fn resolve(&self, len: u64) -> Option<(u64, u64)> {
+        if len == 0 {
+            return None;
+        }
+        match *self {
+            ByteRange::FromTo { start, end } => {
+                if start >= len {
+                    return None;
+                }
+                let end = end.map_or(len - 1, |end| end.min(len - 1));
+                Some((start, end))
+            }
+            ByteRange::Suffix(0) => None,
+            ByteRange::Suffix(n) => Some((len.saturating_sub(n), len - 1)),
+        }
+    }
+}
+
+pub fn parse_byte_range(header: &str) -> Result<ByteRange> {
+    let spec = header
+        .trim()
+        .strip_prefix("bytes=")
+        .ok_or_else(|| anyhow!("unsupported range unit: \"{}\"", header))?;
+    if spec.contains(',') {
+        return Err(anyhow!("multiple ranges are not supported"));
+    }
+    let (start, end) = spec
+        .split_once('-')
+        .ok_or_else(|| anyhow!("invalid range: \"{}\"", header))?;
+    let parse = |s: &str| {
+        s.trim()
+            .parse::<u64>()
+            .map_err(|_| anyhow!("invalid range: \"{}\"", header))
+    };
+    match (start.trim().is_empty(), end.trim().is_empty()) {
+        (true, true) => Err(anyhow!("invalid range: \"{}\"", header)),
+        (true, false) => Ok(ByteRange::Suffix(parse(end)?)),
+        (false, true) => Ok(ByteRange::FromTo {
+            start: parse(start)?,
+            end: None,
+        }),
+        (false, false) => {
+            let (start, end) = (parse(start)?, parse(end)?);
+            if end < start {
+                return Err(anyhow!("invalid range: \"{}\"", header));
+            }
+            Ok(ByteRange::FromTo {
+                start,
+                end: Some(end),
+            })
+        }
+    }
+}
+
+/// Returns the last `n` lines of `buf`. A trailing newline does not count as
+/// an extra empty line.
+pub fn tail_lines(buf: &[u8], n: usize) -> &[u8] {
+    if n == 0 {
+        return &buf[buf.len()..];
+    }
+    let body = buf.strip_suffix(b"\n").unwrap_or(buf);
+    let start = body
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, b)| **b == b'\n')
+        .nth(n - 1)
+        .map_or(0, |(i, _)| i + 1);
+    &buf[start..]
+}
+
+#[cfg(test)]
+mod test_byte_range {
+    use super::*;
+
+    #[test]
+    fn test_parse_byte_range() {
+        assert_eq!(
+            parse_byte_range("bytes=0-99").unwrap(),
+            ByteRange::FromTo {
+                start: 0,
+                end: Some(99)
+            }
+        );
+        assert_eq!(
+            parse_byte_range("bytes=100-").unwrap(),
+            ByteRange::FromTo {
+                start: 100,
+                end: None
+            }
+        );
+        assert_eq!(
+            parse_byte_range("bytes=-500").unwrap(),
+            ByteRange::Suffix(500)
+        );
+        for invalid in [
+            "bytes=-",
+            "bytes=5-1",
+            "items=0-1",
+            "bytes=0-1,4-5",
+            "bytes=a-b",
+        ] {
+            assert!(parse_byte_range(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_resolve_byte_range() {
+        let range = ByteRange::FromTo {
+            start: 2,
+            end: Some(100),
+        };
+        assert_eq!(range.resolve(10), Some((2, 9)));
+        assert_eq!(range.resolve(2), None);
+        assert_eq!(ByteRange::Suffix(4).resolve(10), Some((6, 9)));
+        assert_eq!(ByteRange::Suffix(40).resolve(10), Some((0, 9)));
+        assert_eq!(ByteRange::Suffix(0).resolve(10), None);
+    }
+
+    #[test]
+    fn test_tail_lines() {
+        let log = b"one\ntwo\nthree\n";
+        assert_eq!(tail_lines(log, 1), b"three\n");
+        assert_eq!(tail_lines(log, 2), b"two\nthree\n");
+        assert_eq!(tail_lines(log, 10), log);
+        assert_eq!(tail_lines(b"one\ntwo", 1), b"two");
+        assert_eq!(tail_lines(log, 0), b"");
+    }
+}
+
+/// Encodings of content the server knows how to validate. Content of any
+/// other type is stored and served as it was uploaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadEncoding {
+    Json,
+    ArrowIpc,
+    Protobuf,
+    Bytes,
+}
+
+/// Media types of every encoding, the first one is the canonical one
+const PAYLOAD_ENCODINGS: &[(PayloadEncoding, &[&str])] = &[
+    (PayloadEncoding::Json, &["application/json"]),
+    (
+        PayloadEncoding::ArrowIpc,
+        &[
+            "application/vnd.apache.arrow.stream",
+            "application/vnd.apache.arrow.file",
+        ],
+    ),
+    (
+        PayloadEncoding::Protobuf,
+        &["application/x-protobuf", "application/protobuf"],
+    ),
+    (PayloadEncoding::Bytes, &["application/octet-stream"]),
+];
+
+const ARROW_FILE_MAGIC: &[u8] = b"ARROW1";
+const ARROW_CONTINUATION_MARKER: &[u8] = &[0xff, 0xff, 0xff, 0xff];
+
+impl PayloadEncoding {
+    pub fn from_mime(mime: &Mime) -> Option<Self> {
+        if mime.suffix() == Some(mime::JSON) {
+            return Some(PayloadEncoding::Json);
+        }
+        PAYLOAD_ENCODINGS
+            .iter()
+            .find(|(_, mime_types)| mime_types.contains(&mime.essence_str()))
+            .map(|(encoding, _)| *encoding)
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        PAYLOAD_ENCODINGS
+            .iter()
+            .find(|(encoding, _)| encoding == self)
+            .map(|(_, mime_types)| mime_types[0])
+            .unwrap_or("application/octet-stream")
+    }
+
+    /// Checks that content starting with `prefix` can be of this encoding.
+    /// Only the leading bytes are looked at, so content that passes can still
+    /// be truncated or malformed further in.
+    pub fn validate_prefix(&self, prefix: &[u8]) -> Result<()> {
+        let valid = match self {
+            PayloadEncoding::Json => prefix
+                .strip_prefix(b"\xef\xbb\xbf")
+                .unwrap_or(prefix)
+                .iter()
+                .find(|b| !b.is_ascii_whitespace())
+                .map_or(false, |b| b"{[\"-tfn".contains(b) || b.is_ascii_digit()),
+            PayloadEncoding::ArrowIpc => {
+                prefix.starts_with(ARROW_FILE_MAGIC) ||
+                    prefix.starts_with(ARROW_CONTINUATION_MARKER)
+            }
+            // The first byte of a non empty message is the key of a field,
+            // which needs a field number and one of the wire types in use
+            PayloadEncoding::Protobuf => prefix.first().map_or(true, |key| {
+                matches!(key & 0x07, 0 | 1 | 2 | 5) && (key >> 3 != 0 || key & 0x80 != 0)
+            }),
+            PayloadEncoding::Bytes => true,
+        };
+        if !valid {
+            return Err(anyhow!("content is not valid {}", self.mime_type()));
+        }
+        Ok(())
+    }
+}
+
+/// Parses the content type declared by a client and checks that the leading
+/// bytes of the content match it, when it is one of the known encodings
+pub fn validate_content_type(content_type: &str, prefix: &[u8]) -> Result<Mime> {
+    let mime: Mime = content_type
+        .parse()
+        .map_err(|e| anyhow!("invalid content type \"{}\": {}", content_type, e))?;
+    if let Some(encoding) = PayloadEncoding::from_mime(&mime) {
+        encoding.validate_prefix(prefix)?;
+    }
+    Ok(mime)
+}
+
+/// Picks the media type content of `mime_type` is served as for an `Accept`
+/// request header. Any content can be served as raw bytes when the client
+/// doesn't accept its own type. Returns None when the client accepts
+/// neither.
+pub fn negotiate_content_type(accept: Option<&str>, mime_type: &str) -> Option<String> {
+    let accept = match accept.map(str::trim) {
+        Some(accept) if !accept.is_empty() => accept,
+        _ => return Some(mime_type.to_string()),
+    };
+    let ranges: Vec<(Mime, f32)> = accept
+        .split(',')
+        .filter_map(|range| {
+            let range: Mime = range.trim().parse().ok()?;
+            let q = range
+                .get_param("q")
+                .and_then(|q| q.as_str().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            Some((range, q))
+        })
+        .collect();
+    let quality = |candidate: &str| -> f32 {
+        let Ok(candidate) = candidate.parse::<Mime>() else {
+            return 0.0;
+        };
+        ranges
+            .iter()
+            .filter(|(range, _)| {
+                (range.type_() == mime::STAR || range.type_() == candidate.type_()) &&
+                    (range.subtype() == mime::STAR || range.subtype() == candidate.subtype())
+            })
+            // The most specific range decides the quality of a type
+            .max_by_key(|(range, _)| {
+                (range.type_() != mime::STAR) as u8 + (range.subtype() != mime::STAR) as u8
+            })
+            .map_or(0.0, |(_, q)| *q)
+    };
+    [mime_type, PayloadEncoding::Bytes.mime_type()]
+        .into_iter()
+        .map(|candidate| (candidate, quality(candidate)))
+        .filter(|(_, q)| *q > 0.0)
+        .fold(
+            None,
+            |best: Option<(&str, f32)>, (candidate, q)| match best {
+                Some((_, best_q)) if best_q >= q => best,
+                _ => Some((candidate, q)),
+            },
+        )
+        .map(|(candidate, _)| candidate.to_string())
+}
+
+#[cfg(test)]
+mod test_payload_encoding {
+    use super::*;
+
+    #[test]
+    fn test_validate_content_type() {
+        let mime = validate_content_type("application/json", b"  {\"a\": 1}").unwrap();
+        assert_eq!(
+            PayloadEncoding::from_mime(&mime),
+            Some(PayloadEncoding::Json)
+        );
+        assert!(validate_content_type("application/ld+json", b"[1]").is_ok());
+        assert!(validate_content_type("application/json", b"<html>").is_err());
+        assert!(validate_content_type("application/vnd.apache.arrow.file", b"ARROW1\0\0").is_ok());
+        assert!(
+            validate_content_type("application/vnd.apache.arrow.stream", b"\xff\xff\xff\xff")
+                .is_ok()
+        );
+        assert!(
+            validate_content_type("application/vnd.apache.arrow.stream", b"PK\x03\x04").is_err()
+        );
+        assert!(validate_content_type("application/x-protobuf", b"\x0a\x03foo").is_ok());
+        assert!(validate_content_type("application/x-protobuf", b"\x07").is_err());
+        assert!(validate_content_type("application/x-protobuf", b"").is_ok());
+        assert!(validate_content_type("image/png", b"anything").is_ok());
+        assert!(validate_content_type("not a type", b"").is_err());
+    }
+
+    #[test]
+    fn test_negotiate_content_type() {
+        let json = "application/json";
+        assert_eq!(negotiate_content_type(None, json).as_deref(), Some(json));
+        assert_eq!(
+            negotiate_content_type(Some("*/*"), json).as_deref(),
+            Some(json)
+        );
+        assert_eq!(
+            negotiate_content_type(Some("application/*"), json).as_deref(),
+            Some(json)
+        );
+        assert_eq!(
+            negotiate_content_type(Some("application/octet-stream"), json).as_deref(),
+            Some("application/octet-stream")
+        );
+        assert_eq!(
+            negotiate_content_type(
+                Some("application/json;q=0.5, application/octet-stream"),
+                json
+            )
+            .as_deref(),
+            Some("application/octet-stream")
+        );
+        assert_eq!(
+            negotiate_content_type(Some("application/json, */*;q=0"), "text/plain"),
+            None
+        );
+        assert_eq!(negotiate_content_type(Some("text/html"), json), None);
+    }
+}
+
+/// Structurally compares an existing extraction graph with a proposed
+/// definition of it. Policies are matched by name.
+pub fn diff_extraction_graph(
+    current: &ExtractionGraph,
+    proposed: &ExtractionGraphRequest,
+) -> ExtractionGraphDiff {
+    let current_policies: HashMap<&str, _> = current
+        .extraction_policies
+        .iter()
+        .map(|p| (p.name.as_str(), p))
+        .collect();
+    let proposed_policies: HashMap<&str, _> = proposed
+        .extraction_policies
+        .iter()
+        .map(|p| (p.name.as_str(), p))
+        .collect();
+
+    let mut diff = ExtractionGraphDiff::default();
+    for policy in &proposed.extraction_policies {
+        let Some(existing) = current_policies.get(policy.name.as_str()) else {
+            diff.added_policies.push(policy.name.clone());
+            continue;
+        };
+        let mut changed_fields = Vec::new();
+        if existing.extractor != policy.extractor {
+            changed_fields.push("extractor".to_string());
+        }
+        if existing.input_params.clone().unwrap_or_default() !=
+            policy.input_params.clone().unwrap_or_default()
+        {
+            changed_fields.push("input_params".to_string());
+        }
+        if existing.filters_eq.clone().unwrap_or_default() !=
+            policy.filters_eq.clone().unwrap_or_default()
+        {
+            changed_fields.push("filters_eq".to_string());
+        }
+        if existing.content_source.clone().unwrap_or_default() !=
+            policy.content_source.clone().unwrap_or_default()
+        {
+            changed_fields.push("content_source".to_string());
+        }
+        if existing.output_schema != policy.output_schema {
+            changed_fields.push("output_schema".to_string());
+        }
+        if existing.source_output != policy.source_output {
+            changed_fields.push("source_output".to_string());
+        }
+        if existing.max_tasks_per_second != policy.max_tasks_per_second {
+            changed_fields.push("max_tasks_per_second".to_string());
+        }
+        if existing.expensive_warmup != policy.expensive_warmup {
+            changed_fields.push("expensive_warmup".to_string());
+        }
+        if existing.min_warm_executors != policy.min_warm_executors {
+            changed_fields.push("min_warm_executors".to_string());
+        }
+        if existing.gpu != policy.gpu {
+            changed_fields.push("gpu".to_string());
+        }
+        if existing.slo != policy.slo {
+            changed_fields.push("slo".to_string());
+        }
+        if !changed_fields.is_empty() {
+            diff.changed_policies.push(ExtractionPolicyChange {
+                name: policy.name.clone(),
+                changed_fields,
+            });
+        }
+    }
+    for policy in &current.extraction_policies {
+        if !proposed_policies.contains_key(policy.name.as_str()) {
+            diff.removed_policies.push(policy.name.clone());
+        }
+    }
+
+    let current_edges: BTreeSet<ExtractionGraphEdge> = current
+        .extraction_policies
+        .iter()
+        .map(|p| ExtractionGraphEdge {
+            from: p.content_source.clone().unwrap_or_default(),
+            to: p.name.clone(),
+        })
+        .collect();
+    let proposed_edges: BTreeSet<ExtractionGraphEdge> = proposed
+        .extraction_policies
+        .iter()
+        .map(|p| ExtractionGraphEdge {
+            from: p.content_source.clone().unwrap_or_default(),
+            to: p.name.clone(),
+        })
+        .collect();
+    diff.added_edges = proposed_edges.difference(&current_edges).cloned().collect();
+    diff.removed_edges = current_edges.difference(&proposed_edges).cloned().collect();
+
+    // Walk the proposed graph from every added or changed policy to find
+    // the policies whose outputs would be recomputed.
+    let mut queue: VecDeque<&str> = diff
+        .added_policies
+        .iter()
+        .map(|name| name.as_str())
+        .chain(diff.changed_policies.iter().map(|c| c.name.as_str()))
+        .collect();
+    let mut affected = BTreeSet::new();
+    while let Some(name) = queue.pop_front() {
+        if !affected.insert(name.to_string()) {
+            continue;
+        }
+        queue.extend(
+            proposed_edges
+                .iter()
+                .filter(|edge| edge.from == name)
+                .map(|edge| edge.to.as_str()),
+        );
+    }
+    diff.affected_policies = affected.into_iter().collect();
+    diff
+}
+
+#[cfg(test)]
+mod test_extraction_graph_diff {
+    use super::*;
+    use crate::api::{ExtractionPolicy, ExtractionPolicyRequest};
+
+    fn policy(name: &str, extractor: &str, content_source: &str) -> ExtractionPolicyRequest {
+        ExtractionPolicyRequest {
+            extractor: extractor.to_string(),
+            name: name.to_string(),
+            filters_eq: None,
+            input_params: None,
+            content_source: Some(content_source.to_string()),
+            subgraph: None,
+            output_schema: None,
+            source_output: None,
+            max_tasks_per_second: None,
+            expensive_warmup: false,
+            min_warm_executors: None,
+            gpu: None,
+            slo: None,
+        }
+    }
+
+    fn graph(policies: Vec<ExtractionPolicyRequest>) -> ExtractionGraph {
+        ExtractionGraph {
+            id: "graph".to_string(),
+            name: "graph".to_string(),
+            namespace: "default".to_string(),
+            extraction_policies: policies
+                .into_iter()
+                .map(|p| ExtractionPolicy {
+                    id: p.name.clone(),
+                    extractor: p.extractor,
+                    name: p.name,
+                    filters_eq: Some(p.filters_eq.unwrap_or_default()),
+                    input_params: Some(p.input_params.unwrap_or_default()),
+                    content_source: p.content_source,
+                    graph_name: "graph".to_string(),
+                    output_schema: p.output_schema,
+                    source_output: p.source_output,
+                    max_tasks_per_second: p.max_tasks_per_second,
+                    expensive_warmup: p.expensive_warmup,
+                    min_warm_executors: p.min_warm_executors,
+                    gpu: p.gpu,
+                    slo: p.slo,
+                })
+                .collect(),
+            max_active_content: None,
+            latency_tolerant: false,
+            zone_placement: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_identical_graphs() {
+        let policies = vec![
+            policy("chunk", "chunker", ""),
+            policy("embed", "minilm", "chunk"),
+        ];
+        let current = graph(policies.clone());
+        let proposed = ExtractionGraphRequest {
+            name: "graph".to_string(),
+            extraction_policies: policies,
+            max_active_content: None,
+            latency_tolerant: false,
+            zone_placement: Default::default(),
+            change_summary: None,
+            author: None,
+            strict: false,
+        };
+        assert_eq!(
+            diff_extraction_graph(&current, &proposed),
+            ExtractionGraphDiff::default()
+        );
+    }
+
+    #[test]
+    fn test_changed_graph() {
+        let current = graph(vec![
+            policy("chunk", "chunker", ""),
+            policy("embed", "minilm", "chunk"),
+            policy("summarize", "summarizer", ""),
+        ]);
+        let proposed = ExtractionGraphRequest {
+            name: "graph".to_string(),
+            extraction_policies: vec![
+                policy("chunk", "chunker-v2", ""),
+                policy("embed", "minilm", "chunk"),
+                policy("entities", "ner", "embed"),
+                policy("caption", "captioner", ""),
+            ],
+            max_active_content: None,
+            latency_tolerant: false,
+            zone_placement: Default::default(),
+            change_summary: None,
+            author: None,
+            strict: false,
+        };
+        let diff = diff_extraction_graph(&current, &proposed);
+        assert_eq!(diff.added_policies, vec!["entities", "caption"]);
+        assert_eq!(diff.removed_policies, vec!["summarize"]);
+        assert_eq!(
+            diff.changed_policies,
+            vec![ExtractionPolicyChange {
+                name: "chunk".to_string(),
+                changed_fields: vec!["extractor".to_string()],
+            }]
+        );
+        assert_eq!(
+            diff.added_edges,
+            vec![
+                ExtractionGraphEdge {
+                    from: "".to_string(),
+                    to: "caption".to_string(),
+                },
+                ExtractionGraphEdge {
+                    from: "embed".to_string(),
+                    to: "entities".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            diff.removed_edges,
+            vec![ExtractionGraphEdge {
+                from: "".to_string(),
+                to: "summarize".to_string(),
+            }]
+        );
+        assert_eq!(
+            diff.affected_policies,
+            vec!["caption", "chunk", "embed", "entities"]
+        );
+    }
+}
+
+/// Metadata larger than this, serialized, isn't checked against the output
+/// schema of its extraction policy
+const MAX_VALIDATED_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// Checks metadata extracted by a policy against the policy's output schema.
+/// Every mismatch is described with the path into the metadata where it was
+/// found.
+pub fn output_schema_violations(
+    schema: &serde_json::Value,
+    output: &serde_json::Value,
+) -> Result<Vec<String>> {
+    if serde_json::to_vec(output)?.len() > MAX_VALIDATED_OUTPUT_BYTES {
+        return Ok(Vec::new());
+    }
+    let schema = JSONSchema::compile(schema)
+        .map_err(|e| anyhow!("unable to compile output schema: {}", e))?;
+    let violations = match schema.validate(output) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors
+            .map(|e| {
+                let path = e.instance_path.to_string();
+                let path = if path.is_empty() { "/" } else { &path };
+                format!("at {}: {}", path, e)
+            })
+            .collect(),
+    };
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod test_output_schema {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_output_schema_violations() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "title": {"type": "string"},
+                "pages": {"type": "integer"}
+            },
+            "required": ["title"]
+        });
+        assert!(
+            output_schema_violations(&schema, &json!({"title": "a", "pages": 3}))
+                .unwrap()
+                .is_empty()
+        );
+
+        let violations = output_schema_violations(&schema, &json!({"pages": "three"})).unwrap();
+        assert_eq!(violations.len(), 2);
+        assert!(violations.iter().any(|v| v.starts_with("at /pages:")));
+        assert!(violations
+            .iter()
+            .any(|v| v.starts_with("at /:") && v.contains("title")));
+
+        assert!(output_schema_violations(&json!({"type": "nope"}), &json!({})).is_err());
+    }
+}
+
+/// Encodes every item of a stream as a line of JSON, for responses that are
+/// written as they are read instead of being built in memory. An error is the
+/// last line of the stream.
+pub fn json_lines<T: Serialize>(
+    items: impl Stream<Item = Result<T, StreamError>>,
+) -> impl Stream<Item = Result<Bytes>> {
+    //  The response already started, so its status can't report the error
+    items.scan(false, |failed, item| {
+        if *failed {
+            return futures::future::ready(None);
+        }
+        let line = match item {
+            Ok(item) => serde_json::to_vec(&item),
+            Err(e) => {
+                *failed = true;
+                serde_json::to_vec(&e)
+            }
+        };
+        futures::future::ready(Some(line.map_err(Into::into).map(|mut line| {
+            line.push(b'\n');
+            Bytes::from(line)
+        })))
+    })
+}
+
+#[cfg(test)]
+mod test_json_lines {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_json_lines() {
+        let items = futures::stream::iter(vec![
+            Ok(serde_json::json!({"id": 1})),
+            Ok(serde_json::json!({"id": 2})),
+            Err(StreamError {
+                error: "read failed".to_string(),
+                cursor: Some("2".to_string()),
+            }),
+            Ok(serde_json::json!({"id": 3})),
+        ]);
+        let lines: Vec<Bytes> = json_lines(items).map(|line| line.unwrap()).collect().await;
+        assert_eq!(
+            lines,
+            vec![
+                Bytes::from("{\"id\":1}\n"),
+                Bytes::from("{\"id\":2}\n"),
+                Bytes::from("{\"error\":\"read failed\",\"cursor\":\"2\"}\n"),
+            ]
+        );
+    }
+}
@@ -4,7 +4,7 @@ use anyhow::Result;
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::{future::BoxFuture, ready, stream::BoxStream, StreamExt};
-use object_store::{local::LocalFileSystem, ObjectStore};
+use object_store::{local::LocalFileSystem, GetOptions, ObjectStore};
 use tokio::{
     fs::File,
     io::{AsyncWrite, AsyncWriteExt},
@@ -43,6 +43,10 @@ impl DeleteOnFailureFile {
             Some(parent) => parent.join("tmp").join(file_name),
             None => PathBuf::from("tmp").join(file_name),
         };
+        //  Keys with slashes are stored in nested directories
+        if let Some(tmp_dir) = tmp_path.parent() {
+            tokio::fs::create_dir_all(tmp_dir).await?;
+        }
         let file = File::create(&tmp_path).await?;
         let rename_future = Box::pin(tokio::fs::rename(tmp_path.clone(), path));
 
@@ -188,7 +192,44 @@ impl BlobStorageReader for DiskFileReader {
         let file_path = file_path.trim_start_matches("file://").to_string();
         tokio::spawn(async move {
             let client = LocalFileSystem::new();
-            let mut stream = client.get(&file_path.into()).await.unwrap().into_stream();
+            let mut stream = match client.get(&file_path.into()).await {
+                Ok(result) => result.into_stream(),
+                Err(e) => {
+                    let _ = tx.send(Err(anyhow::Error::new(e).context("Error reading file")));
+                    return;
+                }
+            };
+            while let Some(chunk) = stream.next().await {
+                if let Ok(chunk) = chunk {
+                    let _ = tx.send(Ok(chunk));
+                } else {
+                    let _ = tx.send(Err(anyhow::anyhow!(
+                        "Error reading file: {:?}",
+                        chunk.err()
+                    )));
+                    break;
+                }
+            }
+        });
+        Box::pin(UnboundedReceiverStream::new(rx))
+    }
+
+    fn get_range(&self, file_path: &str, start: u64, end: u64) -> BoxStream<Result<Bytes>> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let file_path = file_path.trim_start_matches("file://").to_string();
+        tokio::spawn(async move {
+            let client = LocalFileSystem::new();
+            let options = GetOptions {
+                range: Some(start as usize..end as usize + 1),
+                ..Default::default()
+            };
+            let mut stream = match client.get_opts(&file_path.into(), options).await {
+                Ok(result) => result.into_stream(),
+                Err(e) => {
+                    let _ = tx.send(Err(anyhow::Error::new(e).context("Error reading file")));
+                    return;
+                }
+            };
             while let Some(chunk) = stream.next().await {
                 if let Ok(chunk) = chunk {
                     let _ = tx.send(Ok(chunk));
@@ -215,6 +256,17 @@ mod tests {
 
     use super::*;
 
+    #[tokio::test]
+    async fn test_read_missing_file() {
+        let dir = tempdir().unwrap();
+        let path = format!("file://{}/missing", dir.path().display());
+        let reader = DiskFileReader::new();
+        for mut stream in [reader.get(&path), reader.get_range(&path, 0, 0)] {
+            let err = stream.next().await.unwrap().unwrap_err();
+            assert!(crate::blob_storage::is_not_found(&err), "{:?}", err);
+        }
+    }
+
     #[tokio::test]
     async fn test_put() -> Result<(), anyhow::Error> {
         let dir = tempdir()?;
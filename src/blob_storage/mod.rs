@@ -60,6 +60,51 @@ type BlobStorageReaderTS = Arc<dyn BlobStorageReader + Sync + Send>;
 
 pub trait BlobStorageReader {
     fn get(&self, key: &str) -> BoxStream<Result<Bytes>>;
+
+    /// Streams the bytes `start..=end` of an object. Readers that can't fetch
+    /// a range from the backing store skip over the rest of the object.
+    fn get_range(&self, key: &str, start: u64, end: u64) -> BoxStream<Result<Bytes>> {
+        slice_stream(self.get(key), start, end)
+    }
+}
+
+/// Narrows a stream of an object's bytes to the bytes `start..=end`
+pub fn slice_stream(
+    mut stream: BoxStream<'_, Result<Bytes>>,
+    start: u64,
+    end: u64,
+) -> BoxStream<'_, Result<Bytes>> {
+    Box::pin(async_stream::stream! {
+        let mut offset: u64 = 0;
+        while let Some(chunk) = stream.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    yield Err(e);
+                    break;
+                }
+            };
+            let chunk_start = offset;
+            offset += chunk.len() as u64;
+            if offset <= start {
+                continue;
+            }
+            let from = start.saturating_sub(chunk_start) as usize;
+            let to = (end + 1 - chunk_start).min(chunk.len() as u64) as usize;
+            yield Ok(chunk.slice(from..to));
+            if offset > end {
+                break;
+            }
+        }
+    })
+}
+
+/// Whether a read failed because the object doesn't exist
+pub fn is_not_found(error: &anyhow::Error) -> bool {
+    matches!(
+        error.downcast_ref::<object_store::Error>(),
+        Some(object_store::Error::NotFound { .. })
+    )
 }
 
 #[derive(Clone)]
@@ -106,6 +151,30 @@ impl BlobStorage {
         ))
     }
 
+    /// Checks that the configured object store can be reached. Disk storage is
+    /// always reachable.
+    pub async fn check_connectivity(&self) -> Result<()> {
+        match self.config.s3.as_ref() {
+            Some(s3) => self.s3_storage(s3)?.check("indexify-preflight").await,
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the url under which an object written with `key` is stored
+    pub fn url_for_key(&self, key: &str) -> String {
+        if let Some(s3) = self.config.s3.as_ref() {
+            format!("s3://{}/{}", s3.bucket, key)
+        } else {
+            let path = self
+                .config
+                .disk
+                .as_ref()
+                .map(|disk| disk.path.as_str())
+                .unwrap_or("blobs");
+            format!("file://{}/{}", path, key)
+        }
+    }
+
     pub async fn writer(&self, _namespace: &str, key: &str) -> Result<StoragePartWriter> {
         if let Some(s3) = self.config.s3.as_ref() {
             self.s3_storage(s3)?.writer(key).await
@@ -246,6 +315,27 @@ mod tests {
 
     use super::*;
 
+    #[tokio::test]
+    async fn test_slice_stream() {
+        let chunks = || {
+            let data = vec![Bytes::from("0123"), Bytes::from("4567"), Bytes::from("89")];
+            Box::pin(stream::iter(data.into_iter().map(Ok))) as BoxStream<Result<Bytes>>
+        };
+        for (start, end, expected) in [
+            (0, 9, "0123456789"),
+            (2, 5, "2345"),
+            (4, 7, "4567"),
+            (9, 9, "9"),
+            (3, 20, "3456789"),
+        ] {
+            let bytes: Vec<Bytes> = slice_stream(chunks(), start, end)
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(bytes.concat(), expected.as_bytes(), "{}-{}", start, end);
+        }
+    }
+
     // This test requires localstack to be running.
     // Configure with key: test and secret: test
     // Make bucket 'test-bucket'
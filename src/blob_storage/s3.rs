@@ -6,6 +6,7 @@ use bytes::Bytes;
 use futures::{stream::BoxStream, Stream, StreamExt};
 use object_store::{
     aws::{AmazonS3, AmazonS3Builder},
+    GetOptions,
     ObjectStore,
 };
 use tokio::{io::AsyncWriteExt, sync::mpsc};
@@ -28,6 +29,21 @@ impl S3Storage {
     }
 }
 
+impl S3Storage {
+    /// Verifies that the bucket can be reached with the configured
+    /// credentials. A missing probe object means the request went through.
+    pub async fn check(&self, probe_key: &str) -> Result<()> {
+        match self.client.head(&probe_key.into()).await {
+            Ok(_) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(anyhow!(
+                "unable to reach s3 bucket {}: {}. Check the bucket, region and AWS credentials",
+                self.bucket,
+                e
+            )),
+        }
+    }
+}
+
 #[async_trait]
 impl BlobStorageWriter for S3Storage {
     async fn put(
@@ -96,7 +112,13 @@ impl BlobStorageReader for S3FileReader {
         let (tx, rx) = mpsc::unbounded_channel();
         let key = self.key.clone();
         tokio::spawn(async move {
-            let mut stream = client_clone.get(&key.into()).await.unwrap().into_stream();
+            let mut stream = match client_clone.get(&key.into()).await {
+                Ok(result) => result.into_stream(),
+                Err(e) => {
+                    let _ = tx.send(Err(anyhow::Error::new(e).context("unable to read object")));
+                    return;
+                }
+            };
             while let Some(chunk) = stream.next().await {
                 if let Ok(chunk) = chunk {
                     let _ = tx.send(Ok(chunk));
@@ -105,6 +127,31 @@ impl BlobStorageReader for S3FileReader {
         });
         Box::pin(UnboundedReceiverStream::new(rx))
     }
+
+    fn get_range(&self, _key: &str, start: u64, end: u64) -> BoxStream<Result<Bytes>> {
+        let client_clone = self.client.clone();
+        let (tx, rx) = mpsc::unbounded_channel();
+        let key = self.key.clone();
+        tokio::spawn(async move {
+            let options = GetOptions {
+                range: Some(start as usize..end as usize + 1),
+                ..Default::default()
+            };
+            let mut stream = match client_clone.get_opts(&key.into(), options).await {
+                Ok(result) => result.into_stream(),
+                Err(e) => {
+                    let _ = tx.send(Err(
+                        anyhow::Error::new(e).context("unable to read range of object")
+                    ));
+                    return;
+                }
+            };
+            while let Some(chunk) = stream.next().await {
+                let _ = tx.send(chunk.map_err(|e| anyhow!("unable to read chunk: {}", e)));
+            }
+        });
+        Box::pin(UnboundedReceiverStream::new(rx))
+    }
 }
 
 #[cfg(test)]
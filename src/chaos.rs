@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::server_config::ChaosConfig;
+
+/// Injects faults at the rates of the chaos config, so retries, reallocation
+/// and recovery can be exercised before an incident does. Every fault is a
+/// no-op when chaos is not configured.
+#[derive(Debug, Default)]
+pub struct ChaosInjector {
+    config: Option<ChaosConfig>,
+}
+
+impl ChaosInjector {
+    pub fn new(config: Option<ChaosConfig>) -> Self {
+        Self { config }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.config.is_some()
+    }
+
+    fn roll(rate: f64) -> bool {
+        rate > 0.0 && rand::thread_rng().gen_bool(rate.min(1.0))
+    }
+
+    /// Delay to hold a state write back by, if it is delayed
+    pub fn state_write_delay(&self) -> Option<Duration> {
+        let config = self.config.as_ref()?;
+        Self::roll(config.state_write_delay_rate)
+            .then(|| Duration::from_millis(config.state_write_delay_ms))
+    }
+
+    pub fn drop_heartbeat(&self) -> bool {
+        self.config
+            .as_ref()
+            .is_some_and(|config| Self::roll(config.heartbeat_drop_rate))
+    }
+
+    /// Whether an in-flight task is killed when the tasks are checked
+    pub fn kill_task(&self) -> bool {
+        self.config
+            .as_ref()
+            .is_some_and(|config| Self::roll(config.task_kill_rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rates() {
+        let disabled = ChaosInjector::default();
+        assert!(disabled.state_write_delay().is_none());
+        assert!(!disabled.drop_heartbeat());
+        assert!(!disabled.kill_task());
+
+        let always = ChaosInjector::new(Some(ChaosConfig {
+            state_write_delay_rate: 1.0,
+            state_write_delay_ms: 20,
+            heartbeat_drop_rate: 1.0,
+            task_kill_rate: 2.0,
+            ..Default::default()
+        }));
+        assert_eq!(always.state_write_delay(), Some(Duration::from_millis(20)));
+        assert!(always.drop_heartbeat());
+        assert!(always.kill_task());
+
+        let never = ChaosInjector::new(Some(ChaosConfig::default()));
+        assert!(never.state_write_delay().is_none());
+        assert!(!never.drop_heartbeat());
+        assert!(!never.kill_task());
+    }
+}
@@ -0,0 +1,650 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use clap::Args as ClapArgs;
+use indexify_internal_api as internal_api;
+use internal_api::{
+    ContentMetadata,
+    ContentMetadataId,
+    ExtractionGraph,
+    ExtractionPolicy,
+    ExtractionPolicyContentSource,
+    ExtractorDescription,
+    TaskOutcome,
+};
+use rocksdb::{
+    Cache,
+    ColumnFamily,
+    ColumnFamilyDescriptor,
+    IteratorMode,
+    OptimisticTransactionDB,
+    Options,
+    WriteBatchWithTransaction,
+};
+use serde::Serialize;
+use serde_json::json;
+use tokio::time::sleep;
+
+use super::GlobalArgs;
+use crate::{
+    coordinator::Coordinator,
+    coordinator_client::CoordinatorClient,
+    garbage_collector::GarbageCollector,
+    prelude::*,
+    server_config::ServerConfig,
+    state::{
+        store::{tuning, StateMachineColumns},
+        App,
+    },
+    utils::timestamp_secs,
+};
+
+const BENCH_NAMESPACE: &str = "bench";
+const BENCH_EXTRACTOR: &str = "BenchExtractor";
+
+/// How long to wait for outstanding tasks once load generation has stopped
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Content and tasks written to the store per batch by the store benchmark
+const STORE_BATCH_SIZE: u64 = 10_000;
+
+/// Reads of each kind timed by the store benchmark
+const STORE_READS: u64 = 10_000;
+
+/// Step between the rows the store benchmark reads, coprime with most row
+/// counts so the reads cover the whole store
+const SAMPLE_STRIDE: u64 = 7_919;
+
+#[derive(Debug, ClapArgs)]
+pub struct Args {
+    /// url of a running server to load, e.g. http://localhost:8900. Without
+    /// it the benchmark runs against an in-process state store
+    #[arg(long)]
+    server_addr: Option<String>,
+
+    /// directory of the in-process state store, defaults to a fresh
+    /// temporary directory
+    #[arg(long)]
+    state_store_path: Option<String>,
+
+    /// extractor used by the generated graphs when loading a running server
+    #[arg(long, default_value = "tensorlake/minilm-l6")]
+    extractor: String,
+
+    /// content created per second
+    #[arg(long, default_value_t = 50, value_parser = clap::value_parser!(u64).range(1..))]
+    rate: u64,
+
+    /// seconds to generate load for
+    #[arg(long, default_value_t = 30)]
+    duration: u64,
+
+    /// number of extraction graphs the content is spread over
+    #[arg(long, default_value_t = 2)]
+    graphs: usize,
+
+    /// extraction policies per graph, each one creates a task per content
+    #[arg(long, default_value_t = 2)]
+    policies: usize,
+
+    /// executors registered with the in-process state store
+    #[arg(long, default_value_t = 4)]
+    executors: usize,
+
+    /// instead of generating load, fill a state store with this many content
+    /// and as many tasks, and time listing and reading them with the default
+    /// options of RocksDB and with the tuned options of the state store
+    #[arg(long, conflicts_with = "server_addr")]
+    store_rows: Option<u64>,
+}
+
+struct Report {
+    operation: String,
+    elapsed: Duration,
+    latencies: Vec<Duration>,
+}
+
+/// The report in numbers, for scripts
+#[derive(Serialize)]
+struct ReportSummary {
+    operation: String,
+    count: usize,
+    elapsed_secs: f64,
+    per_sec: f64,
+    p50_ms: f64,
+    p90_ms: f64,
+    p99_ms: f64,
+    max_ms: f64,
+}
+
+impl Report {
+    fn summary(&self) -> ReportSummary {
+        let mut latencies = self.latencies.clone();
+        latencies.sort();
+        let ms = |latency: Duration| latency.as_secs_f64() * 1000.0;
+        ReportSummary {
+            operation: self.operation.clone(),
+            count: latencies.len(),
+            elapsed_secs: self.elapsed.as_secs_f64(),
+            per_sec: latencies.len() as f64 / self.elapsed.as_secs_f64(),
+            p50_ms: ms(percentile(&latencies, 50.0)),
+            p90_ms: ms(percentile(&latencies, 90.0)),
+            p99_ms: ms(percentile(&latencies, 99.0)),
+            max_ms: ms(latencies.last().copied().unwrap_or_default()),
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut latencies = self.latencies.clone();
+        latencies.sort();
+        writeln!(
+            f,
+            "{} {} in {:.2?} ({:.1}/s)",
+            latencies.len(),
+            self.operation,
+            self.elapsed,
+            latencies.len() as f64 / self.elapsed.as_secs_f64()
+        )?;
+        write!(
+            f,
+            "latency p50: {:.2?} p90: {:.2?} p99: {:.2?} max: {:.2?}",
+            percentile(&latencies, 50.0),
+            percentile(&latencies, 90.0),
+            percentile(&latencies, 99.0),
+            latencies.last().copied().unwrap_or_default()
+        )
+    }
+}
+
+/// Nearest-rank percentile of sorted latencies
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+impl Args {
+    pub async fn run(self, global_args: GlobalArgs) {
+        if let Some(rows) = self.store_rows {
+            match self.run_store_benchmark(rows).await {
+                Ok(reports) => {
+                    let summaries: Vec<ReportSummary> =
+                        reports.iter().map(Report::summary).collect();
+                    global_args.output.print(&summaries, |_| {
+                        reports
+                            .iter()
+                            .map(|report| report.to_string())
+                            .collect::<Vec<_>>()
+                            .join("\n\n")
+                    })
+                }
+                Err(e) => error!("benchmark failed: {:?}", e),
+            }
+            return;
+        }
+        let report = match &self.server_addr {
+            Some(server_addr) => self.run_against_server(server_addr).await,
+            None => self.run_in_process().await,
+        };
+        match report {
+            Ok(report) => global_args
+                .output
+                .print(&report.summary(), |_| report.to_string()),
+            Err(e) => error!("benchmark failed: {:?}", e),
+        }
+    }
+
+    fn graph_names(&self, run_id: &str) -> Vec<String> {
+        (0..self.graphs)
+            .map(|i| format!("bench-{}-{}", run_id, i))
+            .collect()
+    }
+
+    fn policy_names(&self) -> Vec<String> {
+        (0..self.policies)
+            .map(|i| format!("policy-{}", i))
+            .collect()
+    }
+
+    /// Creates content at the configured rate until the duration has passed,
+    /// returning the number of content created
+    async fn generate<F, Fut>(&self, graph_names: &[String], mut create: F) -> Result<usize>
+    where
+        F: FnMut(usize, &str) -> Fut,
+        Fut: std::future::Future<Output = Result<()>>,
+    {
+        let mut interval = tokio::time::interval(Duration::from_secs_f64(1.0 / self.rate as f64));
+        let start = Instant::now();
+        let mut count = 0;
+        while start.elapsed() < Duration::from_secs(self.duration) {
+            interval.tick().await;
+            create(count, &graph_names[count % graph_names.len()]).await?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Runs the scheduler against a local state store with synthetic
+    /// executors that finish every task as soon as it is assigned. Reports
+    /// the time from content creation until its tasks were assigned.
+    async fn run_in_process(&self) -> Result<Report> {
+        let run_id = nanoid::nanoid!(8);
+        let mut config = ServerConfig::default();
+        config.state_store.path = Some(self.state_store_path.clone().unwrap_or_else(|| {
+            std::env::temp_dir()
+                .join(format!("indexify-bench-{}", run_id))
+                .to_string_lossy()
+                .to_string()
+        }));
+        let config = Arc::new(config);
+        let garbage_collector = GarbageCollector::new();
+        let shared_state = App::new(
+            config.clone(),
+            None,
+            garbage_collector.clone(),
+            &config.coordinator_addr,
+            Arc::new(crate::metrics::init_provider()),
+        )
+        .await?;
+        shared_state.initialize_raft().await?;
+        let coordinator = Coordinator::new(
+            shared_state,
+            CoordinatorClient::new(config.clone()),
+            garbage_collector,
+            None,
+            config.scheduler.clone(),
+        );
+
+        coordinator.create_namespace(BENCH_NAMESPACE).await?;
+        let extractor = ExtractorDescription {
+            name: BENCH_EXTRACTOR.to_string(),
+            description: "synthetic extractor used by indexify bench".to_string(),
+            input_params: json!({}),
+            outputs: HashMap::new(),
+            input_mime_types: vec!["*/*".to_string()],
+        };
+        let executor_ids: Vec<String> = (0..self.executors)
+            .map(|i| format!("bench-executor-{}", i))
+            .collect();
+        for (i, executor_id) in executor_ids.iter().enumerate() {
+            coordinator
+                .register_executor(
+                    &format!("localhost:{}", 9900 + i),
+                    executor_id,
+                    vec![extractor.clone()],
+                    Default::default(),
+                )
+                .await?;
+        }
+        coordinator.run_scheduler().await?;
+
+        let graph_names = self.graph_names(&run_id);
+        for graph_name in &graph_names {
+            let extraction_policies = self
+                .policy_names()
+                .into_iter()
+                .map(|name| ExtractionPolicy {
+                    id: ExtractionPolicy::create_id(graph_name, &name, BENCH_NAMESPACE),
+                    graph_name: graph_name.clone(),
+                    name,
+                    namespace: BENCH_NAMESPACE.to_string(),
+                    extractor: BENCH_EXTRACTOR.to_string(),
+                    input_params: json!({}),
+                    content_source: ExtractionPolicyContentSource::Ingestion,
+                    ..Default::default()
+                })
+                .collect();
+            coordinator
+                .create_extraction_graph(ExtractionGraph {
+                    id: ExtractionGraph::create_id(graph_name, BENCH_NAMESPACE),
+                    namespace: BENCH_NAMESPACE.to_string(),
+                    name: graph_name.clone(),
+                    extraction_policies,
+                    max_active_content: None,
+                    latency_tolerant: false,
+                    zone_placement: Default::default(),
+                })
+                .await?;
+        }
+
+        // content id -> time the content was created
+        let created: Arc<Mutex<HashMap<String, Instant>>> = Default::default();
+        let finalized = Arc::new(AtomicUsize::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let scheduler = {
+            let coordinator = coordinator.clone();
+            let stop = stop.clone();
+            tokio::spawn(async move {
+                while !stop.load(Ordering::Relaxed) {
+                    if let Err(e) = coordinator.run_scheduler().await {
+                        error!("scheduler run failed: {:?}", e);
+                    }
+                    sleep(Duration::from_millis(1)).await;
+                }
+            })
+        };
+        let finalizer = {
+            let coordinator = coordinator.clone();
+            let created = created.clone();
+            let finalized = finalized.clone();
+            let stop = stop.clone();
+            tokio::spawn(async move {
+                let mut latencies = Vec::new();
+                while !stop.load(Ordering::Relaxed) {
+                    for executor_id in &executor_ids {
+                        let tasks = coordinator
+                            .shared_state
+                            .tasks_for_executor(executor_id, None)
+                            .await?;
+                        for task in tasks {
+                            let created_at = created
+                                .lock()
+                                .unwrap()
+                                .get(&task.content_metadata.id.id)
+                                .copied();
+                            if let Some(created_at) = created_at {
+                                latencies.push(created_at.elapsed());
+                            }
+                            coordinator
+                                .update_task(
+                                    &task.id,
+                                    executor_id,
+                                    TaskOutcome::Success,
+                                    None,
+                                    None,
+                                )
+                                .await?;
+                            finalized.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+                    sleep(Duration::from_millis(5)).await;
+                }
+                Ok::<_, anyhow::Error>(latencies)
+            })
+        };
+
+        let start = Instant::now();
+        let content_count = self
+            .generate(&graph_names, |i, graph_name| {
+                let content = ContentMetadata {
+                    id: ContentMetadataId::new(&format!("bench-{}-{}", run_id, i)),
+                    namespace: BENCH_NAMESPACE.to_string(),
+                    name: format!("bench-{}", i),
+                    content_type: "text/plain".to_string(),
+                    created_at: timestamp_secs() as i64,
+                    hash: format!("bench-{}-{}", run_id, i),
+                    extraction_graph_names: vec![graph_name.to_string()],
+                    ..Default::default()
+                };
+                created
+                    .lock()
+                    .unwrap()
+                    .insert(content.id.id.clone(), Instant::now());
+                let coordinator = coordinator.clone();
+                async move {
+                    coordinator.create_content_metadata(vec![content]).await?;
+                    Ok(())
+                }
+            })
+            .await?;
+
+        let expected_tasks = content_count * self.policies;
+        let deadline = Instant::now() + DRAIN_TIMEOUT;
+        while finalized.load(Ordering::Relaxed) < expected_tasks && Instant::now() < deadline {
+            sleep(Duration::from_millis(10)).await;
+        }
+        let elapsed = start.elapsed();
+        stop.store(true, Ordering::Relaxed);
+        scheduler.await?;
+        let latencies = finalizer.await??;
+        if latencies.len() < expected_tasks {
+            warn!(
+                "{} of {} tasks were not assigned within {:?} of the load ending",
+                expected_tasks - latencies.len(),
+                expected_tasks,
+                DRAIN_TIMEOUT
+            );
+        }
+        info!("created {} content", content_count);
+        Ok(Report {
+            operation: "tasks assigned".to_string(),
+            elapsed,
+            latencies,
+        })
+    }
+
+    /// Runs the store benchmark with the default options of RocksDB and with
+    /// the tuned options, each on a fresh store
+    async fn run_store_benchmark(&self, rows: u64) -> Result<Vec<Report>> {
+        let run_id = nanoid::nanoid!(8);
+        let base = match &self.state_store_path {
+            Some(path) => PathBuf::from(path),
+            None => std::env::temp_dir().join(format!("indexify-bench-store-{}", run_id)),
+        };
+        let mut reports = Vec::new();
+        for tuned in [false, true] {
+            let path = base.join(if tuned { "tuned" } else { "default" });
+            reports.extend(
+                tokio::task::spawn_blocking(move || bench_store(&path, rows, tuned)).await??,
+            );
+        }
+        Ok(reports)
+    }
+
+    /// Ingests text into a running server at the configured rate. Requests
+    /// are sent without waiting on earlier ones, and the report covers the
+    /// ingestion latency seen by the client. Executors of the server finish
+    /// the generated tasks.
+    async fn run_against_server(&self, server_addr: &str) -> Result<Report> {
+        let run_id = nanoid::nanoid!(8);
+        let server_addr = server_addr.trim_end_matches('/');
+        let client = reqwest::Client::new();
+
+        // The namespace is left over from earlier runs when this fails
+        let _ = client
+            .post(format!("{}/namespaces", server_addr))
+            .json(&json!({"name": BENCH_NAMESPACE, "extraction_graphs": [], "labels": {}}))
+            .send()
+            .await?;
+        let graph_names = self.graph_names(&run_id);
+        for graph_name in &graph_names {
+            let extraction_policies: Vec<_> = self
+                .policy_names()
+                .into_iter()
+                .map(|name| json!({"extractor": self.extractor, "name": name}))
+                .collect();
+            client
+                .post(format!(
+                    "{}/namespaces/{}/extraction_graphs",
+                    server_addr, BENCH_NAMESPACE
+                ))
+                .json(&json!({"name": graph_name, "extraction_policies": extraction_policies}))
+                .send()
+                .await?
+                .error_for_status()?;
+        }
+
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let start = Instant::now();
+        self.generate(&graph_names, |i, graph_name| {
+            let request = client
+                .post(format!(
+                    "{}/namespaces/{}/add_texts",
+                    server_addr, BENCH_NAMESPACE
+                ))
+                .json(&json!({
+                    "documents": [{"text": format!("bench content {} of run {}", i, run_id)}],
+                    "extraction_graph_names": [graph_name],
+                }));
+            requests.lock().unwrap().push(tokio::spawn(async move {
+                let sent_at = Instant::now();
+                request.send().await?.error_for_status()?;
+                Ok::<_, anyhow::Error>(sent_at.elapsed())
+            }));
+            async { Ok(()) }
+        })
+        .await?;
+
+        let requests = std::mem::take(&mut *requests.lock().unwrap());
+        let mut latencies = Vec::with_capacity(requests.len());
+        for request in requests {
+            match request.await? {
+                Ok(latency) => latencies.push(latency),
+                Err(e) => warn!("ingestion request failed: {:?}", e),
+            }
+        }
+        Ok(Report {
+            operation: "content ingested".to_string(),
+            elapsed: start.elapsed(),
+            latencies,
+        })
+    }
+}
+
+/// Fills a state store with `rows` content and tasks and times reading them.
+/// With `tuned` the column families are opened with the options of the state
+/// store, otherwise with the defaults of RocksDB.
+fn bench_store(path: &Path, rows: u64, tuned: bool) -> Result<Vec<Report>> {
+    let block_cache = Cache::new_lru_cache(tuning::BLOCK_CACHE_SIZE);
+    let columns = [
+        StateMachineColumns::ContentTable,
+        StateMachineColumns::Tasks,
+    ];
+    let descriptors = columns.iter().map(|column| {
+        let options = if tuned {
+            column.options(&block_cache)
+        } else {
+            Options::default()
+        };
+        ColumnFamilyDescriptor::new(column.to_string(), options)
+    });
+    let mut db_opts = Options::default();
+    db_opts.create_missing_column_families(true);
+    db_opts.create_if_missing(true);
+    let db = Arc::new(OptimisticTransactionDB::open_cf_descriptors(
+        &db_opts,
+        path,
+        descriptors,
+    )?);
+    let content_cf = StateMachineColumns::ContentTable.cf(&db);
+    let tasks_cf = StateMachineColumns::Tasks.cf(&db);
+
+    let fill_start = Instant::now();
+    for batch_start in (0..rows).step_by(STORE_BATCH_SIZE as usize) {
+        let mut batch = WriteBatchWithTransaction::<true>::default();
+        for i in batch_start..(batch_start + STORE_BATCH_SIZE).min(rows) {
+            let content = ContentMetadata {
+                id: ContentMetadataId::new(&bench_key("content", i)),
+                namespace: BENCH_NAMESPACE.to_string(),
+                name: format!("bench-{}", i),
+                content_type: "text/plain".to_string(),
+                storage_url: format!("file:///tmp/indexify-bench/{}", i),
+                ..Default::default()
+            };
+            let task = internal_api::Task {
+                id: bench_key("task", i),
+                extractor: BENCH_EXTRACTOR.to_string(),
+                namespace: BENCH_NAMESPACE.to_string(),
+                content_metadata: content.clone(),
+                ..Default::default()
+            };
+            batch.put_cf(content_cf, content.id_key(), serde_json::to_vec(&content)?);
+            batch.put_cf(tasks_cf, &task.id, serde_json::to_vec(&task)?);
+        }
+        db.write(batch)?;
+    }
+    db.flush_cf(content_cf)?;
+    db.flush_cf(tasks_cf)?;
+    info!("filled {} in {:.2?}", path.display(), fill_start.elapsed());
+
+    let options = if tuned { "tuned" } else { "default" };
+    let mut reports = Vec::new();
+    let mut time_reads = |operation: &str, cf: &ColumnFamily, keys: Vec<String>| -> Result<()> {
+        let start = Instant::now();
+        let mut latencies = Vec::with_capacity(keys.len());
+        for key in keys {
+            let read_at = Instant::now();
+            db.get_cf(cf, key)?;
+            latencies.push(read_at.elapsed());
+        }
+        reports.push(Report {
+            operation: format!("{} ({} options)", operation, options),
+            elapsed: start.elapsed(),
+            latencies,
+        });
+        Ok(())
+    };
+    //  Keys spread over the store like the random ids of content and tasks
+    let sample = |prefix: &str, offset: u64| -> Vec<String> {
+        (0..STORE_READS)
+            .map(|i| {
+                bench_key(
+                    prefix,
+                    (i.wrapping_mul(SAMPLE_STRIDE) + offset) % rows.max(1),
+                )
+            })
+            .collect()
+    };
+    time_reads("content reads", content_cf, sample("content", 0))?;
+    time_reads("missing content reads", content_cf, sample("missing", 0))?;
+    time_reads("task reads", tasks_cf, sample("task", 1))?;
+
+    let start = Instant::now();
+    let mut listed = 0;
+    for row in db.iterator_cf_opt(content_cf, tuning::scan_read_options(), IteratorMode::Start) {
+        let (_, value) = row?;
+        let content: ContentMetadata = serde_json::from_slice(&value)?;
+        if content.namespace == BENCH_NAMESPACE {
+            listed += 1;
+        }
+    }
+    info!("listed {} content", listed);
+    reports.push(Report {
+        operation: format!("content listings ({} options)", options),
+        elapsed: start.elapsed(),
+        latencies: vec![start.elapsed()],
+    });
+    Ok(reports)
+}
+
+/// Key of the `i`th row of a store benchmark, hashed so consecutive rows
+/// land in different files like generated ids do
+fn bench_key(prefix: &str, i: u64) -> String {
+    let mut hasher = DefaultHasher::new();
+    i.hash(&mut hasher);
+    format!("{}-{:016x}", prefix, hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::percentile;
+
+    #[test]
+    fn test_percentile() {
+        assert_eq!(percentile(&[], 50.0), Duration::ZERO);
+
+        let latencies: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&latencies, 50.0), Duration::from_millis(50));
+        assert_eq!(percentile(&latencies, 99.0), Duration::from_millis(99));
+        assert_eq!(percentile(&latencies, 100.0), Duration::from_millis(100));
+        assert_eq!(percentile(&latencies, 0.0), Duration::from_millis(1));
+    }
+}
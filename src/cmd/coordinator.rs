@@ -9,19 +9,35 @@ use crate::{coordinator_service::CoordinatorServer, prelude::*, server_config::S
 pub struct Args {
     #[arg(short, long)]
     config_path: String,
+
+    /// serve reads only, with the APIs that change state disabled and the
+    /// scheduler stopped
+    #[arg(long)]
+    read_only: bool,
 }
 
 impl Args {
     pub async fn run(self, _: GlobalArgs) {
-        let Self { config_path } = self;
+        let Self {
+            config_path,
+            read_only,
+        } = self;
 
         info!("starting indexify coordinator, version: {}", crate::VERSION);
-        let config = ServerConfig::from_path(&config_path).unwrap_or_else(|e| {
+        let mut config = ServerConfig::from_path(&config_path).unwrap_or_else(|e| {
             panic!(
                 "failed to load config for coordinator: {}: {}",
                 config_path, e
             )
         });
+        config.read_only |= read_only;
+        let config = crate::secrets::resolve(&config)
+            .await
+            .unwrap_or_else(|e| panic!("failed to resolve secrets of the config: {}", e));
+        if let Some(log_file) = &config.logging.file {
+            crate::log_file::init(log_file)
+                .unwrap_or_else(|e| panic!("failed to open log file `{}`: {}", log_file.path, e));
+        }
         let registry = Arc::new(crate::metrics::init_provider());
         let coordinator = CoordinatorServer::new(Arc::new(config), registry)
             .await
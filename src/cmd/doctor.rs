@@ -0,0 +1,528 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use clap::Args as ClapArgs;
+use indexify_internal_api as internal_api;
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::GlobalArgs;
+use crate::{
+    api::{
+        GetContentMetadataResponse,
+        ListExecutorsResponse,
+        ListNamespacesResponse,
+        ListStateChangesResponse,
+    },
+    prelude::*,
+    state::store::{read_only::ReadOnlyState, StateMachineColumns},
+    utils::timestamp_secs,
+};
+
+/// Share of a cap after which a graph or the garbage collector is reported as
+/// close to it
+const NEAR_CAP: f64 = 0.8;
+
+#[derive(Debug, ClapArgs)]
+pub struct Args {
+    /// url of a running server, or of its admin port when admin routes are
+    /// served on a separate port
+    #[arg(long, default_value = "http://localhost:8900")]
+    server_addr: String,
+
+    /// api key or token sent as a bearer token, most checks read admin routes
+    #[arg(long)]
+    api_key: Option<String>,
+
+    /// read the state directory of a stopped server instead of asking a
+    /// running one
+    #[arg(long)]
+    state_dir: Option<PathBuf>,
+
+    /// unprocessed state changes older than this are reported as stale
+    #[arg(long, default_value = "300")]
+    stale_after_secs: u64,
+
+    /// unprocessed state changes after which the scheduler is reported as
+    /// lagging behind
+    #[arg(long, default_value = "100")]
+    max_pending_state_changes: usize,
+}
+
+/// What the checks look at, read from a server or from a state directory
+#[derive(Debug, Default)]
+struct ClusterView {
+    max_tasks_per_executor: Option<u32>,
+    max_pending_gc_tasks: Option<u32>,
+    executors: Vec<ExecutorView>,
+    graphs: Vec<GraphView>,
+    /// Creation times of the state changes the scheduler hasn't processed
+    pending_state_changes: Vec<u64>,
+    /// Only known when reading a state directory, the API doesn't list them
+    unfinished_gc_tasks: Option<usize>,
+}
+
+#[derive(Debug)]
+struct ExecutorView {
+    id: String,
+    extractors: Vec<String>,
+    running_tasks: u64,
+    capacity_weight: u32,
+}
+
+#[derive(Debug, Clone)]
+struct GraphView {
+    namespace: String,
+    name: String,
+    /// Extraction policy names and the extractors they run
+    policies: Vec<(String, String)>,
+    max_active_content: Option<u32>,
+    active_content: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Serialize)]
+struct Finding {
+    severity: Severity,
+    check: &'static str,
+    message: String,
+}
+
+impl Finding {
+    fn new(severity: Severity, check: &'static str, message: String) -> Self {
+        Self {
+            severity,
+            check,
+            message,
+        }
+    }
+}
+
+impl Args {
+    pub async fn run(self, global_args: GlobalArgs) {
+        let view = match &self.state_dir {
+            Some(state_dir) => read_state_dir(state_dir).await,
+            None => self.read_server().await,
+        };
+        let view = match view {
+            Ok(view) => view,
+            Err(e) => {
+                error!("failed to read the cluster: {:?}", e);
+                std::process::exit(1);
+            }
+        };
+        let findings = diagnose(
+            &view,
+            self.stale_after_secs,
+            self.max_pending_state_changes,
+            timestamp_secs(),
+        );
+        global_args
+            .output
+            .print(&findings, |findings| render(findings));
+        if findings
+            .iter()
+            .any(|finding| finding.severity == Severity::Error)
+        {
+            std::process::exit(1);
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, client: &reqwest::Client, path: &str) -> Result<T> {
+        let url = format!("{}{}", self.server_addr.trim_end_matches('/'), path);
+        let request = match &self.api_key {
+            Some(api_key) => client.get(url).bearer_auth(api_key),
+            None => client.get(url),
+        };
+        Ok(request.send().await?.error_for_status()?.json().await?)
+    }
+
+    async fn read_server(&self) -> Result<ClusterView> {
+        let client = reqwest::Client::new();
+        let settings: internal_api::SchedulerSettings =
+            self.get(&client, "/scheduler/settings").await?;
+        let executors: ListExecutorsResponse = self.get(&client, "/executors").await?;
+        let namespaces: ListNamespacesResponse = self.get(&client, "/namespaces").await?;
+        let state_changes: ListStateChangesResponse = self.get(&client, "/state_changes").await?;
+
+        let mut graphs = Vec::new();
+        for namespace in namespaces.namespaces {
+            // Active content is only listed by namespace, look up the graphs
+            // of each content when a graph of the namespace has a cap
+            let mut active_content: HashMap<String, usize> = HashMap::new();
+            if namespace
+                .extraction_graphs
+                .iter()
+                .any(|graph| graph.max_active_content.is_some())
+            {
+                let content_ids: Vec<String> = self
+                    .get(
+                        &client,
+                        &format!("/namespaces/{}/active_content", namespace.name),
+                    )
+                    .await?;
+                for content_id in content_ids {
+                    let content: GetContentMetadataResponse = self
+                        .get(
+                            &client,
+                            &format!("/namespaces/{}/content/{}", namespace.name, content_id),
+                        )
+                        .await?;
+                    for graph in content.content_metadata.extraction_graph_names {
+                        *active_content.entry(graph).or_default() += 1;
+                    }
+                }
+            }
+            for graph in namespace.extraction_graphs {
+                graphs.push(GraphView {
+                    namespace: namespace.name.clone(),
+                    active_content: active_content.get(&graph.name).copied().unwrap_or(0),
+                    policies: graph
+                        .extraction_policies
+                        .into_iter()
+                        .map(|policy| (policy.name, policy.extractor))
+                        .collect(),
+                    max_active_content: graph.max_active_content,
+                    name: graph.name,
+                });
+            }
+        }
+
+        Ok(ClusterView {
+            max_tasks_per_executor: settings.max_tasks_per_executor,
+            max_pending_gc_tasks: settings.max_pending_gc_tasks,
+            executors: executors
+                .executors
+                .into_iter()
+                .map(|executor| ExecutorView {
+                    id: executor.id,
+                    extractors: executor
+                        .extractors
+                        .into_iter()
+                        .map(|extractor| extractor.name)
+                        .collect(),
+                    running_tasks: executor.running_tasks,
+                    capacity_weight: executor.capacity_weight.max(1),
+                })
+                .collect(),
+            graphs,
+            pending_state_changes: state_changes
+                .state_changes
+                .into_iter()
+                .filter(|change| change.processed_at.is_none())
+                .map(|change| change.created_at)
+                .collect(),
+            unfinished_gc_tasks: None,
+        })
+    }
+}
+
+/// Reads the state a stopped server left behind, without writing to the
+/// directory. The state is read as of the last snapshot the server applied,
+/// log entries after it are not replayed.
+async fn read_state_dir(state_dir: &Path) -> Result<ClusterView> {
+    let db_path = state_dir.join("db");
+    if !db_path.exists() {
+        return Err(anyhow!("{} holds no state", state_dir.display()));
+    }
+    let state = ReadOnlyState::open(&db_path)
+        .map_err(|e| anyhow!("unable to open {}: {}", state_dir.display(), e))?;
+
+    let settings = state.scheduler_settings()?;
+    let mut running_tasks: HashMap<String, u64> = HashMap::new();
+    for (executor_id, task_ids) in
+        state.rows::<HashSet<String>>(StateMachineColumns::TaskAssignments)?
+    {
+        *running_tasks.entry(executor_id).or_default() += task_ids.len() as u64;
+    }
+    let executors = state
+        .rows::<internal_api::ExecutorMetadata>(StateMachineColumns::Executors)?
+        .into_iter()
+        .map(|(_, executor)| ExecutorView {
+            running_tasks: running_tasks.get(&executor.id).copied().unwrap_or(0),
+            capacity_weight: executor.capacity_weight(),
+            id: executor.id,
+            extractors: executor
+                .extractors
+                .into_iter()
+                .map(|extractor| extractor.name)
+                .collect(),
+        })
+        .collect();
+
+    //  Root content is active in a graph while tasks of the graph are left
+    let mut active_content: HashMap<String, HashSet<String>> = HashMap::new();
+    for (_, task) in state.rows::<internal_api::Task>(StateMachineColumns::Tasks)? {
+        if task.terminal_state() {
+            continue;
+        }
+        active_content
+            .entry(internal_api::ExtractionGraph::create_id(
+                &task.extraction_graph_name,
+                &task.namespace,
+            ))
+            .or_default()
+            .insert(task.content_metadata.get_root_id().to_string());
+    }
+    let graphs = state
+        .rows::<internal_api::ExtractionGraph>(StateMachineColumns::ExtractionGraphs)?
+        .into_iter()
+        .map(|(_, graph)| GraphView {
+            active_content: active_content.get(&graph.id).map_or(0, |c| c.len()),
+            namespace: graph.namespace,
+            name: graph.name,
+            policies: graph
+                .extraction_policies
+                .into_iter()
+                .map(|policy| (policy.name, policy.extractor))
+                .collect(),
+            max_active_content: graph.max_active_content,
+        })
+        .collect();
+    let pending_state_changes = state
+        .rows::<internal_api::StateChange>(StateMachineColumns::StateChanges)?
+        .into_iter()
+        .filter(|(_, change)| change.processed_at.is_none())
+        .map(|(_, change)| change.created_at)
+        .collect();
+    let unfinished_gc_tasks = state
+        .rows::<internal_api::GarbageCollectionTask>(StateMachineColumns::GarbageCollectionTasks)?
+        .into_iter()
+        .filter(|(_, gc_task)| gc_task.outcome == internal_api::TaskOutcome::Unknown)
+        .count();
+
+    Ok(ClusterView {
+        max_tasks_per_executor: settings.max_tasks_per_executor,
+        max_pending_gc_tasks: settings.max_pending_gc_tasks,
+        executors,
+        graphs,
+        pending_state_changes,
+        unfinished_gc_tasks: Some(unfinished_gc_tasks),
+    })
+}
+
+fn near_cap(count: usize, cap: u32) -> bool {
+    count as f64 >= cap as f64 * NEAR_CAP
+}
+
+fn diagnose(
+    view: &ClusterView,
+    stale_after_secs: u64,
+    max_pending_state_changes: usize,
+    now: u64,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    if let Some(max_tasks) = view.max_tasks_per_executor {
+        for executor in &view.executors {
+            let limit = max_tasks as u64 * executor.capacity_weight as u64;
+            if executor.running_tasks >= limit {
+                findings.push(Finding::new(
+                    Severity::Warning,
+                    "executor_capacity",
+                    format!(
+                        "executor {} runs {} tasks and has no capacity left, its limit is {} tasks",
+                        executor.id, executor.running_tasks, limit
+                    ),
+                ));
+            }
+        }
+    }
+
+    let served: HashSet<&str> = view
+        .executors
+        .iter()
+        .flat_map(|executor| executor.extractors.iter().map(String::as_str))
+        .collect();
+    for graph in &view.graphs {
+        for (policy, extractor) in &graph.policies {
+            if !served.contains(extractor.as_str()) {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    "unserved_extractor",
+                    format!(
+                        "extraction policy {} of graph {} in namespace {} runs {}, which no executor runs",
+                        policy, graph.name, graph.namespace, extractor
+                    ),
+                ));
+            }
+        }
+
+        if let Some(max_active_content) = graph.max_active_content {
+            if near_cap(graph.active_content, max_active_content) {
+                findings.push(Finding::new(
+                    Severity::Warning,
+                    "active_content_cap",
+                    format!(
+                        "graph {} in namespace {} has {} of at most {} active content, new content waits at the cap",
+                        graph.name, graph.namespace, graph.active_content, max_active_content
+                    ),
+                ));
+            }
+        }
+    }
+
+    if view.pending_state_changes.len() > max_pending_state_changes {
+        findings.push(Finding::new(
+            Severity::Warning,
+            "state_change_lag",
+            format!(
+                "{} state changes wait for the scheduler",
+                view.pending_state_changes.len()
+            ),
+        ));
+    }
+    let stale = view
+        .pending_state_changes
+        .iter()
+        .filter(|created_at| now.saturating_sub(**created_at) > stale_after_secs)
+        .count();
+    if stale > 0 {
+        findings.push(Finding::new(
+            Severity::Error,
+            "stale_state_change",
+            format!(
+                "{} state changes are unprocessed for more than {}s",
+                stale, stale_after_secs
+            ),
+        ));
+    }
+
+    if let (Some(unfinished), Some(max_pending)) =
+        (view.unfinished_gc_tasks, view.max_pending_gc_tasks)
+    {
+        if near_cap(unfinished, max_pending) {
+            let severity = if unfinished >= max_pending as usize {
+                Severity::Error
+            } else {
+                Severity::Warning
+            };
+            findings.push(Finding::new(
+                severity,
+                "gc_task_cap",
+                format!(
+                    "{} of at most {} garbage collection tasks are unfinished, deletions wait at the cap",
+                    unfinished, max_pending
+                ),
+            ));
+        }
+    }
+    findings
+}
+
+fn render(findings: &[Finding]) -> String {
+    if findings.is_empty() {
+        return "no problems found".to_string();
+    }
+    findings
+        .iter()
+        .map(|finding| {
+            let severity = match finding.severity {
+                Severity::Warning => "WARNING",
+                Severity::Error => "ERROR",
+            };
+            format!("{:<8} {:<20} {}", severity, finding.check, finding.message)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnose() {
+        let healthy = ClusterView {
+            executors: vec![ExecutorView {
+                id: "executor_1".to_string(),
+                extractors: vec!["extractor".to_string()],
+                running_tasks: 1,
+                capacity_weight: 1,
+            }],
+            graphs: vec![GraphView {
+                namespace: "default".to_string(),
+                name: "graph".to_string(),
+                policies: vec![("policy".to_string(), "extractor".to_string())],
+                max_active_content: Some(10),
+                active_content: 2,
+            }],
+            pending_state_changes: vec![90],
+            ..Default::default()
+        };
+        assert!(diagnose(&healthy, 300, 100, 100).is_empty());
+
+        let unhealthy = ClusterView {
+            max_tasks_per_executor: Some(1),
+            max_pending_gc_tasks: Some(5),
+            graphs: vec![GraphView {
+                policies: vec![("policy".to_string(), "missing".to_string())],
+                active_content: 8,
+                ..healthy.graphs[0].clone()
+            }],
+            pending_state_changes: vec![10, 90],
+            unfinished_gc_tasks: Some(5),
+            ..healthy
+        };
+        let findings = diagnose(&unhealthy, 300, 100, 1000);
+        let checks: Vec<(&str, Severity)> = findings
+            .iter()
+            .map(|finding| (finding.check, finding.severity))
+            .collect();
+        assert_eq!(
+            checks,
+            vec![
+                ("executor_capacity", Severity::Warning),
+                ("unserved_extractor", Severity::Error),
+                ("active_content_cap", Severity::Warning),
+                ("stale_state_change", Severity::Error),
+                ("gc_task_cap", Severity::Error),
+            ]
+        );
+        assert!(findings[3].message.starts_with("2 state changes"));
+    }
+
+    fn read_dir_bytes(dir: &Path) -> Vec<(PathBuf, Vec<u8>)> {
+        let mut files: Vec<(PathBuf, Vec<u8>)> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.is_file())
+            .map(|path| {
+                let bytes = std::fs::read(&path).unwrap();
+                (path, bytes)
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[tokio::test]
+    async fn test_read_state_dir_leaves_it_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("db");
+        {
+            let (_, store) = crate::state::store::new_storage(
+                db_path.clone(),
+                dir.path().join("sm-blob"),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+            drop(store);
+        }
+        let before = read_dir_bytes(&db_path);
+
+        let view = read_state_dir(dir.path()).await.unwrap();
+        assert!(view.executors.is_empty());
+        assert_eq!(view.unfinished_gc_tasks, Some(0));
+        assert_eq!(read_dir_bytes(&db_path), before);
+    }
+}
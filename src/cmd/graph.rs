@@ -0,0 +1,223 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use bytes::Bytes;
+use clap::{Args as ClapArgs, Subcommand};
+use reqwest::StatusCode;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use super::GlobalArgs;
+use crate::{
+    api::{ExtractionGraphDiagnostic, ExtractionGraphRequest, ExtractionGraphResponse},
+    blob_storage::{BlobStorage, BlobStorageWriter},
+    prelude::*,
+    server_config::ServerConfig,
+};
+
+#[derive(Debug, ClapArgs)]
+pub struct Args {
+    /// url of a running server
+    #[arg(long, default_value = "http://localhost:8900")]
+    server_addr: String,
+
+    /// api key or token sent as a bearer token when authentication is enabled
+    #[arg(long)]
+    api_key: Option<String>,
+
+    #[arg(short, long, default_value = "default")]
+    namespace: String,
+
+    #[command(subcommand)]
+    action: Action,
+}
+
+#[derive(Debug, Subcommand)]
+enum Action {
+    /// Create an extraction graph from a spec file, or replace its
+    /// definition when it exists
+    Deploy {
+        /// YAML or JSON definition of the extraction graph
+        spec: PathBuf,
+
+        /// code the extractors of the graph run, uploaded to the blob store
+        /// and recorded in the history of the graph with its sha256
+        #[arg(long, requires = "config_path")]
+        code: Option<PathBuf>,
+
+        /// server config whose blob store the code is uploaded to
+        #[arg(short, long)]
+        config_path: Option<String>,
+
+        /// fail the deploy when linting the graph finds warnings
+        #[arg(long)]
+        strict: bool,
+    },
+}
+
+/// Code uploaded with a deploy
+#[derive(Serialize)]
+struct CodeArtifact {
+    url: String,
+    sha256: String,
+}
+
+#[derive(Serialize)]
+struct Deployment {
+    namespace: String,
+    extraction_graph: String,
+    version: u64,
+    code: Option<CodeArtifact>,
+    warnings: Vec<ExtractionGraphDiagnostic>,
+}
+
+impl Args {
+    pub async fn run(self, global_args: GlobalArgs) {
+        let result = match &self.action {
+            Action::Deploy {
+                spec,
+                code,
+                config_path,
+                strict,
+            } => self
+                .deploy(spec, code.as_deref(), config_path.as_deref(), *strict)
+                .await
+                .map(|deployment| global_args.output.print(&deployment, render_deployment)),
+        };
+        if let Err(e) = result {
+            error!("graph command failed: {:?}", e);
+            std::process::exit(1);
+        }
+    }
+
+    async fn deploy(
+        &self,
+        spec: &Path,
+        code: Option<&Path>,
+        config_path: Option<&str>,
+        strict: bool,
+    ) -> Result<Deployment> {
+        let mut graph = parse_spec(&std::fs::read_to_string(spec)?)?;
+        graph.strict |= strict;
+        let mut artifact = None;
+        if let (Some(code), Some(config_path)) = (code, config_path) {
+            let uploaded = self.upload_code(&graph.name, code, config_path).await?;
+            graph.change_summary.get_or_insert_with(|| {
+                format!("deployed code {} from {}", uploaded.sha256, uploaded.url)
+            });
+            artifact = Some(uploaded);
+        }
+
+        let server_addr = self.server_addr.trim_end_matches('/');
+        let graphs_url = format!(
+            "{}/namespaces/{}/extraction_graphs",
+            server_addr, self.namespace
+        );
+        let graph_url = format!("{}/{}", graphs_url, graph.name);
+        let client = reqwest::Client::new();
+        let exists = self
+            .authorize(client.get(format!("{}/versions", graph_url)))
+            .send()
+            .await?;
+        let request = match exists.status() {
+            StatusCode::NOT_FOUND => client.post(&graphs_url),
+            _ => client.put(&graph_url),
+        };
+        let response = self.authorize(request.json(&graph)).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "deploy of {} failed with {}: {}",
+                graph.name,
+                response.status(),
+                response.text().await?
+            ));
+        }
+        let response: ExtractionGraphResponse = response.json().await?;
+        Ok(Deployment {
+            namespace: self.namespace.clone(),
+            extraction_graph: graph.name,
+            version: response.version,
+            code: artifact,
+            warnings: response.warnings,
+        })
+    }
+
+    async fn upload_code(
+        &self,
+        graph_name: &str,
+        code: &Path,
+        config_path: &str,
+    ) -> Result<CodeArtifact> {
+        let config = ServerConfig::from_path(config_path)
+            .map_err(|e| anyhow!("failed to load config: {}: {}", config_path, e))?;
+        let config = crate::secrets::resolve(&config).await?;
+        let data = Bytes::from(std::fs::read(code)?);
+        let sha256 = format!("{:x}", Sha256::digest(&data));
+        // Content addressed, so deploying the same code again reuses the blob
+        let key = format!("code_{}_{}_{}", self.namespace, graph_name, sha256);
+        let result = BlobStorage::new_with_config(config.blob_storage)
+            .put(&key, futures::stream::iter(vec![Ok(data)]))
+            .await?;
+        Ok(CodeArtifact {
+            url: result.url,
+            sha256,
+        })
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(api_key) => request.bearer_auth(api_key),
+            None => request,
+        }
+    }
+}
+
+fn render_deployment(deployment: &Deployment) -> String {
+    let mut lines = Vec::new();
+    if let Some(code) = &deployment.code {
+        lines.push(format!("uploaded code {} to {}", code.sha256, code.url));
+    }
+    lines.push(format!(
+        "deployed extraction graph {} in namespace {}, version {}",
+        deployment.extraction_graph, deployment.namespace, deployment.version
+    ));
+    for warning in &deployment.warnings {
+        match &warning.extraction_policy {
+            Some(policy) => lines.push(format!("warning: {}: {}", policy, warning.message)),
+            None => lines.push(format!("warning: {}", warning.message)),
+        }
+    }
+    lines.join("\n")
+}
+
+/// JSON specs are parsed as YAML too, as JSON is a subset of it
+fn parse_spec(spec: &str) -> Result<ExtractionGraphRequest> {
+    serde_yaml::from_str(spec).map_err(|e| anyhow!("invalid extraction graph spec: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spec() {
+        let yaml = r#"
+name: summarize
+extraction_policies:
+  - extractor: tensorlake/summarization
+    name: summary
+"#;
+        let graph = parse_spec(yaml).unwrap();
+        assert_eq!(graph.name, "summarize");
+        assert_eq!(graph.extraction_policies[0].name, "summary");
+
+        let json = r#"{"name": "summarize", "extraction_policies": [{"extractor": "tensorlake/summarization", "name": "summary"}]}"#;
+        let graph = parse_spec(json).unwrap();
+        assert_eq!(
+            graph.extraction_policies[0].extractor,
+            "tensorlake/summarization"
+        );
+
+        assert!(parse_spec("extraction_policies: []").is_err());
+    }
+}
@@ -0,0 +1,196 @@
+use std::{io::Read, path::PathBuf, time::Duration};
+
+use anyhow::Result;
+use clap::Args as ClapArgs;
+use reqwest::multipart::{Form, Part};
+use serde::Serialize;
+
+use super::{GlobalArgs, OutputFormat};
+use crate::{
+    api::{ContentMetadata, GetContentTreeMetadataResponse, UploadFileResponse},
+    prelude::*,
+};
+
+#[derive(Debug, ClapArgs)]
+pub struct Args {
+    /// url of a running server
+    #[arg(long, default_value = "http://localhost:8900")]
+    server_addr: String,
+
+    /// api key or token sent as a bearer token when authentication is enabled
+    #[arg(long)]
+    api_key: Option<String>,
+
+    #[arg(short, long, default_value = "default")]
+    namespace: String,
+
+    /// extraction graph the payload is ingested into
+    graph: String,
+
+    /// file with the payload, read from stdin when it is unset or `-`
+    #[arg(short, long)]
+    file: Option<PathBuf>,
+
+    /// file name of the payload, its extension decides the mime type.
+    /// Defaults to the name of the file
+    #[arg(long)]
+    name: Option<String>,
+
+    /// labels of the payload as key=value
+    #[arg(short, long, value_parser = parse_label)]
+    label: Vec<(String, String)>,
+
+    /// wait until the graph finished extracting the payload and print the
+    /// content extracted from it
+    #[arg(short, long)]
+    wait: bool,
+
+    /// how long to wait for the extraction
+    #[arg(long, default_value = "600")]
+    timeout_secs: u64,
+}
+
+#[derive(Serialize)]
+struct Invocation {
+    content_id: String,
+    /// Content extracted from the payload, set when waiting for it
+    outputs: Option<Vec<ContentMetadata>>,
+}
+
+fn parse_label(label: &str) -> Result<(String, String), String> {
+    label
+        .split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("label `{}` is not key=value", label))
+}
+
+impl Args {
+    pub async fn run(self, global_args: GlobalArgs) {
+        match self.invoke(global_args.output).await {
+            Ok(invocation) => global_args.output.print(&invocation, render),
+            Err(e) => {
+                error!("invoke failed: {:?}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    async fn invoke(&self, output: OutputFormat) -> Result<Invocation> {
+        let server_addr = self.server_addr.trim_end_matches('/');
+        let client = reqwest::Client::new();
+        let content_id = self.upload(&client, server_addr).await?;
+        if !self.wait {
+            return Ok(Invocation {
+                content_id,
+                outputs: None,
+            });
+        }
+        // Tells the user what is being waited for, scripts get it with the
+        // outputs
+        if output == OutputFormat::Table {
+            println!("ingested content {}", content_id);
+        }
+
+        let content_url = format!(
+            "{}/namespaces/{}/content/{}",
+            server_addr, self.namespace, content_id
+        );
+        let wait = self
+            .authorize(client.get(format!("{}/wait", content_url)))
+            .send();
+        tokio::time::timeout(Duration::from_secs(self.timeout_secs), wait)
+            .await
+            .map_err(|_| anyhow!("extraction didn't finish in {}s", self.timeout_secs))??
+            .error_for_status()?;
+        let tree: GetContentTreeMetadataResponse = self
+            .authorize(client.get(format!("{}/content-tree", content_url)))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let outputs = tree
+            .content_tree_metadata
+            .into_iter()
+            .filter(|content| content.id != content_id)
+            .collect();
+        Ok(Invocation {
+            content_id,
+            outputs: Some(outputs),
+        })
+    }
+
+    async fn upload(&self, client: &reqwest::Client, server_addr: &str) -> Result<String> {
+        let (data, file_name) = match self.file.as_ref().filter(|file| file.as_os_str() != "-") {
+            Some(file) => (
+                std::fs::read(file)?,
+                file.file_name()
+                    .map(|name| name.to_string_lossy().to_string()),
+            ),
+            None => {
+                let mut data = Vec::new();
+                std::io::stdin().read_to_end(&mut data)?;
+                (data, None)
+            }
+        };
+        let file_name = self
+            .name
+            .clone()
+            .or(file_name)
+            .unwrap_or_else(|| "stdin".to_string());
+
+        //  Labels are read from the fields before the file
+        let form = self
+            .label
+            .iter()
+            .fold(Form::new(), |form, (key, value)| {
+                form.text(key.clone(), value.clone())
+            })
+            .part("file", Part::bytes(data).file_name(file_name));
+        let url = format!("{}/namespaces/{}/upload_file", server_addr, self.namespace);
+        let response = self
+            .authorize(client.post(url))
+            .query(&[("extraction_graph_names", &self.graph)])
+            .multipart(form)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "upload failed with {}: {}",
+                response.status(),
+                response.text().await?
+            ));
+        }
+        let response: UploadFileResponse = response.json().await?;
+        Ok(response.content_id)
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(api_key) => request.bearer_auth(api_key),
+            None => request,
+        }
+    }
+}
+
+fn render(invocation: &Invocation) -> String {
+    let Some(outputs) = &invocation.outputs else {
+        return format!("ingested content {}", invocation.content_id);
+    };
+    let mut lines = vec![format!(
+        "extraction finished with {} outputs",
+        outputs.len()
+    )];
+    for content in outputs {
+        lines.push(format!(
+            "  {} from {} by {}, {} {} bytes at {}",
+            content.id,
+            content.parent_id,
+            content.source,
+            content.mime_type,
+            content.size,
+            content.storage_url
+        ));
+    }
+    lines.join("\n")
+}
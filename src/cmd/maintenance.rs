@@ -0,0 +1,70 @@
+use anyhow::Result;
+use clap::{Args as ClapArgs, Subcommand};
+use indexify_internal_api as internal_api;
+use serde_json::json;
+
+use super::GlobalArgs;
+use crate::prelude::*;
+
+#[derive(Debug, ClapArgs)]
+pub struct Args {
+    /// url of a running server, or of its admin port when admin routes are
+    /// served on a separate port
+    #[arg(long, default_value = "http://localhost:8900")]
+    server_addr: String,
+
+    /// api key or token sent as a bearer token when authentication is enabled
+    #[arg(long)]
+    api_key: Option<String>,
+
+    #[command(subcommand)]
+    action: Action,
+}
+
+#[derive(Debug, Subcommand)]
+enum Action {
+    /// Stop the scheduler from creating and assigning tasks, executors keep
+    /// finishing the tasks they have
+    Enable {
+        /// why the cluster is put in maintenance
+        #[arg(long, default_value = "")]
+        reason: String,
+    },
+    /// Resume scheduling, the scheduler catches up with the content ingested
+    /// during maintenance
+    Disable,
+    /// Show whether the cluster is in maintenance
+    Status,
+}
+
+impl Args {
+    pub async fn run(self, global_args: GlobalArgs) {
+        match self.maintenance_mode().await {
+            Ok(maintenance) => global_args.output.print(&maintenance, |maintenance| {
+                if maintenance.enabled {
+                    format!("cluster is in maintenance: {}", maintenance.reason)
+                } else {
+                    "cluster is not in maintenance".to_string()
+                }
+            }),
+            Err(e) => error!("maintenance request failed: {:?}", e),
+        }
+    }
+
+    async fn maintenance_mode(&self) -> Result<internal_api::MaintenanceMode> {
+        let url = format!("{}/maintenance", self.server_addr.trim_end_matches('/'));
+        let client = reqwest::Client::new();
+        let request = match &self.action {
+            Action::Enable { reason } => client
+                .put(&url)
+                .json(&json!({"enabled": true, "reason": reason})),
+            Action::Disable => client.put(&url).json(&json!({"enabled": false})),
+            Action::Status => client.get(&url),
+        };
+        let request = match &self.api_key {
+            Some(api_key) => request.bearer_auth(api_key),
+            None => request,
+        };
+        Ok(request.send().await?.error_for_status()?.json().await?)
+    }
+}
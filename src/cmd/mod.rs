@@ -1,9 +1,20 @@
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
 
+mod bench;
 mod coordinator;
+mod doctor;
+mod graph;
 mod init_compose;
 mod init_config;
+mod invoke;
+mod maintenance;
+mod namespace;
+mod restore;
+mod sdk;
 mod server;
+mod tail;
+mod top;
 
 /// Global arguments for the CLI. These are arguments that are shared across all
 /// subcommands.
@@ -18,16 +29,95 @@ pub struct GlobalArgs {
         action = clap::ArgAction::Count,
     )]
     pub verbosity: u8,
+
+    /// format of the results commands print, json and yaml are meant for
+    /// scripts
+    #[arg(global = true, long, value_enum, default_value_t)]
+    pub output: OutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Yaml,
+}
+
+impl OutputFormat {
+    /// Prints a result, `table` renders it for people
+    pub fn print<T: Serialize>(self, value: &T, table: impl FnOnce(&T) -> String) {
+        match self {
+            OutputFormat::Table => println!("{}", table(value)),
+            OutputFormat::Json => match serde_json::to_string_pretty(value) {
+                Ok(json) => println!("{}", json),
+                Err(e) => eprintln!("failed to serialize output: {}", e),
+            },
+            OutputFormat::Yaml => Self::print_yaml(value),
+        }
+    }
+
+    /// Prints one of a stream of results, as JSON lines or YAML documents,
+    /// so the output can be read before the stream ends
+    pub fn print_item<T: Serialize>(self, value: &T, table: impl FnOnce(&T) -> String) {
+        match self {
+            OutputFormat::Table => println!("{}", table(value)),
+            OutputFormat::Json => match serde_json::to_string(value) {
+                Ok(json) => println!("{}", json),
+                Err(e) => eprintln!("failed to serialize output: {}", e),
+            },
+            OutputFormat::Yaml => {
+                println!("---");
+                Self::print_yaml(value);
+            }
+        }
+    }
+
+    fn print_yaml<T: Serialize>(value: &T) {
+        match serde_yaml::to_string(value) {
+            Ok(yaml) => print!("{}", yaml),
+            Err(e) => eprintln!("failed to serialize output: {}", e),
+        }
+    }
 }
 
 /// The list of commands that can be run on indexify.
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     /// Start the server
+    #[command(visible_alias = "start")]
     Server(server::Args),
     Coordinator(coordinator::Args),
     InitConfig(init_config::Args),
     InitCompose(init_compose::Args),
+    /// Generate synthetic load and report scheduling throughput and latency
+    Bench(bench::Args),
+    /// Pause or resume scheduling of a running cluster
+    Maintenance(maintenance::Args),
+    /// Rebuild the state directory of a server from an archived snapshot
+    Restore(restore::Args),
+    /// Manage the extraction graphs of a running server
+    Graph(graph::Args),
+    /// Export a namespace to an archive or import one, e.g. to move it to
+    /// another cluster
+    Namespace(namespace::Args),
+    /// Ingest a payload into an extraction graph and optionally wait for its
+    /// outputs
+    Invoke(invoke::Args),
+    /// Print the task events of a namespace as they happen
+    Tail(tail::Args),
+    /// Dashboard of executors, task queues and scheduler progress
+    Top(top::Args),
+    /// Check a running server, or the state directory of a stopped one, for
+    /// common problems
+    Doctor(doctor::Args),
+    /// Generate typed Python and TypeScript clients from the API schema
+    Sdk(sdk::Args),
+    /// Print the completion script of a shell, e.g.
+    /// `indexify completions bash > /etc/bash_completion.d/indexify`
+    Completions {
+        shell: clap_complete::Shell,
+    },
 }
 
 /// The main CLI struct. This is the root of the CLI tree.
@@ -49,6 +139,22 @@ impl Cli {
             Commands::Coordinator(args) => args.run(self.global_args).await,
             Commands::InitConfig(args) => args.run(self.global_args).await,
             Commands::InitCompose(args) => args.run(self.global_args).await,
+            Commands::Bench(args) => args.run(self.global_args).await,
+            Commands::Maintenance(args) => args.run(self.global_args).await,
+            Commands::Restore(args) => args.run(self.global_args).await,
+            Commands::Graph(args) => args.run(self.global_args).await,
+            Commands::Namespace(args) => args.run(self.global_args).await,
+            Commands::Invoke(args) => args.run(self.global_args).await,
+            Commands::Tail(args) => args.run(self.global_args).await,
+            Commands::Top(args) => args.run(self.global_args).await,
+            Commands::Doctor(args) => args.run(self.global_args).await,
+            Commands::Sdk(args) => args.run(self.global_args).await,
+            Commands::Completions { shell } => clap_complete::generate(
+                shell,
+                &mut Cli::command(),
+                "indexify",
+                &mut std::io::stdout(),
+            ),
         }
     }
 }
@@ -0,0 +1,178 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::{Args as ClapArgs, Subcommand};
+use serde::{Deserialize, Serialize};
+
+use super::GlobalArgs;
+use crate::{namespace_archive::ImportReport, prelude::*};
+
+#[derive(Debug, ClapArgs)]
+pub struct Args {
+    /// url of a running server, or of its admin port when admin routes are
+    /// served on a separate port
+    #[arg(long, default_value = "http://localhost:8900")]
+    server_addr: String,
+
+    /// api key or token sent as a bearer token, export and import are admin
+    /// routes
+    #[arg(long)]
+    api_key: Option<String>,
+
+    #[command(subcommand)]
+    action: Action,
+}
+
+#[derive(Debug, Subcommand)]
+enum Action {
+    /// Write the extraction graphs and content metadata of a namespace to an
+    /// archive file
+    Export {
+        namespace: String,
+
+        /// file the archive is written to
+        #[arg(short, long)]
+        output_path: PathBuf,
+    },
+    /// Create the extraction graphs and content of an archive in a namespace,
+    /// with new content ids
+    Import {
+        /// archive written by an export
+        archive: PathBuf,
+
+        /// namespace the archive is imported into, the namespace it was
+        /// exported from by default
+        #[arg(short, long)]
+        namespace: Option<String>,
+    },
+}
+
+/// The part of an archive the CLI reads, the server reads the rest
+#[derive(Deserialize)]
+struct ArchiveHeader {
+    namespace: String,
+}
+
+#[derive(Serialize)]
+struct Export {
+    namespace: String,
+    path: PathBuf,
+    size_bytes: usize,
+}
+
+#[derive(Serialize)]
+struct Import {
+    namespace: String,
+    #[serde(flatten)]
+    report: ImportReport,
+}
+
+impl Args {
+    pub async fn run(self, global_args: GlobalArgs) {
+        let result = match &self.action {
+            Action::Export {
+                namespace,
+                output_path,
+            } => self
+                .export(namespace, output_path)
+                .await
+                .map(|export| global_args.output.print(&export, render_export)),
+            Action::Import { archive, namespace } => self
+                .import(archive, namespace.as_deref())
+                .await
+                .map(|import| global_args.output.print(&import, render_import)),
+        };
+        if let Err(e) = result {
+            error!("namespace command failed: {:?}", e);
+            std::process::exit(1);
+        }
+    }
+
+    fn url(&self, namespace: &str, action: &str) -> String {
+        format!(
+            "{}/namespaces/{}/{}",
+            self.server_addr.trim_end_matches('/'),
+            namespace,
+            action
+        )
+    }
+
+    async fn export(&self, namespace: &str, output_path: &Path) -> Result<Export> {
+        let client = reqwest::Client::new();
+        let response = self
+            .authorize(client.get(self.url(namespace, "export")))
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "export of {} failed with {}: {}",
+                namespace,
+                response.status(),
+                response.text().await?
+            ));
+        }
+        let archive = response.bytes().await?;
+        std::fs::write(output_path, &archive)?;
+        Ok(Export {
+            namespace: namespace.to_string(),
+            path: output_path.to_path_buf(),
+            size_bytes: archive.len(),
+        })
+    }
+
+    async fn import(&self, archive: &Path, namespace: Option<&str>) -> Result<Import> {
+        let archive = std::fs::read(archive)?;
+        let namespace = match namespace {
+            Some(namespace) => namespace.to_string(),
+            None => {
+                serde_json::from_slice::<ArchiveHeader>(&archive)
+                    .map_err(|e| anyhow!("invalid archive: {}", e))?
+                    .namespace
+            }
+        };
+        let client = reqwest::Client::new();
+        let response = self
+            .authorize(client.post(self.url(&namespace, "import")).body(archive))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "import into {} failed with {}: {}",
+                namespace,
+                response.status(),
+                response.text().await?
+            ));
+        }
+        Ok(Import {
+            namespace,
+            report: response.json().await?,
+        })
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(api_key) => request.bearer_auth(api_key),
+            None => request,
+        }
+    }
+}
+
+fn render_export(export: &Export) -> String {
+    format!(
+        "exported namespace {} to {} ({} bytes)",
+        export.namespace,
+        export.path.display(),
+        export.size_bytes
+    )
+}
+
+fn render_import(import: &Import) -> String {
+    format!(
+        "imported {} extraction graphs ({} versions) and {} content into namespace {}",
+        import.report.extraction_graphs,
+        import.report.extraction_graph_versions,
+        import.report.content,
+        import.namespace
+    )
+}
@@ -0,0 +1,93 @@
+use std::{path::Path, sync::Arc};
+
+use clap::Args as ClapArgs;
+
+use super::GlobalArgs;
+use crate::{
+    blob_storage::ContentReader,
+    prelude::*,
+    server_config::ServerConfig,
+    state::store::restore::{self, RestoreReport},
+};
+
+#[derive(Debug, ClapArgs)]
+pub struct Args {
+    /// config of the server whose state is restored, the state is written to
+    /// its state store path
+    #[arg(short, long)]
+    config_path: String,
+
+    /// url of the archived snapshot, like s3://bucket/snapshots/<ts>
+    #[arg(long)]
+    from: String,
+
+    /// restore the state as it was at this time, in seconds since the epoch,
+    /// instead of replaying every archived log entry. Log entries are kept
+    /// by the time they were written to the log of the archiving server
+    #[arg(long)]
+    until: Option<u64>,
+}
+
+impl Args {
+    pub async fn run(self, global_args: GlobalArgs) {
+        let Self {
+            config_path,
+            from,
+            until,
+        } = self;
+
+        let config = ServerConfig::from_path(&config_path)
+            .unwrap_or_else(|e| panic!("failed to load config: {}: {}", config_path, e));
+        let config = crate::secrets::resolve(&config)
+            .await
+            .unwrap_or_else(|e| panic!("failed to resolve secrets of the config: {}", e));
+        let state_dir = config.state_store.path.clone().unwrap_or_default();
+        let reader = ContentReader::new(Arc::new(config));
+        let report = match restore::restore(&reader, &from, Path::new(&state_dir), until).await {
+            Ok(report) => report,
+            Err(e) => {
+                error!("failed to restore {}: {:?}", from, e);
+                std::process::exit(1);
+            }
+        };
+        global_args
+            .output
+            .print(&report, |report| render(report, &from, &state_dir));
+    }
+}
+
+fn render(report: &RestoreReport, from: &str, state_dir: &str) -> String {
+    let mut lines = vec![format!(
+        "restored {} into {}: snapshot at log {:?}, {} log entries after it up to {:?}",
+        from,
+        state_dir,
+        report.snapshot_log_id.map(|log_id| log_id.index),
+        report.replayed_entries,
+        report.last_log_id.map(|log_id| log_id.index)
+    )];
+    if report.skipped_entries > 0 {
+        lines.push(format!(
+            "{} archived log entries after the restore point were not restored",
+            report.skipped_entries
+        ));
+    }
+    if report.in_flight_tasks.is_empty() {
+        lines.push("no tasks were running when the snapshot was taken".to_string());
+        return lines.join("\n");
+    }
+    lines.push(format!(
+        "{} tasks were running when the snapshot was taken:",
+        report.in_flight_tasks.len()
+    ));
+    for task in &report.in_flight_tasks {
+        lines.push(format!(
+            "  {} of policy {} on content {} in namespace {}, executor {}",
+            task.task_id,
+            task.extraction_policy_id,
+            task.content_id,
+            task.namespace,
+            task.executor_id
+        ));
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Args as ClapArgs, ValueEnum};
+use utoipa::OpenApi;
+
+use super::GlobalArgs;
+use crate::{prelude::*, sdk_codegen::ApiSchema, server::ApiDoc};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum Language {
+    Python,
+    Typescript,
+}
+
+#[derive(Debug, ClapArgs)]
+pub struct Args {
+    /// directory the clients are written to
+    #[arg(short, long, default_value = "sdk")]
+    out_dir: PathBuf,
+
+    /// languages of the clients, all of them by default
+    #[arg(short, long, value_enum)]
+    language: Vec<Language>,
+
+    /// OpenAPI document to generate the clients from, like the one a running
+    /// server serves at /api-docs/openapi.json. Defaults to the API of this
+    /// build
+    #[arg(long)]
+    schema: Option<PathBuf>,
+}
+
+impl Args {
+    pub async fn run(self, _: GlobalArgs) {
+        if let Err(e) = self.generate() {
+            error!("failed to generate the clients: {:?}", e);
+            std::process::exit(1);
+        }
+    }
+
+    fn generate(&self) -> Result<()> {
+        let openapi = match &self.schema {
+            Some(path) => serde_json::from_slice(&std::fs::read(path)?)?,
+            None => serde_json::to_value(ApiDoc::openapi())?,
+        };
+        let schema = ApiSchema::parse(&openapi)?;
+        let languages = match self.language.as_slice() {
+            [] => &[Language::Python, Language::Typescript][..],
+            languages => languages,
+        };
+        std::fs::create_dir_all(&self.out_dir)?;
+        for language in languages {
+            let (file_name, client) = match language {
+                Language::Python => ("indexify_client.py", schema.python()),
+                Language::Typescript => ("indexify_client.ts", schema.typescript()),
+            };
+            let path = self.out_dir.join(file_name);
+            std::fs::write(&path, client)?;
+            println!("wrote {}", path.display());
+        }
+        Ok(())
+    }
+}
@@ -1,10 +1,12 @@
 use std::sync::Arc;
 
 use clap::Args as ClapArgs;
+use tokio::{signal, sync::watch};
 
 use super::GlobalArgs;
 use crate::{
     coordinator_service::CoordinatorServer,
+    embedded_executor::EmbeddedExecutor,
     prelude::*,
     server,
     server_config::ServerConfig,
@@ -18,6 +20,26 @@ pub struct Args {
 
     #[arg(short, long)]
     dev_mode: bool,
+
+    /// also run an executor with the extractors downloaded on this machine,
+    /// `indexify-extractor join-server` started as a child process. Implies
+    /// dev mode
+    #[arg(long)]
+    all_in_one: bool,
+
+    /// command that runs the executor in all in one mode, defaults to
+    /// `indexify-extractor`
+    #[arg(long, requires = "all_in_one")]
+    executor_command: Option<String>,
+
+    /// number of workers of the executor in all in one mode
+    #[arg(long, requires = "all_in_one")]
+    executor_workers: Option<u32>,
+
+    /// serve reads only, with the APIs that change state disabled and the
+    /// scheduler stopped
+    #[arg(long, conflicts_with = "all_in_one")]
+    read_only: bool,
 }
 
 impl Args {
@@ -25,12 +47,16 @@ impl Args {
         let Self {
             config_path,
             dev_mode,
+            all_in_one,
+            executor_command,
+            executor_workers,
+            read_only,
         } = self;
 
         let registry = Arc::new(crate::metrics::init_provider());
 
         info!("starting indexify server, version: {}", crate::VERSION);
-        let config = if let Some(config_path) = config_path {
+        let mut config = if let Some(config_path) = config_path {
             ServerConfig::from_path(&config_path)
                 .unwrap_or_else(|e| panic!("failed to load config file `{}`: {}", config_path, e))
         } else {
@@ -38,7 +64,15 @@ impl Args {
             ServerConfig::default()
         };
 
+        config.read_only |= read_only;
         debug!("Server config is: {:?}", config);
+        let config = crate::secrets::resolve(&config)
+            .await
+            .unwrap_or_else(|e| panic!("failed to resolve secrets of the config: {}", e));
+        if let Some(log_file) = &config.logging.file {
+            crate::log_file::init(log_file)
+                .unwrap_or_else(|e| panic!("failed to open log file `{}`: {}", log_file.path, e));
+        }
         let server =
             server::Server::new(Arc::new(config.clone())).expect("failed to create server");
 
@@ -48,7 +82,30 @@ impl Args {
                 server.run(registry).await.unwrap();
             }
         });
-        if dev_mode {
+        if all_in_one {
+            let executor =
+                EmbeddedExecutor::new(&config, executor_command.as_deref(), executor_workers)
+                    .expect("failed to create embedded executor");
+            let coordinator = CoordinatorServer::new(Arc::new(config.clone()), registry)
+                .await
+                .expect("failed to create coordinator server");
+            let coordinator_handle = tokio::spawn(async move {
+                coordinator.run().await.unwrap();
+            });
+            let (shutdown_tx, shutdown_rx) = watch::channel(false);
+            tokio::spawn(async move {
+                shutdown_signal().await;
+                let _ = shutdown_tx.send(true);
+            });
+            let executor_handle = tokio::spawn(async move {
+                executor
+                    .run(shutdown_rx)
+                    .await
+                    .expect("failed to run embedded executor");
+            });
+            tokio::try_join!(server_handle, coordinator_handle, executor_handle)
+                .expect("failed to run server, coordinator server or embedded executor");
+        } else if dev_mode {
             let coordinator = CoordinatorServer::new(Arc::new(config.clone()), registry)
                 .await
                 .expect("failed to create coordinator server");
@@ -62,3 +119,29 @@ impl Args {
         }
     }
 }
+
+/// Waits for Ctrl+C or, on unix, SIGTERM, which the server and coordinator
+/// shut down on too
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("failed to install signal handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
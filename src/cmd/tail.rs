@@ -0,0 +1,231 @@
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::Result;
+use clap::Args as ClapArgs;
+use indexify_proto::indexify_coordinator::TaskOutcome;
+use serde::Serialize;
+
+use super::{GlobalArgs, OutputFormat};
+use crate::{
+    api::{ListTasksResponse, Task},
+    prelude::*,
+};
+
+#[derive(Debug, ClapArgs)]
+pub struct Args {
+    /// url of a running server
+    #[arg(long, default_value = "http://localhost:8900")]
+    server_addr: String,
+
+    /// api key or token sent as a bearer token when authentication is enabled
+    #[arg(long)]
+    api_key: Option<String>,
+
+    #[arg(short, long, default_value = "default")]
+    namespace: String,
+
+    /// only show the tasks of an extraction graph
+    #[arg(short, long)]
+    graph: Option<String>,
+
+    /// only show the tasks of the content tree of an ingested content
+    #[arg(short, long)]
+    content: Option<String>,
+
+    /// keep showing new events instead of exiting after the current ones
+    #[arg(short, long)]
+    follow: bool,
+
+    /// how often the tasks are polled when following
+    #[arg(long, default_value = "1000")]
+    interval_ms: u64,
+}
+
+#[derive(Debug, PartialEq)]
+enum TaskEvent {
+    Created { task_id: String, outcome: i32 },
+    Finished { task_id: String, outcome: i32 },
+}
+
+/// Turns successive listings of tasks into the events between them
+#[derive(Default)]
+struct TaskEvents {
+    outcomes: HashMap<String, i32>,
+}
+
+impl TaskEvents {
+    fn update<'a>(&mut self, tasks: impl IntoIterator<Item = &'a Task>) -> Vec<TaskEvent> {
+        let mut events = Vec::new();
+        for task in tasks {
+            match self.outcomes.insert(task.id.clone(), task.outcome) {
+                None => events.push(TaskEvent::Created {
+                    task_id: task.id.clone(),
+                    outcome: task.outcome,
+                }),
+                Some(outcome) if outcome != task.outcome => events.push(TaskEvent::Finished {
+                    task_id: task.id.clone(),
+                    outcome: task.outcome,
+                }),
+                Some(_) => {}
+            }
+        }
+        events
+    }
+}
+
+/// An event with the task it happened to, as it is printed
+#[derive(Serialize)]
+struct EventRecord<'a> {
+    event: &'static str,
+    task_id: &'a str,
+    extraction_policy_id: &'a str,
+    content_id: &'a str,
+    root_content_id: &'a str,
+    outcome: String,
+    diagnostics: Option<&'a str>,
+}
+
+fn outcome_name(outcome: i32) -> String {
+    TaskOutcome::try_from(outcome)
+        .map(|outcome| outcome.as_str_name().to_lowercase())
+        .unwrap_or_else(|_| outcome.to_string())
+}
+
+impl Args {
+    pub async fn run(self, global_args: GlobalArgs) {
+        if let Err(e) = self.tail(global_args.output).await {
+            error!("tail failed: {:?}", e);
+            std::process::exit(1);
+        }
+    }
+
+    fn matches(&self, task: &Task) -> bool {
+        let content = &task.content_metadata;
+        self.graph
+            .as_ref()
+            .map_or(true, |graph| content.extraction_graph_names.contains(graph)) &&
+            self.content.as_ref().map_or(true, |id| {
+                &content.id == id || &content.root_content_id == id
+            })
+    }
+
+    async fn tail(&self, output: OutputFormat) -> Result<()> {
+        let url = format!(
+            "{}/namespaces/{}/tasks",
+            self.server_addr.trim_end_matches('/'),
+            self.namespace
+        );
+        let client = reqwest::Client::new();
+        let mut events = TaskEvents::default();
+        loop {
+            let request = match &self.api_key {
+                Some(api_key) => client.get(&url).bearer_auth(api_key),
+                None => client.get(&url),
+            };
+            let response: ListTasksResponse =
+                request.send().await?.error_for_status()?.json().await?;
+            let tasks: Vec<&Task> = response
+                .tasks
+                .iter()
+                .filter(|task| self.matches(task))
+                .collect();
+            let tasks_by_id: HashMap<&str, &Task> =
+                tasks.iter().map(|&task| (task.id.as_str(), task)).collect();
+            for event in events.update(tasks) {
+                if let Some(record) = event_record(&event, &tasks_by_id) {
+                    output.print_item(&record, render);
+                }
+            }
+            if !self.follow {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_millis(self.interval_ms)).await;
+        }
+    }
+}
+
+fn event_record<'a>(event: &TaskEvent, tasks: &HashMap<&str, &'a Task>) -> Option<EventRecord<'a>> {
+    let (name, task_id, outcome) = match event {
+        TaskEvent::Created { task_id, outcome } => ("created", task_id, outcome),
+        TaskEvent::Finished { task_id, outcome } => ("finished", task_id, outcome),
+    };
+    let task = tasks.get(task_id.as_str())?;
+    Some(EventRecord {
+        event: name,
+        task_id: &task.id,
+        extraction_policy_id: &task.extraction_policy_id,
+        content_id: &task.content_metadata.id,
+        root_content_id: &task.content_metadata.root_content_id,
+        outcome: outcome_name(*outcome),
+        diagnostics: task.diagnostics.as_deref(),
+    })
+}
+
+fn render(record: &EventRecord) -> String {
+    format!(
+        "task {} {}: policy {} on content {} ({}) {}{}",
+        record.task_id,
+        record.event,
+        record.extraction_policy_id,
+        record.content_id,
+        record.root_content_id,
+        record.outcome,
+        record
+            .diagnostics
+            .map(|diagnostics| format!(": {}", diagnostics))
+            .unwrap_or_default()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, outcome: TaskOutcome) -> Task {
+        Task {
+            id: id.to_string(),
+            extractor: "extractor".to_string(),
+            extraction_policy_id: "policy".to_string(),
+            output_index_table_mapping: HashMap::new(),
+            namespace: "default".to_string(),
+            content_metadata: Default::default(),
+            input_params: serde_json::Value::Null,
+            outcome: outcome as i32,
+            index_tables: vec![],
+            diagnostics: None,
+        }
+    }
+
+    #[test]
+    fn test_task_events() {
+        let mut events = TaskEvents::default();
+        let tasks = vec![task("task_1", TaskOutcome::Unknown)];
+        assert_eq!(
+            events.update(&tasks),
+            vec![TaskEvent::Created {
+                task_id: "task_1".to_string(),
+                outcome: TaskOutcome::Unknown as i32
+            }]
+        );
+        assert!(events.update(&tasks).is_empty());
+
+        let tasks = vec![
+            task("task_1", TaskOutcome::Success),
+            task("task_2", TaskOutcome::Unknown),
+        ];
+        assert_eq!(
+            events.update(&tasks),
+            vec![
+                TaskEvent::Finished {
+                    task_id: "task_1".to_string(),
+                    outcome: TaskOutcome::Success as i32
+                },
+                TaskEvent::Created {
+                    task_id: "task_2".to_string(),
+                    outcome: TaskOutcome::Unknown as i32
+                }
+            ]
+        );
+        assert_eq!(outcome_name(TaskOutcome::Failed as i32), "failed");
+    }
+}
@@ -0,0 +1,241 @@
+use std::{collections::BTreeMap, fmt::Write, time::Duration};
+
+use anyhow::Result;
+use clap::Args as ClapArgs;
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{GlobalArgs, OutputFormat};
+use crate::{
+    api::{
+        Executor,
+        ListExecutorsResponse,
+        ListNamespacesResponse,
+        ListStateChangesResponse,
+        ListTaskQueueDepthsResponse,
+        TaskQueueDepth,
+    },
+    prelude::*,
+    utils::timestamp_secs,
+};
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+#[derive(Debug, ClapArgs)]
+pub struct Args {
+    /// url of a running server, or of its admin port when admin routes are
+    /// served on a separate port
+    #[arg(long, default_value = "http://localhost:8900")]
+    server_addr: String,
+
+    /// api key or token sent as a bearer token, the executors and state
+    /// changes are admin routes
+    #[arg(long)]
+    api_key: Option<String>,
+
+    /// only show the queues of a namespace instead of every namespace
+    #[arg(short, long)]
+    namespace: Option<String>,
+
+    /// how often the dashboard is refreshed
+    #[arg(long, default_value = "2")]
+    interval_secs: u64,
+}
+
+/// What the dashboard shows, read from the API on every refresh
+#[derive(Serialize)]
+struct Snapshot {
+    executors: Vec<Executor>,
+    /// Queue depths by namespace
+    queue_depths: BTreeMap<String, Vec<TaskQueueDepth>>,
+    pending_state_changes: usize,
+    processed_state_changes: usize,
+    /// Creation time of the oldest state change the scheduler hasn't
+    /// processed
+    oldest_pending_state_change: Option<u64>,
+}
+
+impl Args {
+    pub async fn run(self, global_args: GlobalArgs) {
+        let client = reqwest::Client::new();
+        // Scripts get a single snapshot instead of a dashboard
+        if global_args.output != OutputFormat::Table {
+            match self.snapshot(&client).await {
+                Ok(snapshot) => global_args.output.print(&snapshot, |_| String::new()),
+                Err(e) => {
+                    error!("failed to read {}: {:?}", self.server_addr, e);
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        let mut refresh = tokio::time::interval(Duration::from_secs(self.interval_secs.max(1)));
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => return,
+                _ = refresh.tick() => {
+                    let screen = match self.snapshot(&client).await {
+                        Ok(snapshot) => render(&snapshot, timestamp_secs()),
+                        Err(e) => format!("failed to read {}: {:?}\n", self.server_addr, e),
+                    };
+                    print!("{}{}", CLEAR_SCREEN, screen);
+                }
+            }
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, client: &reqwest::Client, path: &str) -> Result<T> {
+        let url = format!("{}{}", self.server_addr.trim_end_matches('/'), path);
+        let request = match &self.api_key {
+            Some(api_key) => client.get(url).bearer_auth(api_key),
+            None => client.get(url),
+        };
+        Ok(request.send().await?.error_for_status()?.json().await?)
+    }
+
+    async fn snapshot(&self, client: &reqwest::Client) -> Result<Snapshot> {
+        let executors: ListExecutorsResponse = self.get(client, "/executors").await?;
+        let namespaces = match &self.namespace {
+            Some(namespace) => vec![namespace.clone()],
+            None => {
+                let response: ListNamespacesResponse = self.get(client, "/namespaces").await?;
+                response.namespaces.into_iter().map(|n| n.name).collect()
+            }
+        };
+        let mut queue_depths = BTreeMap::new();
+        for namespace in namespaces {
+            let response: ListTaskQueueDepthsResponse = self
+                .get(
+                    client,
+                    &format!("/namespaces/{}/tasks/queue_depths", namespace),
+                )
+                .await?;
+            queue_depths.insert(namespace, response.queue_depths);
+        }
+        let state_changes: ListStateChangesResponse = self.get(client, "/state_changes").await?;
+        let pending = state_changes
+            .state_changes
+            .iter()
+            .filter(|change| change.processed_at.is_none());
+        Ok(Snapshot {
+            executors: executors.executors,
+            queue_depths,
+            pending_state_changes: pending.clone().count(),
+            processed_state_changes: state_changes
+                .state_changes
+                .iter()
+                .filter(|change| change.processed_at.is_some())
+                .count(),
+            oldest_pending_state_change: pending.map(|change| change.created_at).min(),
+        })
+    }
+}
+
+fn render(snapshot: &Snapshot, now: u64) -> String {
+    let mut screen = String::new();
+    let _ = writeln!(screen, "EXECUTORS ({})", snapshot.executors.len());
+    let _ = writeln!(
+        screen,
+        "{:<24} {:<22} {:>8} {:>6} {:>10}  EXTRACTORS",
+        "ID", "ADDR", "RUNNING", "BURST", "LAST SEEN"
+    );
+    let mut executors: Vec<&Executor> = snapshot.executors.iter().collect();
+    executors.sort_by(|a, b| b.running_tasks.cmp(&a.running_tasks).then(a.id.cmp(&b.id)));
+    for executor in executors {
+        let _ = writeln!(
+            screen,
+            "{:<24} {:<22} {:>8} {:>6} {:>9}s  {}",
+            executor.id,
+            executor.addr,
+            executor.running_tasks,
+            executor.burst_tasks,
+            now.saturating_sub(executor.last_seen),
+            executor
+                .extractors
+                .iter()
+                .map(|extractor| extractor.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    let _ = writeln!(screen, "\nQUEUES");
+    let _ = writeln!(
+        screen,
+        "{:<16} {:<40} {:>12} {:>8} {:>10}",
+        "NAMESPACE", "GRAPH/POLICY", "UNALLOCATED", "RUNNING", "DONE (1H)"
+    );
+    for (namespace, queue_depths) in &snapshot.queue_depths {
+        let mut queue_depths: Vec<&TaskQueueDepth> = queue_depths.iter().collect();
+        queue_depths.sort_by(|a, b| b.unallocated.cmp(&a.unallocated));
+        for depth in queue_depths {
+            let _ = writeln!(
+                screen,
+                "{:<16} {:<40} {:>12} {:>8} {:>10}",
+                namespace,
+                format!("{}/{}", depth.extraction_graph, depth.extraction_policy),
+                depth.unallocated,
+                depth.running,
+                depth.completed_last_hour
+            );
+        }
+    }
+
+    let _ = writeln!(
+        screen,
+        "\nSCHEDULER: {} state changes pending, {} processed{}",
+        snapshot.pending_state_changes,
+        snapshot.processed_state_changes,
+        snapshot
+            .oldest_pending_state_change
+            .map(|created_at| format!(", oldest pending for {}s", now.saturating_sub(created_at)))
+            .unwrap_or_default()
+    );
+    screen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let snapshot = Snapshot {
+            executors: vec![Executor {
+                id: "executor_1".to_string(),
+                addr: "10.0.0.1:9000".to_string(),
+                last_seen: 95,
+                extractors: vec![],
+                running_tasks: 3,
+                burst_tasks: 1,
+                cost_per_hour: None,
+                capacity_weight: 1,
+                zone: None,
+                max_payload_bytes: None,
+                download_bytes_per_sec: None,
+                draining: false,
+                pressure: None,
+                gpu_devices: vec![],
+                graph_versions: vec![],
+            }],
+            queue_depths: BTreeMap::from([(
+                "default".to_string(),
+                vec![TaskQueueDepth {
+                    extraction_graph: "graph".to_string(),
+                    extraction_policy: "policy".to_string(),
+                    unallocated: 7,
+                    running: 3,
+                    completed_last_hour: 12,
+                }],
+            )]),
+            pending_state_changes: 2,
+            processed_state_changes: 40,
+            oldest_pending_state_change: Some(90),
+        };
+        let screen = render(&snapshot, 100);
+        assert!(screen.contains("EXECUTORS (1)"));
+        assert!(screen.contains("executor_1"));
+        assert!(screen.contains("5s"));
+        assert!(screen.contains("graph/policy"));
+        assert!(screen.contains("2 state changes pending, 40 processed, oldest pending for 10s"));
+    }
+}
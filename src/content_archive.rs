@@ -0,0 +1,199 @@
+use std::{
+    collections::HashMap,
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{anyhow, Result};
+use bytes::Bytes;
+use flate2::{write::GzEncoder, Compression};
+use indexify_internal_api::{self as internal_api, StateChangeId};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{
+    blob_storage::{BlobStorage, BlobStorageWriter},
+    server_config::ContentArchiveConfig,
+};
+
+/// What is left of a content tree after it is deleted: the context of its
+/// root, the tasks that ran on it and the metadata of every content in it,
+/// including where its bytes were stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentArchive {
+    pub namespace: String,
+    /// Extraction graphs the root content was ingested into
+    #[serde(default)]
+    pub extraction_graph_names: Vec<String>,
+    pub root_content_id: internal_api::ContentMetadataId,
+    pub archived_at: u64,
+    pub context: HashMap<String, serde_json::Value>,
+    pub tasks: Vec<internal_api::Task>,
+    pub content: Vec<internal_api::ContentMetadata>,
+}
+
+/// Upload of the archive of a content tree, started by the state change
+/// deleting the tree
+enum Upload {
+    Running,
+    Written,
+    Failed(String),
+}
+
+/// Writes gzip compressed archives of content trees to the blob store
+pub struct ContentArchiver {
+    blob_storage: Arc<BlobStorage>,
+    key_prefix: String,
+    uploads: Mutex<HashMap<StateChangeId, Upload>>,
+}
+
+impl ContentArchiver {
+    pub fn new(config: &ContentArchiveConfig, blob_storage: Arc<BlobStorage>) -> Self {
+        Self {
+            blob_storage,
+            key_prefix: config.key_prefix.clone(),
+            uploads: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Keys are derived from the version of the root content, so archiving a
+    /// tree again replaces its archive
+    fn key(&self, archive: &ContentArchive) -> String {
+        let graphs = if archive.extraction_graph_names.is_empty() {
+            "-".to_string()
+        } else {
+            archive.extraction_graph_names.join("+")
+        };
+        format!(
+            "{}/{}/{}/{}_{}.json.gz",
+            self.key_prefix,
+            archive.namespace,
+            graphs,
+            archive.root_content_id.id,
+            archive.root_content_id.version
+        )
+    }
+
+    /// Writes the archive and returns its url
+    pub async fn archive(&self, archive: &ContentArchive) -> Result<String> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&serde_json::to_vec(archive)?)?;
+        let data = Bytes::from(encoder.finish()?);
+        let result = self
+            .blob_storage
+            .put(&self.key(archive), futures::stream::iter(vec![Ok(data)]))
+            .await?;
+        Ok(result.url)
+    }
+
+    /// Whether an upload finished that the state change deleting its tree
+    /// didn't see yet
+    pub fn has_finished_uploads(&self) -> bool {
+        self.uploads
+            .lock()
+            .unwrap()
+            .values()
+            .any(|upload| !matches!(upload, Upload::Running))
+    }
+
+    /// Whether the archive of the content tree deleted by `change_id` was
+    /// written. The first call builds the archive with `archive` and uploads
+    /// it in the background, the calls after it return true once the upload
+    /// is done. A failed upload is returned as an error once, and the next
+    /// call uploads the archive again.
+    pub async fn archive_in_background<F>(
+        self: &Arc<Self>,
+        change_id: &StateChangeId,
+        archive: F,
+    ) -> Result<bool>
+    where
+        F: std::future::Future<Output = Result<ContentArchive>>,
+    {
+        let finished = {
+            let mut uploads = self.uploads.lock().unwrap();
+            match uploads.get(change_id) {
+                Some(Upload::Running) => return Ok(false),
+                Some(_) => uploads.remove(change_id),
+                None => {
+                    uploads.insert(change_id.clone(), Upload::Running);
+                    None
+                }
+            }
+        };
+        match finished {
+            Some(Upload::Written) => return Ok(true),
+            Some(Upload::Failed(e)) => return Err(anyhow!("unable to write the archive: {}", e)),
+            _ => {}
+        }
+        let archive = match archive.await {
+            Ok(archive) => archive,
+            Err(e) => {
+                self.uploads.lock().unwrap().remove(change_id);
+                return Err(e);
+            }
+        };
+        let archiver = self.clone();
+        let change_id = change_id.clone();
+        tokio::spawn(async move {
+            let upload = match archiver.archive(&archive).await {
+                Ok(url) => {
+                    info!("archived content {} to {}", archive.root_content_id.id, url);
+                    Upload::Written
+                }
+                Err(e) => Upload::Failed(e.to_string()),
+            };
+            archiver.uploads.lock().unwrap().insert(change_id, upload);
+        });
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+
+    use super::*;
+    use crate::blob_storage::{BlobStorageConfig, DiskStorageConfig};
+
+    #[tokio::test]
+    async fn test_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let blob_storage = Arc::new(BlobStorage::new_with_config(BlobStorageConfig {
+            s3: None,
+            disk: Some(DiskStorageConfig {
+                path: dir.path().to_str().unwrap().to_string(),
+            }),
+        }));
+        let archiver = ContentArchiver::new(&ContentArchiveConfig::default(), blob_storage);
+        let root_content_id = internal_api::ContentMetadataId::new_with_version("root", 2);
+        let archive = ContentArchive {
+            namespace: "default".to_string(),
+            extraction_graph_names: vec!["graph".to_string()],
+            root_content_id: root_content_id.clone(),
+            archived_at: 1,
+            context: HashMap::from([("user".to_string(), serde_json::json!("u1"))]),
+            tasks: vec![internal_api::Task {
+                id: "task_1".to_string(),
+                outcome: internal_api::TaskOutcome::Success,
+                ..Default::default()
+            }],
+            content: vec![internal_api::ContentMetadata {
+                id: root_content_id,
+                storage_url: "file:///tmp/root".to_string(),
+                ..Default::default()
+            }],
+        };
+
+        let url = archiver.archive(&archive).await.unwrap();
+        assert!(url.ends_with("archive/default/graph/root_2.json.gz"));
+        let file = std::fs::File::open(url.trim_start_matches("file://")).unwrap();
+        let mut json = String::new();
+        GzDecoder::new(file).read_to_string(&mut json).unwrap();
+        let restored: ContentArchive = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.context, archive.context);
+        assert_eq!(restored.tasks[0].id, "task_1");
+        assert_eq!(restored.content[0].storage_url, "file:///tmp/root");
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use sha2::{Digest, Sha256};
+
+use crate::server_config::ContentHookConfig;
+
+const DEFAULT_REDACTION: &str = "[REDACTED]";
+
+/// A hook that redacts or transforms the labels of ingested content before the
+/// coordinator stores them. Labels are copied to the content extracted from
+/// the ingested content and to the indexes, so a label changed by a hook
+/// never shows up in either.
+pub trait ContentHook: Send + Sync {
+    fn apply(&self, labels: &mut HashMap<String, String>);
+}
+
+/// Removes labels
+struct DropLabels {
+    labels: Vec<String>,
+}
+
+impl ContentHook for DropLabels {
+    fn apply(&self, labels: &mut HashMap<String, String>) {
+        for label in &self.labels {
+            labels.remove(label);
+        }
+    }
+}
+
+/// Replaces the value of labels, keeping the fact that they were set
+struct RedactLabels {
+    labels: Vec<String>,
+    replacement: String,
+}
+
+impl ContentHook for RedactLabels {
+    fn apply(&self, labels: &mut HashMap<String, String>) {
+        for label in &self.labels {
+            if let Some(value) = labels.get_mut(label) {
+                *value = self.replacement.clone();
+            }
+        }
+    }
+}
+
+/// Replaces the parts of every label value that match a pattern, like email
+/// addresses or phone numbers
+struct RedactPattern {
+    pattern: Regex,
+    replacement: String,
+}
+
+impl ContentHook for RedactPattern {
+    fn apply(&self, labels: &mut HashMap<String, String>) {
+        for value in labels.values_mut() {
+            if self.pattern.is_match(value) {
+                *value = self
+                    .pattern
+                    .replace_all(value, self.replacement.as_str())
+                    .into_owned();
+            }
+        }
+    }
+}
+
+/// Replaces the value of labels with its SHA-256 digest, so content can still
+/// be filtered and grouped by them without storing the value
+struct HashLabels {
+    labels: Vec<String>,
+}
+
+impl ContentHook for HashLabels {
+    fn apply(&self, labels: &mut HashMap<String, String>) {
+        for label in &self.labels {
+            if let Some(value) = labels.get_mut(label) {
+                *value = format!("{:x}", Sha256::digest(value.as_bytes()));
+            }
+        }
+    }
+}
+
+/// The hooks of the config, applied in order
+#[derive(Default)]
+pub struct ContentHooks {
+    hooks: Vec<Box<dyn ContentHook>>,
+}
+
+impl ContentHooks {
+    pub fn from_config(configs: &[ContentHookConfig]) -> Result<Self> {
+        let hooks = configs
+            .iter()
+            .map(|config| -> Result<Box<dyn ContentHook>> {
+                Ok(match config {
+                    ContentHookConfig::DropLabels { labels } => Box::new(DropLabels {
+                        labels: labels.clone(),
+                    }),
+                    ContentHookConfig::RedactLabels {
+                        labels,
+                        replacement,
+                    } => Box::new(RedactLabels {
+                        labels: labels.clone(),
+                        replacement: replacement
+                            .clone()
+                            .unwrap_or_else(|| DEFAULT_REDACTION.to_string()),
+                    }),
+                    ContentHookConfig::RedactPattern {
+                        pattern,
+                        replacement,
+                    } => Box::new(RedactPattern {
+                        pattern: Regex::new(pattern)
+                            .map_err(|e| anyhow!("invalid redaction pattern {}: {}", pattern, e))?,
+                        replacement: replacement
+                            .clone()
+                            .unwrap_or_else(|| DEFAULT_REDACTION.to_string()),
+                    }),
+                    ContentHookConfig::HashLabels { labels } => Box::new(HashLabels {
+                        labels: labels.clone(),
+                    }),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { hooks })
+    }
+
+    pub fn apply(&self, labels: &mut HashMap<String, String>) {
+        for hook in &self.hooks {
+            hook.apply(labels);
+        }
+    }
+
+    /// Applies the hooks to the labels of extracted content that it didn't
+    /// inherit from its parent. The inherited labels already went through the
+    /// hooks, and hashing them again would change them.
+    pub fn apply_to_extracted(
+        &self,
+        labels: &mut HashMap<String, String>,
+        inherited: &HashMap<String, String>,
+    ) {
+        let (mut added, kept): (HashMap<_, _>, HashMap<_, _>) = std::mem::take(labels)
+            .into_iter()
+            .partition(|(name, value)| inherited.get(name) != Some(value));
+        self.apply(&mut added);
+        *labels = kept;
+        labels.extend(added);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_hooks() {
+        let hooks = ContentHooks::from_config(&[
+            ContentHookConfig::DropLabels {
+                labels: vec!["ssn".to_string()],
+            },
+            ContentHookConfig::RedactLabels {
+                labels: vec!["name".to_string()],
+                replacement: None,
+            },
+            ContentHookConfig::RedactPattern {
+                pattern: r"[\w.+-]+@[\w-]+\.[\w.]+".to_string(),
+                replacement: Some("[email]".to_string()),
+            },
+            ContentHookConfig::HashLabels {
+                labels: vec!["user_id".to_string()],
+            },
+        ])
+        .unwrap();
+        let mut labels = HashMap::from([
+            ("ssn".to_string(), "078-05-1120".to_string()),
+            ("name".to_string(), "Jane Doe".to_string()),
+            (
+                "note".to_string(),
+                "sent by jane@example.com to bob@example.org".to_string(),
+            ),
+            ("user_id".to_string(), "42".to_string()),
+            ("topic".to_string(), "invoices".to_string()),
+        ]);
+        hooks.apply(&mut labels);
+        assert_eq!(
+            labels,
+            HashMap::from([
+                ("name".to_string(), DEFAULT_REDACTION.to_string()),
+                ("note".to_string(), "sent by [email] to [email]".to_string()),
+                (
+                    "user_id".to_string(),
+                    "73475cb40a568e8da8a045ced110137e159f890ac4da883b6b17dc651b3a8049".to_string()
+                ),
+                ("topic".to_string(), "invoices".to_string()),
+            ])
+        );
+
+        //  Only the labels extracted content didn't inherit go through the
+        //  hooks
+        let inherited = labels.clone();
+        let mut extracted = inherited.clone();
+        extracted.insert("name".to_string(), "John Doe".to_string());
+        hooks.apply_to_extracted(&mut extracted, &inherited);
+        assert_eq!(extracted["user_id"], inherited["user_id"]);
+        assert_eq!(extracted["name"], DEFAULT_REDACTION);
+
+        assert!(
+            ContentHooks::from_config(&[ContentHookConfig::RedactPattern {
+                pattern: "(".to_string(),
+                replacement: None,
+            }])
+            .is_err()
+        );
+    }
+}
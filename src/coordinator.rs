@@ -1,43 +1,135 @@
 use std::{
-    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    collections::{
+        hash_map::{DefaultHasher, Entry},
+        HashMap,
+        HashSet,
+    },
     hash::{Hash, Hasher},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+        Weak,
+    },
+    time::{Duration, Instant},
     vec,
 };
 
 use anyhow::{anyhow, Ok, Result};
+use futures::Stream;
 use indexify_internal_api as internal_api;
 use indexify_proto::indexify_coordinator::{self, CreateContentStatus};
 use internal_api::{
     ContentMetadataId,
     ExtractionGraph,
+    ExtractionGraphChange,
     ExtractionPolicyId,
     GarbageCollectionTask,
     OutputSchema,
+    SchedulerSettings,
     ServerTaskType,
     StateChange,
+    StateChangeId,
     StructuredDataSchema,
+    UnschedulableReason,
 };
+use strum::IntoEnumIterator;
 use tokio::sync::{broadcast, watch::Receiver};
-use tracing::{debug, info};
+use tracing::{debug, error, info, warn};
 
 use crate::{
+    alerting::AlertObservation,
+    chaos::ChaosInjector,
+    content_archive::{ContentArchive, ContentArchiver},
     coordinator_client::CoordinatorClient,
     coordinator_filters::*,
     forwardable_coordinator::ForwardableCoordinator,
     garbage_collector::GarbageCollector,
     metrics::Timer,
-    scheduler::Scheduler,
-    state::{store::requests::StateChangeProcessed, RaftMetrics, SharedState},
-    task_allocator::TaskAllocator,
+    namespace_archive::{self, ImportReport, NamespaceArchive},
+    scheduler::{self, Scheduler},
+    server_config::{BackupConfig, DiagnosticsConfig, SchedulerConfig},
+    state::{
+        notifier::ChangeWatcher,
+        store::{
+            archive::StateArchiver,
+            requests::StateChangeProcessed,
+            StateMachineColumns,
+            TaskId,
+        },
+        RaftMetrics,
+        SharedState,
+    },
+    task_allocator::{
+        planner::load_aware_distributor::LoadAwareDistributor,
+        simulation,
+        TaskAllocator,
+    },
+    task_bundler::TaskBundler,
+    task_canceller::{CancelTasksFilter, TaskCanceller},
     utils,
 };
 
+// Number of shards content state changes are split into by the scheduler.
+const SCHEDULER_SHARDS: usize = 8;
+
+// Tasks whose diagnostics are dropped per raft entry.
+const PRUNE_DIAGNOSTICS_BATCH_SIZE: usize = 1000;
+
+// Seconds a task assignment has to keep disagreeing with its task before it
+// is reaped, so that assignments racing with the update of their task are
+// left alone.
+const ORPHANED_ASSIGNMENT_GRACE_SECS: u64 = 60;
+
+// Keys deleted from a column family before their range is compacted. Fewer
+// tombstones are left to regular compactions.
+const COMPACTION_MIN_DELETIONS: u64 = 1000;
+
+// Number of different executors a task can be lost with before it is
+// quarantined instead of being allocated again.
+const MAX_LOST_TASK_ATTEMPTS: usize = 3;
+
+// Limits of the key value context of a root content. The size of a context is
+// the size of its keys and of their JSON encoded values.
+const MAX_CONTENT_CONTEXT_KEY_BYTES: usize = 256;
+const MAX_CONTENT_CONTEXT_VALUE_BYTES: usize = 64 * 1024;
+const MAX_CONTENT_CONTEXT_BYTES: usize = 1024 * 1024;
+
 pub struct Coordinator {
     pub shared_state: SharedState,
     scheduler: Scheduler,
     garbage_collector: Arc<GarbageCollector>,
     forwardable_coordinator: ForwardableCoordinator,
+    task_canceller: Arc<TaskCanceller>,
+    /// Set when garbage collection of content was put off because its
+    /// archive couldn't be written, it's tried again on the next tick
+    deferred_gc: AtomicBool,
+    /// Set when garbage collection of content was put off until enough of
+    /// the pending garbage collection tasks finish
+    gc_waiting_for_capacity: AtomicBool,
+    /// Set when garbage collection of content was put off until the upload
+    /// of its archive finishes
+    gc_waiting_for_archive: AtomicBool,
+    /// Set when the scheduler skipped state changes because the cluster was in
+    /// maintenance
+    paused_for_maintenance: AtomicBool,
+    /// Set when a pass of the scheduler left state changes over its batch
+    /// size
+    state_changes_left: AtomicBool,
+    /// Extraction policies and objectives whose error budget was burning past
+    /// the alert burn rate on the last check
+    slo_alerts: std::sync::Mutex<HashSet<(ExtractionPolicyId, &'static str)>>,
+    /// Time orphaned task assignments and unallocated tasks were first found,
+    /// while they keep being found
+    orphans_seen: std::sync::Mutex<HashMap<TaskId, u64>>,
+    /// Time finished tasks with diagnostics but no known finish time were
+    /// first found, which their retention starts from
+    diagnostics_seen: std::sync::Mutex<HashMap<TaskId, u64>>,
+    scheduler_config: SchedulerConfig,
+    /// Archives content trees before they are deleted, when configured
+    content_archiver: Option<Arc<ContentArchiver>>,
+    /// The coordinator itself, which the workers of the scheduler shards hold
+    /// while they run
+    this: Weak<Coordinator>,
 }
 
 impl Coordinator {
@@ -45,15 +137,34 @@ impl Coordinator {
         shared_state: SharedState,
         coordinator_client: CoordinatorClient,
         garbage_collector: Arc<GarbageCollector>,
+        content_archiver: Option<ContentArchiver>,
+        scheduler_config: SchedulerConfig,
     ) -> Arc<Self> {
         let task_allocator = TaskAllocator::new(shared_state.clone());
-        let scheduler = Scheduler::new(shared_state.clone(), task_allocator);
+        let scheduler = Scheduler::new(
+            shared_state.clone(),
+            task_allocator,
+            scheduler_config.max_tasks_per_pass,
+        );
         let forwardable_coordinator = ForwardableCoordinator::new(coordinator_client);
-        Arc::new(Self {
+        let task_canceller = TaskCanceller::new(shared_state.clone());
+        Arc::new_cyclic(|this| Self {
             shared_state,
             scheduler,
             garbage_collector,
             forwardable_coordinator,
+            task_canceller,
+            deferred_gc: AtomicBool::new(false),
+            gc_waiting_for_capacity: AtomicBool::new(false),
+            gc_waiting_for_archive: AtomicBool::new(false),
+            paused_for_maintenance: AtomicBool::new(false),
+            state_changes_left: AtomicBool::new(false),
+            slo_alerts: std::sync::Mutex::new(HashSet::new()),
+            orphans_seen: std::sync::Mutex::new(HashMap::new()),
+            diagnostics_seen: std::sync::Mutex::new(HashMap::new()),
+            scheduler_config,
+            content_archiver: content_archiver.map(Arc::new),
+            this: this.clone(),
         })
     }
 
@@ -91,6 +202,23 @@ impl Coordinator {
             .await
     }
 
+    /// Streams the content `list_content` returns as it is read, with the key
+    /// to resume the stream after each content
+    pub fn stream_content(
+        &self,
+        namespace: &str,
+        source: &str,
+        parent_id: &str,
+        labels_eq: HashMap<String, String>,
+        cursor: Option<String>,
+    ) -> impl Stream<Item = Result<(String, internal_api::ContentMetadata)>> + Send + 'static {
+        let source = source.to_string();
+        self.shared_state
+            .stream_content(namespace, parent_id, cursor, move |c| {
+                content_filter(c, &source, &labels_eq)
+            })
+    }
+
     pub async fn update_labels(
         &self,
         namespace: &str,
@@ -128,17 +256,562 @@ impl Coordinator {
         task_id: &str,
         executor_id: &str,
         outcome: internal_api::TaskOutcome,
+        diagnostics: Option<String>,
+        usage: Option<internal_api::TaskUsage>,
     ) -> Result<()> {
         info!(
             "updating task: {}, executor_id: {}, outcome: {:?}",
             task_id, executor_id, outcome
         );
         let mut task = self.shared_state.task_with_id(task_id).await?;
+        if task.terminal_state() {
+            // The task was cancelled while the executor was working on it.
+            info!("ignoring update for finished task: {}", task_id);
+            return Ok(());
+        }
+        // The executor was removed and the task was allocated again, the update
+        // comes from an attempt that no longer owns the task
+        if !self
+            .shared_state
+            .is_task_assigned_to(task_id, executor_id)?
+        {
+            return Err(anyhow!(
+                "task {} is not assigned to executor {}, rejecting update from a stale attempt",
+                task_id,
+                executor_id
+            ));
+        }
         task.outcome = outcome;
+        if diagnostics.is_some() {
+            task.diagnostics = diagnostics;
+        }
+        task.usage = usage;
         self.shared_state
             .update_task(task, Some(executor_id.to_string()))
             .await?;
-        Ok(())
+        self.remove_drained_executor(executor_id).await
+    }
+
+    /// Finalizes the tasks of a bundle in a single write. Outcomes of tasks
+    /// that are no longer assigned to the executor are dropped, and their ids
+    /// returned.
+    pub async fn update_tasks(
+        &self,
+        executor_id: &str,
+        finished_tasks: Vec<indexify_coordinator::FinishedTask>,
+    ) -> Result<Vec<String>> {
+        let mut tasks = Vec::new();
+        let mut rejected_task_ids = Vec::new();
+        for finished_task in finished_tasks {
+            let outcome = finished_task.outcome().into();
+            let task = self.shared_state.task_with_id(&finished_task.task_id).await;
+            let Some(mut task) = task.ok() else {
+                rejected_task_ids.push(finished_task.task_id);
+                continue;
+            };
+            if task.terminal_state() {
+                info!("ignoring update for finished task: {}", task.id);
+                continue;
+            }
+            if !self
+                .shared_state
+                .is_task_assigned_to(&task.id, executor_id)?
+            {
+                rejected_task_ids.push(task.id);
+                continue;
+            }
+            task.outcome = outcome;
+            if !finished_task.diagnostics.is_empty() {
+                task.diagnostics = Some(finished_task.diagnostics);
+            }
+            task.usage = finished_task.usage.map(Into::into);
+            tasks.push(task);
+        }
+        info!(
+            "finalizing {} tasks of executor {}, rejected {}",
+            tasks.len(),
+            executor_id,
+            rejected_task_ids.len()
+        );
+        if !tasks.is_empty() {
+            self.shared_state.update_tasks(tasks, executor_id).await?;
+        }
+        self.remove_drained_executor(executor_id).await?;
+        Ok(rejected_task_ids)
+    }
+
+    pub async fn cancel_tasks(&self, namespace: &str, filter: CancelTasksFilter) -> Result<String> {
+        self.task_canceller.start(namespace, filter).await
+    }
+
+    pub fn get_cancel_tasks_job(
+        &self,
+        job_id: &str,
+    ) -> Result<Option<internal_api::CancelTasksJob>> {
+        self.shared_state.get_cancel_tasks_job(job_id)
+    }
+
+    /// Forgets the cancel tasks jobs past their retention. Returns the number
+    /// of jobs forgotten.
+    pub async fn evict_cancel_tasks_jobs(&self, now: u64) -> Result<usize> {
+        self.task_canceller.evict_jobs(now).await
+    }
+
+    /// Aborts the running cancel tasks jobs of coordinators that stopped.
+    /// Returns the number of jobs aborted.
+    pub async fn abort_stale_cancel_tasks_jobs(&self, now: u64) -> Result<usize> {
+        self.task_canceller.abort_stale_jobs(now).await
+    }
+
+    /// Runs an extraction policy that already completed on a content again.
+    /// Outputs of the previous run are tombstoned, and the outputs of the new
+    /// run flow to the downstream policies like newly extracted content.
+    /// Returns the id of the replay.
+    pub async fn rerun_extraction_policy(
+        &self,
+        namespace: &str,
+        content_id: &str,
+        extraction_graph: &str,
+        extraction_policy: &str,
+        initiator: &str,
+    ) -> Result<String> {
+        let content = self
+            .shared_state
+            .state_machine
+            .get_latest_version_of_content(content_id)?
+            .filter(|c| c.namespace == namespace && !c.tombstoned)
+            .ok_or_else(|| anyhow!("content {} not found", content_id))?;
+        let extraction_policy = self
+            .shared_state
+            .get_extraction_graphs_by_name(namespace, &[extraction_graph.to_string()])?
+            .pop()
+            .flatten()
+            .ok_or_else(|| anyhow!("extraction graph {} not found", extraction_graph))?
+            .extraction_policies
+            .into_iter()
+            .find(|p| p.name == extraction_policy)
+            .ok_or_else(|| anyhow!("extraction policy {} not found", extraction_policy))?;
+        let completed = content
+            .extraction_policy_ids
+            .get(&extraction_policy.id)
+            .map_or(false, |completion_time| *completion_time > 0);
+        if !completed {
+            return Err(anyhow!(
+                "extraction policy {} has not completed on content {}",
+                extraction_policy.name,
+                content_id
+            ));
+        }
+
+        let replay = self
+            .start_replay(
+                namespace,
+                internal_api::ReplayKind::ExtractionPolicy,
+                content_id,
+                initiator,
+                vec![self.replay_task(
+                    scheduler::task_id(&extraction_policy, &content.id),
+                    &content,
+                    &extraction_policy,
+                )?],
+            )
+            .await?;
+        self.rerun_on_content(&content, &extraction_policy).await?;
+        Ok(replay.id)
+    }
+
+    fn replay_task(
+        &self,
+        task_id: String,
+        content: &internal_api::ContentMetadata,
+        extraction_policy: &internal_api::ExtractionPolicy,
+    ) -> Result<internal_api::ReplayTask> {
+        let graph_version = self
+            .shared_state
+            .get_extraction_graph_version(&internal_api::ExtractionGraph::create_id(
+                &extraction_policy.graph_name,
+                &extraction_policy.namespace,
+            ))?
+            .unwrap_or(1);
+        Ok(internal_api::ReplayTask {
+            task_id,
+            content_id: content.id.id.clone(),
+            extraction_graph: extraction_policy.graph_name.clone(),
+            extraction_policy: extraction_policy.name.clone(),
+            graph_version,
+            outcome: internal_api::TaskOutcome::Unknown,
+            replaced_by: None,
+            outputs: Vec::new(),
+        })
+    }
+
+    /// Records a replay before its tasks run again, so that the outcomes of
+    /// the tasks are recorded in it
+    async fn start_replay(
+        &self,
+        namespace: &str,
+        kind: internal_api::ReplayKind,
+        content_id: &str,
+        initiator: &str,
+        tasks: Vec<internal_api::ReplayTask>,
+    ) -> Result<internal_api::Replay> {
+        let replay = internal_api::Replay {
+            id: nanoid::nanoid!(16),
+            namespace: namespace.to_string(),
+            kind,
+            content_id: content_id.to_string(),
+            initiator: initiator.to_string(),
+            started_at: utils::timestamp_secs(),
+            finished_at: None,
+            tasks,
+        };
+        info!(
+            "starting {} replay {} of {} tasks on content {}",
+            replay.kind,
+            replay.id,
+            replay.tasks.len(),
+            content_id
+        );
+        self.shared_state.create_replay(replay.clone()).await?;
+        Ok(replay)
+    }
+
+    pub fn list_replays(
+        &self,
+        namespace: &str,
+        extraction_graph: Option<&str>,
+        content_id: Option<&str>,
+    ) -> Result<Vec<internal_api::Replay>> {
+        Ok(self
+            .shared_state
+            .list_replays(namespace)?
+            .into_iter()
+            .filter(|replay| {
+                extraction_graph.map_or(true, |graph| {
+                    replay
+                        .tasks
+                        .iter()
+                        .any(|task| task.extraction_graph == graph)
+                })
+            })
+            .filter(|replay| content_id.map_or(true, |id| replay.content_id == id))
+            .collect())
+    }
+
+    /// A replay and the content its tasks extracted that is still live. The
+    /// outputs of a task are recorded in the replay as they are created.
+    pub async fn get_replay(
+        &self,
+        namespace: &str,
+        replay_id: &str,
+    ) -> Result<(internal_api::Replay, Vec<String>)> {
+        let replay = self
+            .shared_state
+            .get_replay(replay_id)?
+            .filter(|replay| replay.namespace == namespace)
+            .ok_or_else(|| anyhow!("replay {} not found", replay_id))?;
+        let outputs = replay
+            .tasks
+            .iter()
+            .filter(|task| task.replaced_by.is_none())
+            .flat_map(|task| task.outputs.iter().cloned())
+            .collect();
+        let outputs = self
+            .shared_state
+            .get_content_metadata_batch(outputs)
+            .await?
+            .into_iter()
+            .filter(|c| !c.tombstoned)
+            .map(|c| c.id.id)
+            .collect();
+        Ok((replay, outputs))
+    }
+
+    /// Tombstones the outputs a previous run of the extraction policy left on
+    /// the content and runs the policy on it again
+    async fn rerun_on_content(
+        &self,
+        content: &internal_api::ContentMetadata,
+        extraction_policy: &internal_api::ExtractionPolicy,
+    ) -> Result<()> {
+        let source =
+            internal_api::ContentSource::ExtractionPolicyName(extraction_policy.name.clone());
+        let previous_outputs: Vec<String> = self
+            .shared_state
+            .get_content_tree_metadata(&content.id.id)?
+            .into_iter()
+            .filter(|c| {
+                c.parent_id.as_ref() == Some(&content.id) && c.source == source && !c.tombstoned
+            })
+            .map(|c| c.id.id)
+            .collect();
+        if !previous_outputs.is_empty() {
+            self.shared_state
+                .tombstone_content_batch(&previous_outputs)
+                .await?;
+        }
+        self.shared_state
+            .rerun_extraction_policy(&content.id, &extraction_policy.id)
+            .await
+    }
+
+    /// Runs the extraction policies that failed on the content tree rooted at
+    /// `content_id` again. Outputs of the policies that succeeded are kept,
+    /// and the policies downstream of a failed one run on its new outputs as
+    /// they are extracted. Returns the replay of the failed tasks, none when
+    /// no task failed.
+    pub async fn rerun_failed_tasks(
+        &self,
+        namespace: &str,
+        content_id: &str,
+        initiator: &str,
+    ) -> Result<Option<internal_api::Replay>> {
+        let tree = self.shared_state.get_content_tree_metadata(content_id)?;
+        let root = tree
+            .first()
+            .filter(|root| root.namespace == namespace && !root.tombstoned)
+            .ok_or_else(|| anyhow!("content {} not found", content_id))?;
+        if root.parent_id.is_some() {
+            return Err(anyhow!(
+                "content {} is not a root content, rerun the failed tasks of its root {} instead",
+                content_id,
+                root.get_root_id()
+            ));
+        }
+        let contents: HashMap<&str, &internal_api::ContentMetadata> = tree
+            .iter()
+            .filter(|c| !c.tombstoned)
+            .map(|c| (c.id.id.as_str(), c))
+            .collect();
+        let failed_tasks: Vec<internal_api::Task> = self
+            .shared_state
+            .list_tasks(namespace, None)
+            .await?
+            .into_iter()
+            .filter(|task| {
+                matches!(
+                    task.outcome,
+                    internal_api::TaskOutcome::Failed | internal_api::TaskOutcome::Quarantined
+                )
+            })
+            .filter(|task| contents.contains_key(task.content_metadata.id.id.as_str()))
+            .collect();
+        info!(
+            "rerunning {} failed tasks of content {}",
+            failed_tasks.len(),
+            content_id
+        );
+        if failed_tasks.is_empty() {
+            return Ok(None);
+        }
+        let mut reruns = Vec::new();
+        let mut replay_tasks = Vec::new();
+        for task in &failed_tasks {
+            let extraction_policy = self
+                .shared_state
+                .get_extraction_policy(&task.extraction_policy_id)?;
+            let content = contents[task.content_metadata.id.id.as_str()];
+            replay_tasks.push(self.replay_task(task.id.clone(), content, &extraction_policy)?);
+            reruns.push((content, extraction_policy));
+        }
+        let replay = self
+            .start_replay(
+                namespace,
+                internal_api::ReplayKind::FailedTasks,
+                content_id,
+                initiator,
+                replay_tasks,
+            )
+            .await?;
+        for (content, extraction_policy) in &reruns {
+            self.rerun_on_content(content, extraction_policy).await?;
+        }
+        Ok(Some(replay))
+    }
+
+    /// Creates a graph template, or replaces the template with its name.
+    /// Graphs instantiated from the template earlier keep their definition.
+    pub async fn set_graph_template(
+        &self,
+        mut template: internal_api::GraphTemplate,
+    ) -> Result<internal_api::GraphTemplate> {
+        template.validate()?;
+        template.created_at = utils::timestamp_secs();
+        self.shared_state
+            .set_graph_template(template.clone())
+            .await?;
+        Ok(template)
+    }
+
+    pub fn list_graph_templates(&self) -> Result<Vec<internal_api::GraphTemplate>> {
+        self.shared_state.list_graph_templates()
+    }
+
+    pub fn get_graph_template(&self, name: &str) -> Result<internal_api::GraphTemplate> {
+        self.shared_state
+            .get_graph_template(name)?
+            .ok_or_else(|| anyhow!("graph template {} not found", name))
+    }
+
+    pub async fn delete_graph_template(&self, name: &str) -> Result<()> {
+        self.get_graph_template(name)?;
+        self.shared_state.delete_graph_template(name).await
+    }
+
+    /// Last extraction policies of the graphs, policies whose output no other
+    /// policy of the graph extracts from, by namespace and graph. Only the
+    /// graphs of `extraction_graphs` are considered when it isn't empty.
+    pub async fn replicated_policies(
+        &self,
+        extraction_graphs: &[String],
+    ) -> Result<HashSet<(String, String, String)>> {
+        let mut terminal_policies = HashSet::new();
+        for namespace in self.shared_state.list_namespaces().await? {
+            for graph in namespace.extraction_graphs {
+                if !extraction_graphs.is_empty() && !extraction_graphs.contains(&graph.name) {
+                    continue;
+                }
+                let parents: HashSet<&str> = graph
+                    .extraction_policies
+                    .iter()
+                    .filter_map(|policy| match &policy.content_source {
+                        internal_api::ExtractionPolicyContentSource::ExtractionPolicyName(
+                            parent,
+                        ) => Some(parent.as_str()),
+                        internal_api::ExtractionPolicyContentSource::Ingestion => None,
+                    })
+                    .collect();
+                for policy in &graph.extraction_policies {
+                    if !parents.contains(policy.name.as_str()) {
+                        terminal_policies.insert((
+                            namespace.name.clone(),
+                            graph.name.clone(),
+                            policy.name.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(terminal_policies)
+    }
+
+    /// Content whose copy in another region may be out of date, up to
+    /// `limit`: extracted content that was created or changed, and deleted
+    /// content that has a replica
+    pub fn pending_replications(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<internal_api::PendingReplication>> {
+        self.shared_state.pending_replications(limit)
+    }
+
+    pub async fn update_content_replicas(
+        &self,
+        added: Vec<internal_api::ContentReplica>,
+        removed: Vec<String>,
+        processed: HashMap<String, Option<String>>,
+    ) -> Result<()> {
+        self.shared_state
+            .update_content_replicas(added, removed, processed)
+            .await
+    }
+
+    pub fn get_content_replica(
+        &self,
+        namespace: &str,
+        content_id: &str,
+    ) -> Result<internal_api::ContentReplica> {
+        self.shared_state
+            .get_content_replica(content_id)?
+            .filter(|replica| replica.namespace == namespace)
+            .ok_or_else(|| anyhow!("content {} has no replica", content_id))
+    }
+
+    /// Time and estimated cost of an invocation, the content tree rooted at
+    /// `content_id`, by extraction policy. Costs are estimated from the cost
+    /// per hour the executors that ran the tasks registered with.
+    pub async fn get_invocation_breakdown(
+        &self,
+        namespace: &str,
+        content_id: &str,
+    ) -> Result<internal_api::InvocationBreakdown> {
+        let tree = self.shared_state.get_content_tree_metadata(content_id)?;
+        let root = tree
+            .first()
+            .filter(|root| root.namespace == namespace && !root.tombstoned)
+            .ok_or_else(|| anyhow!("content {} not found", content_id))?;
+        if root.parent_id.is_some() {
+            return Err(anyhow!(
+                "content {} is not a root content, get the breakdown of its root {} instead",
+                content_id,
+                root.get_root_id()
+            ));
+        }
+        let content_ids: HashSet<&str> = tree.iter().map(|c| c.id.id.as_str()).collect();
+        let costs: HashMap<String, f64> = self
+            .shared_state
+            .get_executors()
+            .await?
+            .into_iter()
+            .filter_map(|executor| Some((executor.id, executor.cost_per_hour?)))
+            .collect();
+        let now = utils::timestamp_secs();
+        let mut breakdowns: HashMap<String, internal_api::ExtractionPolicyBreakdown> =
+            HashMap::new();
+        for task in self.shared_state.list_tasks(namespace, None).await? {
+            if !content_ids.contains(task.content_metadata.id.id.as_str()) {
+                continue;
+            }
+            let transitions = self.shared_state.get_task_transitions(&task.id)?;
+            let breakdown = match breakdowns.entry(task.extraction_policy_id.clone()) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    //  Tasks of policies removed from their graph are broken
+                    //  down under the id of their policy
+                    let extraction_policy = self
+                        .shared_state
+                        .state_machine
+                        .get_from_cf::<internal_api::ExtractionPolicy, _>(
+                        StateMachineColumns::ExtractionPolicies,
+                        &task.extraction_policy_id,
+                    )?;
+                    entry.insert(match extraction_policy {
+                        Some(extraction_policy) => internal_api::ExtractionPolicyBreakdown {
+                            extraction_graph: extraction_policy.graph_name,
+                            extraction_policy: extraction_policy.name,
+                            ..Default::default()
+                        },
+                        None => internal_api::ExtractionPolicyBreakdown {
+                            extraction_graph: task.extraction_graph_name.clone(),
+                            extraction_policy: task.extraction_policy_id.clone(),
+                            ..Default::default()
+                        },
+                    })
+                }
+            };
+            breakdown.add(
+                &task,
+                &transitions,
+                |executor_id| costs.get(executor_id).copied(),
+                now,
+            );
+        }
+        let mut extraction_policies: Vec<_> = breakdowns.into_values().collect();
+        extraction_policies.sort_by(|a, b| {
+            (a.started_at, &a.extraction_policy).cmp(&(b.started_at, &b.extraction_policy))
+        });
+        let started_at = root.created_at.max(0) as u64;
+        Ok(internal_api::InvocationBreakdown {
+            namespace: namespace.to_string(),
+            content_id: content_id.to_string(),
+            started_at,
+            finished_at: extraction_policies
+                .iter()
+                .map(|breakdown| breakdown.finished_at)
+                .max()
+                .unwrap_or(started_at),
+            extraction_policies,
+        })
     }
 
     pub async fn update_gc_task(
@@ -172,20 +845,269 @@ impl Coordinator {
         self.shared_state.namespace(namespace).await
     }
 
+    /// Packages every version of the extraction graphs of a namespace and the
+    /// latest version of the content ingested into it that isn't being
+    /// deleted
+    pub async fn export_namespace(&self, namespace: &str) -> Result<NamespaceArchive> {
+        let graphs = self
+            .get_namespace(namespace)
+            .await?
+            .ok_or_else(|| anyhow!("namespace {} not found", namespace))?
+            .extraction_graphs;
+        let extraction_graphs = graphs
+            .iter()
+            .map(|graph| self.list_extraction_graph_versions(namespace, &graph.name))
+            .collect::<Result<Vec<_>>>()?;
+        let content = self
+            .shared_state
+            .list_content(namespace, "", |c| {
+                !c.tombstoned && c.latest && c.parent_id.is_none()
+            })
+            .await?;
+        Ok(NamespaceArchive {
+            format_version: namespace_archive::FORMAT_VERSION,
+            namespace: namespace.to_string(),
+            exported_at: utils::timestamp_secs(),
+            extraction_graphs,
+            content,
+        })
+    }
+
+    /// Creates the graphs and content of an archive, or of a batch of it, in
+    /// `namespace`. The versions of each graph are replayed in order, and the
+    /// content is created with new ids, so the graphs extract it again.
+    ///
+    /// Imports can be run again after they stopped: versions of a graph that
+    /// are already in the namespace are skipped and content that was already
+    /// imported is left as it is. A graph of the namespace with a different
+    /// history is not overwritten, the import fails before writing anything
+    /// instead. Returns the latest version of every graph with its indexes,
+    /// whose tables the server creates.
+    pub async fn import_namespace(
+        &self,
+        namespace: &str,
+        archive: NamespaceArchive,
+    ) -> Result<(
+        ImportReport,
+        Vec<(ExtractionGraph, Vec<internal_api::Index>)>,
+    )> {
+        if archive.format_version > namespace_archive::FORMAT_VERSION {
+            return Err(anyhow!(
+                "archive format {} is newer than the supported format {}",
+                archive.format_version,
+                namespace_archive::FORMAT_VERSION
+            ));
+        }
+        let (archive, content_ids) = archive.remap(namespace);
+        let mut imported_versions = Vec::new();
+        for versions in &archive.extraction_graphs {
+            let Some(version) = versions.first() else {
+                imported_versions.push(0);
+                continue;
+            };
+            let name = &version.extraction_graph.name;
+            let existing = self.list_extraction_graph_versions(namespace, name)?;
+            if existing.len() > versions.len() ||
+                existing.iter().zip(versions).any(|(existing, version)| {
+                    existing.extraction_graph != version.extraction_graph
+                })
+            {
+                return Err(anyhow!(
+                    "extraction graph {} already exists in namespace {}",
+                    name,
+                    namespace
+                ));
+            }
+            imported_versions.push(existing.len());
+        }
+        self.create_namespace(namespace).await?;
+
+        let mut report = ImportReport {
+            content_ids,
+            ..Default::default()
+        };
+        let mut graphs = Vec::new();
+        for (versions, imported) in archive.extraction_graphs.into_iter().zip(imported_versions) {
+            let Some(latest) = versions
+                .last()
+                .map(|version| version.extraction_graph.clone())
+            else {
+                continue;
+            };
+            let mut indexes = None;
+            for (i, version) in versions.into_iter().enumerate().skip(imported) {
+                let change = ExtractionGraphChange {
+                    author: version.author,
+                    summary: version.summary,
+                    created_at: 0,
+                };
+                indexes = Some(
+                    self.save_extraction_graph(version.extraction_graph, change, i > 0)
+                        .await?,
+                );
+                report.extraction_graph_versions += 1;
+            }
+            //  Graphs imported before report their indexes without a new version
+            let indexes = match indexes {
+                Some(indexes) => indexes,
+                None => {
+                    self.save_extraction_graph(latest.clone(), Default::default(), true)
+                        .await?
+                }
+            };
+            graphs.push((latest, indexes));
+            report.extraction_graphs += 1;
+        }
+        report.content = archive.content.len();
+        for content in archive.content.chunks(namespace_archive::IMPORT_BATCH_SIZE) {
+            self.shared_state
+                .create_content_batch(content.to_vec())
+                .await?;
+        }
+        Ok((report, graphs))
+    }
+
     pub async fn list_extractors(&self) -> Result<Vec<internal_api::ExtractorDescription>> {
         self.shared_state.list_extractors().await
     }
 
-    pub async fn heartbeat(&self, executor_id: &str) -> Result<Vec<indexify_coordinator::Task>> {
+    /// Registered executors with the number of tasks assigned to them
+    pub async fn list_executors(&self) -> Result<Vec<indexify_coordinator::Executor>> {
+        let mut running_tasks: HashMap<String, u64> = HashMap::new();
+        for executor_id in self.shared_state.task_assignments().await?.into_values() {
+            *running_tasks.entry(executor_id).or_default() += 1;
+        }
+        let executors = self.shared_state.get_executors().await?;
+        let mut gpu_occupancy = HashMap::new();
+        let mut burst_tasks: HashMap<String, u64> = HashMap::new();
+        for executor in &executors {
+            gpu_occupancy.insert(
+                executor.id.clone(),
+                self.shared_state.gpu_occupancy(executor).await?,
+            );
+            if running_tasks.contains_key(&executor.id) {
+                let tasks = self
+                    .shared_state
+                    .tasks_for_executor(&executor.id, None)
+                    .await?;
+                burst_tasks.insert(
+                    executor.id.clone(),
+                    tasks.iter().filter(|task| task.burst).count() as u64,
+                );
+            }
+        }
+        Ok(executors
+            .into_iter()
+            .map(|executor| {
+                let running = running_tasks.get(&executor.id).copied().unwrap_or_default();
+                let capacity_weight = executor.capacity_weight();
+                let occupancy = gpu_occupancy.remove(&executor.id).unwrap_or_default();
+                indexify_coordinator::Executor {
+                    running_tasks: running,
+                    burst_tasks: burst_tasks.get(&executor.id).copied().unwrap_or_default(),
+                    capacity_weight,
+                    id: executor.id,
+                    addr: executor.addr,
+                    last_seen: executor.last_seen,
+                    extractors: executor.extractors.into_iter().map(Into::into).collect(),
+                    cost_per_hour: executor.cost_per_hour.unwrap_or_default(),
+                    zone: executor.zone.unwrap_or_default(),
+                    max_payload_bytes: executor.max_payload_bytes.unwrap_or_default(),
+                    download_bytes_per_sec: executor.download_bytes_per_sec.unwrap_or_default(),
+                    draining: executor.draining,
+                    pressure: executor.pressure.map(Into::into),
+                    gpu_devices: executor
+                        .gpu_devices
+                        .iter()
+                        .zip(occupancy.into_iter().chain(std::iter::repeat(0)))
+                        .map(|(capacity, occupied)| indexify_coordinator::GpuDevice {
+                            capacity_millis: *capacity,
+                            occupied_millis: occupied,
+                        })
+                        .collect(),
+                    graph_versions: executor
+                        .graph_versions
+                        .into_iter()
+                        .map(Into::into)
+                        .collect(),
+                }
+            })
+            .collect())
+    }
+
+    pub async fn heartbeat(
+        &self,
+        executor_id: &str,
+        bundler: &mut TaskBundler,
+    ) -> Result<indexify_coordinator::HeartbeatResponse> {
+        let settings = self.shared_state.get_scheduler_settings()?;
+        //  Enough tasks to fill a bundle
+        let limit = settings.max_bundle_tasks.unwrap_or(0).max(10);
         let tasks = self
             .shared_state
-            .tasks_for_executor(executor_id, Some(10))
+            .tasks_for_executor(executor_id, Some(limit as u64))
             .await?;
-        let tasks = tasks
+        bundler.add(tasks, &settings, Instant::now());
+        Ok(bundler.dispatch().into_response(executor_id))
+    }
+
+    /// Stores the progress an executor reported in a heartbeat on the tasks
+    /// it runs
+    pub async fn update_task_progress(
+        &self,
+        executor_id: &str,
+        updates: Vec<indexify_coordinator::TaskProgressUpdate>,
+    ) -> Result<()> {
+        let updated_at = utils::timestamp_secs();
+        let progress: HashMap<_, _> = updates
             .into_iter()
-            .map(|task| -> Result<indexify_coordinator::Task> { Ok(task.into()) })
-            .collect::<Result<Vec<_>>>()?;
-        Ok(tasks)
+            .filter_map(|update| {
+                let progress = internal_api::TaskProgress {
+                    updated_at,
+                    ..update.progress?.into()
+                };
+                Some((update.task_id, progress))
+            })
+            .collect();
+        if progress.is_empty() {
+            return Ok(());
+        }
+        self.shared_state
+            .update_task_progress(executor_id, progress)
+            .await
+    }
+
+    /// Stores the pressure an executor reported in a heartbeat, or clears it.
+    /// Executors under pressure take fewer or no new tasks until it clears,
+    /// and tasks held back in the meantime are allocated once it eases.
+    pub async fn set_executor_pressure(
+        &self,
+        executor_id: &str,
+        pressure: Option<indexify_coordinator::ExecutorPressure>,
+    ) -> Result<()> {
+        let pressure = pressure
+            .and_then(|pressure| internal_api::ExecutorPressure::try_from(pressure).ok())
+            .map(|pressure| internal_api::ExecutorPressure {
+                since: utils::timestamp_secs(),
+                ..pressure
+            });
+        match &pressure {
+            Some(pressure) => warn!(
+                "executor {} reported {:?} pressure: {}",
+                executor_id, pressure.level, pressure.reason
+            ),
+            None => info!("pressure of executor {} cleared", executor_id),
+        }
+        let eased = pressure.as_ref().map_or(true, |pressure| {
+            pressure.level != internal_api::PressureLevel::Critical
+        });
+        self.shared_state
+            .set_executor_pressure(executor_id, pressure)
+            .await?;
+        if eased && !self.shared_state.get_maintenance_mode()?.enabled {
+            self.scheduler.allocate_held_back_tasks().await?;
+        }
+        Ok(())
     }
 
     pub async fn all_task_assignments(&self) -> Result<HashMap<String, String>> {
@@ -212,616 +1134,5107 @@ impl Coordinator {
         Ok(tasks)
     }
 
-    pub async fn remove_executor(&self, executor_id: &str) -> Result<()> {
-        info!("removing executor: {}", executor_id);
-        self.shared_state.remove_executor(executor_id).await?;
-        Ok(())
+    /// Streams the tasks `list_tasks` returns as they are read, with the key
+    /// to resume the stream after each task
+    pub fn stream_tasks(
+        &self,
+        namespace: &str,
+        extraction_policy: Option<String>,
+        cursor: Option<String>,
+    ) -> impl Stream<Item = Result<(String, internal_api::Task)>> + Send + 'static {
+        self.shared_state
+            .stream_tasks(namespace, extraction_policy, cursor)
     }
 
-    pub async fn list_indexes(&self, namespace: &str) -> Result<Vec<internal_api::Index>> {
-        self.shared_state.list_indexes(namespace).await
+    pub async fn list_task_queue_depths(
+        &self,
+        namespace: &str,
+    ) -> Result<Vec<indexify_coordinator::TaskQueueDepth>> {
+        let queue_depths = self.shared_state.task_queue_depths().await;
+        let extraction_policies = self.shared_state.list_extraction_policy(namespace).await?;
+        Ok(extraction_policies
+            .into_iter()
+            .map(|policy| {
+                let depth = queue_depths.get(&policy.id).cloned().unwrap_or_default();
+                indexify_coordinator::TaskQueueDepth {
+                    extraction_graph: policy.graph_name,
+                    extraction_policy: policy.name,
+                    unallocated: depth.unallocated,
+                    running: depth.running,
+                    completed_last_hour: depth.completed_last_hour,
+                }
+            })
+            .collect())
     }
 
-    pub async fn get_index(&self, namespace: &str, name: &str) -> Result<internal_api::Index> {
-        let mut s = DefaultHasher::new();
-        namespace.hash(&mut s);
-        name.hash(&mut s);
-        let id = format!("{:x}", s.finish());
-        self.shared_state.get_index(&id).await
-    }
-
-    pub async fn update_indexes_state(&self, indexes: Vec<internal_api::Index>) -> Result<()> {
-        self.shared_state.set_indexes(indexes).await
+    /// Returns the unfinished tasks assigned to an executor, or None if the
+    /// executor is not registered.
+    pub async fn list_executor_tasks(
+        &self,
+        executor_id: &str,
+    ) -> Result<Option<Vec<indexify_coordinator::Task>>> {
+        let registered = self
+            .shared_state
+            .get_executors()
+            .await?
+            .iter()
+            .any(|executor| executor.id == executor_id);
+        if !registered {
+            return Ok(None);
+        }
+        let tasks = self
+            .shared_state
+            .tasks_for_executor(executor_id, None)
+            .await?
+            .into_iter()
+            .map(|task| task.into())
+            .collect();
+        Ok(Some(tasks))
     }
 
-    pub async fn get_extractor_coordinates(&self, extractor_name: &str) -> Result<Vec<String>> {
-        let executors = self
+    pub async fn remove_executor(&self, executor_id: &str) -> Result<()> {
+        //  Executors that deregistered are gone already when their heartbeats stop
+        if self
             .shared_state
-            .get_executors_for_extractor(extractor_name)
+            .get_executor_by_id(executor_id)
+            .await
+            .is_err()
+        {
+            return Ok(());
+        }
+        info!("removing executor: {}", executor_id);
+        let tasks = self
+            .shared_state
+            .tasks_for_executor(executor_id, None)
             .await?;
-        let addresses = executors
-            .iter()
-            .map(|e| e.addr.clone())
-            .collect::<Vec<String>>();
-        Ok(addresses)
+        self.shared_state
+            .remove_executor(executor_id, false)
+            .await?;
+        self.quarantine_poison_tasks(tasks).await
     }
 
-    // TODO: edwin
-    pub async fn register_executor(
+    /// Takes an executor that shuts down out of the cluster, new tasks stop
+    /// going to it right away. With `drain` the executor is removed once the
+    /// tasks it runs finished, otherwise right away and its tasks go to other
+    /// executors without counting as lost attempts.
+    pub async fn deregister_executor(
         &self,
-        addr: &str,
         executor_id: &str,
-        extractors: Vec<internal_api::ExtractorDescription>,
-    ) -> Result<()> {
-        let _ = self
+        drain: bool,
+    ) -> Result<indexify_coordinator::DeregisterExecutorResponse> {
+        self.shared_state.get_executor_by_id(executor_id).await?;
+        let tasks = self
             .shared_state
-            .register_executor(addr, executor_id, extractors)
-            .await;
-        Ok(())
+            .tasks_for_executor(executor_id, None)
+            .await?;
+        if drain && !tasks.is_empty() {
+            info!(
+                "draining executor {}, waiting for {} tasks",
+                executor_id,
+                tasks.len()
+            );
+            self.shared_state.drain_executor(executor_id).await?;
+            return Ok(indexify_coordinator::DeregisterExecutorResponse {
+                removed: false,
+                running_tasks: tasks.len() as u64,
+                reassigned_tasks: 0,
+            });
+        }
+        info!(
+            "executor {} deregistered, reassigning {} tasks",
+            executor_id,
+            tasks.len()
+        );
+        self.shared_state.remove_executor(executor_id, true).await?;
+        Ok(indexify_coordinator::DeregisterExecutorResponse {
+            removed: true,
+            running_tasks: 0,
+            reassigned_tasks: tasks.len() as u64,
+        })
     }
 
-    pub async fn register_ingestion_server(&self, ingestion_server_id: &str) -> Result<()> {
-        if let Some(forward_to_leader) = self.shared_state.ensure_leader().await? {
-            let leader_node_id = forward_to_leader
-                .leader_id
-                .ok_or_else(|| anyhow::anyhow!("could not get leader node id"))?;
-            let leader_coord_addr = self
+    /// Removes a draining executor once the last of its tasks finished
+    async fn remove_drained_executor(&self, executor_id: &str) -> Result<()> {
+        let Some(executor) = self.shared_state.get_executor_by_id(executor_id).await.ok() else {
+            return Ok(());
+        };
+        if !executor.draining ||
+            !self
                 .shared_state
-                .get_coordinator_addr(leader_node_id)
+                .tasks_for_executor(executor_id, Some(1))
                 .await?
-                .ok_or_else(|| anyhow::anyhow!("could not get leader node coordinator address"))?;
-            let leader_coord_addr = format!("http://{}", leader_coord_addr);
-            self.forwardable_coordinator
-                .register_ingestion_server(&leader_coord_addr, ingestion_server_id)
-                .await?;
+                .is_empty()
+        {
             return Ok(());
         }
-        self.garbage_collector
-            .register_ingestion_server(ingestion_server_id)
-            .await;
-        Ok(())
+        info!("executor {} drained, removing it", executor_id);
+        self.shared_state.remove_executor(executor_id, true).await
     }
 
-    pub async fn remove_ingestion_server(&self, ingestion_server_id: &str) -> Result<()> {
-        if let Some(forward_to_leader) = self.shared_state.ensure_leader().await? {
-            let leader_node_id = forward_to_leader
-                .leader_id
-                .ok_or_else(|| anyhow::anyhow!("could not get leader node id"))?;
-            let leader_coord_addr = self
-                .shared_state
-                .get_coordinator_addr(leader_node_id)
-                .await?
-                .ok_or_else(|| anyhow::anyhow!("could not get leader node coordinator address"))?;
-            self.forwardable_coordinator
-                .remove_ingestion_server(&leader_coord_addr, ingestion_server_id)
-                .await?;
-            return Ok(());
+    /// Quarantines the tasks that have been lost with too many executors. An
+    /// input that takes down every executor it is allocated to would otherwise
+    /// be allocated forever.
+    async fn quarantine_poison_tasks(&self, tasks: Vec<internal_api::Task>) -> Result<()> {
+        for task in tasks {
+            //  Read the task again for the executors recorded by the removal
+            let mut task = self.shared_state.task_with_id(&task.id).await?;
+            if task.terminal_state() || task.lost_by_executors.len() < MAX_LOST_TASK_ATTEMPTS {
+                continue;
+            }
+            let diagnostics = format!(
+                "executors {} were removed while running the task",
+                task.lost_by_executors.join(", ")
+            );
+            warn!("quarantining task {}: {}", task.id, diagnostics);
+            task.outcome = internal_api::TaskOutcome::Quarantined;
+            task.diagnostics = Some(diagnostics);
+            self.shared_state.update_task(task, None).await?;
         }
-        self.garbage_collector
-            .remove_ingestion_server(ingestion_server_id)
-            .await;
         Ok(())
     }
 
-    pub async fn get_content_metadata(
+    /// Ends the unfinished tasks of content whose TTL ran out with the expired
+    /// outcome. Returns the number of tasks that expired.
+    pub async fn expire_tasks(&self) -> Result<usize> {
+        let tasks = self
+            .shared_state
+            .state_machine
+            .find_expired_tasks(utils::timestamp_secs())
+            .await?;
+        if tasks.is_empty() {
+            return Ok(0);
+        }
+        let assignments = self.shared_state.task_assignments().await?;
+        let expired = tasks.len();
+        for mut task in tasks {
+            let executor_id = assignments.get(&task.id).cloned();
+            task.diagnostics = Some(format!(
+                "content {} expired at {}",
+                task.content_metadata.get_root_id(),
+                task.content_metadata.expires_at
+            ));
+            task.outcome = internal_api::TaskOutcome::Expired;
+            self.shared_state.update_task(task, executor_id).await?;
+        }
+        info!("expired {} tasks", expired);
+        Ok(expired)
+    }
+
+    /// Drops the diagnostics of the tasks that finished longer ago than the
+    /// retention of their namespace. The retention of tasks whose finish time
+    /// is unknown starts when this node first found them. Returns the number
+    /// of tasks whose diagnostics were dropped.
+    pub async fn prune_task_diagnostics(
         &self,
-        content_ids: Vec<String>,
-    ) -> Result<Vec<indexify_coordinator::ContentMetadata>> {
-        let content = self
+        now: u64,
+        config: &DiagnosticsConfig,
+    ) -> Result<usize> {
+        let tasks = self
             .shared_state
-            .get_content_metadata_batch(content_ids)
+            .state_machine
+            .finished_tasks_with_diagnostics()
             .await?;
-        let content = self.internal_content_metadata_to_external(content)?;
-        Ok(content)
+        let task_ids: Vec<String> = {
+            let mut diagnostics_seen = self.diagnostics_seen.lock().unwrap();
+            let mut seen = HashMap::new();
+            let mut task_ids = Vec::new();
+            for (task_id, namespace, finished_at) in tasks {
+                let finished_at = finished_at.unwrap_or_else(|| {
+                    let seen_at = diagnostics_seen.get(&task_id).copied().unwrap_or(now);
+                    seen.insert(task_id.clone(), seen_at);
+                    seen_at
+                });
+                if finished_at.saturating_add(config.retention_secs(&namespace)) <= now {
+                    seen.remove(&task_id);
+                    task_ids.push(task_id);
+                }
+            }
+            *diagnostics_seen = seen;
+            task_ids
+        };
+        for chunk in task_ids.chunks(PRUNE_DIAGNOSTICS_BATCH_SIZE) {
+            self.shared_state
+                .clear_task_diagnostics(chunk.to_vec())
+                .await?;
+        }
+        if !task_ids.is_empty() {
+            info!("dropped the diagnostics of {} tasks", task_ids.len());
+        }
+        Ok(task_ids.len())
     }
 
-    pub async fn get_task(&self, task_id: &str) -> Result<indexify_coordinator::Task> {
-        let task = self.shared_state.task_with_id(task_id).await?;
-        Ok(task.into())
+    /// Compacts the key ranges of the state this node deleted, once enough
+    /// keys of a column family were deleted. Every node compacts its own
+    /// state store.
+    pub async fn compact_deleted_state(&self) -> Result<()> {
+        let reports = self
+            .shared_state
+            .state_machine
+            .compact_deleted_ranges(COMPACTION_MIN_DELETIONS)
+            .await?;
+        for report in reports {
+            info!(
+                "compacted {} keys deleted from {} in {:.2?}, reclaimed {} bytes",
+                report.deletions, report.column, report.elapsed, report.reclaimed_bytes
+            );
+        }
+        Ok(())
     }
 
-    pub async fn get_task_and_root_content(
-        &self,
-        task_id: &str,
-    ) -> Result<(internal_api::Task, Option<internal_api::ContentMetadata>)> {
-        let task = self.shared_state.task_with_id(task_id).await?;
-        let mut root_content = None;
-        if let Some(root_content_id) = &task.content_metadata.root_content_id {
-            let root_cm = self
-                .shared_state
-                .get_content_metadata_batch(vec![root_content_id.clone()])
-                .await?;
-            if let Some(root_cm) = root_cm.first() {
-                root_content.replace(root_cm.clone());
+    /// Backs the state store up to the directory of `config`, unless a backup
+    /// is already being written
+    pub async fn backup_state(&self, config: &BackupConfig) -> Result<()> {
+        match self
+            .shared_state
+            .state_machine
+            .create_backup(config)
+            .await?
+        {
+            Some(report) => info!(
+                "backed state up as backup {} to {}, {} files of {} bytes in {:.2?}",
+                report.backup_id, config.path, report.num_files, report.size_bytes, report.elapsed
+            ),
+            None => warn!("skipping state backup, the previous backup is still running"),
+        }
+        Ok(())
+    }
+
+    /// Ships the log entries committed since the last call to the state
+    /// archive, starting a new archive when a checkpoint is due
+    pub async fn archive_state(&self, archiver: &StateArchiver) -> Result<()> {
+        let Some(report) = archiver.ship(&self.shared_state.state_machine).await? else {
+            debug!("not archiving state, no raft snapshot was taken yet");
+            return Ok(());
+        };
+        if report.checkpointed {
+            info!("started state archive {}", report.archive_url);
+        }
+        if report.shipped_entries > 0 {
+            debug!(
+                "archived {} log entries to {}",
+                report.shipped_entries, report.archive_url
+            );
+        }
+        Ok(())
+    }
+
+    /// Fails in-flight tasks at the kill rate of the chaos config
+    pub async fn kill_tasks(&self, chaos: &ChaosInjector) -> Result<usize> {
+        let assignments = self.shared_state.task_assignments().await?;
+        let mut killed = 0;
+        for (task_id, executor_id) in assignments {
+            if !chaos.kill_task() {
+                continue;
+            }
+            let mut task = self.shared_state.task_with_id(&task_id).await?;
+            if task.terminal_state() {
+                continue;
             }
+            warn!(
+                "chaos: killing task {} on executor {}",
+                task.id, executor_id
+            );
+            task.outcome = internal_api::TaskOutcome::Failed;
+            task.diagnostics = Some("killed by chaos injection".to_string());
+            self.shared_state
+                .update_task(task, Some(executor_id))
+                .await?;
+            killed += 1;
         }
-        Ok((task, root_content))
+        Ok(killed)
     }
 
-    pub async fn get_content_tree_metadata(
-        &self,
-        content_id: &str,
-    ) -> Result<Vec<indexify_coordinator::ContentMetadata>> {
-        let content_tree = self.shared_state.get_content_tree_metadata(content_id)?;
-        let content_tree = self.internal_content_metadata_to_external(content_tree)?;
-        Ok(content_tree)
+    /// Allocates the tasks of rate limited extraction policies as their
+    /// limits allow, and the tasks held back by the limit of tasks per
+    /// executor as executors finish tasks
+    pub async fn allocate_throttled_tasks(&self) -> Result<usize> {
+        if self.shared_state.get_maintenance_mode()?.enabled {
+            return Ok(0);
+        }
+        self.scheduler.allocate_throttled_tasks().await
     }
 
-    pub fn get_extractor(
+    /// Processes the state changes again if root content was queued behind
+    /// the cap of active content of its graph, admitting the content whose
+    /// graphs have room now. Garbage collection that was put off and state
+    /// changes left while the cluster was in maintenance or over the batch
+    /// size of the scheduler are processed the same way.
+    pub async fn admit_queued_content(&self) -> Result<()> {
+        if self.shared_state.get_maintenance_mode()?.enabled {
+            return Ok(());
+        }
+        let deferred_gc = self.deferred_gc.swap(false, Ordering::Relaxed) ||
+            self.gc_unblocked(&self.gc_waiting_for_capacity, || {
+                Ok(self
+                    .shared_state
+                    .get_scheduler_settings()?
+                    .max_pending_gc_tasks
+                    .map_or(true, |max| {
+                        self.shared_state.count_unfinished_gc_tasks() < max as usize
+                    }))
+            })? ||
+            self.gc_unblocked(&self.gc_waiting_for_archive, || {
+                Ok(self
+                    .content_archiver
+                    .as_ref()
+                    .map_or(true, |archiver| archiver.has_finished_uploads()))
+            })?;
+        let paused_for_maintenance = self.paused_for_maintenance.swap(false, Ordering::Relaxed);
+        let state_changes_left = self.state_changes_left.swap(false, Ordering::Relaxed);
+        if self.scheduler.take_queued_content() ||
+            deferred_gc ||
+            paused_for_maintenance ||
+            state_changes_left
+        {
+            self.run_scheduler().await?;
+        }
+        Ok(())
+    }
+
+    /// Clears `waiting` and returns true when garbage collection was put off
+    /// and `unblocked` tells that what it waited for happened. Both are cheap
+    /// reads, the scheduler only runs again once garbage collection can make
+    /// progress.
+    fn gc_unblocked(
         &self,
-        extractor_name: &str,
-    ) -> Result<internal_api::ExtractorDescription> {
-        self.shared_state.extractor_with_name(extractor_name)
+        waiting: &AtomicBool,
+        unblocked: impl FnOnce() -> Result<bool>,
+    ) -> Result<bool> {
+        if !waiting.load(Ordering::Relaxed) || !unblocked()? {
+            return Ok(false);
+        }
+        waiting.store(false, Ordering::Relaxed);
+        Ok(true)
     }
 
-    pub async fn create_extraction_graph(
+    /// How often the scheduler looks for work left over when no state
+    /// changes arrive
+    pub fn scheduler_tick_interval(&self) -> Duration {
+        Duration::from_millis(self.scheduler_config.tick_interval_ms.max(1))
+    }
+
+    pub fn get_scheduler_settings(&self) -> Result<SchedulerSettings> {
+        self.shared_state.get_scheduler_settings()
+    }
+
+    /// Replaces the scheduler settings. They apply to the next allocation, and
+    /// tasks held back by a limit of tasks per executor are allocated again
+    /// right away in case the limit was raised or lifted.
+    pub async fn set_scheduler_settings(
         &self,
-        extraction_graph: ExtractionGraph,
-    ) -> Result<Vec<internal_api::Index>> {
-        let mut structured_data_schema =
-            StructuredDataSchema::new(&extraction_graph.name, &extraction_graph.namespace);
-        let mut indexes_to_create = Vec::new();
-        for extraction_policy in &extraction_graph.extraction_policies {
-            let extractor = self.get_extractor(&extraction_policy.extractor)?;
-            for (output_name, output_schema) in extractor.outputs {
-                match output_schema {
-                    OutputSchema::Embedding(embeddings) => {
-                        let mut index_to_create = internal_api::Index {
-                            id: "".to_string(),
-                            namespace: extraction_policy.namespace.clone(),
-                            name: "".to_string(),
-                            table_name: "".to_string(),
-                            schema: serde_json::to_value(embeddings).unwrap().to_string(),
-                            extraction_policy_name: extraction_policy.name.clone(),
-                            extractor_name: extractor.name.clone(),
-                            graph_name: extraction_graph.name.clone(),
-                            visibility: false,
-                        };
-                        index_to_create.name = index_to_create.build_name(&output_name);
-                        index_to_create.table_name = index_to_create.build_table_name(&output_name);
-                        index_to_create.id = index_to_create.id();
-                        indexes_to_create.push(index_to_create);
-                    }
-                    OutputSchema::Attributes(columns) => {
-                        structured_data_schema.merge(columns);
-                    }
-                }
+        settings: SchedulerSettings,
+    ) -> Result<SchedulerSettings> {
+        if settings.max_tasks_per_executor == Some(0) {
+            return Err(anyhow!("max_tasks_per_executor must be at least 1"));
+        }
+        if settings.max_pending_gc_tasks == Some(0) {
+            return Err(anyhow!("max_pending_gc_tasks must be at least 1"));
+        }
+        if let Some((namespace, _)) = settings
+            .namespace_weights
+            .iter()
+            .find(|(_, weight)| **weight == 0)
+        {
+            return Err(anyhow!(
+                "weight of namespace {} must be at least 1",
+                namespace
+            ));
+        }
+        if !settings.capacity_reservations.is_empty() && settings.max_tasks_per_executor.is_none() {
+            return Err(anyhow!(
+                "capacity reservations need max_tasks_per_executor to count slots"
+            ));
+        }
+        if !settings.overcommit_factors.is_empty() && settings.max_tasks_per_executor.is_none() {
+            return Err(anyhow!(
+                "overcommit factors need max_tasks_per_executor to overcommit"
+            ));
+        }
+        if let Some((extractor, _)) = settings
+            .overcommit_factors
+            .iter()
+            .find(|(_, factor)| !(factor.is_finite() && **factor >= 1.0))
+        {
+            return Err(anyhow!(
+                "overcommit factor of extractor {} must be at least 1",
+                extractor
+            ));
+        }
+        if (settings.max_bundle_bytes.is_some() || settings.max_bundle_latency_ms.is_some()) &&
+            settings.max_bundle_tasks.is_none()
+        {
+            return Err(anyhow!(
+                "bundle limits need max_bundle_tasks to bundle tasks"
+            ));
+        }
+        for reservation in &settings.capacity_reservations {
+            if reservation.slots == 0 || reservation.end <= reservation.start {
+                return Err(anyhow!(
+                    "reservation of namespace {} must have slots and end after it starts",
+                    reservation.namespace
+                ));
             }
         }
+        let previous = self.shared_state.get_scheduler_settings()?;
         self.shared_state
-            .create_extraction_graph(
-                extraction_graph,
-                structured_data_schema,
-                indexes_to_create.clone(),
-            )
+            .set_scheduler_settings(settings.clone())
             .await?;
-        Ok(indexes_to_create)
+        info!(
+            "scheduler settings changed from {:?} to {:?}",
+            previous, settings
+        );
+        if previous.max_tasks_per_executor.is_some() &&
+            !self.shared_state.get_maintenance_mode()?.enabled
+        {
+            self.scheduler.allocate_held_back_tasks().await?;
+        }
+        Ok(settings)
     }
 
-    pub async fn create_content_tree_tasks(
+    /// Predicts the queues of the extractors under a hypothetical change to
+    /// the cluster. The planner runs over the horizon on a copy of the
+    /// placement state with the added executors, and the tasks of the added
+    /// invocations are created on top of the current traffic, which continues
+    /// at the rate extraction policies finished tasks in the last hour.
+    pub async fn simulate_scheduling(
         &self,
-        content_tree: Vec<internal_api::ContentMetadata>,
-        state_change: StateChange,
-    ) -> Result<()> {
-        let mut output_tables = HashMap::new();
+        req: indexify_coordinator::SimulateSchedulingRequest,
+    ) -> Result<Vec<indexify_coordinator::SimulatedQueue>> {
+        let mut invocations: HashMap<(String, String), f64> = HashMap::new();
+        for invocation in &req.invocations {
+            *invocations
+                .entry((
+                    invocation.namespace.clone(),
+                    invocation.extraction_graph.clone(),
+                ))
+                .or_default() += invocation.per_hour;
+        }
 
-        for content_metadata in &content_tree {
-            if content_metadata.extraction_policy_ids.keys().len() == 0 {
-                continue;
+        // Every content ingested into a graph creates a task of each policy
+        // of the graph
+        let queue_depths = self.shared_state.task_queue_depths().await;
+        let mut arrivals = Vec::new();
+        let mut depths_by_extractor: HashMap<String, (u64, u64)> = HashMap::new();
+        for namespace in self.shared_state.list_namespaces().await? {
+            for graph in namespace.extraction_graphs {
+                let added = invocations
+                    .remove(&(namespace.name.clone(), graph.name.clone()))
+                    .unwrap_or_default();
+                for policy in graph.extraction_policies {
+                    let depth = queue_depths.get(&policy.id).cloned().unwrap_or_default();
+                    let totals = depths_by_extractor
+                        .entry(policy.extractor.clone())
+                        .or_default();
+                    totals.0 += depth.running;
+                    totals.1 += depth.completed_last_hour;
+                    let per_hour = depth.completed_last_hour as f64 + added;
+                    if per_hour <= 0.0 {
+                        continue;
+                    }
+                    let content = internal_api::ContentMetadata {
+                        namespace: namespace.name.clone(),
+                        ..Default::default()
+                    };
+                    let extractor = policy.extractor.clone();
+                    let mut task = internal_api::Task::new("", &content, policy);
+                    task.extractor = extractor;
+                    arrivals.push(simulation::Arrivals {
+                        task,
+                        latency_tolerant: graph.latency_tolerant,
+                        zone_placement: graph.zone_placement,
+                        per_hour,
+                    });
+                }
             }
-            let applied_extraction_policy_ids: HashSet<String> = content_metadata
-                .extraction_policy_ids
-                .clone()
-                .into_iter()
-                .filter(|(_, completion_time)| *completion_time > 0)
-                .map(|(extraction_policy_id, _)| extraction_policy_id)
-                .collect();
+        }
+        if let Some((namespace, graph)) = invocations.into_keys().next() {
+            return Err(anyhow!(
+                "extraction graph {} not found in namespace {}",
+                graph,
+                namespace
+            ));
+        }
 
-            if applied_extraction_policy_ids.is_empty() {
-                continue;
-            }
-            let applied_extraction_policies = self
-                .shared_state
-                .get_extraction_policies_from_ids(applied_extraction_policy_ids)
-                .await?;
-            for applied_extraction_policy in applied_extraction_policies.clone().unwrap() {
-                output_tables.insert(
-                    content_metadata.id.clone(),
-                    applied_extraction_policy
-                        .output_table_mapping
-                        .values()
-                        .cloned()
-                        .collect::<HashSet<_>>(),
-                );
+        let extractors: HashSet<String> = arrivals
+            .iter()
+            .map(|arrivals| arrivals.task.extractor.clone())
+            .chain(
+                req.executors
+                    .iter()
+                    .flat_map(|executors| executors.extractors.iter().cloned()),
+            )
+            .collect();
+        let unassigned = self.shared_state.unassigned_task_ids().await;
+        let mut state = LoadAwareDistributor::new(self.shared_state.clone())
+            .placement_state(&unassigned, &extractors, utils::timestamp_secs())
+            .await?;
+        for (i, executors) in req.executors.iter().enumerate() {
+            for j in 0..executors.count {
+                let executor = internal_api::ExecutorMetadata {
+                    id: format!("simulated-executor-{}-{}", i, j),
+                    capacity_weight: executors.capacity_weight,
+                    ..Default::default()
+                };
+                state.add_executor(executor, &executors.extractors);
             }
         }
+        let assignments = self.shared_state.task_assignments().await?;
+        let running = self
+            .shared_state
+            .get_tasks_from_ids(&assignments.keys().cloned().collect())?
+            .into_values()
+            .filter_map(|task| Some((assignments.get(&task.id)?.clone(), task)))
+            .collect();
+        let run_secs = depths_by_extractor
+            .into_iter()
+            .filter_map(|(extractor, (running, completed_last_hour))| {
+                Some((
+                    extractor,
+                    simulation::run_secs(running, completed_last_hour)?,
+                ))
+            })
+            .collect();
+        let simulation = simulation::Simulation {
+            state,
+            running,
+            arrivals,
+            run_secs,
+            horizon_secs: req.horizon_secs,
+        };
+        let queues = tokio::task::spawn_blocking(move || simulation.run()).await?;
+        Ok(queues
+            .into_iter()
+            .map(|queue| indexify_coordinator::SimulatedQueue {
+                extractor: queue.extractor,
+                executors: queue.executors as u64,
+                unallocated: queue.unallocated,
+                queued: queue.queued,
+                predicted_queued: queue.predicted_queued,
+                predicted_wait_secs: queue.predicted_wait_secs,
+            })
+            .collect())
+    }
 
-        let task_type = match state_change.change_type {
-            indexify_internal_api::ChangeType::TombstoneContentTree => ServerTaskType::Delete,
-            _ => ServerTaskType::UpdateLabels,
+    pub fn get_maintenance_mode(&self) -> Result<internal_api::MaintenanceMode> {
+        self.shared_state.get_maintenance_mode()
+    }
+
+    /// Puts the cluster in maintenance or takes it out of it. The scheduler
+    /// catches up with the content ingested and the tasks finished in the
+    /// meantime once maintenance ends.
+    pub async fn set_maintenance_mode(
+        &self,
+        enabled: bool,
+        reason: &str,
+    ) -> Result<internal_api::MaintenanceMode> {
+        let maintenance = internal_api::MaintenanceMode {
+            enabled,
+            reason: reason.to_string(),
+            changed_at: utils::timestamp_secs(),
         };
-        let tasks = self
-            .garbage_collector
-            .create_gc_tasks(content_tree, output_tables, task_type)
+        self.shared_state
+            .set_maintenance_mode(maintenance.clone())
             .await?;
-        self.shared_state.create_gc_tasks(tasks.clone()).await?;
+        if enabled {
+            warn!("cluster is in maintenance: {}", reason);
+        } else {
+            info!("cluster is out of maintenance");
+        }
+        Ok(maintenance)
+    }
+
+    pub async fn record_egress(&self, namespace: &str, bytes: u64) -> Result<()> {
+        if bytes == 0 {
+            return Ok(());
+        }
+        self.shared_state.record_egress(namespace, bytes).await
+    }
+
+    /// Metering records of the periods starting in `[start, end]`, of all
+    /// namespaces when `namespace` is empty
+    pub fn list_metering_records(
+        &self,
+        namespace: &str,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<internal_api::MeteringRecord>> {
+        let namespace = (!namespace.is_empty()).then_some(namespace);
         self.shared_state
-            .mark_change_events_as_processed(vec![state_change], Vec::new())
+            .list_metering_records(namespace, start, end)
+    }
+
+    /// Puts a quarantined task back into the queue. The extraction policy of
+    /// the task runs again on its content, with a fresh attempt count.
+    /// Returns the id of the replay.
+    pub async fn requeue_task(
+        &self,
+        namespace: &str,
+        task_id: &str,
+        initiator: &str,
+    ) -> Result<String> {
+        let task = self.shared_state.task_with_id(task_id).await?;
+        if task.namespace != namespace {
+            return Err(anyhow!("Task with id {} not found", task_id));
+        }
+        if task.outcome != internal_api::TaskOutcome::Quarantined {
+            return Err(anyhow!("task {} is not quarantined", task_id));
+        }
+        info!("requeueing quarantined task: {}", task_id);
+        let extraction_policy = self
+            .shared_state
+            .get_extraction_policy(&task.extraction_policy_id)?;
+        let replay = self
+            .start_replay(
+                namespace,
+                internal_api::ReplayKind::Requeue,
+                &task.content_metadata.id.id,
+                initiator,
+                vec![self.replay_task(
+                    task.id.clone(),
+                    &task.content_metadata,
+                    &extraction_policy,
+                )?],
+            )
             .await?;
-        Ok(())
+        self.shared_state
+            .rerun_extraction_policy(&task.content_metadata.id, &task.extraction_policy_id)
+            .await?;
+        Ok(replay.id)
     }
 
-    pub async fn create_gc_tasks(&self, state_change: StateChange) -> Result<()> {
-        let content_id: ContentMetadataId = state_change.object_id.clone().try_into()?;
-        let content_tree_metadata = self
+    fn context_root_content_id(&self, namespace: &str, content_id: &str) -> Result<String> {
+        let content = self
             .shared_state
-            .get_content_tree_metadata_with_version(&content_id)?;
-        self.create_content_tree_tasks(content_tree_metadata, state_change)
-            .await
+            .state_machine
+            .get_latest_version_of_content(content_id)?
+            .filter(|c| c.namespace == namespace && !c.tombstoned)
+            .ok_or_else(|| anyhow!("content {} not found", content_id))?;
+        Ok(content.get_root_id().to_string())
     }
 
-    async fn handle_tombstone_content_tree_state_change(&self, change: StateChange) -> Result<()> {
-        if let Some(forward_to_leader) = self.shared_state.ensure_leader().await? {
-            let leader_id = forward_to_leader
-                .leader_id
-                .ok_or_else(|| anyhow::anyhow!("could not get leader node id"))?;
-            let leader_coord_addr = self
+    /// Reads the context of the root content of `content_id`
+    pub fn get_content_context(
+        &self,
+        namespace: &str,
+        content_id: &str,
+    ) -> Result<HashMap<String, serde_json::Value>> {
+        let root_content_id = self.context_root_content_id(namespace, content_id)?;
+        self.shared_state.get_content_context(&root_content_id)
+    }
+
+    /// Sets a key of the context of the root content of `content_id`, or
+    /// removes it when `value` is unset. Concurrent writes of a key are
+    /// applied in order, and the last one wins.
+    pub async fn set_content_context(
+        &self,
+        namespace: &str,
+        content_id: &str,
+        key: &str,
+        value: Option<serde_json::Value>,
+    ) -> Result<()> {
+        if key.is_empty() || key.len() > MAX_CONTENT_CONTEXT_KEY_BYTES {
+            return Err(anyhow!(
+                "context keys have to be 1 to {} bytes long",
+                MAX_CONTENT_CONTEXT_KEY_BYTES
+            ));
+        }
+        let root_content_id = self.context_root_content_id(namespace, content_id)?;
+        if let Some(value) = &value {
+            let value_size = value.to_string().len();
+            if value_size > MAX_CONTENT_CONTEXT_VALUE_BYTES {
+                return Err(anyhow!(
+                    "context value of {} is {} bytes, at most {} bytes are allowed",
+                    key,
+                    value_size,
+                    MAX_CONTENT_CONTEXT_VALUE_BYTES
+                ));
+            }
+            let context_size: usize = self
                 .shared_state
-                .get_coordinator_addr(leader_id)
-                .await?
-                .ok_or_else(|| anyhow::anyhow!("could not get leader node coordinator address"))?;
-            self.forwardable_coordinator
-                .create_gc_tasks(&leader_coord_addr, change)
-                .await?;
-            return Ok(());
+                .get_content_context(&root_content_id)?
+                .iter()
+                .filter(|(existing_key, _)| existing_key.as_str() != key)
+                .map(|(existing_key, value)| existing_key.len() + value.to_string().len())
+                .sum::<usize>() +
+                key.len() +
+                value_size;
+            if context_size > MAX_CONTENT_CONTEXT_BYTES {
+                return Err(anyhow!(
+                    "context of content {} would be {} bytes, at most {} bytes are allowed",
+                    root_content_id,
+                    context_size,
+                    MAX_CONTENT_CONTEXT_BYTES
+                ));
+            }
         }
-
-        //  this coordinator node is the leader
-        self.create_gc_tasks(change).await
+        self.shared_state
+            .set_content_context(&root_content_id, key, value)
+            .await
     }
 
-    async fn handle_content_updated(&self, state_change: StateChange) -> Result<()> {
-        let content_tree = self
-            .shared_state
-            .get_content_tree_metadata(&state_change.object_id)?;
-        self.create_content_tree_tasks(content_tree, state_change)
+    /// Lists the root content of a namespace that waits for tasks but has
+    /// nothing pending, or made no progress for `stalled_for`
+    pub async fn list_stuck_content(
+        &self,
+        namespace: &str,
+        stalled_for: Duration,
+    ) -> Result<Vec<internal_api::StuckContent>> {
+        self.shared_state
+            .state_machine
+            .find_stuck_content(Some(namespace), stalled_for)
             .await
     }
 
-    async fn handle_task_completion_state_change(
+    /// Lists the unallocated tasks of a namespace, or of every namespace, that
+    /// waited for an executor for at least `unallocated_for`, the starvation
+    /// threshold of the scheduler by default, with the reason they aren't
+    /// allocated. Tasks that waited the longest come first.
+    pub async fn list_starved_tasks(
         &self,
-        change: StateChange,
-        root_content_id: ContentMetadataId,
-    ) -> Result<()> {
-        let are_content_tasks_completed = self
+        namespace: Option<&str>,
+        unallocated_for: Option<Duration>,
+    ) -> Result<Vec<internal_api::StarvedTask>> {
+        let unallocated_for = unallocated_for.unwrap_or(Duration::from_secs(
+            self.scheduler_config.starvation_threshold_secs,
+        ));
+        let starving = self
             .shared_state
-            .are_content_tasks_completed(&root_content_id)
-            .await;
-
-        if !are_content_tasks_completed {
-            return Ok(());
-        }
-
-        //  this is the first version of the content, so nothing to garbage collect
-        if root_content_id.version <= 1 {
-            self.shared_state
-                .mark_change_events_as_processed(vec![change], Vec::new())
-                .await?;
-            return Ok(());
+            .state_machine
+            .get_starving_tasks(unallocated_for);
+        if starving.is_empty() {
+            return Ok(Vec::new());
         }
-        let previous_version =
-            ContentMetadataId::new_with_version(&root_content_id.id, root_content_id.version - 1);
-        let content_metadata = self
+        let maintenance = self.shared_state.get_maintenance_mode()?.enabled;
+        let settings = self.shared_state.get_scheduler_settings()?;
+        let throttled = self.shared_state.state_machine.get_throttled_tasks();
+        let capacity_weights: HashMap<String, u64> = self
             .shared_state
-            .state_machine
-            .get_content_by_id_and_version(&previous_version)
+            .get_executors()
             .await?
-            .ok_or(anyhow!("content with id: {} not found", previous_version))?;
+            .into_iter()
+            .map(|executor| (executor.id.clone(), executor.capacity_weight() as u64))
+            .collect();
+        let mut starved = Vec::new();
+        for (task_id, waiting) in starving {
+            //  The task may have finished since it was found
+            let Result::Ok(task) = self.shared_state.task_with_id(&task_id).await else {
+                continue;
+            };
+            if namespace.map_or(false, |namespace| task.namespace != namespace) {
+                continue;
+            }
+            let loads = self
+                .shared_state
+                .get_executor_loads_for_extractor(&task.extractor);
+            let reason = if maintenance {
+                UnschedulableReason::Maintenance
+            } else if loads.is_empty() {
+                UnschedulableReason::NoExecutor
+            } else if throttled.contains(&task_id) {
+                UnschedulableReason::RateLimited
+            } else if settings
+                .burst_tasks_per_executor(&task.extractor)
+                .map_or(false, |max| {
+                    loads.iter().all(|(executor_id, running)| {
+                        let weight = capacity_weights.get(executor_id).copied().unwrap_or(1);
+                        *running >= max as u64 * weight
+                    })
+                })
+            {
+                UnschedulableReason::NoCapacity
+            } else {
+                UnschedulableReason::Pending
+            };
+            starved.push(internal_api::StarvedTask {
+                task_id: task.id,
+                namespace: task.namespace,
+                extraction_graph: task.extraction_graph_name,
+                extraction_policy_id: task.extraction_policy_id,
+                extractor: task.extractor,
+                unallocated_secs: waiting.as_secs(),
+                reason,
+            });
+        }
+        starved.sort_by(|a, b| b.unallocated_secs.cmp(&a.unallocated_secs));
+        Ok(starved)
+    }
+
+    /// Counts the tasks starved past the threshold of the scheduler by reason,
+    /// for the metrics
+    pub async fn record_starved_tasks(&self) -> Result<()> {
+        let mut counts: HashMap<UnschedulableReason, u64> = UnschedulableReason::iter()
+            .map(|reason| (reason, 0))
+            .collect();
+        for task in self.list_starved_tasks(None, None).await? {
+            *counts.entry(task.reason).or_default() += 1;
+        }
         self.shared_state
-            .tombstone_content_batch_with_version(
-                &[content_metadata.id.clone()],
-                vec![StateChangeProcessed {
-                    state_change_id: change.id,
-                    processed_at: utils::timestamp_secs(),
-                }],
-            )
-            .await?;
+            .state_machine
+            .set_starved_task_counts(counts);
         Ok(())
     }
 
-    #[tracing::instrument(skip(self))]
-    pub async fn run_scheduler(&self) -> Result<()> {
-        let _timer = Timer::start(&self.shared_state.metrics.scheduler_invocations);
+    /// Current values of the metrics alert rules watch
+    pub async fn alert_observation(&self) -> Result<AlertObservation> {
+        Ok(AlertObservation {
+            unallocated_tasks: self.shared_state.unassigned_task_ids().await.len() as u64,
+            executors: self.shared_state.get_executors().await?.len() as u64,
+            quarantined_tasks: self.shared_state.state_machine.tasks_quarantined(),
+        })
+    }
 
-        let state_changes = self.shared_state.unprocessed_state_change_events().await?;
-        for change in state_changes {
-            debug!(
-                "processing change event: {}, type: {}, id: {}",
-                change.id, change.change_type, change.object_id
-            );
+    fn slo_compliance_of(
+        &self,
+        extraction_graph: &ExtractionGraph,
+    ) -> Vec<(ExtractionPolicyId, internal_api::SloCompliance)> {
+        extraction_graph
+            .extraction_policies
+            .iter()
+            .filter_map(|extraction_policy| {
+                let slo = extraction_policy.slo?;
+                let (tasks, slow, failed) =
+                    self.shared_state.state_machine.get_finished_task_counts(
+                        &extraction_policy.id,
+                        slo.latency_ms.map(Duration::from_millis),
+                    );
+                Some((
+                    extraction_policy.id.clone(),
+                    slo.compliance(&extraction_policy.name, tasks, slow, failed),
+                ))
+            })
+            .collect()
+    }
 
-            match change.change_type {
-                indexify_internal_api::ChangeType::TombstoneContentTree => {
-                    let _ = self
-                        .handle_tombstone_content_tree_state_change(change)
-                        .await?;
-                    continue;
-                }
-                indexify_internal_api::ChangeType::TaskCompleted {
-                    ref root_content_id,
-                } => {
-                    self.handle_task_completion_state_change(
-                        change.clone(),
-                        root_content_id.clone(),
-                    )
-                    .await?;
-                    continue;
-                }
-                indexify_internal_api::ChangeType::ExecutorAdded => {
-                    self.scheduler.redistribute_tasks(&change).await?
-                }
-                indexify_internal_api::ChangeType::NewContent => {
-                    self.scheduler.create_new_tasks(change).await?
-                }
-                indexify_internal_api::ChangeType::ExecutorRemoved => {
-                    self.scheduler.handle_executor_removed(change).await?
-                }
-                indexify_internal_api::ChangeType::ContentUpdated => {
-                    self.handle_content_updated(change).await?
+    /// Computes the compliance of every extraction policy with objectives for
+    /// the metrics, and logs an alert when a policy starts burning the error
+    /// budget of an objective faster than the alert burn rate
+    pub async fn record_slo_compliance(&self) -> Result<()> {
+        let mut compliance = HashMap::new();
+        for namespace in self.list_namespaces().await? {
+            for extraction_graph in &namespace.extraction_graphs {
+                compliance.extend(self.slo_compliance_of(extraction_graph));
+            }
+        }
+        let mut burning = HashMap::new();
+        for (extraction_policy_id, compliance) in &compliance {
+            for (objective, burn_rate) in [
+                ("latency", compliance.latency_burn_rate),
+                ("error_rate", compliance.error_burn_rate),
+            ] {
+                if let Some(burn_rate) = burn_rate
+                    .filter(|burn_rate| *burn_rate > self.scheduler_config.slo_alert_burn_rate)
+                {
+                    burning.insert(
+                        (extraction_policy_id.clone(), objective),
+                        (burn_rate, compliance.tasks),
+                    );
                 }
             }
         }
+        let mut alerting = self.slo_alerts.lock().unwrap();
+        for ((extraction_policy_id, objective), (burn_rate, tasks)) in &burning {
+            if !alerting.contains(&(extraction_policy_id.clone(), *objective)) {
+                warn!(
+                    "extraction policy {} burns the error budget of its {} objective {:.2} times as fast as allowed over {} tasks",
+                    extraction_policy_id, objective, burn_rate, tasks
+                );
+            }
+        }
+        for (extraction_policy_id, objective) in alerting.iter() {
+            if !burning.contains_key(&(extraction_policy_id.clone(), *objective)) {
+                info!(
+                    "extraction policy {} is back within the error budget of its {} objective",
+                    extraction_policy_id, objective
+                );
+            }
+        }
+        *alerting = burning.into_keys().collect();
+        drop(alerting);
+        self.shared_state
+            .state_machine
+            .set_slo_compliance(compliance);
         Ok(())
     }
 
-    pub async fn subscribe_to_gc_events(&self) -> broadcast::Receiver<GarbageCollectionTask> {
-        self.shared_state.subscribe_to_gc_task_events().await
-    }
-
-    pub fn get_state_watcher(&self) -> Receiver<StateChange> {
-        self.shared_state.get_state_change_watcher()
+    /// Recomputes which extraction policies still have to run on the content
+    /// tree rooted at `content_id`, re-creates their missing tasks and resets
+    /// the number of tasks the content waits for. Returns the number of tasks
+    /// that were re-created.
+    pub async fn repair_content(&self, namespace: &str, content_id: &str) -> Result<usize> {
+        let tree = self.shared_state.get_content_tree_metadata(content_id)?;
+        let root = tree
+            .first()
+            .filter(|root| root.namespace == namespace)
+            .ok_or_else(|| anyhow!("content {} not found", content_id))?;
+        if root.parent_id.is_some() {
+            return Err(anyhow!(
+                "content {} is not a root content, repair its root {} instead",
+                content_id,
+                root.get_root_id()
+            ));
+        }
+        self.repair_content_tree(content_id, &tree).await
     }
 
-    pub async fn create_content_metadata(
+    async fn repair_content_tree(
         &self,
-        content_list: Vec<indexify_internal_api::ContentMetadata>,
-    ) -> Result<Vec<CreateContentStatus>> {
-        self.shared_state.create_content_batch(content_list).await
+        root_content_id: &str,
+        tree: &[internal_api::ContentMetadata],
+    ) -> Result<usize> {
+        let mut tasks = Vec::new();
+        for content in tree {
+            tasks.extend(self.scheduler.create_missing_tasks(content).await?);
+        }
+        info!(
+            "repairing content {}, re-creating {} tasks",
+            root_content_id,
+            tasks.len()
+        );
+        let created = tasks.len();
+        self.shared_state
+            .repair_content(root_content_id, tasks)
+            .await?;
+        Ok(created)
     }
 
-    pub async fn tombstone_content_metadatas(&self, content_ids: &[String]) -> Result<()> {
-        self.shared_state
-            .tombstone_content_batch(content_ids)
+    /// Repairs every root content that is stuck for `stalled_for`
+    pub async fn repair_stuck_content(&self, stalled_for: Duration) -> Result<usize> {
+        let stuck = self
+            .shared_state
+            .state_machine
+            .find_stuck_content(None, stalled_for)
             .await?;
-        Ok(())
+        for content in &stuck {
+            warn!(
+                "content {} is stuck with {} outstanding and {} pending tasks",
+                content.content_id, content.outstanding_tasks, content.pending_tasks
+            );
+            let tree = self
+                .shared_state
+                .get_content_tree_metadata(&content.content_id)?;
+            if let Err(e) = self.repair_content_tree(&content.content_id, &tree).await {
+                error!("unable to repair content {}: {}", content.content_id, e);
+            }
+        }
+        Ok(stuck.len())
     }
 
-    pub async fn get_schema(
+    pub async fn renew_executor_lease(&self, executor_id: &str) -> Result<()> {
+        self.shared_state.renew_executor_lease(executor_id).await
+    }
+
+    /// Removes the executors whose lease ran out `lease_ttl` after they were
+    /// last seen. Their tasks go back to the unassigned tasks and are
+    /// allocated to other executors.
+    pub async fn remove_expired_executors(
         &self,
-        namespace: &str,
-        content_source: &str,
-    ) -> Result<StructuredDataSchema> {
-        self.shared_state
-            .get_structured_data_schema(namespace, content_source)
-            .await
+        now_secs: u64,
+        lease_ttl: Duration,
+    ) -> Result<Vec<String>> {
+        let expired: Vec<String> = self
+            .shared_state
+            .get_executors()
+            .await?
+            .into_iter()
+            .filter(|executor| executor.last_seen + lease_ttl.as_secs() < now_secs)
+            .map(|executor| executor.id)
+            .collect();
+        for executor_id in &expired {
+            warn!("lease of executor {} expired", executor_id);
+            self.remove_executor(executor_id).await?;
+        }
+        Ok(expired)
     }
 
-    pub async fn list_schemas(&self, namespace: &str) -> Result<Vec<StructuredDataSchema>> {
-        self.shared_state.get_schemas_for_namespace(namespace).await
+    /// Repairs task assignments that disagree with the tasks: assignments
+    /// of finished or deleted tasks are dropped, and unfinished tasks that
+    /// are neither assigned nor unassigned are allocated again. They are
+    /// repaired once they were found for the grace period. Returns the
+    /// number of orphaned assignments and unallocated tasks reaped.
+    pub async fn reap_orphaned_assignments(&self, now: u64) -> Result<(usize, usize)> {
+        let (mut finished_task_assignments, mut unallocated_tasks) =
+            self.shared_state.find_orphaned_assignments().await?;
+        {
+            let mut orphans_seen = self.orphans_seen.lock().unwrap();
+            let mut seen = HashMap::new();
+            let mut past_grace = |task_id: &TaskId| {
+                let seen_at = orphans_seen.get(task_id).copied().unwrap_or(now);
+                seen.insert(task_id.clone(), seen_at);
+                seen_at.saturating_add(ORPHANED_ASSIGNMENT_GRACE_SECS) <= now
+            };
+            for task_ids in finished_task_assignments.values_mut() {
+                task_ids.retain(|task_id| past_grace(task_id));
+            }
+            finished_task_assignments.retain(|_, task_ids| !task_ids.is_empty());
+            unallocated_tasks.retain(|task_id| past_grace(task_id));
+            *orphans_seen = seen;
+        }
+        let (dropped, requeued) = self
+            .shared_state
+            .reap_orphaned_assignments(finished_task_assignments, unallocated_tasks)
+            .await?;
+        if dropped > 0 || requeued > 0 {
+            warn!(
+                "reaped {} assignments of finished tasks and {} unallocated tasks",
+                dropped, requeued
+            );
+        }
+        Ok((dropped, requeued))
     }
 
-    pub fn get_leader_change_watcher(&self) -> Receiver<bool> {
-        self.shared_state.leader_change_rx.clone()
+    /// Takes burst tasks back from the executors that hold more tasks than
+    /// their extractor allows, because the overcommit factor or the limit of
+    /// tasks per executor was lowered or the executor reported pressure.
+    /// Executors under critical pressure give back every burst task above the
+    /// nominal limit. Tasks assigned within the nominal limit are never taken
+    /// back, and burst tasks that didn't report progress go first. Returns the
+    /// number of tasks shed.
+    pub async fn shed_burst_tasks(&self) -> Result<usize> {
+        let settings = self.shared_state.get_scheduler_settings()?;
+        let Some(max_tasks) = settings.max_tasks_per_executor else {
+            return Ok(0);
+        };
+        let mut running_tasks: HashMap<String, usize> = HashMap::new();
+        for executor_id in self.shared_state.task_assignments().await?.into_values() {
+            *running_tasks.entry(executor_id).or_default() += 1;
+        }
+        let mut shed: HashMap<String, HashSet<String>> = HashMap::new();
+        for executor in self.shared_state.get_executors().await? {
+            let level = executor.pressure.as_ref().map(|pressure| pressure.level);
+            let throttled = level == Some(internal_api::PressureLevel::Elevated);
+            let load = |running: usize| if throttled { running * 2 } else { running };
+            let weight = executor.capacity_weight() as usize;
+            let mut running = running_tasks.get(&executor.id).copied().unwrap_or_default();
+            //  Burst limits are at least the nominal one
+            if load(running) <= max_tasks as usize * weight {
+                continue;
+            }
+            let mut burst_tasks: Vec<internal_api::Task> = self
+                .shared_state
+                .tasks_for_executor(&executor.id, None)
+                .await?
+                .into_iter()
+                .filter(|task| task.burst && !task.terminal_state())
+                .collect();
+            burst_tasks
+                .sort_by(|a, b| (a.progress.is_some(), &a.id).cmp(&(b.progress.is_some(), &b.id)));
+            for task in burst_tasks {
+                let limit = if level == Some(internal_api::PressureLevel::Critical) {
+                    max_tasks
+                } else {
+                    settings
+                        .burst_tasks_per_executor(&task.extractor)
+                        .unwrap_or(max_tasks)
+                };
+                if load(running) <= limit as usize * weight {
+                    continue;
+                }
+                running -= 1;
+                shed.entry(executor.id.clone()).or_default().insert(task.id);
+            }
+        }
+        let count = shed.values().map(|task_ids| task_ids.len()).sum();
+        if count > 0 {
+            info!("shedding {} burst tasks", count);
+            self.shared_state.shed_burst_tasks(shed).await?;
+        }
+        Ok(count)
     }
 
-    pub fn get_raft_metrics(&self) -> RaftMetrics {
-        self.shared_state.get_raft_metrics()
+    pub async fn list_indexes(&self, namespace: &str) -> Result<Vec<internal_api::Index>> {
+        self.shared_state.list_indexes(namespace).await
     }
 
-    pub async fn wait_content_extraction(&self, content_id: &str) {
-        self.shared_state
-            .state_machine
-            .data
-            .indexify_state
-            .wait_root_task_count_zero(content_id)
-            .await
+    pub async fn get_index(&self, namespace: &str, name: &str) -> Result<internal_api::Index> {
+        let mut s = DefaultHasher::new();
+        namespace.hash(&mut s);
+        name.hash(&mut s);
+        let id = format!("{:x}", s.finish());
+        self.shared_state.get_index(&id).await
     }
-}
+
+    pub async fn update_indexes_state(&self, indexes: Vec<internal_api::Index>) -> Result<()> {
+        self.shared_state.set_indexes(indexes).await
+    }
+
+    pub async fn get_extractor_coordinates(&self, extractor_name: &str) -> Result<Vec<String>> {
+        let executors = self
+            .shared_state
+            .get_executors_for_extractor(extractor_name)
+            .await?;
+        let addresses = executors
+            .iter()
+            .map(|e| e.addr.clone())
+            .collect::<Vec<String>>();
+        Ok(addresses)
+    }
+
+    // TODO: edwin
+    pub async fn register_executor(
+        &self,
+        addr: &str,
+        executor_id: &str,
+        extractors: Vec<internal_api::ExtractorDescription>,
+        profile: internal_api::ExecutorProfile,
+    ) -> Result<()> {
+        let _ = self
+            .shared_state
+            .register_executor(addr, executor_id, extractors, profile)
+            .await;
+        Ok(())
+    }
+
+    pub async fn register_ingestion_server(&self, ingestion_server_id: &str) -> Result<()> {
+        if let Some(forward_to_leader) = self.shared_state.ensure_leader().await? {
+            let leader_node_id = forward_to_leader
+                .leader_id
+                .ok_or_else(|| anyhow::anyhow!("could not get leader node id"))?;
+            let leader_coord_addr = self
+                .shared_state
+                .get_coordinator_addr(leader_node_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("could not get leader node coordinator address"))?;
+            let leader_coord_addr = format!("http://{}", leader_coord_addr);
+            self.forwardable_coordinator
+                .register_ingestion_server(&leader_coord_addr, ingestion_server_id)
+                .await?;
+            return Ok(());
+        }
+        self.garbage_collector
+            .register_ingestion_server(ingestion_server_id)
+            .await;
+        Ok(())
+    }
+
+    pub async fn remove_ingestion_server(&self, ingestion_server_id: &str) -> Result<()> {
+        if let Some(forward_to_leader) = self.shared_state.ensure_leader().await? {
+            let leader_node_id = forward_to_leader
+                .leader_id
+                .ok_or_else(|| anyhow::anyhow!("could not get leader node id"))?;
+            let leader_coord_addr = self
+                .shared_state
+                .get_coordinator_addr(leader_node_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("could not get leader node coordinator address"))?;
+            self.forwardable_coordinator
+                .remove_ingestion_server(&leader_coord_addr, ingestion_server_id)
+                .await?;
+            return Ok(());
+        }
+        self.garbage_collector
+            .remove_ingestion_server(ingestion_server_id)
+            .await;
+        Ok(())
+    }
+
+    pub async fn get_content_metadata(
+        &self,
+        content_ids: Vec<String>,
+    ) -> Result<Vec<indexify_coordinator::ContentMetadata>> {
+        let content = self
+            .shared_state
+            .get_content_metadata_batch(content_ids)
+            .await?;
+        let content = self.internal_content_metadata_to_external(content)?;
+        Ok(content)
+    }
+
+    pub async fn get_task(&self, task_id: &str) -> Result<indexify_coordinator::Task> {
+        let task = self.shared_state.task_with_id(task_id).await?;
+        Ok(task.into())
+    }
+
+    /// States a task moved through, oldest first
+    pub fn get_task_transitions(&self, task_id: &str) -> Result<Vec<internal_api::TaskTransition>> {
+        self.shared_state.get_task_transitions(task_id)
+    }
+
+    /// Most recent attempts of the allocator to place a task of a namespace,
+    /// oldest first, with the executors it considered. Attempts are only kept
+    /// in memory by the leader, so followers ask the leader for them.
+    pub async fn get_task_scheduling(
+        &self,
+        namespace: &str,
+        task_id: &str,
+    ) -> Result<Vec<internal_api::SchedulingAttempt>> {
+        if let Some(forward_to_leader) = self.shared_state.ensure_leader().await? {
+            let leader_node_id = forward_to_leader
+                .leader_id
+                .ok_or_else(|| anyhow::anyhow!("could not get leader node id"))?;
+            let leader_coord_addr = self
+                .shared_state
+                .get_coordinator_addr(leader_node_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("could not get leader node coordinator address"))?;
+            let leader_coord_addr = format!("http://{}", leader_coord_addr);
+            return self
+                .forwardable_coordinator
+                .get_task_scheduling(&leader_coord_addr, namespace, task_id)
+                .await;
+        }
+        let task = self.shared_state.task_with_id(task_id).await?;
+        if task.namespace != namespace {
+            return Err(anyhow!("task {} not found", task_id));
+        }
+        Ok(self
+            .shared_state
+            .state_machine
+            .get_scheduling_attempts(&task.id))
+    }
+
+    pub async fn get_task_and_root_content(
+        &self,
+        task_id: &str,
+    ) -> Result<(internal_api::Task, Option<internal_api::ContentMetadata>)> {
+        let task = self.shared_state.task_with_id(task_id).await?;
+        let mut root_content = None;
+        if let Some(root_content_id) = &task.content_metadata.root_content_id {
+            let root_cm = self
+                .shared_state
+                .get_content_metadata_batch(vec![root_content_id.clone()])
+                .await?;
+            if let Some(root_cm) = root_cm.first() {
+                root_content.replace(root_cm.clone());
+            }
+        }
+        Ok((task, root_content))
+    }
+
+    pub async fn get_content_tree_metadata(
+        &self,
+        content_id: &str,
+    ) -> Result<Vec<indexify_coordinator::ContentMetadata>> {
+        let content_tree = self.shared_state.get_content_tree_metadata(content_id)?;
+        let content_tree = self.internal_content_metadata_to_external(content_tree)?;
+        Ok(content_tree)
+    }
+
+    pub fn get_extractor(
+        &self,
+        extractor_name: &str,
+    ) -> Result<internal_api::ExtractorDescription> {
+        self.shared_state.extractor_with_name(extractor_name)
+    }
+
+    pub fn get_extraction_graph(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<Option<ExtractionGraph>> {
+        Ok(self
+            .shared_state
+            .get_extraction_graphs_by_name(namespace, &[name.to_string()])?
+            .pop()
+            .flatten())
+    }
+
+    pub async fn create_extraction_graph(
+        &self,
+        extraction_graph: ExtractionGraph,
+    ) -> Result<Vec<internal_api::Index>> {
+        self.save_extraction_graph(extraction_graph, ExtractionGraphChange::default(), false)
+            .await
+    }
+
+    /// Creates an extraction graph, or with `replace` replaces the definition
+    /// of an existing one. The definition is added to the history of the graph
+    /// with `change`, whose summary is generated when it's empty. Replacing a
+    /// graph with the same definition changes nothing.
+    pub async fn save_extraction_graph(
+        &self,
+        extraction_graph: ExtractionGraph,
+        mut change: ExtractionGraphChange,
+        replace: bool,
+    ) -> Result<Vec<internal_api::Index>> {
+        let mut structured_data_schema =
+            StructuredDataSchema::new(&extraction_graph.name, &extraction_graph.namespace);
+        let mut indexes_to_create = Vec::new();
+        for extraction_policy in &extraction_graph.extraction_policies {
+            let extractor = self.get_extractor(&extraction_policy.extractor)?;
+            for (output_name, output_schema) in extractor.outputs {
+                match output_schema {
+                    OutputSchema::Embedding(embeddings) => {
+                        let mut index_to_create = internal_api::Index {
+                            id: "".to_string(),
+                            namespace: extraction_policy.namespace.clone(),
+                            name: "".to_string(),
+                            table_name: "".to_string(),
+                            schema: serde_json::to_value(embeddings).unwrap().to_string(),
+                            extraction_policy_name: extraction_policy.name.clone(),
+                            extractor_name: extractor.name.clone(),
+                            graph_name: extraction_graph.name.clone(),
+                            visibility: false,
+                        };
+                        index_to_create.name = index_to_create.build_name(&output_name);
+                        index_to_create.table_name = index_to_create.build_table_name(&output_name);
+                        index_to_create.id = index_to_create.id();
+                        indexes_to_create.push(index_to_create);
+                    }
+                    OutputSchema::Attributes(columns) => {
+                        structured_data_schema.merge(columns);
+                    }
+                }
+            }
+        }
+        change.created_at = utils::timestamp_secs();
+        if !replace {
+            if change.summary.is_empty() {
+                change.summary = "created".to_string();
+            }
+            self.shared_state
+                .create_extraction_graph(
+                    extraction_graph,
+                    structured_data_schema,
+                    indexes_to_create.clone(),
+                    change,
+                )
+                .await?;
+            return Ok(indexes_to_create);
+        }
+
+        let current = self
+            .get_extraction_graph(&extraction_graph.namespace, &extraction_graph.name)?
+            .ok_or_else(|| {
+                anyhow!(
+                    "extraction graph {} not found in namespace {}",
+                    extraction_graph.name,
+                    extraction_graph.namespace
+                )
+            })?;
+        let Some(summary) = summarize_extraction_graph_change(&current, &extraction_graph) else {
+            return Ok(indexes_to_create);
+        };
+        if change.summary.is_empty() {
+            change.summary = summary;
+        }
+        self.shared_state
+            .update_extraction_graph(
+                extraction_graph,
+                structured_data_schema,
+                indexes_to_create.clone(),
+                change,
+            )
+            .await?;
+        Ok(indexes_to_create)
+    }
+
+    /// Definitions of an extraction graph, oldest first
+    pub fn list_extraction_graph_versions(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<Vec<internal_api::ExtractionGraphVersion>> {
+        let history = self
+            .shared_state
+            .get_extraction_graph_history(&ExtractionGraph::create_id(name, namespace))?;
+        if !history.is_empty() {
+            return Ok(history);
+        }
+        //  Graphs created before the history was kept have their definition as
+        //  first version
+        Ok(self
+            .get_extraction_graph(namespace, name)?
+            .map(|extraction_graph| internal_api::ExtractionGraphVersion {
+                version: 1,
+                extraction_graph,
+                author: String::new(),
+                summary: "definition before the history was recorded".to_string(),
+                created_at: 0,
+            })
+            .into_iter()
+            .collect())
+    }
+
+    /// Resources the tasks of every version of an extraction graph used, as
+    /// reported by executors
+    pub fn get_extraction_graph_usage(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<Vec<internal_api::ExtractionGraphUsage>> {
+        if self.get_extraction_graph(namespace, name)?.is_none() {
+            return Err(anyhow!("extraction graph {} not found", name));
+        }
+        self.shared_state
+            .get_extraction_graph_usage(&ExtractionGraph::create_id(name, namespace))
+    }
+
+    /// Compliance of the extraction policies of a graph that have objectives
+    /// with them, over the tasks they finished within the last hour
+    pub fn get_slo_compliance(
+        &self,
+        namespace: &str,
+        name: &str,
+    ) -> Result<Vec<internal_api::SloCompliance>> {
+        let extraction_graph = self
+            .get_extraction_graph(namespace, name)?
+            .ok_or_else(|| anyhow!("extraction graph {} not found", name))?;
+        Ok(self
+            .slo_compliance_of(&extraction_graph)
+            .into_iter()
+            .map(|(_, compliance)| compliance)
+            .collect())
+    }
+
+    /// Makes a prior definition of an extraction graph its latest version and
+    /// returns it
+    pub async fn rollback_extraction_graph(
+        &self,
+        namespace: &str,
+        name: &str,
+        version: u64,
+        author: &str,
+    ) -> Result<(ExtractionGraph, Vec<internal_api::Index>)> {
+        let target = self
+            .list_extraction_graph_versions(namespace, name)?
+            .into_iter()
+            .find(|v| v.version == version)
+            .ok_or_else(|| {
+                anyhow!(
+                    "version {} of extraction graph {} not found in namespace {}",
+                    version,
+                    name,
+                    namespace
+                )
+            })?;
+        let change = ExtractionGraphChange {
+            author: author.to_string(),
+            summary: format!("rolled back to version {}", version),
+            ..Default::default()
+        };
+        let indexes = self
+            .save_extraction_graph(target.extraction_graph.clone(), change, true)
+            .await?;
+        Ok((target.extraction_graph, indexes))
+    }
+
+    pub async fn create_content_tree_tasks(
+        &self,
+        content_tree: Vec<internal_api::ContentMetadata>,
+        state_change: StateChange,
+    ) -> Result<()> {
+        if let Some(max_pending_gc_tasks) = self
+            .shared_state
+            .get_scheduler_settings()?
+            .max_pending_gc_tasks
+        {
+            if self.shared_state.count_unfinished_gc_tasks() >= max_pending_gc_tasks as usize {
+                //  The state change stays unprocessed until garbage collection
+                //  catches up
+                info!(
+                    "putting off garbage collection of content {}, {} or more garbage collection tasks are pending",
+                    state_change.object_id, max_pending_gc_tasks
+                );
+                self.gc_waiting_for_capacity.store(true, Ordering::Relaxed);
+                return Ok(());
+            }
+        }
+        let task_type = match state_change.change_type {
+            indexify_internal_api::ChangeType::TombstoneContentTree => ServerTaskType::Delete,
+            _ => ServerTaskType::UpdateLabels,
+        };
+        if task_type == ServerTaskType::Delete {
+            //  Deleting the content without its archive would lose it for
+            //  good, so the state change stays unprocessed until the archive
+            //  is written
+            match self
+                .archive_content_tree(&state_change.id, &content_tree)
+                .await
+            {
+                Result::Ok(true) => {}
+                Result::Ok(false) => {
+                    debug!(
+                        "putting off garbage collection of content {} until its archive is written",
+                        state_change.object_id
+                    );
+                    self.gc_waiting_for_archive.store(true, Ordering::Relaxed);
+                    return Ok(());
+                }
+                Err(e) => {
+                    error!(
+                        "putting off garbage collection of content {}: {:?}",
+                        state_change.object_id, e
+                    );
+                    self.deferred_gc.store(true, Ordering::Relaxed);
+                    return Ok(());
+                }
+            }
+        }
+        let mut output_tables = HashMap::new();
+
+        for content_metadata in &content_tree {
+            if content_metadata.extraction_policy_ids.keys().len() == 0 {
+                continue;
+            }
+            let applied_extraction_policy_ids: HashSet<String> = content_metadata
+                .extraction_policy_ids
+                .clone()
+                .into_iter()
+                .filter(|(_, completion_time)| *completion_time > 0)
+                .map(|(extraction_policy_id, _)| extraction_policy_id)
+                .collect();
+
+            if applied_extraction_policy_ids.is_empty() {
+                continue;
+            }
+            let applied_extraction_policies = self
+                .shared_state
+                .get_extraction_policies_from_ids(applied_extraction_policy_ids)
+                .await?;
+            for applied_extraction_policy in applied_extraction_policies.clone().unwrap() {
+                output_tables.insert(
+                    content_metadata.id.clone(),
+                    applied_extraction_policy
+                        .output_table_mapping
+                        .values()
+                        .cloned()
+                        .collect::<HashSet<_>>(),
+                );
+            }
+        }
+
+        let tasks = self
+            .garbage_collector
+            .create_gc_tasks(content_tree, output_tables, task_type)
+            .await?;
+        self.shared_state.create_gc_tasks(tasks.clone()).await?;
+        self.shared_state
+            .mark_change_events_as_processed(vec![state_change], Vec::new())
+            .await?;
+        Ok(())
+    }
+
+    /// Exports the context, tasks and content metadata of a content tree to
+    /// the blob store, when archiving is configured. The archive is written
+    /// off the scheduler loop, returns whether it was written.
+    async fn archive_content_tree(
+        &self,
+        change_id: &StateChangeId,
+        content_tree: &[internal_api::ContentMetadata],
+    ) -> Result<bool> {
+        let (Some(archiver), Some(root)) = (&self.content_archiver, content_tree.first()) else {
+            return Ok(true);
+        };
+        archiver
+            .archive_in_background(change_id, async {
+                Ok(ContentArchive {
+                    namespace: root.namespace.clone(),
+                    extraction_graph_names: root.extraction_graph_names.clone(),
+                    root_content_id: root.id.clone(),
+                    archived_at: utils::timestamp_secs(),
+                    context: self.shared_state.get_content_context(&root.id.id)?,
+                    tasks: self.content_tree_tasks(content_tree).await?,
+                    content: content_tree.to_vec(),
+                })
+            })
+            .await
+    }
+
+    /// Tasks that ran on a content tree, read by the ids of the tasks of the
+    /// extraction policies applied to each content
+    async fn content_tree_tasks(
+        &self,
+        content_tree: &[internal_api::ContentMetadata],
+    ) -> Result<Vec<internal_api::Task>> {
+        let policy_ids: HashSet<String> = content_tree
+            .iter()
+            .flat_map(|content| content.extraction_policy_ids.keys().cloned())
+            .collect();
+        if policy_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let policies: HashMap<String, internal_api::ExtractionPolicy> = self
+            .shared_state
+            .get_extraction_policies_from_ids(policy_ids)
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|policy| (policy.id.clone(), policy))
+            .collect();
+        let task_ids: HashSet<TaskId> = content_tree
+            .iter()
+            .flat_map(|content| {
+                content
+                    .extraction_policy_ids
+                    .keys()
+                    .filter_map(|policy_id| policies.get(policy_id))
+                    .map(|policy| scheduler::task_id(policy, &content.id))
+            })
+            .collect();
+        Ok(self
+            .shared_state
+            .get_tasks_from_ids(&task_ids)?
+            .into_values()
+            .collect())
+    }
+
+    pub async fn create_gc_tasks(&self, state_change: StateChange) -> Result<()> {
+        let content_id: ContentMetadataId = state_change.object_id.clone().try_into()?;
+        let content_tree_metadata = self
+            .shared_state
+            .get_content_tree_metadata_with_version(&content_id)?;
+        self.create_content_tree_tasks(content_tree_metadata, state_change)
+            .await
+    }
+
+    async fn handle_tombstone_content_tree_state_change(&self, change: StateChange) -> Result<()> {
+        if let Some(forward_to_leader) = self.shared_state.ensure_leader().await? {
+            let leader_id = forward_to_leader
+                .leader_id
+                .ok_or_else(|| anyhow::anyhow!("could not get leader node id"))?;
+            let leader_coord_addr = self
+                .shared_state
+                .get_coordinator_addr(leader_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("could not get leader node coordinator address"))?;
+            self.forwardable_coordinator
+                .create_gc_tasks(&leader_coord_addr, change)
+                .await?;
+            return Ok(());
+        }
+
+        //  this coordinator node is the leader
+        self.create_gc_tasks(change).await
+    }
+
+    async fn handle_content_updated(&self, state_change: StateChange) -> Result<()> {
+        let content_tree = self
+            .shared_state
+            .get_content_tree_metadata(&state_change.object_id)?;
+        self.create_content_tree_tasks(content_tree, state_change)
+            .await
+    }
+
+    async fn handle_task_completion_state_change(
+        &self,
+        change: StateChange,
+        root_content_id: ContentMetadataId,
+    ) -> Result<()> {
+        let are_content_tasks_completed = self
+            .shared_state
+            .are_content_tasks_completed(&root_content_id)
+            .await;
+
+        if !are_content_tasks_completed {
+            return Ok(());
+        }
+
+        //  this is the first version of the content, so nothing to garbage collect
+        if root_content_id.version <= 1 {
+            self.shared_state
+                .mark_change_events_as_processed(vec![change], Vec::new())
+                .await?;
+            return Ok(());
+        }
+        let previous_version =
+            ContentMetadataId::new_with_version(&root_content_id.id, root_content_id.version - 1);
+        let content_metadata = self
+            .shared_state
+            .state_machine
+            .get_content_by_id_and_version(&previous_version)
+            .await?
+            .ok_or(anyhow!("content with id: {} not found", previous_version))?;
+        self.shared_state
+            .tombstone_content_batch_with_version(
+                &[content_metadata.id.clone()],
+                vec![StateChangeProcessed {
+                    state_change_id: change.id,
+                    processed_at: utils::timestamp_secs(),
+                }],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Processes the pending state changes. Changes that a later change of
+    /// the pass covers are marked processed without being processed, see
+    /// [`coalesce_state_changes`]. Executor changes affect the allocation of
+    /// every namespace, so they are processed one at a time in the order they
+    /// were made. The content changes between two of them are split into
+    /// shards by namespace and every shard is processed by a worker of its
+    /// own, so the changes of one namespace stay ordered. Tasks are placed on
+    /// executors one shard at a time, see `Scheduler::allocation`.
+    /// A pass processes the oldest state changes up to the batch size of the
+    /// scheduler, the rest wait for the next tick.
+    #[tracing::instrument(skip(self))]
+    pub async fn run_scheduler(&self) -> Result<()> {
+        if self.shared_state.get_maintenance_mode()?.enabled {
+            //  The state changes are processed once maintenance ends
+            self.paused_for_maintenance.store(true, Ordering::Relaxed);
+            return Ok(());
+        }
+        let _timer = Timer::start(&self.shared_state.metrics.scheduler_invocations);
+
+        let mut state_changes = self.shared_state.unprocessed_state_change_events().await?;
+        if let Some(batch_size) = self.scheduler_config.state_change_batch_size {
+            if state_changes.len() > batch_size {
+                state_changes.truncate(batch_size.max(1));
+                self.state_changes_left.store(true, Ordering::Relaxed);
+            }
+        }
+        let (state_changes, redundant) = coalesce_state_changes(state_changes);
+        if !redundant.is_empty() {
+            debug!(
+                "skipping {} state changes covered by later ones",
+                redundant.len()
+            );
+            self.shared_state
+                .mark_change_events_as_processed(redundant, Vec::new())
+                .await?;
+        }
+        let mut content_changes = Vec::new();
+        for change in state_changes {
+            if is_executor_change(&change) {
+                self.process_content_changes(std::mem::take(&mut content_changes))
+                    .await?;
+                self.process_state_change(change).await?;
+            } else {
+                content_changes.push(change);
+            }
+        }
+        self.process_content_changes(content_changes).await
+    }
+
+    /// Processes content state changes on a worker per shard, in order
+    /// within each namespace
+    async fn process_content_changes(&self, changes: Vec<StateChange>) -> Result<()> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let coordinator = self
+            .this
+            .upgrade()
+            .ok_or_else(|| anyhow!("coordinator is shutting down"))?;
+        let mut shards: Vec<Vec<StateChange>> = (0..SCHEDULER_SHARDS).map(|_| Vec::new()).collect();
+        for change in changes {
+            let mut s = DefaultHasher::new();
+            self.namespace_of_change(&change).hash(&mut s);
+            shards[(s.finish() % SCHEDULER_SHARDS as u64) as usize].push(change);
+        }
+        run_scheduler_shards(shards, move |change| {
+            let coordinator = coordinator.clone();
+            async move { coordinator.process_state_change(change).await }
+        })
+        .await
+    }
+
+    /// Namespace of the content a content state change is about. Changes
+    /// whose content is gone are keyed by the id of their root content
+    /// instead.
+    fn namespace_of_change(&self, change: &StateChange) -> String {
+        let root_content_id = self.root_content_of_change(change);
+        match self
+            .shared_state
+            .state_machine
+            .get_latest_version_of_content(&root_content_id)
+        {
+            Result::Ok(Some(content)) => content.namespace,
+            _ => root_content_id,
+        }
+    }
+
+    /// Id of the root of the content tree a content state change is about
+    fn root_content_of_change(&self, change: &StateChange) -> String {
+        if let indexify_internal_api::ChangeType::TaskCompleted { root_content_id } =
+            &change.change_type
+        {
+            return root_content_id.id.clone();
+        }
+        if let Some(root_content_id) = &change.refcnt_object_id {
+            return root_content_id.clone();
+        }
+        match self
+            .shared_state
+            .state_machine
+            .get_latest_version_of_content(&change.object_id)
+        {
+            Result::Ok(Some(content)) => content.get_root_id().to_string(),
+            _ => change.object_id.clone(),
+        }
+    }
+
+    async fn process_state_change(&self, change: StateChange) -> Result<()> {
+        debug!(
+            "processing change event: {}, type: {}, id: {}",
+            change.id, change.change_type, change.object_id
+        );
+
+        match change.change_type {
+            indexify_internal_api::ChangeType::TombstoneContentTree => {
+                let _ = self
+                    .handle_tombstone_content_tree_state_change(change)
+                    .await?;
+            }
+            indexify_internal_api::ChangeType::TaskCompleted {
+                ref root_content_id,
+            } => {
+                self.handle_task_completion_state_change(change.clone(), root_content_id.clone())
+                    .await?;
+            }
+            indexify_internal_api::ChangeType::ExecutorAdded => {
+                self.scheduler.redistribute_tasks(&change).await?
+            }
+            indexify_internal_api::ChangeType::NewContent => {
+                self.scheduler.create_new_tasks(change).await?
+            }
+            indexify_internal_api::ChangeType::ExecutorRemoved |
+            indexify_internal_api::ChangeType::OrphanedAssignmentsReaped |
+            indexify_internal_api::ChangeType::BurstTasksShed |
+            indexify_internal_api::ChangeType::ContentRepaired => {
+                self.scheduler.allocate_unassigned_tasks(change).await?
+            }
+            indexify_internal_api::ChangeType::ContentUpdated => {
+                self.handle_content_updated(change).await?
+            }
+            indexify_internal_api::ChangeType::RerunExtractionPolicy {
+                ref extraction_policy_id,
+            } => {
+                let extraction_policy_id = extraction_policy_id.clone();
+                self.scheduler
+                    .rerun_extraction_policy(change, &extraction_policy_id)
+                    .await?
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn subscribe_to_gc_events(&self) -> broadcast::Receiver<GarbageCollectionTask> {
+        self.shared_state.subscribe_to_gc_task_events().await
+    }
+
+    pub fn subscribe_to_state_changes(&self, consumer: &'static str) -> ChangeWatcher<StateChange> {
+        self.shared_state.subscribe_to_state_changes(consumer)
+    }
+
+    pub async fn create_content_metadata(
+        &self,
+        content_list: Vec<indexify_internal_api::ContentMetadata>,
+    ) -> Result<Vec<CreateContentStatus>> {
+        self.shared_state.create_content_batch(content_list).await
+    }
+
+    pub async fn tombstone_content_metadatas(&self, content_ids: &[String]) -> Result<()> {
+        self.shared_state
+            .tombstone_content_batch(content_ids)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_schema(
+        &self,
+        namespace: &str,
+        content_source: &str,
+    ) -> Result<StructuredDataSchema> {
+        self.shared_state
+            .get_structured_data_schema(namespace, content_source)
+            .await
+    }
+
+    pub async fn list_schemas(&self, namespace: &str) -> Result<Vec<StructuredDataSchema>> {
+        self.shared_state.get_schemas_for_namespace(namespace).await
+    }
+
+    pub fn get_leader_change_watcher(&self) -> Receiver<bool> {
+        self.shared_state.leader_change_rx.clone()
+    }
+
+    pub fn get_raft_metrics(&self) -> RaftMetrics {
+        self.shared_state.get_raft_metrics()
+    }
+
+    pub async fn wait_content_extraction(&self, content_id: &str) {
+        self.shared_state
+            .state_machine
+            .data
+            .indexify_state
+            .wait_root_task_count_zero(content_id)
+            .await
+    }
+}
+
+/// Summarizes how the definition of a graph changed by comparing its policies
+/// by name, or returns `None` when the definitions are the same
+fn summarize_extraction_graph_change(
+    current: &ExtractionGraph,
+    proposed: &ExtractionGraph,
+) -> Option<String> {
+    let current_policies: HashMap<&str, &internal_api::ExtractionPolicy> = current
+        .extraction_policies
+        .iter()
+        .map(|policy| (policy.name.as_str(), policy))
+        .collect();
+    let proposed_policies: HashMap<&str, &internal_api::ExtractionPolicy> = proposed
+        .extraction_policies
+        .iter()
+        .map(|policy| (policy.name.as_str(), policy))
+        .collect();
+    let mut added: Vec<&str> = proposed_policies
+        .keys()
+        .filter(|name| !current_policies.contains_key(*name))
+        .copied()
+        .collect();
+    let mut removed: Vec<&str> = current_policies
+        .keys()
+        .filter(|name| !proposed_policies.contains_key(*name))
+        .copied()
+        .collect();
+    let mut changed: Vec<&str> = proposed_policies
+        .iter()
+        .filter(|(name, policy)| {
+            current_policies
+                .get(*name)
+                .map_or(false, |current| current != *policy)
+        })
+        .map(|(name, _)| *name)
+        .collect();
+    added.sort();
+    removed.sort();
+    changed.sort();
+
+    let mut changes = Vec::new();
+    for (kind, names) in [("added", added), ("removed", removed), ("changed", changed)] {
+        if !names.is_empty() {
+            changes.push(format!("{} policies {}", kind, names.join(", ")));
+        }
+    }
+    if current.max_active_content != proposed.max_active_content {
+        changes.push("changed max_active_content".to_string());
+    }
+    if current.latency_tolerant != proposed.latency_tolerant {
+        changes.push("changed latency_tolerant".to_string());
+    }
+    if current.zone_placement != proposed.zone_placement {
+        changes.push("changed zone_placement".to_string());
+    }
+    Some(changes.join("; ")).filter(|summary| !summary.is_empty())
+}
+
+/// Whether a state change changes the executors or puts tasks back to be
+/// allocated, rather than being about one content
+fn is_executor_change(change: &StateChange) -> bool {
+    matches!(
+        change.change_type,
+        indexify_internal_api::ChangeType::ExecutorAdded |
+            indexify_internal_api::ChangeType::ExecutorRemoved |
+            indexify_internal_api::ChangeType::OrphanedAssignmentsReaped |
+            indexify_internal_api::ChangeType::BurstTasksShed |
+            indexify_internal_api::ChangeType::ContentRepaired
+    )
+}
+
+/// What processing a state change does, changes with the same effect have
+/// the same key. Changes that create or finish the work of one content have
+/// no key, each of them is processed.
+fn coalesce_key(change: &StateChange) -> Option<(&'static str, &str, &str)> {
+    match &change.change_type {
+        //  Each of these allocates every unassigned task
+        indexify_internal_api::ChangeType::ExecutorRemoved |
+        indexify_internal_api::ChangeType::OrphanedAssignmentsReaped |
+        indexify_internal_api::ChangeType::BurstTasksShed |
+        indexify_internal_api::ChangeType::ContentRepaired => Some(("allocate", "", "")),
+        indexify_internal_api::ChangeType::ExecutorAdded => {
+            Some(("executor_added", &change.object_id, ""))
+        }
+        indexify_internal_api::ChangeType::ContentUpdated => {
+            Some(("content_updated", &change.object_id, ""))
+        }
+        indexify_internal_api::ChangeType::RerunExtractionPolicy {
+            extraction_policy_id,
+        } => Some(("rerun", &change.object_id, extraction_policy_id)),
+        indexify_internal_api::ChangeType::NewContent |
+        indexify_internal_api::ChangeType::TombstoneContentTree |
+        indexify_internal_api::ChangeType::TaskCompleted { .. } => None,
+    }
+}
+
+/// Splits state changes into the ones to process and the redundant ones. Of
+/// the changes with the same effect only the latest is processed: handlers
+/// read the state as it is when they run, which already holds what every
+/// earlier change of the same kind recorded.
+fn coalesce_state_changes(changes: Vec<StateChange>) -> (Vec<StateChange>, Vec<StateChange>) {
+    let redundant: HashSet<StateChangeId> = {
+        let mut latest = HashMap::new();
+        for change in &changes {
+            if let Some(key) = coalesce_key(change) {
+                latest.insert(key, change.id);
+            }
+        }
+        changes
+            .iter()
+            .filter(|change| coalesce_key(change).map_or(false, |key| latest[&key] != change.id))
+            .map(|change| change.id)
+            .collect()
+    };
+    changes
+        .into_iter()
+        .partition(|change| !redundant.contains(&change.id))
+}
+
+/// Processes the state changes of every shard in order on a worker of its
+/// own. A shard stops at its first failing change, which is left unprocessed
+/// together with the changes after it so the next pass retries them in
+/// order, while the other shards keep going.
+async fn run_scheduler_shards<F, Fut>(shards: Vec<Vec<StateChange>>, process: F) -> Result<()>
+where
+    F: Fn(StateChange) -> Fut + Clone + Send + 'static,
+    Fut: std::future::Future<Output = Result<()>> + Send + 'static,
+{
+    let mut workers = tokio::task::JoinSet::new();
+    for (shard, changes) in shards.into_iter().enumerate() {
+        if changes.is_empty() {
+            continue;
+        }
+        let process = process.clone();
+        workers.spawn(async move {
+            for change in changes {
+                let change_id = change.id;
+                if let Err(e) = process(change).await {
+                    error!(
+                        "scheduler shard {} stopped at state change {}: {:?}",
+                        shard, change_id, e
+                    );
+                    return Err(e);
+                }
+            }
+            Ok(())
+        });
+    }
+    let mut failed = 0;
+    while let Some(result) = workers.join_next().await {
+        match result {
+            Result::Ok(Result::Ok(())) => {}
+            Result::Ok(Err(_)) => failed += 1,
+            Err(e) => {
+                error!("scheduler shard worker panicked: {:?}", e);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(anyhow!(
+            "{} scheduler shards stopped early, their state changes are retried on the next pass",
+            failed
+        ));
+    }
+    Ok(())
+}
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashMap, fs, sync::Arc, time::Duration, vec};
+    use std::{
+        collections::{HashMap, HashSet},
+        fs,
+        io::Read,
+        sync::{atomic::Ordering, Arc},
+        time::Duration,
+        vec,
+    };
+
+    use indexify_internal_api as internal_api;
+    use indexify_proto::indexify_coordinator::{self, CreateContentStatus};
+    use internal_api::{
+        ContentMetadataId,
+        ContentSource,
+        ExtractionGraphChange,
+        SchedulerSettings,
+        TaskOutcome,
+        UnschedulableReason,
+    };
+    use serde_json::json;
+
+    use super::{
+        coalesce_state_changes,
+        run_scheduler_shards,
+        Coordinator,
+        ORPHANED_ASSIGNMENT_GRACE_SECS,
+    };
+    use crate::{
+        blob_storage::{BlobStorage, BlobStorageConfig, DiskStorageConfig},
+        chaos::ChaosInjector,
+        content_archive::{ContentArchive, ContentArchiver},
+        coordinator_client::CoordinatorClient,
+        garbage_collector::GarbageCollector,
+        server_config::{
+            ChaosConfig,
+            ContentArchiveConfig,
+            DiagnosticsConfig,
+            SchedulerConfig,
+            ServerConfig,
+        },
+        state::App,
+        task_bundler::TaskBundler,
+        task_canceller::{CancelTasksFilter, JOB_RETENTION_SECS, STALE_JOB_SECS},
+        test_util::db_utils::{
+            complete_task,
+            create_content_for_task,
+            create_test_extraction_graph,
+            create_test_extraction_graph_with_children,
+            mock_extractor,
+            next_child,
+            perform_all_tasks,
+            perform_task,
+            test_mock_content_metadata,
+            Parent::{Child, Root},
+            DEFAULT_TEST_NAMESPACE,
+        },
+        test_utils::RaftTestCluster,
+    };
+
+    async fn setup_coordinator() -> (Arc<Coordinator>, Arc<App>) {
+        setup_coordinator_with(None, SchedulerConfig::default()).await
+    }
+
+    async fn setup_coordinator_with(
+        content_archiver: Option<ContentArchiver>,
+        scheduler_config: SchedulerConfig,
+    ) -> (Arc<Coordinator>, Arc<App>) {
+        let config = Arc::new(ServerConfig {
+            scheduler: scheduler_config,
+            ..Default::default()
+        });
+        let _ = fs::remove_dir_all(config.state_store.clone().path.unwrap());
+        let garbage_collector = GarbageCollector::new();
+        let coordinator_client = CoordinatorClient::new(Arc::clone(&config));
+        let shared_state = App::new(
+            config.clone(),
+            None,
+            garbage_collector.clone(),
+            &config.coordinator_addr,
+            Arc::new(crate::metrics::init_provider()),
+        )
+        .await
+        .unwrap();
+        shared_state.initialize_raft().await.unwrap();
+        let coordinator = crate::coordinator::Coordinator::new(
+            shared_state.clone(),
+            coordinator_client,
+            garbage_collector,
+            content_archiver,
+            config.scheduler.clone(),
+        );
+        (coordinator, shared_state)
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_create_and_read_extraction_graph() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+
+        // Add a namespace
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+
+        //  Register an executor
+        let extractor = mock_extractor();
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                "test_executor_id",
+                vec![extractor],
+                Default::default(),
+            )
+            .await?;
+        coordinator.run_scheduler().await?;
+
+        //  Create an extraction graph
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator.run_scheduler().await?;
+
+        //  Read the extraction graph back
+        let ret_graph =
+            shared_state.get_extraction_graphs_by_name(DEFAULT_TEST_NAMESPACE, &[eg.name])?;
+        assert!(ret_graph.first().unwrap().is_some());
+        assert_eq!(ret_graph.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_create_content_metadata() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+
+        // Add a namespace
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+
+        //  Register an executor
+        let extractor = mock_extractor();
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                "test_executor_id",
+                vec![extractor],
+                Default::default(),
+            )
+            .await?;
+        coordinator.run_scheduler().await?;
+
+        //  Create an extraction graph
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator.run_scheduler().await?;
+
+        let content_metadata = test_mock_content_metadata("test", "test", &eg.name);
+
+        //  Add content which will trigger task creation
+        coordinator
+            .create_content_metadata(vec![content_metadata.clone()])
+            .await?;
+        coordinator.run_scheduler().await?;
+
+        //  Read the content back from shared state and ensure graph id is correct
+        let retr_content = shared_state
+            .get_content_metadata_batch(vec![content_metadata.id.id.clone()])
+            .await?;
+        assert_eq!(
+            retr_content.first().unwrap().extraction_graph_names.len(),
+            1
+        );
+        assert_eq!(
+            retr_content
+                .first()
+                .unwrap()
+                .extraction_graph_names
+                .first()
+                .unwrap(),
+            &eg.name
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    // #[tracing_test::traced_test]
+    async fn test_create_and_complete_tasks() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+
+        // Add a namespace
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+
+        //  Register an executor
+        let executor_id = "test_executor_id";
+        let extractor = mock_extractor();
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![extractor],
+                Default::default(),
+            )
+            .await?;
+        coordinator.run_scheduler().await?;
+
+        //  Create an extraction graph
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator.run_scheduler().await?;
+
+        //  Add content which will trigger task creation because it is part of the graph
+        let content_metadata = test_mock_content_metadata("test", "test", &eg.name);
+        coordinator
+            .create_content_metadata(vec![content_metadata.clone()])
+            .await?;
+        let events = shared_state.unprocessed_state_change_events().await?;
+        assert_eq!(events.len(), 1);
+        coordinator.run_scheduler().await?;
+
+        //  Check that tasks have been created and assigned
+        let tasks = shared_state.unassigned_task_ids().await;
+        assert_eq!(tasks.len(), 0);
+        let tasks = shared_state.tasks_for_executor(executor_id, None).await?;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(
+            shared_state.unprocessed_state_change_events().await?.len(),
+            0
+        );
+
+        //  Create a separate piece of content metadata which will not trigger task
+        // creation
+        let content_metadata = test_mock_content_metadata("test2", "test2", "not_present_graph");
+        let result = coordinator
+            .create_content_metadata(vec![content_metadata.clone()])
+            .await;
+        assert!(result.is_err());
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state.unassigned_task_ids().await;
+        assert_eq!(tasks.len(), 0);
+        let tasks = shared_state.tasks_for_executor(executor_id, None).await?;
+        assert_eq!(tasks.len(), 1);
+
+        // FIX ME Should be in state machine test
+        let mut task_clone = tasks[0].clone();
+        task_clone.outcome = internal_api::TaskOutcome::Success;
+        shared_state
+            .update_task(task_clone.clone(), Some(executor_id.to_string()))
+            .await
+            .unwrap();
+        let tasks = shared_state
+            .tasks_for_executor(executor_id, None)
+            .await
+            .unwrap();
+        assert_eq!(0, tasks.clone().len());
+
+        //  The task was created, assigned and finished
+        let transitions = coordinator.get_task_transitions(&task_clone.id)?;
+        assert_eq!(
+            transitions.iter().map(|t| t.state).collect::<Vec<_>>(),
+            vec![
+                internal_api::TaskState::Pending,
+                internal_api::TaskState::Running,
+                internal_api::TaskState::Finalized
+            ]
+        );
+        assert_eq!(transitions[0].executor_id, None);
+        assert_eq!(transitions[1].executor_id.as_deref(), Some(executor_id));
+        assert_eq!(
+            transitions[2].outcome,
+            Some(internal_api::TaskOutcome::Success)
+        );
+        assert!(transitions.windows(2).all(|w| w[0].at <= w[1].at));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_remove_executors_with_expired_leases() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+
+        let executor_id = "test_executor_id";
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        coordinator.run_scheduler().await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![test_mock_content_metadata("test", "test", &eg.name)])
+            .await?;
+        coordinator.run_scheduler().await?;
+        assert_eq!(
+            shared_state
+                .tasks_for_executor(executor_id, None)
+                .await?
+                .len(),
+            1
+        );
+
+        let lease_ttl = Duration::from_secs(30);
+        let last_seen = shared_state
+            .get_executor_by_id(executor_id)
+            .await?
+            .last_seen;
+        coordinator.renew_executor_lease(executor_id).await?;
+        assert!(
+            shared_state
+                .get_executor_by_id(executor_id)
+                .await?
+                .last_seen >=
+                last_seen
+        );
+
+        //  The lease is still valid
+        let removed = coordinator
+            .remove_expired_executors(last_seen + lease_ttl.as_secs(), lease_ttl)
+            .await?;
+        assert!(removed.is_empty());
+
+        let now = shared_state
+            .get_executor_by_id(executor_id)
+            .await?
+            .last_seen +
+            lease_ttl.as_secs() +
+            1;
+        let removed = coordinator.remove_expired_executors(now, lease_ttl).await?;
+        assert_eq!(removed, vec![executor_id.to_string()]);
+        assert!(shared_state.get_executor_by_id(executor_id).await.is_err());
+        assert_eq!(shared_state.unassigned_task_ids().await.len(), 1);
+
+        //  Renewing the lease of a removed executor doesn't register it again
+        coordinator.renew_executor_lease(executor_id).await?;
+        assert!(shared_state.get_executor_by_id(executor_id).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reject_stale_task_updates() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+
+        let executor_id = "test_executor_id";
+        let extractor = mock_extractor();
+        let extractor_name = extractor.name.clone();
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![extractor],
+                Default::default(),
+            )
+            .await?;
+        coordinator.run_scheduler().await?;
+
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![
+                test_mock_content_metadata("test1", "test1", &eg.name),
+                test_mock_content_metadata("test2", "test2", &eg.name),
+            ])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state.tasks_for_executor(executor_id, None).await?;
+        assert_eq!(tasks.len(), 2);
+        let load = || shared_state.get_executor_loads_for_extractor(&extractor_name);
+        assert_eq!(load(), vec![(executor_id.to_string(), 2)]);
+
+        //  An executor the task isn't assigned to can't finish it
+        let result = coordinator
+            .update_task(
+                &tasks[0].id,
+                "stale_executor_id",
+                internal_api::TaskOutcome::Success,
+                None,
+                None,
+            )
+            .await;
+        assert!(result.is_err());
+        assert!(!shared_state
+            .task_with_id(&tasks[0].id)
+            .await?
+            .terminal_state());
+
+        coordinator
+            .update_task(
+                &tasks[0].id,
+                executor_id,
+                internal_api::TaskOutcome::Success,
+                None,
+                None,
+            )
+            .await?;
+        assert_eq!(load(), vec![(executor_id.to_string(), 1)]);
+
+        //  Finishing the task again leaves the executor's load alone
+        let mut task = tasks[0].clone();
+        task.outcome = internal_api::TaskOutcome::Failed;
+        shared_state
+            .update_task(task, Some(executor_id.to_string()))
+            .await?;
+        assert_eq!(load(), vec![(executor_id.to_string(), 1)]);
+        assert_eq!(
+            shared_state.task_with_id(&tasks[0].id).await?.outcome,
+            internal_api::TaskOutcome::Success
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_task_progress() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let executor_id = "test_executor_id";
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![test_mock_content_metadata("test1", "test1", &eg.name)])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let task_id = shared_state.tasks_for_executor(executor_id, None).await?[0]
+            .id
+            .clone();
+        let update = |percent: f64| indexify_coordinator::TaskProgressUpdate {
+            task_id: task_id.clone(),
+            progress: Some(indexify_coordinator::TaskProgress {
+                percent: Some(percent),
+                items_processed: 10,
+                updated_at: 0,
+            }),
+        };
+
+        coordinator
+            .update_task_progress(executor_id, vec![update(40.0)])
+            .await?;
+        let progress = shared_state.task_with_id(&task_id).await?.progress.unwrap();
+        assert_eq!(progress.percent, Some(40.0));
+        assert_eq!(progress.items_processed, 10);
+        assert!(progress.updated_at > 0);
+
+        //  Executors the task isn't assigned to can't report its progress
+        coordinator
+            .update_task_progress("stale_executor_id", vec![update(90.0)])
+            .await?;
+        let task = shared_state.task_with_id(&task_id).await?;
+        assert_eq!(task.progress.unwrap().percent, Some(40.0));
+
+        //  The progress is kept on the task after it finishes
+        coordinator
+            .update_task(
+                &task_id,
+                executor_id,
+                internal_api::TaskOutcome::Success,
+                None,
+                None,
+            )
+            .await?;
+        coordinator
+            .update_task_progress(executor_id, vec![update(90.0)])
+            .await?;
+        let task = shared_state.task_with_id(&task_id).await?;
+        assert_eq!(task.progress.unwrap().percent, Some(40.0));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_tasks() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let executor_id = "test_executor_id";
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![
+                test_mock_content_metadata("test1", "test1", &eg.name),
+                test_mock_content_metadata("test2", "test2", &eg.name),
+                test_mock_content_metadata("test3", "test3", &eg.name),
+            ])
+            .await?;
+        coordinator.run_scheduler().await?;
+        coordinator
+            .set_scheduler_settings(SchedulerSettings {
+                max_bundle_tasks: Some(2),
+                ..Default::default()
+            })
+            .await?;
+
+        //  The tasks are sent in a full bundle and on their own
+        let response = coordinator
+            .heartbeat(executor_id, &mut TaskBundler::default())
+            .await?;
+        assert_eq!(response.tasks.len(), 1);
+        assert_eq!(response.bundles.len(), 1);
+        assert_eq!(response.bundles[0].tasks.len(), 2);
+
+        let finished = |task_id: &str| indexify_coordinator::FinishedTask {
+            task_id: task_id.to_string(),
+            outcome: indexify_coordinator::TaskOutcome::Success as i32,
+            diagnostics: "".to_string(),
+            usage: None,
+        };
+        let mut finished_tasks: Vec<_> = response.bundles[0]
+            .tasks
+            .iter()
+            .map(|task| finished(&task.id))
+            .collect();
+        finished_tasks.push(finished("unknown_task_id"));
+        let rejected = coordinator
+            .update_tasks(executor_id, finished_tasks)
+            .await?;
+        assert_eq!(rejected, vec!["unknown_task_id".to_string()]);
+        for task in &response.bundles[0].tasks {
+            let task = shared_state.task_with_id(&task.id).await?;
+            assert_eq!(task.outcome, internal_api::TaskOutcome::Success);
+        }
+        let tasks = shared_state.tasks_for_executor(executor_id, None).await?;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, response.tasks[0].id);
+
+        //  Tasks of other executors are rejected
+        let rejected = coordinator
+            .update_tasks("stale_executor_id", vec![finished(&tasks[0].id)])
+            .await?;
+        assert_eq!(rejected, vec![tasks[0].id.clone()]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_executor_pressure() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        for executor_id in ["executor_1", "executor_2"] {
+            coordinator
+                .register_executor(
+                    "localhost:8950",
+                    executor_id,
+                    vec![mock_extractor()],
+                    Default::default(),
+                )
+                .await?;
+        }
+        coordinator
+            .set_executor_pressure(
+                "executor_1",
+                Some(indexify_coordinator::ExecutorPressure {
+                    level: indexify_coordinator::PressureLevel::Critical as i32,
+                    reason: "disk full".to_string(),
+                    since: 0,
+                }),
+            )
+            .await?;
+        let pressure = shared_state
+            .get_executor_by_id("executor_1")
+            .await?
+            .pressure
+            .unwrap();
+        assert_eq!(pressure.level, internal_api::PressureLevel::Critical);
+        assert_eq!(pressure.reason, "disk full");
+        assert!(pressure.since > 0);
+
+        //  Executors under critical pressure take no new tasks
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![
+                test_mock_content_metadata("test1", "test1", &eg.name),
+                test_mock_content_metadata("test2", "test2", &eg.name),
+            ])
+            .await?;
+        coordinator.run_scheduler().await?;
+        assert!(shared_state
+            .tasks_for_executor("executor_1", None)
+            .await?
+            .is_empty());
+        assert_eq!(
+            shared_state
+                .tasks_for_executor("executor_2", None)
+                .await?
+                .len(),
+            2
+        );
+
+        //  A normal level clears the pressure
+        coordinator
+            .set_executor_pressure(
+                "executor_1",
+                Some(indexify_coordinator::ExecutorPressure {
+                    level: indexify_coordinator::PressureLevel::Normal as i32,
+                    reason: "".to_string(),
+                    since: 0,
+                }),
+            )
+            .await?;
+        assert!(shared_state
+            .get_executor_by_id("executor_1")
+            .await?
+            .pressure
+            .is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fractional_gpu_tasks() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                "gpu_executor",
+                vec![mock_extractor()],
+                internal_api::ExecutorProfile {
+                    gpu_devices: vec![1000],
+                    ..Default::default()
+                },
+            )
+            .await?;
+        coordinator
+            .register_executor(
+                "localhost:8951",
+                "cpu_executor",
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+
+        //  Two tasks taking 0.4 of a GPU share the device, the third waits
+        //  for room on it
+        let mut eg =
+            create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        eg.extraction_policies[0].gpu_millis = Some(400);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![
+                test_mock_content_metadata("test1", "test1", &eg.name),
+                test_mock_content_metadata("test2", "test2", &eg.name),
+                test_mock_content_metadata("test3", "test3", &eg.name),
+            ])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state
+            .tasks_for_executor("gpu_executor", None)
+            .await?;
+        assert_eq!(tasks.len(), 2);
+        for task in &tasks {
+            assert_eq!(task.gpu_millis, Some(400));
+            assert_eq!(task.gpu_device, Some(0));
+        }
+        assert!(shared_state
+            .tasks_for_executor("cpu_executor", None)
+            .await?
+            .is_empty());
+        assert_eq!(shared_state.unassigned_task_ids().await.len(), 1);
+
+        let executors = coordinator.list_executors().await?;
+        let gpu_executor = executors
+            .iter()
+            .find(|executor| executor.id == "gpu_executor")
+            .unwrap();
+        assert_eq!(
+            gpu_executor.gpu_devices,
+            vec![indexify_coordinator::GpuDevice {
+                capacity_millis: 1000,
+                occupied_millis: 800,
+            }]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_slo_compliance() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                "test_executor_id",
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        let mut eg =
+            create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        eg.extraction_policies[0].slo = Some(internal_api::ExtractionPolicySlo {
+            latency_ms: Some(60_000),
+            latency_target_ppm: 990_000,
+            max_error_rate_ppm: Some(100_000),
+        });
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![
+                test_mock_content_metadata("test1", "test1", &eg.name),
+                test_mock_content_metadata("test2", "test2", &eg.name),
+            ])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state
+            .tasks_for_executor("test_executor_id", None)
+            .await?;
+        assert_eq!(tasks.len(), 2);
+        for (mut task, outcome) in tasks.into_iter().zip([
+            internal_api::TaskOutcome::Success,
+            internal_api::TaskOutcome::Failed,
+        ]) {
+            task.outcome = outcome;
+            shared_state
+                .update_task(task, Some("test_executor_id".to_string()))
+                .await?;
+        }
+
+        //  Half of the tasks failed where a tenth may, so the error budget
+        //  burns five times as fast as allowed
+        let compliance = coordinator.get_slo_compliance(DEFAULT_TEST_NAMESPACE, &eg.name)?;
+        assert_eq!(compliance.len(), 1);
+        assert_eq!(compliance[0].extraction_policy, "extraction_policy_1");
+        assert_eq!(compliance[0].tasks, 2);
+        assert_eq!(compliance[0].latency_compliance, Some(1.0));
+        assert_eq!(compliance[0].latency_burn_rate, Some(0.0));
+        assert_eq!(compliance[0].error_rate, 0.5);
+        assert_eq!(compliance[0].error_burn_rate, Some(5.0));
+
+        coordinator.record_slo_compliance().await?;
+        let recorded = shared_state
+            .state_machine
+            .data
+            .indexify_state
+            .slo_compliance
+            .lock()
+            .unwrap()
+            .clone();
+        assert_eq!(recorded[&eg.extraction_policies[0].id], compliance[0]);
+
+        assert!(coordinator
+            .get_slo_compliance(DEFAULT_TEST_NAMESPACE, "missing")
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_task_scheduling_attempts() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .set_scheduler_settings(SchedulerSettings {
+                max_tasks_per_executor: Some(1),
+                ..Default::default()
+            })
+            .await?;
+        for (addr, executor_id) in [
+            ("localhost:8950", "executor_1"),
+            ("localhost:8951", "executor_2"),
+        ] {
+            coordinator
+                .register_executor(
+                    addr,
+                    executor_id,
+                    vec![mock_extractor()],
+                    Default::default(),
+                )
+                .await?;
+        }
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![
+                test_mock_content_metadata("test1", "test1", &eg.name),
+                test_mock_content_metadata("test2", "test2", &eg.name),
+                test_mock_content_metadata("test3", "test3", &eg.name),
+            ])
+            .await?;
+        coordinator.run_scheduler().await?;
+
+        //  The executor that got a task was the one picked among the
+        //  executors of the extractor
+        let task = shared_state
+            .tasks_for_executor("executor_1", None)
+            .await?
+            .pop()
+            .unwrap();
+        let attempts = coordinator
+            .get_task_scheduling(DEFAULT_TEST_NAMESPACE, &task.id)
+            .await?;
+        let attempt = attempts.last().unwrap();
+        assert_eq!(attempt.executor_id.as_deref(), Some("executor_1"));
+        assert_eq!(attempt.candidates.len(), 2);
+        assert!(attempt
+            .candidates
+            .contains(&internal_api::PlacementCandidate {
+                executor_id: "executor_1".to_string(),
+                rejection: None,
+            }));
+
+        //  The task left over was passed over by both executors for being full
+        let task_id = shared_state
+            .unassigned_task_ids()
+            .await
+            .into_iter()
+            .next()
+            .unwrap();
+        let attempts = coordinator
+            .get_task_scheduling(DEFAULT_TEST_NAMESPACE, &task_id)
+            .await?;
+        let attempt = attempts.last().unwrap();
+        assert_eq!(attempt.executor_id, None);
+        assert_eq!(attempt.candidates.len(), 2);
+        assert!(attempt
+            .candidates
+            .iter()
+            .all(|candidate| candidate.rejection == Some(internal_api::PlacementRejection::Full)));
+
+        assert!(coordinator
+            .get_task_scheduling("other", &task_id)
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_graph_version_routing() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let mut eg =
+            create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        for (executor_id, min_version, max_version) in [
+            ("executor_v1", None, Some(1)),
+            ("executor_v2", Some(2), None),
+        ] {
+            coordinator
+                .register_executor(
+                    "localhost:8950",
+                    executor_id,
+                    vec![mock_extractor()],
+                    internal_api::ExecutorProfile {
+                        graph_versions: vec![internal_api::GraphVersionRange {
+                            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                            graph_name: eg.name.clone(),
+                            min_version,
+                            max_version,
+                        }],
+                        ..Default::default()
+                    },
+                )
+                .await?;
+        }
+
+        //  Tasks of the first version only go to the executor that runs it
+        coordinator
+            .create_content_metadata(vec![test_mock_content_metadata("test1", "test1", &eg.name)])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state.tasks_for_executor("executor_v1", None).await?;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].graph_version, Some(1));
+
+        //  Once the graph changes its new tasks go to the executor of the new
+        //  release
+        eg.max_active_content = Some(100);
+        coordinator
+            .save_extraction_graph(eg.clone(), Default::default(), true)
+            .await?;
+        coordinator
+            .create_content_metadata(vec![test_mock_content_metadata("test2", "test2", &eg.name)])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state.tasks_for_executor("executor_v2", None).await?;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].graph_version, Some(2));
+        assert_eq!(
+            shared_state
+                .tasks_for_executor("executor_v1", None)
+                .await?
+                .len(),
+            1
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_queue_content_at_active_content_cap() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                "executor_id",
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        let mut eg =
+            create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        eg.max_active_content = Some(1);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![
+                test_mock_content_metadata("test1", "test1", &eg.name),
+                test_mock_content_metadata("test2", "test2", &eg.name),
+            ])
+            .await?;
+        coordinator.run_scheduler().await?;
+
+        //  The graph extracts one content at a time, the other one is queued
+        //  with its state change left unprocessed
+        let tasks = shared_state.tasks_for_executor("executor_id", None).await?;
+        assert_eq!(tasks.len(), 1);
+        let active = tasks[0].content_metadata.id.id.clone();
+        let queued = if active == "test1" { "test2" } else { "test1" };
+        assert_eq!(
+            shared_state.state_machine.active_root_content(&eg.id),
+            HashSet::from([active.clone()])
+        );
+        assert!(coordinator.scheduler.take_queued_content());
+        assert!(!shared_state
+            .unprocessed_state_change_events()
+            .await?
+            .is_empty());
+
+        //  Content stays queued while the graph is at its cap
+        coordinator.run_scheduler().await?;
+        assert!(coordinator.scheduler.take_queued_content());
+        assert_eq!(
+            shared_state
+                .tasks_for_executor("executor_id", None)
+                .await?
+                .len(),
+            1
+        );
+
+        //  The queued content is admitted once the active content is done
+        coordinator
+            .update_task(
+                &tasks[0].id,
+                "executor_id",
+                TaskOutcome::Success,
+                None,
+                None,
+            )
+            .await?;
+        coordinator.run_scheduler().await?;
+        coordinator.run_scheduler().await?;
+        assert_eq!(
+            shared_state.state_machine.active_root_content(&eg.id),
+            HashSet::from([queued.to_string()])
+        );
+        let tasks: Vec<_> = shared_state
+            .tasks_for_executor("executor_id", None)
+            .await?
+            .into_iter()
+            .filter(|task| !task.terminal_state())
+            .collect();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].content_metadata.id.id, queued);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deregister_executor() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        for executor_id in ["executor_1", "executor_2"] {
+            coordinator
+                .register_executor(
+                    "localhost:8950",
+                    executor_id,
+                    vec![mock_extractor()],
+                    Default::default(),
+                )
+                .await?;
+        }
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![
+                test_mock_content_metadata("test1", "test1", &eg.name),
+                test_mock_content_metadata("test2", "test2", &eg.name),
+            ])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let task_id = shared_state.tasks_for_executor("executor_1", None).await?[0]
+            .id
+            .clone();
+
+        //  A draining executor keeps its tasks and is removed after the last one
+        let response = coordinator.deregister_executor("executor_1", true).await?;
+        assert!(!response.removed);
+        assert_eq!(response.running_tasks, 1);
+        assert!(
+            shared_state
+                .get_executor_by_id("executor_1")
+                .await?
+                .draining
+        );
+        coordinator
+            .create_content_metadata(vec![test_mock_content_metadata("test3", "test3", &eg.name)])
+            .await?;
+        coordinator.run_scheduler().await?;
+        assert_eq!(
+            shared_state
+                .tasks_for_executor("executor_1", None)
+                .await?
+                .len(),
+            1
+        );
+        coordinator
+            .update_task(
+                &task_id,
+                "executor_1",
+                internal_api::TaskOutcome::Success,
+                None,
+                None,
+            )
+            .await?;
+        assert!(shared_state.get_executor_by_id("executor_1").await.is_err());
+
+        //  Without draining the tasks are reassigned without counting as lost
+        let tasks = shared_state.tasks_for_executor("executor_2", None).await?;
+        let response = coordinator.deregister_executor("executor_2", false).await?;
+        assert!(response.removed);
+        assert_eq!(response.reassigned_tasks, tasks.len() as u64);
+        assert!(shared_state.get_executor_by_id("executor_2").await.is_err());
+        for task in tasks {
+            let task = shared_state.task_with_id(&task.id).await?;
+            assert!(task.lost_by_executors.is_empty());
+        }
+        assert!(coordinator
+            .deregister_executor("executor_2", false)
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_quarantine_and_requeue_poison_task() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![test_mock_content_metadata("test", "test", &eg.name)])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let task_ids = shared_state.unassigned_task_ids().await;
+        assert_eq!(task_ids.len(), 1);
+        let task_id = task_ids.into_iter().next().unwrap();
+
+        //  Every executor the task is allocated to goes away while running it
+        for attempt in 0..MAX_LOST_TASK_ATTEMPTS {
+            let executor_id = format!("test_executor_id_{}", attempt);
+            coordinator
+                .register_executor(
+                    "localhost:8950",
+                    &executor_id,
+                    vec![mock_extractor()],
+                    Default::default(),
+                )
+                .await?;
+            coordinator.run_scheduler().await?;
+            let tasks = shared_state.tasks_for_executor(&executor_id, None).await?;
+            assert_eq!(tasks.len(), 1);
+            assert_eq!(tasks[0].id, task_id);
+
+            coordinator.remove_executor(&executor_id).await?;
+            coordinator.run_scheduler().await?;
+            let task = shared_state.task_with_id(&task_id).await?;
+            assert_eq!(task.lost_by_executors.len(), attempt + 1);
+        }
+
+        let task = shared_state.task_with_id(&task_id).await?;
+        assert_eq!(task.outcome, internal_api::TaskOutcome::Quarantined);
+        assert!(task.diagnostics.unwrap().contains("test_executor_id_2"));
+        assert!(shared_state.unassigned_task_ids().await.is_empty());
+
+        //  Only quarantined tasks of the namespace can be requeued
+        assert!(coordinator
+            .requeue_task("other_namespace", &task_id, "")
+            .await
+            .is_err());
+        coordinator
+            .requeue_task(DEFAULT_TEST_NAMESPACE, &task_id, "")
+            .await?;
+        coordinator.run_scheduler().await?;
+        let task = shared_state.task_with_id(&task_id).await?;
+        assert_eq!(task.outcome, internal_api::TaskOutcome::Unknown);
+        assert!(task.lost_by_executors.is_empty());
+        assert!(task.diagnostics.is_none());
+        assert_eq!(shared_state.unassigned_task_ids().await.len(), 1);
+        assert!(coordinator
+            .requeue_task(DEFAULT_TEST_NAMESPACE, &task_id, "")
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_expire_tasks() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let executor_id = "test_executor_id";
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        let mut expired = test_mock_content_metadata("expired", "expired", &eg.name);
+        expired.expires_at = utils::timestamp_secs() - 1;
+        let mut fresh = test_mock_content_metadata("fresh", "fresh", &eg.name);
+        fresh.expires_at = utils::timestamp_secs() + 3600;
+        coordinator
+            .create_content_metadata(vec![
+                expired,
+                fresh,
+                test_mock_content_metadata("forever", "forever", &eg.name),
+            ])
+            .await?;
+        coordinator.run_scheduler().await?;
+        assert_eq!(
+            shared_state
+                .tasks_for_executor(executor_id, None)
+                .await?
+                .len(),
+            3
+        );
+
+        assert_eq!(coordinator.expire_tasks().await?, 1);
+        let tasks = shared_state
+            .list_tasks(DEFAULT_TEST_NAMESPACE, None)
+            .await?;
+        let task = tasks
+            .iter()
+            .find(|task| task.content_metadata.id.id == "expired")
+            .unwrap();
+        assert_eq!(task.outcome, internal_api::TaskOutcome::Expired);
+        assert!(task.diagnostics.as_ref().unwrap().contains("expired"));
+        assert_eq!(
+            shared_state
+                .tasks_for_executor(executor_id, None)
+                .await?
+                .len(),
+            2
+        );
+
+        //  Expired tasks are not expired again
+        assert_eq!(coordinator.expire_tasks().await?, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_cancel_tasks() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let executor_id = "test_executor_id";
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        let eg1 = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg1.clone()).await?;
+        let eg2 = create_test_extraction_graph("extraction_graph_2", vec!["extraction_policy_2"]);
+        coordinator.create_extraction_graph(eg2.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![
+                test_mock_content_metadata("content_1", "content_1", &eg1.name),
+                test_mock_content_metadata("content_2", "content_2", &eg2.name),
+            ])
+            .await?;
+        coordinator.run_scheduler().await?;
+
+        let filter = CancelTasksFilter {
+            extraction_graph: Some(eg1.name.clone()),
+            ..Default::default()
+        };
+        let job_id = coordinator
+            .cancel_tasks(DEFAULT_TEST_NAMESPACE, filter)
+            .await?;
+        let job = loop {
+            let job = coordinator.get_cancel_tasks_job(&job_id)?.unwrap();
+            if job.state != internal_api::CancelTasksJobState::Running {
+                break job;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+        assert_eq!(job.state, internal_api::CancelTasksJobState::Completed);
+        assert_eq!(job.matched_tasks, 1);
+        assert_eq!(job.cancelled_tasks, 1);
+
+        //  Cancelled tasks aren't failures and leave their executor
+        let tasks = shared_state
+            .list_tasks(DEFAULT_TEST_NAMESPACE, None)
+            .await?;
+        for task in &tasks {
+            let expected = if task.extraction_graph_name == eg1.name {
+                TaskOutcome::Cancelled
+            } else {
+                TaskOutcome::Unknown
+            };
+            assert_eq!(task.outcome, expected);
+        }
+        let assigned = shared_state.tasks_for_executor(executor_id, None).await?;
+        assert_eq!(assigned.len(), 1);
+        assert_eq!(assigned[0].extraction_graph_name, eg2.name);
+
+        //  The job is recorded in the state, and forgotten after its retention
+        assert_eq!(shared_state.list_cancel_tasks_jobs()?, vec![job.clone()]);
+        assert_eq!(
+            coordinator.evict_cancel_tasks_jobs(job.updated_at).await?,
+            0
+        );
+        assert_eq!(
+            coordinator
+                .evict_cancel_tasks_jobs(job.updated_at + JOB_RETENTION_SECS)
+                .await?,
+            1
+        );
+        assert!(coordinator.get_cancel_tasks_job(&job_id)?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_cancel_tasks_in_pages() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        //  More pages of tasks than are cancelled at the same time, and a
+        //  last page that isn't full
+        let content_count = 401;
+        coordinator
+            .create_content_metadata(
+                (0..content_count)
+                    .map(|i| {
+                        let id = format!("content_{}", i);
+                        test_mock_content_metadata(&id, &id, &eg.name)
+                    })
+                    .collect(),
+            )
+            .await?;
+        coordinator.run_scheduler().await?;
+
+        let job_id = coordinator
+            .cancel_tasks(DEFAULT_TEST_NAMESPACE, CancelTasksFilter::default())
+            .await?;
+        let job = loop {
+            let job = coordinator.get_cancel_tasks_job(&job_id)?.unwrap();
+            assert!(job.cancelled_tasks <= job.matched_tasks);
+            if job.state != internal_api::CancelTasksJobState::Running {
+                break job;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+        assert_eq!(job.state, internal_api::CancelTasksJobState::Completed);
+        assert_eq!(job.matched_tasks, content_count);
+        assert_eq!(job.cancelled_tasks, content_count);
+
+        let tasks = shared_state
+            .list_tasks(DEFAULT_TEST_NAMESPACE, None)
+            .await?;
+        assert_eq!(tasks.len() as u64, content_count);
+        assert!(tasks
+            .iter()
+            .all(|task| task.outcome == TaskOutcome::Cancelled));
+        assert!(shared_state.unassigned_task_ids().await.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_abort_stale_cancel_tasks_jobs() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        let now = crate::utils::timestamp_secs();
+        let job = |id: &str, updated_at| internal_api::CancelTasksJob {
+            id: id.to_string(),
+            namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+            state: internal_api::CancelTasksJobState::Running,
+            matched_tasks: 10,
+            cancelled_tasks: 2,
+            error: None,
+            started_at: updated_at,
+            updated_at,
+        };
+        shared_state
+            .set_cancel_tasks_job(job("stale", now - STALE_JOB_SECS))
+            .await?;
+        shared_state
+            .set_cancel_tasks_job(job("live", now - 1))
+            .await?;
+
+        //  Only the job nobody recorded progress of for a while is aborted
+        assert_eq!(coordinator.abort_stale_cancel_tasks_jobs(now).await?, 1);
+        let stale = coordinator.get_cancel_tasks_job("stale")?.unwrap();
+        assert_eq!(stale.state, internal_api::CancelTasksJobState::Aborted);
+        assert!(stale.error.is_some());
+        assert_eq!(stale.cancelled_tasks, 2);
+        assert_eq!(stale.updated_at, now);
+        let live = coordinator.get_cancel_tasks_job("live")?.unwrap();
+        assert_eq!(live.state, internal_api::CancelTasksJobState::Running);
+
+        //  Aborted jobs aren't aborted again
+        assert_eq!(coordinator.abort_stale_cancel_tasks_jobs(now).await?, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_rate_limited_allocation() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let mut eg =
+            create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        eg.extraction_policies[0].max_tasks_per_second = Some(2);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(
+                (0..3)
+                    .map(|i| {
+                        let id = format!("content_{}", i);
+                        test_mock_content_metadata(&id, &id, &eg.name)
+                    })
+                    .collect(),
+            )
+            .await?;
+        coordinator.run_scheduler().await?;
+        assert_eq!(shared_state.unassigned_task_ids().await.len(), 3);
+
+        //  The tasks admitted while no executor could run them didn't take
+        //  tokens, the executor gets as many tasks as the limit allows
+        let executor_id = "test_executor_id";
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        coordinator.run_scheduler().await?;
+        assert_eq!(
+            shared_state
+                .tasks_for_executor(executor_id, None)
+                .await?
+                .len(),
+            2
+        );
+        assert_eq!(shared_state.unassigned_task_ids().await.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shed_burst_tasks() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let overcommitted = SchedulerSettings {
+            max_tasks_per_executor: Some(1),
+            overcommit_factors: HashMap::from([(mock_extractor().name, 2.0)]),
+            ..Default::default()
+        };
+        coordinator
+            .set_scheduler_settings(overcommitted.clone())
+            .await?;
+        let executor_id = "test_executor_id";
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![
+                test_mock_content_metadata("test1", "test1", &eg.name),
+                test_mock_content_metadata("test2", "test2", &eg.name),
+            ])
+            .await?;
+        coordinator.run_scheduler().await?;
+
+        //  The task above the nominal limit is tracked as a burst task
+        let tasks = shared_state.tasks_for_executor(executor_id, None).await?;
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks.iter().filter(|task| task.burst).count(), 1);
+        let executors = coordinator.list_executors().await?;
+        assert_eq!(executors[0].running_tasks, 2);
+        assert_eq!(executors[0].burst_tasks, 1);
+
+        //  Nothing is shed while the executor is within its burst limit
+        assert_eq!(coordinator.shed_burst_tasks().await?, 0);
+
+        //  Without the overcommit factor the burst task is taken back, the
+        //  task within the nominal limit keeps running
+        coordinator
+            .set_scheduler_settings(SchedulerSettings {
+                overcommit_factors: HashMap::new(),
+                ..overcommitted
+            })
+            .await?;
+        assert_eq!(coordinator.shed_burst_tasks().await?, 1);
+        let tasks = shared_state.tasks_for_executor(executor_id, None).await?;
+        assert_eq!(tasks.len(), 1);
+        assert!(!tasks[0].burst);
+        assert_eq!(shared_state.unassigned_task_ids().await.len(), 1);
+        assert_eq!(coordinator.shed_burst_tasks().await?, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cost_aware_placement() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .set_scheduler_settings(SchedulerSettings {
+                max_tasks_per_executor: Some(2),
+                ..Default::default()
+            })
+            .await?;
+        for (addr, executor_id, cost_per_hour) in [
+            ("localhost:8950", "on_demand", 2.0),
+            ("localhost:8951", "spot", 0.3),
+        ] {
+            coordinator
+                .register_executor(
+                    addr,
+                    executor_id,
+                    vec![mock_extractor()],
+                    internal_api::ExecutorProfile {
+                        cost_per_hour: Some(cost_per_hour),
+                        ..Default::default()
+                    },
+                )
+                .await?;
+        }
+        let mut tolerant = create_test_extraction_graph("tolerant", vec!["extraction_policy_1"]);
+        tolerant.latency_tolerant = true;
+        coordinator
+            .create_extraction_graph(tolerant.clone())
+            .await?;
+        let urgent = create_test_extraction_graph("urgent", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(urgent.clone()).await?;
+
+        //  Tasks of the latency tolerant graph go to the cheapest executor
+        coordinator
+            .create_content_metadata(vec![
+                test_mock_content_metadata("tolerant_1", "tolerant_1", &tolerant.name),
+                test_mock_content_metadata("tolerant_2", "tolerant_2", &tolerant.name),
+            ])
+            .await?;
+        coordinator.run_scheduler().await?;
+        assert_eq!(
+            shared_state.tasks_for_executor("spot", None).await?.len(),
+            2
+        );
+
+        //  The expensive executor is reserved for the task that can't wait,
+        //  even though the latency tolerant content has a higher priority
+        let mut contents = vec![test_mock_content_metadata(
+            "urgent_1",
+            "urgent_1",
+            &urgent.name,
+        )];
+        for id in ["tolerant_3", "tolerant_4"] {
+            let mut content = test_mock_content_metadata(id, id, &tolerant.name);
+            content.priority = 10;
+            contents.push(content);
+        }
+        coordinator.create_content_metadata(contents).await?;
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state.tasks_for_executor("on_demand", None).await?;
+        assert_eq!(tasks.len(), 2);
+        assert!(tasks
+            .iter()
+            .any(|task| task.content_metadata.id.id == "urgent_1"));
+        assert_eq!(shared_state.unassigned_task_ids().await.len(), 1);
+        Ok(())
+    }
+
+    /// Finishes the tasks assigned to the executors, and returns the
+    /// executors that had tasks
+    async fn finish_assigned_tasks(
+        shared_state: &App,
+        executor_ids: &[&str],
+    ) -> Result<HashSet<String>, anyhow::Error> {
+        let mut finished_by = HashSet::new();
+        for executor_id in executor_ids {
+            for mut task in shared_state.tasks_for_executor(executor_id, None).await? {
+                task.outcome = TaskOutcome::Success;
+                shared_state
+                    .update_task(task, Some(executor_id.to_string()))
+                    .await?;
+                finished_by.insert(executor_id.to_string());
+            }
+        }
+        Ok(finished_by)
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_min_warm_executors() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let executor_ids = ["executor_1", "executor_2"];
+        for (port, executor_id) in [8950, 8951].into_iter().zip(executor_ids) {
+            coordinator
+                .register_executor(
+                    &format!("localhost:{}", port),
+                    executor_id,
+                    vec![mock_extractor()],
+                    Default::default(),
+                )
+                .await?;
+        }
+        let mut eg =
+            create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        eg.extraction_policies[0].min_warm_executors = Some(2);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        let content = |id: &str| test_mock_content_metadata(id, id, &eg.name);
+        let unfinished_task_ids = || async {
+            Ok::<_, anyhow::Error>(
+                shared_state
+                    .list_tasks(DEFAULT_TEST_NAMESPACE, None)
+                    .await?
+                    .into_iter()
+                    .filter(|task| !task.terminal_state())
+                    .map(|task| task.id)
+                    .collect::<HashSet<_>>(),
+            )
+        };
+
+        coordinator
+            .create_content_metadata(vec![content("content_1")])
+            .await?;
+        coordinator.run_scheduler().await?;
+        assert_eq!(
+            finish_assigned_tasks(&shared_state, &executor_ids)
+                .await?
+                .len(),
+            1
+        );
+
+        //  With fewer warm executors than the policy wants, its tasks aren't
+        //  kept to the warm one so that they warm up others
+        coordinator
+            .create_content_metadata(vec![content("content_2"), content("content_3")])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let task_ids = unfinished_task_ids().await?;
+        assert_eq!(task_ids.len(), 2);
+        assert!(shared_state.warm_executors_for_tasks(&task_ids)?.is_empty());
+        assert_eq!(
+            finish_assigned_tasks(&shared_state, &executor_ids)
+                .await?
+                .len(),
+            2
+        );
+
+        //  Once enough executors are warm, the tasks are kept to them
+        coordinator
+            .create_content_metadata(vec![content("content_4")])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let task_ids = unfinished_task_ids().await?;
+        assert_eq!(task_ids.len(), 1);
+        let warm_executors = shared_state.warm_executors_for_tasks(&task_ids)?;
+        assert_eq!(
+            warm_executors.get(task_ids.iter().next().unwrap()),
+            Some(&HashSet::from(executor_ids.map(str::to_string)))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_simulate_scheduling() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .set_scheduler_settings(SchedulerSettings {
+                max_tasks_per_executor: Some(2),
+                ..Default::default()
+            })
+            .await?;
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                "test_executor_id",
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        let contents = (0..5)
+            .map(|i| {
+                let id = format!("content_{}", i);
+                test_mock_content_metadata(&id, &id, &eg.name)
+            })
+            .collect();
+        coordinator.create_content_metadata(contents).await?;
+        coordinator.run_scheduler().await?;
+        assert_eq!(shared_state.unassigned_task_ids().await.len(), 3);
+
+        //  Without a change the executor is full, and the queue isn't
+        //  predicted as no task finished yet
+        let extractor = mock_extractor().name;
+        let queues = coordinator
+            .simulate_scheduling(indexify_coordinator::SimulateSchedulingRequest {
+                horizon_secs: 600,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(queues.len(), 1);
+        assert_eq!(queues[0].extractor, extractor);
+        assert_eq!(queues[0].executors, 1);
+        assert_eq!(queues[0].unallocated, 3);
+        assert_eq!(queues[0].queued, 3);
+        assert_eq!(queues[0].predicted_queued, None);
+
+        //  The planner places the queued tasks on an added executor of twice
+        //  the size, the cluster is left as it is
+        let queues = coordinator
+            .simulate_scheduling(indexify_coordinator::SimulateSchedulingRequest {
+                executors: vec![indexify_coordinator::SimulatedExecutors {
+                    count: 1,
+                    extractors: vec![extractor.clone()],
+                    capacity_weight: Some(2),
+                }],
+                horizon_secs: 600,
+                ..Default::default()
+            })
+            .await?;
+        assert_eq!(queues[0].executors, 2);
+        assert_eq!(queues[0].queued, 0);
+        assert_eq!(shared_state.unassigned_task_ids().await.len(), 3);
+
+        //  Invocations of a graph that doesn't exist can't be simulated
+        let result = coordinator
+            .simulate_scheduling(indexify_coordinator::SimulateSchedulingRequest {
+                invocations: vec![indexify_coordinator::SimulatedInvocations {
+                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                    extraction_graph: "missing".to_string(),
+                    per_hour: 10.0,
+                }],
+                ..Default::default()
+            })
+            .await;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_reap_orphaned_assignments() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        let content = test_mock_content_metadata("test", "test", &eg.name);
+        coordinator.create_content_metadata(vec![content]).await?;
+        coordinator.run_scheduler().await?;
+
+        let task_id = shared_state
+            .unassigned_task_ids()
+            .await
+            .into_iter()
+            .next()
+            .unwrap();
+
+        //  The task drops out of the unassigned tasks without being assigned,
+        //  and is only requeued once it was found for the grace period
+        let unassigned_tasks = &shared_state
+            .state_machine
+            .data
+            .indexify_state
+            .unassigned_tasks;
+        unassigned_tasks.remove(&task_id);
+        let now = utils::timestamp_secs();
+        assert_eq!(coordinator.reap_orphaned_assignments(now).await?, (0, 0));
+        assert!(!unassigned_tasks.contains(&task_id));
+        assert_eq!(
+            coordinator
+                .reap_orphaned_assignments(now + ORPHANED_ASSIGNMENT_GRACE_SECS)
+                .await?,
+            (0, 1)
+        );
+        assert!(unassigned_tasks.contains(&task_id));
+        assert_eq!(
+            coordinator
+                .reap_orphaned_assignments(now + ORPHANED_ASSIGNMENT_GRACE_SECS)
+                .await?,
+            (0, 0)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_prune_task_diagnostics() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                "test_executor_id",
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        let mut expired = test_mock_content_metadata("expired", "expired", &eg.name);
+        expired.expires_at = utils::timestamp_secs() - 1;
+        coordinator.create_content_metadata(vec![expired]).await?;
+        coordinator.run_scheduler().await?;
+        assert_eq!(coordinator.expire_tasks().await?, 1);
+
+        //  Diagnostics are kept for the retention after the task finished
+        let config = DiagnosticsConfig::default();
+        let now = utils::timestamp_secs();
+        assert_eq!(coordinator.prune_task_diagnostics(now, &config).await?, 0);
+
+        //  The retention of the namespace overrides the default
+        let config = DiagnosticsConfig {
+            namespace_retention_secs: HashMap::from([(DEFAULT_TEST_NAMESPACE.to_string(), 0)]),
+            ..Default::default()
+        };
+        assert_eq!(
+            coordinator.prune_task_diagnostics(now + 1, &config).await?,
+            1
+        );
+        let tasks = shared_state
+            .list_tasks(DEFAULT_TEST_NAMESPACE, None)
+            .await?;
+        assert_eq!(tasks[0].outcome, internal_api::TaskOutcome::Expired);
+        assert!(tasks[0].diagnostics.is_none());
+        assert_eq!(
+            coordinator.prune_task_diagnostics(now + 1, &config).await?,
+            0
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_extraction_graph_history_and_rollback() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+
+        let updated =
+            create_test_extraction_graph("extraction_graph_1", vec!["policy_1", "policy_2"]);
+        let change = ExtractionGraphChange {
+            author: "alice".to_string(),
+            ..Default::default()
+        };
+        coordinator
+            .save_extraction_graph(updated.clone(), change.clone(), true)
+            .await?;
+        //  Saving the same definition again doesn't add a version
+        coordinator
+            .save_extraction_graph(updated.clone(), change, true)
+            .await?;
+        let versions =
+            coordinator.list_extraction_graph_versions(DEFAULT_TEST_NAMESPACE, &eg.name)?;
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].extraction_graph, eg);
+        assert_eq!(versions[0].summary, "created");
+        assert_eq!(versions[1].extraction_graph, updated);
+        assert_eq!(versions[1].author, "alice");
+        assert_eq!(versions[1].summary, "added policies policy_2");
+        let extraction_graph_id =
+            internal_api::ExtractionGraph::create_id(&eg.name, DEFAULT_TEST_NAMESPACE);
+        assert_eq!(
+            shared_state.get_extraction_graph_version(&extraction_graph_id)?,
+            Some(2)
+        );
+
+        let (graph, _) = coordinator
+            .rollback_extraction_graph(DEFAULT_TEST_NAMESPACE, &eg.name, 1, "bob")
+            .await?;
+        assert_eq!(graph, eg);
+        assert_eq!(
+            coordinator.get_extraction_graph(DEFAULT_TEST_NAMESPACE, &eg.name)?,
+            Some(eg.clone())
+        );
+        let versions =
+            coordinator.list_extraction_graph_versions(DEFAULT_TEST_NAMESPACE, &eg.name)?;
+        assert_eq!(versions.len(), 3);
+        assert_eq!(versions[2].version, 3);
+        assert_eq!(versions[2].author, "bob");
+        assert_eq!(versions[2].summary, "rolled back to version 1");
+        assert_eq!(
+            shared_state.get_extraction_graph_version(&extraction_graph_id)?,
+            Some(3)
+        );
+
+        //  The policy the rollback removed from the graph is deleted
+        assert!(shared_state
+            .get_extraction_policy(&updated.extraction_policies[1].id)
+            .is_err());
+        let policies = shared_state
+            .list_extraction_policy(DEFAULT_TEST_NAMESPACE)
+            .await?;
+        assert_eq!(policies.len(), 1);
+        assert_eq!(policies[0].name, "policy_1");
+
+        assert!(coordinator
+            .rollback_extraction_graph(DEFAULT_TEST_NAMESPACE, &eg.name, 9, "bob")
+            .await
+            .is_err());
+        let missing = create_test_extraction_graph("extraction_graph_2", vec!["policy_1"]);
+        assert!(coordinator
+            .save_extraction_graph(missing, ExtractionGraphChange::default(), true)
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_export_and_import_namespace() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        let updated =
+            create_test_extraction_graph("extraction_graph_1", vec!["policy_1", "policy_2"]);
+        coordinator
+            .save_extraction_graph(updated, ExtractionGraphChange::default(), true)
+            .await?;
+        coordinator
+            .create_content_metadata(vec![test_mock_content_metadata("test1", "test1", &eg.name)])
+            .await?;
+
+        let archive = coordinator.export_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        assert_eq!(archive.extraction_graphs[0].len(), 2);
+        assert_eq!(archive.content.len(), 1);
+
+        let (report, graphs) = coordinator
+            .import_namespace("imported", archive.clone())
+            .await?;
+        assert_eq!(report.extraction_graphs, 1);
+        assert_eq!(report.extraction_graph_versions, 2);
+        assert_eq!(report.content, 1);
+        assert_eq!(graphs.len(), 1);
+        assert_eq!(graphs[0].0.namespace, "imported");
+        let versions = coordinator.list_extraction_graph_versions("imported", &eg.name)?;
+        assert_eq!(versions.len(), 2);
+        let graph = &versions[1].extraction_graph;
+        assert_eq!(graph.namespace, "imported");
+        assert_eq!(graph.extraction_policies.len(), 2);
+        assert!(graph
+            .extraction_policies
+            .iter()
+            .all(|policy| policy.namespace == "imported"));
+
+        //  The content is extracted again by the imported graphs
+        let content = shared_state.list_content("imported", "", |_| true).await?;
+        assert_eq!(content.len(), 1);
+        assert_eq!(content[0].id.id, report.content_ids["test1"]);
+        assert_ne!(content[0].id.id, "test1");
+        assert!(content[0].extraction_policy_ids.is_empty());
+        assert!(shared_state
+            .unprocessed_state_change_events()
+            .await?
+            .iter()
+            .any(|change| change.object_id == content[0].id.id));
+
+        //  An import that is run again skips what it imported before
+        let (resumed, _) = coordinator
+            .import_namespace("imported", archive.clone())
+            .await?;
+        assert_eq!(resumed.extraction_graph_versions, 0);
+        assert_eq!(resumed.content_ids, report.content_ids);
+        let versions = coordinator.list_extraction_graph_versions("imported", &eg.name)?;
+        assert_eq!(versions.len(), 2);
+        let content = shared_state.list_content("imported", "", |_| true).await?;
+        assert_eq!(content.len(), 1);
+
+        //  Graphs of the namespace with another history aren't overwritten
+        let mut changed = versions[1].extraction_graph.clone();
+        changed.extraction_policies.pop();
+        coordinator
+            .save_extraction_graph(changed, ExtractionGraphChange::default(), true)
+            .await?;
+        assert!(coordinator
+            .import_namespace("imported", archive)
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_scheduler_settings() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        assert_eq!(
+            coordinator.get_scheduler_settings()?,
+            SchedulerSettings::default()
+        );
+
+        let settings = SchedulerSettings {
+            placement_strategy: internal_api::PlacementStrategy::LeastLoaded,
+            max_tasks_per_executor: Some(4),
+            namespace_weights: HashMap::from([("interactive".to_string(), 3)]),
+            max_pending_gc_tasks: Some(100),
+            capacity_reservations: vec![internal_api::CapacityReservation {
+                namespace: "batch".to_string(),
+                extraction_graph: None,
+                slots: 2,
+                start: 100,
+                end: 200,
+            }],
+            overcommit_factors: HashMap::from([("extractor".to_string(), 1.5)]),
+            max_input_download_secs: Some(600),
+            max_bundle_tasks: Some(16),
+            max_bundle_bytes: Some(1 << 20),
+            max_bundle_latency_ms: Some(50),
+        };
+        coordinator.set_scheduler_settings(settings.clone()).await?;
+        assert_eq!(coordinator.get_scheduler_settings()?, settings);
+
+        //  Invalid settings are refused and leave the settings as they were
+        assert!(coordinator
+            .set_scheduler_settings(SchedulerSettings {
+                max_tasks_per_executor: Some(0),
+                ..Default::default()
+            })
+            .await
+            .is_err());
+        assert!(coordinator
+            .set_scheduler_settings(SchedulerSettings {
+                namespace_weights: HashMap::from([("batch".to_string(), 0)]),
+                ..Default::default()
+            })
+            .await
+            .is_err());
+        assert!(coordinator
+            .set_scheduler_settings(SchedulerSettings {
+                capacity_reservations: settings.capacity_reservations.clone(),
+                ..Default::default()
+            })
+            .await
+            .is_err());
+        assert!(coordinator
+            .set_scheduler_settings(SchedulerSettings {
+                max_tasks_per_executor: Some(4),
+                overcommit_factors: HashMap::from([("extractor".to_string(), 0.5)]),
+                ..Default::default()
+            })
+            .await
+            .is_err());
+        assert!(coordinator
+            .set_scheduler_settings(SchedulerSettings {
+                max_bundle_latency_ms: Some(50),
+                ..Default::default()
+            })
+            .await
+            .is_err());
+        assert_eq!(coordinator.get_scheduler_settings()?, settings);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_maintenance_mode() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let executor_id = "test_executor_id";
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator.run_scheduler().await?;
+
+        let maintenance = coordinator.set_maintenance_mode(true, "backup").await?;
+        assert_eq!(coordinator.get_maintenance_mode()?, maintenance);
+        assert_eq!(maintenance.reason, "backup");
+
+        //  Content is accepted but no tasks are created for it
+        let content_metadata = test_mock_content_metadata("test", "test", &eg.name);
+        coordinator
+            .create_content_metadata(vec![content_metadata])
+            .await?;
+        coordinator.run_scheduler().await?;
+        assert_eq!(
+            shared_state.unprocessed_state_change_events().await?.len(),
+            1
+        );
+        assert_eq!(shared_state.state_machine.state_change_backlog()?.0, 1);
+        let processed = shared_state.state_machine.state_changes_processed();
+        assert!(shared_state
+            .tasks_for_executor(executor_id, None)
+            .await?
+            .is_empty());
+
+        //  The scheduler catches up once maintenance ends
+        assert!(!coordinator.set_maintenance_mode(false, "").await?.enabled);
+        coordinator.admit_queued_content().await?;
+        assert_eq!(
+            shared_state.unprocessed_state_change_events().await?.len(),
+            0
+        );
+        assert_eq!(shared_state.state_machine.state_change_backlog()?, (0, 0));
+        assert!(shared_state.state_machine.state_changes_processed() > processed);
+        assert_eq!(
+            shared_state
+                .tasks_for_executor(executor_id, None)
+                .await?
+                .len(),
+            1
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_starved_tasks() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![test_mock_content_metadata(
+                "test_1", "test_1", &eg.name,
+            )])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let starved = coordinator
+            .list_starved_tasks(Some(DEFAULT_TEST_NAMESPACE), Some(Duration::ZERO))
+            .await?;
+        assert_eq!(starved.len(), 1);
+        assert_eq!(starved[0].reason, UnschedulableReason::NoExecutor);
+
+        //  The executor takes the first task and is full
+        coordinator
+            .set_scheduler_settings(SchedulerSettings {
+                max_tasks_per_executor: Some(1),
+                ..Default::default()
+            })
+            .await?;
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                "test_executor_id",
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        coordinator.run_scheduler().await?;
+        coordinator
+            .create_content_metadata(vec![test_mock_content_metadata(
+                "test_2", "test_2", &eg.name,
+            )])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let starved = coordinator
+            .list_starved_tasks(Some(DEFAULT_TEST_NAMESPACE), Some(Duration::ZERO))
+            .await?;
+        assert_eq!(starved.len(), 1);
+        assert_eq!(starved[0].reason, UnschedulableReason::NoCapacity);
+        assert!(coordinator
+            .list_starved_tasks(Some("other"), Some(Duration::ZERO))
+            .await?
+            .is_empty());
+
+        coordinator.set_maintenance_mode(true, "backup").await?;
+        let starved = coordinator
+            .list_starved_tasks(Some(DEFAULT_TEST_NAMESPACE), Some(Duration::ZERO))
+            .await?;
+        assert_eq!(starved[0].reason, UnschedulableReason::Maintenance);
+
+        //  The task is younger than the default threshold
+        assert!(coordinator.list_starved_tasks(None, None).await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_scheduler_batching() -> Result<(), anyhow::Error> {
+        let scheduler_config = SchedulerConfig {
+            state_change_batch_size: Some(1),
+            ..Default::default()
+        };
+        let (coordinator, shared_state) = setup_coordinator_with(None, scheduler_config).await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                "test_executor_id",
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        coordinator.run_scheduler().await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator.run_scheduler().await?;
+        coordinator
+            .create_content_metadata(vec![
+                test_mock_content_metadata("test_1", "test_1", &eg.name),
+                test_mock_content_metadata("test_2", "test_2", &eg.name),
+            ])
+            .await?;
+
+        //  A pass processes one state change and the tick the other
+        coordinator.run_scheduler().await?;
+        assert_eq!(
+            shared_state.unprocessed_state_change_events().await?.len(),
+            1
+        );
+        coordinator.admit_queued_content().await?;
+        assert_eq!(
+            shared_state.unprocessed_state_change_events().await?.len(),
+            0
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_scheduler_shards_share_executor_capacity() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .set_scheduler_settings(SchedulerSettings {
+                max_tasks_per_executor: Some(2),
+                ..Default::default()
+            })
+            .await?;
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                "test_executor_id",
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        coordinator.run_scheduler().await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+
+        //  Enough root content for every shard to plan tasks at once
+        let content = (0..32)
+            .map(|i| {
+                let id = format!("test_{}", i);
+                test_mock_content_metadata(&id, &id, &eg.name)
+            })
+            .collect();
+        coordinator.create_content_metadata(content).await?;
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state
+            .tasks_for_executor("test_executor_id", None)
+            .await?;
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(shared_state.unassigned_task_ids().await.len(), 30);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_metering() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let executor_id = "test_executor_id";
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
 
-    use indexify_internal_api as internal_api;
-    use indexify_proto::indexify_coordinator::CreateContentStatus;
-    use internal_api::{ContentMetadataId, ContentSource, TaskOutcome};
+        let start = crate::utils::timestamp_secs();
+        let mut content = test_mock_content_metadata("test", "test", &eg.name);
+        content.created_at = start as i64;
+        content.size_bytes = 100;
+        coordinator.create_content_metadata(vec![content]).await?;
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state.tasks_for_executor(executor_id, None).await?;
+        assert_eq!(tasks.len(), 1);
+        coordinator
+            .update_task(&tasks[0].id, executor_id, TaskOutcome::Success, None, None)
+            .await?;
+        coordinator
+            .record_egress(DEFAULT_TEST_NAMESPACE, 40)
+            .await?;
+        coordinator.record_egress(DEFAULT_TEST_NAMESPACE, 2).await?;
 
-    use super::Coordinator;
-    use crate::{
-        coordinator_client::CoordinatorClient,
-        garbage_collector::GarbageCollector,
-        server_config::ServerConfig,
-        state::App,
-        test_util::db_utils::{
-            complete_task,
-            create_content_for_task,
-            create_test_extraction_graph,
-            create_test_extraction_graph_with_children,
-            mock_extractor,
-            next_child,
-            perform_all_tasks,
-            perform_task,
-            test_mock_content_metadata,
-            Parent::{Child, Root},
-            DEFAULT_TEST_NAMESPACE,
-        },
-        test_utils::RaftTestCluster,
-    };
+        //  The test can cross the end of a period, so the records are summed
+        let records = coordinator.list_metering_records("", 0, crate::utils::timestamp_secs())?;
+        assert!(!records.is_empty());
+        assert!(records
+            .iter()
+            .all(|record| record.namespace == DEFAULT_TEST_NAMESPACE));
+        assert_eq!(records.iter().map(|r| r.invocations).sum::<u64>(), 1);
+        assert_eq!(records.iter().map(|r| r.stored_bytes).sum::<u64>(), 100);
+        assert_eq!(records.iter().map(|r| r.egress_bytes).sum::<u64>(), 42);
+        assert!(records.iter().any(|record| record
+            .task_seconds
+            .contains_key("extraction_graph_1/extraction_policy_1")));
+
+        assert!(coordinator
+            .list_metering_records("other_namespace", 0, crate::utils::timestamp_secs())?
+            .is_empty());
+        assert!(coordinator
+            .list_metering_records(DEFAULT_TEST_NAMESPACE, start + 7200, start + 7200)?
+            .is_empty());
+        Ok(())
+    }
 
-    async fn setup_coordinator() -> (Arc<Coordinator>, Arc<App>) {
-        let config = Arc::new(ServerConfig::default());
-        let _ = fs::remove_dir_all(config.state_store.clone().path.unwrap());
-        let garbage_collector = GarbageCollector::new();
-        let coordinator_client = CoordinatorClient::new(Arc::clone(&config));
-        let shared_state = App::new(
-            config.clone(),
-            None,
-            garbage_collector.clone(),
-            &config.coordinator_addr,
-            Arc::new(crate::metrics::init_provider()),
-        )
-        .await
-        .unwrap();
-        shared_state.initialize_raft().await.unwrap();
-        let coordinator = crate::coordinator::Coordinator::new(
-            shared_state.clone(),
-            coordinator_client,
-            garbage_collector,
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_list_executors() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                "executor_1",
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![test_mock_content_metadata("test", "test", &eg.name)])
+            .await?;
+        coordinator.run_scheduler().await?;
+        coordinator
+            .register_executor(
+                "localhost:8951",
+                "executor_2",
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+
+        let mut executors = coordinator.list_executors().await?;
+        executors.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(executors.len(), 2);
+        assert_eq!(executors[0].addr, "localhost:8950");
+        assert_eq!(executors[0].running_tasks, 1);
+        assert_eq!(executors[0].extractors.len(), 1);
+        assert_eq!(executors[1].running_tasks, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_list_executor_tasks() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        assert!(coordinator
+            .list_executor_tasks("executor_1")
+            .await?
+            .is_none());
+
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                "executor_1",
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![test_mock_content_metadata("test", "test", &eg.name)])
+            .await?;
+        coordinator.run_scheduler().await?;
+        coordinator
+            .register_executor(
+                "localhost:8951",
+                "executor_2",
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+
+        let assigned = shared_state.tasks_for_executor("executor_1", None).await?;
+        assert_eq!(assigned.len(), 1);
+        let tasks = coordinator
+            .list_executor_tasks("executor_1")
+            .await?
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, assigned[0].id);
+        assert_eq!(tasks[0].content_metadata.as_ref().unwrap().id, "test");
+        assert!(coordinator
+            .list_executor_tasks("executor_2")
+            .await?
+            .unwrap()
+            .is_empty());
+
+        //  Finished tasks are no longer listed
+        let mut task = assigned[0].clone();
+        task.outcome = internal_api::TaskOutcome::Success;
+        shared_state
+            .update_task(task, Some("executor_1".to_string()))
+            .await?;
+        assert!(coordinator
+            .list_executor_tasks("executor_1")
+            .await?
+            .unwrap()
+            .is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_kill_tasks() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let executor_id = "test_executor_id";
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![test_mock_content_metadata("test", "test", &eg.name)])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state.tasks_for_executor(executor_id, None).await?;
+        assert_eq!(tasks.len(), 1);
+
+        //  Nothing is killed without chaos
+        assert_eq!(coordinator.kill_tasks(&ChaosInjector::default()).await?, 0);
+
+        let chaos = ChaosInjector::new(Some(ChaosConfig {
+            task_kill_rate: 1.0,
+            ..Default::default()
+        }));
+        assert_eq!(coordinator.kill_tasks(&chaos).await?, 1);
+        let task = shared_state.task_with_id(&tasks[0].id).await?;
+        assert_eq!(task.outcome, TaskOutcome::Failed);
+        assert!(shared_state
+            .tasks_for_executor(executor_id, None)
+            .await?
+            .is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_extraction_graph_usage() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let executor_id = "test_executor_id";
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![
+                test_mock_content_metadata("test1", "test1", &eg.name),
+                test_mock_content_metadata("test2", "test2", &eg.name),
+            ])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state.tasks_for_executor(executor_id, None).await?;
+        assert_eq!(tasks.len(), 2);
+
+        let usage = [
+            internal_api::TaskUsage {
+                execution_secs: 2.0,
+                cpu_secs: 1.5,
+                max_memory_bytes: 300,
+            },
+            internal_api::TaskUsage {
+                execution_secs: 3.0,
+                cpu_secs: 2.5,
+                max_memory_bytes: 200,
+            },
+        ];
+        for (task, usage) in tasks.iter().zip(usage) {
+            coordinator
+                .update_task(
+                    &task.id,
+                    executor_id,
+                    TaskOutcome::Success,
+                    None,
+                    Some(usage),
+                )
+                .await?;
+        }
+
+        let usage = coordinator.get_extraction_graph_usage(DEFAULT_TEST_NAMESPACE, &eg.name)?;
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].version, 1);
+        let policy_usage = usage[0]
+            .extraction_policies
+            .get("extraction_policy_1")
+            .unwrap();
+        assert_eq!(policy_usage.tasks, 2);
+        assert_eq!(policy_usage.execution_secs, 5.0);
+        assert_eq!(policy_usage.cpu_secs, 4.0);
+        assert_eq!(policy_usage.max_memory_bytes, 300);
+
+        assert!(coordinator
+            .get_extraction_graph_usage(DEFAULT_TEST_NAMESPACE, "unknown")
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_invocation_breakdown() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let executor_id = "test_executor_id";
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![mock_extractor()],
+                internal_api::ExecutorProfile {
+                    cost_per_hour: Some(36.0),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![test_mock_content_metadata("test1", "test1", &eg.name)])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let tasks = shared_state.tasks_for_executor(executor_id, None).await?;
+        assert_eq!(tasks.len(), 1);
+
+        //  Running tasks count as unfinished
+        let breakdown = coordinator
+            .get_invocation_breakdown(DEFAULT_TEST_NAMESPACE, "test1")
+            .await?;
+        assert_eq!(breakdown.extraction_policies.len(), 1);
+        assert_eq!(breakdown.extraction_policies[0].unfinished_tasks, 1);
+
+        coordinator
+            .update_task(
+                &tasks[0].id,
+                executor_id,
+                TaskOutcome::Success,
+                None,
+                Some(internal_api::TaskUsage {
+                    execution_secs: 100.0,
+                    ..Default::default()
+                }),
+            )
+            .await?;
+        let breakdown = coordinator
+            .get_invocation_breakdown(DEFAULT_TEST_NAMESPACE, "test1")
+            .await?;
+        let policy = &breakdown.extraction_policies[0];
+        assert_eq!(policy.extraction_graph, eg.name);
+        assert_eq!(policy.extraction_policy, "extraction_policy_1");
+        assert_eq!(policy.tasks, 1);
+        assert_eq!(policy.unfinished_tasks, 0);
+        assert_eq!(policy.execution_secs, 100.0);
+        assert!((policy.estimated_cost - 1.0).abs() < 1e-9);
+        assert_eq!(policy.unpriced_tasks, 0);
+        assert!(breakdown.finished_at >= policy.finished_at);
+
+        assert!(coordinator
+            .get_invocation_breakdown(DEFAULT_TEST_NAMESPACE, "unknown")
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_graph_templates() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        let parameter =
+            |name: &str, default: Option<serde_json::Value>| internal_api::GraphTemplateParameter {
+                name: name.to_string(),
+                description: String::new(),
+                default,
+            };
+        let template = internal_api::GraphTemplate {
+            name: "rag".to_string(),
+            parameters: vec![
+                parameter("model", None),
+                parameter("chunk_size", Some(json!(1000))),
+            ],
+            graph: json!({
+                "extraction_policies": [{
+                    "extractor": "{{ model }}",
+                    "name": "chunks_{{chunk_size}}",
+                    "input_params": {"chunk_size": "{{ chunk_size }}"},
+                }],
+            }),
+            ..Default::default()
+        };
+        let template = coordinator.set_graph_template(template).await?;
+        assert!(template.created_at > 0);
+
+        //  Placeholders have to be parameters
+        let mut invalid = template.clone();
+        invalid.graph["extraction_policies"][0]["extractor"] = json!("{{ extractor }}");
+        assert!(coordinator.set_graph_template(invalid).await.is_err());
+
+        let stored = coordinator.get_graph_template("rag")?;
+        assert_eq!(stored, template);
+        assert_eq!(coordinator.list_graph_templates()?, vec![template.clone()]);
+
+        //  A placeholder alone takes the value as is, within text as text
+        let graph = stored.render(&HashMap::from([(
+            "model".to_string(),
+            json!("tensorlake/minilm-l6"),
+        )]))?;
+        assert_eq!(
+            graph["extraction_policies"][0],
+            json!({
+                "extractor": "tensorlake/minilm-l6",
+                "name": "chunks_1000",
+                "input_params": {"chunk_size": 1000},
+            })
         );
-        (coordinator, shared_state)
+        assert!(stored.render(&HashMap::new()).is_err());
+        assert!(stored
+            .render(&HashMap::from([
+                ("model".to_string(), json!("a")),
+                ("sink".to_string(), json!("b")),
+            ]))
+            .is_err());
+
+        coordinator.delete_graph_template("rag").await?;
+        assert!(coordinator.get_graph_template("rag").is_err());
+        assert!(coordinator.delete_graph_template("rag").await.is_err());
+        Ok(())
     }
 
     #[tokio::test]
     #[tracing_test::traced_test]
-    async fn test_create_and_read_extraction_graph() -> Result<(), anyhow::Error> {
+    async fn test_content_context() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        let root = test_mock_content_metadata("root", "", &eg.name);
+        let mut child = test_mock_content_metadata("child", "root", &eg.name);
+        child.parent_id = Some(root.id.clone());
+        coordinator
+            .create_content_metadata(vec![root, child])
+            .await?;
+
+        //  The context of a content tree is shared by all of its content
+        coordinator
+            .set_content_context(DEFAULT_TEST_NAMESPACE, "child", "pages", Some(json!(1)))
+            .await?;
+        coordinator
+            .set_content_context(DEFAULT_TEST_NAMESPACE, "root", "pages", Some(json!(2)))
+            .await?;
+        coordinator
+            .set_content_context(DEFAULT_TEST_NAMESPACE, "child", "title", Some(json!("a")))
+            .await?;
+        assert_eq!(
+            coordinator.get_content_context(DEFAULT_TEST_NAMESPACE, "root")?,
+            HashMap::from([
+                ("pages".to_string(), json!(2)),
+                ("title".to_string(), json!("a"))
+            ])
+        );
+
+        coordinator
+            .set_content_context(DEFAULT_TEST_NAMESPACE, "root", "title", None)
+            .await?;
+        assert_eq!(
+            coordinator.get_content_context(DEFAULT_TEST_NAMESPACE, "child")?,
+            HashMap::from([("pages".to_string(), json!(2))])
+        );
+
+        let too_large = json!("x".repeat(super::MAX_CONTENT_CONTEXT_VALUE_BYTES));
+        assert!(coordinator
+            .set_content_context(DEFAULT_TEST_NAMESPACE, "root", "large", Some(too_large))
+            .await
+            .is_err());
+        assert!(coordinator
+            .set_content_context(DEFAULT_TEST_NAMESPACE, "missing", "pages", Some(json!(1)))
+            .await
+            .is_err());
+        assert!(coordinator
+            .get_content_context("other_namespace", "root")
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_detect_and_repair_stuck_content() -> Result<(), anyhow::Error> {
         let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
 
-        // Add a namespace
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![test_mock_content_metadata("test", "test", &eg.name)])
+            .await?;
+        coordinator.run_scheduler().await?;
+        let task_id = shared_state
+            .unassigned_task_ids()
+            .await
+            .into_iter()
+            .next()
+            .unwrap();
+
+        //  Content with a pending task is only stuck once it stalls
+        let stalled_after = Duration::from_secs(3600);
+        assert!(coordinator
+            .list_stuck_content(DEFAULT_TEST_NAMESPACE, stalled_after)
+            .await?
+            .is_empty());
+        let stuck = coordinator
+            .list_stuck_content(DEFAULT_TEST_NAMESPACE, Duration::ZERO)
+            .await?;
+        assert_eq!(stuck.len(), 1);
+        assert_eq!(stuck[0].pending_tasks, 1);
+
+        //  The task drops out of the indexes, nothing can finish the content now
+        let task = shared_state.task_with_id(&task_id).await?;
+        let state = &shared_state.state_machine.data.indexify_state;
+        state
+            .unfinished_tasks_by_extractor
+            .remove(&task.extractor, &task.id);
+        state.unassigned_tasks.remove(&task.id);
+        let stuck = coordinator
+            .list_stuck_content(DEFAULT_TEST_NAMESPACE, stalled_after)
+            .await?;
+        assert_eq!(stuck.len(), 1);
+        assert_eq!(stuck[0].content_id, "test");
+        assert_eq!(stuck[0].outstanding_tasks, 1);
+        assert_eq!(stuck[0].pending_tasks, 0);
+        assert!(coordinator
+            .list_stuck_content("other_namespace", stalled_after)
+            .await?
+            .is_empty());
+
+        assert_eq!(
+            coordinator
+                .repair_content(DEFAULT_TEST_NAMESPACE, "test")
+                .await?,
+            1
+        );
+        coordinator.run_scheduler().await?;
+        assert!(coordinator
+            .list_stuck_content(DEFAULT_TEST_NAMESPACE, stalled_after)
+            .await?
+            .is_empty());
+        assert_eq!(
+            shared_state.unassigned_task_ids().await,
+            HashSet::from([task_id])
+        );
+        assert!(coordinator
+            .repair_content("other_namespace", "test")
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rerun_extraction_policy() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
         coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let executor_id = "test_executor_id";
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
 
-        //  Register an executor
-        let extractor = mock_extractor();
+        let eg = create_test_extraction_graph(
+            "extraction_graph_1",
+            vec!["extraction_policy_1", "extraction_policy_2"],
+        );
+        coordinator.create_extraction_graph(eg.clone()).await?;
         coordinator
-            .register_executor("localhost:8950", "test_executor_id", vec![extractor])
+            .create_content_metadata(vec![test_mock_content_metadata("test", "test", &eg.name)])
             .await?;
         coordinator.run_scheduler().await?;
+        let mut tasks = shared_state.tasks_for_executor(executor_id, None).await?;
+        tasks.sort_by(|a, b| a.extraction_policy_id.cmp(&b.extraction_policy_id));
+        assert_eq!(tasks.len(), 2);
+
+        //  A policy that hasn't completed on the content can't run again
+        assert!(coordinator
+            .rerun_extraction_policy(
+                DEFAULT_TEST_NAMESPACE,
+                "test",
+                &eg.name,
+                "extraction_policy_1",
+                "operator",
+            )
+            .await
+            .is_err());
 
-        //  Create an extraction graph
-        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
-        coordinator.create_extraction_graph(eg.clone()).await?;
+        perform_task(&coordinator, &tasks[0], "output_1", executor_id).await?;
+        perform_task(&coordinator, &tasks[1], "output_2", executor_id).await?;
+        coordinator.run_scheduler().await?;
+        assert!(shared_state
+            .tasks_for_executor(executor_id, None)
+            .await?
+            .is_empty());
+        assert!(coordinator
+            .rerun_extraction_policy(
+                DEFAULT_TEST_NAMESPACE,
+                "test",
+                &eg.name,
+                "unknown_policy",
+                "operator",
+            )
+            .await
+            .is_err());
+
+        //  Only the task of the policy that runs again is recreated, and the
+        //  outputs of its previous run are tombstoned
+        let replay_id = coordinator
+            .rerun_extraction_policy(
+                DEFAULT_TEST_NAMESPACE,
+                "test",
+                &eg.name,
+                "extraction_policy_1",
+                "operator",
+            )
+            .await?;
         coordinator.run_scheduler().await?;
+        let assigned = shared_state.tasks_for_executor(executor_id, None).await?;
+        assert_eq!(assigned.len(), 1);
+        assert_eq!(assigned[0].id, tasks[0].id);
+        assert_eq!(
+            shared_state.task_with_id(&tasks[0].id).await?.outcome,
+            internal_api::TaskOutcome::Unknown
+        );
+        assert_eq!(
+            shared_state.task_with_id(&tasks[1].id).await?.outcome,
+            internal_api::TaskOutcome::Success
+        );
+        let tombstoned = |id: &str| {
+            shared_state
+                .state_machine
+                .get_latest_version_of_content(id)
+                .unwrap()
+                .unwrap()
+                .tombstoned
+        };
+        assert!(tombstoned("output_1"));
+        assert!(!tombstoned("output_2"));
 
-        //  Read the extraction graph back
-        let ret_graph =
-            shared_state.get_extraction_graphs_by_name(DEFAULT_TEST_NAMESPACE, &[eg.name])?;
-        assert!(ret_graph.first().unwrap().is_some());
-        assert_eq!(ret_graph.len(), 1);
+        let (replay, _) = coordinator
+            .get_replay(DEFAULT_TEST_NAMESPACE, &replay_id)
+            .await?;
+        assert_eq!(replay.kind, internal_api::ReplayKind::ExtractionPolicy);
+        assert_eq!(replay.tasks.len(), 1);
+        assert_eq!(replay.tasks[0].task_id, tasks[0].id);
         Ok(())
     }
 
     #[tokio::test]
-    #[tracing_test::traced_test]
-    async fn test_create_content_metadata() -> Result<(), anyhow::Error> {
+    async fn test_rerun_failed_tasks() -> Result<(), anyhow::Error> {
         let (coordinator, shared_state) = setup_coordinator().await;
-
-        // Add a namespace
         coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let executor_id = "test_executor_id";
+        coordinator
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![mock_extractor()],
+                Default::default(),
+            )
+            .await?;
 
-        //  Register an executor
-        let extractor = mock_extractor();
+        let eg = create_test_extraction_graph(
+            "extraction_graph_1",
+            vec!["extraction_policy_1", "extraction_policy_2"],
+        );
+        coordinator.create_extraction_graph(eg.clone()).await?;
         coordinator
-            .register_executor("localhost:8950", "test_executor_id", vec![extractor])
+            .create_content_metadata(vec![test_mock_content_metadata("test", "test", &eg.name)])
             .await?;
         coordinator.run_scheduler().await?;
+        let mut tasks = shared_state.tasks_for_executor(executor_id, None).await?;
+        tasks.sort_by(|a, b| a.extraction_policy_id.cmp(&b.extraction_policy_id));
+        assert_eq!(tasks.len(), 2);
+        for (task, outcome) in tasks.iter().zip([
+            internal_api::TaskOutcome::Success,
+            internal_api::TaskOutcome::Failed,
+        ]) {
+            coordinator
+                .update_task(&task.id, executor_id, outcome, None, None)
+                .await?;
+        }
+        coordinator.run_scheduler().await?;
 
-        //  Create an extraction graph
-        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
-        coordinator.create_extraction_graph(eg.clone()).await?;
+        //  Only the failed task runs again
+        let replay = coordinator
+            .rerun_failed_tasks(DEFAULT_TEST_NAMESPACE, "test", "operator")
+            .await?
+            .unwrap();
+        assert_eq!(replay.kind, internal_api::ReplayKind::FailedTasks);
+        assert_eq!(replay.initiator, "operator");
+        assert_eq!(replay.tasks.len(), 1);
+        assert_eq!(replay.tasks[0].task_id, tasks[1].id);
+        assert_eq!(replay.tasks[0].graph_version, 1);
         coordinator.run_scheduler().await?;
+        assert_eq!(
+            shared_state.task_with_id(&tasks[0].id).await?.outcome,
+            internal_api::TaskOutcome::Success
+        );
+        assert_eq!(
+            shared_state.task_with_id(&tasks[1].id).await?.outcome,
+            internal_api::TaskOutcome::Unknown
+        );
 
-        let content_metadata = test_mock_content_metadata("test", "test", &eg.name);
+        //  Nothing failed anymore
+        assert!(coordinator
+            .rerun_failed_tasks(DEFAULT_TEST_NAMESPACE, "test", "operator")
+            .await?
+            .is_none());
+        assert!(coordinator
+            .rerun_failed_tasks("other_namespace", "test", "operator")
+            .await
+            .is_err());
 
-        //  Add content which will trigger task creation
+        //  The replay finishes with its last task, and its outputs are the
+        //  content the task extracted while it ran
+        let (running, _) = coordinator
+            .get_replay(DEFAULT_TEST_NAMESPACE, &replay.id)
+            .await?;
+        assert_eq!(running.finished_at, None);
+        let output = |id: &str| {
+            let mut output = test_mock_content_metadata(id, "test", &eg.name);
+            output.parent_id = Some(internal_api::ContentMetadataId::new("test"));
+            output.source = internal_api::ContentSource::ExtractionPolicyName(
+                replay.tasks[0].extraction_policy.clone(),
+            );
+            output
+        };
         coordinator
-            .create_content_metadata(vec![content_metadata.clone()])
+            .create_content_metadata(vec![output("replay_output")])
             .await?;
-        coordinator.run_scheduler().await?;
-
-        //  Read the content back from shared state and ensure graph id is correct
-        let retr_content = shared_state
-            .get_content_metadata_batch(vec![content_metadata.id.id.clone()])
+        coordinator
+            .update_task(
+                &tasks[1].id,
+                executor_id,
+                internal_api::TaskOutcome::Success,
+                None,
+                None,
+            )
             .await?;
+        coordinator
+            .create_content_metadata(vec![output("later_output")])
+            .await?;
+        let (finished, outputs) = coordinator
+            .get_replay(DEFAULT_TEST_NAMESPACE, &replay.id)
+            .await?;
+        assert!(finished.finished_at.is_some());
         assert_eq!(
-            retr_content.first().unwrap().extraction_graph_names.len(),
-            1
+            finished.tasks[0].outcome,
+            internal_api::TaskOutcome::Success
         );
+        assert_eq!(finished.failed_tasks(), 0);
+        assert_eq!(outputs, vec!["replay_output".to_string()]);
         assert_eq!(
-            retr_content
-                .first()
-                .unwrap()
-                .extraction_graph_names
-                .first()
-                .unwrap(),
-            &eg.name
+            coordinator.list_replays(DEFAULT_TEST_NAMESPACE, Some(&eg.name), Some("test"))?,
+            vec![finished]
         );
+        assert!(coordinator
+            .list_replays(DEFAULT_TEST_NAMESPACE, Some("other_graph"), None)?
+            .is_empty());
+        assert!(coordinator
+            .get_replay("other_namespace", &replay.id)
+            .await
+            .is_err());
         Ok(())
     }
 
     #[tokio::test]
-    // #[tracing_test::traced_test]
-    async fn test_create_and_complete_tasks() -> Result<(), anyhow::Error> {
+    async fn test_replay_lineage_across_graph_versions() -> Result<(), anyhow::Error> {
         let (coordinator, shared_state) = setup_coordinator().await;
-
-        // Add a namespace
         coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
-
-        //  Register an executor
         let executor_id = "test_executor_id";
-        let extractor = mock_extractor();
         coordinator
-            .register_executor("localhost:8950", executor_id, vec![extractor])
+            .register_executor(
+                "localhost:8950",
+                executor_id,
+                vec![mock_extractor()],
+                Default::default(),
+            )
             .await?;
-        coordinator.run_scheduler().await?;
-
-        //  Create an extraction graph
-        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        let mut eg =
+            create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
         coordinator.create_extraction_graph(eg.clone()).await?;
-        coordinator.run_scheduler().await?;
-
-        //  Add content which will trigger task creation because it is part of the graph
-        let content_metadata = test_mock_content_metadata("test", "test", &eg.name);
         coordinator
-            .create_content_metadata(vec![content_metadata.clone()])
+            .create_content_metadata(vec![test_mock_content_metadata("test", "test", &eg.name)])
             .await?;
-        let events = shared_state.unprocessed_state_change_events().await?;
-        assert_eq!(events.len(), 1);
-        coordinator.run_scheduler().await?;
-
-        //  Check that tasks have been created and assigned
-        let tasks = shared_state.unassigned_tasks().await?;
-        assert_eq!(tasks.len(), 0);
-        let tasks = shared_state.tasks_for_executor(executor_id, None).await?;
-        assert_eq!(tasks.len(), 1);
-        assert_eq!(
-            shared_state.unprocessed_state_change_events().await?.len(),
-            0
-        );
-
-        //  Create a separate piece of content metadata which will not trigger task
-        // creation
-        let content_metadata = test_mock_content_metadata("test2", "test2", "not_present_graph");
-        let result = coordinator
-            .create_content_metadata(vec![content_metadata.clone()])
-            .await;
-        assert!(result.is_err());
         coordinator.run_scheduler().await?;
-        let tasks = shared_state.unassigned_tasks().await?;
-        assert_eq!(tasks.len(), 0);
         let tasks = shared_state.tasks_for_executor(executor_id, None).await?;
         assert_eq!(tasks.len(), 1);
+        perform_task(&coordinator, &tasks[0], "output_1", executor_id).await?;
+
+        //  The policy is replayed on the first version of the graph, and again
+        //  once the graph changed
+        let mut replay_ids = Vec::new();
+        for (initiator, output) in [("alice", "output_2"), ("bob", "output_3")] {
+            if !replay_ids.is_empty() {
+                eg.max_active_content = Some(100);
+                coordinator
+                    .save_extraction_graph(eg.clone(), Default::default(), true)
+                    .await?;
+            }
+            replay_ids.push(
+                coordinator
+                    .rerun_extraction_policy(
+                        DEFAULT_TEST_NAMESPACE,
+                        "test",
+                        &eg.name,
+                        "extraction_policy_1",
+                        initiator,
+                    )
+                    .await?,
+            );
+            coordinator.run_scheduler().await?;
+            let tasks = shared_state.tasks_for_executor(executor_id, None).await?;
+            assert_eq!(tasks.len(), 1);
+            perform_task(&coordinator, &tasks[0], output, executor_id).await?;
+        }
 
-        // FIX ME Should be in state machine test
-        let mut task_clone = tasks[0].clone();
-        task_clone.outcome = internal_api::TaskOutcome::Success;
-        shared_state
-            .update_task(task_clone, Some(executor_id.to_string()))
-            .await
-            .unwrap();
-        let tasks = shared_state
-            .tasks_for_executor(executor_id, None)
-            .await
-            .unwrap();
-        assert_eq!(0, tasks.clone().len());
+        //  Each replay records who started it, the graph version it ran and
+        //  the content its task extracted, also once a later replay
+        //  tombstoned that content
+        let (first, first_outputs) = coordinator
+            .get_replay(DEFAULT_TEST_NAMESPACE, &replay_ids[0])
+            .await?;
+        assert_eq!(first.initiator, "alice");
+        assert_eq!(first.tasks.len(), 1);
+        assert_eq!(first.tasks[0].graph_version, 1);
+        assert_eq!(first.tasks[0].outcome, internal_api::TaskOutcome::Success);
+        assert_eq!(first.tasks[0].outputs, vec!["output_2".to_string()]);
+        assert!(first.finished_at.is_some());
+        assert!(first_outputs.is_empty());
+
+        let (second, second_outputs) = coordinator
+            .get_replay(DEFAULT_TEST_NAMESPACE, &replay_ids[1])
+            .await?;
+        assert_eq!(second.initiator, "bob");
+        assert_eq!(second.tasks[0].graph_version, 2);
+        assert_eq!(second.tasks[0].outputs, vec!["output_3".to_string()]);
+        assert!(second.finished_at.is_some());
+        assert_eq!(second.failed_tasks(), 0);
+        assert_eq!(second_outputs, vec!["output_3".to_string()]);
+
+        let mut listed: Vec<String> = coordinator
+            .list_replays(DEFAULT_TEST_NAMESPACE, Some(&eg.name), Some("test"))?
+            .into_iter()
+            .map(|replay| replay.id)
+            .collect();
+        listed.sort();
+        replay_ids.sort();
+        assert_eq!(listed, replay_ids);
         Ok(())
     }
 
@@ -837,7 +6250,12 @@ mod tests {
         let executor_id_1 = "test_executor_id_1";
         let extractor1 = mock_extractor();
         coordinator
-            .register_executor("localhost:8956", executor_id_1, vec![extractor1.clone()])
+            .register_executor(
+                "localhost:8956",
+                executor_id_1,
+                vec![extractor1.clone()],
+                Default::default(),
+            )
             .await?;
         coordinator.run_scheduler().await?;
 
@@ -852,7 +6270,12 @@ mod tests {
         let mut extractor2 = mock_extractor();
         extractor2.name = "MockExtractor2".to_string();
         coordinator
-            .register_executor("localhost:8957", executor_id_2, vec![extractor2.clone()])
+            .register_executor(
+                "localhost:8957",
+                executor_id_2,
+                vec![extractor2.clone()],
+                Default::default(),
+            )
             .await?;
         coordinator.run_scheduler().await?;
 
@@ -894,6 +6317,7 @@ mod tests {
                 "localhost:8956",
                 "test_executor_id",
                 vec![extractor.clone()],
+                Default::default(),
             )
             .await?;
         let eg = create_test_extraction_graph("eg_name", vec!["extraction_policy_name_1"]);
@@ -922,6 +6346,7 @@ mod tests {
                 "localhost:8957",
                 "test_executor_id_2",
                 vec![extractor_2.clone()],
+                Default::default(),
             )
             .await?;
 
@@ -979,7 +6404,12 @@ mod tests {
         let executor_id_1 = "test_executor_id_1";
         let extractor_1 = mock_extractor();
         coordinator
-            .register_executor("localhost:8956", executor_id_1, vec![extractor_1.clone()])
+            .register_executor(
+                "localhost:8956",
+                executor_id_1,
+                vec![extractor_1.clone()],
+                Default::default(),
+            )
             .await?;
         let eg = create_test_extraction_graph("eg_name_1", vec!["ep_policy_name_1"]);
         coordinator.create_extraction_graph(eg.clone()).await?;
@@ -988,7 +6418,12 @@ mod tests {
         let mut extractor_2 = mock_extractor();
         extractor_2.name = "MockExtractor2".to_string();
         coordinator
-            .register_executor("localhost:8957", executor_id_2, vec![extractor_2.clone()])
+            .register_executor(
+                "localhost:8957",
+                executor_id_2,
+                vec![extractor_2.clone()],
+                Default::default(),
+            )
             .await?;
 
         //  Create an extraction graph with two levels of policies
@@ -1061,7 +6496,12 @@ mod tests {
         let executor_id_1 = "test_executor_id_1";
         let extractor_1 = mock_extractor();
         coordinator
-            .register_executor("localhost:8956", executor_id_1, vec![extractor_1.clone()])
+            .register_executor(
+                "localhost:8956",
+                executor_id_1,
+                vec![extractor_1.clone()],
+                Default::default(),
+            )
             .await?;
 
         //  Create an extraction graph
@@ -1160,6 +6600,7 @@ mod tests {
                 "localhost:8956",
                 "test_executor_id",
                 vec![extractor_1.clone()],
+                Default::default(),
             )
             .await?;
 
@@ -1232,7 +6673,12 @@ mod tests {
         let executor_id_1 = "test_executor_id_1";
         let extractor_1 = mock_extractor();
         coordinator
-            .register_executor("localhost:8956", executor_id_1, vec![extractor_1.clone()])
+            .register_executor(
+                "localhost:8956",
+                executor_id_1,
+                vec![extractor_1.clone()],
+                Default::default(),
+            )
             .await?;
 
         //  Create an extraction graph
@@ -1274,8 +6720,10 @@ mod tests {
             coordinator
                 .update_task(
                     &task.id,
-                    "test_executor_id",
+                    "test_executor_id_1",
                     internal_api::TaskOutcome::Success,
+                    None,
+                    None,
                 )
                 .await?;
         }
@@ -1303,6 +6751,116 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_gc_waits_for_pending_gc_tasks() -> Result<(), anyhow::Error> {
+        let (coordinator, shared_state) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        coordinator
+            .set_scheduler_settings(SchedulerSettings {
+                max_pending_gc_tasks: Some(1),
+                ..Default::default()
+            })
+            .await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![
+                test_mock_content_metadata("first", "first", &eg.name),
+                test_mock_content_metadata("second", "second", &eg.name),
+            ])
+            .await?;
+        coordinator.run_scheduler().await?;
+
+        coordinator
+            .tombstone_content_metadatas(&["first".to_string()])
+            .await?;
+        coordinator.run_scheduler().await?;
+        assert_eq!(shared_state.count_unfinished_gc_tasks(), 1);
+
+        //  The second tree waits, and the scheduler doesn't run again for it
+        //  until a garbage collection task finishes
+        coordinator
+            .tombstone_content_metadatas(&["second".to_string()])
+            .await?;
+        coordinator.run_scheduler().await?;
+        coordinator.admit_queued_content().await?;
+        let gc_tasks = shared_state.list_all_gc_tasks().await?;
+        assert_eq!(gc_tasks.len(), 1);
+        assert_eq!(gc_tasks[0].content_id.id, "first");
+        assert!(coordinator.gc_waiting_for_capacity.load(Ordering::Relaxed));
+
+        let mut gc_task = gc_tasks[0].clone();
+        gc_task.outcome = internal_api::TaskOutcome::Success;
+        shared_state.update_gc_task(gc_task).await?;
+        assert_eq!(shared_state.count_unfinished_gc_tasks(), 0);
+        coordinator.admit_queued_content().await?;
+        assert!(!coordinator.gc_waiting_for_capacity.load(Ordering::Relaxed));
+        assert_eq!(shared_state.count_unfinished_gc_tasks(), 1);
+        assert!(shared_state
+            .list_all_gc_tasks()
+            .await?
+            .iter()
+            .any(|gc_task| gc_task.content_id.id == "second"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_archive_content_before_deletion() -> Result<(), anyhow::Error> {
+        let dir = tempfile::tempdir()?;
+        let blob_storage = Arc::new(BlobStorage::new_with_config(BlobStorageConfig {
+            s3: None,
+            disk: Some(DiskStorageConfig {
+                path: dir.path().to_str().unwrap().to_string(),
+            }),
+        }));
+        let archiver = ContentArchiver::new(&ContentArchiveConfig::default(), blob_storage);
+        let (coordinator, shared_state) =
+            setup_coordinator_with(Some(archiver), SchedulerConfig::default()).await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![test_mock_content_metadata("test", "test", &eg.name)])
+            .await?;
+        coordinator.run_scheduler().await?;
+        coordinator
+            .set_content_context(DEFAULT_TEST_NAMESPACE, "test", "user", Some(json!("u1")))
+            .await?;
+
+        coordinator
+            .tombstone_content_metadatas(&["test".to_string()])
+            .await?;
+        coordinator.run_scheduler().await?;
+        //  The content is deleted once its archive was written off the
+        //  scheduler loop
+        assert!(shared_state.list_all_gc_tasks().await?.is_empty());
+        for _ in 0..50 {
+            coordinator.admit_queued_content().await?;
+            if !shared_state.list_all_gc_tasks().await?.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        let path = dir.path().join(format!(
+            "archive/{}/{}/test_1.json.gz",
+            DEFAULT_TEST_NAMESPACE, eg.name
+        ));
+        let mut json = String::new();
+        flate2::read::GzDecoder::new(fs::File::open(path)?).read_to_string(&mut json)?;
+        let archive: ContentArchive = serde_json::from_str(&json)?;
+        assert_eq!(archive.context.get("user"), Some(&json!("u1")));
+        assert_eq!(archive.tasks.len(), 1);
+        assert_eq!(
+            archive.tasks[0].extraction_policy_id,
+            eg.extraction_policies[0].id
+        );
+        assert_eq!(archive.content.len(), 1);
+        assert_eq!(shared_state.list_all_gc_tasks().await?.len(), 1);
+        Ok(())
+    }
+
     use futures::FutureExt;
     use tokio::select;
 
@@ -1318,6 +6876,7 @@ mod tests {
                 "localhost:8956",
                 "test_executor_id",
                 vec![extractor.clone()],
+                Default::default(),
             )
             .await?;
 
@@ -1385,6 +6944,7 @@ mod tests {
                 "localhost:8956",
                 "test_executor_id",
                 vec![extractor_1.clone()],
+                Default::default(),
             )
             .await?;
 
@@ -1708,7 +7268,7 @@ mod tests {
         let executor_id = "executor_id";
         let addr = "addr";
         coordinator
-            .register_executor(addr, executor_id, vec![extractor])
+            .register_executor(addr, executor_id, vec![extractor], Default::default())
             .await?;
 
         //  Create the extraction policy under the namespace of the content
@@ -1735,11 +7295,124 @@ mod tests {
             .shared_state
             .tasks_for_executor(executor_id, None)
             .await?;
-        let unassigned_tasks = coordinator.shared_state.unassigned_tasks().await?;
+        let unassigned_tasks = coordinator.shared_state.unassigned_task_ids().await;
 
         // Check if the task is created for the content that matches the policy
         // and    // non matching content does not have a task.
         assert_eq!(tasks.len() + unassigned_tasks.len(), 1);
         Ok(())
     }
+
+    #[test]
+    fn test_coalesce_state_changes() {
+        let changes: Vec<internal_api::StateChange> = [
+            ("executor_1", internal_api::ChangeType::ExecutorRemoved),
+            ("content_1", internal_api::ChangeType::NewContent),
+            ("content_1", internal_api::ChangeType::ContentUpdated),
+            ("", internal_api::ChangeType::OrphanedAssignmentsReaped),
+            ("content_1", internal_api::ChangeType::ContentUpdated),
+            ("content_2", internal_api::ChangeType::ContentUpdated),
+            ("content_1", internal_api::ChangeType::NewContent),
+            ("executor_2", internal_api::ChangeType::ExecutorAdded),
+            ("executor_2", internal_api::ChangeType::ExecutorAdded),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, (object_id, change_type))| internal_api::StateChange {
+            id: internal_api::StateChangeId::new(i as u64),
+            ..internal_api::StateChange::new(object_id.to_string(), change_type, 0)
+        })
+        .collect();
+
+        let (kept, redundant) = coalesce_state_changes(changes);
+        let ids = |changes: &[internal_api::StateChange]| {
+            changes.iter().map(|change| change.id).collect::<Vec<_>>()
+        };
+        //  Only the latest allocation, update of a content and addition of an
+        //  executor are kept, every new content is
+        assert_eq!(
+            ids(&redundant),
+            [0, 2, 7].map(internal_api::StateChangeId::new)
+        );
+        assert_eq!(
+            ids(&kept),
+            [1, 3, 4, 5, 6, 8].map(internal_api::StateChangeId::new)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_shard_workers() {
+        let change = |id: u64| internal_api::StateChange {
+            id: internal_api::StateChangeId::new(id),
+            ..internal_api::StateChange::new(
+                format!("content_{}", id),
+                internal_api::ChangeType::NewContent,
+                0,
+            )
+        };
+        let processed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let shards = vec![
+            vec![change(1), change(2)],
+            vec![],
+            vec![change(3), change(4), change(5)],
+        ];
+        let result = run_scheduler_shards(shards, {
+            let processed = processed.clone();
+            move |change: internal_api::StateChange| {
+                let processed = processed.clone();
+                async move {
+                    let id: u64 = change.id.into();
+                    if id == 1 {
+                        return Err(anyhow::anyhow!("change 1 fails"));
+                    }
+                    //  Earlier changes take longer, they still finish first
+                    tokio::time::sleep(Duration::from_millis(10 * (6 - id))).await;
+                    processed.lock().unwrap().push(id);
+                    Ok(())
+                }
+            }
+        })
+        .await;
+
+        //  The failing shard stops at its failing change, the other one isn't
+        //  cancelled and keeps the order of its changes
+        assert!(result.is_err());
+        assert_eq!(*processed.lock().unwrap(), vec![3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_shards_by_namespace() -> Result<(), anyhow::Error> {
+        let (coordinator, _) = setup_coordinator().await;
+        coordinator.create_namespace(DEFAULT_TEST_NAMESPACE).await?;
+        let eg = create_test_extraction_graph("extraction_graph_1", vec!["extraction_policy_1"]);
+        coordinator.create_extraction_graph(eg.clone()).await?;
+        coordinator
+            .create_content_metadata(vec![
+                test_mock_content_metadata("root_1", "", &eg.name),
+                test_mock_content_metadata("child_1", "root_1", &eg.name),
+            ])
+            .await?;
+
+        //  The changes of a content tree are processed by the shard of its
+        //  namespace, changes of content that is gone by the shard of their
+        //  content
+        for object_id in ["root_1", "child_1"] {
+            let change = internal_api::StateChange::new(
+                object_id.to_string(),
+                internal_api::ChangeType::ContentUpdated,
+                0,
+            );
+            assert_eq!(
+                coordinator.namespace_of_change(&change),
+                DEFAULT_TEST_NAMESPACE
+            );
+        }
+        let change = internal_api::StateChange::new(
+            "missing".to_string(),
+            internal_api::ChangeType::ContentUpdated,
+            0,
+        );
+        assert_eq!(coordinator.namespace_of_change(&change), "missing");
+        Ok(())
+    }
 }
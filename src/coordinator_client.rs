@@ -221,6 +221,18 @@ impl CoordinatorClient {
         Ok(response.into_inner().task)
     }
 
+    /// Task with the states it moved through, oldest first
+    pub async fn get_task_with_transitions(
+        &self,
+        task_id: &str,
+    ) -> Result<indexify_proto::indexify_coordinator::GetTaskResponse> {
+        let request = tonic::Request::new(indexify_proto::indexify_coordinator::GetTaskRequest {
+            task_id: task_id.to_string(),
+        });
+        let response = self.get().await?.get_task(request).await?;
+        Ok(response.into_inner())
+    }
+
     pub async fn get_metadata_for_ingestion(
         &self,
         task_id: &str,
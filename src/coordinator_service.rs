@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     net::SocketAddr,
     pin::Pin,
     sync::{
@@ -7,11 +7,11 @@ use std::{
         Arc,
     },
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use anyhow::{anyhow, Result};
-use axum::{extract::State, routing::get};
+use axum::{extract::State, middleware, routing::get};
 use futures::StreamExt;
 use hyper::StatusCode;
 use indexify_internal_api as internal_api;
@@ -25,6 +25,11 @@ use indexify_proto::indexify_coordinator::{
     CreateExtractionGraphResponse,
     CreateGcTasksRequest,
     CreateGcTasksResponse,
+    DeregisterExecutorRequest,
+    DeregisterExecutorResponse,
+    DiagnosticSeverity,
+    ExtractionGraphDiagnostic,
+    ExtractionPolicyRequest,
     GcTask,
     GcTaskAcknowledgement,
     GetAllSchemaRequest,
@@ -74,6 +79,9 @@ use indexify_proto::indexify_coordinator::{
     UpdateIndexesStateResponse,
     UpdateTaskRequest,
     UpdateTaskResponse,
+    UpdateTasksRequest,
+    UpdateTasksResponse,
+    ValidateExtractionGraphResponse,
     WaitContentExtractionRequest,
     WaitContentExtractionResponse,
 };
@@ -97,32 +105,91 @@ use tokio::{
     time::timeout,
 };
 use tokio_stream::{wrappers::ReceiverStream, Stream};
-use tonic::{body::BoxBody, Request, Response, Status, Streaming};
+use tonic::{body::BoxBody, metadata::MetadataMap, Request, Response, Status, Streaming};
 use tower::{Layer, Service, ServiceBuilder};
 use tracing::{error, info, warn, Instrument};
 
 use crate::{
-    api::IndexifyAPIError,
+    admin_routes::{restrict_admin_routes, AdminRoutePolicy, Listener},
+    alerting::Alerter,
+    api::{IndexifyAPIError, STREAM_CURSOR_METADATA_KEY},
+    auth::{authenticate_request, Authenticator},
+    blob_storage::BlobStorage,
+    chaos::ChaosInjector,
+    content_archive::ContentArchiver,
+    content_hooks::ContentHooks,
     coordinator::Coordinator,
     coordinator_client::CoordinatorClient,
     garbage_collector::GarbageCollector,
-    server_config::ServerConfig,
-    state::{self, grpc_config::GrpcConfig},
+    metering::MeteringPusher,
+    preflight,
+    profiling,
+    read_only::ReadOnlyLayer,
+    replication::Replicator,
+    server_config::{ServerConfig, StuckContentConfig},
+    state::{
+        self,
+        grpc_config::GrpcConfig,
+        notifier::ChangeWatcher,
+        store::archive::StateArchiver,
+    },
+    task_bundler::TaskBundler,
+    task_canceller::CancelTasksFilter,
     tonic_streamer::DropReceiver,
+    utils::timestamp_secs,
 };
 
 type HBResponseStream = Pin<Box<dyn Stream<Item = Result<HeartbeatResponse, Status>> + Send>>;
 type GCTasksResponseStream =
     Pin<Box<dyn tokio_stream::Stream<Item = Result<CoordinatorCommand, Status>> + Send + Sync>>;
+type ContentResponseStream =
+    Pin<Box<dyn Stream<Item = Result<indexify_coordinator::ContentMetadata, Status>> + Send>>;
+type TasksResponseStream =
+    Pin<Box<dyn Stream<Item = Result<indexify_coordinator::Task, Status>> + Send>>;
+
+/// Sends the rows of a stream read from the store. A read error ends the
+/// stream with a status holding the key of the last row sent, or the cursor
+/// the stream started from, to resume it from.
+fn stream_rows<T, R>(
+    rows: impl Stream<Item = Result<(String, T)>> + Send + 'static,
+    cursor: Option<String>,
+) -> impl Stream<Item = Result<R, Status>> + Send + 'static
+where
+    T: Into<R>,
+    R: Send + 'static,
+{
+    rows.scan(cursor, |cursor, row| {
+        let row = match row {
+            Ok((key, row)) => {
+                *cursor = Some(key);
+                Ok(row.into())
+            }
+            Err(e) => {
+                let mut metadata = MetadataMap::new();
+                if let Some(cursor) = cursor.as_ref().and_then(|c| c.parse().ok()) {
+                    metadata.insert(STREAM_CURSOR_METADATA_KEY, cursor);
+                }
+                Err(Status::with_metadata(
+                    tonic::Code::Aborted,
+                    e.to_string(),
+                    metadata,
+                ))
+            }
+        };
+        futures::future::ready(Some(row))
+    })
+}
 
 pub struct ExtractionPolicyCreationResult {
     extraction_policies: Vec<internal_api::ExtractionPolicy>,
-    extractors: Vec<internal_api::ExtractorDescription>,
 }
 
 pub struct CoordinatorServiceServer {
     coordinator: Arc<Coordinator>,
     shutdown_rx: Receiver<()>,
+    stuck_content_config: StuckContentConfig,
+    content_hooks: Arc<ContentHooks>,
+    chaos: Arc<ChaosInjector>,
 }
 
 struct MetadataMap<'a>(&'a reqwest::header::HeaderMap);
@@ -140,7 +207,425 @@ impl<'a> Extractor for MetadataMap<'a> {
 // How often we expect the executor to send us heartbeats.
 const EXECUTOR_HEARTBEAT_PERIOD: Duration = Duration::new(5, 0);
 
+// Executors hold a lease on their tasks that their heartbeats renew. An
+// executor whose lease isn't renewed within the TTL is removed by the leader,
+// even if the coordinator that was receiving its heartbeats went away.
+const EXECUTOR_LEASE_TTL: Duration = Duration::new(30, 0);
+const EXECUTOR_LEASE_RENEW_PERIOD: Duration = Duration::new(10, 0);
+const EXECUTOR_LEASE_CHECK_PERIOD: Duration = Duration::new(5, 0);
+
+// How often the leader looks for task assignments that disagree with the tasks
+const ORPHANED_ASSIGNMENT_REAP_PERIOD: Duration = Duration::new(60, 0);
+
+// How often the leader takes burst tasks back from executors that hold more
+// tasks than they may
+const BURST_TASK_SHEDDING_PERIOD: Duration = Duration::new(30, 0);
+
+// How often the leader repairs stuck content when auto repair is on
+const STUCK_CONTENT_REPAIR_PERIOD: Duration = Duration::new(300, 0);
+
+// How often the leader counts the tasks starved past the threshold of the
+// scheduler for the metrics
+const STARVED_TASK_CHECK_PERIOD: Duration = Duration::new(30, 0);
+
+// How often the leader computes the compliance of extraction policies with
+// their objectives for the metrics and alerts
+const SLO_CHECK_PERIOD: Duration = Duration::new(60, 0);
+
+// How often every node compacts the key ranges of the state it deleted
+const DELETED_STATE_COMPACTION_PERIOD: Duration = Duration::new(600, 0);
+
+// How often the leader expires the tasks of content whose TTL ran out
+const TASK_EXPIRY_PERIOD: Duration = Duration::new(30, 0);
+
+// How often the leader aborts the cancel tasks jobs of coordinators that
+// stopped and forgets the jobs past their retention
+const CANCEL_TASKS_JOB_EVICTION_PERIOD: Duration = Duration::new(600, 0);
+
+// How often the leader allocates tasks held back by the rate limit of their
+// extraction policy. Limits are per second, so this has to be shorter.
+const THROTTLED_TASK_ALLOCATION_PERIOD: Duration = Duration::from_millis(200);
+
+// Policies reading from the same source beyond which a graph is linted for
+// its fan out, every content of the source creates a task for each of them
+const LARGE_FAN_OUT: usize = 8;
+
+/// Problems of an extraction graph that would make its creation fail, with
+/// the extractors looked up by `get_extractor`
+fn extraction_graph_errors(
+    extraction_graph: &CreateExtractionGraphRequest,
+    get_extractor: impl Fn(&str) -> Result<internal_api::ExtractorDescription>,
+) -> Vec<ExtractionGraphDiagnostic> {
+    let diagnostic = |severity: DiagnosticSeverity, extraction_policy: &str, message: String| {
+        ExtractionGraphDiagnostic {
+            severity: severity as i32,
+            extraction_policy: extraction_policy.to_string(),
+            message,
+        }
+    };
+    let mut diagnostics = Vec::new();
+    if extraction_graph.name.is_empty() {
+        diagnostics.push(diagnostic(
+            DiagnosticSeverity::Error,
+            "",
+            "extraction graph name is empty".to_string(),
+        ));
+    }
+
+    let mut name_to_policy_mapping = HashMap::new();
+    for policy in &extraction_graph.policies {
+        if name_to_policy_mapping
+            .insert(policy.name.as_str(), policy)
+            .is_some()
+        {
+            diagnostics.push(diagnostic(
+                DiagnosticSeverity::Error,
+                &policy.name,
+                format!(
+                    "extraction policy '{}' is defined more than once",
+                    policy.name
+                ),
+            ));
+        }
+    }
+
+    for policy in &extraction_graph.policies {
+        let source = policy.content_source.as_str();
+        if !source.is_empty() && !name_to_policy_mapping.contains_key(source) {
+            diagnostics.push(diagnostic(
+                DiagnosticSeverity::Error,
+                &policy.name,
+                format!("content source '{}' is not found in the graph", source),
+            ));
+        }
+        if source.is_empty() && !policy.source_output.is_empty() {
+            diagnostics.push(diagnostic(
+                DiagnosticSeverity::Error,
+                &policy.name,
+                format!(
+                    "output '{}' is read without a content source",
+                    policy.source_output
+                ),
+            ));
+        }
+        if let Err(e) = parse_output_schema(&policy.output_schema) {
+            diagnostics.push(diagnostic(
+                DiagnosticSeverity::Error,
+                &policy.name,
+                e.to_string(),
+            ));
+        }
+        let input_params: serde_json::Value = match serde_json::from_str(&policy.input_params) {
+            Ok(input_params) => input_params,
+            Err(e) => {
+                diagnostics.push(diagnostic(
+                    DiagnosticSeverity::Error,
+                    &policy.name,
+                    format!("unable to parse input_params: {}", e),
+                ));
+                continue;
+            }
+        };
+        //  Subgraphs are expanded before the graph is validated, so a policy
+        //  without an extractor references nothing to run
+        if policy.extractor.is_empty() && policy.subgraph.is_empty() {
+            diagnostics.push(diagnostic(
+                DiagnosticSeverity::Error,
+                &policy.name,
+                "extraction policy has neither an extractor nor a subgraph".to_string(),
+            ));
+            continue;
+        }
+        let extractor = match get_extractor(&policy.extractor) {
+            Ok(extractor) => extractor,
+            Err(e) => {
+                diagnostics.push(diagnostic(
+                    DiagnosticSeverity::Error,
+                    &policy.name,
+                    e.to_string(),
+                ));
+                continue;
+            }
+        };
+        if let Err(e) = extractor.validate_input_params(&input_params) {
+            diagnostics.push(diagnostic(
+                DiagnosticSeverity::Error,
+                &policy.name,
+                format!("invalid input_params: {}", e),
+            ));
+        }
+    }
+
+    // Every policy has a single content source, so following the sources
+    // from a policy either leaves the graph or runs into a cycle.
+    for policy in name_to_policy_mapping.values() {
+        let mut path = vec![policy.name.as_str()];
+        let mut source = policy.content_source.as_str();
+        while let Some(parent) = name_to_policy_mapping.get(source) {
+            if parent.name == policy.name {
+                // Report each cycle once, from its smallest policy name
+                if path.iter().all(|name| *name >= policy.name.as_str()) {
+                    path.push(policy.name.as_str());
+                    diagnostics.push(diagnostic(
+                        DiagnosticSeverity::Error,
+                        &policy.name,
+                        format!("content sources form a cycle: {}", path.join(" <- ")),
+                    ));
+                }
+                break;
+            }
+            if path.contains(&parent.name.as_str()) {
+                break;
+            }
+            path.push(parent.name.as_str());
+            source = parent.content_source.as_str();
+        }
+    }
+    diagnostics
+}
+
+/// Warnings about a graph that would be created as is but likely doesn't do
+/// what its author meant, from its definition alone
+fn lint_extraction_graph(
+    extraction_graph: &CreateExtractionGraphRequest,
+) -> Vec<ExtractionGraphDiagnostic> {
+    let warning = |extraction_policy: &str, message: String| ExtractionGraphDiagnostic {
+        severity: DiagnosticSeverity::Warning as i32,
+        extraction_policy: extraction_policy.to_string(),
+        message,
+    };
+    let policies: HashMap<&str, &ExtractionPolicyRequest> = extraction_graph
+        .policies
+        .iter()
+        .map(|policy| (policy.name.as_str(), policy))
+        .collect();
+    let mut diagnostics = Vec::new();
+    for policy in &extraction_graph.policies {
+        //  Extracted content inherits the labels of its parent, so a filter
+        //  that contradicts a filter of a policy upstream never matches
+        let mut visited = HashSet::from([policy.name.as_str()]);
+        let mut source = policy.content_source.as_str();
+        'ancestors: while let Some(ancestor) = policies.get(source) {
+            if !visited.insert(ancestor.name.as_str()) {
+                break;
+            }
+            for (key, value) in &policy.filters {
+                if let Some(upstream) = ancestor.filters.get(key).filter(|v| *v != value) {
+                    diagnostics.push(warning(
+                        &policy.name,
+                        format!(
+                            "unreachable: filter {}={} never matches, content reaching it was selected by '{}' with {}={}",
+                            key, value, ancestor.name, key, upstream
+                        ),
+                    ));
+                    break 'ancestors;
+                }
+            }
+            source = ancestor.content_source.as_str();
+        }
+
+        let latency_ms = policy.slo.as_ref().map_or(0, |slo| slo.latency_ms);
+        if policy.max_tasks_per_second > 0 && latency_ms == 0 {
+            diagnostics.push(warning(
+                &policy.name,
+                "calls a rate limited service without a latency objective, set slo.latency_ms so that slow calls are noticed".to_string(),
+            ));
+        }
+    }
+
+    let mut fan_outs: BTreeMap<&str, usize> = BTreeMap::new();
+    for policy in &extraction_graph.policies {
+        *fan_outs.entry(policy.content_source.as_str()).or_default() += 1;
+    }
+    for (source, fan_out) in fan_outs {
+        if fan_out > LARGE_FAN_OUT {
+            let from = if source.is_empty() {
+                "ingested content".to_string()
+            } else {
+                format!("the output of '{}'", source)
+            };
+            diagnostics.push(warning(
+                source,
+                format!(
+                    "large fan out: {} policies read {}, each content creates {} tasks",
+                    fan_out, from, fan_out
+                ),
+            ));
+        }
+    }
+    diagnostics
+}
+
+/// Parses the output schema of an extraction policy request, which is empty
+/// when the policy has none, and checks that it compiles
+fn parse_output_schema(output_schema: &str) -> Result<Option<serde_json::Value>> {
+    if output_schema.is_empty() {
+        return Ok(None);
+    }
+    let schema: serde_json::Value = serde_json::from_str(output_schema)
+        .map_err(|e| anyhow!("unable to parse output_schema: {}", e))?;
+    jsonschema::JSONSchema::compile(&schema)
+        .map_err(|e| anyhow!("invalid output_schema: {}", e))?;
+    Ok(Some(schema))
+}
+
+/// Copies the policies of `subgraph` into a graph in place of `node`. The
+/// copies are named after the node, and the entry policies of the subgraph
+/// read from the node's content source with the node's filters.
+fn expand_subgraph(
+    node: &ExtractionPolicyRequest,
+    subgraph: &ExtractionGraph,
+) -> Result<Vec<ExtractionPolicyRequest>> {
+    subgraph
+        .extraction_policies
+        .iter()
+        .map(|policy| {
+            let (content_source, source_output, filters) = match &policy.content_source {
+                internal_api::ExtractionPolicyContentSource::Ingestion => {
+                    let mut filters = node.filters.clone();
+                    filters.extend(policy.filters.clone());
+                    (
+                        node.content_source.clone(),
+                        node.source_output.clone(),
+                        filters,
+                    )
+                }
+                internal_api::ExtractionPolicyContentSource::ExtractionPolicyName(source) => (
+                    format!("{}.{}", node.name, source),
+                    policy.source_output.clone().unwrap_or_default(),
+                    policy.filters.clone(),
+                ),
+            };
+            Ok(ExtractionPolicyRequest {
+                namespace: node.namespace.clone(),
+                extractor: policy.extractor.clone(),
+                name: format!("{}.{}", node.name, policy.name),
+                input_params: serde_json::to_string(&policy.input_params)?,
+                filters,
+                content_source,
+                created_at: node.created_at,
+                subgraph: String::new(),
+                output_schema: policy
+                    .output_schema
+                    .as_ref()
+                    .map(|schema| schema.to_string())
+                    .unwrap_or_default(),
+                source_output,
+                max_tasks_per_second: policy.max_tasks_per_second.unwrap_or_default(),
+                expensive_warmup: policy.expensive_warmup,
+                min_warm_executors: policy.min_warm_executors.unwrap_or_default(),
+                gpu_millis: policy.gpu_millis.unwrap_or_default(),
+                slo: policy.slo.map(Into::into),
+            })
+        })
+        .collect()
+}
+
+/// Name of the copied policy that a policy reading from the subgraph node
+/// `node_name` reads from. That is the only policy of the subgraph no other
+/// policy reads from.
+fn subgraph_output(node_name: &str, subgraph: &ExtractionGraph) -> Result<String> {
+    let leaves: Vec<_> = subgraph
+        .extraction_policies
+        .iter()
+        .filter(|policy| {
+            !subgraph
+                .extraction_policies
+                .iter()
+                .any(|child| child.content_source.to_string() == policy.name)
+        })
+        .collect();
+    match leaves.as_slice() {
+        [leaf] => Ok(format!("{}.{}", node_name, leaf.name)),
+        _ => Err(anyhow!(
+            "subgraph '{}' has {} leaf extraction policies, \
+            use '{}.<extraction policy>' as the content source",
+            subgraph.name,
+            leaves.len(),
+            node_name
+        )),
+    }
+}
+
 impl CoordinatorServiceServer {
+    /// Replaces the policies of `extraction_graph` that reference a subgraph
+    /// with copies of the subgraph's policies. Stored graphs are already
+    /// expanded, so subgraphs of subgraphs don't need another pass.
+    fn expand_subgraphs(
+        &self,
+        mut extraction_graph: CreateExtractionGraphRequest,
+    ) -> Result<CreateExtractionGraphRequest> {
+        let mut subgraphs = HashMap::new();
+        for policy in &extraction_graph.policies {
+            if policy.subgraph.is_empty() {
+                continue;
+            }
+            let subgraph = self
+                .coordinator
+                .get_extraction_graph(&extraction_graph.namespace, &policy.subgraph)?
+                .ok_or_else(|| {
+                    anyhow!(
+                        "subgraph '{}' of extraction policy '{}' is not found in namespace '{}'",
+                        policy.subgraph,
+                        policy.name,
+                        extraction_graph.namespace
+                    )
+                })?;
+            subgraphs.insert(policy.name.clone(), subgraph);
+        }
+        if subgraphs.is_empty() {
+            return Ok(extraction_graph);
+        }
+
+        let mut policies = Vec::new();
+        for policy in extraction_graph.policies {
+            match subgraphs.get(&policy.name) {
+                Some(subgraph) => policies.extend(expand_subgraph(&policy, subgraph)?),
+                None => policies.push(policy),
+            }
+        }
+        for policy in &mut policies {
+            if let Some(subgraph) = subgraphs.get(&policy.content_source) {
+                policy.content_source = subgraph_output(&policy.content_source, subgraph)?;
+            }
+        }
+        extraction_graph.policies = policies;
+        Ok(extraction_graph)
+    }
+
+    /// The policies and extractors of a graph with its latest version in the
+    /// history of the graph
+    fn extraction_graph_response(
+        &self,
+        graph: ExtractionGraph,
+        indexes: Vec<internal_api::Index>,
+    ) -> Result<CreateExtractionGraphResponse> {
+        let mut extractors = HashMap::new();
+        for policy in &graph.extraction_policies {
+            if !extractors.contains_key(&policy.extractor) {
+                let extractor = self.coordinator.get_extractor(&policy.extractor)?;
+                extractors.insert(policy.extractor.clone(), extractor.into());
+            }
+        }
+        let version = self
+            .coordinator
+            .list_extraction_graph_versions(&graph.namespace, &graph.name)?
+            .last()
+            .map_or(0, |version| version.version);
+        Ok(CreateExtractionGraphResponse {
+            graph_id: graph.id,
+            extractors,
+            policies: graph
+                .extraction_policies
+                .into_iter()
+                .map(|policy| (policy.name.clone(), policy.into()))
+                .collect(),
+            indexes: indexes.into_iter().map(|index| index.into()).collect(),
+            version,
+            warnings: vec![],
+        })
+    }
+
     fn create_extraction_policies_for_graph(
         &self,
         extraction_graph: &CreateExtractionGraphRequest,
@@ -152,12 +637,12 @@ impl CoordinatorServiceServer {
         }
 
         let mut extraction_policies = Vec::new();
-        let mut extractors = Vec::new();
 
         for (_, policy_request) in name_to_policy_mapping.iter() {
             let input_params = serde_json::from_str(&policy_request.input_params)
                 .map_err(|e| anyhow!(format!("unable to parse input_params: {}", e)))?;
             let extractor = self.coordinator.get_extractor(&policy_request.extractor)?;
+            let output_schema = parse_output_schema(&policy_request.output_schema)?;
             let content_source = if policy_request.content_source.eq("") {
                 internal_api::ExtractionPolicyContentSource::Ingestion
             } else {
@@ -174,6 +659,31 @@ impl CoordinatorServiceServer {
                 );
                 return Err(anyhow!(message));
             }
+            if _source.is_empty() && !policy_request.source_output.is_empty() {
+                return Err(anyhow!(
+                    "extraction policy '{}' reads output '{}' without a content source",
+                    policy_request.name,
+                    policy_request.source_output
+                ));
+            }
+            if policy_request.gpu_millis > 1000 {
+                return Err(anyhow!(
+                    "extraction policy '{}' takes {} thousandths of a gpu, tasks can't take more than one gpu",
+                    policy_request.name,
+                    policy_request.gpu_millis
+                ));
+            }
+            if let Some(slo) = policy_request
+                .slo
+                .as_ref()
+                .filter(|slo| slo.latency_target_ppm >= 1_000_000)
+            {
+                return Err(anyhow!(
+                    "extraction policy '{}' has latency target of {} parts per million, it has to be below one million",
+                    policy_request.name,
+                    slo.latency_target_ppm
+                ));
+            }
 
             let policy = ExtractionPolicyBuilder::default()
                 .namespace(policy_request.namespace.clone())
@@ -182,31 +692,112 @@ impl CoordinatorServiceServer {
                 .filters(policy_request.filters.clone())
                 .input_params(input_params)
                 .content_source(content_source)
+                .output_schema(output_schema)
+                .source_output(
+                    Some(policy_request.source_output.clone()).filter(|output| !output.is_empty()),
+                )
+                .max_tasks_per_second(
+                    Some(policy_request.max_tasks_per_second).filter(|max| *max > 0),
+                )
+                .expensive_warmup(policy_request.expensive_warmup)
+                .min_warm_executors(Some(policy_request.min_warm_executors).filter(|min| *min > 0))
+                .gpu_millis(Some(policy_request.gpu_millis).filter(|millis| *millis > 0))
+                .slo(policy_request.slo.clone().map(Into::into))
                 .build(&extraction_graph.name, extractor.clone())
                 .map_err(|e| anyhow!(e))?;
             extraction_policies.push(policy.clone());
-            extractors.push(extractor.clone());
         }
         Ok(ExtractionPolicyCreationResult {
             extraction_policies,
-            extractors,
         })
     }
+
+    /// Checks an extraction graph without creating it. Problems that would
+    /// make creation fail are errors, problems that would only keep tasks
+    /// from running are warnings.
+    async fn validate_extraction_graph_request(
+        &self,
+        extraction_graph: &CreateExtractionGraphRequest,
+    ) -> Vec<ExtractionGraphDiagnostic> {
+        let mut diagnostics = extraction_graph_errors(extraction_graph, |name| {
+            self.coordinator.get_extractor(name)
+        });
+        for policy in &extraction_graph.policies {
+            //  Unknown extractors are already reported as errors
+            if self.coordinator.get_extractor(&policy.extractor).is_err() {
+                continue;
+            }
+            match self
+                .coordinator
+                .get_extractor_coordinates(&policy.extractor)
+                .await
+            {
+                Ok(addresses) if addresses.is_empty() => {
+                    diagnostics.push(ExtractionGraphDiagnostic {
+                        severity: DiagnosticSeverity::Warning as i32,
+                        extraction_policy: policy.name.clone(),
+                        message: format!(
+                            "no executor is running extractor '{}', tasks will wait until one registers",
+                            policy.extractor
+                        ),
+                    })
+                }
+                Ok(_) => {}
+                //  Not a problem of the graph, it isn't reported as one
+                Err(e) => warn!(
+                    "unable to look up the executors of extractor {}: {}",
+                    policy.extractor, e
+                ),
+            }
+        }
+        diagnostics.extend(lint_extraction_graph(extraction_graph));
+        diagnostics
+    }
+}
+
+fn describe_diagnostics(diagnostics: &[ExtractionGraphDiagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|diagnostic| match diagnostic.extraction_policy.as_str() {
+            "" => diagnostic.message.clone(),
+            policy => format!("{}: {}", policy, diagnostic.message),
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
 }
 
 #[tonic::async_trait]
 impl CoordinatorService for CoordinatorServiceServer {
     type GCTasksStreamStream = GCTasksResponseStream;
     type HeartbeatStream = HBResponseStream;
+    type StreamContentStream = ContentResponseStream;
+    type StreamTasksStream = TasksResponseStream;
 
     async fn create_content(
         &self,
         request: tonic::Request<CreateContentRequest>,
     ) -> Result<tonic::Response<CreateContentResponse>, tonic::Status> {
-        let content_meta = request
+        let mut content_meta = request
             .into_inner()
             .content
             .ok_or(tonic::Status::aborted("content is missing"))?;
+        //  Extracted content inherits the labels of its parent, which were
+        //  already passed through the hooks. The labels added by the extractor
+        //  go through them.
+        if content_meta.parent_id.is_empty() {
+            self.content_hooks.apply(&mut content_meta.labels);
+        } else {
+            let inherited = self
+                .coordinator
+                .shared_state
+                .state_machine
+                .get_latest_version_of_content(&content_meta.parent_id)
+                .map_err(|e| tonic::Status::aborted(e.to_string()))?
+                .map(|parent| parent.labels)
+                .unwrap_or_default();
+            self.content_hooks
+                .apply_to_extracted(&mut content_meta.labels, &inherited);
+        }
         let content_meta: indexify_internal_api::ContentMetadata = content_meta.into();
         let content_list = vec![content_meta];
         let statuses = self
@@ -226,7 +817,8 @@ impl CoordinatorService for CoordinatorServiceServer {
         &self,
         request: tonic::Request<indexify_coordinator::UpdateLabelsRequest>,
     ) -> Result<tonic::Response<indexify_coordinator::UpdateLabelsResponse>, tonic::Status> {
-        let request = request.into_inner();
+        let mut request = request.into_inner();
+        self.content_hooks.apply(&mut request.labels);
         self.coordinator
             .update_labels(&request.namespace, &request.content_id, request.labels)
             .await
@@ -236,6 +828,382 @@ impl CoordinatorService for CoordinatorServiceServer {
         ))
     }
 
+    async fn cancel_tasks(
+        &self,
+        request: tonic::Request<indexify_coordinator::CancelTasksRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::CancelTasksResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let filter = CancelTasksFilter {
+            extraction_graph: Some(request.extraction_graph).filter(|g| !g.is_empty()),
+            labels_eq: request.labels_eq,
+            created_after: Some(request.created_after).filter(|t| *t > 0),
+            created_before: Some(request.created_before).filter(|t| *t > 0),
+        };
+        let job_id = self
+            .coordinator
+            .cancel_tasks(&request.namespace, filter)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::CancelTasksResponse { job_id },
+        ))
+    }
+
+    async fn rerun_extraction_policy(
+        &self,
+        request: tonic::Request<indexify_coordinator::RerunExtractionPolicyRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::RerunExtractionPolicyResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let replay_id = self
+            .coordinator
+            .rerun_extraction_policy(
+                &request.namespace,
+                &request.content_id,
+                &request.extraction_graph,
+                &request.extraction_policy,
+                &request.initiator,
+            )
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::RerunExtractionPolicyResponse { replay_id },
+        ))
+    }
+
+    async fn requeue_task(
+        &self,
+        request: tonic::Request<indexify_coordinator::RequeueTaskRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::RequeueTaskResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let replay_id = self
+            .coordinator
+            .requeue_task(&request.namespace, &request.task_id, &request.initiator)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::RequeueTaskResponse { replay_id },
+        ))
+    }
+
+    async fn list_stuck_content(
+        &self,
+        request: tonic::Request<indexify_coordinator::ListStuckContentRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::ListStuckContentResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let stalled_for_secs = request
+            .stalled_for_secs
+            .unwrap_or(self.stuck_content_config.stalled_after_secs);
+        let content = self
+            .coordinator
+            .list_stuck_content(&request.namespace, Duration::from_secs(stalled_for_secs))
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?
+            .into_iter()
+            .map(|c| c.into())
+            .collect();
+        Ok(tonic::Response::new(
+            indexify_coordinator::ListStuckContentResponse { content },
+        ))
+    }
+
+    async fn list_starved_tasks(
+        &self,
+        request: tonic::Request<indexify_coordinator::ListStarvedTasksRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::ListStarvedTasksResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let tasks = self
+            .coordinator
+            .list_starved_tasks(
+                Some(&request.namespace),
+                request.unallocated_for_secs.map(Duration::from_secs),
+            )
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        Ok(tonic::Response::new(
+            indexify_coordinator::ListStarvedTasksResponse { tasks },
+        ))
+    }
+
+    async fn get_task_scheduling(
+        &self,
+        request: tonic::Request<indexify_coordinator::GetTaskSchedulingRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::GetTaskSchedulingResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let attempts = self
+            .coordinator
+            .get_task_scheduling(&request.namespace, &request.task_id)
+            .await
+            .map_err(|e| tonic::Status::not_found(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::GetTaskSchedulingResponse {
+                attempts: attempts.into_iter().map(Into::into).collect(),
+            },
+        ))
+    }
+
+    async fn repair_content(
+        &self,
+        request: tonic::Request<indexify_coordinator::RepairContentRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::RepairContentResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let created_tasks = self
+            .coordinator
+            .repair_content(&request.namespace, &request.content_id)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::RepairContentResponse {
+                created_tasks: created_tasks as u64,
+            },
+        ))
+    }
+
+    async fn rerun_failed_tasks(
+        &self,
+        request: tonic::Request<indexify_coordinator::RerunFailedTasksRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::RerunFailedTasksResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let replay = self
+            .coordinator
+            .rerun_failed_tasks(&request.namespace, &request.content_id, &request.initiator)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        let response = match replay {
+            Some(replay) => indexify_coordinator::RerunFailedTasksResponse {
+                task_ids: replay.tasks.into_iter().map(|task| task.task_id).collect(),
+                replay_id: replay.id,
+            },
+            None => Default::default(),
+        };
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn list_replays(
+        &self,
+        request: tonic::Request<indexify_coordinator::ListReplaysRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::ListReplaysResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let replays = self
+            .coordinator
+            .list_replays(
+                &request.namespace,
+                Some(request.extraction_graph.as_str()).filter(|graph| !graph.is_empty()),
+                Some(request.content_id.as_str()).filter(|id| !id.is_empty()),
+            )
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::ListReplaysResponse {
+                replays: replays.into_iter().map(|replay| replay.into()).collect(),
+            },
+        ))
+    }
+
+    async fn get_replay(
+        &self,
+        request: tonic::Request<indexify_coordinator::GetReplayRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::GetReplayResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let (replay, output_content_ids) = self
+            .coordinator
+            .get_replay(&request.namespace, &request.replay_id)
+            .await
+            .map_err(|e| tonic::Status::not_found(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::GetReplayResponse {
+                replay: Some(replay.into()),
+                output_content_ids,
+            },
+        ))
+    }
+
+    async fn get_invocation_breakdown(
+        &self,
+        request: tonic::Request<indexify_coordinator::GetInvocationBreakdownRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::GetInvocationBreakdownResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let breakdown = self
+            .coordinator
+            .get_invocation_breakdown(&request.namespace, &request.content_id)
+            .await
+            .map_err(|e| tonic::Status::not_found(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::GetInvocationBreakdownResponse {
+                breakdown: Some(breakdown.into()),
+            },
+        ))
+    }
+
+    async fn set_graph_template(
+        &self,
+        request: tonic::Request<indexify_coordinator::SetGraphTemplateRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::SetGraphTemplateResponse>, tonic::Status>
+    {
+        let template: internal_api::GraphTemplate = request
+            .into_inner()
+            .template
+            .ok_or_else(|| tonic::Status::invalid_argument("graph template is required"))?
+            .try_into()
+            .map_err(|e: anyhow::Error| tonic::Status::invalid_argument(e.to_string()))?;
+        let template = self
+            .coordinator
+            .set_graph_template(template)
+            .await
+            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::SetGraphTemplateResponse {
+                template: Some(template.into()),
+            },
+        ))
+    }
+
+    async fn list_graph_templates(
+        &self,
+        _request: tonic::Request<indexify_coordinator::ListGraphTemplatesRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::ListGraphTemplatesResponse>, tonic::Status>
+    {
+        let templates = self
+            .coordinator
+            .list_graph_templates()
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::ListGraphTemplatesResponse {
+                templates: templates.into_iter().map(Into::into).collect(),
+            },
+        ))
+    }
+
+    async fn get_graph_template(
+        &self,
+        request: tonic::Request<indexify_coordinator::GetGraphTemplateRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::GetGraphTemplateResponse>, tonic::Status>
+    {
+        let template = self
+            .coordinator
+            .get_graph_template(&request.into_inner().name)
+            .map_err(|e| tonic::Status::not_found(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::GetGraphTemplateResponse {
+                template: Some(template.into()),
+            },
+        ))
+    }
+
+    async fn delete_graph_template(
+        &self,
+        request: tonic::Request<indexify_coordinator::DeleteGraphTemplateRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::DeleteGraphTemplateResponse>, tonic::Status>
+    {
+        self.coordinator
+            .delete_graph_template(&request.into_inner().name)
+            .await
+            .map_err(|e| tonic::Status::not_found(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::DeleteGraphTemplateResponse {},
+        ))
+    }
+
+    async fn get_content_replica(
+        &self,
+        request: tonic::Request<indexify_coordinator::GetContentReplicaRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::GetContentReplicaResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let replica = self
+            .coordinator
+            .get_content_replica(&request.namespace, &request.content_id)
+            .map_err(|e| tonic::Status::not_found(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::GetContentReplicaResponse {
+                replica: Some(replica.into()),
+            },
+        ))
+    }
+
+    async fn get_content_context(
+        &self,
+        request: tonic::Request<indexify_coordinator::GetContentContextRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::GetContentContextResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let context = self
+            .coordinator
+            .get_content_context(&request.namespace, &request.content_id)
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?
+            .into_iter()
+            .map(|(key, value)| (key, value.to_string()))
+            .collect();
+        Ok(tonic::Response::new(
+            indexify_coordinator::GetContentContextResponse { context },
+        ))
+    }
+
+    async fn set_content_context(
+        &self,
+        request: tonic::Request<indexify_coordinator::SetContentContextRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::SetContentContextResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let value = request
+            .value
+            .map(|value| serde_json::from_str(&value))
+            .transpose()
+            .map_err(|e| {
+                tonic::Status::invalid_argument(format!("invalid context value: {}", e))
+            })?;
+        self.coordinator
+            .set_content_context(&request.namespace, &request.content_id, &request.key, value)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::SetContentContextResponse {},
+        ))
+    }
+
+    async fn get_cancel_tasks_job(
+        &self,
+        request: tonic::Request<indexify_coordinator::GetCancelTasksJobRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::GetCancelTasksJobResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let job = self
+            .coordinator
+            .get_cancel_tasks_job(&request.job_id)
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?
+            .ok_or_else(|| {
+                tonic::Status::not_found(format!("cancel tasks job {} not found", request.job_id))
+            })?;
+        let state = match job.state {
+            internal_api::CancelTasksJobState::Running => {
+                indexify_coordinator::CancelTasksJobState::Running
+            }
+            internal_api::CancelTasksJobState::Completed => {
+                indexify_coordinator::CancelTasksJobState::Completed
+            }
+            internal_api::CancelTasksJobState::Aborted => {
+                indexify_coordinator::CancelTasksJobState::Aborted
+            }
+        };
+        Ok(tonic::Response::new(
+            indexify_coordinator::GetCancelTasksJobResponse {
+                job_id: job.id,
+                namespace: job.namespace,
+                state: state as i32,
+                matched_tasks: job.matched_tasks,
+                cancelled_tasks: job.cancelled_tasks,
+                error: job.error.unwrap_or_default(),
+            },
+        ))
+    }
+
     async fn tombstone_content(
         &self,
         request: tonic::Request<TombstoneContentRequest>,
@@ -265,6 +1233,22 @@ impl CoordinatorService for CoordinatorServiceServer {
         Ok(tonic::Response::new(ListContentResponse { content_list }))
     }
 
+    async fn stream_content(
+        &self,
+        request: tonic::Request<ListContentRequest>,
+    ) -> Result<tonic::Response<Self::StreamContentStream>, tonic::Status> {
+        let req = request.into_inner();
+        let cursor = (!req.cursor.is_empty()).then_some(req.cursor);
+        let content = self.coordinator.stream_content(
+            &req.namespace,
+            &req.source,
+            &req.parent_id,
+            req.labels_eq,
+            cursor.clone(),
+        );
+        Ok(tonic::Response::new(Box::pin(stream_rows(content, cursor))))
+    }
+
     async fn list_active_contents(
         &self,
         request: tonic::Request<ListActiveContentsRequest>,
@@ -283,11 +1267,52 @@ impl CoordinatorService for CoordinatorServiceServer {
         }))
     }
 
+    async fn validate_extraction_graph(
+        &self,
+        request: tonic::Request<CreateExtractionGraphRequest>,
+    ) -> Result<tonic::Response<ValidateExtractionGraphResponse>, tonic::Status> {
+        let request = match self.expand_subgraphs(request.into_inner()) {
+            Ok(request) => request,
+            Err(e) => {
+                return Ok(tonic::Response::new(ValidateExtractionGraphResponse {
+                    diagnostics: vec![ExtractionGraphDiagnostic {
+                        severity: DiagnosticSeverity::Error as i32,
+                        extraction_policy: String::new(),
+                        message: e.to_string(),
+                    }],
+                }))
+            }
+        };
+        let diagnostics = self.validate_extraction_graph_request(&request).await;
+        Ok(tonic::Response::new(ValidateExtractionGraphResponse {
+            diagnostics,
+        }))
+    }
+
     async fn create_extraction_graph(
         &self,
         request: tonic::Request<CreateExtractionGraphRequest>,
     ) -> Result<tonic::Response<CreateExtractionGraphResponse>, tonic::Status> {
-        let request = request.into_inner();
+        let request = self
+            .expand_subgraphs(request.into_inner())
+            .map_err(|e| tonic::Status::aborted(format!("unable to expand subgraphs: {}", e)))?;
+        let (errors, warnings): (Vec<_>, Vec<_>) = self
+            .validate_extraction_graph_request(&request)
+            .await
+            .into_iter()
+            .partition(|diagnostic| diagnostic.severity() == DiagnosticSeverity::Error);
+        if !errors.is_empty() {
+            return Err(tonic::Status::invalid_argument(format!(
+                "extraction graph is invalid: {}",
+                describe_diagnostics(&errors)
+            )));
+        }
+        if request.strict && !warnings.is_empty() {
+            return Err(tonic::Status::failed_precondition(format!(
+                "extraction graph has warnings: {}",
+                describe_diagnostics(&warnings)
+            )));
+        }
         let graph_id = ExtractionGraph::create_id(&request.name, &request.namespace);
         let creation_result = self
             .create_extraction_policies_for_graph(&request)
@@ -299,34 +1324,151 @@ impl CoordinatorService for CoordinatorServiceServer {
             .namespace(request.namespace.clone())
             .name(request.name.clone())
             .extraction_policies(creation_result.extraction_policies.clone())
+            .max_active_content(Some(request.max_active_content).filter(|max| *max > 0))
+            .latency_tolerant(request.latency_tolerant)
+            .zone_placement(request.zone_placement.parse().unwrap_or_default())
             .build()
             .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        let change = internal_api::ExtractionGraphChange {
+            author: request.author,
+            summary: request.change_summary,
+            ..Default::default()
+        };
         let indexes = self
             .coordinator
-            .create_extraction_graph(graph.clone())
+            .save_extraction_graph(graph.clone(), change, request.replace)
             .await
             .map_err(|e| tonic::Status::aborted(e.to_string()))?;
-        let policies = creation_result
-            .extraction_policies
-            .clone()
-            .into_iter()
-            .map(|p| (p.name.clone(), p.clone().into()))
-            .collect();
-        let extractors: HashMap<_, _> = creation_result
-            .extractors
-            .iter()
-            .map(|extractor| (extractor.name.clone(), extractor.clone().into()))
-            .collect();
-        let indexes = indexes
+        let mut response = self
+            .extraction_graph_response(graph, indexes)
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        response.warnings = warnings;
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn list_extraction_graph_versions(
+        &self,
+        request: tonic::Request<indexify_coordinator::ListExtractionGraphVersionsRequest>,
+    ) -> Result<
+        tonic::Response<indexify_coordinator::ListExtractionGraphVersionsResponse>,
+        tonic::Status,
+    > {
+        let request = request.into_inner();
+        let versions = self
+            .coordinator
+            .list_extraction_graph_versions(&request.namespace, &request.extraction_graph)
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        if versions.is_empty() {
+            return Err(tonic::Status::not_found(format!(
+                "extraction graph {} not found",
+                request.extraction_graph
+            )));
+        }
+        Ok(tonic::Response::new(
+            indexify_coordinator::ListExtractionGraphVersionsResponse {
+                versions: versions.into_iter().map(|version| version.into()).collect(),
+            },
+        ))
+    }
+
+    async fn get_extraction_graph_usage(
+        &self,
+        request: tonic::Request<indexify_coordinator::GetExtractionGraphUsageRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::GetExtractionGraphUsageResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let usage = self
+            .coordinator
+            .get_extraction_graph_usage(&request.namespace, &request.extraction_graph)
+            .map_err(|e| tonic::Status::not_found(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::GetExtractionGraphUsageResponse {
+                usage: usage.into_iter().map(Into::into).collect(),
+            },
+        ))
+    }
+
+    async fn get_slo_compliance(
+        &self,
+        request: tonic::Request<indexify_coordinator::GetSloComplianceRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::GetSloComplianceResponse>, tonic::Status>
+    {
+        let request = request.into_inner();
+        let compliance = self
+            .coordinator
+            .get_slo_compliance(&request.namespace, &request.extraction_graph)
+            .map_err(|e| tonic::Status::not_found(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::GetSloComplianceResponse {
+                compliance: compliance.into_iter().map(Into::into).collect(),
+            },
+        ))
+    }
+
+    async fn export_namespace(
+        &self,
+        request: tonic::Request<indexify_coordinator::ExportNamespaceRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::ExportNamespaceResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let archive = self
+            .coordinator
+            .export_namespace(&request.namespace)
+            .await
+            .map_err(|e| tonic::Status::not_found(e.to_string()))?;
+        let archive =
+            serde_json::to_vec(&archive).map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::ExportNamespaceResponse { archive },
+        ))
+    }
+
+    async fn import_namespace(
+        &self,
+        request: tonic::Request<indexify_coordinator::ImportNamespaceRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::ImportNamespaceResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let archive = serde_json::from_slice(&request.archive)
+            .map_err(|e| tonic::Status::invalid_argument(format!("invalid archive: {}", e)))?;
+        let (report, graphs) = self
+            .coordinator
+            .import_namespace(&request.namespace, archive)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        let graphs = graphs
             .into_iter()
-            .map(|index| index.into())
-            .collect::<Vec<indexify_coordinator::Index>>();
-        Ok(tonic::Response::new(CreateExtractionGraphResponse {
-            graph_id: graph.id,
-            extractors,
-            policies,
-            indexes,
-        }))
+            .map(|(graph, indexes)| self.extraction_graph_response(graph, indexes))
+            .collect::<Result<_>>()
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::ImportNamespaceResponse {
+                extraction_graphs: report.extraction_graphs as u64,
+                extraction_graph_versions: report.extraction_graph_versions as u64,
+                content: report.content as u64,
+                content_ids: report.content_ids,
+                graphs,
+            },
+        ))
+    }
+
+    async fn rollback_extraction_graph(
+        &self,
+        request: tonic::Request<indexify_coordinator::RollbackExtractionGraphRequest>,
+    ) -> Result<tonic::Response<CreateExtractionGraphResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let (graph, indexes) = self
+            .coordinator
+            .rollback_extraction_graph(
+                &request.namespace,
+                &request.extraction_graph,
+                request.version,
+                &request.author,
+            )
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        let response = self
+            .extraction_graph_response(graph, indexes)
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(response))
     }
 
     async fn get_extraction_policy(
@@ -426,6 +1568,20 @@ impl CoordinatorService for CoordinatorServiceServer {
         Ok(tonic::Response::new(ListExtractorsResponse { extractors }))
     }
 
+    async fn list_executors(
+        &self,
+        _request: tonic::Request<indexify_coordinator::ListExecutorsRequest>,
+    ) -> Result<tonic::Response<indexify_coordinator::ListExecutorsResponse>, tonic::Status> {
+        let executors = self
+            .coordinator
+            .list_executors()
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(tonic::Response::new(
+            indexify_coordinator::ListExecutorsResponse { executors },
+        ))
+    }
+
     // TODO: edwin
     async fn register_executor(
         &self,
@@ -441,7 +1597,21 @@ impl CoordinatorService for CoordinatorServiceServer {
 
         let _resp = self
             .coordinator
-            .register_executor(&request.addr, &request.executor_id, extractors)
+            .register_executor(
+                &request.addr,
+                &request.executor_id,
+                extractors,
+                internal_api::ExecutorProfile {
+                    cost_per_hour: Some(request.cost_per_hour).filter(|cost| *cost > 0.0),
+                    capacity_weight: Some(request.capacity_weight).filter(|weight| *weight > 0),
+                    zone: Some(request.zone).filter(|zone| !zone.is_empty()),
+                    max_payload_bytes: Some(request.max_payload_bytes).filter(|max| *max > 0),
+                    download_bytes_per_sec: Some(request.download_bytes_per_sec)
+                        .filter(|bandwidth| *bandwidth > 0),
+                    gpu_devices: request.gpu_device_millis,
+                    graph_versions: request.graph_versions.into_iter().map(Into::into).collect(),
+                },
+            )
             .await
             .map_err(|e| tonic::Status::aborted(e.to_string()))?;
 
@@ -450,6 +1620,19 @@ impl CoordinatorService for CoordinatorServiceServer {
         }))
     }
 
+    async fn deregister_executor(
+        &self,
+        request: tonic::Request<DeregisterExecutorRequest>,
+    ) -> Result<tonic::Response<DeregisterExecutorResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let response = self
+            .coordinator
+            .deregister_executor(&request.executor_id, request.drain)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(tonic::Response::new(response))
+    }
+
     async fn register_ingestion_server(
         &self,
         request: tonic::Request<RegisterIngestionServerRequest>,
@@ -603,47 +1786,105 @@ impl CoordinatorService for CoordinatorServiceServer {
         let rx = DropReceiver { inner: rx };
         let coordinator = self.coordinator.clone();
         let mut shutdown_rx = self.shutdown_rx.clone();
+        let chaos = self.chaos.clone();
         tokio::spawn(async move {
             let mut executor_id: Option<String> = None;
+            let mut lease_renewed_at: Option<Instant> = None;
+            let mut reported_progress = HashMap::new();
+            //  Unset until the first report of the stream is stored, so a
+            //  pressure stored before the executor reconnected is overwritten
+            let mut reported_pressure = None;
+            let mut bundler = TaskBundler::default();
             loop {
+                let next_flush = bundler.next_flush();
                 select! {
                     _ = shutdown_rx.changed() => {
                         info!("shutting down server, stopping heartbeats from executor: {:?}", executor_id);
                         break;
                     }
+                    // Bundles that aren't full are sent at their deadline rather than with
+                    // the next heartbeat
+                    _ = async {
+                        match next_flush {
+                            Some(at) => tokio::time::sleep_until(at.into()).await,
+                            None => std::future::pending().await,
+                        }
+                    } => {
+                        if let Some(executor_id) = executor_id.as_deref() {
+                            if bundler.flush(Instant::now()) {
+                                let resp = bundler.dispatch().into_response(executor_id);
+                                if let Err(err) = tx.send(Ok(resp)).await {
+                                    error!("error sending heartbeat response: {:?}", err);
+                                    break;
+                                }
+                            }
+                        }
+                    }
                     result = timeout(EXECUTOR_HEARTBEAT_PERIOD * 3, in_stream.next()) => {
                         match result {
                             Ok(frame) => {
                         // Ensure the frame has something
-                        if frame.as_ref().is_none() {
-                            break;
-                        }
-                        if let Err(err) = frame.as_ref().unwrap() {
-                            info!("error receiving heartbeat request: {:?}", err);
-                            break;
-                        }
+                        let hb_request = match frame {
+                            Some(Ok(hb_request)) => hb_request,
+                            Some(Err(err)) => {
+                                info!("error receiving heartbeat request: {:?}", err);
+                                break;
+                            }
+                            None => break,
+                        };
                         // We could have used Option<> here but it would be inconvenient to dereference
                         // it every time we need to use it below
                         if executor_id.is_none() {
-                            if let Some(Ok(hb_request)) = frame {
-                                executor_id.replace(hb_request.executor_id.clone());
-                            }
+                            executor_id.replace(hb_request.executor_id.clone());
+                        }
+                        if chaos.drop_heartbeat() {
+                            warn!("chaos: dropping heartbeat of executor {:?}", executor_id);
+                            continue;
                         }
                         if let Some(executor_id) = executor_id.clone() {
-                            let tasks = coordinator.heartbeat(&executor_id).await;
-                            match tasks {
+                            if lease_renewed_at.map_or(true, |at| at.elapsed() >= EXECUTOR_LEASE_RENEW_PERIOD) {
+                                match coordinator.renew_executor_lease(&executor_id).await {
+                                    Ok(()) => lease_renewed_at = Some(Instant::now()),
+                                    Err(err) => warn!("error renewing lease of executor {}: {}", executor_id, err),
+                                }
+                            }
+                            // Executors report every task they run, only progress that moved
+                            // since the last heartbeat is written
+                            let latest_progress: HashMap<_, _> = hb_request
+                                .task_progress
+                                .iter()
+                                .map(|update| (update.task_id.clone(), update.progress.clone()))
+                                .collect();
+                            let task_progress: Vec<_> = hb_request
+                                .task_progress
+                                .into_iter()
+                                .filter(|update| reported_progress.get(&update.task_id) != Some(&update.progress))
+                                .collect();
+                            match coordinator.update_task_progress(&executor_id, task_progress).await {
+                                Ok(()) => reported_progress = latest_progress,
+                                Err(err) => warn!("error storing task progress of executor {}: {}", executor_id, err),
+                            }
+                            // Executors report their pressure in every heartbeat, it is
+                            // written when it changes
+                            let pressure = hb_request
+                                .pressure
+                                .filter(|pressure| pressure.level() != indexify_coordinator::PressureLevel::Normal)
+                                .map(|pressure| indexify_coordinator::ExecutorPressure { since: 0, ..pressure });
+                            if reported_pressure.as_ref() != Some(&pressure) {
+                                match coordinator.set_executor_pressure(&executor_id, pressure.clone()).await {
+                                    Ok(()) => reported_pressure = Some(pressure),
+                                    Err(err) => warn!("error storing pressure of executor {}: {}", executor_id, err),
+                                }
+                            }
+                            let resp = coordinator.heartbeat(&executor_id, &mut bundler).await;
+                            match resp {
                                 Err(err) => {
                                     if let Err(err) = tx.send(Err(tonic::Status::internal(err.to_string()))).await {
                                         error!("error sending error message in heartbeat response: {}",err);
                                         break;
                                     }
                                 }
-                                Ok(tasks) => {
-                                    // let tasks = tasks.into_iter().map(|t| t.into()).collect::<Vec<indexify_coordinator::Task>>();
-                                    let resp = HeartbeatResponse {
-                                        executor_id: executor_id.clone(),
-                                        tasks,
-                                    };
+                                Ok(resp) => {
                                     if let Err(err) = tx.send(Ok(resp)).await {
                                         error!("error sending heartbeat response: {:?}", err);
                                         break;
@@ -678,12 +1919,33 @@ impl CoordinatorService for CoordinatorServiceServer {
         let outcome: internal_api::TaskOutcome = request.outcome().into();
         let _ = self
             .coordinator
-            .update_task(&request.task_id, &request.executor_id, outcome)
+            .update_task(
+                &request.task_id,
+                &request.executor_id,
+                outcome,
+                Some(request.diagnostics).filter(|d| !d.is_empty()),
+                request.usage.map(Into::into),
+            )
             .await
             .map_err(|e| tonic::Status::aborted(e.to_string()))?;
         Ok(tonic::Response::new(UpdateTaskResponse {}))
     }
 
+    async fn update_tasks(
+        &self,
+        request: tonic::Request<UpdateTasksRequest>,
+    ) -> Result<tonic::Response<UpdateTasksResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let rejected_task_ids = self
+            .coordinator
+            .update_tasks(&request.executor_id, request.tasks)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(tonic::Response::new(UpdateTasksResponse {
+            rejected_task_ids,
+        }))
+    }
+
     async fn list_indexes(
         &self,
         request: Request<ListIndexesRequest>,
@@ -781,7 +2043,14 @@ impl CoordinatorService for CoordinatorServiceServer {
             .get_task(&req.task_id)
             .await
             .map_err(|e| tonic::Status::aborted(e.to_string()))?;
-        Ok(Response::new(GetTaskResponse { task: Some(task) }))
+        let transitions = self
+            .coordinator
+            .get_task_transitions(&req.task_id)
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(GetTaskResponse {
+            task: Some(task),
+            transitions: transitions.into_iter().map(Into::into).collect(),
+        }))
     }
 
     async fn get_ingestion_info(
@@ -860,6 +2129,56 @@ impl CoordinatorService for CoordinatorServiceServer {
         }))
     }
 
+    async fn stream_tasks(
+        &self,
+        req: Request<ListTasksRequest>,
+    ) -> Result<Response<Self::StreamTasksStream>, Status> {
+        let req = req.into_inner();
+        let extraction_policy = if req.extraction_policy.is_empty() {
+            None
+        } else {
+            Some(req.extraction_policy)
+        };
+        let cursor = (!req.cursor.is_empty()).then_some(req.cursor);
+        let tasks =
+            self.coordinator
+                .stream_tasks(&req.namespace, extraction_policy, cursor.clone());
+        Ok(Response::new(Box::pin(stream_rows(tasks, cursor))))
+    }
+
+    async fn list_task_queue_depths(
+        &self,
+        req: Request<indexify_coordinator::ListTaskQueueDepthsRequest>,
+    ) -> Result<Response<indexify_coordinator::ListTaskQueueDepthsResponse>, Status> {
+        let req = req.into_inner();
+        let queue_depths = self
+            .coordinator
+            .list_task_queue_depths(&req.namespace)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(
+            indexify_coordinator::ListTaskQueueDepthsResponse { queue_depths },
+        ))
+    }
+
+    async fn list_executor_tasks(
+        &self,
+        req: Request<indexify_coordinator::ListExecutorTasksRequest>,
+    ) -> Result<Response<ListTasksResponse>, Status> {
+        let req = req.into_inner();
+        let tasks = self
+            .coordinator
+            .list_executor_tasks(&req.executor_id)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?
+            .ok_or_else(|| {
+                tonic::Status::not_found(format!("executor {} not found", req.executor_id))
+            })?;
+        Ok(Response::new(indexify_coordinator::ListTasksResponse {
+            tasks,
+        }))
+    }
+
     async fn get_schema(
         &self,
         req: Request<GetSchemaRequest>,
@@ -985,6 +2304,117 @@ impl CoordinatorService for CoordinatorServiceServer {
             .await;
         Ok(Response::new(WaitContentExtractionResponse {}))
     }
+
+    async fn get_scheduler_settings(
+        &self,
+        _req: Request<indexify_coordinator::GetSchedulerSettingsRequest>,
+    ) -> Result<Response<indexify_coordinator::GetSchedulerSettingsResponse>, Status> {
+        let settings = self
+            .coordinator
+            .get_scheduler_settings()
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(Response::new(
+            indexify_coordinator::GetSchedulerSettingsResponse {
+                settings: Some(settings.into()),
+            },
+        ))
+    }
+
+    async fn set_scheduler_settings(
+        &self,
+        req: Request<indexify_coordinator::SetSchedulerSettingsRequest>,
+    ) -> Result<Response<indexify_coordinator::SetSchedulerSettingsResponse>, Status> {
+        let settings: internal_api::SchedulerSettings = req
+            .into_inner()
+            .settings
+            .unwrap_or_default()
+            .try_into()
+            .map_err(|e: anyhow::Error| tonic::Status::invalid_argument(e.to_string()))?;
+        let settings = self
+            .coordinator
+            .set_scheduler_settings(settings)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(
+            indexify_coordinator::SetSchedulerSettingsResponse {
+                settings: Some(settings.into()),
+            },
+        ))
+    }
+
+    async fn simulate_scheduling(
+        &self,
+        req: Request<indexify_coordinator::SimulateSchedulingRequest>,
+    ) -> Result<Response<indexify_coordinator::SimulateSchedulingResponse>, Status> {
+        let extractors = self
+            .coordinator
+            .simulate_scheduling(req.into_inner())
+            .await
+            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+        Ok(Response::new(
+            indexify_coordinator::SimulateSchedulingResponse { extractors },
+        ))
+    }
+
+    async fn get_maintenance_mode(
+        &self,
+        _req: Request<indexify_coordinator::GetMaintenanceModeRequest>,
+    ) -> Result<Response<indexify_coordinator::GetMaintenanceModeResponse>, Status> {
+        let maintenance = self
+            .coordinator
+            .get_maintenance_mode()
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(Response::new(
+            indexify_coordinator::GetMaintenanceModeResponse {
+                maintenance: Some(maintenance.into()),
+            },
+        ))
+    }
+
+    async fn set_maintenance_mode(
+        &self,
+        req: Request<indexify_coordinator::SetMaintenanceModeRequest>,
+    ) -> Result<Response<indexify_coordinator::SetMaintenanceModeResponse>, Status> {
+        let req = req.into_inner();
+        let maintenance = self
+            .coordinator
+            .set_maintenance_mode(req.enabled, &req.reason)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(
+            indexify_coordinator::SetMaintenanceModeResponse {
+                maintenance: Some(maintenance.into()),
+            },
+        ))
+    }
+
+    async fn record_egress(
+        &self,
+        req: Request<indexify_coordinator::RecordEgressRequest>,
+    ) -> Result<Response<indexify_coordinator::RecordEgressResponse>, Status> {
+        let req = req.into_inner();
+        self.coordinator
+            .record_egress(&req.namespace, req.bytes)
+            .await
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(indexify_coordinator::RecordEgressResponse {}))
+    }
+
+    async fn list_metering_records(
+        &self,
+        req: Request<indexify_coordinator::ListMeteringRecordsRequest>,
+    ) -> Result<Response<indexify_coordinator::ListMeteringRecordsResponse>, Status> {
+        let req = req.into_inner();
+        let records = self
+            .coordinator
+            .list_metering_records(&req.namespace, req.start, req.end)
+            .map_err(|e| tonic::Status::aborted(e.to_string()))?;
+        Ok(Response::new(
+            indexify_coordinator::ListMeteringRecordsResponse {
+                records: records.into_iter().map(Into::into).collect(),
+            },
+        ))
+    }
 }
 
 pub struct CoordinatorServer {
@@ -1137,20 +2567,47 @@ where
 }
 
 fn start_server(app: &CoordinatorServer) -> Result<JoinHandle<Result<()>>> {
-    let server = axum::Router::new()
+    let mut server = axum::Router::new()
         .route("/metrics", get(metrics_handler))
         .with_state(app.shared_state.clone());
-    let addr: SocketAddr = format!(
-        "{}:{}",
-        app.config.listen_if, app.config.coordinator_http_port
-    )
-    .parse()?;
+    if app.config.profiling.enabled {
+        //  Profiles are expensive to take and show the memory of the process,
+        //  so they need an admin token and are only served to the networks of
+        //  the admin api
+        if app.config.oidc.is_none() && app.config.api_keys.is_empty() {
+            warn!("not serving profiling endpoints on the coordinator http port, as api authentication is not configured");
+        } else {
+            info!("serving profiling endpoints on the coordinator http port");
+            let authenticator = Arc::new(Authenticator::new(
+                app.config.oidc.clone(),
+                &app.config.api_keys,
+            ));
+            crate::secrets::on_refresh({
+                let authenticator = authenticator.clone();
+                move |config| authenticator.set_api_keys(&config.api_keys)
+            });
+            let profiling = profiling::routes(&app.config.profiling)
+                .layer(middleware::from_fn_with_state(
+                    authenticator,
+                    authenticate_request,
+                ))
+                .layer(middleware::from_fn_with_state(
+                    Arc::new(AdminRoutePolicy::new(
+                        &app.config.admin_api,
+                        Listener::Admin,
+                    )?),
+                    restrict_admin_routes,
+                ));
+            server = server.merge(profiling);
+        }
+    }
+    let addr: SocketAddr = app.config.coordinator_http_addr_sock()?;
     let handle = app.server_handle.clone();
 
     Ok(tokio::spawn(async move {
         axum_server::bind(addr)
             .handle(handle)
-            .serve(server.into_make_service())
+            .serve(server.into_make_service_with_connect_info::<SocketAddr>())
             .await?;
         Ok(())
     }))
@@ -1162,6 +2619,10 @@ impl CoordinatorServer {
         registry: Arc<prometheus::Registry>,
     ) -> Result<Self, anyhow::Error> {
         let addr: SocketAddr = config.coordinator_lis_addr_sock()?;
+        preflight::check_coordinator(&config).await?;
+        if config.chaos.is_some() {
+            warn!("chaos injection is enabled, faults will be injected into this coordinator");
+        }
         let garbage_collector = GarbageCollector::new();
         let shared_state = state::App::new(
             config.clone(),
@@ -1172,11 +2633,19 @@ impl CoordinatorServer {
         )
         .await?;
         let coordinator_client = CoordinatorClient::new(Arc::clone(&config));
+        let content_archiver = config.content_archive.enabled.then(|| {
+            ContentArchiver::new(
+                &config.content_archive,
+                Arc::new(BlobStorage::new_with_config(config.blob_storage.clone())),
+            )
+        });
 
         let coordinator = Coordinator::new(
             shared_state.clone(),
             coordinator_client,
             Arc::clone(&garbage_collector),
+            content_archiver,
+            config.scheduler.clone(),
         );
         info!("coordinator listening on: {}", addr.to_string());
         Ok(Self {
@@ -1193,6 +2662,9 @@ impl CoordinatorServer {
         let svc = CoordinatorServiceServer {
             coordinator: self.coordinator.clone(),
             shutdown_rx: shutdown_rx.clone(),
+            stuck_content_config: self.config.stuck_content.clone(),
+            content_hooks: Arc::new(ContentHooks::from_config(&self.config.content_hooks)?),
+            chaos: Arc::new(ChaosInjector::new(self.config.chaos.clone())),
         };
         let srvr =
             indexify_coordinator::coordinator_service_server::CoordinatorServiceServer::new(svc)
@@ -1200,30 +2672,41 @@ impl CoordinatorServer {
                 .max_encoding_message_size(GrpcConfig::MAX_ENCODING_SIZE);
 
         let shared_state = self.shared_state.clone();
-        shared_state
-            .initialize_raft()
-            .await
-            .map_err(|e| anyhow!("unable to initialize shared state: {}", e.to_string()))?;
+        if !self.config.read_only {
+            shared_state
+                .initialize_raft()
+                .await
+                .map_err(|e| anyhow!("unable to initialize shared state: {}", e.to_string()))?;
+        }
         let leader_change_watcher = self.coordinator.get_leader_change_watcher();
         let coordinator_clone = self.coordinator.clone();
-        let state_watcher_rx = self.coordinator.get_state_watcher();
+        let state_watcher_rx = self.coordinator.subscribe_to_state_changes("scheduler");
+        let config = self.config.clone();
         if let Err(e) = start_server(self) {
             error!("unable to start metrics server: {}", e);
         }
-        tokio::spawn(async move {
-            let _ = run_scheduler(
-                shutdown_rx,
-                leader_change_watcher,
-                state_watcher_rx,
-                coordinator_clone,
-            )
-            .await;
-        });
+        if self.config.read_only {
+            info!("coordinator is read only, the scheduler is stopped");
+        } else {
+            tokio::spawn(async move {
+                let _ = run_scheduler(
+                    shutdown_rx,
+                    leader_change_watcher,
+                    state_watcher_rx,
+                    coordinator_clone,
+                    config,
+                )
+                .await;
+            });
+        }
 
         let layer = ServiceBuilder::new()
             .layer(TraceLayer {
                 name: "indexify-coordinator-grpc",
             })
+            .layer(ReadOnlyLayer {
+                enabled: self.config.read_only,
+            })
             .into_inner();
 
         if let Some(tls_config) = self.config.coordinator_tls.as_ref() {
@@ -1297,10 +2780,86 @@ impl CoordinatorServer {
 async fn run_scheduler(
     mut shutdown_rx: Receiver<()>,
     mut leader_changed: Receiver<bool>,
-    mut state_watcher_rx: Receiver<StateChange>,
+    mut state_watcher_rx: ChangeWatcher<StateChange>,
     coordinator: Arc<Coordinator>,
+    config: Arc<ServerConfig>,
 ) -> Result<()> {
     let is_leader = AtomicBool::new(false);
+    // Executors get a full lease period to reconnect to a new leader before
+    // their leases are checked
+    let mut leader_since: Option<Instant> = None;
+    let mut lease_check = tokio::time::interval(EXECUTOR_LEASE_CHECK_PERIOD);
+    let mut orphaned_assignment_reap = tokio::time::interval(ORPHANED_ASSIGNMENT_REAP_PERIOD);
+    let mut burst_task_shedding = tokio::time::interval(BURST_TASK_SHEDDING_PERIOD);
+    let mut stuck_content_repair = tokio::time::interval(STUCK_CONTENT_REPAIR_PERIOD);
+    let mut task_expiry = tokio::time::interval(TASK_EXPIRY_PERIOD);
+    let mut cancel_tasks_job_eviction = tokio::time::interval(CANCEL_TASKS_JOB_EVICTION_PERIOD);
+    let mut deleted_state_compaction = tokio::time::interval(DELETED_STATE_COMPACTION_PERIOD);
+    let mut starved_task_check = tokio::time::interval(STARVED_TASK_CHECK_PERIOD);
+    let mut slo_check = tokio::time::interval(SLO_CHECK_PERIOD);
+    let mut throttled_task_allocation = tokio::time::interval(THROTTLED_TASK_ALLOCATION_PERIOD);
+    // The leader checks on every tick of the scheduler whether content queued
+    // behind the cap of active content of its graph can be admitted
+    let mut queued_content_admission = tokio::time::interval(coordinator.scheduler_tick_interval());
+    let stalled_after = Duration::from_secs(config.stuck_content.stalled_after_secs);
+    let mut metering_pusher = config
+        .metering
+        .push_url
+        .as_deref()
+        .map(|push_url| MeteringPusher::new(push_url, config.metering.push_secret.as_deref()));
+    let mut metering_push = tokio::time::interval(Duration::from_secs(
+        config.metering.push_interval_secs.max(1),
+    ));
+    let mut alerter = (!config.alerting.rules.is_empty()).then(|| Alerter::new(&config.alerting));
+    if let Some(alerter) = &alerter {
+        alerter.apply_refreshed_secrets();
+    }
+    let mut alert_check = tokio::time::interval(Duration::from_secs(
+        config.alerting.check_interval_secs.max(1),
+    ));
+    let mut diagnostics_prune = tokio::time::interval(Duration::from_secs(
+        config.diagnostics.prune_interval_secs.max(1),
+    ));
+    let replicator = config
+        .replication
+        .as_ref()
+        .map(|replication| Arc::new(Replicator::new(&config, replication)));
+    let mut replication = tokio::time::interval(Duration::from_secs(
+        config
+            .replication
+            .as_ref()
+            .map_or(1, |replication| replication.interval_secs.max(1)),
+    ));
+    let mut chaos_task_kill = tokio::time::interval(Duration::from_secs(
+        config
+            .chaos
+            .as_ref()
+            .map_or(1, |chaos| chaos.task_kill_interval_secs.max(1)),
+    ));
+    let mut state_backup = tokio::time::interval(Duration::from_secs(
+        config
+            .state_store
+            .backup
+            .as_ref()
+            .map_or(1, |backup| backup.interval_secs.max(1)),
+    ));
+    // The first tick of an interval is immediate, backups start one interval
+    // after the server
+    state_backup.reset();
+    let state_archiver = config.state_store.archive.as_ref().map(|archive| {
+        Arc::new(StateArchiver::new(
+            archive,
+            Arc::new(BlobStorage::new_with_config(config.blob_storage.clone())),
+        ))
+    });
+    let mut state_archive = tokio::time::interval(Duration::from_secs(
+        config
+            .state_store
+            .archive
+            .as_ref()
+            .map_or(1, |archive| archive.interval_secs.max(1)),
+    ));
+    let chaos = ChaosInjector::new(config.chaos.clone());
 
     loop {
         tokio::select! {
@@ -1320,6 +2879,165 @@ async fn run_scheduler(
                 let leader_state = *leader_changed.borrow_and_update();
                 info!("leader changed detected: {:?}", leader_state);
                 is_leader.store(leader_state, std::sync::atomic::Ordering::Relaxed);
+                leader_since = leader_state.then(Instant::now);
+                // A new leader takes over the state changes the previous leader
+                // left unprocessed instead of waiting for the next state change
+                if leader_state {
+                    if let Err(err) = coordinator.run_scheduler().await {
+                        error!("error processing state changes after becoming leader: {:?}", err);
+                    }
+                }
+            }
+            _ = lease_check.tick() => {
+                if leader_since.map_or(false, |since| since.elapsed() >= EXECUTOR_LEASE_TTL) {
+                    if let Err(err) = coordinator.remove_expired_executors(timestamp_secs(), EXECUTOR_LEASE_TTL).await {
+                        error!("error removing executors with expired leases: {:?}", err);
+                    }
+                }
+            }
+            _ = orphaned_assignment_reap.tick() => {
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Err(err) = coordinator.reap_orphaned_assignments(timestamp_secs()).await {
+                        error!("error reaping orphaned task assignments: {:?}", err);
+                    }
+                }
+            }
+            _ = burst_task_shedding.tick() => {
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Err(err) = coordinator.shed_burst_tasks().await {
+                        error!("error shedding burst tasks: {:?}", err);
+                    }
+                }
+            }
+            _ = stuck_content_repair.tick(), if config.stuck_content.auto_repair => {
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Err(err) = coordinator.repair_stuck_content(stalled_after).await {
+                        error!("error repairing stuck content: {:?}", err);
+                    }
+                }
+            }
+            _ = task_expiry.tick() => {
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Err(err) = coordinator.expire_tasks().await {
+                        error!("error expiring tasks: {:?}", err);
+                    }
+                }
+            }
+            _ = cancel_tasks_job_eviction.tick() => {
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Err(err) = coordinator.abort_stale_cancel_tasks_jobs(timestamp_secs()).await {
+                        error!("error aborting stale cancel tasks jobs: {:?}", err);
+                    }
+                    if let Err(err) = coordinator.evict_cancel_tasks_jobs(timestamp_secs()).await {
+                        error!("error evicting cancel tasks jobs: {:?}", err);
+                    }
+                }
+            }
+            _ = starved_task_check.tick() => {
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Err(err) = coordinator.record_starved_tasks().await {
+                        error!("error counting starved tasks: {:?}", err);
+                    }
+                }
+            }
+            _ = slo_check.tick() => {
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Err(err) = coordinator.record_slo_compliance().await {
+                        error!("error computing slo compliance: {:?}", err);
+                    }
+                }
+            }
+            _ = throttled_task_allocation.tick() => {
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Err(err) = coordinator.allocate_throttled_tasks().await {
+                        error!("error allocating rate limited tasks: {:?}", err);
+                    }
+                }
+            }
+            _ = queued_content_admission.tick() => {
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Err(err) = coordinator.admit_queued_content().await {
+                        error!("error admitting queued content: {:?}", err);
+                    }
+                }
+            }
+            _ = metering_push.tick(), if metering_pusher.is_some() => {
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Some(pusher) = metering_pusher.as_mut() {
+                        if let Err(err) = pusher.push(&coordinator).await {
+                            error!("error pushing metering records: {:?}", err);
+                        }
+                    }
+                }
+            }
+            _ = alert_check.tick(), if alerter.is_some() => {
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Some(alerter) = alerter.as_mut() {
+                        if let Err(err) = alerter.check(&coordinator).await {
+                            error!("error checking alert rules: {:?}", err);
+                        }
+                    }
+                }
+            }
+            _ = deleted_state_compaction.tick() => {
+                if let Err(err) = coordinator.compact_deleted_state().await {
+                    error!("error compacting deleted state: {:?}", err);
+                }
+            }
+            _ = diagnostics_prune.tick() => {
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Err(err) = coordinator.prune_task_diagnostics(timestamp_secs(), &config.diagnostics).await {
+                        error!("error dropping task diagnostics past their retention: {:?}", err);
+                    }
+                }
+            }
+            _ = state_backup.tick(), if config.state_store.backup.is_some() => {
+                // Backups take long on large stores, the scheduler doesn't wait
+                // for them
+                let coordinator = coordinator.clone();
+                let config = config.clone();
+                tokio::spawn(async move {
+                    if let Some(backup) = &config.state_store.backup {
+                        if let Err(err) = coordinator.backup_state(backup).await {
+                            error!("error backing state up: {:?}", err);
+                        }
+                    }
+                });
+            }
+            _ = state_archive.tick(), if state_archiver.is_some() => {
+                // Uploads can take long, the scheduler doesn't wait for them.
+                // Ticks that come while the previous upload runs wait for it.
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Some(archiver) = state_archiver.clone() {
+                        let coordinator = coordinator.clone();
+                        tokio::spawn(async move {
+                            if let Err(err) = coordinator.archive_state(&archiver).await {
+                                error!("error archiving state: {:?}", err);
+                            }
+                        });
+                    }
+                }
+            }
+            _ = replication.tick(), if replicator.is_some() => {
+                // Copies to the other region can take long, the scheduler
+                // doesn't wait for them
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Some(replicator) = replicator.clone() {
+                        let coordinator = coordinator.clone();
+                        tokio::spawn(async move {
+                            if let Err(err) = replicator.run(&coordinator).await {
+                                error!("error replicating content: {:?}", err);
+                            }
+                        });
+                    }
+                }
+            }
+            _ = chaos_task_kill.tick(), if chaos.enabled() => {
+                if is_leader.load(Ordering::Relaxed) {
+                    if let Err(err) = coordinator.kill_tasks(&chaos).await {
+                        error!("error killing tasks for chaos injection: {:?}", err);
+                    }
+                }
             }
         }
     }
@@ -1354,3 +3072,213 @@ async fn shutdown_signal(shutdown_tx: Sender<()>) {
     shutdown_tx.send(()).unwrap();
     info!("signal received, shutting down server gracefully");
 }
+
+#[cfg(test)]
+mod tests {
+    use internal_api::{ExtractionPolicy, ExtractionPolicyContentSource};
+    use serde_json::json;
+
+    use super::*;
+
+    fn subgraph() -> ExtractionGraph {
+        let policy = |name: &str, extractor: &str, content_source| ExtractionPolicy {
+            name: name.to_string(),
+            extractor: extractor.to_string(),
+            input_params: json!({"chunk_size": 512}),
+            content_source,
+            ..Default::default()
+        };
+        ExtractionGraph {
+            id: ExtractionGraph::create_id("chunk_and_embed", "default"),
+            namespace: "default".to_string(),
+            name: "chunk_and_embed".to_string(),
+            extraction_policies: vec![
+                policy("chunk", "chunker", ExtractionPolicyContentSource::Ingestion),
+                policy(
+                    "embed",
+                    "embedder",
+                    ExtractionPolicyContentSource::ExtractionPolicyName("chunk".to_string()),
+                ),
+            ],
+            max_active_content: None,
+            latency_tolerant: false,
+            zone_placement: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_expand_subgraph() {
+        let node = ExtractionPolicyRequest {
+            namespace: "default".to_string(),
+            name: "pipeline".to_string(),
+            content_source: "pdf".to_string(),
+            filters: HashMap::from([("lang".to_string(), "en".to_string())]),
+            subgraph: "chunk_and_embed".to_string(),
+            ..Default::default()
+        };
+        let subgraph = subgraph();
+        let policies = expand_subgraph(&node, &subgraph).unwrap();
+        assert_eq!(policies.len(), 2);
+
+        let chunk = &policies[0];
+        assert_eq!(chunk.name, "pipeline.chunk");
+        assert_eq!(chunk.extractor, "chunker");
+        assert_eq!(chunk.content_source, "pdf");
+        assert_eq!(chunk.filters.get("lang"), Some(&"en".to_string()));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&chunk.input_params).unwrap(),
+            json!({"chunk_size": 512})
+        );
+        assert!(chunk.subgraph.is_empty());
+
+        let embed = &policies[1];
+        assert_eq!(embed.name, "pipeline.embed");
+        assert_eq!(embed.content_source, "pipeline.chunk");
+        assert!(embed.filters.is_empty());
+
+        assert_eq!(
+            subgraph_output("pipeline", &subgraph).unwrap(),
+            "pipeline.embed"
+        );
+    }
+
+    #[test]
+    fn test_subgraph_output_with_several_leaves() {
+        let mut subgraph = subgraph();
+        subgraph.extraction_policies.push(ExtractionPolicy {
+            name: "summarize".to_string(),
+            content_source: ExtractionPolicyContentSource::ExtractionPolicyName(
+                "chunk".to_string(),
+            ),
+            ..Default::default()
+        });
+        assert!(subgraph_output("pipeline", &subgraph).is_err());
+    }
+
+    #[test]
+    fn test_lint_extraction_graph() {
+        let policy = |name: &str, content_source: &str| ExtractionPolicyRequest {
+            name: name.to_string(),
+            content_source: content_source.to_string(),
+            ..Default::default()
+        };
+        let mut policies = vec![
+            ExtractionPolicyRequest {
+                filters: HashMap::from([("lang".to_string(), "en".to_string())]),
+                ..policy("chunk", "")
+            },
+            ExtractionPolicyRequest {
+                filters: HashMap::from([("lang".to_string(), "fr".to_string())]),
+                ..policy("translate", "chunk")
+            },
+            ExtractionPolicyRequest {
+                max_tasks_per_second: 10,
+                ..policy("embed", "chunk")
+            },
+            ExtractionPolicyRequest {
+                max_tasks_per_second: 10,
+                slo: Some(indexify_coordinator::ExtractionPolicySlo {
+                    latency_ms: 5000,
+                    ..Default::default()
+                }),
+                ..policy("summarize", "chunk")
+            },
+        ];
+        policies.extend((0..LARGE_FAN_OUT).map(|i| policy(&format!("ner_{}", i), "chunk")));
+        let graph = CreateExtractionGraphRequest {
+            name: "graph".to_string(),
+            policies,
+            ..Default::default()
+        };
+        let warnings: Vec<(String, String)> = lint_extraction_graph(&graph)
+            .into_iter()
+            .map(|warning| {
+                assert_eq!(warning.severity(), DiagnosticSeverity::Warning);
+                (warning.extraction_policy, warning.message)
+            })
+            .collect();
+        assert_eq!(warnings.len(), 3);
+        assert_eq!(warnings[0].0, "translate");
+        assert!(warnings[0].1.starts_with("unreachable: filter lang=fr"));
+        assert_eq!(warnings[1].0, "embed");
+        assert!(warnings[1].1.contains("without a latency objective"));
+        assert_eq!(warnings[2].0, "chunk");
+        assert!(warnings[2].1.starts_with("large fan out: 11 policies"));
+    }
+
+    #[test]
+    fn test_extraction_graph_errors() {
+        let extractor = internal_api::ExtractorDescription {
+            name: "chunker".to_string(),
+            input_params: json!({}),
+            ..Default::default()
+        };
+        let get_extractor = |name: &str| {
+            (name == extractor.name)
+                .then(|| extractor.clone())
+                .ok_or_else(|| anyhow!("extractor {} not found", name))
+        };
+        let policy = |name: &str, extractor: &str, content_source: &str| ExtractionPolicyRequest {
+            name: name.to_string(),
+            extractor: extractor.to_string(),
+            content_source: content_source.to_string(),
+            input_params: "{}".to_string(),
+            ..Default::default()
+        };
+        let errors = |policies: Vec<ExtractionPolicyRequest>| -> Vec<(String, String)> {
+            let graph = CreateExtractionGraphRequest {
+                name: "graph".to_string(),
+                policies,
+                ..Default::default()
+            };
+            extraction_graph_errors(&graph, get_extractor)
+                .into_iter()
+                .map(|error| {
+                    assert_eq!(error.severity(), DiagnosticSeverity::Error);
+                    (error.extraction_policy, error.message)
+                })
+                .collect()
+        };
+
+        assert!(errors(vec![
+            policy("chunk", "chunker", ""),
+            policy("split", "chunker", "chunk"),
+        ])
+        .is_empty());
+
+        let cycle = errors(vec![
+            policy("chunk", "chunker", "split"),
+            policy("split", "chunker", "chunk"),
+        ]);
+        assert_eq!(
+            cycle,
+            vec![(
+                "chunk".to_string(),
+                "content sources form a cycle: chunk <- split <- chunk".to_string()
+            )]
+        );
+
+        let missing_extractor = errors(vec![policy("embed", "embedder", "")]);
+        assert_eq!(missing_extractor.len(), 1);
+        assert_eq!(missing_extractor[0].0, "embed");
+        assert!(missing_extractor[0].1.contains("embedder"));
+
+        let nothing_to_run = errors(vec![policy("embed", "", "")]);
+        assert_eq!(
+            nothing_to_run,
+            vec![(
+                "embed".to_string(),
+                "extraction policy has neither an extractor nor a subgraph".to_string()
+            )]
+        );
+
+        let dangling_source = errors(vec![policy("embed", "chunker", "chunk")]);
+        assert_eq!(
+            dangling_source,
+            vec![(
+                "embed".to_string(),
+                "content source 'chunk' is not found in the graph".to_string()
+            )]
+        );
+    }
+}
@@ -29,6 +29,9 @@ use crate::{
         MetadataReaderTS,
         MetadataStorageTS,
     },
+    namespace_archive::{ImportReport, NamespaceArchive},
+    task_allocator::simulation::DEFAULT_HORIZON_SECS,
+    utils::timestamp_secs,
     vector_index::{ScoredText, VectorIndexManager},
 };
 
@@ -139,13 +142,44 @@ impl DataManager {
         Ok(policy.into())
     }
 
-    pub async fn create_extraction_graph(
-        &self,
+    fn create_extraction_graph_request(
         namespace: &str,
         req: ExtractionGraphRequest,
-    ) -> Result<Vec<internal_api::IndexName>> {
+    ) -> Result<indexify_coordinator::CreateExtractionGraphRequest> {
         let mut extraction_policies = Vec::new();
         for ep in req.extraction_policies {
+            if let Some(gpu) = ep.gpu.filter(|gpu| !(0.001..=1.0).contains(gpu)) {
+                return Err(anyhow!(
+                    "extraction policy '{}' takes {} gpus, tasks take between 0.001 and 1 gpu",
+                    ep.name,
+                    gpu
+                ));
+            }
+            if let Some(slo) = &ep.slo {
+                if slo.latency_ms.is_none() && slo.max_error_rate.is_none() {
+                    return Err(anyhow!(
+                        "slo of extraction policy '{}' needs a latency_ms or a max_error_rate",
+                        ep.name
+                    ));
+                }
+                if !(slo.latency_target > 0.0 && slo.latency_target < 1.0) {
+                    return Err(anyhow!(
+                        "slo of extraction policy '{}' has latency target {}, it has to be between 0 and 1",
+                        ep.name,
+                        slo.latency_target
+                    ));
+                }
+                if let Some(rate) = slo
+                    .max_error_rate
+                    .filter(|rate| !(*rate > 0.0 && *rate < 1.0))
+                {
+                    return Err(anyhow!(
+                        "slo of extraction policy '{}' has max error rate {}, it has to be between 0 and 1",
+                        ep.name,
+                        rate
+                    ));
+                }
+            }
             let input_params_serialized = serde_json::to_string(&ep.input_params)
                 .map_err(|e| anyhow!("unable to serialize input params to str {}", e))?;
             let req = indexify_coordinator::ExtractionPolicyRequest {
@@ -155,17 +189,67 @@ impl DataManager {
                 filters: ep.filters_eq.clone().unwrap_or_default(),
                 input_params: input_params_serialized,
                 content_source: ep.content_source.clone().unwrap_or_default(),
+                subgraph: ep.subgraph.clone().unwrap_or_default(),
+                output_schema: ep
+                    .output_schema
+                    .as_ref()
+                    .map(|schema| schema.to_string())
+                    .unwrap_or_default(),
+                source_output: ep.source_output.clone().unwrap_or_default(),
+                max_tasks_per_second: ep.max_tasks_per_second.unwrap_or_default(),
+                expensive_warmup: ep.expensive_warmup,
+                min_warm_executors: ep.min_warm_executors.unwrap_or_default(),
+                gpu_millis: ep
+                    .gpu
+                    .map(|gpu| (gpu * 1000.0).round() as u32)
+                    .unwrap_or_default(),
+                slo: ep.slo.clone().map(Into::into),
                 created_at: SystemTime::now()
                     .duration_since(SystemTime::UNIX_EPOCH)?
                     .as_secs() as i64,
             };
             extraction_policies.push(req);
         }
-        let req = indexify_coordinator::CreateExtractionGraphRequest {
+        Ok(indexify_coordinator::CreateExtractionGraphRequest {
             namespace: namespace.to_string(),
             name: req.name,
             policies: extraction_policies,
-        };
+            max_active_content: req.max_active_content.unwrap_or_default(),
+            latency_tolerant: req.latency_tolerant,
+            zone_placement: req.zone_placement.to_string(),
+            author: req.author.unwrap_or_default(),
+            change_summary: req.change_summary.unwrap_or_default(),
+            replace: false,
+            strict: req.strict,
+        })
+    }
+
+    pub async fn validate_extraction_graph(
+        &self,
+        namespace: &str,
+        req: ExtractionGraphRequest,
+    ) -> Result<Vec<api::ExtractionGraphDiagnostic>> {
+        let req = Self::create_extraction_graph_request(namespace, req)?;
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .validate_extraction_graph(req)
+            .await?
+            .into_inner();
+        Ok(response
+            .diagnostics
+            .into_iter()
+            .map(|diagnostic| diagnostic.into())
+            .collect())
+    }
+
+    pub async fn create_extraction_graph(
+        &self,
+        namespace: &str,
+        req: ExtractionGraphRequest,
+    ) -> Result<api::ExtractionGraphResponse> {
+        let req = Self::create_extraction_graph_request(namespace, req)?;
         let response = self
             .coordinator_client
             .get()
@@ -173,6 +257,359 @@ impl DataManager {
             .create_extraction_graph(req)
             .await?
             .into_inner();
+        self.create_extraction_graph_indexes(namespace, response)
+            .await
+    }
+
+    /// Creates an extraction graph from a graph template with the parameters
+    /// of the request
+    pub async fn instantiate_graph_template(
+        &self,
+        namespace: &str,
+        template_name: &str,
+        req: api::InstantiateGraphTemplateRequest,
+        author: Option<String>,
+    ) -> Result<api::ExtractionGraphResponse> {
+        let template: internal_api::GraphTemplate = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_graph_template(indexify_coordinator::GetGraphTemplateRequest {
+                name: template_name.to_string(),
+            })
+            .await?
+            .into_inner()
+            .template
+            .ok_or_else(|| anyhow!("graph template {} not found", template_name))?
+            .try_into()?;
+        let mut graph = template.render(&req.parameters)?;
+        if let Some(graph) = graph.as_object_mut() {
+            graph.insert("name".to_string(), serde_json::Value::String(req.name));
+        }
+        let mut graph: ExtractionGraphRequest = serde_json::from_value(graph).map_err(|e| {
+            anyhow!(
+                "graph template {} doesn't render to an extraction graph: {}",
+                template_name,
+                e
+            )
+        })?;
+        graph.author = author;
+        graph
+            .change_summary
+            .get_or_insert_with(|| format!("instantiated from graph template {}", template_name));
+        self.create_extraction_graph(namespace, graph).await
+    }
+
+    /// Replaces the definition of an existing extraction graph, adding a
+    /// version to the history of the graph
+    pub async fn update_extraction_graph(
+        &self,
+        namespace: &str,
+        req: ExtractionGraphRequest,
+    ) -> Result<api::ExtractionGraphResponse> {
+        let mut req = Self::create_extraction_graph_request(namespace, req)?;
+        req.replace = true;
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .create_extraction_graph(req)
+            .await?
+            .into_inner();
+        self.create_extraction_graph_indexes(namespace, response)
+            .await
+    }
+
+    pub async fn list_extraction_graph_versions(
+        &self,
+        namespace: &str,
+        extraction_graph: &str,
+    ) -> Result<Vec<api::ExtractionGraphVersion>> {
+        let req = indexify_coordinator::ListExtractionGraphVersionsRequest {
+            namespace: namespace.to_string(),
+            extraction_graph: extraction_graph.to_string(),
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .list_extraction_graph_versions(req)
+            .await?
+            .into_inner();
+        Ok(response
+            .versions
+            .into_iter()
+            .map(|version| version.into())
+            .collect())
+    }
+
+    pub async fn get_extraction_graph_usage(
+        &self,
+        namespace: &str,
+        extraction_graph: &str,
+    ) -> Result<Vec<internal_api::ExtractionGraphUsage>> {
+        let req = indexify_coordinator::GetExtractionGraphUsageRequest {
+            namespace: namespace.to_string(),
+            extraction_graph: extraction_graph.to_string(),
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_extraction_graph_usage(req)
+            .await?
+            .into_inner();
+        Ok(response.usage.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn get_slo_compliance(
+        &self,
+        namespace: &str,
+        extraction_graph: &str,
+    ) -> Result<Vec<internal_api::SloCompliance>> {
+        let req = indexify_coordinator::GetSloComplianceRequest {
+            namespace: namespace.to_string(),
+            extraction_graph: extraction_graph.to_string(),
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_slo_compliance(req)
+            .await?
+            .into_inner();
+        Ok(response.compliance.into_iter().map(Into::into).collect())
+    }
+
+    /// JSON encoded archive of a namespace
+    pub async fn export_namespace(&self, namespace: &str) -> Result<Vec<u8>> {
+        let req = indexify_coordinator::ExportNamespaceRequest {
+            namespace: namespace.to_string(),
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .export_namespace(req)
+            .await?
+            .into_inner();
+        Ok(response.archive)
+    }
+
+    /// Imports a JSON encoded archive of a namespace into `namespace`. The
+    /// graphs are imported first and their index tables created, then the
+    /// content in batches. An import that failed can be run again, it skips
+    /// what was imported already.
+    pub async fn import_namespace(
+        &self,
+        namespace: &str,
+        archive: Vec<u8>,
+    ) -> Result<ImportReport> {
+        let archive: NamespaceArchive =
+            serde_json::from_slice(&archive).map_err(|e| anyhow!("invalid archive: {}", e))?;
+        let mut report = ImportReport::default();
+        for batch in archive.into_batches() {
+            let req = indexify_coordinator::ImportNamespaceRequest {
+                namespace: namespace.to_string(),
+                archive: serde_json::to_vec(&batch)?,
+            };
+            let response = self
+                .coordinator_client
+                .get()
+                .await?
+                .import_namespace(req)
+                .await?
+                .into_inner();
+            for graph in response.graphs {
+                self.create_extraction_graph_indexes(namespace, graph)
+                    .await?;
+            }
+            report.merge(ImportReport {
+                extraction_graphs: response.extraction_graphs as usize,
+                extraction_graph_versions: response.extraction_graph_versions as usize,
+                content: response.content as usize,
+                content_ids: response.content_ids,
+            });
+        }
+        Ok(report)
+    }
+
+    /// Makes a prior version of an extraction graph its latest version
+    pub async fn rollback_extraction_graph(
+        &self,
+        namespace: &str,
+        extraction_graph: &str,
+        version: u64,
+        author: &str,
+    ) -> Result<api::ExtractionGraphResponse> {
+        let req = indexify_coordinator::RollbackExtractionGraphRequest {
+            namespace: namespace.to_string(),
+            extraction_graph: extraction_graph.to_string(),
+            version,
+            author: author.to_string(),
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .rollback_extraction_graph(req)
+            .await?
+            .into_inner();
+        self.create_extraction_graph_indexes(namespace, response)
+            .await
+    }
+
+    pub async fn get_scheduler_settings(&self) -> Result<internal_api::SchedulerSettings> {
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_scheduler_settings(indexify_coordinator::GetSchedulerSettingsRequest {})
+            .await?
+            .into_inner();
+        response.settings.unwrap_or_default().try_into()
+    }
+
+    pub async fn set_scheduler_settings(
+        &self,
+        settings: internal_api::SchedulerSettings,
+    ) -> Result<internal_api::SchedulerSettings> {
+        let req = indexify_coordinator::SetSchedulerSettingsRequest {
+            settings: Some(settings.into()),
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .set_scheduler_settings(req)
+            .await?
+            .into_inner();
+        response.settings.unwrap_or_default().try_into()
+    }
+
+    /// Predicts the queues of the extractors under a hypothetical change to
+    /// the cluster. The coordinator runs the planner on a copy of its state.
+    pub async fn simulate_scheduling(
+        &self,
+        req: api::SimulateSchedulingRequest,
+    ) -> Result<api::SimulateSchedulingResponse> {
+        let horizon_secs = req.horizon_secs.unwrap_or(DEFAULT_HORIZON_SECS);
+        let req = indexify_coordinator::SimulateSchedulingRequest {
+            executors: req
+                .executors
+                .into_iter()
+                .map(|executors| indexify_coordinator::SimulatedExecutors {
+                    count: executors.count,
+                    extractors: executors.extractors,
+                    capacity_weight: executors.capacity_weight,
+                })
+                .collect(),
+            invocations: req
+                .invocations
+                .into_iter()
+                .map(|invocations| indexify_coordinator::SimulatedInvocations {
+                    namespace: invocations.namespace,
+                    extraction_graph: invocations.extraction_graph,
+                    per_hour: invocations.per_hour,
+                })
+                .collect(),
+            horizon_secs,
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .simulate_scheduling(req)
+            .await?
+            .into_inner();
+        Ok(api::SimulateSchedulingResponse {
+            horizon_secs,
+            extractors: response
+                .extractors
+                .into_iter()
+                .map(|queue| api::SimulatedQueue {
+                    extractor: queue.extractor,
+                    executors: queue.executors as usize,
+                    unallocated: queue.unallocated,
+                    queued: queue.queued,
+                    predicted_queued: queue.predicted_queued,
+                    predicted_wait_secs: queue.predicted_wait_secs,
+                })
+                .collect(),
+        })
+    }
+
+    pub async fn get_maintenance_mode(&self) -> Result<internal_api::MaintenanceMode> {
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .get_maintenance_mode(indexify_coordinator::GetMaintenanceModeRequest {})
+            .await?
+            .into_inner();
+        Ok(response.maintenance.unwrap_or_default().into())
+    }
+
+    pub async fn set_maintenance_mode(
+        &self,
+        enabled: bool,
+        reason: &str,
+    ) -> Result<internal_api::MaintenanceMode> {
+        let req = indexify_coordinator::SetMaintenanceModeRequest {
+            enabled,
+            reason: reason.to_string(),
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .set_maintenance_mode(req)
+            .await?
+            .into_inner();
+        Ok(response.maintenance.unwrap_or_default().into())
+    }
+
+    pub async fn record_egress(&self, namespace: &str, bytes: u64) -> Result<()> {
+        let req = indexify_coordinator::RecordEgressRequest {
+            namespace: namespace.to_string(),
+            bytes,
+        };
+        self.coordinator_client
+            .get()
+            .await?
+            .record_egress(req)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn list_metering_records(
+        &self,
+        namespace: Option<String>,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<internal_api::MeteringRecord>> {
+        let req = indexify_coordinator::ListMeteringRecordsRequest {
+            namespace: namespace.unwrap_or_default(),
+            start,
+            end,
+        };
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .list_metering_records(req)
+            .await?
+            .into_inner();
+        Ok(response.records.into_iter().map(Into::into).collect())
+    }
+
+    /// Creates the tables of the indexes of a graph and makes the indexes
+    /// visible
+    async fn create_extraction_graph_indexes(
+        &self,
+        namespace: &str,
+        response: indexify_coordinator::CreateExtractionGraphResponse,
+    ) -> Result<api::ExtractionGraphResponse> {
         for (_, policy) in response.policies {
             let extractor = response
                 .extractors
@@ -205,12 +642,16 @@ impl DataManager {
             .update_indexes_state(req)
             .await?;
 
-        let index_names = response
+        let indexes = response
             .indexes
             .iter()
             .map(|index| index.name.clone())
             .collect();
-        Ok(index_names)
+        Ok(api::ExtractionGraphResponse {
+            indexes,
+            version: response.version,
+            warnings: response.warnings.into_iter().map(Into::into).collect(),
+        })
     }
 
     // FIXME - Pass Namespace to this so that we don't let waiting on content that
@@ -240,6 +681,7 @@ impl DataManager {
             source: source_filter.to_string(),
             parent_id: parent_id_filter.to_string(),
             labels_eq: labels_eq_filter.unwrap_or(&HashMap::new()).clone(),
+            cursor: String::new(),
         };
         let response = self
             .coordinator_client
@@ -256,6 +698,33 @@ impl DataManager {
         Ok(content_list)
     }
 
+    /// Streams the content `list_content` returns as the coordinator reads it,
+    /// after the content the cursor of a failed stream points to
+    pub async fn stream_content(
+        &self,
+        namespace: &str,
+        source_filter: &str,
+        parent_id_filter: &str,
+        labels_eq_filter: Option<&HashMap<String, String>>,
+        cursor: Option<String>,
+    ) -> Result<impl Stream<Item = Result<api::ContentMetadata, api::StreamError>>> {
+        let req = indexify_coordinator::ListContentRequest {
+            namespace: namespace.to_string(),
+            source: source_filter.to_string(),
+            parent_id: parent_id_filter.to_string(),
+            labels_eq: labels_eq_filter.cloned().unwrap_or_default(),
+            cursor: cursor.unwrap_or_default(),
+        };
+        let content = self
+            .coordinator_client
+            .get()
+            .await?
+            .stream_content(req)
+            .await?
+            .into_inner();
+        Ok(content.map(|content| content.map(Into::into).map_err(Into::into)))
+    }
+
     pub async fn list_active_contents(&self, namespace: &str) -> Result<Vec<String>> {
         let req = ListActiveContentsRequest {
             namespace: namespace.to_string(),
@@ -276,11 +745,13 @@ impl DataManager {
         namespace: &str,
         content_list: Vec<api::ContentWithId>,
         extraction_graph_names: Vec<internal_api::ExtractionGraphName>,
+        priority: i32,
+        expires_at: u64,
     ) -> Result<()> {
         for content_with_id in content_list {
             let text = content_with_id.content;
             let stream = futures::stream::once(async { Ok(Bytes::from(text.bytes)) });
-            let content_metadata = self
+            let mut content_metadata = self
                 .write_content_bytes(
                     namespace,
                     Box::pin(stream),
@@ -292,6 +763,8 @@ impl DataManager {
                     &extraction_graph_names,
                 )
                 .await?;
+            content_metadata.priority = priority;
+            content_metadata.expires_at = expires_at;
 
             let req = indexify_coordinator::CreateContentRequest {
                 content: Some(content_metadata),
@@ -392,6 +865,8 @@ impl DataManager {
         mime: &str,
         labels: HashMap<String, String>,
         extraction_graph_names: &Vec<internal_api::ExtractionGraphName>,
+        priority: i32,
+        expires_at: u64,
     ) -> Result<String> {
         if !(["https://", "http://", "s3://", "file://"]
             .iter()
@@ -419,6 +894,9 @@ impl DataManager {
             extraction_policy_ids: HashMap::new(),
             root_content_id: "".to_string(),
             extraction_graph_names: extraction_graph_names.clone(),
+            priority,
+            expires_at,
+            output: "".to_string(),
         };
         let req: indexify_coordinator::CreateContentRequest =
             indexify_coordinator::CreateContentRequest {
@@ -555,6 +1033,12 @@ impl DataManager {
         format!("{:x}", s.finish())
     }
 
+    /// Unix time in seconds at which content ingested now with `ttl_secs`
+    /// expires, 0 if it doesn't
+    pub fn expires_at(ttl_secs: Option<u64>) -> u64 {
+        ttl_secs.map_or(0, |ttl_secs| timestamp_secs() + ttl_secs)
+    }
+
     /// Checks if the given string is a valid hexadecimal.
     pub fn is_hex_string(s: &str) -> bool {
         s.chars().all(|c| c.is_ascii_hexdigit())
@@ -612,19 +1096,29 @@ impl DataManager {
             hash: content_hash,
             extraction_policy_ids: HashMap::new(),
             extraction_graph_names: extraction_graph_names.to_vec(),
+            priority: 0,
+            expires_at: 0,
+            output: "".to_string(),
         })
     }
 
     pub async fn finish_extracted_content_write(
         &self,
         begin_ingest: BeginExtractedContentIngest,
+        diagnostics: Option<String>,
+        usage: Option<internal_api::TaskUsage>,
     ) -> Result<()> {
-        let outcome: indexify_coordinator::TaskOutcome = begin_ingest.task_outcome.into();
+        let outcome: indexify_coordinator::TaskOutcome = match diagnostics {
+            Some(_) => indexify_coordinator::TaskOutcome::Failed,
+            None => begin_ingest.task_outcome.into(),
+        };
 
         let req = indexify_coordinator::UpdateTaskRequest {
             executor_id: begin_ingest.executor_id,
             task_id: begin_ingest.task_id,
             outcome: outcome as i32,
+            diagnostics: diagnostics.unwrap_or_default(),
+            usage: usage.map(Into::into),
         };
         let res = self.coordinator_client.get().await?.update_task(req).await;
         if let Err(err) = res {
@@ -955,6 +1449,22 @@ impl DataManager {
         Ok(extractors)
     }
 
+    pub async fn list_executors(&self) -> Result<Vec<api::Executor>> {
+        let req = indexify_coordinator::ListExecutorsRequest {};
+        let response = self
+            .coordinator_client
+            .get()
+            .await?
+            .list_executors(req)
+            .await?
+            .into_inner();
+        response
+            .executors
+            .into_iter()
+            .map(|e| e.try_into())
+            .collect()
+    }
+
     #[tracing::instrument(skip(file))]
     pub async fn write_to_blob_store(
         &self,
@@ -968,6 +1478,28 @@ impl DataManager {
     pub async fn blob_store_writer(&self, namespace: &str, key: &str) -> Result<StoragePartWriter> {
         self.blob_storage.writer(namespace, key).await
     }
+
+    /// Blob store key under which the output of a task attempt is stored
+    pub fn task_log_key(
+        namespace: &str,
+        task_id: &str,
+        attempt: u32,
+        stream: api::TaskLogStream,
+    ) -> String {
+        format!("task_logs/{}/{}/{}/{}", namespace, task_id, attempt, stream)
+    }
+
+    pub fn task_log_url(
+        &self,
+        namespace: &str,
+        task_id: &str,
+        attempt: u32,
+        stream: api::TaskLogStream,
+    ) -> String {
+        self.blob_storage.url_for_key(&DataManager::task_log_key(
+            namespace, task_id, attempt, stream,
+        ))
+    }
 }
 
 #[cfg(test)]
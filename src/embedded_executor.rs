@@ -0,0 +1,182 @@
+use std::{
+    process::Stdio,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use tokio::{process::Command, sync::watch};
+use tracing::{error, info, warn};
+
+use crate::server_config::ServerConfig;
+
+const DEFAULT_EXECUTOR_COMMAND: &str = "indexify-extractor";
+
+const MIN_RESTART_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(30);
+
+/// An executor that ran for this long before it exited is restarted right
+/// away, one that exits sooner is restarted with a growing delay
+const HEALTHY_RUN: Duration = Duration::from_secs(60);
+
+/// The executor of all in one mode, `indexify-extractor join-server` run as a
+/// child process of the server. It runs the extractors downloaded on the
+/// machine, is restarted whenever it exits and is killed when the server shuts
+/// down.
+#[derive(Debug)]
+pub struct EmbeddedExecutor {
+    program: String,
+    args: Vec<String>,
+}
+
+impl EmbeddedExecutor {
+    /// `command` replaces `indexify-extractor`, e.g. to run the CLI of a
+    /// virtualenv
+    pub fn new(config: &ServerConfig, command: Option<&str>, workers: Option<u32>) -> Result<Self> {
+        let mut command = command
+            .unwrap_or(DEFAULT_EXECUTOR_COMMAND)
+            .split_whitespace()
+            .map(str::to_string);
+        let program = command
+            .next()
+            .ok_or_else(|| anyhow!("the executor command is empty"))?;
+        let mut args: Vec<String> = command.collect();
+        args.extend([
+            "join-server".to_string(),
+            "--coordinator-addr".to_string(),
+            config.coordinator_addr.clone(),
+            "--ingestion-addr".to_string(),
+            format!("localhost:{}", config.listen_port),
+        ]);
+        if let Some(workers) = workers {
+            args.extend(["--workers".to_string(), workers.to_string()]);
+        }
+        Ok(Self { program, args })
+    }
+
+    /// Runs the executor until `shutdown_rx` is set, then kills it and
+    /// returns
+    pub async fn run(self, mut shutdown_rx: watch::Receiver<bool>) -> Result<()> {
+        let mut backoff = Duration::ZERO;
+        while !*shutdown_rx.borrow() {
+            if !backoff.is_zero() {
+                tokio::select! {
+                    _ = tokio::time::sleep(backoff) => {}
+                    _ = shutdown_rx.changed() => continue,
+                }
+            }
+            info!(
+                "starting embedded executor: {} {}",
+                self.program,
+                self.args.join(" ")
+            );
+            let started_at = Instant::now();
+            let child = Command::new(&self.program)
+                .args(&self.args)
+                .stdin(Stdio::null())
+                .kill_on_drop(true)
+                .spawn();
+            let mut child = match child {
+                Ok(child) => child,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    return Err(anyhow!(
+                        "`{}` was not found, install the executor with `pip install indexify-extractor`",
+                        self.program
+                    ));
+                }
+                Err(e) => return Err(e.into()),
+            };
+            tokio::select! {
+                status = child.wait() => match status {
+                    Ok(status) => warn!("embedded executor exited with {}", status),
+                    Err(e) => error!("error waiting for the embedded executor: {:?}", e),
+                },
+                _ = shutdown_rx.changed() => {
+                    info!("stopping embedded executor");
+                    if let Err(e) = child.kill().await {
+                        error!("error killing the embedded executor: {:?}", e);
+                    }
+                    break;
+                }
+            }
+            backoff = restart_backoff(backoff, started_at.elapsed());
+        }
+        Ok(())
+    }
+}
+
+/// Delay before restarting an executor that exited after running for
+/// `ran_for`
+fn restart_backoff(previous: Duration, ran_for: Duration) -> Duration {
+    if ran_for >= HEALTHY_RUN {
+        return Duration::ZERO;
+    }
+    (previous * 2).clamp(MIN_RESTART_BACKOFF, MAX_RESTART_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded_executor() {
+        let config = ServerConfig {
+            listen_port: 9000,
+            coordinator_addr: "localhost:9950".to_string(),
+            ..Default::default()
+        };
+        let executor = EmbeddedExecutor::new(
+            &config,
+            Some("python -m indexify_extractor_sdk.cli"),
+            Some(2),
+        )
+        .unwrap();
+        assert_eq!(executor.program, "python");
+        assert_eq!(
+            executor.args,
+            vec![
+                "-m",
+                "indexify_extractor_sdk.cli",
+                "join-server",
+                "--coordinator-addr",
+                "localhost:9950",
+                "--ingestion-addr",
+                "localhost:9000",
+                "--workers",
+                "2"
+            ]
+        );
+        assert!(EmbeddedExecutor::new(&config, Some(" "), None).is_err());
+
+        //  Executors that keep exiting are restarted slower and slower
+        let backoff = restart_backoff(Duration::ZERO, Duration::from_secs(1));
+        assert_eq!(backoff, MIN_RESTART_BACKOFF);
+        let backoff = restart_backoff(backoff, Duration::from_secs(1));
+        assert_eq!(backoff, Duration::from_secs(2));
+        assert_eq!(
+            restart_backoff(Duration::from_secs(20), Duration::from_secs(1)),
+            MAX_RESTART_BACKOFF
+        );
+        assert_eq!(
+            restart_backoff(MAX_RESTART_BACKOFF, HEALTHY_RUN),
+            Duration::ZERO
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_embedded_executor_shutdown() {
+        let executor = EmbeddedExecutor {
+            program: "sleep".to_string(),
+            args: vec!["60".to_string()],
+        };
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let run = tokio::spawn(executor.run(shutdown_rx));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        shutdown_tx.send(true).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), run)
+            .await
+            .expect("the executor kept running after shutdown")
+            .unwrap()
+            .unwrap();
+    }
+}
@@ -59,4 +59,27 @@ impl ForwardableCoordinator {
 
         Ok(())
     }
+
+    pub async fn get_task_scheduling(
+        &self,
+        leader_addr: &str,
+        namespace: &str,
+        task_id: &str,
+    ) -> Result<Vec<indexify_internal_api::SchedulingAttempt>, anyhow::Error> {
+        let req = indexify_coordinator::GetTaskSchedulingRequest {
+            namespace: namespace.to_string(),
+            task_id: task_id.to_string(),
+        };
+
+        let mut client = self.coordinator_client.get_coordinator(leader_addr).await?;
+
+        let response = client.get_task_scheduling(req).await?;
+
+        Ok(response
+            .into_inner()
+            .attempts
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{hash_map::Entry, HashMap};
 
 use anyhow::{anyhow, Result};
 use axum::extract::ws;
@@ -20,6 +20,7 @@ use tracing::info;
 
 use crate::{
     api::*,
+    api_utils,
     blob_storage::StoragePartWriter,
     data_manager::DataManager,
     server::NamespaceEndpointState,
@@ -36,6 +37,7 @@ fn msg_type_str(msg: &IngestExtractedContent) -> &'static str {
         IngestExtractedContent::FinishMultipartContent(_) => "FinishMultipartContent",
         IngestExtractedContent::ExtractedFeatures(_) => "ExtractedFeatures",
         IngestExtractedContent::FinishExtractedContentIngest(_) => "FinishExtractedContentIngest",
+        IngestExtractedContent::TaskOutput(_) => "TaskOutput",
     }
 }
 
@@ -65,6 +67,10 @@ struct ContentStateWriting {
     task: indexify_coordinator::Task,
     root_content_metadata: Option<indexify_internal_api::ContentMetadata>,
     frame_state: FrameState,
+    output_schema: Option<serde_json::Value>,
+    schema_violations: Vec<String>,
+    /// Writers of the logs of the task's output, by stream
+    task_logs: HashMap<TaskLogStream, StoragePartWriter>,
 }
 
 impl ContentStateWriting {
@@ -72,6 +78,7 @@ impl ContentStateWriting {
         ingest_metadata: BeginExtractedContentIngest,
         task: indexify_coordinator::Task,
         root_content: Option<indexify_coordinator::ContentMetadata>,
+        output_schema: Option<serde_json::Value>,
     ) -> Result<Self> {
         if task.content_metadata.is_none() {
             return Err(anyhow!("task does not have content metadata"));
@@ -82,9 +89,90 @@ impl ContentStateWriting {
             task,
             root_content_metadata: root_content,
             frame_state: FrameState::New,
+            output_schema,
+            schema_violations: Vec::new(),
+            task_logs: HashMap::new(),
         })
     }
 
+    async fn write_task_output(
+        &mut self,
+        state: &NamespaceEndpointState,
+        payload: TaskOutput,
+    ) -> Result<()> {
+        if payload.bytes.is_empty() {
+            return Ok(());
+        }
+        let writer = match self.task_logs.entry(payload.stream) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let key = DataManager::task_log_key(
+                    &self.task.namespace,
+                    &self.task.id,
+                    self.task.attempt,
+                    payload.stream,
+                );
+                entry.insert(
+                    state
+                        .data_manager
+                        .blob_store_writer(&self.task.namespace, &key)
+                        .await?,
+                )
+            }
+        };
+        writer
+            .writer
+            .write_all(&payload.bytes)
+            .await
+            .map_err(|e| anyhow!("unable to write task output to blob store: {}", e))
+    }
+
+    /// Stores the logs of the task's output, which are readable once the
+    /// task finished
+    async fn finish_task_logs(&mut self) -> Result<()> {
+        for (stream, mut writer) in self.task_logs.drain() {
+            writer.writer.shutdown().await.map_err(|e| {
+                anyhow!("unable to store {} of task {}: {}", stream, self.task.id, e)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Drops the metadata features that don't match the output schema of the
+    /// task's extraction policy, recording why, and returns the rest
+    fn check_output_schema(&mut self, features: Vec<Feature>) -> Result<Vec<Feature>> {
+        let Some(schema) = &self.output_schema else {
+            return Ok(features);
+        };
+        let mut valid = Vec::with_capacity(features.len());
+        for feature in features {
+            if let FeatureType::Metadata = feature.feature_type {
+                let violations = api_utils::output_schema_violations(schema, &feature.data)?;
+                if !violations.is_empty() {
+                    self.schema_violations.extend(
+                        violations
+                            .into_iter()
+                            .map(|violation| format!("metadata '{}' {}", feature.name, violation)),
+                    );
+                    continue;
+                }
+            }
+            valid.push(feature);
+        }
+        Ok(valid)
+    }
+
+    /// Why the task failed when its metadata didn't match the output schema
+    fn diagnostics(&self) -> Option<String> {
+        if self.schema_violations.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "extracted metadata doesn't match the output schema: {}",
+            self.schema_violations.join("; ")
+        ))
+    }
+
     fn content_metadata(&self) -> &indexify_coordinator::ContentMetadata {
         self.task.content_metadata.as_ref().unwrap()
     }
@@ -148,6 +236,7 @@ impl ContentStateWriting {
         state: &NamespaceEndpointState,
         payload: FinishContent,
     ) -> Result<String> {
+        let features = self.check_output_schema(payload.features)?;
         let mut labels = self.content_metadata().labels.clone();
         let parent_id = self.content_metadata().id.clone();
         match &mut self.frame_state {
@@ -156,6 +245,8 @@ impl ContentStateWriting {
             )),
             FrameState::Writing(frame_state) => {
                 frame_state.writer.writer.shutdown().await?;
+                //  The coordinator runs the labels added by the extractor
+                //  through the content hooks, the inherited ones already were
                 labels.extend(payload.labels);
                 let hash_result = frame_state.hasher.clone().finalize();
                 let content_hash = format!("{:x}", hash_result);
@@ -183,6 +274,9 @@ impl ContentStateWriting {
                     hash: content_hash,
                     extraction_policy_ids: HashMap::new(),
                     extraction_graph_names: vec![extraction_policy.graph_name],
+                    priority: root_content_metadata.priority,
+                    expires_at: root_content_metadata.expires_at,
+                    output: payload.output.unwrap_or_default(),
                 };
                 state
                     .data_manager
@@ -191,7 +285,7 @@ impl ContentStateWriting {
                         Some(root_content_metadata.clone()),
                         &self.task.extractor,
                         &self.task.extraction_graph_name,
-                        payload.features,
+                        features,
                         &self.task.output_index_mapping,
                     )
                     .await?;
@@ -211,6 +305,7 @@ impl ContentStateWriting {
         state: &NamespaceEndpointState,
         payload: ExtractedFeatures,
     ) -> Result<()> {
+        let features = self.check_output_schema(payload.features)?;
         state
             .data_manager
             .write_existing_content_features(
@@ -218,7 +313,7 @@ impl ContentStateWriting {
                 &self.task.extraction_graph_name,
                 self.content_metadata(),
                 self.root_content_metadata.clone(),
-                payload.features,
+                features,
                 &self.task.output_index_mapping,
                 &self.task.index_tables,
             )
@@ -252,9 +347,19 @@ impl IngestExtractedContentState {
             .get_metadata_for_ingestion(&payload.task_id)
             .await?;
         let task = task.ok_or_else(|| anyhow!("task {} not found", payload.task_id))?;
+        let output_schema = self
+            .state
+            .data_manager
+            .get_extraction_policy(&task.extraction_policy_id)
+            .await?
+            .output_schema;
 
-        self.content_state =
-            ContentState::Writing(ContentStateWriting::new(payload, task, root_content)?);
+        self.content_state = ContentState::Writing(ContentStateWriting::new(
+            payload,
+            task,
+            root_content,
+            output_schema,
+        )?);
         Ok(())
     }
 
@@ -288,12 +393,24 @@ impl IngestExtractedContentState {
         }
     }
 
-    async fn finish(&mut self) -> Result<()> {
+    async fn write_task_output(&mut self, payload: TaskOutput) -> Result<()> {
+        match &mut self.content_state {
+            ContentState::Writing(s) => s.write_task_output(&self.state, payload).await,
+            ContentState::Init => Err(anyhow!("received task output without header metadata")),
+        }
+    }
+
+    async fn finish(&mut self, payload: FinishExtractedContentIngest) -> Result<()> {
         match &mut self.content_state {
             ContentState::Writing(s) => {
+                s.finish_task_logs().await?;
                 self.state
                     .data_manager
-                    .finish_extracted_content_write(s.ingest_metadata.clone())
+                    .finish_extracted_content_write(
+                        s.ingest_metadata.clone(),
+                        s.diagnostics(),
+                        payload.usage,
+                    )
                     .await?;
                 self.content_state = ContentState::Init;
                 Ok(())
@@ -331,8 +448,11 @@ impl IngestExtractedContentState {
                         IngestExtractedContent::ExtractedFeatures(payload) => {
                             self.write_features(payload).await
                         }
-                        IngestExtractedContent::FinishExtractedContentIngest(_) => {
-                            let res = self.finish().await;
+                        IngestExtractedContent::TaskOutput(payload) => {
+                            self.write_task_output(payload).await
+                        }
+                        IngestExtractedContent::FinishExtractedContentIngest(payload) => {
+                            let res = self.finish(payload).await;
                             let msg = match res {
                                 Ok(_) => IngestExtractedContentResponse::Success,
                                 Err(e) => IngestExtractedContentResponse::Error(e.to_string()),
@@ -526,7 +646,12 @@ mod tests {
         pub async fn create_extractor(&self, extractor: ExtractorDescription) -> Result<()> {
             self.coordinator
                 .shared_state
-                .register_executor("localhost:8950", "executor_id", vec![extractor])
+                .register_executor(
+                    "localhost:8950",
+                    "executor_id",
+                    vec![extractor],
+                    Default::default(),
+                )
                 .await?;
             Ok(())
         }
@@ -573,12 +698,7 @@ mod tests {
         }
 
         pub async fn create_task(&self, task: Task) -> Result<()> {
-            let state_change_id = self
-                .coordinator
-                .shared_state
-                .get_state_change_watcher()
-                .borrow_and_update()
-                .id;
+            let state_change_id = self.coordinator.shared_state.latest_state_change().id;
 
             self.coordinator
                 .shared_state
@@ -693,6 +813,7 @@ mod tests {
             content_type: "test".to_string(),
             features: Vec::new(),
             labels: HashMap::new(),
+            output: None,
         };
 
         ingest_state.finish_content(payload).await.unwrap();
@@ -711,6 +832,52 @@ mod tests {
         coordinator.stop().await;
     }
 
+    #[tokio::test]
+    async fn test_task_output() {
+        let state = new_endpoint_state().await.unwrap();
+        let coordinator = TestCoordinator::new().await;
+
+        let mut ingest_state = IngestExtractedContentState::new(state);
+        ingest_state
+            .begin(BeginExtractedContentIngest {
+                task_id: "test".to_string(),
+                executor_id: "test".to_string(),
+                task_outcome: TaskOutcome::Success,
+            })
+            .await
+            .unwrap();
+        for bytes in [b"line 1\n".to_vec(), Vec::new(), b"line 2\n".to_vec()] {
+            ingest_state
+                .write_task_output(TaskOutput {
+                    stream: TaskLogStream::Stdout,
+                    bytes,
+                })
+                .await
+                .unwrap();
+        }
+        let ContentState::Writing(s) = &mut ingest_state.content_state else {
+            panic!("content_state should be Writing");
+        };
+        //  Only the streams the extractor wrote to are stored
+        assert_eq!(s.task_logs.len(), 1);
+        s.finish_task_logs().await.unwrap();
+        let namespace = s.task.namespace.clone();
+
+        let state = &ingest_state.state;
+        let url = state
+            .data_manager
+            .task_log_url(&namespace, "test", 0, TaskLogStream::Stdout);
+        let log = state.content_reader.bytes(&url).await.unwrap();
+        assert_eq!(log, "line 1\nline 2\n");
+        let url = state
+            .data_manager
+            .task_log_url(&namespace, "test", 0, TaskLogStream::Stderr);
+        let err = state.content_reader.bytes(&url).await.unwrap_err();
+        assert!(crate::blob_storage::is_not_found(&err));
+
+        coordinator.stop().await;
+    }
+
     #[tokio::test]
     async fn test_embedding_metadata() {
         set_tracing();
@@ -754,6 +921,7 @@ mod tests {
             content_type: "test".to_string(),
             features: Vec::new(),
             labels: HashMap::new(),
+            output: None,
         };
 
         payload.features.push(Feature {
@@ -902,6 +1070,7 @@ mod tests {
             content_type: "test".to_string(),
             features: Vec::new(),
             labels: HashMap::new(),
+            output: None,
         };
 
         let metadata1 = json!({"key1" : "value1", "key2" : "value2"});
@@ -1025,6 +1194,7 @@ mod tests {
                 "localhost:8956",
                 "test_executor_id",
                 vec![extractor_1.clone()],
+                Default::default(),
             )
             .await?;
 
@@ -0,0 +1,226 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+use anyhow::Result;
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::{
+    server_config::{LogFileConfig, LogRotation},
+    utils::timestamp_secs,
+};
+
+/// The file logs are written to once a command loaded its config. Logs of
+/// events before that only go to stderr.
+static LOG_FILE: OnceLock<Mutex<RotatingFile>> = OnceLock::new();
+
+/// Starts writing logs to the file of the config. Logs keep going to stderr
+/// as well.
+pub fn init(config: &LogFileConfig) -> Result<()> {
+    let file = RotatingFile::open(config, timestamp_secs())?;
+    LOG_FILE
+        .set(Mutex::new(file))
+        .map_err(|_| anyhow::anyhow!("file logging is already initialized"))
+}
+
+/// Writer of the fmt layer that writes to the log file, it drops the logs
+/// until the file is initialized
+pub struct LogFileWriter;
+
+impl<'a> MakeWriter<'a> for LogFileWriter {
+    type Writer = LogFileWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        LogFileWriter
+    }
+}
+
+impl Write for LogFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match LOG_FILE.get() {
+            Some(file) => file
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .write(buf, timestamp_secs()),
+            None => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match LOG_FILE.get() {
+            Some(file) => file.lock().unwrap_or_else(|e| e.into_inner()).file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A log file that is renamed to `<path>.<unix seconds>` when it grows past
+/// its maximum size or its rotation period ends. Only the newest rotated
+/// files are kept.
+struct RotatingFile {
+    path: PathBuf,
+    max_size_bytes: Option<u64>,
+    period_secs: Option<u64>,
+    max_files: usize,
+    file: File,
+    size: u64,
+    /// Start of the rotation period the file was opened in
+    period_start: u64,
+}
+
+impl RotatingFile {
+    fn open(config: &LogFileConfig, now: u64) -> Result<Self> {
+        let path = PathBuf::from(&config.path);
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        let period_secs = match config.rotation {
+            LogRotation::Never => None,
+            LogRotation::Hourly => Some(3600),
+            LogRotation::Daily => Some(24 * 3600),
+        };
+        Ok(Self {
+            path,
+            max_size_bytes: config.max_size_bytes,
+            period_secs,
+            max_files: config.max_files,
+            file,
+            size,
+            period_start: period_start(now, period_secs),
+        })
+    }
+
+    fn write(&mut self, buf: &[u8], now: u64) -> io::Result<usize> {
+        let period_ended = self.period_start != period_start(now, self.period_secs);
+        let full = self
+            .max_size_bytes
+            .is_some_and(|max_size| self.size > 0 && self.size + buf.len() as u64 > max_size);
+        if period_ended || full {
+            self.rotate(now)?;
+        }
+        self.file.write_all(buf)?;
+        self.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn rotate(&mut self, now: u64) -> io::Result<()> {
+        self.file.flush()?;
+        let mut rotated = suffixed(&self.path, &now.to_string());
+        // The file can fill up more than once a second
+        let mut n = 1;
+        while rotated.exists() {
+            rotated = suffixed(&self.path, &format!("{}-{}", now, n));
+            n += 1;
+        }
+        fs::rename(&self.path, rotated)?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = 0;
+        self.period_start = period_start(now, self.period_secs);
+        self.remove_old_files()
+    }
+
+    fn remove_old_files(&self) -> io::Result<()> {
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let prefix = format!(
+            "{}.",
+            self.path.file_name().unwrap_or_default().to_string_lossy()
+        );
+        // Only files named like rotated files are removed
+        let mut rotated = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let suffix = name.strip_prefix(&prefix)?;
+                let (secs, n) = match suffix.split_once('-') {
+                    Some((secs, n)) => (secs.parse::<u64>().ok()?, n.parse::<u64>().ok()?),
+                    None => (suffix.parse::<u64>().ok()?, 0),
+                };
+                Some(((secs, n), entry.path()))
+            })
+            .collect::<Vec<_>>();
+        rotated.sort();
+        let excess = rotated.len().saturating_sub(self.max_files);
+        for (_, path) in rotated.into_iter().take(excess) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+fn period_start(now: u64, period_secs: Option<u64>) -> u64 {
+    period_secs.map_or(0, |period| now - now % period)
+}
+
+fn suffixed(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rotated_files(dir: &Path) -> Vec<String> {
+        let mut files = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .filter(|name| name != "indexify.log")
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = LogFileConfig {
+            path: dir
+                .path()
+                .join("indexify.log")
+                .to_str()
+                .unwrap()
+                .to_string(),
+            max_size_bytes: Some(10),
+            rotation: LogRotation::Hourly,
+            max_files: 2,
+        };
+        let mut file = RotatingFile::open(&config, 3600).unwrap();
+
+        file.write(b"12345", 3600).unwrap();
+        file.write(b"12345", 3601).unwrap();
+        assert!(rotated_files(dir.path()).is_empty());
+
+        // The file is full
+        file.write(b"1", 3602).unwrap();
+        assert_eq!(rotated_files(dir.path()), vec!["indexify.log.3602"]);
+
+        // The hour ended
+        file.write(b"1", 7200).unwrap();
+        assert_eq!(
+            rotated_files(dir.path()),
+            vec!["indexify.log.3602", "indexify.log.7200"]
+        );
+
+        // Only the newest rotated files are kept
+        file.write(b"1", 10800).unwrap();
+        assert_eq!(rotated_files(dir.path()).len(), 2);
+        assert!(!rotated_files(dir.path()).contains(&"indexify.log.3602".to_string()));
+        assert_eq!(
+            fs::read_to_string(dir.path().join("indexify.log")).unwrap(),
+            "1"
+        );
+    }
+}
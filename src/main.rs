@@ -20,32 +20,62 @@ pub mod server_config;
 pub mod state;
 pub mod task_allocator;
 
+mod admin_routes;
+mod alerting;
 mod api;
 mod api_utils;
+mod auth;
 mod blob_storage;
 mod caching;
+mod chaos;
 mod cmd;
+mod content_archive;
+mod content_hooks;
 mod coordinator;
 mod coordinator_client;
 mod data_manager;
+mod embedded_executor;
 mod extractor_router;
 mod forwardable_coordinator;
 mod garbage_collector;
 mod grpc_helper;
 mod ingest_extracted_content;
+mod log_file;
 mod metadata_storage;
+mod metering;
+mod namespace_archive;
+mod preflight;
+mod profiling;
+mod read_only;
+mod replication;
 mod scheduler;
+mod sdk_codegen;
+mod secrets;
+mod task_bundler;
+mod task_canceller;
 mod test_util;
 mod tls;
 mod tonic_streamer;
 mod utils;
 mod vector_index;
 mod vectordbs;
+mod webhooks;
 
 //  test modules
 #[cfg(test)]
 mod test_utils;
 
+//  Heap profiles are sampled by jemalloc, which has to be the allocator of the
+//  process with profiling turned on from the start
+#[cfg(feature = "profiling")]
+#[global_allocator]
+static ALLOC: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
+#[cfg(feature = "profiling")]
+#[allow(non_upper_case_globals)]
+#[export_name = "malloc_conf"]
+pub static malloc_conf: &[u8] = b"prof:true,prof_active:true,lg_prof_sample:19\0";
+
 fn setup_stdout_tracing() -> Result<()> {
     global::set_text_map_propagator(TraceContextPropagator::new());
     let provider = opentelemetry_sdk::trace::TracerProvider::builder()
@@ -154,12 +184,20 @@ fn setup_tracing(trace_type: &str) -> Result<()> {
 fn setup_fmt_tracing() {
     let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
-    println!("Running with tracing filter {}", env_filter);
-    let subscriber = tracing_subscriber::Registry::default().with(
-        tracing_subscriber::fmt::layer()
-            .with_writer(std::io::stderr)
-            .with_filter(env_filter),
-    );
+    eprintln!("Running with tracing filter {}", env_filter);
+    let file_env_filter = env_filter.to_string();
+    let subscriber = tracing_subscriber::Registry::default()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(std::io::stderr)
+                .with_filter(env_filter),
+        )
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(log_file::LogFileWriter)
+                .with_ansi(false)
+                .with_filter(tracing_subscriber::EnvFilter::new(file_env_filter)),
+        );
     if let Err(e) = tracing::subscriber::set_global_default(subscriber) {
         eprintln!("failed to set global default subscriber: {}", e);
     }
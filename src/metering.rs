@@ -0,0 +1,125 @@
+use anyhow::Result;
+use indexify_internal_api::{MeteringRecord, METERING_PERIOD_SECS};
+use tracing::info;
+
+use crate::{coordinator::Coordinator, utils::timestamp_secs, webhooks};
+
+/// Content is metered when its creation is committed, which can be a little
+/// after it was created. Periods are pushed once this long has passed since
+/// they ended.
+const PUSH_DELAY_SECS: u64 = 60;
+
+/// Pushes the metering records of finished periods to a billing endpoint.
+/// Records are identified by their namespace and period, so the endpoint can
+/// replace the records it already has when a period is pushed twice, like
+/// after the leader changed.
+pub struct MeteringPusher {
+    client: reqwest::Client,
+    push_url: String,
+    push_secret: Option<String>,
+    /// Start of the first period that wasn't pushed
+    next_period: u64,
+    /// Periods of the last push that failed, and how many times they were
+    /// pushed
+    failed_push: Option<((u64, u64), u32)>,
+}
+
+impl MeteringPusher {
+    pub fn new(push_url: &str, push_secret: Option<&str>) -> Self {
+        // The previous leader might not have pushed the last finished period
+        let next_period =
+            MeteringRecord::period_of(timestamp_secs().saturating_sub(PUSH_DELAY_SECS))
+                .saturating_sub(METERING_PERIOD_SECS);
+        Self {
+            client: reqwest::Client::new(),
+            push_url: push_url.to_string(),
+            push_secret: push_secret.map(str::to_string),
+            next_period,
+            failed_push: None,
+        }
+    }
+
+    /// Starts of the first and the last period that finished and wasn't
+    /// pushed by `now`
+    fn pending_periods(&self, now: u64) -> Option<(u64, u64)> {
+        let current = MeteringRecord::period_of(now.saturating_sub(PUSH_DELAY_SECS));
+        (self.next_period < current).then(|| (self.next_period, current - METERING_PERIOD_SECS))
+    }
+
+    /// Attempt number of a push of the periods, counting the pushes of the
+    /// same periods that failed before
+    fn attempt(&self, periods: (u64, u64)) -> u32 {
+        match self.failed_push {
+            Some((failed, attempts)) if failed == periods => attempts + 1,
+            _ => 1,
+        }
+    }
+
+    pub async fn push(&mut self, coordinator: &Coordinator) -> Result<()> {
+        let Some((start, end)) = self.pending_periods(timestamp_secs()) else {
+            return Ok(());
+        };
+        let records = coordinator.list_metering_records("", start, end)?;
+        if !records.is_empty() {
+            //  A push of the same periods has the same delivery id, so the
+            //  endpoint can tell retries apart from new periods
+            let attempt = self.attempt((start, end));
+            self.failed_push = Some(((start, end), attempt));
+            webhooks::delivery(
+                self.client.post(&self.push_url),
+                &format!("metering-{}-{}", start, end),
+                attempt,
+                self.push_secret.as_deref(),
+                serde_json::to_vec(&records)?,
+            )
+            .send()
+            .await?
+            .error_for_status()?;
+            self.failed_push = None;
+            info!(
+                "pushed {} metering records of the periods from {} to {}",
+                records.len(),
+                start,
+                end
+            );
+        }
+        self.next_period = end + METERING_PERIOD_SECS;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_periods() {
+        let mut pusher = MeteringPusher::new("http://localhost:8080/usage", None);
+        pusher.next_period = 2 * METERING_PERIOD_SECS;
+
+        // The current period hasn't finished
+        assert_eq!(pusher.pending_periods(2 * METERING_PERIOD_SECS + 10), None);
+        // The previous period finished too recently
+        assert_eq!(pusher.pending_periods(3 * METERING_PERIOD_SECS + 10), None);
+        assert_eq!(
+            pusher.pending_periods(3 * METERING_PERIOD_SECS + PUSH_DELAY_SECS),
+            Some((2 * METERING_PERIOD_SECS, 2 * METERING_PERIOD_SECS))
+        );
+        assert_eq!(
+            pusher.pending_periods(5 * METERING_PERIOD_SECS + PUSH_DELAY_SECS),
+            Some((2 * METERING_PERIOD_SECS, 4 * METERING_PERIOD_SECS))
+        );
+    }
+
+    #[test]
+    fn test_push_attempts() {
+        let mut pusher = MeteringPusher::new("http://localhost:8080/usage", Some("secret"));
+        assert_eq!(pusher.attempt((0, 0)), 1);
+
+        // Failed pushes of the same periods count as attempts of a delivery,
+        // a push that takes in a newly finished period is a new delivery
+        pusher.failed_push = Some(((0, 0), 2));
+        assert_eq!(pusher.attempt((0, 0)), 3);
+        assert_eq!(pusher.attempt((0, METERING_PERIOD_SECS)), 1);
+    }
+}
@@ -430,6 +430,14 @@ pub mod coordinator {
         pub content_extracted_bytes: ObservableCounter<u64>,
         pub scheduler_invocations: Histogram<f64>,
         pub tasks_per_executor: ObservableGauge<u64>,
+        pub task_queue_depth: ObservableGauge<u64>,
+        pub starved_tasks: ObservableGauge<u64>,
+        pub slo_compliance: ObservableGauge<f64>,
+        pub slo_burn_rate: ObservableGauge<f64>,
+        pub state_changes_coalesced: ObservableCounter<u64>,
+        pub state_changes_unprocessed: ObservableGauge<u64>,
+        pub state_changes_unprocessed_age: ObservableGauge<u64>,
+        pub state_changes_processed: ObservableCounter<u64>,
     }
 
     impl Metrics {
@@ -615,6 +623,173 @@ pub mod coordinator {
                 .with_description("Number of tasks per executor")
                 .init();
 
+            let task_queue_depth = meter
+                .u64_observable_gauge("indexify.coordinator.task_queue_depth")
+                .with_callback({
+                    let app = app.clone();
+                    move |observer| {
+                        let depths = app.data.indexify_state.get_task_queue_depths();
+                        for (extraction_policy_id, depth) in depths.iter() {
+                            for (state, count) in [
+                                ("unallocated", depth.unallocated),
+                                ("running", depth.running),
+                                ("completed_last_hour", depth.completed_last_hour),
+                            ] {
+                                observer.observe(
+                                    count,
+                                    &[
+                                        KeyValue::new(
+                                            "extraction_policy_id",
+                                            extraction_policy_id.to_string(),
+                                        ),
+                                        KeyValue::new("state", state),
+                                    ],
+                                );
+                            }
+                        }
+                    }
+                })
+                .with_description("Number of tasks per extraction policy and state")
+                .init();
+
+            let starved_tasks = meter
+                .u64_observable_gauge("indexify.coordinator.starved_tasks")
+                .with_callback({
+                    let app = app.clone();
+                    move |observer| {
+                        let counts = app.data.indexify_state.starved_tasks.lock().unwrap();
+                        for (reason, count) in counts.iter() {
+                            observer
+                                .observe(*count, &[KeyValue::new("reason", reason.to_string())]);
+                        }
+                    }
+                })
+                .with_description(
+                    "Number of tasks unallocated past the starvation threshold per reason",
+                )
+                .init();
+
+            let slo_compliance = meter
+                .f64_observable_gauge("indexify.coordinator.slo_compliance")
+                .with_callback({
+                    let app = app.clone();
+                    move |observer| {
+                        let compliance = app.data.indexify_state.slo_compliance.lock().unwrap();
+                        for (extraction_policy_id, compliance) in compliance.iter() {
+                            for (objective, value) in [
+                                ("latency", compliance.latency_compliance),
+                                (
+                                    "error_rate",
+                                    compliance
+                                        .error_burn_rate
+                                        .map(|_| 1.0 - compliance.error_rate),
+                                ),
+                            ] {
+                                let Some(value) = value else { continue };
+                                observer.observe(
+                                    value,
+                                    &[
+                                        KeyValue::new(
+                                            "extraction_policy_id",
+                                            extraction_policy_id.to_string(),
+                                        ),
+                                        KeyValue::new("objective", objective),
+                                    ],
+                                );
+                            }
+                        }
+                    }
+                })
+                .with_description(
+                    "Share of the tasks of an extraction policy finished within the last hour that met an objective",
+                )
+                .init();
+
+            let slo_burn_rate = meter
+                .f64_observable_gauge("indexify.coordinator.slo_burn_rate")
+                .with_callback({
+                    let app = app.clone();
+                    move |observer| {
+                        let compliance = app.data.indexify_state.slo_compliance.lock().unwrap();
+                        for (extraction_policy_id, compliance) in compliance.iter() {
+                            for (objective, burn_rate) in [
+                                ("latency", compliance.latency_burn_rate),
+                                ("error_rate", compliance.error_burn_rate),
+                            ] {
+                                let Some(burn_rate) = burn_rate else { continue };
+                                observer.observe(
+                                    burn_rate,
+                                    &[
+                                        KeyValue::new(
+                                            "extraction_policy_id",
+                                            extraction_policy_id.to_string(),
+                                        ),
+                                        KeyValue::new("objective", objective),
+                                    ],
+                                );
+                            }
+                        }
+                    }
+                })
+                .with_description(
+                    "How fast an extraction policy burns the error budget of an objective, it runs out at 1",
+                )
+                .init();
+
+            let state_changes_coalesced = meter
+                .u64_observable_counter("indexify.coordinator.state_changes_coalesced")
+                .with_callback({
+                    let app = app.clone();
+                    move |observer| {
+                        for (consumer, lag) in app.data.state_changes.lag() {
+                            observer.observe(lag.coalesced, &[KeyValue::new("consumer", consumer)]);
+                        }
+                    }
+                })
+                .with_description(
+                    "Number of state changes a consumer was woken up for together with a later one",
+                )
+                .init();
+
+            let state_changes_unprocessed = meter
+                .u64_observable_gauge("indexify.coordinator.state_changes_unprocessed")
+                .with_callback({
+                    let app = app.clone();
+                    move |observer| {
+                        if let Ok((count, _)) = app.state_change_backlog() {
+                            observer.observe(count as u64, &[]);
+                        }
+                    }
+                })
+                .with_description("Number of state changes the scheduler hasn't processed yet")
+                .init();
+
+            let state_changes_unprocessed_age = meter
+                .u64_observable_gauge("indexify.coordinator.state_changes_unprocessed_age")
+                .with_callback({
+                    let app = app.clone();
+                    move |observer| {
+                        if let Ok((_, age)) = app.state_change_backlog() {
+                            observer.observe(age, &[]);
+                        }
+                    }
+                })
+                .with_description(
+                    "Seconds the oldest state change the scheduler hasn't processed has waited",
+                )
+                .init();
+
+            let state_changes_processed = meter
+                .u64_observable_counter("indexify.coordinator.state_changes_processed")
+                .with_callback({
+                    let app = app.clone();
+                    move |observer| {
+                        observer.observe(app.state_changes_processed(), &[]);
+                    }
+                })
+                .with_description("Number of state changes the scheduler processed")
+                .init();
+
             Metrics {
                 tasks_completed,
                 tasks_errored,
@@ -626,6 +801,14 @@ pub mod coordinator {
                 content_extracted_bytes,
                 scheduler_invocations,
                 tasks_per_executor,
+                task_queue_depth,
+                starved_tasks,
+                slo_compliance,
+                slo_burn_rate,
+                state_changes_coalesced,
+                state_changes_unprocessed,
+                state_changes_unprocessed_age,
+                state_changes_processed,
             }
         }
     }
@@ -762,11 +945,15 @@ pub mod vector_storage {
 }
 
 pub mod state_machine {
-    use opentelemetry::metrics::Histogram;
+    use opentelemetry::metrics::{Counter, Histogram};
 
     #[derive(Debug)]
     pub struct Metrics {
         pub state_machine_apply: Histogram<f64>,
+        pub compaction_reclaimed_bytes: Counter<u64>,
+        pub backup_duration: Histogram<f64>,
+        pub backup_size: Histogram<u64>,
+        pub backup_rate_limit_utilization: Histogram<f64>,
     }
 
     impl Default for Metrics {
@@ -784,8 +971,34 @@ pub mod state_machine {
                 .with_description("State machine apply changes latencies in seconds")
                 .init();
 
+            let compaction_reclaimed_bytes = meter
+                .u64_counter("indexify.state_machine_compaction_reclaimed_bytes")
+                .with_description("Bytes reclaimed by compacting the key ranges of deleted state")
+                .init();
+
+            let backup_duration = meter
+                .f64_histogram("indexify.state_machine_backup_duration")
+                .with_description("State store backup durations in seconds")
+                .init();
+
+            let backup_size = meter
+                .u64_histogram("indexify.state_machine_backup_size")
+                .with_description("Bytes of the files of state store backups")
+                .init();
+
+            let backup_rate_limit_utilization = meter
+                .f64_histogram("indexify.state_machine_backup_rate_limit_utilization")
+                .with_description(
+                    "Share of the rate limit state store backups used, 1 when the limit held them back",
+                )
+                .init();
+
             Metrics {
                 state_machine_apply,
+                compaction_reclaimed_bytes,
+                backup_duration,
+                backup_size,
+                backup_rate_limit_utilization,
             }
         }
     }
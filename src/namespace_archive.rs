@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use indexify_internal_api as internal_api;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+/// Version of the layout of namespace archives, bumped when an archive
+/// written by an older server can't be imported as it is
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Content sent to the coordinator per import request, and written per raft
+/// entry, to keep both within the size of a message
+pub const IMPORT_BATCH_SIZE: usize = 1000;
+
+/// A namespace packaged to be moved to another cluster: every version of its
+/// extraction graphs and the metadata of the content ingested into it,
+/// including where its bytes are stored. Extracted content, embeddings and
+/// metadata aren't archived, the content is extracted again once imported.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceArchive {
+    pub format_version: u32,
+    pub namespace: String,
+    pub exported_at: u64,
+    /// Versions of each extraction graph, oldest first
+    pub extraction_graphs: Vec<Vec<internal_api::ExtractionGraphVersion>>,
+    pub content: Vec<internal_api::ContentMetadata>,
+}
+
+/// What an import created in the target namespace
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ImportReport {
+    pub extraction_graphs: usize,
+    pub extraction_graph_versions: usize,
+    pub content: usize,
+    /// Id the content was imported with by its id in the archive
+    pub content_ids: HashMap<String, String>,
+}
+
+impl ImportReport {
+    /// Adds up the reports of the batches of an import
+    pub fn merge(&mut self, other: ImportReport) {
+        self.extraction_graphs += other.extraction_graphs;
+        self.extraction_graph_versions += other.extraction_graph_versions;
+        self.content += other.content;
+        self.content_ids.extend(other.content_ids);
+    }
+}
+
+/// Id of the content with `id` in an archive once imported into `namespace`.
+/// It doesn't depend on when the archive is imported, so an import that
+/// stopped can be run again and skips the content it already imported.
+pub fn import_id(namespace: &str, id: &str) -> String {
+    let digest = Sha256::digest(format!("{}/{}", namespace, id).as_bytes());
+    format!("{:x}", digest)[..16].to_string()
+}
+
+impl NamespaceArchive {
+    /// Splits the archive into the requests of an import: the extraction
+    /// graphs first, then the content in batches
+    pub fn into_batches(self) -> Vec<NamespaceArchive> {
+        let header = NamespaceArchive {
+            content: Vec::new(),
+            ..self.clone()
+        };
+        let content = self
+            .content
+            .chunks(IMPORT_BATCH_SIZE)
+            .map(|content| NamespaceArchive {
+                extraction_graphs: Vec::new(),
+                content: content.to_vec(),
+                ..header.clone()
+            });
+        std::iter::once(header.clone()).chain(content).collect()
+    }
+
+    /// Moves the archive to `namespace`. Graph and policy ids are derived from
+    /// the namespace so they are computed again, and content gets ids from
+    /// [`import_id`] so it can't collide with content of the target cluster.
+    /// Only the latest version of ingested content is kept, without the
+    /// policies that extracted it, so the extraction graphs run on it again.
+    /// Returns the new id of every content by its id in the archive.
+    pub fn remap(mut self, namespace: &str) -> (Self, HashMap<String, String>) {
+        for versions in &mut self.extraction_graphs {
+            for version in versions.iter_mut() {
+                let graph = &mut version.extraction_graph;
+                graph.namespace = namespace.to_string();
+                graph.id = internal_api::ExtractionGraph::create_id(&graph.name, namespace);
+                for policy in &mut graph.extraction_policies {
+                    let id = internal_api::ExtractionPolicy::create_id(
+                        &graph.name,
+                        &policy.name,
+                        namespace,
+                    );
+                    policy.id = id;
+                    policy.namespace = namespace.to_string();
+                    for table in policy.output_table_mapping.values_mut() {
+                        if let Some(name) = table.strip_prefix(&format!("{}.", self.namespace)) {
+                            *table = format!("{}.{}", namespace, name);
+                        }
+                    }
+                }
+            }
+        }
+
+        //  Extracted content was written by the graphs of the source cluster,
+        //  it's extracted again by the graphs of the target namespace
+        self.content
+            .retain(|content| content.parent_id.is_none() && content.latest);
+        let mut content_ids = HashMap::new();
+        for content in &mut self.content {
+            let id = import_id(namespace, &content.id.id);
+            content_ids.insert(content.id.id.clone(), id.clone());
+            content.id.id = id;
+            content.namespace = namespace.to_string();
+            content.root_content_id = None;
+            content.extraction_policy_ids.clear();
+        }
+        self.namespace = namespace.to_string();
+        (self, content_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remap() {
+        let policy = internal_api::ExtractionPolicy {
+            id: internal_api::ExtractionPolicy::create_id("graph", "embed", "source"),
+            graph_name: "graph".to_string(),
+            name: "embed".to_string(),
+            namespace: "source".to_string(),
+            output_table_mapping: HashMap::from([(
+                "embedding".to_string(),
+                "source.graph.embed.embedding".to_string(),
+            )]),
+            ..Default::default()
+        };
+        let graph = internal_api::ExtractionGraph {
+            id: internal_api::ExtractionGraph::create_id("graph", "source"),
+            name: "graph".to_string(),
+            namespace: "source".to_string(),
+            extraction_policies: vec![policy.clone()],
+            max_active_content: None,
+            latency_tolerant: false,
+            zone_placement: Default::default(),
+        };
+        let root = internal_api::ContentMetadata {
+            id: internal_api::ContentMetadataId::new("root"),
+            namespace: "source".to_string(),
+            extraction_policy_ids: HashMap::from([(policy.id.clone(), 10)]),
+            ..Default::default()
+        };
+        let child = internal_api::ContentMetadata {
+            id: internal_api::ContentMetadataId::new("child"),
+            parent_id: Some(root.id.clone()),
+            root_content_id: Some("root".to_string()),
+            namespace: "source".to_string(),
+            ..Default::default()
+        };
+        let archive = NamespaceArchive {
+            format_version: FORMAT_VERSION,
+            namespace: "source".to_string(),
+            exported_at: 0,
+            extraction_graphs: vec![vec![internal_api::ExtractionGraphVersion {
+                version: 1,
+                extraction_graph: graph,
+                author: String::new(),
+                summary: "created".to_string(),
+                created_at: 0,
+            }]],
+            content: vec![root, child],
+        };
+
+        let (archive, content_ids) = archive.remap("target");
+        assert_eq!(archive.namespace, "target");
+        let graph = &archive.extraction_graphs[0][0].extraction_graph;
+        assert_eq!(
+            graph.id,
+            internal_api::ExtractionGraph::create_id("graph", "target")
+        );
+        let policy_id = internal_api::ExtractionPolicy::create_id("graph", "embed", "target");
+        assert_eq!(graph.extraction_policies[0].id, policy_id);
+        assert_eq!(
+            graph.extraction_policies[0].output_table_mapping["embedding"],
+            "target.graph.embed.embedding"
+        );
+
+        //  Only ingested content is imported, to be extracted again
+        assert_eq!(content_ids.len(), 1);
+        assert_eq!(content_ids["root"], import_id("target", "root"));
+        assert_ne!(content_ids["root"], import_id("other", "root"));
+        assert_eq!(archive.content.len(), 1);
+        let root = &archive.content[0];
+        assert_eq!(root.id.id, content_ids["root"]);
+        assert_eq!(root.namespace, "target");
+        assert!(root.extraction_policy_ids.is_empty());
+
+        let batches = archive.into_batches();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].extraction_graphs.len(), 1);
+        assert!(batches[0].content.is_empty());
+        assert!(batches[1].extraction_graphs.is_empty());
+        assert_eq!(batches[1].content.len(), 1);
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    fs,
+    net::{SocketAddr, TcpListener},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Result};
+use tracing::{info, warn};
+
+use crate::{blob_storage::BlobStorage, server_config::ServerConfig};
+
+/// Free space that has to be left on the volumes holding the state store and
+/// the blobs for a node to start
+const MIN_FREE_DISK_BYTES: u64 = 512 * 1024 * 1024;
+
+/// 2024-01-01T00:00:00Z. A clock reading earlier than this is a clock that was
+/// never set, which breaks task timestamps and TLS certificate validation.
+const MIN_CLOCK_SECS: u64 = 1_704_067_200;
+
+const PROBE_FILE_NAME: &str = ".indexify_preflight";
+
+/// Checks that the ingestion server can start with `config`. Every check
+/// runs, and the failures are reported together.
+pub async fn check_server(config: &ServerConfig, addr: SocketAddr) -> Result<()> {
+    let mut failures = Vec::new();
+    failures.extend(check_clock().err());
+    failures.extend(check_port_available("api", addr).err());
+    match config.admin_addr_sock() {
+        Ok(Some(admin_addr)) => {
+            failures.extend(check_port_available("admin api", admin_addr).err())
+        }
+        Ok(None) => {}
+        Err(e) => failures.push(e),
+    }
+    if config.blob_storage.s3.is_none() {
+        let path = config
+            .blob_storage
+            .disk
+            .as_ref()
+            .map(|disk| disk.path.as_str())
+            .unwrap_or("blobs");
+        failures.extend(check_writable_dir("blob storage", path).err());
+        failures.extend(check_disk_headroom("blob storage", path).await.err());
+    }
+    failures.extend(
+        BlobStorage::new_with_config(config.blob_storage.clone())
+            .check_connectivity()
+            .await
+            .err(),
+    );
+    report("server", failures)
+}
+
+/// Checks that the coordinator can start with `config`. This runs before the
+/// state store is opened and before any of the coordinator's ports are bound.
+pub async fn check_coordinator(config: &ServerConfig) -> Result<()> {
+    let mut failures = Vec::new();
+    failures.extend(check_clock().err());
+    for (name, addr) in [
+        ("coordinator", config.coordinator_lis_addr_sock()),
+        ("raft", config.raft_addr_sock()),
+        ("coordinator http", config.coordinator_http_addr_sock()),
+    ] {
+        match addr {
+            Ok(addr) => failures.extend(check_port_available(name, addr).err()),
+            Err(e) => failures.push(e),
+        }
+    }
+    match config.state_store.path.as_deref() {
+        Some(path) if !path.is_empty() => {
+            failures.extend(check_writable_dir("state store", path).err());
+            failures.extend(check_disk_headroom("state store", path).await.err());
+        }
+        _ => failures.push(anyhow!(
+            "state store path is not set, set `state_store.path` in the config"
+        )),
+    }
+    failures.extend(
+        config
+            .alerting
+            .rules
+            .iter()
+            .filter_map(|rule| rule.validate().err()),
+    );
+    report("coordinator", failures)
+}
+
+fn report(component: &str, failures: Vec<anyhow::Error>) -> Result<()> {
+    if failures.is_empty() {
+        info!("{} preflight checks passed", component);
+        return Ok(());
+    }
+    let failures = failures
+        .iter()
+        .map(|e| format!("  - {}", e))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Err(anyhow!(
+        "{} preflight checks failed:\n{}",
+        component,
+        failures
+    ))
+}
+
+fn check_clock() -> Result<()> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| anyhow!("system clock is set before the unix epoch, sync the clock with NTP"))?
+        .as_secs();
+    if now < MIN_CLOCK_SECS {
+        return Err(anyhow!(
+            "system clock reads {} seconds since the unix epoch, which is in the past; sync the clock with NTP",
+            now
+        ));
+    }
+    Ok(())
+}
+
+fn check_port_available(name: &str, addr: SocketAddr) -> Result<()> {
+    TcpListener::bind(addr).map(|_| ()).map_err(|e| {
+        anyhow!(
+            "{} address {} can't be bound: {}. Stop the process using port {} or configure a different port",
+            name,
+            addr,
+            e,
+            addr.port()
+        )
+    })
+}
+
+/// Creates `path` if it is missing and writes a probe file to it
+fn check_writable_dir(name: &str, path: &str) -> Result<()> {
+    let dir = Path::new(path);
+    fs::create_dir_all(dir).map_err(|e| {
+        anyhow!(
+            "{} directory {} can't be created: {}. Check the permissions of its parent directory",
+            name,
+            path,
+            e
+        )
+    })?;
+    let probe = dir.join(PROBE_FILE_NAME);
+    fs::write(&probe, b"").and_then(|_| fs::remove_file(&probe)).map_err(|e| {
+        anyhow!(
+            "{} directory {} is not writable: {}. Check the permissions of the directory or run the server as its owner",
+            name,
+            path,
+            e
+        )
+    })
+}
+
+async fn check_disk_headroom(name: &str, path: &str) -> Result<()> {
+    let available = match available_disk_bytes(path).await {
+        Ok(available) => available,
+        Err(e) => {
+            warn!("unable to read free disk space of {}: {}", path, e);
+            return Ok(());
+        }
+    };
+    if available < MIN_FREE_DISK_BYTES {
+        return Err(anyhow!(
+            "{} directory {} has {} MiB of free disk space, at least {} MiB are needed. Free up space or move it to a larger volume",
+            name,
+            path,
+            available / (1024 * 1024),
+            MIN_FREE_DISK_BYTES / (1024 * 1024)
+        ));
+    }
+    Ok(())
+}
+
+/// Reads the space available to unprivileged users on the volume holding
+/// `path` from the POSIX output of `df`
+async fn available_disk_bytes(path: &str) -> Result<u64> {
+    let output = tokio::process::Command::new("df")
+        .arg("-Pk")
+        .arg(path)
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "df exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    parse_df_available(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_df_available(output: &str) -> Result<u64> {
+    let available_kb = output
+        .lines()
+        .nth(1)
+        .and_then(|line| line.split_whitespace().nth(3))
+        .ok_or_else(|| anyhow!("unexpected df output: {}", output))?
+        .parse::<u64>()?;
+    Ok(available_kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_df_available() {
+        let output = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n\
+                      /dev/nvme0n1p2   490617784 123456789 342165000      27% /\n";
+        assert_eq!(parse_df_available(output).unwrap(), 342165000 * 1024);
+        assert!(parse_df_available("Filesystem\n").is_err());
+    }
+
+    #[test]
+    fn test_check_port_available() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        assert!(check_port_available("api", addr).is_err());
+        drop(listener);
+        assert!(check_port_available("api", addr).is_ok());
+    }
+}
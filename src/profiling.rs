@@ -0,0 +1,209 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+
+use crate::{api::IndexifyAPIError, server_config::ProfilingConfig};
+
+/// Set while a CPU profile is taken. The profiler samples the whole process,
+/// so only one profile is taken at a time.
+static CPU_PROFILE_RUNNING: AtomicBool = AtomicBool::new(false);
+
+const DEFAULT_CPU_PROFILE_SECS: u64 = 30;
+const DEFAULT_CPU_PROFILE_FREQUENCY: i32 = 99;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ProfileFormat {
+    /// Protobuf profile for `go tool pprof`
+    #[default]
+    Pprof,
+    /// SVG flamegraph
+    Flamegraph,
+}
+
+#[derive(Debug, Deserialize)]
+struct CpuProfileParams {
+    /// Seconds to sample for, 30 by default
+    seconds: Option<u64>,
+    /// Samples per second, 99 by default
+    frequency: Option<i32>,
+    #[serde(default)]
+    format: ProfileFormat,
+}
+
+/// Routes that profile the running process. `/debug/pprof/profile` samples
+/// the CPU for a while and `/debug/pprof/heap` dumps the heap profile of the
+/// allocator.
+pub fn routes(config: &ProfilingConfig) -> Router {
+    Router::new()
+        .route("/debug/pprof/profile", get(cpu_profile))
+        .route("/debug/pprof/heap", get(heap_profile))
+        .with_state(config.clone())
+}
+
+fn not_built() -> IndexifyAPIError {
+    IndexifyAPIError::new(
+        StatusCode::NOT_IMPLEMENTED,
+        "the server was built without the profiling feature",
+    )
+}
+
+async fn cpu_profile(
+    State(config): State<ProfilingConfig>,
+    Query(params): Query<CpuProfileParams>,
+) -> Result<Response, IndexifyAPIError> {
+    if !cfg!(feature = "profiling") {
+        return Err(not_built());
+    }
+    let seconds = params
+        .seconds
+        .unwrap_or(DEFAULT_CPU_PROFILE_SECS)
+        .clamp(1, config.max_cpu_profile_secs.max(1));
+    let frequency = params
+        .frequency
+        .unwrap_or(DEFAULT_CPU_PROFILE_FREQUENCY)
+        .max(1);
+    if CPU_PROFILE_RUNNING.swap(true, Ordering::SeqCst) {
+        return Err(IndexifyAPIError::new(
+            StatusCode::CONFLICT,
+            "a cpu profile is already being taken",
+        ));
+    }
+    //  Sampling blocks a thread for the whole duration
+    let profile = tokio::task::spawn_blocking(move || {
+        sample_cpu(Duration::from_secs(seconds), frequency, params.format)
+    })
+    .await;
+    CPU_PROFILE_RUNNING.store(false, Ordering::SeqCst);
+    let (content_type, body) = profile
+        .map_err(|e| IndexifyAPIError::internal_error(anyhow!(e)))?
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(([(header::CONTENT_TYPE, content_type)], body).into_response())
+}
+
+async fn heap_profile() -> Result<Response, IndexifyAPIError> {
+    if !cfg!(feature = "profiling") {
+        return Err(not_built());
+    }
+    let body = dump_heap()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/octet-stream"),
+            (header::CONTENT_ENCODING, "gzip"),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+#[cfg(feature = "profiling")]
+fn sample_cpu(
+    duration: Duration,
+    frequency: i32,
+    format: ProfileFormat,
+) -> Result<(&'static str, Vec<u8>)> {
+    use pprof::protos::Message;
+
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(frequency)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()?;
+    std::thread::sleep(duration);
+    let report = guard.report().build()?;
+    let mut body = Vec::new();
+    match format {
+        ProfileFormat::Pprof => {
+            report.pprof()?.encode(&mut body)?;
+            Ok(("application/octet-stream", body))
+        }
+        ProfileFormat::Flamegraph => {
+            report.flamegraph(&mut body)?;
+            Ok(("image/svg+xml", body))
+        }
+    }
+}
+
+#[cfg(not(feature = "profiling"))]
+fn sample_cpu(
+    _duration: Duration,
+    _frequency: i32,
+    _format: ProfileFormat,
+) -> Result<(&'static str, Vec<u8>)> {
+    Err(anyhow!(
+        "the server was built without the profiling feature"
+    ))
+}
+
+/// Gzipped pprof profile of the heap, sampled by jemalloc
+#[cfg(feature = "profiling")]
+async fn dump_heap() -> Result<Vec<u8>> {
+    let prof_ctl = jemalloc_pprof::PROF_CTL
+        .as_ref()
+        .ok_or_else(|| anyhow!("jemalloc heap profiling is not available"))?;
+    let mut prof_ctl = prof_ctl.lock().await;
+    if !prof_ctl.activated() {
+        return Err(anyhow!("jemalloc heap profiling is not active"));
+    }
+    prof_ctl.dump_pprof()
+}
+
+#[cfg(not(feature = "profiling"))]
+async fn dump_heap() -> Result<Vec<u8>> {
+    Err(anyhow!(
+        "the server was built without the profiling feature"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, extract::Request};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn get(path: &str) -> Response {
+        routes(&ProfilingConfig::default())
+            .oneshot(Request::builder().uri(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[cfg(not(feature = "profiling"))]
+    #[tokio::test]
+    async fn test_profiles_need_the_profiling_feature() {
+        for path in ["/debug/pprof/profile?seconds=1", "/debug/pprof/heap"] {
+            assert_eq!(get(path).await.status(), StatusCode::NOT_IMPLEMENTED);
+        }
+    }
+
+    #[cfg(feature = "profiling")]
+    #[tokio::test]
+    async fn test_cpu_profile() {
+        //  Only one profile is taken at a time
+        CPU_PROFILE_RUNNING.store(true, Ordering::SeqCst);
+        let response = get("/debug/pprof/profile?seconds=1").await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        CPU_PROFILE_RUNNING.store(false, Ordering::SeqCst);
+
+        let response = get("/debug/pprof/profile?seconds=1&format=flamegraph").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "image/svg+xml"
+        );
+        assert!(!CPU_PROFILE_RUNNING.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,239 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::Request,
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use tonic::body::BoxBody;
+use tower::{Layer, Service};
+
+use crate::api::IndexifyAPIError;
+
+/// Routes a read only server serves: reads, and the POST routes that only
+/// query, like search, validating a graph or simulating the scheduler
+pub fn is_read_route(method: &Method, path: &str) -> bool {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match segments.as_slice() {
+        //  Extractors write their outputs over a websocket opened with a GET
+        ["write_content"] => false,
+        ["namespaces", _, "search" | "sql_query"] => true,
+        ["namespaces", _, "extraction_graphs", "validate"] => true,
+        ["namespaces", _, "extraction_graphs", _, "diff"] => true,
+        ["scheduler", "simulate"] | ["extractors", "extract"] => true,
+        _ => matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS),
+    }
+}
+
+/// Methods of the coordinator service a read only coordinator serves. New
+/// methods are rejected until they are listed here.
+const READ_RPCS: &[&str] = &[
+    "GetContentMetadata",
+    "GetContentTreeMetadata",
+    "ListContent",
+    "ValidateExtractionGraph",
+    "GetExtractionPolicy",
+    "ListExtractionPolicies",
+    "ListNS",
+    "GetNS",
+    "ListExtractors",
+    "ListExecutors",
+    "ListIndexes",
+    "GetIndex",
+    "GetExtractorCoordinates",
+    "ListStateChanges",
+    "ListTasks",
+    "ListExecutorTasks",
+    "ListTaskQueueDepths",
+    "GetSchema",
+    "ListSchemas",
+    "GetRaftMetricsSnapshot",
+    "GetAllTaskAssignments",
+    "GetTask",
+    "GetIngestionInfo",
+    "WaitContentExtraction",
+    "ListActiveContents",
+    "GetCancelTasksJob",
+    "ListStuckContent",
+    "ListStarvedTasks",
+    "GetTaskScheduling",
+    "ListReplays",
+    "GetReplay",
+    "GetInvocationBreakdown",
+    "ListGraphTemplates",
+    "GetGraphTemplate",
+    "GetContentReplica",
+    "GetContentContext",
+    "ListExtractionGraphVersions",
+    "GetSchedulerSettings",
+    "SimulateScheduling",
+    "GetMaintenanceMode",
+    "ListMeteringRecords",
+    "GetExtractionGraphUsage",
+    "GetSloCompliance",
+    "ExportNamespace",
+    "StreamContent",
+    "StreamTasks",
+];
+
+/// Whether a read only coordinator serves a gRPC request, from the last
+/// segment of its path
+fn is_read_rpc(path: &str) -> bool {
+    let method = path.rsplit('/').next().unwrap_or_default();
+    READ_RPCS.contains(&method)
+}
+
+pub async fn reject_writes(request: Request, next: Next) -> Result<Response, IndexifyAPIError> {
+    if !is_read_route(request.method(), request.uri().path()) {
+        return Err(IndexifyAPIError::new(
+            StatusCode::FORBIDDEN,
+            &format!(
+                "{} {} is not allowed, the server is read only",
+                request.method(),
+                request.uri().path()
+            ),
+        ));
+    }
+    Ok(next.run(request).await)
+}
+
+/// Rejects the gRPC methods of the coordinator that change its state
+#[derive(Debug, Clone, Default)]
+pub struct ReadOnlyLayer {
+    pub enabled: bool,
+}
+
+impl<S> Layer<S> for ReadOnlyLayer {
+    type Service = ReadOnlyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ReadOnlyService {
+            inner,
+            enabled: self.enabled,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReadOnlyService<S> {
+    inner: S,
+    enabled: bool,
+}
+
+type BoxFuture<T> = Pin<Box<dyn std::future::Future<Output = T> + Send + 'static>>;
+
+impl<S, ReqBody> Service<tonic::codegen::http::Request<ReqBody>> for ReadOnlyService<S>
+where
+    S: Service<
+            tonic::codegen::http::Request<ReqBody>,
+            Response = tonic::codegen::http::Response<BoxBody>,
+        > + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+{
+    type Error = S::Error;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+    type Response = S::Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: tonic::codegen::http::Request<ReqBody>) -> Self::Future {
+        if self.enabled && !is_read_rpc(req.uri().path()) {
+            let status = tonic::Status::failed_precondition(format!(
+                "{} is not allowed, the coordinator is read only",
+                req.uri().path()
+            ));
+            return Box::pin(async move { Ok(status.to_http()) });
+        }
+        let mut inner = self.inner.clone();
+        Box::pin(async move { inner.call(req).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_routes() {
+        assert!(is_read_route(&Method::GET, "/namespaces/default/content"));
+        assert!(is_read_route(&Method::POST, "/namespaces/default/search"));
+        assert!(is_read_route(
+            &Method::POST,
+            "/namespaces/default/extraction_graphs/rag/diff"
+        ));
+        assert!(is_read_route(&Method::POST, "/scheduler/simulate"));
+        assert!(!is_read_route(&Method::GET, "/write_content"));
+        assert!(!is_read_route(
+            &Method::POST,
+            "/namespaces/default/upload_file"
+        ));
+        assert!(!is_read_route(
+            &Method::DELETE,
+            "/namespaces/default/content/c1"
+        ));
+        assert!(!is_read_route(&Method::PUT, "/maintenance"));
+    }
+
+    #[test]
+    fn test_read_rpcs() {
+        //  Methods of the coordinator service that change its state
+        let write_rpcs = [
+            "CreateContent",
+            "TombstoneContent",
+            "CreateExtractionGraph",
+            "CreateNS",
+            "RegisterExecutor",
+            "DeregisterExecutor",
+            "RegisterIngestionServer",
+            "RemoveIngestionServer",
+            "CreateGCTasks",
+            "GCTasksStream",
+            "Heartbeat",
+            "UpdateIndexesState",
+            "UpdateTask",
+            "UpdateTasks",
+            "UpdateLabels",
+            "CancelTasks",
+            "RerunExtractionPolicy",
+            "RequeueTask",
+            "RepairContent",
+            "SetGraphTemplate",
+            "DeleteGraphTemplate",
+            "RerunFailedTasks",
+            "SetContentContext",
+            "RollbackExtractionGraph",
+            "SetSchedulerSettings",
+            "SetMaintenanceMode",
+            "RecordEgress",
+            "ImportNamespace",
+        ];
+
+        //  Every method of the service is either served or rejected on
+        //  purpose
+        let proto = include_str!("../protos/coordinator_service.proto");
+        let methods: Vec<&str> = proto
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("rpc "))
+            .map(|line| line.split('(').next().unwrap().trim())
+            .collect();
+        assert_eq!(methods.len(), READ_RPCS.len() + write_rpcs.len());
+        for method in methods {
+            let path = format!("/indexify_coordinator.CoordinatorService/{}", method);
+            assert_eq!(
+                is_read_rpc(&path),
+                !write_rpcs.contains(&method),
+                "{} is misclassified",
+                method
+            );
+        }
+    }
+}
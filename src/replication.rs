@@ -0,0 +1,280 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use anyhow::{anyhow, Result};
+use bytes::Bytes;
+use indexify_internal_api as internal_api;
+use sha2::{Digest, Sha256};
+use tracing::{error, info};
+
+use crate::{
+    blob_storage::{BlobStorage, BlobStorageWriter, ContentReader},
+    coordinator::Coordinator,
+    server_config::{ReplicationConfig, ServerConfig},
+    utils::timestamp_secs,
+};
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Copies the outputs of the last extraction policies of the graphs, and
+/// their metadata, to the blob store of another region. Runs on the leader in
+/// the background on the content the state machine queued as created, changed
+/// or deleted: a copy is read back from the other region and checked against
+/// the hash of the content before its replica is recorded, a new version of
+/// the content is copied again when its hash changed, and the replicas of
+/// deleted content are deleted.
+pub struct Replicator {
+    config: ReplicationConfig,
+    source: ContentReader,
+    destination: BlobStorage,
+    destination_reader: ContentReader,
+    running: AtomicBool,
+}
+
+impl Replicator {
+    pub fn new(server_config: &ServerConfig, config: &ReplicationConfig) -> Self {
+        //  Copies are read back with the credentials and region of the
+        //  destination
+        let destination_config = ServerConfig {
+            blob_storage: config.blob_storage.clone(),
+            ..server_config.clone()
+        };
+        Self {
+            config: config.clone(),
+            source: ContentReader::new(Arc::new(server_config.clone())),
+            destination: BlobStorage::new_with_config(config.blob_storage.clone()),
+            destination_reader: ContentReader::new(Arc::new(destination_config)),
+            running: AtomicBool::new(false),
+        }
+    }
+
+    fn key(&self, content: &internal_api::ContentMetadata) -> String {
+        format!(
+            "{}_{}_{}",
+            self.config.key_prefix, content.namespace, content.id.id
+        )
+    }
+
+    async fn put(&self, key: &str, data: Bytes) -> Result<String> {
+        let result = self
+            .destination
+            .put(key, futures::stream::iter(vec![Ok(data)]))
+            .await?;
+        Ok(result.url)
+    }
+
+    /// Copies a content and its metadata, and returns the replica once the
+    /// copy matches the hash of the content
+    pub async fn replicate(
+        &self,
+        content: &internal_api::ContentMetadata,
+    ) -> Result<internal_api::ContentReplica> {
+        let data = self.source.bytes(&content.storage_url).await?;
+        let hash = sha256_hex(&data);
+        if !content.hash.is_empty() && content.hash != hash {
+            return Err(anyhow!(
+                "content {} doesn't match its hash, expected {} got {}",
+                content.id,
+                content.hash,
+                hash
+            ));
+        }
+        let size_bytes = data.len() as u64;
+        let key = self.key(content);
+        let url = self.put(&key, data).await?;
+        let metadata = Bytes::from(serde_json::to_vec(content)?);
+        let metadata_url = self
+            .put(&format!("{}.metadata.json", key), metadata)
+            .await?;
+
+        let copy = self.destination_reader.bytes(&url).await?;
+        let copy_hash = sha256_hex(&copy);
+        if copy_hash != hash {
+            if let Err(err) = self.destination.delete(&url).await {
+                error!("error deleting replica {}: {:?}", url, err);
+            }
+            return Err(anyhow!(
+                "replica of content {} doesn't match its hash, expected {} got {}",
+                content.id,
+                hash,
+                copy_hash
+            ));
+        }
+        Ok(internal_api::ContentReplica {
+            content_id: content.id.id.clone(),
+            namespace: content.namespace.clone(),
+            region: self.config.region.clone(),
+            url,
+            metadata_url,
+            size_bytes,
+            hash,
+            replicated_at: timestamp_secs(),
+        })
+    }
+
+    async fn remove(&self, replica: &internal_api::ContentReplica) -> Result<()> {
+        self.destination.delete(&replica.url).await?;
+        self.destination.delete(&replica.metadata_url).await
+    }
+
+    /// Replicates a batch of the content that changed and removes the
+    /// replicas of deleted content. Returns right away while a previous run is
+    /// still going.
+    pub async fn run(&self, coordinator: &Coordinator) -> Result<()> {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        let result = self.run_once(coordinator).await;
+        self.running.store(false, Ordering::SeqCst);
+        result
+    }
+
+    async fn run_once(&self, coordinator: &Coordinator) -> Result<()> {
+        let pending = coordinator.pending_replications(self.config.batch_size)?;
+        if pending.is_empty() {
+            return Ok(());
+        }
+        let policies = coordinator
+            .replicated_policies(&self.config.extraction_graphs)
+            .await?;
+
+        //  Content that failed to be copied or removed stays pending, and is
+        //  tried again on the next run
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        let mut processed = HashMap::new();
+        for pending in pending {
+            match (&pending.content, &pending.replica) {
+                (Some(content), replica) if is_replicated_output(content, &policies) => {
+                    let current = replica
+                        .as_ref()
+                        .is_some_and(|replica| replica.hash == content.hash);
+                    if !current {
+                        match self.replicate(content).await {
+                            Ok(replica) => added.push(replica),
+                            Err(err) => {
+                                error!("error replicating content {}: {:?}", content.id, err);
+                                continue;
+                            }
+                        }
+                    }
+                    processed.insert(pending.content_id, Some(content.hash.clone()));
+                }
+                (Some(content), _) => {
+                    processed.insert(pending.content_id, Some(content.hash.clone()));
+                }
+                (None, Some(replica)) => {
+                    if let Err(err) = self.remove(replica).await {
+                        error!(
+                            "error removing replica of content {}: {:?}",
+                            replica.content_id, err
+                        );
+                        continue;
+                    }
+                    removed.push(pending.content_id.clone());
+                    processed.insert(pending.content_id, None);
+                }
+                (None, None) => {
+                    processed.insert(pending.content_id, None);
+                }
+            }
+        }
+        if !added.is_empty() {
+            info!(
+                "replicated {} content to region {}",
+                added.len(),
+                self.config.region
+            );
+        }
+        if !processed.is_empty() {
+            coordinator
+                .update_content_replicas(added, removed, processed)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether the content is an output of one of the extraction policies of
+/// `policies`, by namespace, graph and policy name
+fn is_replicated_output(
+    content: &internal_api::ContentMetadata,
+    policies: &HashSet<(String, String, String)>,
+) -> bool {
+    let internal_api::ContentSource::ExtractionPolicyName(policy) = &content.source else {
+        return false;
+    };
+    content
+        .extraction_graph_names
+        .iter()
+        .any(|graph| policies.contains(&(content.namespace.clone(), graph.clone(), policy.clone())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blob_storage::{BlobStorageConfig, DiskStorageConfig};
+
+    fn disk(path: &std::path::Path) -> BlobStorageConfig {
+        BlobStorageConfig {
+            s3: None,
+            disk: Some(DiskStorageConfig {
+                path: path.to_str().unwrap().to_string(),
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replicate() {
+        let primary = tempfile::tempdir().unwrap();
+        let secondary = tempfile::tempdir().unwrap();
+        let source_path = primary.path().join("embedding");
+        std::fs::write(&source_path, b"[0.1, 0.2]").unwrap();
+        let config = ReplicationConfig {
+            region: "eu-west-1".to_string(),
+            blob_storage: disk(secondary.path()),
+            extraction_graphs: vec![],
+            key_prefix: "replica".to_string(),
+            interval_secs: 30,
+            batch_size: 100,
+        };
+        let server_config = ServerConfig {
+            blob_storage: disk(primary.path()),
+            ..Default::default()
+        };
+        let replicator = Replicator::new(&server_config, &config);
+        let mut content = internal_api::ContentMetadata {
+            id: internal_api::ContentMetadataId::new("embedding_1"),
+            namespace: "default".to_string(),
+            storage_url: format!("file://{}", source_path.to_str().unwrap()),
+            hash: sha256_hex(b"[0.1, 0.2]"),
+            ..Default::default()
+        };
+
+        let replica = replicator.replicate(&content).await.unwrap();
+        assert_eq!(replica.region, "eu-west-1");
+        assert_eq!(replica.size_bytes, 10);
+        assert!(replica.url.ends_with("replica_default_embedding_1"));
+        let copy = std::fs::read(replica.url.trim_start_matches("file://")).unwrap();
+        assert_eq!(copy, b"[0.1, 0.2]");
+        let metadata: internal_api::ContentMetadata = serde_json::from_slice(
+            &std::fs::read(replica.metadata_url.trim_start_matches("file://")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(metadata, content);
+
+        //  Content that doesn't match its hash isn't copied
+        content.hash = sha256_hex(b"other");
+        assert!(replicator.replicate(&content).await.is_err());
+
+        replicator.remove(&replica).await.unwrap();
+        assert!(!std::path::Path::new(replica.url.trim_start_matches("file://")).exists());
+    }
+}
@@ -1,16 +1,18 @@
 use std::{
-    collections::{hash_map::DefaultHasher, HashMap},
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
     hash::{Hash, Hasher},
+    sync::atomic::{AtomicBool, Ordering},
 };
 
 use anyhow::{anyhow, Ok, Result};
 use indexify_internal_api as internal_api;
 use indexify_internal_api::StateChange;
 use internal_api::OutputSchema;
+use tokio::sync::Mutex;
 use tracing::info;
 
 use crate::{
-    state::SharedState,
+    state::{store::TaskId, SharedState},
     task_allocator::{planner::plan::TaskAllocationPlan, TaskAllocator},
     utils::timestamp_secs,
 };
@@ -18,16 +20,92 @@ use crate::{
 pub struct Scheduler {
     shared_state: SharedState,
     task_allocator: TaskAllocator,
+    /// Held while root content is checked against the caps of active content
+    /// of its graphs and its tasks are created, so content of different
+    /// scheduler shards can't be admitted past a cap together
+    admission: Mutex<()>,
+    /// Held from planning tasks onto executors until their assignments are
+    /// committed, so the scheduler shards plan against the capacity the
+    /// others left instead of all taking the same free slots
+    allocation: Mutex<()>,
+    /// Root content that is waiting for other content of its graphs to finish
+    queued_content: std::sync::Mutex<HashSet<String>>,
+    /// Unassigned tasks a pass over them places at most
+    max_tasks_per_pass: Option<usize>,
+    /// Set when a pass left unassigned tasks over its maximum
+    tasks_left: AtomicBool,
 }
 
 impl Scheduler {
-    pub fn new(shared_state: SharedState, task_allocator: TaskAllocator) -> Self {
+    pub fn new(
+        shared_state: SharedState,
+        task_allocator: TaskAllocator,
+        max_tasks_per_pass: Option<usize>,
+    ) -> Self {
         Scheduler {
             shared_state,
             task_allocator,
+            admission: Mutex::new(()),
+            allocation: Mutex::new(()),
+            queued_content: Default::default(),
+            max_tasks_per_pass,
+            tasks_left: AtomicBool::new(false),
         }
     }
 
+    /// The tasks a pass places, the others are placed by the next pass
+    fn take_pass(&self, task_ids: HashSet<TaskId>) -> HashSet<TaskId> {
+        match self.max_tasks_per_pass {
+            Some(max) if task_ids.len() > max => {
+                self.tasks_left.store(true, Ordering::Relaxed);
+                task_ids.into_iter().take(max.max(1)).collect()
+            }
+            _ => task_ids,
+        }
+    }
+
+    /// Extraction graph id and cap of active content of the graphs of root
+    /// content that have a cap
+    fn capped_graphs(
+        &self,
+        content: &internal_api::ContentMetadata,
+    ) -> Result<Vec<(String, usize)>> {
+        if content.parent_id.is_some() {
+            return Ok(Vec::new());
+        }
+        Ok(self
+            .shared_state
+            .get_extraction_graphs_by_name(&content.namespace, &content.extraction_graph_names)?
+            .into_iter()
+            .flatten()
+            .filter_map(|graph| graph.max_active_content.map(|max| (graph.id, max as usize)))
+            .collect())
+    }
+
+    fn must_queue(
+        &self,
+        content: &internal_api::ContentMetadata,
+        capped_graphs: &[(String, usize)],
+    ) -> bool {
+        capped_graphs.iter().any(|(graph_id, max)| {
+            let active = self
+                .shared_state
+                .state_machine
+                .active_root_content(graph_id);
+            !active.contains(&content.id.id) && active.len() >= *max
+        })
+    }
+
+    /// Returns whether any root content was queued, and forgets it. Queued
+    /// content is queued again when its state change is processed and its
+    /// graphs are still at their cap.
+    pub fn take_queued_content(&self) -> bool {
+        let mut queued_content = self.queued_content.lock().unwrap();
+        let queued = !queued_content.is_empty();
+        queued_content.clear();
+        queued
+    }
+
     async fn tables_for_policies(
         &self,
         policies: &[internal_api::ExtractionPolicy],
@@ -71,9 +149,16 @@ impl Scheduler {
         }
     }
 
-    pub async fn handle_executor_removed(&self, state_change: StateChange) -> Result<()> {
-        let tasks = self.shared_state.unassigned_tasks().await?;
-        let plan = self.allocate_tasks(tasks).await?.0;
+    /// Allocates every unassigned task, after an executor was removed or
+    /// orphaned tasks were put back into the unassigned tasks
+    pub async fn allocate_unassigned_tasks(&self, state_change: StateChange) -> Result<()> {
+        let _allocation = self.allocation.lock().await;
+        let task_ids = self.take_pass(self.shared_state.unassigned_task_ids().await);
+        let plan = self
+            .task_allocator
+            .allocate_tasks(task_ids)
+            .await
+            .map_err(|e| anyhow!("allocate_tasks: {}", e))?;
         if !plan.is_empty() {
             self.shared_state
                 .commit_task_assignments(plan, state_change.id)
@@ -85,6 +170,61 @@ impl Scheduler {
         }
     }
 
+    /// Allocates the tasks that were held back by the rate limit of their
+    /// extraction policy, by the limit of tasks per executor, or by the
+    /// maximum of tasks of a pass. Returns the number of tasks assigned.
+    pub async fn allocate_throttled_tasks(&self) -> Result<usize> {
+        let tasks_left = self.tasks_left.swap(false, Ordering::Relaxed);
+        if tasks_left ||
+            self.shared_state
+                .get_scheduler_settings()?
+                .max_tasks_per_executor
+                .is_some()
+        {
+            return self.allocate_held_back_tasks().await;
+        }
+        let task_ids = self.shared_state.state_machine.get_throttled_tasks();
+        self.assign_tasks(task_ids).await
+    }
+
+    /// Allocates the unassigned tasks of extractors that have executors, which
+    /// the limit of tasks per executor held back. Tasks of extractors without
+    /// executors are allocated when an executor registers.
+    pub async fn allocate_held_back_tasks(&self) -> Result<usize> {
+        let unassigned = self.shared_state.unassigned_task_ids().await;
+        let task_ids = self
+            .shared_state
+            .group_unfinished_tasks_by_extractor(&unassigned)
+            .into_iter()
+            .filter(|(extractor_name, _)| {
+                !self
+                    .shared_state
+                    .get_executor_loads_for_extractor(extractor_name)
+                    .is_empty()
+            })
+            .flat_map(|(_, task_ids)| task_ids.into_iter().cloned())
+            .collect();
+        self.assign_tasks(task_ids).await
+    }
+
+    async fn assign_tasks(&self, task_ids: HashSet<TaskId>) -> Result<usize> {
+        if task_ids.is_empty() {
+            return Ok(0);
+        }
+        let task_ids = self.take_pass(task_ids);
+        let _allocation = self.allocation.lock().await;
+        let plan = self
+            .task_allocator
+            .allocate_tasks(task_ids)
+            .await
+            .map_err(|e| anyhow!("allocate_tasks: {}", e))?;
+        let assigned = plan.len();
+        if !plan.is_empty() {
+            self.shared_state.assign_tasks(plan).await?;
+        }
+        Ok(assigned)
+    }
+
     pub async fn create_new_tasks(&self, state_change: StateChange) -> Result<()> {
         let mut tasks: Vec<internal_api::Task> = Vec::new();
         let content = match self
@@ -100,6 +240,26 @@ impl Scheduler {
                     .await
             }
         };
+        let capped_graphs = self.capped_graphs(&content)?;
+        let admission = if capped_graphs.is_empty() {
+            None
+        } else {
+            let admission = self.admission.lock().await;
+            if self.must_queue(&content, &capped_graphs) {
+                //  The state change stays unprocessed until the content is
+                //  admitted
+                info!(
+                    "queueing content {}, its graph is at its cap of active content",
+                    content.id
+                );
+                self.queued_content
+                    .lock()
+                    .unwrap()
+                    .insert(content.id.id.clone());
+                return Ok(());
+            }
+            Some(admission)
+        };
         let extraction_policies = self
             .shared_state
             .match_extraction_policies_for_content(&content)
@@ -124,16 +284,88 @@ impl Scheduler {
         self.shared_state
             .create_tasks(tasks.clone(), state_change.id)
             .await?;
+        drop(admission);
+        let _allocation = self.allocation.lock().await;
         let allocation_plan = self.allocate_tasks(tasks).await?;
-        if !allocation_plan.0.is_empty() {
+        if !allocation_plan.is_empty() {
             self.shared_state
-                .commit_task_assignments(allocation_plan.0, state_change.id)
+                .commit_task_assignments(allocation_plan, state_change.id)
                 .await
         } else {
             Ok(())
         }
     }
 
+    pub async fn rerun_extraction_policy(
+        &self,
+        state_change: StateChange,
+        extraction_policy_id: &str,
+    ) -> Result<()> {
+        let content = match self
+            .shared_state
+            .state_machine
+            .get_latest_version_of_content(&state_change.object_id)?
+        {
+            Some(content) if !content.tombstoned => content,
+            _ => {
+                return self
+                    .shared_state
+                    .mark_change_events_as_processed(vec![state_change], Vec::new())
+                    .await
+            }
+        };
+        let extraction_policy = self
+            .shared_state
+            .get_extraction_policy(extraction_policy_id)?;
+        let tables = self.tables_for_policies(&[extraction_policy]).await?;
+        // Task ids are derived from the policy and the content, so this replaces
+        // the finished task of the previous run.
+        let task = self
+            .create_task(extraction_policy_id, &content, &tables)
+            .await?;
+        self.shared_state
+            .create_tasks(vec![task.clone()], state_change.id)
+            .await?;
+        let _allocation = self.allocation.lock().await;
+        let allocation_plan = self.allocate_tasks(vec![task]).await?;
+        if !allocation_plan.is_empty() {
+            self.shared_state
+                .commit_task_assignments(allocation_plan, state_change.id)
+                .await
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Creates the tasks of the extraction policies that apply to `content`
+    /// but never finished on it
+    pub async fn create_missing_tasks(
+        &self,
+        content: &internal_api::ContentMetadata,
+    ) -> Result<Vec<internal_api::Task>> {
+        let extraction_policies: Vec<_> = self
+            .shared_state
+            .match_extraction_policies_for_content(content)
+            .await?
+            .into_iter()
+            .filter(|policy| {
+                content
+                    .extraction_policy_ids
+                    .get(&policy.id)
+                    .map_or(true, |completed_at| *completed_at == 0)
+            })
+            .collect();
+        let tables = self.tables_for_policies(&extraction_policies).await?;
+        let mut tasks = Vec::new();
+        for extraction_policy in &extraction_policies {
+            tasks.push(
+                self.create_task(&extraction_policy.id, content, &tables)
+                    .await?,
+            );
+        }
+        Ok(tasks)
+    }
+
     pub async fn allocate_tasks(
         &self,
         tasks: Vec<internal_api::Task>,
@@ -159,22 +391,19 @@ impl Scheduler {
             .map(|extractor| extractor.name.clone())
             .collect::<Vec<String>>();
 
-        // This HashMap is used to aggregate the task re-allocation
-        // plan for each extractor in the executor.
-        let mut task_allocation_plan = HashMap::new();
+        // Aggregates the task re-allocation plan of each extractor in the
+        // executor.
+        let mut task_allocation_plan = TaskAllocationPlan::default();
 
+        let _allocation = self.allocation.lock().await;
         for extractor_name in extractor_names {
             let plan = self
                 .task_allocator
-                .reallocate_all_tasks_matching_extractor(&extractor_name)
+                .allocate_unassigned_tasks_matching_extractor(&extractor_name)
                 .await
                 .map_err(|e| anyhow!("redistribute_tasks: {}", e))?;
 
-            // Transfer the task id and executor id from the plan to the
-            // aggregated task allocation plan.
-            for (task_id, executor_id) in plan.0 {
-                task_allocation_plan.insert(task_id, executor_id);
-            }
+            task_allocation_plan.merge(plan);
         }
 
         if !task_allocation_plan.is_empty() {
@@ -207,11 +436,13 @@ impl Scheduler {
             output_mapping.insert(name.clone(), table_name.clone());
         }
 
-        let mut hasher = DefaultHasher::new();
-        extraction_policy.name.hash(&mut hasher);
-        extraction_policy.namespace.hash(&mut hasher);
-        content.id.hash(&mut hasher);
-        let id = format!("{:x}", hasher.finish());
+        let id = task_id(&extraction_policy, &content.id);
+        let graph_version = self.shared_state.get_extraction_graph_version(
+            &internal_api::ExtractionGraph::create_id(
+                &extraction_policy.graph_name,
+                &extraction_policy.namespace,
+            ),
+        )?;
         let task = internal_api::Task {
             id,
             extractor: extraction_policy.extractor.clone(),
@@ -223,8 +454,29 @@ impl Scheduler {
             input_params: extraction_policy.input_params.clone(),
             outcome: internal_api::TaskOutcome::Unknown,
             index_tables: index_tables.to_vec(),
+            lost_by_executors: Vec::new(),
+            diagnostics: None,
+            usage: None,
+            progress: None,
+            gpu_millis: extraction_policy.gpu_millis,
+            gpu_device: None,
+            burst: false,
+            graph_version,
         };
         info!("created task: {:?}", task);
         Ok(task)
     }
 }
+
+/// Id of the task of an extraction policy on a content. Running the policy on
+/// the content again replaces the task with the same id.
+pub fn task_id(
+    extraction_policy: &internal_api::ExtractionPolicy,
+    content_id: &internal_api::ContentMetadataId,
+) -> TaskId {
+    let mut hasher = DefaultHasher::new();
+    extraction_policy.name.hash(&mut hasher);
+    extraction_policy.namespace.hash(&mut hasher);
+    content_id.hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
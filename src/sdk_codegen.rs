@@ -0,0 +1,1266 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+const JSON: &str = "application/json";
+const JSON_LINES: &str = "application/x-ndjson";
+const MULTIPART: &str = "multipart/form-data";
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Names of the arguments the generated methods take besides the parameters
+/// of an operation
+const RESERVED_ARGUMENTS: &[&str] = &["self", "body", "files", "data", "content", "query"];
+
+/// Methods of the clients written by hand, generated methods with the same
+/// name get a suffix
+const HELPERS: &[&str] = &["invoke", "wait", "outputs", "close"];
+
+#[derive(Debug)]
+enum RequestBody {
+    Json(Value),
+    Multipart,
+    Bytes,
+}
+
+#[derive(Debug)]
+enum ResponseBody {
+    /// JSON of an unknown type when the schema doesn't describe it
+    Json(Option<Value>),
+    /// JSON lines, read as they are written
+    Lines(Option<Value>),
+    Bytes,
+}
+
+#[derive(Debug)]
+struct QueryParam {
+    name: String,
+    schema: Option<Value>,
+    required: bool,
+}
+
+#[derive(Debug)]
+struct Operation {
+    name: String,
+    method: String,
+    /// Path with its parameters as `{name}`
+    path: String,
+    doc: Option<String>,
+    path_params: Vec<String>,
+    query_params: Vec<QueryParam>,
+    body: Option<RequestBody>,
+    response: ResponseBody,
+}
+
+/// The OpenAPI document of the server, read into what the clients are
+/// generated from
+#[derive(Debug)]
+pub struct ApiSchema {
+    version: String,
+    /// Type names of the component schemas, by their name in the document
+    type_names: HashMap<String, String>,
+    /// Component schemas ordered by type name
+    schemas: Vec<(String, Value)>,
+    operations: Vec<Operation>,
+}
+
+impl ApiSchema {
+    pub fn parse(openapi: &Value) -> Result<Self> {
+        let version = openapi
+            .pointer("/info/version")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let components = openapi
+            .pointer("/components/schemas")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        let type_names: HashMap<String, String> = components
+            .keys()
+            .map(|name| (name.clone(), type_name(name)))
+            .collect();
+        let mut schemas: Vec<(String, Value)> = components
+            .into_iter()
+            .map(|(name, schema)| (type_names[&name].clone(), schema))
+            .collect();
+        schemas.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let paths = openapi
+            .get("paths")
+            .and_then(Value::as_object)
+            .ok_or_else(|| anyhow!("the api schema has no paths"))?;
+        let mut names: HashSet<String> = HELPERS.iter().map(|name| name.to_string()).collect();
+        let mut operations = Vec::new();
+        for (path, item) in paths {
+            for method in ["get", "put", "post", "patch", "delete"] {
+                let Some(operation) = item.get(method) else {
+                    continue;
+                };
+                let base_name = operation
+                    .get("operationId")
+                    .and_then(Value::as_str)
+                    .map(identifier)
+                    .unwrap_or_else(|| identifier(&format!("{}_{}", method, path)));
+                let mut name = base_name.clone();
+                let mut suffix = 2;
+                while !names.insert(name.clone()) {
+                    name = format!("{}_{}", base_name, suffix);
+                    suffix += 1;
+                }
+                operations.push(Operation::parse(name, method, path, operation));
+            }
+        }
+        Ok(Self {
+            version,
+            type_names,
+            schemas,
+            operations,
+        })
+    }
+
+    /// Type name a `$ref` points to, `None` when the schema isn't a reference
+    /// and `Some(None)` when it points to a schema the document doesn't have
+    fn reference(&self, schema: &Value) -> Option<Option<&str>> {
+        let reference = schema.get("$ref")?.as_str().unwrap_or_default();
+        Some(
+            reference
+                .strip_prefix("#/components/schemas/")
+                .and_then(|name| self.type_names.get(name))
+                .map(String::as_str),
+        )
+    }
+
+    pub fn python(&self) -> String {
+        let mut lines = vec![
+            format!(
+                "# Generated by `indexify sdk` from the API schema of Indexify {}, do not edit.",
+                self.version
+            ),
+            "import json".to_string(),
+            "from typing import Any, Dict, Iterator, List, Literal, Optional, Union".to_string(),
+            "from urllib.parse import quote".to_string(),
+            String::new(),
+            "import httpx".to_string(),
+            "from typing_extensions import NotRequired, TypedDict".to_string(),
+        ];
+        for (name, schema) in &self.schemas {
+            lines.extend([String::new(), String::new()]);
+            lines.extend(self.python_definition(name, schema));
+        }
+        lines.extend([String::new(), String::new()]);
+        lines.push(PYTHON_CLIENT.trim().to_string());
+        for operation in &self.operations {
+            lines.push(String::new());
+            lines.extend(self.python_method(operation));
+        }
+        lines.push(String::new());
+        lines.push(PYTHON_HELPERS.trim_matches('\n').to_string());
+        lines.join("\n") + "\n"
+    }
+
+    pub fn typescript(&self) -> String {
+        let mut lines = vec![
+            format!(
+                "// Generated by `indexify sdk` from the API schema of Indexify {}, do not edit.",
+                self.version
+            ),
+            String::new(),
+        ];
+        for (name, schema) in &self.schemas {
+            lines.extend(self.typescript_definition(name, schema));
+            lines.push(String::new());
+        }
+        lines.push(TYPESCRIPT_CLIENT.trim().to_string());
+        for operation in &self.operations {
+            lines.push(String::new());
+            lines.extend(self.typescript_method(operation));
+        }
+        lines.push(String::new());
+        lines.push(TYPESCRIPT_HELPERS.trim_matches('\n').to_string());
+        lines.push("}".to_string());
+        lines.join("\n") + "\n"
+    }
+
+    /// Python type of a schema. Names of other schemas are quoted, so the
+    /// types can be used before they are defined.
+    fn python_type(&self, schema: &Value) -> String {
+        let ty = self.python_base_type(schema);
+        if is_nullable(schema) && ty != "Any" {
+            format!("Optional[{}]", ty)
+        } else {
+            ty
+        }
+    }
+
+    fn python_base_type(&self, schema: &Value) -> String {
+        if let Some(name) = self.reference(schema) {
+            return name.map_or("Any".to_string(), |name| format!("\"{}\"", name));
+        }
+        if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+            return match parts.as_slice() {
+                [part] => self.python_type(part),
+                _ => "Dict[str, Any]".to_string(),
+            };
+        }
+        if let Some(variants) = variants(schema) {
+            let types = dedup(variants.iter().map(|variant| self.python_type(variant)));
+            return match types.as_slice() {
+                [ty] => ty.clone(),
+                _ if types.iter().any(|ty| ty == "Any") => "Any".to_string(),
+                _ => format!("Union[{}]", types.join(", ")),
+            };
+        }
+        if let Some(literals) = literals(schema) {
+            return format!("Literal[{}]", literals.join(", "));
+        }
+        match schema.get("type").and_then(Value::as_str) {
+            Some("string") => "str".to_string(),
+            Some("integer") => "int".to_string(),
+            Some("number") => "float".to_string(),
+            Some("boolean") => "bool".to_string(),
+            Some("array") => format!(
+                "List[{}]",
+                schema
+                    .get("items")
+                    .map_or("Any".to_string(), |items| self.python_type(items))
+            ),
+            Some("object") => match schema.get("additionalProperties") {
+                Some(values @ Value::Object(_)) => {
+                    format!("Dict[str, {}]", self.python_type(values))
+                }
+                _ => "Dict[str, Any]".to_string(),
+            },
+            _ => "Any".to_string(),
+        }
+    }
+
+    fn python_definition(&self, name: &str, schema: &Value) -> Vec<String> {
+        let Some(properties) = properties(schema) else {
+            return vec![format!("{} = {}", name, self.python_type(schema))];
+        };
+        let required = required(schema);
+        let fields: Vec<(&String, String)> = properties
+            .iter()
+            .map(|(property, property_schema)| {
+                let ty = self.python_type(property_schema);
+                let ty = if required.contains(property.as_str()) {
+                    ty
+                } else {
+                    format!("NotRequired[{}]", ty)
+                };
+                (property, ty)
+            })
+            .collect();
+        //  Fields that aren't identifiers need the functional syntax
+        if properties
+            .keys()
+            .any(|property| !is_python_identifier(property))
+        {
+            let mut lines = vec![
+                format!("{} = TypedDict(", name),
+                format!("    \"{}\",", name),
+            ];
+            lines.push("    {".to_string());
+            for (property, ty) in fields {
+                lines.push(format!(
+                    "        {}: {},",
+                    Value::from(property.as_str()),
+                    ty
+                ));
+            }
+            lines.push("    },".to_string());
+            lines.push(")".to_string());
+            return lines;
+        }
+        let mut lines = vec![format!("class {}(TypedDict):", name)];
+        if let Some(doc) = description(schema) {
+            lines.extend(python_docstring(&doc, "    "));
+        }
+        for (property, ty) in fields {
+            if let Some(doc) = properties.get(property).and_then(description) {
+                lines.extend(
+                    doc.lines()
+                        .map(|line| format!("    #: {}", line).trim_end().to_string()),
+                );
+            }
+            lines.push(format!("    {}: {}", property, ty));
+        }
+        lines
+    }
+
+    fn python_method(&self, operation: &Operation) -> Vec<String> {
+        let mut arguments = vec!["self".to_string()];
+        for param in &operation.path_params {
+            arguments.push(format!("{}: str", python_argument(param)));
+        }
+        let mut call_arguments = Vec::new();
+        match &operation.body {
+            Some(RequestBody::Json(schema)) => {
+                arguments.push(format!("body: {}", self.python_type(schema)));
+                call_arguments.push("json=body".to_string());
+            }
+            Some(RequestBody::Multipart) => {
+                arguments.push("files: Dict[str, Any]".to_string());
+                arguments.push("data: Optional[Dict[str, str]] = None".to_string());
+                call_arguments.push("files=files".to_string());
+                call_arguments.push("data=data".to_string());
+            }
+            Some(RequestBody::Bytes) => {
+                arguments.push("content: bytes".to_string());
+                call_arguments.push("content=content".to_string());
+            }
+            None => {}
+        }
+        if !operation.query_params.is_empty() {
+            arguments.push("*".to_string());
+            let mut query_params: Vec<&QueryParam> = operation.query_params.iter().collect();
+            query_params.sort_by_key(|param| !param.required);
+            for param in query_params {
+                let ty = param
+                    .schema
+                    .as_ref()
+                    .map_or("Any".to_string(), |schema| self.python_type(schema));
+                let argument = python_argument(&param.name);
+                if param.required {
+                    arguments.push(format!("{}: {}", argument, ty));
+                } else if ty.starts_with("Optional[") || ty == "Any" {
+                    arguments.push(format!("{}: {} = None", argument, ty));
+                } else {
+                    arguments.push(format!("{}: Optional[{}] = None", argument, ty));
+                }
+            }
+            let params: Vec<String> = operation
+                .query_params
+                .iter()
+                .map(|param| format!("\"{}\": {}", param.name, python_argument(&param.name)))
+                .collect();
+            call_arguments.insert(0, format!("params={{{}}}", params.join(", ")));
+        }
+
+        let mut path = operation.path.clone();
+        for param in &operation.path_params {
+            path = path.replace(
+                &format!("{{{}}}", param),
+                &format!("{{_quote({})}}", python_argument(param)),
+            );
+        }
+        let path = if operation.path_params.is_empty() {
+            format!("\"{}\"", path)
+        } else {
+            format!("f\"{}\"", path)
+        };
+        let (returns, send, result) = match &operation.response {
+            ResponseBody::Json(schema) => (
+                schema
+                    .as_ref()
+                    .map_or("Any".to_string(), |schema| self.python_type(schema)),
+                "_send",
+                "self._json",
+            ),
+            ResponseBody::Lines(schema) => (
+                format!(
+                    "Iterator[{}]",
+                    schema
+                        .as_ref()
+                        .map_or("Any".to_string(), |schema| self.python_type(schema))
+                ),
+                "_lines",
+                "",
+            ),
+            ResponseBody::Bytes => ("bytes".to_string(), "_send", "_content"),
+        };
+        let call = format!(
+            "self.{}({})",
+            send,
+            [format!("\"{}\"", operation.method.to_uppercase()), path]
+                .into_iter()
+                .chain(call_arguments)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let call = match result {
+            "" => call,
+            "_content" => format!("{}.content", call),
+            _ => format!("{}({})", result, call),
+        };
+
+        let mut lines = vec![format!(
+            "    def {}({}) -> {}:",
+            operation.name,
+            arguments.join(", "),
+            returns
+        )];
+        if let Some(doc) = &operation.doc {
+            lines.extend(python_docstring(doc, "        "));
+        }
+        lines.push(format!("        return {}", call));
+        lines
+    }
+
+    fn typescript_type(&self, schema: &Value) -> String {
+        let ty = self.typescript_base_type(schema);
+        if is_nullable(schema) && ty != "unknown" {
+            format!("{} | null", ty)
+        } else {
+            ty
+        }
+    }
+
+    fn typescript_base_type(&self, schema: &Value) -> String {
+        if let Some(name) = self.reference(schema) {
+            return name.unwrap_or("unknown").to_string();
+        }
+        if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+            let types = dedup(parts.iter().map(|part| self.typescript_operand(part)));
+            return types.join(" & ");
+        }
+        if let Some(variants) = variants(schema) {
+            let types = dedup(variants.iter().map(|variant| self.typescript_type(variant)));
+            return types.join(" | ");
+        }
+        if let Some(literals) = literals(schema) {
+            return literals.join(" | ");
+        }
+        if let Some(properties) = properties(schema) {
+            let required = required(schema);
+            let fields: Vec<String> = properties
+                .iter()
+                .map(|(property, property_schema)| {
+                    format!(
+                        "{}{}: {}",
+                        typescript_property(property),
+                        if required.contains(property.as_str()) {
+                            ""
+                        } else {
+                            "?"
+                        },
+                        self.typescript_type(property_schema)
+                    )
+                })
+                .collect();
+            return format!("{{ {} }}", fields.join("; "));
+        }
+        match schema.get("type").and_then(Value::as_str) {
+            Some("string") => "string".to_string(),
+            Some("integer") | Some("number") => "number".to_string(),
+            Some("boolean") => "boolean".to_string(),
+            Some("array") => format!(
+                "Array<{}>",
+                schema
+                    .get("items")
+                    .map_or("unknown".to_string(), |items| self.typescript_type(items))
+            ),
+            Some("object") => match schema.get("additionalProperties") {
+                Some(values @ Value::Object(_)) => {
+                    format!("Record<string, {}>", self.typescript_type(values))
+                }
+                _ => "Record<string, unknown>".to_string(),
+            },
+            _ => "unknown".to_string(),
+        }
+    }
+
+    /// Type of a part of an intersection, unions are parenthesized
+    fn typescript_operand(&self, schema: &Value) -> String {
+        let ty = self.typescript_type(schema);
+        if ty.contains(" | ") {
+            format!("({})", ty)
+        } else {
+            ty
+        }
+    }
+
+    fn typescript_definition(&self, name: &str, schema: &Value) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(doc) = description(schema) {
+            lines.extend(typescript_doc(&doc, ""));
+        }
+        let Some(properties) = properties(schema) else {
+            lines.push(format!(
+                "export type {} = {};",
+                name,
+                self.typescript_type(schema)
+            ));
+            return lines;
+        };
+        let required = required(schema);
+        lines.push(format!("export interface {} {{", name));
+        for (property, property_schema) in properties {
+            if let Some(doc) = description(property_schema) {
+                lines.extend(typescript_doc(&doc, "  "));
+            }
+            lines.push(format!(
+                "  {}{}: {};",
+                typescript_property(property),
+                if required.contains(property.as_str()) {
+                    ""
+                } else {
+                    "?"
+                },
+                self.typescript_type(property_schema)
+            ));
+        }
+        lines.push("}".to_string());
+        lines
+    }
+
+    fn typescript_method(&self, operation: &Operation) -> Vec<String> {
+        let mut arguments: Vec<String> = operation
+            .path_params
+            .iter()
+            .map(|param| format!("{}: string", camel_case(param)))
+            .collect();
+        let mut options = Vec::new();
+        match &operation.body {
+            Some(RequestBody::Json(schema)) => {
+                arguments.push(format!("body: {}", self.typescript_type(schema)));
+                options.push("json: body".to_string());
+            }
+            Some(RequestBody::Multipart) => {
+                arguments.push("body: FormData".to_string());
+                options.push("body".to_string());
+            }
+            Some(RequestBody::Bytes) => {
+                arguments.push("body: BodyInit".to_string());
+                options.push("body".to_string());
+            }
+            None => {}
+        }
+        if !operation.query_params.is_empty() {
+            let fields: Vec<String> = operation
+                .query_params
+                .iter()
+                .map(|param| {
+                    let ty = param
+                        .schema
+                        .as_ref()
+                        .map_or("unknown".to_string(), |schema| self.typescript_type(schema));
+                    if param.required {
+                        format!("{}: {}", typescript_property(&param.name), ty)
+                    } else {
+                        format!("{}?: {}", typescript_property(&param.name), ty)
+                    }
+                })
+                .collect();
+            let initializer = if operation.query_params.iter().any(|param| param.required) {
+                ""
+            } else {
+                " = {}"
+            };
+            arguments.push(format!("query: {{ {} }}{}", fields.join("; "), initializer));
+            options.insert(0, "query".to_string());
+        }
+
+        let mut path = operation.path.clone();
+        for param in &operation.path_params {
+            path = path.replace(
+                &format!("{{{}}}", param),
+                &format!("${{encodeURIComponent({})}}", camel_case(param)),
+            );
+        }
+        let send = format!(
+            "await this.send(\"{}\", `{}`{})",
+            operation.method.to_uppercase(),
+            path,
+            if options.is_empty() {
+                String::new()
+            } else {
+                format!(", {{ {} }}", options.join(", "))
+            }
+        );
+        let name = camel_case(&operation.name);
+        let (signature, body) = match &operation.response {
+            ResponseBody::Json(schema) => {
+                let ty = schema
+                    .as_ref()
+                    .map_or("unknown".to_string(), |schema| self.typescript_type(schema));
+                (
+                    format!("async {}({}): Promise<{}>", name, arguments.join(", "), ty),
+                    format!("return this.json<{}>({});", ty, send),
+                )
+            }
+            ResponseBody::Lines(schema) => {
+                let ty = schema
+                    .as_ref()
+                    .map_or("unknown".to_string(), |schema| self.typescript_type(schema));
+                (
+                    format!(
+                        "async *{}({}): AsyncGenerator<{}>",
+                        name,
+                        arguments.join(", "),
+                        ty
+                    ),
+                    format!("yield* this.lines<{}>({});", ty, send),
+                )
+            }
+            ResponseBody::Bytes => (
+                format!(
+                    "async {}({}): Promise<ArrayBuffer>",
+                    name,
+                    arguments.join(", ")
+                ),
+                format!("return ({}).arrayBuffer();", send),
+            ),
+        };
+
+        let mut lines = Vec::new();
+        if let Some(doc) = &operation.doc {
+            lines.extend(typescript_doc(doc, "  "));
+        }
+        lines.push(format!("  {} {{", signature));
+        lines.push(format!("    {}", body));
+        lines.push("  }".to_string());
+        lines
+    }
+}
+
+impl Operation {
+    fn parse(name: String, method: &str, path: &str, operation: &Value) -> Self {
+        //  Parameters are taken from the path, the document may not list them
+        let mut path_params = Vec::new();
+        let segments: Vec<String> = path
+            .split('/')
+            .map(|segment| {
+                let param = segment
+                    .strip_prefix('{')
+                    .and_then(|segment| segment.strip_suffix('}'))
+                    .or_else(|| segment.strip_prefix(':'));
+                match param {
+                    Some(param) => {
+                        path_params.push(param.to_string());
+                        format!("{{{}}}", param)
+                    }
+                    None => segment.to_string(),
+                }
+            })
+            .collect();
+        let query_params = operation
+            .get("parameters")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter(|param| param.get("in").and_then(Value::as_str) == Some("query"))
+            .filter_map(|param| {
+                Some(QueryParam {
+                    name: param.get("name")?.as_str()?.to_string(),
+                    schema: param.get("schema").cloned(),
+                    required: param.get("required").and_then(Value::as_bool) == Some(true),
+                })
+            })
+            .collect();
+        //  The description may repeat the summary
+        let text = |key: &str| {
+            operation
+                .get(key)
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(str::to_string)
+        };
+        let doc = match (text("summary"), text("description")) {
+            (Some(summary), Some(description)) if !description.starts_with(&summary) => {
+                Some(format!("{}\n\n{}", summary, description))
+            }
+            (summary, description) => description.or(summary),
+        };
+        let body = operation
+            .pointer("/requestBody/content")
+            .and_then(Value::as_object)
+            .filter(|content| !content.is_empty())
+            .map(|content| match content.get(JSON) {
+                Some(media) => {
+                    RequestBody::Json(media.get("schema").cloned().unwrap_or(Value::Null))
+                }
+                None if content.contains_key(MULTIPART) => RequestBody::Multipart,
+                None => RequestBody::Bytes,
+            });
+        let response = operation
+            .get("responses")
+            .and_then(Value::as_object)
+            .and_then(|responses| {
+                let mut statuses: Vec<&String> = responses
+                    .keys()
+                    .filter(|status| status.starts_with('2'))
+                    .collect();
+                statuses.sort();
+                responses
+                    .get(statuses.first()?.as_str())?
+                    .get("content")?
+                    .as_object()
+            })
+            .filter(|content| !content.is_empty())
+            .map_or(ResponseBody::Json(None), |content| {
+                if let Some(media) = content.get(JSON) {
+                    ResponseBody::Json(media.get("schema").cloned())
+                } else if let Some(media) = content.get(JSON_LINES) {
+                    ResponseBody::Lines(media.get("schema").cloned())
+                } else {
+                    ResponseBody::Bytes
+                }
+            });
+        Self {
+            name,
+            method: method.to_string(),
+            path: segments.join("/"),
+            doc,
+            path_params,
+            query_params,
+            body,
+            response,
+        }
+    }
+}
+
+fn is_nullable(schema: &Value) -> bool {
+    schema.get("nullable").and_then(Value::as_bool) == Some(true)
+}
+
+fn description(schema: &Value) -> Option<String> {
+    schema
+        .get("description")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|doc| !doc.is_empty())
+        .map(str::to_string)
+}
+
+fn variants(schema: &Value) -> Option<&Vec<Value>> {
+    schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array)
+}
+
+/// Values of an enum as literals of both languages, `None` when it isn't an
+/// enum of strings and numbers
+fn literals(schema: &Value) -> Option<Vec<String>> {
+    schema
+        .get("enum")?
+        .as_array()?
+        .iter()
+        .map(|value| match value {
+            Value::String(_) | Value::Number(_) => Some(value.to_string()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+        .filter(|literals| !literals.is_empty())
+}
+
+fn properties(schema: &Value) -> Option<&serde_json::Map<String, Value>> {
+    if schema.get("type").map_or(false, |ty| ty != "object") {
+        return None;
+    }
+    schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .filter(|properties| !properties.is_empty())
+}
+
+fn required(schema: &Value) -> HashSet<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect()
+}
+
+fn dedup(types: impl Iterator<Item = String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    types.filter(|ty| seen.insert(ty.clone())).collect()
+}
+
+/// Name of a schema as a type name of both languages
+fn type_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+/// Name of an operation as a snake case identifier
+fn identifier(name: &str) -> String {
+    let name = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+fn camel_case(name: &str) -> String {
+    let mut camel = String::new();
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' || c == '-' {
+            upper = !camel.is_empty();
+        } else if upper {
+            camel.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            camel.push(c);
+        }
+    }
+    camel
+}
+
+fn is_python_identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') &&
+        name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') &&
+        !PYTHON_KEYWORDS.contains(&name)
+}
+
+/// Name of the argument a parameter is passed as
+fn python_argument(name: &str) -> String {
+    let argument = identifier(name);
+    if PYTHON_KEYWORDS.contains(&argument.as_str()) ||
+        RESERVED_ARGUMENTS.contains(&argument.as_str())
+    {
+        format!("{}_", argument)
+    } else {
+        argument
+    }
+}
+
+fn typescript_property(name: &str) -> String {
+    let is_identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$') &&
+        name.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        name.to_string()
+    } else {
+        Value::from(name).to_string()
+    }
+}
+
+fn python_docstring(doc: &str, indent: &str) -> Vec<String> {
+    let doc = doc.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\"");
+    let mut lines: Vec<String> = doc
+        .lines()
+        .map(|line| format!("{}{}", indent, line).trim_end().to_string())
+        .collect();
+    if let Some(first) = lines.first_mut() {
+        *first = format!("{}\"\"\"{}", indent, first.trim_start());
+    }
+    if let Some(last) = lines.last_mut() {
+        last.push_str("\"\"\"");
+    }
+    lines
+}
+
+fn typescript_doc(doc: &str, indent: &str) -> Vec<String> {
+    let doc = doc.replace("*/", "*\\/");
+    let lines: Vec<&str> = doc.lines().collect();
+    if let [line] = lines.as_slice() {
+        return vec![format!("{}/** {} */", indent, line)];
+    }
+    let mut doc = vec![format!("{}/**", indent)];
+    doc.extend(
+        lines
+            .iter()
+            .map(|line| format!("{} * {}", indent, line).trim_end().to_string()),
+    );
+    doc.push(format!("{} */", indent));
+    doc
+}
+
+const PYTHON_CLIENT: &str = r#"
+def _quote(value: Any) -> str:
+    return quote(str(value), safe="")
+
+
+class IndexifyError(Exception):
+    def __init__(self, status_code: int, message: str, cursor: Optional[str] = None):
+        super().__init__(f"{status_code}: {message}")
+        self.status_code = status_code
+        self.message = message
+        # Resumes a failed stream after the last line it sent
+        self.cursor = cursor
+
+
+class IndexifyClient:
+    def __init__(
+        self,
+        service_url: str = "http://localhost:8900",
+        api_key: Optional[str] = None,
+        timeout: Optional[float] = 60,
+    ):
+        headers = {"Authorization": f"Bearer {api_key}"} if api_key else {}
+        self._client = httpx.Client(
+            base_url=service_url.rstrip("/"), headers=headers, timeout=timeout
+        )
+
+    def __enter__(self) -> "IndexifyClient":
+        return self
+
+    def __exit__(self, *args: Any) -> None:
+        self.close()
+
+    def close(self) -> None:
+        self._client.close()
+
+    @staticmethod
+    def _params(params: Optional[Dict[str, Any]]) -> Dict[str, Any]:
+        # Lists are sent comma separated and unset parameters are left out
+        return {
+            key: ",".join(map(str, value)) if isinstance(value, list) else value
+            for key, value in (params or {}).items()
+            if value is not None
+        }
+
+    def _send(
+        self, method: str, path: str, params: Optional[Dict[str, Any]] = None, **kwargs: Any
+    ) -> httpx.Response:
+        response = self._client.request(
+            method, path, params=self._params(params), **kwargs
+        )
+        if response.is_error:
+            raise IndexifyError(response.status_code, response.text)
+        return response
+
+    @staticmethod
+    def _json(response: httpx.Response) -> Any:
+        return response.json() if response.content else None
+
+    def _lines(
+        self, method: str, path: str, params: Optional[Dict[str, Any]] = None
+    ) -> Iterator[Any]:
+        with self._client.stream(
+            method, path, params=self._params(params), timeout=None
+        ) as response:
+            if response.is_error:
+                response.read()
+                raise IndexifyError(response.status_code, response.text)
+            for line in response.iter_lines():
+                if line.strip():
+                    item = json.loads(line)
+                    if isinstance(item, dict) and "error" in item and set(item) <= {"error", "cursor"}:
+                        raise IndexifyError(response.status_code, item["error"], item.get("cursor"))
+                    yield item
+"#;
+
+const PYTHON_HELPERS: &str = r#"
+    def invoke(
+        self,
+        namespace: str,
+        extraction_graph: str,
+        data: bytes,
+        file_name: str = "payload",
+        labels: Optional[Dict[str, str]] = None,
+    ) -> str:
+        """Ingests a payload into an extraction graph and returns the id of its content"""
+        response = self._send(
+            "POST",
+            f"/namespaces/{_quote(namespace)}/upload_file",
+            params={"extraction_graph_names": extraction_graph},
+            data=labels or {},
+            files={"file": (file_name, data)},
+        )
+        return response.json()["content_id"]
+
+    def wait(self, namespace: str, content_id: str, timeout: Optional[float] = 600) -> None:
+        """Waits until the extraction graphs finished extracting a content"""
+        self._send(
+            "GET",
+            f"/namespaces/{_quote(namespace)}/content/{_quote(content_id)}/wait",
+            timeout=timeout,
+        )
+
+    def outputs(self, namespace: str, content_id: str) -> List["ContentMetadata"]:
+        """Content extracted from a content, its content tree without itself"""
+        response = self._send(
+            "GET",
+            f"/namespaces/{_quote(namespace)}/content/{_quote(content_id)}/content-tree",
+        )
+        return [
+            content
+            for content in response.json()["content_tree_metadata"]
+            if content["id"] != content_id
+        ]
+"#;
+
+const TYPESCRIPT_CLIENT: &str = r#"
+export class IndexifyError extends Error {
+  constructor(
+    readonly status: number,
+    message: string,
+    // Resumes a failed stream after the last line it sent
+    readonly cursor?: string,
+  ) {
+    super(`${status}: ${message}`);
+  }
+}
+
+// Last line of a stream that failed after it started
+function isStreamError(item: unknown): item is { error: string; cursor?: string } {
+  return (
+    typeof item === "object" &&
+    item !== null &&
+    "error" in item &&
+    Object.keys(item).every((key) => key === "error" || key === "cursor")
+  );
+}
+
+export interface ClientOptions {
+  serviceUrl?: string;
+  apiKey?: string;
+  fetch?: typeof fetch;
+}
+
+interface RequestOptions {
+  query?: object;
+  json?: unknown;
+  body?: BodyInit;
+}
+
+export class IndexifyClient {
+  private readonly serviceUrl: string;
+  private readonly headers: Record<string, string>;
+  private readonly fetch: typeof fetch;
+
+  constructor(options: ClientOptions = {}) {
+    this.serviceUrl = (options.serviceUrl ?? "http://localhost:8900").replace(/\/+$/, "");
+    this.headers = options.apiKey ? { Authorization: `Bearer ${options.apiKey}` } : {};
+    this.fetch = options.fetch ?? globalThis.fetch.bind(globalThis);
+  }
+
+  private async send(method: string, path: string, options: RequestOptions = {}): Promise<Response> {
+    const url = new URL(this.serviceUrl + path);
+    // Lists are sent comma separated and unset parameters are left out
+    for (const [key, value] of Object.entries(options.query ?? {})) {
+      if (value !== undefined && value !== null) {
+        url.searchParams.set(key, String(value));
+      }
+    }
+    const headers = { ...this.headers };
+    let body = options.body;
+    if (options.json !== undefined) {
+      headers["Content-Type"] = "application/json";
+      body = JSON.stringify(options.json);
+    }
+    const response = await this.fetch(url, { method, headers, body });
+    if (!response.ok) {
+      throw new IndexifyError(response.status, await response.text());
+    }
+    return response;
+  }
+
+  private async json<T>(response: Response): Promise<T> {
+    const text = await response.text();
+    return (text ? JSON.parse(text) : undefined) as T;
+  }
+
+  private async *lines<T>(response: Response): AsyncGenerator<T> {
+    if (!response.body) {
+      return;
+    }
+    const reader = response.body.getReader();
+    const decoder = new TextDecoder();
+    let buffered = "";
+    for (;;) {
+      const { done, value } = await reader.read();
+      buffered += decoder.decode(value, { stream: !done });
+      const lines = buffered.split("\n");
+      buffered = done ? "" : lines.pop() ?? "";
+      for (const line of lines) {
+        if (line.trim()) {
+          const item = JSON.parse(line);
+          if (isStreamError(item)) {
+            throw new IndexifyError(response.status, item.error, item.cursor);
+          }
+          yield item as T;
+        }
+      }
+      if (done) {
+        return;
+      }
+    }
+  }
+"#;
+
+const TYPESCRIPT_HELPERS: &str = r#"
+  /** Ingests a payload into an extraction graph and returns the id of its content */
+  async invoke(
+    namespace: string,
+    extractionGraph: string,
+    data: Blob,
+    fileName = "payload",
+    labels: Record<string, string> = {},
+  ): Promise<string> {
+    const form = new FormData();
+    for (const [key, value] of Object.entries(labels)) {
+      form.append(key, value);
+    }
+    form.append("file", data, fileName);
+    const response = await this.send("POST", `/namespaces/${encodeURIComponent(namespace)}/upload_file`, {
+      query: { extraction_graph_names: extractionGraph },
+      body: form,
+    });
+    return (await this.json<{ content_id: string }>(response)).content_id;
+  }
+
+  /** Waits until the extraction graphs finished extracting a content */
+  async wait(namespace: string, contentId: string): Promise<void> {
+    await this.send("GET", `/namespaces/${encodeURIComponent(namespace)}/content/${encodeURIComponent(contentId)}/wait`);
+  }
+
+  /** Content extracted from a content, its content tree without itself */
+  async outputs(namespace: string, contentId: string): Promise<ContentMetadata[]> {
+    const response = await this.send(
+      "GET",
+      `/namespaces/${encodeURIComponent(namespace)}/content/${encodeURIComponent(contentId)}/content-tree`,
+    );
+    const tree = await this.json<{ content_tree_metadata: ContentMetadata[] }>(response);
+    return tree.content_tree_metadata.filter((content) => content.id !== contentId);
+  }
+"#;
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use utoipa::OpenApi;
+
+    use super::*;
+
+    fn openapi() -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "info": {"title": "indexify", "version": "0.1.0"},
+            "paths": {
+                "/namespaces/{namespace}/tasks": {
+                    "get": {
+                        "operationId": "list_tasks",
+                        "summary": "Lists the tasks of a namespace",
+                        "parameters": [
+                            {"name": "namespace", "in": "path", "required": true, "schema": {"type": "string"}},
+                            {"name": "outcome", "in": "query", "required": false, "schema": {"$ref": "#/components/schemas/TaskOutcome"}},
+                            {"name": "from", "in": "query", "required": false, "schema": {"type": "integer"}}
+                        ],
+                        "responses": {
+                            "200": {"description": "", "content": {"application/json": {"schema": {"$ref": "#/components/schemas/ListTasksResponse"}}}},
+                            "500": {"description": ""}
+                        }
+                    }
+                },
+                "/namespaces/{namespace}/tasks_stream": {
+                    "get": {
+                        "operationId": "stream_tasks",
+                        "responses": {
+                            "200": {"description": "", "content": {"application/x-ndjson": {"schema": {"$ref": "#/components/schemas/Task"}}}}
+                        }
+                    }
+                },
+                "/namespaces/{namespace}/content/:content_id": {
+                    "put": {
+                        "operationId": "wait",
+                        "requestBody": {"content": {"application/json": {"schema": {"$ref": "#/components/schemas/Missing"}}}},
+                        "responses": {"200": {"description": ""}}
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Task": {
+                        "type": "object",
+                        "description": "A task of an extraction policy",
+                        "required": ["id", "outcome"],
+                        "properties": {
+                            "id": {"type": "string"},
+                            "outcome": {"$ref": "#/components/schemas/TaskOutcome"},
+                            "labels": {"type": "object", "additionalProperties": {"type": "string"}},
+                            "parent": {"allOf": [{"$ref": "#/components/schemas/Task"}], "nullable": true}
+                        }
+                    },
+                    "TaskOutcome": {"type": "string", "enum": ["unknown", "success", "failed"]},
+                    "ListTasksResponse": {
+                        "type": "object",
+                        "required": ["tasks"],
+                        "properties": {"tasks": {"type": "array", "items": {"$ref": "#/components/schemas/Task"}}}
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_python_client() {
+        let python = ApiSchema::parse(&openapi()).unwrap().python();
+        assert!(python.contains("TaskOutcome = Literal[\"unknown\", \"success\", \"failed\"]"));
+        assert!(python.contains(
+            "class Task(TypedDict):\n    \"\"\"A task of an extraction policy\"\"\"\n    id: str\n"
+        ));
+        assert!(python.contains("    labels: NotRequired[Dict[str, str]]\n"));
+        assert!(python.contains("    parent: NotRequired[Optional[\"Task\"]]\n"));
+        assert!(python.contains("    tasks: List[\"Task\"]\n"));
+        assert!(python.contains(
+            "    def list_tasks(self, namespace: str, *, outcome: Optional[\"TaskOutcome\"] = None, from_: Optional[int] = None) -> \"ListTasksResponse\":\n        \"\"\"Lists the tasks of a namespace\"\"\"\n        return self._json(self._send(\"GET\", f\"/namespaces/{_quote(namespace)}/tasks\", params={\"outcome\": outcome, \"from\": from_}))\n"
+        ));
+        assert!(python.contains(
+            "    def stream_tasks(self, namespace: str) -> Iterator[\"Task\"]:\n        return self._lines(\"GET\", f\"/namespaces/{_quote(namespace)}/tasks_stream\")\n"
+        ));
+        //  Names of the helpers are kept, references to unknown schemas are
+        //  untyped
+        assert!(python.contains(
+            "    def wait_2(self, namespace: str, content_id: str, body: Any) -> Any:\n"
+        ));
+        assert!(
+            python.contains("f\"/namespaces/{_quote(namespace)}/content/{_quote(content_id)}\"")
+        );
+    }
+
+    #[test]
+    fn test_typescript_client() {
+        let typescript = ApiSchema::parse(&openapi()).unwrap().typescript();
+        assert!(typescript
+            .contains("export type TaskOutcome = \"unknown\" | \"success\" | \"failed\";"));
+        assert!(typescript.contains(
+            "/** A task of an extraction policy */\nexport interface Task {\n  id: string;\n"
+        ));
+        assert!(typescript.contains("\n  labels?: Record<string, string>;\n"));
+        assert!(typescript.contains("\n  outcome: TaskOutcome;\n"));
+        assert!(typescript.contains("\n  parent?: Task | null;\n"));
+        assert!(typescript.contains(
+            "  /** Lists the tasks of a namespace */\n  async listTasks(namespace: string, query: { outcome?: TaskOutcome; from?: number } = {}): Promise<ListTasksResponse> {\n    return this.json<ListTasksResponse>(await this.send(\"GET\", `/namespaces/${encodeURIComponent(namespace)}/tasks`, { query }));\n  }"
+        ));
+        assert!(typescript.contains(
+            "  async *streamTasks(namespace: string): AsyncGenerator<Task> {\n    yield* this.lines<Task>(await this.send(\"GET\", `/namespaces/${encodeURIComponent(namespace)}/tasks_stream`));\n  }"
+        ));
+        assert!(typescript.contains(
+            "  async wait2(namespace: string, contentId: string, body: unknown): Promise<unknown> {"
+        ));
+    }
+
+    #[test]
+    fn test_clients_of_api_schema() {
+        let openapi = serde_json::to_value(crate::server::ApiDoc::openapi()).unwrap();
+        let schema = ApiSchema::parse(&openapi).unwrap();
+        let python = schema.python();
+        assert!(python.contains("    def get_task(self, namespace: str, task_id: str"));
+        assert!(python.contains("    def stream_tasks(self, namespace: str"));
+        assert!(python.contains("class ContentMetadata(TypedDict):"));
+        let typescript = schema.typescript();
+        assert!(typescript.contains("  async *streamTasks(namespace: string"));
+        assert!(typescript.contains("export interface ContentMetadata {"));
+    }
+}
@@ -0,0 +1,597 @@
+//! Resolves references to secrets in the server config. Any string value of
+//! the config can embed references that are replaced by the secret when the
+//! config is loaded:
+//!
+//! - `${vault:<path>#<key>}` reads `key` of the secret at `path` from Vault
+//! - `${aws:<secret id or arn>#<key>}` reads a secret from AWS Secrets Manager
+//! - `${gcp:projects/<project>/secrets/<secret>#<key>}` reads the latest
+//!   version of a secret from GCP Secret Manager
+//!
+//! The key can be left out for secrets that hold a single value. For example
+//! `postgres://indexify:${vault:kv/data/postgres#password}@db:5432/indexify`.
+//!
+//! When the secrets are refreshed, rotated secrets are applied to the api keys
+//! and the alert webhooks while the server runs. The other values are only
+//! read at startup.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{anyhow, Result};
+use aws_credential_types::Credentials;
+use aws_sigv4::{
+    http_request::{sign, SignableBody, SignableRequest, SigningSettings},
+    sign::v4,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use serde_json::{json, Value};
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use crate::server_config::{SecretsConfig, ServerConfig};
+
+static REFERENCE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\$\{(vault|aws|gcp):([^}#]+)(?:#([^}]+))?\}").unwrap());
+
+/// The config resolved with the secrets of the last refresh
+static REFRESHED: Lazy<watch::Sender<Option<Arc<ServerConfig>>>> =
+    Lazy::new(|| watch::channel(None).0);
+
+/// Config paths whose rotated secrets are applied while the server runs, by
+/// the consumers registered with [`on_refresh`]
+const REFRESHED_PATHS: &[&str] = &["api_keys", "alerting.webhooks"];
+
+const GCP_METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct SecretReference {
+    provider: String,
+    name: String,
+    key: Option<String>,
+}
+
+impl SecretReference {
+    fn from_captures(captures: &Captures) -> Self {
+        Self {
+            provider: captures[1].to_string(),
+            name: captures[2].trim().to_string(),
+            key: captures.get(3).map(|key| key.as_str().trim().to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for SecretReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.key {
+            Some(key) => write!(f, "{}:{}#{}", self.provider, self.name, key),
+            None => write!(f, "{}:{}", self.provider, self.name),
+        }
+    }
+}
+
+/// Config paths of the secret references in the string values of `value`.
+/// The `secrets` section holds the credentials of the secret stores and is
+/// never resolved.
+fn find_references(
+    value: &Value,
+    path: &str,
+    references: &mut HashMap<SecretReference, HashSet<String>>,
+) {
+    match value {
+        Value::String(s) => {
+            for captures in REFERENCE.captures_iter(s) {
+                references
+                    .entry(SecretReference::from_captures(&captures))
+                    .or_default()
+                    .insert(path.to_string());
+            }
+        }
+        Value::Array(values) => {
+            for (i, value) in values.iter().enumerate() {
+                find_references(value, &format!("{}[{}]", path, i), references);
+            }
+        }
+        Value::Object(fields) => {
+            for (name, value) in fields {
+                if path.is_empty() && name == "secrets" {
+                    continue;
+                }
+                let path = if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}.{}", path, name)
+                };
+                find_references(value, &path, references);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn substitute(value: &mut Value, secrets: &HashMap<SecretReference, String>, top_level: bool) {
+    match value {
+        Value::String(s) => {
+            if REFERENCE.is_match(s) {
+                *s = REFERENCE
+                    .replace_all(s, |captures: &Captures| {
+                        secrets
+                            .get(&SecretReference::from_captures(captures))
+                            .cloned()
+                            .unwrap_or_default()
+                    })
+                    .into_owned();
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                substitute(value, secrets, false);
+            }
+        }
+        Value::Object(fields) => {
+            for (name, value) in fields {
+                if !(top_level && name == "secrets") {
+                    substitute(value, secrets, false);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Picks `key` out of a secret. Secrets are either JSON objects holding
+/// several values or plain strings.
+fn select_key(reference: &SecretReference, secret: Value) -> Result<String> {
+    let value = match &reference.key {
+        Some(key) => secret
+            .get(key)
+            .cloned()
+            .ok_or_else(|| anyhow!("secret {} has no key {}", reference.name, key))?,
+        None => secret,
+    };
+    match value {
+        Value::String(s) => Ok(s),
+        Value::Number(_) | Value::Bool(_) => Ok(value.to_string()),
+        _ => Err(anyhow!(
+            "secret {} holds several values, pick one with `#<key>`",
+            reference
+        )),
+    }
+}
+
+/// Secret payloads that are JSON objects can be picked apart with keys
+fn parse_payload(payload: String) -> Value {
+    serde_json::from_str::<Value>(&payload)
+        .ok()
+        .filter(|value| value.is_object())
+        .unwrap_or(Value::String(payload))
+}
+
+struct SecretResolver {
+    config: SecretsConfig,
+    client: reqwest::Client,
+}
+
+impl SecretResolver {
+    fn new(config: SecretsConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn read_secrets(&self, config: &Value) -> Result<HashMap<SecretReference, String>> {
+        let mut references = HashMap::new();
+        find_references(config, "", &mut references);
+        let mut secrets = HashMap::new();
+        for (reference, paths) in references {
+            let secret = self.read(&reference).await.map_err(|e| {
+                anyhow!(
+                    "unable to read secret {} referenced by {}: {}",
+                    reference,
+                    paths.into_iter().collect::<Vec<_>>().join(", "),
+                    e
+                )
+            })?;
+            secrets.insert(reference, secret);
+        }
+        Ok(secrets)
+    }
+
+    async fn read(&self, reference: &SecretReference) -> Result<String> {
+        let secret = match reference.provider.as_str() {
+            "vault" => self.read_vault(&reference.name).await?,
+            "aws" => self.read_aws(&reference.name).await?,
+            "gcp" => self.read_gcp(&reference.name).await?,
+            provider => return Err(anyhow!("unknown secret provider {}", provider)),
+        };
+        select_key(reference, secret)
+    }
+
+    async fn read_vault(&self, path: &str) -> Result<Value> {
+        let vault = self
+            .config
+            .vault
+            .as_ref()
+            .ok_or_else(|| anyhow!("`secrets.vault` is not configured"))?;
+        let token = match &vault.token {
+            Some(token) => token.clone(),
+            None => std::env::var("VAULT_TOKEN")
+                .map_err(|_| anyhow!("no vault token configured and VAULT_TOKEN is not set"))?,
+        };
+        let mut request = self
+            .client
+            .get(format!(
+                "{}/v1/{}",
+                vault.addr.trim_end_matches('/'),
+                path.trim_start_matches('/')
+            ))
+            .header("X-Vault-Token", token);
+        if let Some(namespace) = &vault.namespace {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+        let mut response: Value = request.send().await?.error_for_status()?.json().await?;
+        let data = response["data"].take();
+        //  KV version 2 nests the secret under data next to its metadata
+        if data.get("metadata").is_some() && data["data"].is_object() {
+            return Ok(data["data"].clone());
+        }
+        Ok(data)
+    }
+
+    async fn read_aws(&self, secret_id: &str) -> Result<Value> {
+        let region = aws_region(secret_id)?;
+        let access_key_id = std::env::var("AWS_ACCESS_KEY_ID")
+            .map_err(|_| anyhow!("AWS_ACCESS_KEY_ID is not set"))?;
+        let secret_access_key = std::env::var("AWS_SECRET_ACCESS_KEY")
+            .map_err(|_| anyhow!("AWS_SECRET_ACCESS_KEY is not set"))?;
+        let session_token = std::env::var("AWS_SESSION_TOKEN").ok();
+
+        let url = format!("https://secretsmanager.{}.amazonaws.com/", region);
+        let body = json!({ "SecretId": secret_id }).to_string();
+        let headers = [
+            ("content-type", "application/x-amz-json-1.1".to_string()),
+            ("x-amz-target", "secretsmanager.GetSecretValue".to_string()),
+        ];
+        let credentials = Credentials::new(
+            access_key_id,
+            secret_access_key,
+            session_token,
+            None,
+            "environment",
+        );
+        let signed_headers = sign_aws_request(
+            credentials,
+            &region,
+            &url,
+            &headers,
+            &body,
+            SystemTime::now(),
+        )?;
+
+        let mut request = self.client.post(url).body(body);
+        for (name, value) in headers {
+            request = request.header(name, value);
+        }
+        for (name, value) in signed_headers {
+            request = request.header(name, value);
+        }
+        let response: Value = request.send().await?.error_for_status()?.json().await?;
+        if let Some(secret) = response["SecretString"].as_str() {
+            return Ok(parse_payload(secret.to_string()));
+        }
+        let binary = response["SecretBinary"]
+            .as_str()
+            .ok_or_else(|| anyhow!("secret {} has no value", secret_id))?;
+        Ok(parse_payload(String::from_utf8(BASE64.decode(binary)?)?))
+    }
+
+    async fn read_gcp(&self, name: &str) -> Result<Value> {
+        let token = match std::env::var("GOOGLE_OAUTH_ACCESS_TOKEN") {
+            Ok(token) => token,
+            Err(_) => {
+                let response: Value = self
+                    .client
+                    .get(GCP_METADATA_TOKEN_URL)
+                    .header("Metadata-Flavor", "Google")
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                response["access_token"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("no access token from the GCP metadata server"))?
+                    .to_string()
+            }
+        };
+        let name = name.trim_start_matches('/');
+        let version = if name.contains("/versions/") {
+            name.to_string()
+        } else {
+            format!("{}/versions/latest", name)
+        };
+        let response: Value = self
+            .client
+            .get(format!(
+                "https://secretmanager.googleapis.com/v1/{}:access",
+                version
+            ))
+            .bearer_auth(token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let data = response["payload"]["data"]
+            .as_str()
+            .ok_or_else(|| anyhow!("secret {} has no payload", name))?;
+        Ok(parse_payload(String::from_utf8(BASE64.decode(data)?)?))
+    }
+}
+
+/// Returns `config` with its secret references replaced by the secrets. When a
+/// refresh interval is configured, the secrets are read again periodically and
+/// the config resolved with them is sent to the consumers registered with
+/// [`on_refresh`]. Rotations of the secrets of other config values are
+/// reported, since those are only read at startup.
+pub async fn resolve(config: &ServerConfig) -> Result<ServerConfig> {
+    let resolver = SecretResolver::new(config.secrets.clone());
+    let unresolved = serde_json::to_value(config)?;
+    let secrets = resolver.read_secrets(&unresolved).await?;
+    if secrets.is_empty() {
+        return Ok(config.clone());
+    }
+    info!(
+        "resolved {} secrets referenced by the config",
+        secrets.len()
+    );
+    let mut resolved = unresolved.clone();
+    substitute(&mut resolved, &secrets, true);
+    let resolved = serde_json::from_value(resolved)?;
+
+    if let Some(interval_secs) = config
+        .secrets
+        .refresh_interval_secs
+        .filter(|secs| *secs > 0)
+    {
+        tokio::spawn(async move {
+            let mut secrets = secrets;
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let refreshed = match resolver.read_secrets(&unresolved).await {
+                    Ok(refreshed) => refreshed,
+                    Err(e) => {
+                        warn!("unable to refresh secrets: {}", e);
+                        continue;
+                    }
+                };
+                if refreshed == secrets {
+                    continue;
+                }
+                let mut references = HashMap::new();
+                find_references(&unresolved, "", &mut references);
+                for (reference, secret) in &refreshed {
+                    if secrets.get(reference) == Some(secret) {
+                        continue;
+                    }
+                    let (applied, mut stale): (Vec<_>, Vec<_>) = references
+                        .remove(reference)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .partition(|path| is_refreshed_path(path));
+                    if !applied.is_empty() {
+                        info!("secret {} was rotated, applying it", reference);
+                    }
+                    if !stale.is_empty() {
+                        stale.sort();
+                        warn!(
+                            "secret {} was rotated, restart to apply it to {}",
+                            reference,
+                            stale.join(", ")
+                        );
+                    }
+                }
+                let mut config = unresolved.clone();
+                substitute(&mut config, &refreshed, true);
+                match serde_json::from_value::<ServerConfig>(config) {
+                    Ok(config) => {
+                        REFRESHED.send_replace(Some(Arc::new(config)));
+                    }
+                    Err(e) => warn!("unable to apply the refreshed secrets: {}", e),
+                }
+                secrets = refreshed;
+            }
+        });
+    }
+    Ok(resolved)
+}
+
+fn is_refreshed_path(path: &str) -> bool {
+    REFRESHED_PATHS
+        .iter()
+        .any(|prefix| path == *prefix || path.starts_with(&format!("{}[", prefix)))
+}
+
+/// Calls `apply` with the config resolved with the refreshed secrets every
+/// time a secret referenced by the config is rotated
+pub fn on_refresh<F>(mut apply: F)
+where
+    F: FnMut(&ServerConfig) + Send + 'static,
+{
+    let mut refreshed = REFRESHED.subscribe();
+    refreshed.mark_unchanged();
+    tokio::spawn(async move {
+        while refreshed.changed().await.is_ok() {
+            let config = refreshed.borrow_and_update().clone();
+            if let Some(config) = config {
+                apply(&config);
+            }
+        }
+    });
+}
+
+/// Secrets Manager ARNs carry their region. Names use the region of the
+/// environment.
+fn aws_region(secret_id: &str) -> Result<String> {
+    if let Some(region) = secret_id
+        .strip_prefix("arn:")
+        .and_then(|arn| arn.split(':').nth(2))
+        .filter(|region| !region.is_empty())
+    {
+        return Ok(region.to_string());
+    }
+    std::env::var("AWS_REGION")
+        .or_else(|_| std::env::var("AWS_DEFAULT_REGION"))
+        .map_err(|_| anyhow!("AWS_REGION is not set"))
+}
+
+/// Signs a POST of `body` to Secrets Manager with Signature Version 4, and
+/// returns the headers the signature adds to the request
+fn sign_aws_request(
+    credentials: Credentials,
+    region: &str,
+    url: &str,
+    headers: &[(&str, String)],
+    body: &str,
+    time: SystemTime,
+) -> Result<Vec<(String, String)>> {
+    let identity = credentials.into();
+    let params = v4::SigningParams::builder()
+        .identity(&identity)
+        .region(region)
+        .name("secretsmanager")
+        .time(time)
+        .settings(SigningSettings::default())
+        .build()?
+        .into();
+    let request = SignableRequest::new(
+        "POST",
+        url,
+        headers.iter().map(|(name, value)| (*name, value.as_str())),
+        SignableBody::Bytes(body.as_bytes()),
+    )?;
+    let (instructions, _) = sign(request, &params)?.into_parts();
+    Ok(instructions
+        .headers()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(provider: &str, name: &str, key: Option<&str>) -> SecretReference {
+        SecretReference {
+            provider: provider.to_string(),
+            name: name.to_string(),
+            key: key.map(|key| key.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_substitute_references() {
+        let mut config = json!({
+            "metadata_storage": {
+                "conn_url": "postgres://indexify:${vault:kv/data/postgres#password}@db/indexify"
+            },
+            "cache": {"redis": ["${aws:prod/redis}"]},
+            "secrets": {"vault": {"addr": "${vault:kv/data/postgres#password}"}},
+        });
+        let mut references = HashMap::new();
+        find_references(&config, "", &mut references);
+        let postgres = reference("vault", "kv/data/postgres", Some("password"));
+        let redis = reference("aws", "prod/redis", None);
+        assert_eq!(
+            references,
+            HashMap::from([
+                (
+                    postgres.clone(),
+                    HashSet::from(["metadata_storage.conn_url".to_string()])
+                ),
+                (redis.clone(), HashSet::from(["cache.redis[0]".to_string()])),
+            ])
+        );
+
+        let secrets = HashMap::from([
+            (postgres, "hunter2".to_string()),
+            (redis, "redis://redis:6379".to_string()),
+        ]);
+        substitute(&mut config, &secrets, true);
+        assert_eq!(
+            config["metadata_storage"]["conn_url"],
+            "postgres://indexify:hunter2@db/indexify"
+        );
+        assert_eq!(config["cache"]["redis"][0], "redis://redis:6379");
+        assert_eq!(
+            config["secrets"]["vault"]["addr"],
+            "${vault:kv/data/postgres#password}"
+        );
+    }
+
+    #[test]
+    fn test_select_key() {
+        let secret = parse_payload(r#"{"password": "hunter2", "port": 5432}"#.to_string());
+        assert_eq!(
+            select_key(&reference("aws", "db", Some("password")), secret.clone()).unwrap(),
+            "hunter2"
+        );
+        assert_eq!(
+            select_key(&reference("aws", "db", Some("port")), secret.clone()).unwrap(),
+            "5432"
+        );
+        assert!(select_key(&reference("aws", "db", Some("user")), secret.clone()).is_err());
+        assert!(select_key(&reference("aws", "db", None), secret).is_err());
+        assert_eq!(
+            select_key(
+                &reference("gcp", "token", None),
+                parse_payload("hunter2".to_string())
+            )
+            .unwrap(),
+            "hunter2"
+        );
+    }
+
+    #[test]
+    fn test_aws_signing() {
+        let headers = [
+            ("content-type", "application/x-amz-json-1.1".to_string()),
+            ("x-amz-target", "secretsmanager.GetSecretValue".to_string()),
+        ];
+        let signed_headers: HashMap<String, String> = sign_aws_request(
+            Credentials::new("AKID", "secret", Some("token".to_string()), None, "test"),
+            "eu-west-1",
+            "https://secretsmanager.eu-west-1.amazonaws.com/",
+            &headers,
+            r#"{"SecretId":"db"}"#,
+            std::time::UNIX_EPOCH + Duration::from_secs(1709210096),
+        )
+        .unwrap()
+        .into_iter()
+        .collect();
+        assert_eq!(signed_headers["x-amz-date"], "20240229T123456Z");
+        assert_eq!(signed_headers["x-amz-security-token"], "token");
+        assert!(signed_headers["authorization"].starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKID/20240229/eu-west-1/secretsmanager/aws4_request"
+        ));
+        assert_eq!(
+            aws_region("arn:aws:secretsmanager:eu-west-1:123456789012:secret:db").unwrap(),
+            "eu-west-1"
+        );
+    }
+
+    #[test]
+    fn test_refreshed_paths() {
+        assert!(is_refreshed_path("api_keys[0].key"));
+        assert!(is_refreshed_path("alerting.webhooks[1].secret"));
+        assert!(!is_refreshed_path("metadata_storage.conn_url"));
+    }
+}
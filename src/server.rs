@@ -3,8 +3,9 @@ use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
 use anyhow::{anyhow, Result};
 use axum::{
     body::Body,
-    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
-    http::StatusCode,
+    extract::{multipart::Field, DefaultBodyLimit, Multipart, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    middleware,
     response::{IntoResponse, Response},
     routing::{delete, get, post, put},
     Extension,
@@ -15,7 +16,20 @@ use axum_otel_metrics::HttpMetricsLayerBuilder;
 use axum_server::{tls_rustls::RustlsConfig, Handle};
 use axum_tracing_opentelemetry::middleware::OtelAxumLayer;
 use axum_typed_websockets::WebSocketUpgrade;
-use hyper::{header::CONTENT_TYPE, Method};
+use bytes::Bytes;
+use hyper::{
+    header::{
+        ACCEPT,
+        ACCEPT_RANGES,
+        AUTHORIZATION,
+        CONTENT_LENGTH,
+        CONTENT_RANGE,
+        CONTENT_TYPE,
+        RANGE,
+        VARY,
+    },
+    Method,
+};
 use indexify_internal_api as internal_api;
 use indexify_proto::indexify_coordinator::{
     self,
@@ -29,17 +43,20 @@ use tokio::{
     signal,
     sync::{mpsc, watch},
 };
-use tokio_stream::StreamExt;
+use tokio_stream::{Stream, StreamExt};
 use tower_http::cors::{Any, CorsLayer};
-use tracing::info;
+use tracing::{info, warn};
 use utoipa::OpenApi;
 use utoipa_rapidoc::RapiDoc;
 use utoipa_redoc::{Redoc, Servable};
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::{
+    admin_routes::{restrict_admin_routes, AdminRoutePolicy, Listener},
     api::{self, *},
-    blob_storage::{BlobStorage, ContentReader},
+    api_utils,
+    auth::{authenticate_request, authorize_graphs, Authenticator, Principal},
+    blob_storage::{self, BlobStorage, ContentReader},
     caching::caches_extension::Caches,
     coordinator_client::CoordinatorClient,
     data_manager::DataManager,
@@ -47,8 +64,12 @@ use crate::{
     ingest_extracted_content::IngestExtractedContentState,
     metadata_storage::{self, MetadataReaderTS, MetadataStorageTS},
     metrics,
+    namespace_archive::ImportReport,
+    preflight,
+    profiling,
+    read_only::reject_writes,
     server_config::ServerConfig,
-    tls::build_mtls_config,
+    tls::{build_mtls_config, spawn_certificate_reloader},
     vector_index::VectorIndexManager,
     vectordbs,
 };
@@ -74,15 +95,58 @@ pub struct NamespaceEndpointState {
             create_namespace,
             list_namespaces,
             get_namespace,
+            export_namespace,
+            import_namespace,
             add_texts,
             list_indexes,
             index_search,
             list_extractors,
             list_executors,
+            list_executor_tasks,
+            list_task_queue_depths,
             list_content,
+            stream_content,
             get_content_metadata,
+            get_content_tree_metadata,
             upload_file,
             list_tasks,
+            stream_tasks,
+            get_task,
+            get_task_logs,
+            get_task_scheduling,
+            list_replays,
+            get_replay,
+            get_invocation_breakdown,
+            requeue_task,
+            list_stuck_content,
+            list_starved_tasks,
+            repair_content,
+            rerun_failed_tasks,
+            get_content_context,
+            set_content_context,
+            delete_content_context,
+            get_content_replica,
+            validate_extraction_graph,
+            diff_extraction_graph,
+            update_extraction_graph,
+            set_graph_template,
+            list_graph_templates,
+            get_graph_template,
+            delete_graph_template,
+            instantiate_graph_template,
+            list_extraction_graph_versions,
+            get_extraction_graph_usage,
+            get_slo_compliance,
+            rollback_extraction_graph,
+            rerun_extraction_policy,
+            cancel_tasks,
+            get_cancel_tasks_job,
+            get_scheduler_settings,
+            set_scheduler_settings,
+            simulate_scheduling,
+            get_maintenance_mode,
+            set_maintenance_mode,
+            list_metering_records,
             extract_content
         ),
         components(
@@ -91,7 +155,7 @@ pub struct NamespaceEndpointState {
                 DocumentFragment, ListIndexesResponse, ExtractorOutputSchema, Index, SearchRequest, ListNamespacesResponse, ListExtractorsResponse
             , ExtractorDescription, DataNamespace, ExtractionPolicy, ExtractionPolicyRequest, ExtractionPolicyResponse, Executor,
             MetadataResponse, ExtractedMetadata, ListExecutorsResponse, EmbeddingSchema, ExtractResponse, ExtractRequest,
-            Content, Feature, FeatureType, GetContentMetadataResponse, ListTasksResponse, internal_api::Task, internal_api::TaskOutcome,
+            Content, Feature, FeatureType, GetContentMetadataResponse, ListTasksResponse, TaskLogStream, TaskQueueDepth, ListTaskQueueDepthsResponse, ValidateExtractionGraphResponse, ExtractionGraphDiagnostic, DiagnosticSeverity, ExtractionGraphDiff, ExtractionGraphEdge, ExtractionPolicyChange, RerunExtractionPolicyRequest, CancelTasksRequest, CancelTasksResponse, CancelTasksJob, CancelTasksJobState, StuckContent, ListStuckContentResponse, StarvedTask, ListStarvedTasksResponse, internal_api::UnschedulableReason, internal_api::ZonePlacement, GetTaskResponse, internal_api::TaskTransition, internal_api::TaskState, RepairContentResponse, RerunFailedTasksResponse, ContentContextResponse, ExtractionGraphVersion, ListExtractionGraphVersionsResponse, RollbackExtractionGraphRequest, internal_api::SchedulerSettings, internal_api::CapacityReservation, SimulateSchedulingRequest, SimulatedExecutors, SimulatedInvocations, SimulateSchedulingResponse, SimulatedQueue, internal_api::PlacementStrategy, internal_api::MaintenanceMode, SetMaintenanceModeRequest, internal_api::MeteringRecord, ListMeteringRecordsResponse, internal_api::TaskUsage, internal_api::ExtractionPolicyUsage, internal_api::ExtractionGraphUsage, ExtractionGraphUsageResponse, internal_api::SloCompliance, SloComplianceResponse, ExtractionPolicySlo, internal_api::PlacementRejection, internal_api::PlacementCandidate, internal_api::SchedulingAttempt, TaskSchedulingResponse, ReplayStartedResponse, internal_api::ReplayKind, internal_api::ReplayTask, internal_api::Replay, ListReplaysResponse, ReplayResponse, internal_api::ExtractionPolicyBreakdown, internal_api::InvocationBreakdown, InvocationBreakdownResponse, internal_api::GraphTemplate, internal_api::GraphTemplateParameter, ListGraphTemplatesResponse, internal_api::ContentReplica, InstantiateGraphTemplateRequest, ExtractionGraphRequest, ExtractionGraphResponse, UpdateLabelsRequest, GetContentTreeMetadataResponse, UploadFileResponse, ImportReport, internal_api::Task, internal_api::TaskOutcome, internal_api::ExecutorPressure, internal_api::PressureLevel, GpuDevice, internal_api::GraphVersionRange,
             internal_api::Content, internal_api::ContentMetadata, ListContentResponse, GetNamespaceResponse, ExtractionPolicyResponse,
         )
         ),
@@ -99,7 +163,7 @@ pub struct NamespaceEndpointState {
             (name = "indexify", description = "Indexify API")
         )
     )]
-struct ApiDoc;
+pub struct ApiDoc;
 
 pub struct Server {
     addr: SocketAddr,
@@ -112,6 +176,7 @@ impl Server {
     }
 
     pub async fn run(&self, registry: Arc<prometheus::Registry>) -> Result<()> {
+        preflight::check_server(&self.config, self.addr).await?;
         // let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
         let (shutdown_tx, shutdown_rx) = watch::channel(false);
 
@@ -149,12 +214,16 @@ impl Server {
         ));
         let ingestion_server_id = nanoid::nanoid!(16);
 
-        self.start_gc_tasks_stream(
-            coordinator_client.clone(),
-            &ingestion_server_id,
-            data_manager.clone(),
-            shutdown_rx.clone(),
-        );
+        if self.config.read_only {
+            info!("starting indexify server in read only mode");
+        } else {
+            self.start_gc_tasks_stream(
+                coordinator_client.clone(),
+                &ingestion_server_id,
+                data_manager.clone(),
+                shutdown_rx.clone(),
+            );
+        }
         let namespace_endpoint_state = NamespaceEndpointState {
             data_manager: data_manager.clone(),
             coordinator_client: coordinator_client.clone(),
@@ -166,7 +235,7 @@ impl Server {
         let cors = CorsLayer::new()
             .allow_methods([Method::GET, Method::POST])
             .allow_origin(Any)
-            .allow_headers([CONTENT_TYPE]);
+            .allow_headers([CONTENT_TYPE, AUTHORIZATION]);
 
         let metrics = HttpMetricsLayerBuilder::new().build();
         let app = Router::new()
@@ -179,6 +248,50 @@ impl Server {
                 "/namespaces/:namespace/extraction_graphs",
                 post(create_extraction_graph).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/namespaces/:namespace/extraction_graphs/validate",
+                post(validate_extraction_graph).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/extraction_graphs/:extraction_graph/diff",
+                post(diff_extraction_graph).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/extraction_graphs/:extraction_graph",
+                put(update_extraction_graph).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/graph_templates",
+                get(list_graph_templates)
+                    .post(set_graph_template)
+                    .with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/graph_templates/:template",
+                get(get_graph_template)
+                    .delete(delete_graph_template)
+                    .with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/graph_templates/:template/instantiate",
+                post(instantiate_graph_template).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/extraction_graphs/:extraction_graph/versions",
+                get(list_extraction_graph_versions).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/extraction_graphs/:extraction_graph/usage",
+                get(get_extraction_graph_usage).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/extraction_graphs/:extraction_graph/slo",
+                get(get_slo_compliance).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/extraction_graphs/:extraction_graph/rollback",
+                post(rollback_extraction_graph).with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/namespaces/:namespace/indexes",
                 get(list_indexes).with_state(namespace_endpoint_state.clone()),
@@ -195,6 +308,10 @@ impl Server {
                 "/namespaces/:namespace/content",
                 get(list_content).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/namespaces/:namespace/content_stream",
+                get(stream_content).with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/namespaces/:namespace/active_content",
                 get(active_content).with_state(namespace_endpoint_state.clone()),
@@ -219,6 +336,10 @@ impl Server {
                 "/namespaces/:namespace/content/:content_id/download",
                 get(download_content).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/namespaces/:namespace/content/:content_id/rerun",
+                post(rerun_extraction_policy).with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/namespaces/:namespace/content/:content_id/content-tree",
                 get(get_content_tree_metadata).with_state(namespace_endpoint_state.clone()),
@@ -261,10 +382,22 @@ impl Server {
                 "/namespaces/:namespace",
                 get(get_namespace).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/namespaces/:namespace/export",
+                get(export_namespace).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/import",
+                post(import_namespace).with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/executors",
                 get(list_executors).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/executors/:executor_id/tasks",
+                get(list_executor_tasks).with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/write_content",
                 get(ingest_extracted_content).with_state(namespace_endpoint_state.clone()),
@@ -281,6 +414,80 @@ impl Server {
                 "/namespaces/:namespace/tasks",
                 get(list_tasks).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/namespaces/:namespace/tasks_stream",
+                get(stream_tasks).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/tasks/queue_depths",
+                get(list_task_queue_depths).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/cancel_tasks",
+                post(cancel_tasks).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/cancel_tasks/:job_id",
+                get(get_cancel_tasks_job).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/tasks/:task_id",
+                get(get_task).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/tasks/:task_id/logs",
+                get(get_task_logs).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/tasks/:task_id/scheduling",
+                get(get_task_scheduling).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/tasks/:task_id/requeue",
+                post(requeue_task).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/stuck_content",
+                get(list_stuck_content).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/tasks/starved",
+                get(list_starved_tasks).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/content/:content_id/repair",
+                post(repair_content).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/content/:content_id/rerun_failed",
+                post(rerun_failed_tasks).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/replays",
+                get(list_replays).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/replays/:replay_id",
+                get(get_replay).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/content/:content_id/breakdown",
+                get(get_invocation_breakdown).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/content/:content_id/context",
+                get(get_content_context).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/content/:content_id/replica",
+                get(get_content_replica).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/content/:content_id/context/:key",
+                put(set_content_context)
+                    .delete(delete_content_context)
+                    .with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/extractors/extract",
                 post(extract_content).with_state(namespace_endpoint_state.clone()),
@@ -289,6 +496,26 @@ impl Server {
                 "/task_assignments",
                 get(list_task_assignments).with_state(namespace_endpoint_state.clone()),
             )
+            .route(
+                "/scheduler/settings",
+                get(get_scheduler_settings)
+                    .put(set_scheduler_settings)
+                    .with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/scheduler/simulate",
+                post(simulate_scheduling).with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/maintenance",
+                get(get_maintenance_mode)
+                    .put(set_maintenance_mode)
+                    .with_state(namespace_endpoint_state.clone()),
+            )
+            .route(
+                "/metering",
+                get(list_metering_records).with_state(namespace_endpoint_state.clone()),
+            )
             .route(
                 "/metrics/raft",
                 get(get_raft_metrics_snapshot).with_state(namespace_endpoint_state.clone()),
@@ -298,7 +525,55 @@ impl Server {
                 get(ingest_metrics).with_state(namespace_endpoint_state.clone()),
             )
             .route("/ui", get(ui_index_handler))
-            .route("/ui/*rest", get(ui_handler))
+            .route("/ui/*rest", get(ui_handler));
+        let authenticated = self.config.oidc.is_some() || !self.config.api_keys.is_empty();
+        let app = if self.config.profiling.enabled && authenticated {
+            info!("serving profiling endpoints as admin routes");
+            app.merge(profiling::routes(&self.config.profiling))
+        } else {
+            if self.config.profiling.enabled {
+                warn!("not serving profiling endpoints, as api authentication is not configured");
+            }
+            app
+        };
+        let app = if authenticated {
+            info!(
+                "authenticating API requests with {} api keys and {}",
+                self.config.api_keys.len(),
+                self.config
+                    .oidc
+                    .as_ref()
+                    .map_or("no oidc issuer".to_string(), |oidc| {
+                        format!("tokens of {}", oidc.issuer)
+                    })
+            );
+            let authenticator = Arc::new(
+                Authenticator::new(self.config.oidc.clone(), &self.config.api_keys)
+                    .with_extractor_client_certificates(
+                        use_tls &&
+                            self.config
+                                .tls
+                                .as_ref()
+                                .map_or(false, |tls| tls.ca_file.is_some()),
+                    ),
+            );
+            crate::secrets::on_refresh({
+                let authenticator = authenticator.clone();
+                move |config| authenticator.set_api_keys(&config.api_keys)
+            });
+            app.layer(middleware::from_fn_with_state(
+                authenticator,
+                authenticate_request,
+            ))
+        } else {
+            app
+        };
+        let app = if self.config.read_only {
+            app.layer(middleware::from_fn(reject_writes))
+        } else {
+            app
+        };
+        let app = app
             .layer(OtelAxumLayer::default())
             .layer(metrics)
             .layer(Extension(caches))
@@ -317,13 +592,16 @@ impl Server {
         });
 
         // Create the default namespace. It's idempotent so we can keep trying
-        while let Err(err) = data_manager
-            .create_namespace(&DataNamespace {
-                name: "default".to_string(),
-                extraction_graphs: vec![],
-            })
-            .await
-        {
+        while !self.config.read_only {
+            let Err(err) = data_manager
+                .create_namespace(&DataNamespace {
+                    name: "default".to_string(),
+                    extraction_graphs: vec![],
+                })
+                .await
+            else {
+                break;
+            };
             info!("failed to create default namespace: {}", err);
             tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 
@@ -333,26 +611,44 @@ impl Server {
             }
         }
 
-        let handle = handle.clone();
-        if use_tls {
-            if let Some(tls_config) = self.config.tls.clone() {
-                let config = build_mtls_config(&tls_config)?;
-                let rustls_config = RustlsConfig::from_config(config);
-                axum_server::tls_rustls::bind_rustls(self.addr, rustls_config)
-                    .handle(handle)
-                    .serve(app.into_make_service())
-                    .await?;
-            } else {
-                return Err(anyhow!("TLS is enabled but no TLS config provided"));
-            }
+        let rustls_config = if use_tls {
+            let tls_config = self
+                .config
+                .tls
+                .clone()
+                .ok_or_else(|| anyhow!("TLS is enabled but no TLS config provided"))?;
+            let rustls_config = RustlsConfig::from_config(build_mtls_config(&tls_config)?);
+            spawn_certificate_reloader(tls_config, rustls_config.clone(), shutdown_rx.clone());
+            Some(rustls_config)
         } else {
-            let handle = handle.clone();
-            axum_server::bind(self.addr)
-                .handle(handle)
-                .serve(app.into_make_service())
-                .await?;
-        }
+            None
+        };
 
+        let with_admin_policy = |listener| -> Result<Router> {
+            Ok(app.clone().layer(middleware::from_fn_with_state(
+                Arc::new(AdminRoutePolicy::new(&self.config.admin_api, listener)?),
+                restrict_admin_routes,
+            )))
+        };
+        let public_app = with_admin_policy(Listener::Public)?;
+        let admin_listener = match self.config.admin_addr_sock()? {
+            Some(admin_addr) => {
+                info!("serving admin routes on {}", admin_addr);
+                Some((admin_addr, with_admin_policy(Listener::Admin)?))
+            }
+            None => None,
+        };
+        tokio::try_join!(
+            serve(self.addr, public_app, rustls_config.clone(), handle.clone()),
+            async {
+                match admin_listener {
+                    Some((admin_addr, admin_app)) => {
+                        serve(admin_addr, admin_app, rustls_config.clone(), handle.clone()).await
+                    }
+                    None => Ok(()),
+                }
+            }
+        )?;
         Ok(())
     }
 
@@ -549,6 +845,56 @@ async fn get_namespace(
     }))
 }
 
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/export",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Archive of the extraction graphs and content of the namespace"),
+        (status = NOT_FOUND, description = "Namespace not found")
+    ),
+)]
+#[axum::debug_handler]
+async fn export_namespace(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Response<Body>, IndexifyAPIError> {
+    let archive = state
+        .data_manager
+        .export_namespace(&namespace)
+        .await
+        .map_err(|e| IndexifyAPIError::not_found(&e.to_string()))?;
+    Response::builder()
+        .header(hyper::header::CONTENT_TYPE, "application/json")
+        .body(Body::from(archive))
+        .map_err(|e| IndexifyAPIError::internal_error(anyhow!(e)))
+}
+
+#[tracing::instrument(skip(archive))]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/import",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "The extraction graphs and content of the archive were created in the namespace", body = ImportReport),
+        (status = BAD_REQUEST, description = "Invalid archive, or an extraction graph of the archive exists in the namespace")
+    ),
+)]
+#[axum::debug_handler]
+async fn import_namespace(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    archive: Bytes,
+) -> Result<Json<ImportReport>, IndexifyAPIError> {
+    let report = state
+        .data_manager
+        .import_namespace(&namespace, archive.to_vec())
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    Ok(Json(report))
+}
+
 #[utoipa::path(
     post,
     path = "/namespace/{namespace}/extraction_graph",
@@ -565,547 +911,1089 @@ async fn create_extraction_graph(
     // FIXME: also throws a 500 when the index name already exists
     Path(namespace): Path<String>,
     State(state): State<NamespaceEndpointState>,
-    Json(payload): Json<ExtractionGraphRequest>,
+    principal: Option<Extension<Principal>>,
+    Json(mut payload): Json<ExtractionGraphRequest>,
 ) -> Result<Json<ExtractionGraphResponse>, IndexifyAPIError> {
-    let indexes = state
+    authorize_graphs(principal.as_deref(), &[payload.name.clone()])?;
+    if let Some(principal) = principal {
+        payload.author = Some(principal.subject.clone());
+    }
+    let response = state
         .data_manager
         .create_extraction_graph(&namespace, payload)
         .await
-        .map_err(IndexifyAPIError::internal_error)?
-        .into_iter()
-        .collect();
-    Ok(Json(ExtractionGraphResponse { indexes }))
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(response))
 }
 
-#[tracing::instrument(skip(state, payload))]
+#[tracing::instrument]
 #[utoipa::path(
-    post,
-    path = "/namespaces/{namespace}/add_texts",
-    request_body = TextAddRequest,
+    put,
+    path = "/namespaces/{namespace}/extraction_graphs/{extraction_graph}",
+    request_body = ExtractionGraphRequest,
     tag = "indexify",
     responses(
-        (status = 200, description = "Texts were successfully added to the namespace", body = TextAdditionResponse),
-        (status = BAD_REQUEST, description = "Unable to add texts")
+        (status = 200, description = "Definition of the extraction graph replaced, and added to its history", body = ExtractionGraphResponse),
+        (status = BAD_REQUEST, description = "Extraction graph not found or the definition is invalid")
     ),
 )]
 #[axum::debug_handler]
-async fn add_texts(
-    Path(namespace): Path<String>,
+async fn update_extraction_graph(
+    Path((namespace, extraction_graph)): Path<(String, String)>,
     State(state): State<NamespaceEndpointState>,
-    Json(payload): Json<TextAddRequest>,
-) -> Result<Json<TextAdditionResponse>, IndexifyAPIError> {
-    if payload.extraction_graph_names.is_empty() {
+    principal: Option<Extension<Principal>>,
+    Json(mut payload): Json<ExtractionGraphRequest>,
+) -> Result<Json<ExtractionGraphResponse>, IndexifyAPIError> {
+    if payload.name != extraction_graph {
         return Err(IndexifyAPIError::new(
             StatusCode::BAD_REQUEST,
-            "extraction_graph_names must not be empty",
+            "extraction graph name in the body does not match the path",
         ));
     }
-    for document in &payload.documents {
-        if let Some(id) = &document.id {
-            if !DataManager::is_hex_string(id) {
-                return Err(IndexifyAPIError::new(
-                    StatusCode::BAD_REQUEST,
-                    &format!("Invalid ID format: {}, ID must be a hex string", id),
-                ));
-            }
-            let retrieved_content = state
-                .data_manager
-                .get_content_metadata(&namespace, vec![id.clone()])
-                .await
-                .map_err(IndexifyAPIError::internal_error)?;
-            if !retrieved_content.is_empty() {
-                return Err(IndexifyAPIError::new(
-                    StatusCode::BAD_REQUEST,
-                    &format!("content with the provided id {} already exists", id),
-                ));
-            }
-        }
+    if let Some(principal) = principal {
+        payload.author = Some(principal.subject.clone());
     }
-
-    let content: Vec<api::ContentWithId> = payload
-        .documents
-        .iter()
-        .map(|d| api::ContentWithId {
-            id: d.id.clone().unwrap_or_else(DataManager::make_id),
-            content: api::Content {
-                content_type: mime::TEXT_PLAIN.to_string(),
-                bytes: d.text.as_bytes().to_vec(),
-                labels: d.labels.clone(),
-                features: vec![],
-            },
-            extraction_graph_names: payload.extraction_graph_names.clone(),
-        })
-        .collect();
-    let content_ids = content.iter().map(|c| c.id.clone()).collect();
-    state
-        .data_manager
-        .add_texts(&namespace, content, payload.extraction_graph_names)
-        .await
-        .map_err(|e| {
-            IndexifyAPIError::new(
-                StatusCode::BAD_REQUEST,
-                &format!("failed to add text: {}", e),
-            )
-        })?;
-    Ok(Json(TextAdditionResponse { content_ids }))
-}
-
-#[axum::debug_handler]
-async fn ingest_remote_file(
-    Path(namespace): Path<String>,
-    State(state): State<NamespaceEndpointState>,
-    Json(payload): Json<IngestRemoteFile>,
-) -> Result<Json<IngestRemoteFileResponse>, IndexifyAPIError> {
-    let content_id = state
+    let response = state
         .data_manager
-        .ingest_remote_file(
-            &namespace,
-            payload.id,
-            &payload.url,
-            &payload.mime_type,
-            payload.labels,
-            &payload.extraction_graph_names,
-        )
+        .update_extraction_graph(&namespace, payload)
         .await
-        .map_err(|e| {
-            IndexifyAPIError::new(
-                StatusCode::BAD_REQUEST,
-                &format!("failed to add text: {}", e),
-            )
-        })?;
-    Ok(Json(IngestRemoteFileResponse { content_id }))
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    Ok(Json(response))
 }
 
 #[tracing::instrument]
 #[utoipa::path(
-    put,
-    path = "/namespaces/{namespace}/content/{content_id}/labels",
-    request_body = UpdateLabelsRequest,
+    post,
+    path = "/graph_templates",
+    request_body = internal_api::GraphTemplate,
     tag = "indexify",
     responses(
-        (status = 200, description = "Labels updated successfully"),
-        (status = BAD_REQUEST, description = "Unable to update labels")
+        (status = 200, description = "Graph template created, or replaced when it exists", body = internal_api::GraphTemplate),
+        (status = BAD_REQUEST, description = "Invalid graph template")
     ),
 )]
 #[axum::debug_handler]
-async fn update_labels(
-    Path((namespace, content_id)): Path<(String, String)>,
+async fn set_graph_template(
     State(state): State<NamespaceEndpointState>,
-    Json(body): Json<UpdateLabelsRequest>,
-) -> Result<(), IndexifyAPIError> {
-    state
-        .data_manager
-        .update_labels(&namespace, &content_id, body.labels)
+    principal: Option<Extension<Principal>>,
+    Json(mut payload): Json<internal_api::GraphTemplate>,
+) -> Result<Json<internal_api::GraphTemplate>, IndexifyAPIError> {
+    if let Some(principal) = principal {
+        payload.author = principal.subject.clone();
+    }
+    let template = state
+        .coordinator_client
+        .get()
         .await
-        .map_err(IndexifyAPIError::internal_error)?;
-    Ok(())
+        .map_err(IndexifyAPIError::internal_error)?
+        .set_graph_template(indexify_coordinator::SetGraphTemplateRequest {
+            template: Some(payload.into()),
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, e.message()))?
+        .into_inner()
+        .template
+        .ok_or_else(|| IndexifyAPIError::internal_error(anyhow!("graph template not returned")))?;
+    Ok(Json(
+        template
+            .try_into()
+            .map_err(IndexifyAPIError::internal_error)?,
+    ))
 }
 
 #[tracing::instrument]
 #[utoipa::path(
     get,
-    path = "/namespaces/{namespace}/content",
+    path = "/graph_templates",
     tag = "indexify",
     responses(
-        (status = 200, description = "Lists the contents in the namespace", body = ListContentResponse),
-        (status = BAD_REQUEST, description = "Unable to list contents")
+        (status = 200, description = "Graph templates ordered by name", body = ListGraphTemplatesResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to list graph templates")
     ),
 )]
 #[axum::debug_handler]
-async fn list_content(
-    Path(namespace): Path<String>,
+async fn list_graph_templates(
     State(state): State<NamespaceEndpointState>,
-    filter: Query<super::api::ListContentFilters>,
-) -> Result<Json<ListContentResponse>, IndexifyAPIError> {
-    let content_list = state
-        .data_manager
-        .list_content(
-            &namespace,
-            &filter.source,
-            &filter.parent_id,
-            filter.labels_eq.as_ref(),
-        )
+) -> Result<Json<ListGraphTemplatesResponse>, IndexifyAPIError> {
+    let templates = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .list_graph_templates(indexify_coordinator::ListGraphTemplatesRequest {})
         .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, e.message()))?
+        .into_inner()
+        .templates
+        .into_iter()
+        .map(TryInto::try_into)
+        .collect::<Result<_, _>>()
         .map_err(IndexifyAPIError::internal_error)?;
-    Ok(Json(ListContentResponse { content_list }))
+    Ok(Json(ListGraphTemplatesResponse { templates }))
 }
 
 #[tracing::instrument]
 #[utoipa::path(
-    delete,
-    path = "/namespaces/{namespace}/content",
+    get,
+    path = "/graph_templates/{template}",
     tag = "indexify",
     responses(
-        (status = 200, description = "Deletes specified pieces of content", body = DeleteContentResponse),
-        (status = BAD_REQUEST, description = "Unable to find a piece of content to delete")
+        (status = 200, description = "A graph template", body = internal_api::GraphTemplate),
+        (status = NOT_FOUND, description = "Graph template not found")
     ),
 )]
 #[axum::debug_handler]
-async fn delete_content(
-    Path(namespace): Path<String>,
+async fn get_graph_template(
+    Path(template): Path<String>,
     State(state): State<NamespaceEndpointState>,
-    Json(body): Json<super::api::TombstoneContentRequest>,
-) -> Result<Json<()>, IndexifyAPIError> {
-    let request = indexify_coordinator::TombstoneContentRequest {
-        namespace: namespace.clone(),
-        content_ids: body.content_ids.clone(),
-    };
+) -> Result<Json<internal_api::GraphTemplate>, IndexifyAPIError> {
+    let template = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .get_graph_template(indexify_coordinator::GetGraphTemplateRequest { name: template })
+        .await
+        .map_err(|e| IndexifyAPIError::not_found(e.message()))?
+        .into_inner()
+        .template
+        .ok_or_else(|| IndexifyAPIError::not_found("graph template not found"))?;
+    Ok(Json(
+        template
+            .try_into()
+            .map_err(IndexifyAPIError::internal_error)?,
+    ))
+}
 
+#[tracing::instrument]
+#[utoipa::path(
+    delete,
+    path = "/graph_templates/{template}",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Graph template deleted, graphs instantiated from it are kept"),
+        (status = NOT_FOUND, description = "Graph template not found")
+    ),
+)]
+#[axum::debug_handler]
+async fn delete_graph_template(
+    Path(template): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<(), IndexifyAPIError> {
     state
         .coordinator_client
         .get()
         .await
-        .map_err(|e| {
-            IndexifyAPIError::new(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("failed to get coordinator client: {}", e).as_str(),
-            )
-        })?
-        .tombstone_content(request)
+        .map_err(IndexifyAPIError::internal_error)?
+        .delete_graph_template(indexify_coordinator::DeleteGraphTemplateRequest { name: template })
         .await
-        .map_err(|e| {
-            IndexifyAPIError::new(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("failed to delete content: {}", e).as_str(),
-            )
-        })?;
-
-    Ok(Json(()))
+        .map_err(|e| IndexifyAPIError::not_found(e.message()))?;
+    Ok(())
 }
 
 #[tracing::instrument]
 #[utoipa::path(
-    get,
-    path = "/namespaces/{namespace}/content/{content_id}",
+    post,
+    path = "/namespaces/{namespace}/graph_templates/{template}/instantiate",
+    request_body = InstantiateGraphTemplateRequest,
     tag = "indexify",
     responses(
-        (status = 200, description = "Reads a specific content in the namespace", body = GetRawContentResponse),
-        (status = BAD_REQUEST, description = "Unable to read content")
+        (status = 200, description = "Extraction graph created from the template", body = ExtractionGraphResponse),
+        (status = BAD_REQUEST, description = "Graph template not found, or parameters missing or unknown")
     ),
 )]
 #[axum::debug_handler]
-async fn get_content_metadata(
-    Path((namespace, content_id)): Path<(String, String)>,
+async fn instantiate_graph_template(
+    Path((namespace, template)): Path<(String, String)>,
     State(state): State<NamespaceEndpointState>,
-) -> Result<Json<GetContentMetadataResponse>, IndexifyAPIError> {
-    let content_list = state
+    principal: Option<Extension<Principal>>,
+    Json(payload): Json<InstantiateGraphTemplateRequest>,
+) -> Result<Json<ExtractionGraphResponse>, IndexifyAPIError> {
+    authorize_graphs(principal.as_deref(), &[payload.name.clone()])?;
+    let response = state
         .data_manager
-        .get_content_metadata(&namespace, vec![content_id])
+        .instantiate_graph_template(
+            &namespace,
+            &template,
+            payload,
+            principal.map(|p| p.subject.clone()),
+        )
         .await
-        .map_err(IndexifyAPIError::internal_error)?;
-    let content_metadata = content_list
-        .first()
-        .ok_or_else(|| IndexifyAPIError::new(StatusCode::NOT_FOUND, "content not found"))?;
-
-    Ok(Json(GetContentMetadataResponse {
-        content_metadata: content_metadata.clone(),
-    }))
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    Ok(Json(response))
 }
 
 #[tracing::instrument]
 #[utoipa::path(
     get,
-    path ="/namespaces/{namespace}/content/{content_id}/wait",
+    path = "/namespaces/{namespace}/extraction_graphs/{extraction_graph}/versions",
     tag = "indexify",
     responses(
-        (status = 200, description = "wait for all extraction tasks for content to complete"),
+        (status = 200, description = "Definitions of the extraction graph, oldest first", body = ListExtractionGraphVersionsResponse),
+        (status = NOT_FOUND, description = "Extraction graph not found")
     ),
 )]
 #[axum::debug_handler]
-async fn wait_content_extraction(
-    Path((namespace, content_id)): Path<(String, String)>,
+async fn list_extraction_graph_versions(
+    Path((namespace, extraction_graph)): Path<(String, String)>,
     State(state): State<NamespaceEndpointState>,
-) -> Result<(), IndexifyAPIError> {
-    state
+) -> Result<Json<ListExtractionGraphVersionsResponse>, IndexifyAPIError> {
+    let versions = state
         .data_manager
-        .wait_content_extraction(&content_id)
+        .list_extraction_graph_versions(&namespace, &extraction_graph)
         .await
-        .map_err(IndexifyAPIError::internal_error)
+        .map_err(|e| IndexifyAPIError::not_found(&e.to_string()))?;
+    Ok(Json(ListExtractionGraphVersionsResponse { versions }))
 }
 
 #[tracing::instrument]
 #[utoipa::path(
     get,
-    path ="/namespaces/{namespace}/active_content",
+    path = "/namespaces/{namespace}/extraction_graphs/{extraction_graph}/usage",
     tag = "indexify",
     responses(
-        (status = 200, description = "wait for all extraction tasks for content to complete"),
+        (status = 200, description = "Resources the tasks of each version of the extraction graph used", body = ExtractionGraphUsageResponse),
+        (status = NOT_FOUND, description = "Extraction graph not found")
     ),
 )]
 #[axum::debug_handler]
-async fn active_content(
-    Path(namespace): Path<String>,
+async fn get_extraction_graph_usage(
+    Path((namespace, extraction_graph)): Path<(String, String)>,
     State(state): State<NamespaceEndpointState>,
-) -> Result<Json<Vec<String>>, IndexifyAPIError> {
-    let res = state
+) -> Result<Json<ExtractionGraphUsageResponse>, IndexifyAPIError> {
+    let usage = state
         .data_manager
-        .list_active_contents(&namespace)
+        .get_extraction_graph_usage(&namespace, &extraction_graph)
         .await
-        .map_err(IndexifyAPIError::internal_error)?;
-    Ok(Json(res))
+        .map_err(|e| IndexifyAPIError::not_found(&e.to_string()))?;
+    Ok(Json(ExtractionGraphUsageResponse { usage }))
 }
 
 #[tracing::instrument]
 #[utoipa::path(
     get,
-    path = "/namespaces/{namespace}/content/{content_id}/content-tree",
+    path = "/namespaces/{namespace}/extraction_graphs/{extraction_graph}/slo",
     tag = "indexify",
     responses(
-        (status = 200, description = "Gets a content tree rooted at a specific content id in the namespace"),
-        (status = BAD_REQUEST, description = "Unable to read content tree")
-    )
+        (status = 200, description = "Compliance of the extraction policies of the graph with their objectives over the last hour", body = SloComplianceResponse),
+        (status = NOT_FOUND, description = "Extraction graph not found")
+    ),
 )]
 #[axum::debug_handler]
-async fn get_content_tree_metadata(
-    Path((namespace, content_id)): Path<(String, String)>,
+async fn get_slo_compliance(
+    Path((namespace, extraction_graph)): Path<(String, String)>,
     State(state): State<NamespaceEndpointState>,
-) -> Result<Json<GetContentTreeMetadataResponse>, IndexifyAPIError> {
-    let content_tree_metadata = state
+) -> Result<Json<SloComplianceResponse>, IndexifyAPIError> {
+    let compliance = state
         .data_manager
-        .get_content_tree_metadata(&namespace, content_id)
+        .get_slo_compliance(&namespace, &extraction_graph)
         .await
-        .map_err(IndexifyAPIError::internal_error)?;
-    Ok(Json(GetContentTreeMetadataResponse {
-        content_tree_metadata,
-    }))
+        .map_err(|e| IndexifyAPIError::not_found(&e.to_string()))?;
+    Ok(Json(SloComplianceResponse { compliance }))
 }
 
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/extraction_graphs/{extraction_graph}/rollback",
+    request_body = RollbackExtractionGraphRequest,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "The version is the latest version of the extraction graph", body = ExtractionGraphResponse),
+        (status = BAD_REQUEST, description = "Extraction graph or version not found")
+    ),
+)]
 #[axum::debug_handler]
-async fn download_content(
-    Path((namespace, content_id)): Path<(String, String)>,
+async fn rollback_extraction_graph(
+    Path((namespace, extraction_graph)): Path<(String, String)>,
     State(state): State<NamespaceEndpointState>,
-) -> Result<Response<Body>, IndexifyAPIError> {
-    let content_list = state
+    principal: Option<Extension<Principal>>,
+    Json(payload): Json<RollbackExtractionGraphRequest>,
+) -> Result<Json<ExtractionGraphResponse>, IndexifyAPIError> {
+    let author = principal
+        .map(|principal| principal.subject.clone())
+        .or(payload.author)
+        .unwrap_or_default();
+    let response = state
         .data_manager
-        .get_content_metadata(&namespace, vec![content_id])
-        .await;
-    let content_list = content_list.map_err(IndexifyAPIError::internal_error)?;
-    let content_metadata = content_list
-        .first()
-        .ok_or(anyhow!("content not found"))
-        .map_err(|e| IndexifyAPIError::not_found(&e.to_string()))?
-        .clone();
-    let mut resp_builder =
-        Response::builder().header("Content-Type", content_metadata.mime_type.clone());
-    if content_metadata.size > 0 {
-        resp_builder = resp_builder.header("Content-Length", content_metadata.size);
-    }
-    resp_builder
-        .body(Body::from_stream(async_stream::stream! {
-            let storage_url = &content_metadata.storage_url.clone();
-            let content_reader = state.content_reader.clone();
-            let reader = content_reader.get(storage_url);
-            let mut content_stream = reader.get(storage_url);
-            while let Some(buf)  = content_stream.next().await {
-                yield buf;
-            }
-        }))
-        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))
-}
-
-#[derive(Debug, serde::Deserialize)]
-struct UploadFileQueryParams {
-    id: Option<String>,
-    extraction_graph_names: Option<String>,
+        .rollback_extraction_graph(&namespace, &extraction_graph, payload.version, &author)
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    Ok(Json(response))
 }
 
 #[tracing::instrument]
 #[utoipa::path(
     post,
-    path = "/namespaces/{namespace}/upload_file",
-    request_body(content_type = "multipart/form-data", content = Vec<u8>),
+    path = "/namespaces/{namespace}/extraction_graphs/validate",
+    request_body = ExtractionGraphRequest,
     tag = "indexify",
     responses(
-        (status = 200, description = "Uploads a file to the namespace"),
-        (status = BAD_REQUEST, description = "Unable to upload file")
+        (status = 200, description = "Diagnostics for the extraction graph, nothing is created", body = ValidateExtractionGraphResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to validate the extraction graph")
     ),
 )]
 #[axum::debug_handler]
-async fn upload_file(
+async fn validate_extraction_graph(
     Path(namespace): Path<String>,
     State(state): State<NamespaceEndpointState>,
-    Query(params): Query<UploadFileQueryParams>,
-    mut files: Multipart,
-) -> Result<Json<UploadFileResponse>, IndexifyAPIError> {
-    let mut labels = HashMap::new();
-
-    let extraction_graph_names = params
-        .extraction_graph_names
-        .clone()
-        .ok_or_else(|| {
-            IndexifyAPIError::new(
-                StatusCode::BAD_REQUEST,
-                "extraction_graph_names parameter is required",
-            )
-        })?
-        .split(',')
-        .map(|s| s.trim().to_string())
-        .collect();
+    Json(payload): Json<ExtractionGraphRequest>,
+) -> Result<Json<ValidateExtractionGraphResponse>, IndexifyAPIError> {
+    let diagnostics = state
+        .data_manager
+        .validate_extraction_graph(&namespace, payload)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    let valid = diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.severity != DiagnosticSeverity::Error);
+    Ok(Json(ValidateExtractionGraphResponse { valid, diagnostics }))
+}
 
-    let id = params.id.clone().unwrap_or_else(DataManager::make_id);
-    if !DataManager::is_hex_string(&id) {
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/extraction_graphs/{extraction_graph}/diff",
+    request_body = ExtractionGraphRequest,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Difference between the extraction graph and the proposed definition", body = ExtractionGraphDiff),
+        (status = NOT_FOUND, description = "Extraction graph not found")
+    ),
+)]
+#[axum::debug_handler]
+async fn diff_extraction_graph(
+    Path((namespace, extraction_graph)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<ExtractionGraphRequest>,
+) -> Result<Json<ExtractionGraphDiff>, IndexifyAPIError> {
+    if payload.name != extraction_graph {
         return Err(IndexifyAPIError::new(
             StatusCode::BAD_REQUEST,
-            "Invalid ID format, ID must be a hex string",
+            "extraction graph name in the body does not match the path",
         ));
     }
-
-    //  check if the id already exists for content metadata
-    let retrieved_content = state
+    let current = state
         .data_manager
-        .get_content_metadata(&namespace, vec![id.clone()])
+        .get(&namespace)
         .await
-        .map_err(IndexifyAPIError::internal_error)?;
-    if !retrieved_content.is_empty() {
+        .map_err(IndexifyAPIError::internal_error)?
+        .extraction_graphs
+        .into_iter()
+        .find(|graph| graph.name == extraction_graph)
+        .ok_or_else(|| {
+            IndexifyAPIError::new(StatusCode::NOT_FOUND, "extraction graph not found")
+        })?;
+    Ok(Json(api_utils::diff_extraction_graph(&current, &payload)))
+}
+
+#[tracing::instrument(skip(state, payload))]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/add_texts",
+    request_body = TextAddRequest,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Texts were successfully added to the namespace", body = TextAdditionResponse),
+        (status = BAD_REQUEST, description = "Unable to add texts")
+    ),
+)]
+#[axum::debug_handler]
+async fn add_texts(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    principal: Option<Extension<Principal>>,
+    Json(payload): Json<TextAddRequest>,
+) -> Result<Json<TextAdditionResponse>, IndexifyAPIError> {
+    authorize_graphs(principal.as_deref(), &payload.extraction_graph_names)?;
+    if payload.extraction_graph_names.is_empty() {
         return Err(IndexifyAPIError::new(
             StatusCode::BAD_REQUEST,
-            "content with the provided id already exists",
+            "extraction_graph_names must not be empty",
         ));
     }
-
-    while let Some(field) = files.next_field().await.unwrap() {
-        if let Some(name) = field.file_name() {
-            info!("user provided file name = {:?}", name);
-            let ext = std::path::Path::new(&name)
-                .extension()
-                .unwrap_or_default()
-                .to_str()
-                .unwrap_or_default();
-            let name = nanoid::nanoid!(16);
-            let name = if !ext.is_empty() {
-                format!("{}.{}", name, ext)
-            } else {
-                name
-            };
-            let content_mime = mime_guess::from_ext(ext).first_or_octet_stream();
-            info!("writing to blob store, file name = {:?}", name);
-
-            let stream = field.map(|res| res.map_err(|err| anyhow::anyhow!(err)));
-            let content_metadata = state
+    for document in &payload.documents {
+        if let Some(id) = &document.id {
+            if !DataManager::is_hex_string(id) {
+                return Err(IndexifyAPIError::new(
+                    StatusCode::BAD_REQUEST,
+                    &format!("Invalid ID format: {}, ID must be a hex string", id),
+                ));
+            }
+            let retrieved_content = state
                 .data_manager
-                .upload_file(
-                    &namespace,
-                    stream,
-                    &name,
-                    content_mime,
-                    labels,
-                    Some(&id),
-                    extraction_graph_names,
-                )
-                .await
-                .map_err(|e| {
-                    IndexifyAPIError::new(
-                        StatusCode::BAD_REQUEST,
-                        &format!("failed to upload file: {}", e),
-                    )
-                })?;
-            let size_bytes = content_metadata.size_bytes;
-            state
-                .data_manager
-                .create_content_metadata(content_metadata)
+                .get_content_metadata(&namespace, vec![id.clone()])
                 .await
-                .map_err(|e| {
-                    IndexifyAPIError::new(
-                        StatusCode::BAD_REQUEST,
-                        &format!("failed to create content for file: {}", e),
-                    )
-                })?;
-            state.metrics.node_content_uploads.add(1, &[]);
-            state
-                .metrics
-                .node_content_bytes_uploaded
-                .add(size_bytes, &[]);
-            return Ok(Json(UploadFileResponse { content_id: id }));
-        } else if let Some(name) = field.name() {
-            let name = name.to_string();
-            let value = field.text().await.map_err(|e| {
-                IndexifyAPIError::new(
+                .map_err(IndexifyAPIError::internal_error)?;
+            if !retrieved_content.is_empty() {
+                return Err(IndexifyAPIError::new(
                     StatusCode::BAD_REQUEST,
-                    &format!("failed to upload file: {}", e),
-                )
-            })?;
-            labels.insert(name, value);
+                    &format!("content with the provided id {} already exists", id),
+                ));
+            }
         }
     }
-    Err(IndexifyAPIError::new(
-        StatusCode::BAD_REQUEST,
-        "no file provided",
-    ))
+
+    let content: Vec<api::ContentWithId> = payload
+        .documents
+        .iter()
+        .map(|d| api::ContentWithId {
+            id: d.id.clone().unwrap_or_else(DataManager::make_id),
+            content: api::Content {
+                content_type: mime::TEXT_PLAIN.to_string(),
+                bytes: d.text.as_bytes().to_vec(),
+                labels: d.labels.clone(),
+                features: vec![],
+            },
+            extraction_graph_names: payload.extraction_graph_names.clone(),
+        })
+        .collect();
+    let content_ids = content.iter().map(|c| c.id.clone()).collect();
+    state
+        .data_manager
+        .add_texts(
+            &namespace,
+            content,
+            payload.extraction_graph_names,
+            payload.priority,
+            DataManager::expires_at(payload.ttl_secs),
+        )
+        .await
+        .map_err(|e| {
+            IndexifyAPIError::new(
+                StatusCode::BAD_REQUEST,
+                &format!("failed to add text: {}", e),
+            )
+        })?;
+    Ok(Json(TextAdditionResponse { content_ids }))
+}
+
+#[axum::debug_handler]
+async fn ingest_remote_file(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    principal: Option<Extension<Principal>>,
+    Json(payload): Json<IngestRemoteFile>,
+) -> Result<Json<IngestRemoteFileResponse>, IndexifyAPIError> {
+    authorize_graphs(principal.as_deref(), &payload.extraction_graph_names)?;
+    // The bytes are fetched by the executors, only the declared type can be
+    // checked here
+    payload.mime_type.parse::<mime::Mime>().map_err(|e| {
+        IndexifyAPIError::new(
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            &format!("invalid mime type \"{}\": {}", payload.mime_type, e),
+        )
+    })?;
+    let content_id = state
+        .data_manager
+        .ingest_remote_file(
+            &namespace,
+            payload.id,
+            &payload.url,
+            &payload.mime_type,
+            payload.labels,
+            &payload.extraction_graph_names,
+            payload.priority,
+            DataManager::expires_at(payload.ttl_secs),
+        )
+        .await
+        .map_err(|e| {
+            IndexifyAPIError::new(
+                StatusCode::BAD_REQUEST,
+                &format!("failed to add text: {}", e),
+            )
+        })?;
+    Ok(Json(IngestRemoteFileResponse { content_id }))
 }
 
 #[tracing::instrument]
 #[utoipa::path(
     put,
-    path = "/namespaces/{namespace}/content/:content_id",
+    path = "/namespaces/{namespace}/content/{content_id}/labels",
+    request_body = UpdateLabelsRequest,
     tag = "indexify",
     responses(
-        (status = 200, description = "Updates a specified piece of content", body = UpdateContentResponse),
-        (status = BAD_REQUEST, description = "Unable to find a piece of content to update")
+        (status = 200, description = "Labels updated successfully"),
+        (status = BAD_REQUEST, description = "Unable to update labels")
     ),
 )]
 #[axum::debug_handler]
-async fn update_content(
+async fn update_labels(
     Path((namespace, content_id)): Path<(String, String)>,
     State(state): State<NamespaceEndpointState>,
-    mut files: Multipart,
+    Json(body): Json<UpdateLabelsRequest>,
 ) -> Result<(), IndexifyAPIError> {
-    //  check that the content exists
-    let content_metadata = state
+    state
         .data_manager
-        .get_content_metadata(&namespace, vec![content_id.clone()])
+        .update_labels(&namespace, &content_id, body.labels)
         .await
         .map_err(IndexifyAPIError::internal_error)?;
+    Ok(())
+}
 
-    let content_metadata = content_metadata
-        .first()
-        .ok_or_else(|| IndexifyAPIError::not_found(&format!("content {} not found", content_id)))?;
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/content",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Lists the contents in the namespace", body = ListContentResponse),
+        (status = BAD_REQUEST, description = "Unable to list contents")
+    ),
+)]
+#[axum::debug_handler]
+async fn list_content(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    filter: Query<super::api::ListContentFilters>,
+) -> Result<Json<ListContentResponse>, IndexifyAPIError> {
+    let content_list = state
+        .data_manager
+        .list_content(
+            &namespace,
+            &filter.source,
+            &filter.parent_id,
+            filter.labels_eq.as_ref(),
+        )
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(ListContentResponse { content_list }))
+}
 
-    while let Some(file) = files.next_field().await.unwrap() {
-        let name = file
-            .file_name()
-            .ok_or(IndexifyAPIError::new(
-                StatusCode::BAD_REQUEST,
-                "file_name is not present",
-            ))?
-            .to_string();
-        info!("user provided file name = {:?}", name);
-        let ext = std::path::Path::new(&name)
-            .extension()
-            .unwrap_or_default()
-            .to_str()
-            .unwrap_or_default();
-        let name = nanoid::nanoid!(16);
-        let name = if !ext.is_empty() {
-            format!("{}.{}", name, ext)
-        } else {
-            name
-        };
-        let content_mime = mime_guess::from_ext(ext).first_or_octet_stream();
-        info!("writing to blob store, file name = {:?}", name);
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/content_stream",
+    tag = "indexify",
+    params(StreamCursor),
+    responses(
+        (status = 200, description = "The content of the namespace as JSON lines, written as it is read. A stream that fails ends with a StreamError line.", content_type = "application/x-ndjson", body = ContentMetadata),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to list contents")
+    ),
+)]
+#[axum::debug_handler]
+async fn stream_content(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    filter: Query<super::api::ListContentFilters>,
+    Query(StreamCursor { cursor }): Query<StreamCursor>,
+) -> Result<Response<Body>, IndexifyAPIError> {
+    let content = state
+        .data_manager
+        .stream_content(
+            &namespace,
+            &filter.source,
+            &filter.parent_id,
+            filter.labels_eq.as_ref(),
+            cursor,
+        )
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Response::builder()
+        .header(CONTENT_TYPE, "application/x-ndjson")
+        .body(Body::from_stream(api_utils::json_lines(content)))
+        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))
+}
 
-        let stream = file.map(|res| res.map_err(|err| anyhow::anyhow!(err)));
-        let new_content_metadata = state
-            .data_manager
-            .upload_file(
-                &namespace,
-                stream,
-                &name,
-                content_mime,
-                content_metadata.labels.clone(),
-                Some(&content_metadata.id),
-                vec![],
+#[tracing::instrument]
+#[utoipa::path(
+    delete,
+    path = "/namespaces/{namespace}/content",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Deletes specified pieces of content", body = DeleteContentResponse),
+        (status = BAD_REQUEST, description = "Unable to find a piece of content to delete")
+    ),
+)]
+#[axum::debug_handler]
+async fn delete_content(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Json(body): Json<super::api::TombstoneContentRequest>,
+) -> Result<Json<()>, IndexifyAPIError> {
+    let request = indexify_coordinator::TombstoneContentRequest {
+        namespace: namespace.clone(),
+        content_ids: body.content_ids.clone(),
+    };
+
+    state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(|e| {
+            IndexifyAPIError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("failed to get coordinator client: {}", e).as_str(),
             )
-            .await
-            .map_err(|e| {
-                IndexifyAPIError::new(
-                    StatusCode::BAD_REQUEST,
-                    &format!("failed to upload file: {}", e),
-                )
-            })?;
+        })?
+        .tombstone_content(request)
+        .await
+        .map_err(|e| {
+            IndexifyAPIError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("failed to delete content: {}", e).as_str(),
+            )
+        })?;
 
-        if new_content_metadata.hash == content_metadata.hash {
-            info!("the content received is the same, not creating content metadata and removing the created file");
-            let _ = state
+    Ok(Json(()))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/content/{content_id}",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Reads a specific content in the namespace", body = GetRawContentResponse),
+        (status = BAD_REQUEST, description = "Unable to read content")
+    ),
+)]
+#[axum::debug_handler]
+async fn get_content_metadata(
+    Path((namespace, content_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<GetContentMetadataResponse>, IndexifyAPIError> {
+    let content_list = state
+        .data_manager
+        .get_content_metadata(&namespace, vec![content_id])
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    let content_metadata = content_list
+        .first()
+        .ok_or_else(|| IndexifyAPIError::new(StatusCode::NOT_FOUND, "content not found"))?;
+
+    Ok(Json(GetContentMetadataResponse {
+        content_metadata: content_metadata.clone(),
+    }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path ="/namespaces/{namespace}/content/{content_id}/wait",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "wait for all extraction tasks for content to complete"),
+    ),
+)]
+#[axum::debug_handler]
+async fn wait_content_extraction(
+    Path((namespace, content_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<(), IndexifyAPIError> {
+    state
+        .data_manager
+        .wait_content_extraction(&content_id)
+        .await
+        .map_err(IndexifyAPIError::internal_error)
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path ="/namespaces/{namespace}/active_content",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "wait for all extraction tasks for content to complete"),
+    ),
+)]
+#[axum::debug_handler]
+async fn active_content(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<Vec<String>>, IndexifyAPIError> {
+    let res = state
+        .data_manager
+        .list_active_contents(&namespace)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(res))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/content/{content_id}/content-tree",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Gets a content tree rooted at a specific content id in the namespace", body = GetContentTreeMetadataResponse),
+        (status = BAD_REQUEST, description = "Unable to read content tree")
+    )
+)]
+#[axum::debug_handler]
+async fn get_content_tree_metadata(
+    Path((namespace, content_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<GetContentTreeMetadataResponse>, IndexifyAPIError> {
+    let content_tree_metadata = state
+        .data_manager
+        .get_content_tree_metadata(&namespace, content_id)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(GetContentTreeMetadataResponse {
+        content_tree_metadata,
+    }))
+}
+
+#[axum::debug_handler]
+async fn download_content(
+    Path((namespace, content_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, IndexifyAPIError> {
+    let content_list = state
+        .data_manager
+        .get_content_metadata(&namespace, vec![content_id])
+        .await;
+    let content_list = content_list.map_err(IndexifyAPIError::internal_error)?;
+    let content_metadata = content_list
+        .first()
+        .ok_or(anyhow!("content not found"))
+        .map_err(|e| IndexifyAPIError::not_found(&e.to_string()))?
+        .clone();
+    let accept = headers.get(ACCEPT).and_then(|value| value.to_str().ok());
+    let content_type = api_utils::negotiate_content_type(accept, &content_metadata.mime_type)
+        .ok_or_else(|| {
+            IndexifyAPIError::new(
+                StatusCode::NOT_ACCEPTABLE,
+                &format!(
+                    "content is {}, which the accept header doesn't allow",
+                    content_metadata.mime_type
+                ),
+            )
+        })?;
+    let mut resp_builder = Response::builder()
+        .header("Content-Type", content_type)
+        .header(VARY, "Accept");
+
+    // Ranges can only be resolved when the size of the content is known, the
+    // full content is returned otherwise.
+    let range = headers
+        .get(RANGE)
+        .filter(|_| content_metadata.size > 0)
+        .map(|value| {
+            value
+                .to_str()
+                .map_err(|e| anyhow!(e))
+                .and_then(api_utils::parse_byte_range)
+                .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))
+        })
+        .transpose()?;
+    if let Some(range) = range {
+        let size = content_metadata.size;
+        let (start, end) = range.resolve(size).ok_or_else(|| {
+            IndexifyAPIError::new(
+                StatusCode::RANGE_NOT_SATISFIABLE,
+                &format!("range not satisfiable for content of {} bytes", size),
+            )
+        })?;
+        return resp_builder
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(ACCEPT_RANGES, "bytes")
+            .header(CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, size))
+            .header(CONTENT_LENGTH, end - start + 1)
+            .body(Body::from_stream(async_stream::stream! {
+                let storage_url = &content_metadata.storage_url.clone();
+                let reader = state.content_reader.get(storage_url);
+                let mut content_stream = reader.get_range(storage_url, start, end);
+                let mut sent = 0;
+                while let Some(buf) = content_stream.next().await {
+                    if let Ok(bytes) = &buf {
+                        sent += bytes.len() as u64;
+                    }
+                    yield buf;
+                }
+                record_egress(state.data_manager.clone(), namespace, sent);
+            }))
+            .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()));
+    }
+
+    if content_metadata.size > 0 {
+        resp_builder = resp_builder
+            .header("Content-Length", content_metadata.size)
+            .header(ACCEPT_RANGES, "bytes");
+    }
+    resp_builder
+        .body(Body::from_stream(async_stream::stream! {
+            let storage_url = &content_metadata.storage_url.clone();
+            let content_reader = state.content_reader.clone();
+            let reader = content_reader.get(storage_url);
+            let mut content_stream = reader.get(storage_url);
+            let mut sent = 0;
+            while let Some(buf)  = content_stream.next().await {
+                if let Ok(bytes) = &buf {
+                    sent += bytes.len() as u64;
+                }
+                yield buf;
+            }
+            record_egress(state.data_manager.clone(), namespace, sent);
+        }))
+        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))
+}
+
+/// Meters the bytes sent by a download once it finished, without holding up
+/// the end of the response. Downloads the client aborted are not metered.
+fn record_egress(data_manager: Arc<DataManager>, namespace: String, bytes: u64) {
+    tokio::spawn(async move {
+        if let Err(e) = data_manager.record_egress(&namespace, bytes).await {
+            tracing::warn!(
+                "failed to meter {} bytes downloaded from namespace {}: {}",
+                bytes,
+                namespace,
+                e
+            );
+        }
+    });
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct UploadFileQueryParams {
+    id: Option<String>,
+    extraction_graph_names: Option<String>,
+    #[serde(default)]
+    priority: i32,
+    ttl_secs: Option<u64>,
+}
+
+/// Returns the content type of an uploaded file along with its bytes. The
+/// type declared for the part wins over the one guessed from the file
+/// extension, and the first chunk of the file is checked against it.
+async fn checked_file_stream(
+    field: Field<'_>,
+    guessed_mime: mime::Mime,
+) -> Result<
+    (
+        mime::Mime,
+        impl Stream<Item = Result<Bytes>> + Send + Unpin + '_,
+    ),
+    IndexifyAPIError,
+> {
+    let content_type = field
+        .content_type()
+        .filter(|content_type| *content_type != mime::APPLICATION_OCTET_STREAM.as_ref())
+        .map(str::to_string)
+        .unwrap_or_else(|| guessed_mime.to_string());
+    let mut stream = field.map(|res| res.map_err(|err| anyhow::anyhow!(err)));
+    let first_chunk = stream
+        .next()
+        .await
+        .transpose()
+        .map_err(|e| {
+            IndexifyAPIError::new(
+                StatusCode::BAD_REQUEST,
+                &format!("failed to upload file: {}", e),
+            )
+        })?
+        .unwrap_or_default();
+    let content_mime = api_utils::validate_content_type(&content_type, &first_chunk)
+        .map_err(|e| IndexifyAPIError::new(StatusCode::UNSUPPORTED_MEDIA_TYPE, &e.to_string()))?;
+    Ok((
+        content_mime,
+        tokio_stream::iter([Ok(first_chunk)]).chain(stream),
+    ))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/upload_file",
+    request_body(content_type = "multipart/form-data", content = Vec<u8>),
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Uploads a file to the namespace", body = UploadFileResponse),
+        (status = BAD_REQUEST, description = "Unable to upload file")
+    ),
+)]
+#[axum::debug_handler]
+async fn upload_file(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Query(params): Query<UploadFileQueryParams>,
+    principal: Option<Extension<Principal>>,
+    mut files: Multipart,
+) -> Result<Json<UploadFileResponse>, IndexifyAPIError> {
+    let mut labels = HashMap::new();
+
+    let extraction_graph_names: Vec<String> = params
+        .extraction_graph_names
+        .clone()
+        .ok_or_else(|| {
+            IndexifyAPIError::new(
+                StatusCode::BAD_REQUEST,
+                "extraction_graph_names parameter is required",
+            )
+        })?
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .collect();
+    authorize_graphs(principal.as_deref(), &extraction_graph_names)?;
+
+    let id = params.id.clone().unwrap_or_else(DataManager::make_id);
+    if !DataManager::is_hex_string(&id) {
+        return Err(IndexifyAPIError::new(
+            StatusCode::BAD_REQUEST,
+            "Invalid ID format, ID must be a hex string",
+        ));
+    }
+
+    //  check if the id already exists for content metadata
+    let retrieved_content = state
+        .data_manager
+        .get_content_metadata(&namespace, vec![id.clone()])
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    if !retrieved_content.is_empty() {
+        return Err(IndexifyAPIError::new(
+            StatusCode::BAD_REQUEST,
+            "content with the provided id already exists",
+        ));
+    }
+
+    while let Some(field) = files.next_field().await.unwrap() {
+        if let Some(name) = field.file_name() {
+            info!("user provided file name = {:?}", name);
+            let ext = std::path::Path::new(&name)
+                .extension()
+                .unwrap_or_default()
+                .to_str()
+                .unwrap_or_default();
+            let name = nanoid::nanoid!(16);
+            let name = if !ext.is_empty() {
+                format!("{}.{}", name, ext)
+            } else {
+                name
+            };
+            let guessed_mime = mime_guess::from_ext(ext).first_or_octet_stream();
+            let (content_mime, stream) = checked_file_stream(field, guessed_mime).await?;
+            info!("writing to blob store, file name = {:?}", name);
+
+            let mut content_metadata = state
+                .data_manager
+                .upload_file(
+                    &namespace,
+                    stream,
+                    &name,
+                    content_mime,
+                    labels,
+                    Some(&id),
+                    extraction_graph_names,
+                )
+                .await
+                .map_err(|e| {
+                    IndexifyAPIError::new(
+                        StatusCode::BAD_REQUEST,
+                        &format!("failed to upload file: {}", e),
+                    )
+                })?;
+            content_metadata.priority = params.priority;
+            content_metadata.expires_at = DataManager::expires_at(params.ttl_secs);
+            let size_bytes = content_metadata.size_bytes;
+            state
+                .data_manager
+                .create_content_metadata(content_metadata)
+                .await
+                .map_err(|e| {
+                    IndexifyAPIError::new(
+                        StatusCode::BAD_REQUEST,
+                        &format!("failed to create content for file: {}", e),
+                    )
+                })?;
+            state.metrics.node_content_uploads.add(1, &[]);
+            state
+                .metrics
+                .node_content_bytes_uploaded
+                .add(size_bytes, &[]);
+            return Ok(Json(UploadFileResponse { content_id: id }));
+        } else if let Some(name) = field.name() {
+            let name = name.to_string();
+            let value = field.text().await.map_err(|e| {
+                IndexifyAPIError::new(
+                    StatusCode::BAD_REQUEST,
+                    &format!("failed to upload file: {}", e),
+                )
+            })?;
+            labels.insert(name, value);
+        }
+    }
+    Err(IndexifyAPIError::new(
+        StatusCode::BAD_REQUEST,
+        "no file provided",
+    ))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    put,
+    path = "/namespaces/{namespace}/content/:content_id",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Updates a specified piece of content", body = UpdateContentResponse),
+        (status = BAD_REQUEST, description = "Unable to find a piece of content to update")
+    ),
+)]
+#[axum::debug_handler]
+async fn update_content(
+    Path((namespace, content_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    mut files: Multipart,
+) -> Result<(), IndexifyAPIError> {
+    //  check that the content exists
+    let content_metadata = state
+        .data_manager
+        .get_content_metadata(&namespace, vec![content_id.clone()])
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+
+    let content_metadata = content_metadata
+        .first()
+        .ok_or_else(|| IndexifyAPIError::not_found(&format!("content {} not found", content_id)))?;
+
+    while let Some(file) = files.next_field().await.unwrap() {
+        let name = file
+            .file_name()
+            .ok_or(IndexifyAPIError::new(
+                StatusCode::BAD_REQUEST,
+                "file_name is not present",
+            ))?
+            .to_string();
+        info!("user provided file name = {:?}", name);
+        let ext = std::path::Path::new(&name)
+            .extension()
+            .unwrap_or_default()
+            .to_str()
+            .unwrap_or_default();
+        let name = nanoid::nanoid!(16);
+        let name = if !ext.is_empty() {
+            format!("{}.{}", name, ext)
+        } else {
+            name
+        };
+        let guessed_mime = mime_guess::from_ext(ext).first_or_octet_stream();
+        let (content_mime, stream) = checked_file_stream(file, guessed_mime).await?;
+        info!("writing to blob store, file name = {:?}", name);
+
+        let mut new_content_metadata = state
+            .data_manager
+            .upload_file(
+                &namespace,
+                stream,
+                &name,
+                content_mime,
+                content_metadata.labels.clone(),
+                Some(&content_metadata.id),
+                vec![],
+            )
+            .await
+            .map_err(|e| {
+                IndexifyAPIError::new(
+                    StatusCode::BAD_REQUEST,
+                    &format!("failed to upload file: {}", e),
+                )
+            })?;
+
+        if new_content_metadata.hash == content_metadata.hash {
+            info!("the content received is the same, not creating content metadata and removing the created file");
+            let _ = state
                 .data_manager
                 .delete_file(&new_content_metadata.storage_url)
                 .await
@@ -1114,140 +2002,939 @@ async fn update_content(
                 });
             return Ok(());
         }
+        new_content_metadata.priority = content_metadata.priority;
+        new_content_metadata.expires_at = content_metadata.expires_at;
+
+        state
+            .data_manager
+            .create_content_metadata(new_content_metadata)
+            .await
+            .map_err(|e| {
+                IndexifyAPIError::new(
+                    StatusCode::BAD_REQUEST,
+                    &format!("failed to create content for file: {}", e),
+                )
+            })?;
+    }
+
+    Ok(())
+}
+
+async fn ingest_extracted_content(
+    ws: WebSocketUpgrade<IngestExtractedContentResponse, IngestExtractedContent>,
+    State(state): State<NamespaceEndpointState>,
+) -> impl IntoResponse {
+    ws.on_upgrade(|socket| IngestExtractedContentState::new(state).run(socket))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/executors",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "List of currently running executors", body = ListExecutorsResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to load executors")
+    ),
+)]
+#[axum::debug_handler]
+async fn list_executors(
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<ListExecutorsResponse>, IndexifyAPIError> {
+    let executors = state
+        .data_manager
+        .list_executors()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(ListExecutorsResponse { executors }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/extractors",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "List of extractors available", body = ListExtractorsResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to search index")
+    ),
+)]
+#[axum::debug_handler]
+async fn list_extractors(
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<ListExtractorsResponse>, IndexifyAPIError> {
+    let extractors = state
+        .data_manager
+        .list_extractors()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .into_iter()
+        .collect();
+    Ok(Json(ListExtractorsResponse { extractors }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/extractors/extract",
+    tag = "indexify",
+    request_body = ExtractRequest,
+    responses(
+        (status = 200, description = "Extract content from an extractor", body = ExtractResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to search index")
+    ),
+)]
+#[axum::debug_handler]
+async fn list_state_changes(
+    State(state): State<NamespaceEndpointState>,
+    Query(_query): Query<ListStateChanges>,
+) -> Result<Json<ListStateChangesResponse>, IndexifyAPIError> {
+    let state_changes = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .list_state_changes(ListStateChangesRequest {})
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))?
+        .into_inner()
+        .changes;
+
+    let state_changes: Vec<indexify_internal_api::StateChange> = state_changes
+        .into_iter()
+        .map(|c| c.try_into())
+        .filter_map(|c| c.ok())
+        .collect();
+
+    Ok(Json(ListStateChangesResponse { state_changes }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/tasks",
+    path = "/tasks",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Lists tasks", body = ListTasksResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to list tasks")
+    ),
+)]
+async fn list_tasks(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Query(query): Query<ListTasks>,
+) -> Result<Json<ListTasksResponse>, IndexifyAPIError> {
+    let tasks = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .list_tasks(ListTasksRequest {
+            namespace: namespace.clone(),
+            extraction_policy: query.extraction_policy.unwrap_or("".to_string()),
+            cursor: String::new(),
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, e.message()))?
+        .into_inner()
+        .tasks;
+    let tasks = tasks
+        .into_iter()
+        .map(|t| t.try_into())
+        .filter_map(|t| t.ok())
+        .collect();
+    Ok(Json(ListTasksResponse { tasks }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/tasks_stream",
+    tag = "indexify",
+    params(StreamCursor),
+    responses(
+        (status = 200, description = "The tasks of the namespace as JSON lines, written as they are read. A stream that fails ends with a StreamError line.", content_type = "application/x-ndjson", body = Task),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to list tasks")
+    ),
+)]
+#[axum::debug_handler]
+async fn stream_tasks(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Query(query): Query<ListTasks>,
+    Query(StreamCursor { cursor }): Query<StreamCursor>,
+) -> Result<Response<Body>, IndexifyAPIError> {
+    let tasks = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .stream_tasks(ListTasksRequest {
+            namespace,
+            extraction_policy: query.extraction_policy.unwrap_or_default(),
+            cursor: cursor.unwrap_or_default(),
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, e.message()))?
+        .into_inner()
+        .map(|task| task.map(Task::from).map_err(StreamError::from));
+    Response::builder()
+        .header(CONTENT_TYPE, "application/x-ndjson")
+        .body(Body::from_stream(api_utils::json_lines(tasks)))
+        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/tasks/queue_depths",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Task counts of every extraction policy in the namespace", body = ListTaskQueueDepthsResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to list queue depths")
+    ),
+)]
+#[axum::debug_handler]
+async fn list_task_queue_depths(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<ListTaskQueueDepthsResponse>, IndexifyAPIError> {
+    let queue_depths = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .list_task_queue_depths(indexify_coordinator::ListTaskQueueDepthsRequest { namespace })
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, e.message()))?
+        .into_inner()
+        .queue_depths
+        .into_iter()
+        .map(|depth| depth.into())
+        .collect();
+    Ok(Json(ListTaskQueueDepthsResponse { queue_depths }))
+}
 
-        state
-            .data_manager
-            .create_content_metadata(new_content_metadata)
-            .await
-            .map_err(|e| {
-                IndexifyAPIError::new(
-                    StatusCode::BAD_REQUEST,
-                    &format!("failed to create content for file: {}", e),
-                )
-            })?;
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/executors/{executor_id}/tasks",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Unfinished tasks assigned to the executor", body = ListTasksResponse),
+        (status = NOT_FOUND, description = "Executor not found"),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to list tasks")
+    ),
+)]
+#[axum::debug_handler]
+async fn list_executor_tasks(
+    Path(executor_id): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<ListTasksResponse>, IndexifyAPIError> {
+    let tasks = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .list_executor_tasks(indexify_coordinator::ListExecutorTasksRequest { executor_id })
+        .await
+        .map_err(|e| match e.code() {
+            tonic::Code::NotFound => IndexifyAPIError::not_found(e.message()),
+            _ => IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, e.message()),
+        })?
+        .into_inner()
+        .tasks;
+    let tasks = tasks
+        .into_iter()
+        .map(|t| t.try_into())
+        .filter_map(|t| t.ok())
+        .collect();
+    Ok(Json(ListTasksResponse { tasks }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/content/{content_id}/rerun",
+    request_body = RerunExtractionPolicyRequest,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Extraction policy scheduled to run again on the content", body = ReplayStartedResponse),
+        (status = BAD_REQUEST, description = "Unable to run the extraction policy again")
+    ),
+)]
+#[axum::debug_handler]
+async fn rerun_extraction_policy(
+    Path((namespace, content_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    principal: Option<Extension<Principal>>,
+    Json(request): Json<RerunExtractionPolicyRequest>,
+) -> Result<Json<ReplayStartedResponse>, IndexifyAPIError> {
+    let response = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .rerun_extraction_policy(indexify_coordinator::RerunExtractionPolicyRequest {
+            namespace,
+            content_id,
+            extraction_graph: request.extraction_graph,
+            extraction_policy: request.extraction_policy,
+            initiator: principal
+                .map(|principal| principal.subject.clone())
+                .unwrap_or_default(),
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, e.message()))?
+        .into_inner();
+    Ok(Json(ReplayStartedResponse {
+        replay_id: response.replay_id,
+    }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/cancel_tasks",
+    request_body = CancelTasksRequest,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Started cancelling the matching tasks", body = CancelTasksResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to cancel tasks")
+    ),
+)]
+#[axum::debug_handler]
+async fn cancel_tasks(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Json(request): Json<CancelTasksRequest>,
+) -> Result<Json<CancelTasksResponse>, IndexifyAPIError> {
+    let job_id = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .cancel_tasks(indexify_coordinator::CancelTasksRequest {
+            namespace,
+            extraction_graph: request.extraction_graph.unwrap_or_default(),
+            labels_eq: request.labels_eq,
+            created_after: request.created_after.unwrap_or_default(),
+            created_before: request.created_before.unwrap_or_default(),
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, e.message()))?
+        .into_inner()
+        .job_id;
+    Ok(Json(CancelTasksResponse { job_id }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/cancel_tasks/{job_id}",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Progress of a cancel tasks job", body = CancelTasksJob),
+        (status = NOT_FOUND, description = "Cancel tasks job not found")
+    ),
+)]
+#[axum::debug_handler]
+async fn get_cancel_tasks_job(
+    Path((namespace, job_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<CancelTasksJob>, IndexifyAPIError> {
+    let job: CancelTasksJob = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .get_cancel_tasks_job(indexify_coordinator::GetCancelTasksJobRequest { job_id })
+        .await
+        .map_err(|e| match e.code() {
+            tonic::Code::NotFound => IndexifyAPIError::not_found(e.message()),
+            _ => IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, e.message()),
+        })?
+        .into_inner()
+        .into();
+    if job.namespace != namespace {
+        return Err(IndexifyAPIError::not_found("cancel tasks job not found"));
     }
+    Ok(Json(job))
+}
 
-    Ok(())
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/tasks/{task_id}",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Task with the states it moved through", body = GetTaskResponse),
+        (status = NOT_FOUND, description = "Task not found")
+    ),
+)]
+#[axum::debug_handler]
+async fn get_task(
+    Path((namespace, task_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<GetTaskResponse>, IndexifyAPIError> {
+    let response = state
+        .coordinator_client
+        .get_task_with_transitions(&task_id)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    let task = response
+        .task
+        .filter(|task| task.namespace == namespace)
+        .ok_or_else(|| IndexifyAPIError::not_found("task not found"))?;
+    Ok(Json(GetTaskResponse {
+        task: task.into(),
+        transitions: response.transitions.into_iter().map(Into::into).collect(),
+    }))
 }
 
-async fn ingest_extracted_content(
-    ws: WebSocketUpgrade<IngestExtractedContentResponse, IngestExtractedContent>,
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/tasks/{task_id}/scheduling",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Most recent attempts of the allocator to place the task, with the executors it considered", body = TaskSchedulingResponse),
+        (status = NOT_FOUND, description = "Task not found")
+    ),
+)]
+#[axum::debug_handler]
+async fn get_task_scheduling(
+    Path((namespace, task_id)): Path<(String, String)>,
     State(state): State<NamespaceEndpointState>,
-) -> impl IntoResponse {
-    ws.on_upgrade(|socket| IngestExtractedContentState::new(state).run(socket))
+) -> Result<Json<TaskSchedulingResponse>, IndexifyAPIError> {
+    let response = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .get_task_scheduling(indexify_coordinator::GetTaskSchedulingRequest { namespace, task_id })
+        .await
+        .map_err(|e| IndexifyAPIError::not_found(e.message()))?
+        .into_inner();
+    Ok(Json(TaskSchedulingResponse {
+        attempts: response.attempts.into_iter().map(Into::into).collect(),
+    }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/tasks/{task_id}/requeue",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Quarantined task scheduled to run again", body = ReplayStartedResponse),
+        (status = BAD_REQUEST, description = "Task not found or not quarantined")
+    ),
+)]
+#[axum::debug_handler]
+async fn requeue_task(
+    Path((namespace, task_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    principal: Option<Extension<Principal>>,
+) -> Result<Json<ReplayStartedResponse>, IndexifyAPIError> {
+    let response = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .requeue_task(indexify_coordinator::RequeueTaskRequest {
+            namespace,
+            task_id,
+            initiator: principal
+                .map(|principal| principal.subject.clone())
+                .unwrap_or_default(),
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, e.message()))?
+        .into_inner();
+    Ok(Json(ReplayStartedResponse {
+        replay_id: response.replay_id,
+    }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/stuck_content",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Content waiting for tasks that are missing or made no progress", body = ListStuckContentResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to list stuck content")
+    ),
+)]
+#[axum::debug_handler]
+async fn list_stuck_content(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Query(params): Query<ListStuckContentParams>,
+) -> Result<Json<ListStuckContentResponse>, IndexifyAPIError> {
+    let response = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .list_stuck_content(indexify_coordinator::ListStuckContentRequest {
+            namespace,
+            stalled_for_secs: params.stalled_for_secs,
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, e.message()))?
+        .into_inner();
+    Ok(Json(ListStuckContentResponse {
+        content: response.content.into_iter().map(Into::into).collect(),
+    }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/tasks/starved",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Tasks that stayed unallocated past the starvation threshold, with the reason they aren't allocated", body = ListStarvedTasksResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to list starved tasks")
+    ),
+)]
+#[axum::debug_handler]
+async fn list_starved_tasks(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Query(params): Query<ListStarvedTasksParams>,
+) -> Result<Json<ListStarvedTasksResponse>, IndexifyAPIError> {
+    let response = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .list_starved_tasks(indexify_coordinator::ListStarvedTasksRequest {
+            namespace,
+            unallocated_for_secs: params.unallocated_for_secs,
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, e.message()))?
+        .into_inner();
+    let tasks: Vec<StarvedTask> = response.tasks.into_iter().map(Into::into).collect();
+    let mut reasons: HashMap<String, u64> = HashMap::new();
+    for task in &tasks {
+        *reasons.entry(task.reason.to_string()).or_default() += 1;
+    }
+    Ok(Json(ListStarvedTasksResponse { tasks, reasons }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/content/{content_id}/repair",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Missing tasks of the content re-created", body = RepairContentResponse),
+        (status = BAD_REQUEST, description = "Content not found or not a root content")
+    ),
+)]
+#[axum::debug_handler]
+async fn repair_content(
+    Path((namespace, content_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<RepairContentResponse>, IndexifyAPIError> {
+    let response = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .repair_content(indexify_coordinator::RepairContentRequest {
+            namespace,
+            content_id,
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, e.message()))?
+        .into_inner();
+    Ok(Json(RepairContentResponse {
+        created_tasks: response.created_tasks,
+    }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/namespaces/{namespace}/content/{content_id}/rerun_failed",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Failed tasks of the content run again", body = RerunFailedTasksResponse),
+        (status = BAD_REQUEST, description = "Content not found or not a root content")
+    ),
+)]
+#[axum::debug_handler]
+async fn rerun_failed_tasks(
+    Path((namespace, content_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    principal: Option<Extension<Principal>>,
+) -> Result<Json<RerunFailedTasksResponse>, IndexifyAPIError> {
+    let response = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .rerun_failed_tasks(indexify_coordinator::RerunFailedTasksRequest {
+            namespace,
+            content_id,
+            initiator: principal
+                .map(|principal| principal.subject.clone())
+                .unwrap_or_default(),
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, e.message()))?
+        .into_inner();
+    Ok(Json(RerunFailedTasksResponse {
+        task_ids: response.task_ids,
+        replay_id: Some(response.replay_id).filter(|id| !id.is_empty()),
+    }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/replays",
+    params(ListReplaysParams),
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Replays of the namespace, latest first", body = ListReplaysResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to list replays")
+    ),
+)]
+#[axum::debug_handler]
+async fn list_replays(
+    Path(namespace): Path<String>,
+    State(state): State<NamespaceEndpointState>,
+    Query(params): Query<ListReplaysParams>,
+) -> Result<Json<ListReplaysResponse>, IndexifyAPIError> {
+    let response = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .list_replays(indexify_coordinator::ListReplaysRequest {
+            namespace,
+            extraction_graph: params.extraction_graph.unwrap_or_default(),
+            content_id: params.content_id.unwrap_or_default(),
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, e.message()))?
+        .into_inner();
+    Ok(Json(ListReplaysResponse {
+        replays: response.replays.into_iter().map(Into::into).collect(),
+    }))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/namespaces/{namespace}/replays/{replay_id}",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "A replay and the content its tasks extracted", body = ReplayResponse),
+        (status = NOT_FOUND, description = "Replay not found")
+    ),
+)]
+#[axum::debug_handler]
+async fn get_replay(
+    Path((namespace, replay_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<ReplayResponse>, IndexifyAPIError> {
+    let response = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .get_replay(indexify_coordinator::GetReplayRequest {
+            namespace,
+            replay_id,
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::not_found(e.message()))?
+        .into_inner();
+    let replay = response
+        .replay
+        .ok_or_else(|| IndexifyAPIError::not_found("replay not found"))?;
+    Ok(Json(ReplayResponse {
+        replay: replay.into(),
+        output_content_ids: response.output_content_ids,
+    }))
 }
 
 #[tracing::instrument]
 #[utoipa::path(
     get,
-    path = "/executors",
+    path = "/namespaces/{namespace}/content/{content_id}/breakdown",
     tag = "indexify",
     responses(
-        (status = 200, description = "List of currently running executors", body = ListExecutorsResponse),
-        (status = INTERNAL_SERVER_ERROR, description = "Unable to load executors")
+        (status = 200, description = "Time and estimated cost of the extraction policies that ran on the content tree", body = InvocationBreakdownResponse),
+        (status = NOT_FOUND, description = "Content not found or not a root content")
     ),
 )]
 #[axum::debug_handler]
-async fn list_executors(
-    State(_state): State<NamespaceEndpointState>,
-) -> Result<Json<ListExecutorsResponse>, IndexifyAPIError> {
-    Ok(Json(ListExecutorsResponse { executors: vec![] }))
+async fn get_invocation_breakdown(
+    Path((namespace, content_id)): Path<(String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<InvocationBreakdownResponse>, IndexifyAPIError> {
+    let breakdown = state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .get_invocation_breakdown(indexify_coordinator::GetInvocationBreakdownRequest {
+            namespace,
+            content_id,
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::not_found(e.message()))?
+        .into_inner()
+        .breakdown
+        .ok_or_else(|| IndexifyAPIError::not_found("content not found"))?;
+    Ok(Json(InvocationBreakdownResponse {
+        breakdown: breakdown.into(),
+    }))
 }
 
 #[tracing::instrument]
 #[utoipa::path(
     get,
-    path = "/extractors",
+    path = "/namespaces/{namespace}/content/{content_id}/context",
     tag = "indexify",
     responses(
-        (status = 200, description = "List of extractors available", body = ListExtractorsResponse),
-        (status = INTERNAL_SERVER_ERROR, description = "Unable to search index")
+        (status = 200, description = "Context of the root content of the content", body = ContentContextResponse),
+        (status = BAD_REQUEST, description = "Content not found")
     ),
 )]
 #[axum::debug_handler]
-async fn list_extractors(
+async fn get_content_context(
+    Path((namespace, content_id)): Path<(String, String)>,
     State(state): State<NamespaceEndpointState>,
-) -> Result<Json<ListExtractorsResponse>, IndexifyAPIError> {
-    let extractors = state
-        .data_manager
-        .list_extractors()
+) -> Result<Json<ContentContextResponse>, IndexifyAPIError> {
+    let response = state
+        .coordinator_client
+        .get()
         .await
         .map_err(IndexifyAPIError::internal_error)?
+        .get_content_context(indexify_coordinator::GetContentContextRequest {
+            namespace,
+            content_id,
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, e.message()))?
+        .into_inner();
+    let context = response
+        .context
         .into_iter()
-        .collect();
-    Ok(Json(ListExtractorsResponse { extractors }))
+        .map(|(key, value)| Ok((key, serde_json::from_str(&value)?)))
+        .collect::<Result<_, serde_json::Error>>()
+        .map_err(|e| IndexifyAPIError::internal_error(e.into()))?;
+    Ok(Json(ContentContextResponse { context }))
 }
 
+#[tracing::instrument]
 #[utoipa::path(
-    post,
-    path = "/extractors/extract",
+    get,
+    path = "/namespaces/{namespace}/content/{content_id}/replica",
     tag = "indexify",
-    request_body = ExtractRequest,
     responses(
-        (status = 200, description = "Extract content from an extractor", body = ExtractResponse),
-        (status = INTERNAL_SERVER_ERROR, description = "Unable to search index")
+        (status = 200, description = "Copy of the content in the replication region", body = internal_api::ContentReplica),
+        (status = NOT_FOUND, description = "Content has no replica")
     ),
 )]
 #[axum::debug_handler]
-async fn list_state_changes(
+async fn get_content_replica(
+    Path((namespace, content_id)): Path<(String, String)>,
     State(state): State<NamespaceEndpointState>,
-    Query(_query): Query<ListStateChanges>,
-) -> Result<Json<ListStateChangesResponse>, IndexifyAPIError> {
-    let state_changes = state
+) -> Result<Json<internal_api::ContentReplica>, IndexifyAPIError> {
+    let replica = state
         .coordinator_client
         .get()
         .await
         .map_err(IndexifyAPIError::internal_error)?
-        .list_state_changes(ListStateChangesRequest {})
+        .get_content_replica(indexify_coordinator::GetContentReplicaRequest {
+            namespace,
+            content_id,
+        })
         .await
-        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))?
+        .map_err(|e| IndexifyAPIError::not_found(e.message()))?
         .into_inner()
-        .changes;
+        .replica
+        .ok_or_else(|| IndexifyAPIError::not_found("content has no replica"))?;
+    Ok(Json(replica.into()))
+}
 
-    let state_changes: Vec<indexify_internal_api::StateChange> = state_changes
-        .into_iter()
-        .map(|c| c.try_into())
-        .filter_map(|c| c.ok())
-        .collect();
+async fn write_content_context(
+    state: NamespaceEndpointState,
+    namespace: String,
+    content_id: String,
+    key: String,
+    value: Option<serde_json::Value>,
+) -> Result<(), IndexifyAPIError> {
+    state
+        .coordinator_client
+        .get()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?
+        .set_content_context(indexify_coordinator::SetContentContextRequest {
+            namespace,
+            content_id,
+            key,
+            value: value.map(|value| value.to_string()),
+        })
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, e.message()))?;
+    Ok(())
+}
 
-    Ok(Json(ListStateChangesResponse { state_changes }))
+#[tracing::instrument]
+#[utoipa::path(
+    put,
+    path = "/namespaces/{namespace}/content/{content_id}/context/{key}",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Context key set to the JSON value in the body, replacing its previous value"),
+        (status = BAD_REQUEST, description = "Content not found or the context is over its size limits")
+    ),
+)]
+#[axum::debug_handler]
+async fn set_content_context(
+    Path((namespace, content_id, key)): Path<(String, String, String)>,
+    State(state): State<NamespaceEndpointState>,
+    Json(value): Json<serde_json::Value>,
+) -> Result<(), IndexifyAPIError> {
+    write_content_context(state, namespace, content_id, key, Some(value)).await
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    delete,
+    path = "/namespaces/{namespace}/content/{content_id}/context/{key}",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Context key removed"),
+        (status = BAD_REQUEST, description = "Content not found")
+    ),
+)]
+#[axum::debug_handler]
+async fn delete_content_context(
+    Path((namespace, content_id, key)): Path<(String, String, String)>,
+    State(state): State<NamespaceEndpointState>,
+) -> Result<(), IndexifyAPIError> {
+    write_content_context(state, namespace, content_id, key, None).await
 }
 
 #[tracing::instrument]
 #[utoipa::path(
     get,
-    path = "/namespaces/{namespace}/tasks",
-    path = "/tasks",
+    path = "/namespaces/{namespace}/tasks/{task_id}/logs",
     tag = "indexify",
     responses(
-        (status = 200, description = "Lists tasks", body = ListTasksResponse),
-        (status = INTERNAL_SERVER_ERROR, description = "Unable to list tasks")
+        (status = 200, description = "Output of a task attempt"),
+        (status = 206, description = "Requested byte range of the output of a task attempt"),
+        (status = NOT_FOUND, description = "Task not found, or no output was written for the attempt"),
+        (status = RANGE_NOT_SATISFIABLE, description = "Requested range is outside of the log")
     ),
 )]
-async fn list_tasks(
-    Path(namespace): Path<String>,
+#[axum::debug_handler]
+async fn get_task_logs(
+    Path((namespace, task_id)): Path<(String, String)>,
     State(state): State<NamespaceEndpointState>,
-    Query(query): Query<ListTasks>,
-) -> Result<Json<ListTasksResponse>, IndexifyAPIError> {
-    let tasks = state
+    Query(params): Query<TaskLogParams>,
+    headers: HeaderMap,
+) -> Result<Response<Body>, IndexifyAPIError> {
+    let task = state
         .coordinator_client
-        .get()
+        .get_task(&task_id)
         .await
         .map_err(IndexifyAPIError::internal_error)?
-        .list_tasks(ListTasksRequest {
-            namespace: namespace.clone(),
-            extraction_policy: query.extraction_policy.unwrap_or("".to_string()),
+        .filter(|task| task.namespace == namespace)
+        .ok_or_else(|| IndexifyAPIError::not_found("task not found"))?;
+    let range = headers
+        .get(RANGE)
+        .map(|value| {
+            value
+                .to_str()
+                .map_err(|e| anyhow!(e))
+                .and_then(api_utils::parse_byte_range)
+                .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))
         })
+        .transpose()?;
+    let log_url = state.data_manager.task_log_url(
+        &namespace,
+        &task.id,
+        params.attempt.unwrap_or(task.attempt),
+        params.stream,
+    );
+    let resp_builder = Response::builder().header(CONTENT_TYPE, "text/plain; charset=utf-8");
+
+    // Executors only upload the output of tasks that wrote any, so a log that
+    // was never written is not found rather than empty
+    let no_log = || IndexifyAPIError::not_found("no output was written for the task attempt");
+    let read_error = |e: anyhow::Error| {
+        if blob_storage::is_not_found(&e) {
+            no_log()
+        } else {
+            IndexifyAPIError::internal_error(e)
+        }
+    };
+    if range.is_none() && params.tail.is_none() {
+        state
+            .content_reader
+            .get(&log_url)
+            .get_range(&log_url, 0, 0)
+            .next()
+            .await
+            .ok_or_else(no_log)?
+            .map_err(read_error)?;
+        return resp_builder
+            .body(Body::from_stream(async_stream::stream! {
+                let reader = state.content_reader.get(&log_url);
+                let mut log_stream = reader.get(&log_url);
+                while let Some(buf) = log_stream.next().await {
+                    yield buf;
+                }
+            }))
+            .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()));
+    }
+
+    // Ranges and tails are resolved against the end of the log, so the log is
+    // read in full before slicing.
+    let mut log = state
+        .content_reader
+        .bytes(&log_url)
         .await
-        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, e.message()))?
-        .into_inner()
-        .tasks;
-    let tasks = tasks
-        .into_iter()
-        .map(|t| t.try_into())
-        .filter_map(|t| t.ok())
-        .collect();
-    Ok(Json(ListTasksResponse { tasks }))
+        .map_err(read_error)?;
+    if log.is_empty() {
+        return Err(no_log());
+    }
+    if let Some(tail) = params.tail {
+        let tail_len = api_utils::tail_lines(&log, tail).len();
+        log = log.slice(log.len() - tail_len..);
+    }
+    let Some(range) = range else {
+        return resp_builder
+            .body(Body::from(log))
+            .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()));
+    };
+    let (start, end) = range.resolve(log.len() as u64).ok_or_else(|| {
+        IndexifyAPIError::new(
+            StatusCode::RANGE_NOT_SATISFIABLE,
+            &format!("range not satisfiable for log of {} bytes", log.len()),
+        )
+    })?;
+    resp_builder
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header(
+            CONTENT_RANGE,
+            format!("bytes {}-{}/{}", start, end, log.len()),
+        )
+        .body(Body::from(log.slice(start as usize..=end as usize)))
+        .map_err(|e| IndexifyAPIError::new(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))
 }
 
 #[utoipa::path(
@@ -1279,6 +2966,147 @@ async fn extract_content(
     Ok(Json(response.into()))
 }
 
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/scheduler/settings",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "The scheduler settings in use", body = internal_api::SchedulerSettings),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to get the scheduler settings")
+    ),
+)]
+#[axum::debug_handler]
+async fn get_scheduler_settings(
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<internal_api::SchedulerSettings>, IndexifyAPIError> {
+    let settings = state
+        .data_manager
+        .get_scheduler_settings()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(settings))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    put,
+    path = "/scheduler/settings",
+    request_body = internal_api::SchedulerSettings,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "The scheduler settings were replaced and apply to the next allocation", body = internal_api::SchedulerSettings),
+        (status = BAD_REQUEST, description = "Invalid scheduler settings")
+    ),
+)]
+#[axum::debug_handler]
+async fn set_scheduler_settings(
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<internal_api::SchedulerSettings>,
+) -> Result<Json<internal_api::SchedulerSettings>, IndexifyAPIError> {
+    let settings = state
+        .data_manager
+        .set_scheduler_settings(payload)
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    Ok(Json(settings))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    post,
+    path = "/scheduler/simulate",
+    request_body = SimulateSchedulingRequest,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Predicted queues of the extractors, the cluster is left as it is", body = SimulateSchedulingResponse),
+        (status = BAD_REQUEST, description = "Unable to simulate the change")
+    ),
+)]
+#[axum::debug_handler]
+async fn simulate_scheduling(
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<SimulateSchedulingRequest>,
+) -> Result<Json<SimulateSchedulingResponse>, IndexifyAPIError> {
+    let response = state
+        .data_manager
+        .simulate_scheduling(payload)
+        .await
+        .map_err(|e| IndexifyAPIError::new(StatusCode::BAD_REQUEST, &e.to_string()))?;
+    Ok(Json(response))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/maintenance",
+    tag = "indexify",
+    responses(
+        (status = 200, description = "Whether the cluster is in maintenance", body = internal_api::MaintenanceMode),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to get the maintenance mode")
+    ),
+)]
+#[axum::debug_handler]
+async fn get_maintenance_mode(
+    State(state): State<NamespaceEndpointState>,
+) -> Result<Json<internal_api::MaintenanceMode>, IndexifyAPIError> {
+    let maintenance = state
+        .data_manager
+        .get_maintenance_mode()
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(maintenance))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    put,
+    path = "/maintenance",
+    request_body = SetMaintenanceModeRequest,
+    tag = "indexify",
+    responses(
+        (status = 200, description = "The cluster is in or out of maintenance", body = internal_api::MaintenanceMode),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to change the maintenance mode")
+    ),
+)]
+#[axum::debug_handler]
+async fn set_maintenance_mode(
+    State(state): State<NamespaceEndpointState>,
+    Json(payload): Json<SetMaintenanceModeRequest>,
+) -> Result<Json<internal_api::MaintenanceMode>, IndexifyAPIError> {
+    let maintenance = state
+        .data_manager
+        .set_maintenance_mode(payload.enabled, &payload.reason)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(maintenance))
+}
+
+#[tracing::instrument]
+#[utoipa::path(
+    get,
+    path = "/metering",
+    tag = "indexify",
+    params(ListMeteringRecordsParams),
+    responses(
+        (status = 200, description = "Hourly usage of the namespaces", body = ListMeteringRecordsResponse),
+        (status = INTERNAL_SERVER_ERROR, description = "Unable to list the metering records")
+    ),
+)]
+#[axum::debug_handler]
+async fn list_metering_records(
+    State(state): State<NamespaceEndpointState>,
+    Query(params): Query<ListMeteringRecordsParams>,
+) -> Result<Json<ListMeteringRecordsResponse>, IndexifyAPIError> {
+    let end = params.end.unwrap_or_else(crate::utils::timestamp_secs);
+    let records = state
+        .data_manager
+        .list_metering_records(params.namespace, params.start, end)
+        .await
+        .map_err(IndexifyAPIError::internal_error)?;
+    Ok(Json(ListMeteringRecordsResponse { records }))
+}
+
 #[axum::debug_handler]
 async fn list_task_assignments(
     State(namespace_endpoint): State<NamespaceEndpointState>,
@@ -1515,7 +3343,27 @@ async fn ui_handler(Path(url): Path<String>) -> impl IntoResponse {
 }
 
 #[tracing::instrument]
-pub async fn shutdown_signal(handle: Handle) {
+pub async fn serve(
+    addr: SocketAddr,
+    app: Router,
+    rustls_config: Option<RustlsConfig>,
+    handle: Handle,
+) -> std::io::Result<()> {
+    //  The connection info carries the address of the client, which the admin
+    //  routes are restricted by
+    let service = app.into_make_service_with_connect_info::<SocketAddr>();
+    match rustls_config {
+        Some(rustls_config) => {
+            axum_server::tls_rustls::bind_rustls(addr, rustls_config)
+                .handle(handle)
+                .serve(service)
+                .await
+        }
+        None => axum_server::bind(addr).handle(handle).serve(service).await,
+    }
+}
+
+async fn shutdown_signal(handle: Handle) {
     let ctrl_c = async {
         signal::ctrl_c()
             .await
@@ -1542,3 +3390,132 @@ pub async fn shutdown_signal(handle: Handle) {
     handle.shutdown();
     info!("signal received, shutting down server gracefully");
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use axum::{body::to_bytes, http::Request};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{
+        blob_storage::BlobStorageConfig,
+        coordinator_service::CoordinatorServer,
+        server_config::IndexStoreKind,
+        test_util::db_utils::{test_mock_content_metadata, DEFAULT_TEST_NAMESPACE},
+    };
+
+    async fn read_lines(app: &Router, uri: &str) -> (StatusCode, Vec<serde_json::Value>) {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let lines = body
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+        (status, lines)
+    }
+
+    #[tokio::test]
+    async fn test_stream_content() -> Result<()> {
+        let state_dir = tempfile::tempdir()?;
+        let mut config = ServerConfig::default();
+        config.coordinator_port += 200;
+        config.coordinator_addr = format!("localhost:{}", config.coordinator_port);
+        config.listen_port += 200;
+        config.index_config.index_store = IndexStoreKind::Qdrant;
+        config.index_config.qdrant_config = Some(Default::default());
+        config.blob_storage = BlobStorageConfig {
+            s3: None,
+            disk: Some(crate::blob_storage::DiskStorageConfig {
+                path: state_dir.path().join("blobs").to_string_lossy().to_string(),
+            }),
+        };
+        config.state_store.path =
+            Some(state_dir.path().join("state").to_string_lossy().to_string());
+        let config = Arc::new(config);
+
+        let registry = Arc::new(crate::metrics::init_provider());
+        let coordinator_server = CoordinatorServer::new(config.clone(), registry.clone()).await?;
+        let coordinator = coordinator_server.get_coordinator();
+        let handle = tokio::spawn(async move {
+            coordinator_server.run().await.unwrap();
+        });
+        let coordinator_client = Arc::new(CoordinatorClient::new(config.clone()));
+        while coordinator_client.get().await.is_err() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        //  More content than a page of the stream
+        let content = (0..300)
+            .map(|i| internal_api::ContentMetadata {
+                extraction_graph_names: Vec::new(),
+                ..test_mock_content_metadata(&format!("content-{:03}", i), "", "")
+            })
+            .collect();
+        coordinator.create_content_metadata(content).await?;
+
+        let vector_db = vectordbs::create_vectordb(config.index_config.clone()).await?;
+        let vector_index_manager = Arc::new(
+            VectorIndexManager::new(coordinator_client.clone(), vector_db)
+                .map_err(|e| anyhow!("unable to create vector index {}", e))?,
+        );
+        let data_manager = Arc::new(DataManager::new(
+            vector_index_manager,
+            metadata_storage::from_config(&config.metadata_storage)?,
+            metadata_storage::from_config_reader(&config.metadata_storage)?,
+            Arc::new(BlobStorage::new_with_config(config.blob_storage.clone())),
+            coordinator_client.clone(),
+        ));
+        let state = NamespaceEndpointState {
+            data_manager,
+            coordinator_client,
+            content_reader: Arc::new(ContentReader::new(config.clone())),
+            registry,
+            metrics: Arc::new(crate::metrics::server::Metrics::new()),
+        };
+        let app = Router::new()
+            .route(
+                "/namespaces/:namespace/content_stream",
+                get(stream_content).with_state(state.clone()),
+            )
+            .route(
+                "/namespaces/:namespace/content/:content_id",
+                get(get_content_metadata).with_state(state.clone()),
+            );
+
+        let uri = format!("/namespaces/{}/content_stream", DEFAULT_TEST_NAMESPACE);
+        let (status, lines) = read_lines(&app, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        let ids: HashSet<_> = lines
+            .iter()
+            .map(|line| line["id"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(lines.len(), 300);
+        assert_eq!(ids.len(), 300);
+
+        //  A stream resumed from a cursor starts after its row
+        let key = coordinator
+            .shared_state
+            .get_content_metadata_batch(vec!["content-199".to_string()])
+            .await?
+            .remove(0)
+            .id_key();
+        let (status, lines) = read_lines(&app, &format!("{}?cursor={}", uri, key)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(lines.len(), 100);
+        assert_eq!(lines[0]["id"], "content-200");
+
+        //  Content with the id "stream" isn't shadowed by the stream
+        let uri = format!("/namespaces/{}/content/stream", DEFAULT_TEST_NAMESPACE);
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        handle.abort();
+        Ok(())
+    }
+}
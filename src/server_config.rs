@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     fmt,
     fs,
     net::{AddrParseError, IpAddr, Ipv4Addr, SocketAddr},
@@ -294,6 +295,11 @@ pub struct TlsConfig {
     pub cert_file: String,
     pub key_file: String,
     pub ca_file: Option<String>,
+    /// How often the certificate, key and CA files are checked for changes,
+    /// so rotated certificates are picked up without a restart. Files are
+    /// only read at startup when this is not set.
+    #[serde(default)]
+    pub reload_interval_secs: Option<u64>,
 }
 
 /// If a relative path is provided, it is assumed to be relative to the project
@@ -372,16 +378,773 @@ pub struct ServerCacheConfig {
 pub struct StateStoreConfig {
     /// path is the path to the sled database.
     pub path: Option<String>,
+
+    /// invariant_validation decides what the state machine does with requests
+    /// that would break its invariants. It has to be the same on every
+    /// coordinator of a cluster, otherwise their states diverge.
+    #[serde(default)]
+    pub invariant_validation: InvariantValidation,
+
+    /// backup makes every coordinator back its state store up periodically
+    /// while it keeps serving.
+    #[serde(default)]
+    pub backup: Option<BackupConfig>,
+
+    /// archive makes the leader coordinator ship its state to the blob store,
+    /// so it can be restored with `indexify restore`.
+    #[serde(default)]
+    pub archive: Option<StateArchiveConfig>,
 }
 
 impl Default for StateStoreConfig {
     fn default() -> Self {
         Self {
             path: Some("/tmp/indexify/internal_state".to_string()),
+            invariant_validation: InvariantValidation::default(),
+            backup: None,
+            archive: None,
+        }
+    }
+}
+
+/// BackupConfig is the configuration of the online backups of the state
+/// store.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupConfig {
+    /// path is the directory backups are written to. Files that didn't change
+    /// since the previous backup are shared with it.
+    pub path: String,
+
+    /// interval_secs is how often a backup is taken.
+    #[serde(default = "default_backup_interval_secs")]
+    pub interval_secs: u64,
+
+    /// rate_limit_bytes_per_sec caps how fast backups read the state store,
+    /// so they leave disk bandwidth to the writes of the state machine.
+    /// Backups aren't limited when it is 0.
+    #[serde(default = "default_backup_rate_limit_bytes_per_sec")]
+    pub rate_limit_bytes_per_sec: u64,
+
+    /// keep is how many backups are kept, older ones are purged.
+    #[serde(default = "default_backups_kept")]
+    pub keep: usize,
+}
+
+fn default_backup_interval_secs() -> u64 {
+    24 * 3600
+}
+
+fn default_backup_rate_limit_bytes_per_sec() -> u64 {
+    32 * 1024 * 1024
+}
+
+fn default_backups_kept() -> usize {
+    7
+}
+
+/// StateArchiveConfig is the configuration of the archives of the state
+/// store in the blob store. An archive holds a raft snapshot and the log
+/// entries committed after it, which are shipped as they are committed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateArchiveConfig {
+    /// key_prefix is prepended to the keys of the archives, every archive is
+    /// stored under <key_prefix>/<time it was started at>.
+    #[serde(default = "default_state_archive_key_prefix")]
+    pub key_prefix: String,
+
+    /// interval_secs is how often the committed log entries are shipped.
+    #[serde(default = "default_state_archive_interval_secs")]
+    pub interval_secs: u64,
+
+    /// checkpoint_interval_secs is how often a new archive is started from
+    /// the latest raft snapshot.
+    #[serde(default = "default_state_archive_checkpoint_interval_secs")]
+    pub checkpoint_interval_secs: u64,
+}
+
+fn default_state_archive_key_prefix() -> String {
+    "snapshots".to_string()
+}
+
+fn default_state_archive_interval_secs() -> u64 {
+    60
+}
+
+fn default_state_archive_checkpoint_interval_secs() -> u64 {
+    24 * 3600
+}
+
+/// InvariantValidation is how the state machine handles a request that
+/// violates its invariants, like assigning a task that is already assigned or
+/// removing an executor that isn't registered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InvariantValidation {
+    /// Log the violations and apply the request
+    #[default]
+    LogOnly,
+
+    /// Log the violations and drop the change the request makes, with the
+    /// state changes it carries. The state changes it processed are still
+    /// marked processed.
+    Strict,
+}
+
+/// StuckContentConfig is the configuration for detecting content whose
+/// extraction stopped making progress.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StuckContentConfig {
+    /// stalled_after_secs is how long content with outstanding tasks can go
+    /// without any of its tasks being created or finished before it is
+    /// reported as stuck. Content with outstanding tasks but no pending work
+    /// is reported right away.
+    #[serde(default = "default_stalled_after_secs")]
+    pub stalled_after_secs: u64,
+
+    /// auto_repair makes the coordinator re-create the missing tasks of stuck
+    /// content periodically instead of only reporting it.
+    #[serde(default)]
+    pub auto_repair: bool,
+}
+
+fn default_stalled_after_secs() -> u64 {
+    3600
+}
+
+impl Default for StuckContentConfig {
+    fn default() -> Self {
+        Self {
+            stalled_after_secs: default_stalled_after_secs(),
+            auto_repair: false,
+        }
+    }
+}
+
+/// ContentArchiveConfig makes the coordinator export the context, tasks and
+/// output manifest of a content tree to the blob store before it is deleted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentArchiveConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// key_prefix is prepended to the keys of the archives in the blob store.
+    #[serde(default = "default_content_archive_key_prefix")]
+    pub key_prefix: String,
+}
+
+fn default_content_archive_key_prefix() -> String {
+    "archive".to_string()
+}
+
+impl Default for ContentArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            key_prefix: default_content_archive_key_prefix(),
+        }
+    }
+}
+
+/// ReplicationConfig makes the leader coordinator copy the content extracted
+/// by the last extraction policies of the graphs, and its metadata, to the
+/// blob store of another region. Copies are read back and checked against
+/// the hash of the content before they are recorded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplicationConfig {
+    /// region is the name of the region the content is copied to.
+    pub region: String,
+
+    pub blob_storage: BlobStorageConfig,
+
+    /// extraction_graphs limits replication to the outputs of these graphs,
+    /// every graph is replicated when empty.
+    #[serde(default)]
+    pub extraction_graphs: Vec<String>,
+
+    /// key_prefix is prepended to the keys of the copies in the blob store.
+    #[serde(default = "default_replication_key_prefix")]
+    pub key_prefix: String,
+
+    /// interval_secs is how often the content queued for replication is
+    /// copied.
+    #[serde(default = "default_replication_interval_secs")]
+    pub interval_secs: u64,
+
+    /// batch_size is the most content copied in one run.
+    #[serde(default = "default_replication_batch_size")]
+    pub batch_size: usize,
+}
+
+fn default_replication_key_prefix() -> String {
+    "replica".to_string()
+}
+
+fn default_replication_interval_secs() -> u64 {
+    30
+}
+
+fn default_replication_batch_size() -> usize {
+    100
+}
+
+/// DiagnosticsConfig is how long the diagnostics of finished tasks are kept.
+/// Diagnostics are meant for debugging and can be verbose, so they are
+/// dropped well before the outputs of the tasks, which are kept until their
+/// content is deleted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsConfig {
+    /// retention_secs is how long after a task finished its diagnostics are
+    /// kept.
+    #[serde(default = "default_diagnostics_retention_secs")]
+    pub retention_secs: u64,
+
+    /// namespace_retention_secs overrides retention_secs for the tasks of a
+    /// namespace.
+    #[serde(default)]
+    pub namespace_retention_secs: HashMap<String, u64>,
+
+    /// prune_interval_secs is how often the leader drops the diagnostics past
+    /// their retention.
+    #[serde(default = "default_diagnostics_prune_interval_secs")]
+    pub prune_interval_secs: u64,
+}
+
+fn default_diagnostics_retention_secs() -> u64 {
+    7 * 24 * 3600
+}
+
+fn default_diagnostics_prune_interval_secs() -> u64 {
+    3600
+}
+
+impl Default for DiagnosticsConfig {
+    fn default() -> Self {
+        Self {
+            retention_secs: default_diagnostics_retention_secs(),
+            namespace_retention_secs: HashMap::new(),
+            prune_interval_secs: default_diagnostics_prune_interval_secs(),
+        }
+    }
+}
+
+impl DiagnosticsConfig {
+    pub fn retention_secs(&self, namespace: &str) -> u64 {
+        self.namespace_retention_secs
+            .get(namespace)
+            .copied()
+            .unwrap_or(self.retention_secs)
+    }
+}
+
+/// MeteringConfig is the configuration for exporting the hourly usage of
+/// every namespace to a billing system.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeteringConfig {
+    /// push_url is an endpoint the leader coordinator POSTs the metering
+    /// records of finished periods to, as a JSON array. When it is unset the
+    /// records are only available from the metering API.
+    #[serde(default)]
+    pub push_url: Option<String>,
+
+    /// push_interval_secs is how often the leader checks for finished
+    /// periods to push.
+    #[serde(default = "default_metering_push_interval_secs")]
+    pub push_interval_secs: u64,
+
+    /// push_secret signs the pushes to push_url, so the endpoint can check
+    /// they come from the coordinator.
+    #[serde(default)]
+    pub push_secret: Option<String>,
+}
+
+fn default_metering_push_interval_secs() -> u64 {
+    300
+}
+
+impl Default for MeteringConfig {
+    fn default() -> Self {
+        Self {
+            push_url: None,
+            push_interval_secs: default_metering_push_interval_secs(),
+            push_secret: None,
+        }
+    }
+}
+
+/// LoggingConfig is the configuration for where logs are written next to
+/// stderr.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// file writes the logs to a file that is rotated by the server, for
+    /// deployments without a log collector.
+    #[serde(default)]
+    pub file: Option<LogFileConfig>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogRotation {
+    Never,
+    Hourly,
+    Daily,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogFileConfig {
+    /// path of the log file. Rotated files are renamed to
+    /// `<path>.<unix seconds>`.
+    pub path: String,
+
+    /// max_size_bytes rotates the file before it grows past this size.
+    #[serde(default = "default_log_file_max_size_bytes")]
+    pub max_size_bytes: Option<u64>,
+
+    /// rotation rotates the file at the start of every hour or day in UTC.
+    #[serde(default = "default_log_rotation")]
+    pub rotation: LogRotation,
+
+    /// max_files is how many rotated files are kept, older ones are
+    /// removed.
+    #[serde(default = "default_log_max_files")]
+    pub max_files: usize,
+}
+
+fn default_log_file_max_size_bytes() -> Option<u64> {
+    Some(100 * 1024 * 1024)
+}
+
+fn default_log_rotation() -> LogRotation {
+    LogRotation::Daily
+}
+
+fn default_log_max_files() -> usize {
+    7
+}
+
+/// SchedulerConfig trades the latency of the scheduler for its throughput.
+/// Small passes and a short tick keep interactive deployments responsive,
+/// large passes get through the backlog of batch heavy deployments faster.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchedulerConfig {
+    /// state_change_batch_size is how many state changes a pass of the
+    /// scheduler processes at most. The rest are processed on the next tick.
+    /// Unlimited when unset.
+    #[serde(default)]
+    pub state_change_batch_size: Option<usize>,
+
+    /// max_tasks_per_pass is how many unassigned tasks a pass over them
+    /// places at most, when executors join or held back tasks are allocated
+    /// again. The rest are placed on the next tick. Unlimited when unset.
+    #[serde(default)]
+    pub max_tasks_per_pass: Option<usize>,
+
+    /// tick_interval_ms is how often the scheduler looks for work left over
+    /// when no state changes arrive, like queued content and the remainder of
+    /// a pass.
+    #[serde(default = "default_scheduler_tick_interval_ms")]
+    pub tick_interval_ms: u64,
+
+    /// starvation_threshold_secs is how long a task can stay unallocated
+    /// before it is reported as starved, with the reason it isn't allocated.
+    #[serde(default = "default_starvation_threshold_secs")]
+    pub starvation_threshold_secs: u64,
+
+    /// slo_alert_burn_rate is the burn rate of the error budget of an
+    /// extraction policy objective over which an alert is logged. At 1 the
+    /// budget runs out over the window compliance is computed over.
+    #[serde(default = "default_slo_alert_burn_rate")]
+    pub slo_alert_burn_rate: f64,
+}
+
+fn default_scheduler_tick_interval_ms() -> u64 {
+    1000
+}
+
+fn default_starvation_threshold_secs() -> u64 {
+    300
+}
+
+fn default_slo_alert_burn_rate() -> f64 {
+    1.0
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            state_change_batch_size: None,
+            max_tasks_per_pass: None,
+            tick_interval_ms: default_scheduler_tick_interval_ms(),
+            starvation_threshold_secs: default_starvation_threshold_secs(),
+            slo_alert_burn_rate: default_slo_alert_burn_rate(),
+        }
+    }
+}
+
+/// ChaosConfig injects faults into the coordinator at the given rates, to
+/// validate retries, reallocation and recovery. It is meant for test and
+/// staging clusters, never enable it in production. Rates are probabilities
+/// between 0 and 1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChaosConfig {
+    /// state_write_delay_rate is the share of state writes that are delayed
+    /// by state_write_delay_ms.
+    #[serde(default)]
+    pub state_write_delay_rate: f64,
+
+    #[serde(default = "default_chaos_state_write_delay_ms")]
+    pub state_write_delay_ms: u64,
+
+    /// heartbeat_drop_rate is the share of executor heartbeats that are
+    /// ignored. Executors are removed when enough of their heartbeats are
+    /// dropped in a row.
+    #[serde(default)]
+    pub heartbeat_drop_rate: f64,
+
+    /// task_kill_rate is the chance of every in-flight task to be failed
+    /// each time the tasks are checked, every task_kill_interval_secs.
+    #[serde(default)]
+    pub task_kill_rate: f64,
+
+    #[serde(default = "default_chaos_task_kill_interval_secs")]
+    pub task_kill_interval_secs: u64,
+}
+
+fn default_chaos_state_write_delay_ms() -> u64 {
+    1000
+}
+
+fn default_chaos_task_kill_interval_secs() -> u64 {
+    30
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        Self {
+            state_write_delay_rate: 0.0,
+            state_write_delay_ms: default_chaos_state_write_delay_ms(),
+            heartbeat_drop_rate: 0.0,
+            task_kill_rate: 0.0,
+            task_kill_interval_secs: default_chaos_task_kill_interval_secs(),
+        }
+    }
+}
+
+/// OidcConfig is the configuration for authenticating requests to the
+/// namespace APIs with bearer JWTs issued by an OIDC identity provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcConfig {
+    /// issuer is the issuer URL of the identity provider. Tokens have to carry
+    /// it in their `iss` claim, and its discovery document is used to find
+    /// the signing keys unless jwks_url is set.
+    pub issuer: String,
+
+    /// audience is the value tokens have to carry in their `aud` claim.
+    pub audience: String,
+
+    /// jwks_url overrides the signing keys URL from the discovery document.
+    #[serde(default)]
+    pub jwks_url: Option<String>,
+
+    /// namespaces_claim is the claim listing the namespaces a token can
+    /// access. Nested claims are addressed with dots, like
+    /// `indexify.namespaces`.
+    #[serde(default = "default_namespaces_claim")]
+    pub namespaces_claim: String,
+
+    /// roles_claim is the claim listing the roles of a token.
+    #[serde(default = "default_roles_claim")]
+    pub roles_claim: String,
+
+    /// writer_role lets a token change the namespaces it can access. Tokens
+    /// without it can only read them.
+    #[serde(default = "default_writer_role")]
+    pub writer_role: String,
+
+    /// admin_role lets a token read and change every namespace.
+    #[serde(default = "default_admin_role")]
+    pub admin_role: String,
+}
+
+fn default_namespaces_claim() -> String {
+    "namespaces".to_string()
+}
+
+fn default_roles_claim() -> String {
+    "roles".to_string()
+}
+
+fn default_writer_role() -> String {
+    "writer".to_string()
+}
+
+fn default_admin_role() -> String {
+    "admin".to_string()
+}
+
+/// AdminApiConfig controls where the admin routes of the server, like
+/// re-running or deleting content and managing executors and tasks, can be
+/// called from.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdminApiConfig {
+    /// allowed_cidrs are the networks and addresses admin routes accept
+    /// requests from. Requests from anywhere are accepted when it is empty.
+    #[serde(default)]
+    pub allowed_cidrs: Vec<String>,
+
+    /// listen_port serves the admin routes on a separate port. The API port
+    /// doesn't serve them when it is set.
+    #[serde(default)]
+    pub listen_port: Option<u64>,
+}
+
+/// AlertingConfig is a set of threshold rules the leader coordinator checks
+/// the cluster against, with the webhooks alerts are sent to when a rule
+/// starts or stops firing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertingConfig {
+    #[serde(default)]
+    pub rules: Vec<AlertRule>,
+
+    #[serde(default)]
+    pub webhooks: Vec<AlertWebhook>,
+
+    /// check_interval_secs is how often the rules are checked.
+    #[serde(default = "default_alert_check_interval_secs")]
+    pub check_interval_secs: u64,
+}
+
+fn default_alert_check_interval_secs() -> u64 {
+    30
+}
+
+impl Default for AlertingConfig {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            webhooks: Vec::new(),
+            check_interval_secs: default_alert_check_interval_secs(),
         }
     }
 }
 
+/// What an alert rule watches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, strum::Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AlertMetric {
+    /// Tasks waiting for an executor
+    UnallocatedTasks,
+    /// Registered executors
+    Executors,
+    /// Tasks quarantined since the coordinator started
+    QuarantinedTasks,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, strum::Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AlertSeverity {
+    Critical,
+    Error,
+    #[default]
+    Warning,
+    Info,
+}
+
+/// AlertRule fires when its metric is above or below a threshold for a
+/// while. With `growth_over_secs` the rule compares how much the metric grew
+/// over that window instead of its value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    pub metric: AlertMetric,
+    #[serde(default)]
+    pub above: Option<f64>,
+    #[serde(default)]
+    pub below: Option<f64>,
+    /// for_secs is how long the threshold has to be crossed before the rule
+    /// fires.
+    #[serde(default)]
+    pub for_secs: u64,
+    #[serde(default)]
+    pub growth_over_secs: Option<u64>,
+    #[serde(default)]
+    pub severity: AlertSeverity,
+}
+
+impl AlertRule {
+    pub fn validate(&self) -> Result<()> {
+        match (self.above, self.below) {
+            (Some(_), None) | (None, Some(_)) => Ok(()),
+            _ => Err(anyhow!(
+                "alert rule {} needs exactly one of `above` and `below`",
+                self.name
+            )),
+        }
+    }
+}
+
+/// The format of the alerts sent to a webhook
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertFormat {
+    /// The alert as JSON
+    #[default]
+    Json,
+    /// A PagerDuty Events API v2 event
+    PagerDuty,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertWebhook {
+    pub url: String,
+    #[serde(default)]
+    pub format: AlertFormat,
+    /// routing_key is the integration key of the PagerDuty service.
+    #[serde(default)]
+    pub routing_key: Option<String>,
+    /// secret signs the deliveries to the webhook, so it can check they come
+    /// from the coordinator.
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+/// ProfilingConfig serves endpoints that profile the running process under
+/// `/debug/pprof`, as admin routes of the server and on the HTTP port of the
+/// coordinator. They need a build with the `profiling` feature.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfilingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// max_cpu_profile_secs is the longest a CPU profile samples for.
+    #[serde(default = "default_max_cpu_profile_secs")]
+    pub max_cpu_profile_secs: u64,
+}
+
+fn default_max_cpu_profile_secs() -> u64 {
+    120
+}
+
+impl Default for ProfilingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_cpu_profile_secs: default_max_cpu_profile_secs(),
+        }
+    }
+}
+
+/// ContentHookConfig is a hook that redacts or transforms the labels of
+/// ingested content before they are stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ContentHookConfig {
+    /// DropLabels removes labels.
+    DropLabels { labels: Vec<String> },
+
+    /// RedactLabels replaces the value of labels with the replacement,
+    /// `[REDACTED]` by default.
+    RedactLabels {
+        labels: Vec<String>,
+        #[serde(default)]
+        replacement: Option<String>,
+    },
+
+    /// RedactPattern replaces the matches of a regular expression in the
+    /// values of all labels.
+    RedactPattern {
+        pattern: String,
+        #[serde(default)]
+        replacement: Option<String>,
+    },
+
+    /// HashLabels replaces the value of labels with its SHA-256 digest.
+    HashLabels { labels: Vec<String> },
+}
+
+/// ApiVerb is what a request to the namespace APIs does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiVerb {
+    /// Read reads namespaces, content, indexes and tasks.
+    Read,
+
+    /// Ingest uploads content into extraction graphs.
+    Ingest,
+
+    /// Write changes namespaces, like creating extraction graphs or deleting
+    /// content.
+    Write,
+}
+
+/// ApiKeyConfig is an API key and the requests it can make to the namespace
+/// APIs. Keys are sent as bearer tokens.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyConfig {
+    /// name identifies the key in logs and errors.
+    pub name: String,
+
+    /// key is the secret value of the key. It can reference a secret.
+    pub key: String,
+
+    /// namespaces are the namespaces the key can access, `*` stands for all
+    /// of them.
+    pub namespaces: Vec<String>,
+
+    /// graphs restricts the extraction graphs the key can ingest into and
+    /// manage. The key can use all graphs of its namespaces when it is not
+    /// set.
+    #[serde(default)]
+    pub graphs: Option<Vec<String>>,
+
+    /// verbs are the kinds of requests the key can make.
+    #[serde(default = "default_api_key_verbs")]
+    pub verbs: Vec<ApiVerb>,
+
+    /// admin keys can make any request, like admin tokens of the OIDC
+    /// issuer, including taking profiles of the process.
+    #[serde(default)]
+    pub admin: bool,
+}
+
+fn default_api_key_verbs() -> Vec<ApiVerb> {
+    vec![ApiVerb::Read]
+}
+
+/// VaultConfig is the configuration for reading secrets from HashiCorp Vault.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaultConfig {
+    /// addr is the address of the Vault server, like
+    /// `https://vault.example.com:8200`.
+    pub addr: String,
+
+    /// token authenticates with Vault. The `VAULT_TOKEN` environment variable
+    /// is used when it is not set.
+    #[serde(default)]
+    pub token: Option<String>,
+
+    /// namespace is the Vault Enterprise namespace the secrets live in.
+    #[serde(default)]
+    pub namespace: Option<String>,
+}
+
+/// SecretsConfig is the configuration for resolving references to secrets in
+/// the other values of the config, like `${vault:kv/data/indexify#password}`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SecretsConfig {
+    /// vault is needed to resolve `${vault:...}` references.
+    #[serde(default)]
+    pub vault: Option<VaultConfig>,
+
+    /// refresh_interval_secs is how often the referenced secrets are read
+    /// again to detect rotations. Rotated secrets of the api keys and alert
+    /// webhooks are applied while running, the others on restart. Secrets are
+    /// only read at startup when it is not set.
+    #[serde(default)]
+    pub refresh_interval_secs: Option<u64>,
+}
+
 /// ServerCacheBackend is an enum that represents the different cache backends
 /// supported by the server.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -432,6 +1195,50 @@ pub struct ServerConfig {
     pub cache: ServerCacheConfig,
     #[serde(default)]
     pub state_store: StateStoreConfig,
+    #[serde(default)]
+    pub stuck_content: StuckContentConfig,
+    #[serde(default)]
+    pub content_archive: ContentArchiveConfig,
+    #[serde(default)]
+    pub metering: MeteringConfig,
+    #[serde(default)]
+    pub diagnostics: DiagnosticsConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub chaos: Option<ChaosConfig>,
+    /// oidc enables bearer token authentication of the namespace APIs. The
+    /// APIs are open when neither it nor api_keys are set.
+    #[serde(default)]
+    pub oidc: Option<OidcConfig>,
+    /// secrets configures where references to secrets in the config are
+    /// read from.
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+    /// api_keys are the keys that can call the namespace APIs, next to the
+    /// tokens of the OIDC issuer.
+    #[serde(default)]
+    pub api_keys: Vec<ApiKeyConfig>,
+    /// content_hooks redact or transform the labels of ingested content
+    /// before the coordinator stores them, in order.
+    #[serde(default)]
+    pub content_hooks: Vec<ContentHookConfig>,
+    #[serde(default)]
+    pub admin_api: AdminApiConfig,
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+    #[serde(default)]
+    pub profiling: ProfilingConfig,
+    #[serde(default)]
+    pub alerting: AlertingConfig,
+    #[serde(default)]
+    pub replication: Option<ReplicationConfig>,
+    /// read_only disables the APIs that change state, and stops the scheduler
+    /// and the garbage collection tasks of the server. The coordinator serves
+    /// a copy of the state directory without joining the raft cluster, for
+    /// inspecting the state of a cluster.
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 impl Default for ServerConfig {
@@ -458,6 +1265,22 @@ impl Default for ServerConfig {
             node_id: 0,
             cache: ServerCacheConfig::default(),
             state_store: StateStoreConfig::default(),
+            stuck_content: StuckContentConfig::default(),
+            content_archive: ContentArchiveConfig::default(),
+            metering: MeteringConfig::default(),
+            diagnostics: DiagnosticsConfig::default(),
+            logging: LoggingConfig::default(),
+            chaos: None,
+            oidc: None,
+            secrets: SecretsConfig::default(),
+            api_keys: Vec::new(),
+            content_hooks: Vec::new(),
+            admin_api: AdminApiConfig::default(),
+            scheduler: SchedulerConfig::default(),
+            profiling: ProfilingConfig::default(),
+            alerting: AlertingConfig::default(),
+            replication: None,
+            read_only: false,
         }
     }
 }
@@ -494,12 +1317,29 @@ impl ServerConfig {
         })
     }
 
+    pub fn coordinator_http_addr_sock(&self) -> Result<SocketAddr> {
+        let addr = format!("{}:{}", self.listen_if, self.coordinator_http_port);
+        addr.parse().map_err(|e: AddrParseError| {
+            anyhow!("Failed to parse listen address {} :{}", addr, e.to_string())
+        })
+    }
+
     pub fn raft_addr_sock(&self) -> Result<SocketAddr> {
         let addr = format!("{}:{}", self.listen_if, self.raft_port);
         addr.parse().map_err(|e: AddrParseError| {
             anyhow!("Failed to parse listen address {} :{}", addr, e.to_string())
         })
     }
+
+    pub fn admin_addr_sock(&self) -> Result<Option<SocketAddr>> {
+        let Some(port) = self.admin_api.listen_port else {
+            return Ok(None);
+        };
+        let addr = format!("{}:{}", self.listen_if, port);
+        addr.parse().map(Some).map_err(|e: AddrParseError| {
+            anyhow!("Failed to parse listen address {} :{}", addr, e.to_string())
+        })
+    }
 }
 
 #[cfg(test)]
@@ -1,4 +1,4 @@
-use std::{collections::BTreeMap, fmt::Debug};
+use std::{collections::BTreeMap, fmt::Debug, sync::Arc};
 
 use anyhow;
 
@@ -13,7 +13,7 @@ use super::{
     StateMachineUpdateRequest,
     TokioRuntime,
 };
-use crate::state::store::requests::StateMachineUpdateResponse;
+use crate::{chaos::ChaosInjector, state::store::requests::StateMachineUpdateResponse};
 
 openraft::declare_raft_types!(
   pub TypeConfig:
@@ -31,17 +31,26 @@ pub struct ForwardableRaft {
     id: NodeId,
     pub raft: Raft, //  the OpenRaft instance
     network: Network,
+    chaos: Arc<ChaosInjector>,
 }
 
 impl ForwardableRaft {
-    pub fn new(id: NodeId, raft: Raft, network: Network) -> Self {
-        Self { id, raft, network }
+    pub fn new(id: NodeId, raft: Raft, network: Network, chaos: Arc<ChaosInjector>) -> Self {
+        Self {
+            id,
+            raft,
+            network,
+            chaos,
+        }
     }
 
     pub async fn client_write(
         &self,
         request: StateMachineUpdateRequest,
     ) -> anyhow::Result<StateMachineUpdateResponse> {
+        if let Some(delay) = self.chaos.state_write_delay() {
+            tokio::time::sleep(delay).await;
+        }
         //  check whether this node is not the leader
         if let Some(forward_to_leader) = self.ensure_leader().await? {
             let leader_address = forward_to_leader
@@ -14,7 +14,13 @@ use super::{raft_client::RaftClient, NodeId};
 use crate::{
     grpc_helper::GrpcHelper,
     metrics::{raft_metrics, CounterGuard},
-    state::{store::requests, Raft},
+    state::{
+        store::{
+            requests,
+            state_version::{can_join_cluster, STATE_FORMAT_VERSION},
+        },
+        Raft,
+    },
 };
 
 pub struct RaftGrpcServer {
@@ -73,7 +79,14 @@ impl RaftGrpcServer {
         node_id: NodeId,
         address: &str,
         coordinator_addr: &str,
+        state_format_version: u32,
     ) -> Result<tonic::Response<RaftReply>, Status> {
+        if !can_join_cluster(state_format_version) {
+            return Err(GrpcHelper::internal_err(format!(
+                "node {} uses state format {} and can't join a cluster using format {}",
+                node_id, state_format_version, STATE_FORMAT_VERSION
+            )));
+        }
         let nodes_in_cluster = self.get_nodes_in_cluster();
         if nodes_in_cluster.contains_key(&node_id) {
             let response = StateMachineUpdateResponse {
@@ -111,6 +124,7 @@ impl RaftGrpcServer {
                 node_id: self.id,
                 address: self.address.clone(),
                 coordinator_addr: self.coordinator_address.clone(),
+                state_format_version: STATE_FORMAT_VERSION,
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
@@ -127,6 +141,7 @@ impl RaftGrpcServer {
                 node_id,
                 address: address.to_string(),
                 coordinator_addr: coordinator_addr.to_string(),
+                state_format_version,
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
@@ -189,10 +204,16 @@ impl RaftApi for RaftGrpcServer {
             node_id,
             address,
             coordinator_addr,
+            state_format_version,
         } = req.payload
         {
             return self
-                .add_node_to_cluster_if_absent(node_id, &address, &coordinator_addr)
+                .add_node_to_cluster_if_absent(
+                    node_id,
+                    &address,
+                    &coordinator_addr,
+                    state_format_version,
+                )
                 .await;
         }
 
@@ -279,6 +300,7 @@ impl RaftApi for RaftGrpcServer {
             node_id,
             address,
             coordinator_addr,
+            state_format_version,
         } = req.payload
         else {
             return Err(GrpcHelper::internal_err("Invalid request"));
@@ -300,6 +322,7 @@ impl RaftApi for RaftGrpcServer {
                     node_id,
                     address,
                     coordinator_addr,
+                    state_format_version,
                 },
                 new_state_changes: vec![],
                 state_changes_processed: vec![],
@@ -313,7 +336,12 @@ impl RaftApi for RaftGrpcServer {
         };
 
         //  This node is the leader - we've confirmed it
-        self.add_node_to_cluster_if_absent(node_id, &address, &coordinator_addr)
-            .await
+        self.add_node_to_cluster_if_absent(
+            node_id,
+            &address,
+            &coordinator_addr,
+            state_format_version,
+        )
+        .await
     }
 }
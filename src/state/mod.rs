@@ -4,13 +4,15 @@
 use std::{
     cell::RefCell,
     collections::{BTreeMap, HashMap, HashSet},
-    io::Cursor,
-    path::Path,
+    fs,
+    io::{self, Cursor},
+    path::{Path, PathBuf},
     sync::Arc,
     time::SystemTime,
 };
 
 use anyhow::{anyhow, Result};
+use futures::{Stream, TryStreamExt};
 use grpc_server::RaftGrpcServer;
 use indexify_internal_api as internal_api;
 use indexify_proto::{
@@ -20,10 +22,13 @@ use indexify_proto::{
 use internal_api::{
     ContentMetadataId,
     ExtractionGraph,
+    ExtractionGraphChange,
     ExtractionPolicy,
     StateChange,
     StateChangeId,
     StructuredDataSchema,
+    TaskTransition,
+    ZonePlacement,
 };
 use itertools::Itertools;
 use network::Network;
@@ -33,9 +38,9 @@ use openraft::{
     BasicNode,
     TokioRuntime,
 };
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
 use store::{
-    requests::{RequestPayload, StateChangeProcessed, StateMachineUpdateRequest},
+    requests::{RequestPayload, StateChangeProcessed, StateMachineUpdateRequest, TaskUpdate},
     ExecutorId,
     ExecutorIdRef,
     Response,
@@ -53,9 +58,11 @@ use tracing::{error, info, warn};
 
 use self::{
     forwardable_raft::ForwardableRaft,
+    notifier::{ChangeNotifier, ChangeWatcher, ConsumerLag},
     store::{requests::CreateOrUpdateContentEntry, StateMachineColumns, StateMachineStore},
 };
 use crate::{
+    chaos::ChaosInjector,
     coordinator_filters::matches_mime_type,
     garbage_collector::GarbageCollector,
     metrics::{
@@ -64,6 +71,7 @@ use crate::{
     },
     server_config::ServerConfig,
     state::{grpc_config::GrpcConfig, raft_client::RaftClient, store::new_storage},
+    task_allocator::planner::plan::TaskAllocationPlan,
     utils::timestamp_secs,
 };
 
@@ -71,6 +79,7 @@ pub mod forwardable_raft;
 pub mod grpc_config;
 pub mod grpc_server;
 pub mod network;
+pub mod notifier;
 pub mod raft_client;
 pub mod store;
 
@@ -115,6 +124,9 @@ pub mod typ {
 
 const MEMBERSHIP_CHECK_INTERVAL: tokio::time::Duration = tokio::time::Duration::from_secs(3);
 
+// Rows a stream reads from the store at a time.
+const STREAM_PAGE_SIZE: usize = 256;
+
 #[derive(Serialize)]
 pub struct RaftMetrics {
     pub openraft_metrics: openraft::RaftMetrics<NodeId, BasicNode>,
@@ -133,13 +145,15 @@ pub struct App {
     pub leader_change_rx: Receiver<bool>,
     join_handles: Mutex<Vec<JoinHandle<Result<()>>>>,
     pub config: Arc<openraft::Config>,
-    state_change_rx: Receiver<StateChange>,
+    state_changes: Arc<ChangeNotifier<StateChange>>,
     pub network: Network,
     pub node_addr: String,
     pub state_machine: Arc<StateMachineStore>,
     pub garbage_collector: Arc<GarbageCollector>,
     pub registry: Arc<prometheus::Registry>,
     pub metrics: Metrics,
+    /// Copy of the state directory a read only node runs on
+    working_dir: Option<PathBuf>,
 }
 
 #[derive(Clone)]
@@ -168,6 +182,26 @@ fn add_update_entry(
     statuses.push(CreateContentStatus::Created);
 }
 
+/// Copies a state directory, missing directories are left out. RocksDB writes
+/// its lock, manifest and options files whenever a database is opened, so a
+/// read only node opens a copy to leave the state directory as it was.
+fn copy_state_dir(from: &Path, to: &Path) -> io::Result<()> {
+    if !from.exists() {
+        return Ok(());
+    }
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_state_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
 impl App {
     pub async fn new(
         server_config: Arc<ServerConfig>,
@@ -196,24 +230,58 @@ impl App {
             }
         }
 
+        //  A read only node serves the state it was started with, it doesn't
+        //  elect a leader, replicate or build snapshots
+        if server_config.read_only {
+            raft_config.enable_tick = false;
+            raft_config.enable_heartbeat = false;
+            raft_config.enable_elect = false;
+            raft_config.snapshot_policy = openraft::SnapshotPolicy::Never;
+        }
+
         let config = Arc::new(
             raft_config
                 .validate()
                 .map_err(|e| anyhow!("invalid raft config: {}", e.to_string()))?,
         );
-        let db_path = server_config
-            .state_store
-            .path
-            .clone()
-            .unwrap_or_default()
-            .clone();
-        let db_path_str = db_path.as_str().to_owned() + "/db";
-        let sm_blob_store_path_str = db_path.as_str().to_owned() + "/sm-blob";
-        let db_path: &Path = Path::new(&db_path_str);
-        let sm_blob_store_path: &Path = Path::new(&sm_blob_store_path_str);
-
-        let (log_store, state_machine) = new_storage(db_path, sm_blob_store_path).await;
-        let state_change_rx = state_machine.state_change_rx.clone();
+        let state_dir = PathBuf::from(server_config.state_store.path.clone().unwrap_or_default());
+        let working_dir = server_config.read_only.then(|| {
+            std::env::temp_dir().join(format!(
+                "indexify-read-only-{}-{}",
+                server_config.node_id,
+                std::process::id()
+            ))
+        });
+        let state_dir = match &working_dir {
+            Some(working_dir) => {
+                let _ = fs::remove_dir_all(working_dir);
+                copy_state_dir(&state_dir, working_dir).map_err(|e| {
+                    anyhow!(
+                        "unable to copy {} to {}: {}",
+                        state_dir.display(),
+                        working_dir.display(),
+                        e
+                    )
+                })?;
+                info!(
+                    "read only, serving a copy of {} from {}",
+                    state_dir.display(),
+                    working_dir.display()
+                );
+                working_dir.clone()
+            }
+            None => state_dir,
+        };
+        let db_path = state_dir.join("db");
+        let sm_blob_store_path = state_dir.join("sm-blob");
+
+        let (log_store, state_machine) = new_storage(
+            db_path.as_path(),
+            sm_blob_store_path.as_path(),
+            server_config.state_store.invariant_validation,
+        )
+        .await?;
+        let state_changes = state_machine.data.state_changes.clone();
 
         let raft_client = Arc::new(RaftClient::new());
         let network = Network::new(Arc::clone(&raft_client));
@@ -228,8 +296,12 @@ impl App {
         .await
         .map_err(|e| anyhow!("unable to create raft: {}", e.to_string()))?;
 
-        let forwardable_raft =
-            ForwardableRaft::new(server_config.node_id, raft.clone(), network.clone());
+        let forwardable_raft = ForwardableRaft::new(
+            server_config.node_id,
+            raft.clone(),
+            network.clone(),
+            Arc::new(ChaosInjector::new(server_config.chaos.clone())),
+        );
 
         let mut nodes = BTreeMap::new();
         nodes.insert(
@@ -273,13 +345,14 @@ impl App {
             join_handles: Mutex::new(vec![]),
             nodes,
             config,
-            state_change_rx,
+            state_changes,
             network,
             node_addr: format!("{}:{}", server_config.listen_if, server_config.raft_port),
             state_machine,
             garbage_collector,
             registry,
             metrics,
+            working_dir,
         });
 
         let raft_clone = app.forwardable_raft.clone();
@@ -292,6 +365,11 @@ impl App {
             let _ = watch_for_leader_change(raft_clone, leader_change_tx, shutdown_rx).await;
         });
 
+        //  A read only node doesn't take part in the cluster
+        if server_config.read_only {
+            return Ok(app);
+        }
+
         //  Start task for GRPC server
         let grpc_svc = tonic::transport::Server::builder().add_service(raft_srvr);
         let h = tokio::spawn(async move {
@@ -350,8 +428,20 @@ impl App {
         }
     }
 
-    pub fn get_state_change_watcher(&self) -> Receiver<StateChange> {
-        self.state_change_rx.clone()
+    /// Watches the state changes applied from now on, with the lag of
+    /// `consumer` kept by the notifier of state changes
+    pub fn subscribe_to_state_changes(&self, consumer: &'static str) -> ChangeWatcher<StateChange> {
+        self.state_changes.subscribe(consumer)
+    }
+
+    /// The last state change applied to the state machine
+    pub fn latest_state_change(&self) -> StateChange {
+        self.state_changes.latest().clone()
+    }
+
+    /// How far behind the state changes each of their consumers is
+    pub fn state_change_lag(&self) -> BTreeMap<&'static str, ConsumerLag> {
+        self.state_changes.lag()
     }
 
     pub async fn stop(&self) -> Result<()> {
@@ -368,6 +458,9 @@ impl App {
                 error!("task quit with error: {:?}", e);
             }
         }
+        if let Some(working_dir) = &self.working_dir {
+            let _ = fs::remove_dir_all(working_dir);
+        }
         Ok(())
     }
 
@@ -438,6 +531,13 @@ impl App {
             if content_metadata.source.to_string() != extraction_policy.content_source.to_string() {
                 continue;
             }
+            if extraction_policy
+                .source_output
+                .as_ref()
+                .map_or(false, |output| *output != content_metadata.output)
+            {
+                continue;
+            }
             if !extraction_policy.filters.iter().all(|(name, value)| {
                 content_metadata
                     .labels
@@ -476,23 +576,6 @@ impl App {
             .get_extraction_policies_from_ids(extraction_policy_ids)
     }
 
-    pub async fn unassigned_tasks(&self) -> Result<Vec<internal_api::Task>> {
-        let mut tasks = vec![];
-        for task_id in self.state_machine.get_unassigned_tasks().await.iter() {
-            let task = self
-                .state_machine
-                .get_from_cf::<internal_api::Task, _>(StateMachineColumns::Tasks, task_id)?
-                .ok_or_else(|| {
-                    anyhow!(
-                        "Unable to get task with id {} from state machine store",
-                        task_id
-                    )
-                })?;
-            tasks.push(task.clone());
-        }
-        Ok(tasks)
-    }
-
     pub async fn task_assignments(&self) -> Result<HashMap<ExecutorId, TaskId>> {
         self.state_machine.get_all_task_assignments().await
     }
@@ -513,22 +596,178 @@ impl App {
             .await
     }
 
-    pub async fn get_executor_running_task_count(&self) -> HashMap<ExecutorId, u64> {
-        self.state_machine.get_executor_running_task_count().await
+    pub fn get_executor_loads_for_extractor(&self, extractor: &str) -> Vec<(ExecutorId, u64)> {
+        self.state_machine
+            .get_executor_loads_for_extractor(extractor)
+    }
+
+    pub fn warm_executors_for_tasks(
+        &self,
+        task_ids: &HashSet<TaskId>,
+    ) -> Result<HashMap<TaskId, HashSet<ExecutorId>>> {
+        self.state_machine.warm_executors_for_tasks(task_ids)
+    }
+
+    pub fn get_latency_tolerant_tasks(
+        &self,
+        task_ids: &HashSet<TaskId>,
+    ) -> Result<HashSet<TaskId>> {
+        self.state_machine.get_latency_tolerant_tasks(task_ids)
+    }
+
+    /// The tasks of `task_ids` that exist, by id
+    pub fn get_tasks_from_ids(
+        &self,
+        task_ids: &HashSet<TaskId>,
+    ) -> Result<HashMap<TaskId, internal_api::Task>> {
+        self.state_machine.get_tasks_from_ids(task_ids)
+    }
+
+    pub fn get_task_transitions(&self, task_id: &str) -> Result<Vec<TaskTransition>> {
+        self.state_machine.get_task_transitions(task_id)
+    }
+
+    pub async fn create_replay(&self, replay: internal_api::Replay) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::CreateReplay { replay },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub fn get_replay(&self, replay_id: &str) -> Result<Option<internal_api::Replay>> {
+        self.state_machine.get_replay(replay_id)
+    }
+
+    pub fn list_replays(&self, namespace: &str) -> Result<Vec<internal_api::Replay>> {
+        self.state_machine.list_replays(namespace)
+    }
+
+    pub async fn set_graph_template(&self, template: internal_api::GraphTemplate) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::SetGraphTemplate { template },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn delete_graph_template(&self, name: &str) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::DeleteGraphTemplate {
+                name: name.to_string(),
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub fn get_graph_template(&self, name: &str) -> Result<Option<internal_api::GraphTemplate>> {
+        self.state_machine.get_graph_template(name)
+    }
+
+    pub fn list_graph_templates(&self) -> Result<Vec<internal_api::GraphTemplate>> {
+        self.state_machine.list_graph_templates()
+    }
+
+    pub async fn set_cancel_tasks_job(&self, job: internal_api::CancelTasksJob) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::SetCancelTasksJob { job },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn delete_cancel_tasks_jobs(&self, job_ids: Vec<String>) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::DeleteCancelTasksJobs { job_ids },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub fn get_cancel_tasks_job(
+        &self,
+        job_id: &str,
+    ) -> Result<Option<internal_api::CancelTasksJob>> {
+        self.state_machine.get_cancel_tasks_job(job_id)
+    }
+
+    pub fn list_cancel_tasks_jobs(&self) -> Result<Vec<internal_api::CancelTasksJob>> {
+        self.state_machine.list_cancel_tasks_jobs()
+    }
+
+    pub async fn update_content_replicas(
+        &self,
+        added: Vec<internal_api::ContentReplica>,
+        removed: Vec<String>,
+        processed: HashMap<String, Option<String>>,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::UpdateContentReplicas {
+                added,
+                removed,
+                processed,
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub fn get_content_replica(
+        &self,
+        content_id: &str,
+    ) -> Result<Option<internal_api::ContentReplica>> {
+        self.state_machine.get_content_replica(content_id)
+    }
+
+    pub fn pending_replications(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<internal_api::PendingReplication>> {
+        self.state_machine.pending_replications(limit)
+    }
+
+    /// Zone placement and root content id of the tasks that are placed by
+    /// zone
+    pub fn get_zone_placements(
+        &self,
+        task_ids: &HashSet<TaskId>,
+    ) -> Result<HashMap<TaskId, (ZonePlacement, String)>> {
+        self.state_machine.get_zone_placements(task_ids)
+    }
+
+    /// Tasks given to each zone by root content, for the content placed by
+    /// zone
+    pub fn get_content_zones<'a>(
+        &self,
+        root_content_ids: impl IntoIterator<Item = &'a String>,
+    ) -> Result<HashMap<String, HashMap<String, usize>>> {
+        self.state_machine.get_content_zones(root_content_ids)
+    }
+
+    pub fn count_unfinished_gc_tasks(&self) -> usize {
+        self.state_machine.count_unfinished_gc_tasks()
     }
 
     pub async fn unfinished_tasks_by_extractor(
         &self,
         extractor: &str,
     ) -> Result<HashSet<TaskId>, anyhow::Error> {
-        let task_ids = self
+        Ok(self
             .state_machine
-            .get_unfinished_tasks_by_extractor()
-            .await
-            .get(extractor)
-            .cloned()
-            .unwrap_or_default();
-        Ok(task_ids)
+            .get_unfinished_tasks_for_extractor(extractor))
     }
 
     /// Get all content from a namespace
@@ -542,10 +781,142 @@ impl App {
             .list_content(namespace, parent_id, predicate)
     }
 
-    pub async fn remove_executor(&self, executor_id: &str) -> Result<()> {
+    /// Streams the content of a namespace with the keys of its rows, instead
+    /// of listing it all at once like `list_content`. The stream starts after
+    /// the row with the key `cursor`, and ends after an error.
+    pub fn stream_content(
+        &self,
+        namespace: &str,
+        parent_id: &str,
+        cursor: Option<String>,
+        predicate: impl Fn(&internal_api::ContentMetadata) -> bool + Send + Sync + 'static,
+    ) -> impl Stream<Item = Result<(String, internal_api::ContentMetadata)>> + Send + 'static {
+        let namespace = namespace.to_string();
+        let parent_id = parent_id.to_string();
+        self.stream_rows(
+            StateMachineColumns::ContentTable,
+            cursor,
+            move |content: &internal_api::ContentMetadata| {
+                content.namespace == namespace &&
+                    (parent_id.is_empty() ||
+                        content.parent_id.as_ref().map(|id| id.id.as_str()) ==
+                            Some(parent_id.as_str())) &&
+                    predicate(content)
+            },
+        )
+    }
+
+    /// Reads the rows of a column family that match `predicate` a page at a
+    /// time as the stream is polled, so no read holds the store for the whole
+    /// column family
+    fn stream_rows<V: DeserializeOwned + Send + 'static>(
+        &self,
+        column: StateMachineColumns,
+        cursor: Option<String>,
+        predicate: impl Fn(&V) -> bool + Send + Sync + 'static,
+    ) -> impl Stream<Item = Result<(String, V)>> + Send + 'static {
+        let state_machine = self.state_machine.clone();
+        let predicate = Arc::new(predicate);
+        let cursor = cursor.map(String::into_bytes);
+        //  The state is none once the column family was read to its end
+        futures::stream::try_unfold(Some(cursor), move |cursor| {
+            let state_machine = state_machine.clone();
+            let predicate = predicate.clone();
+            async move {
+                let Some(cursor) = cursor else {
+                    return Ok(None);
+                };
+                let (rows, next) = tokio::task::spawn_blocking(move || {
+                    state_machine.read_rows_page(
+                        column,
+                        cursor.as_deref(),
+                        STREAM_PAGE_SIZE,
+                        |row| predicate(row),
+                    )
+                })
+                .await??;
+                let rows = futures::stream::iter(rows.into_iter().map(Ok));
+                Ok::<_, anyhow::Error>(Some((rows, next.map(Some))))
+            }
+        })
+        .try_flatten()
+    }
+
+    /// Assignments of finished or deleted tasks by executor, and unfinished
+    /// tasks that are neither assigned nor unassigned
+    pub async fn find_orphaned_assignments(
+        &self,
+    ) -> Result<(HashMap<ExecutorId, HashSet<TaskId>>, HashSet<TaskId>)> {
+        self.state_machine.find_orphaned_assignments().await
+    }
+
+    /// Drops the assignments of finished or deleted tasks and puts unfinished
+    /// tasks that lost track of their executor back into the unassigned
+    /// tasks. Returns the number of orphaned assignments and unallocated tasks
+    /// that were reaped.
+    pub async fn reap_orphaned_assignments(
+        &self,
+        finished_task_assignments: HashMap<ExecutorId, HashSet<TaskId>>,
+        unallocated_tasks: HashSet<TaskId>,
+    ) -> Result<(usize, usize)> {
+        if finished_task_assignments.is_empty() && unallocated_tasks.is_empty() {
+            return Ok((0, 0));
+        }
+        let dropped = finished_task_assignments.values().map(|t| t.len()).sum();
+        let requeued = unallocated_tasks.len();
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::ReapOrphanedAssignments {
+                finished_task_assignments,
+                unallocated_tasks,
+                ts_secs: timestamp_secs(),
+            },
+            new_state_changes: vec![StateChange::new(
+                self.id.to_string(),
+                internal_api::ChangeType::OrphanedAssignmentsReaped,
+                timestamp_secs(),
+            )],
+            state_changes_processed: vec![],
+        };
+        self.forwardable_raft
+            .client_write(req)
+            .await
+            .map_err(|e| anyhow!("unable to reap orphaned assignments {}", e))?;
+        Ok((dropped, requeued))
+    }
+
+    /// Takes burst tasks back from their executors and puts them back into
+    /// the unassigned tasks
+    pub async fn shed_burst_tasks(
+        &self,
+        tasks: HashMap<ExecutorId, HashSet<TaskId>>,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::ShedBurstTasks {
+                tasks,
+                ts_secs: timestamp_secs(),
+            },
+            new_state_changes: vec![StateChange::new(
+                self.id.to_string(),
+                internal_api::ChangeType::BurstTasksShed,
+                timestamp_secs(),
+            )],
+            state_changes_processed: vec![],
+        };
+        self.forwardable_raft
+            .client_write(req)
+            .await
+            .map_err(|e| anyhow!("unable to shed burst tasks {}", e))?;
+        Ok(())
+    }
+
+    /// Removes an executor. Its unfinished tasks go back to the unassigned
+    /// tasks, as lost attempts unless the executor left `graceful`ly.
+    pub async fn remove_executor(&self, executor_id: &str, graceful: bool) -> Result<()> {
         let req = StateMachineUpdateRequest {
             payload: RequestPayload::RemoveExecutor {
                 executor_id: executor_id.to_string(),
+                graceful,
+                ts_secs: timestamp_secs(),
             },
             new_state_changes: vec![StateChange::new(
                 executor_id.to_string(),
@@ -566,6 +937,7 @@ impl App {
         extraction_graph: ExtractionGraph,
         structured_data_schema: StructuredDataSchema,
         indexes: Vec<internal_api::Index>,
+        change: ExtractionGraphChange,
     ) -> Result<()> {
         let existing_graph = self.state_machine.get_from_cf::<ExtractionGraph, _>(
             StateMachineColumns::ExtractionGraphs,
@@ -574,11 +946,48 @@ impl App {
         if existing_graph.is_some() {
             return Ok(());
         }
+        self.write_extraction_graph(extraction_graph, structured_data_schema, indexes, change)
+            .await
+    }
+
+    /// Replaces the definition of an existing extraction graph. Policies
+    /// missing from the new definition stop matching content, the tasks and
+    /// content they already created are kept.
+    pub async fn update_extraction_graph(
+        &self,
+        extraction_graph: ExtractionGraph,
+        structured_data_schema: StructuredDataSchema,
+        indexes: Vec<internal_api::Index>,
+        change: ExtractionGraphChange,
+    ) -> Result<()> {
+        let existing_graph = self.state_machine.get_from_cf::<ExtractionGraph, _>(
+            StateMachineColumns::ExtractionGraphs,
+            &extraction_graph.id,
+        )?;
+        if existing_graph.is_none() {
+            return Err(anyhow!(
+                "extraction graph {} not found in namespace {}",
+                extraction_graph.name,
+                extraction_graph.namespace
+            ));
+        }
+        self.write_extraction_graph(extraction_graph, structured_data_schema, indexes, change)
+            .await
+    }
+
+    async fn write_extraction_graph(
+        &self,
+        extraction_graph: ExtractionGraph,
+        structured_data_schema: StructuredDataSchema,
+        indexes: Vec<internal_api::Index>,
+        change: ExtractionGraphChange,
+    ) -> Result<()> {
         let req = StateMachineUpdateRequest {
             payload: RequestPayload::CreateExtractionGraph {
                 extraction_graph,
                 structured_data_schema,
                 indexes,
+                change,
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
@@ -590,6 +999,32 @@ impl App {
         Ok(())
     }
 
+    /// Definitions of an extraction graph, oldest first
+    pub fn get_extraction_graph_history(
+        &self,
+        extraction_graph_id: &str,
+    ) -> Result<Vec<internal_api::ExtractionGraphVersion>> {
+        self.state_machine
+            .get_extraction_graph_history(extraction_graph_id)
+    }
+
+    /// Latest version of an extraction graph, none for graphs created before
+    /// the history was kept and never changed since
+    pub fn get_extraction_graph_version(&self, extraction_graph_id: &str) -> Result<Option<u64>> {
+        Ok(self
+            .state_machine
+            .get_latest_extraction_graph_version(extraction_graph_id)?
+            .map(|version| version.version))
+    }
+
+    pub fn get_extraction_graph_usage(
+        &self,
+        extraction_graph_id: &str,
+    ) -> Result<Vec<internal_api::ExtractionGraphUsage>> {
+        self.state_machine
+            .get_extraction_graph_usage(extraction_graph_id)
+    }
+
     pub fn get_extraction_graphs_by_name(
         &self,
         namespace: &str,
@@ -604,6 +1039,82 @@ impl App {
         task: internal_api::Task,
         executor_id: Option<String>,
     ) -> Result<()> {
+        let new_state_changes = self
+            .task_completed_state_change(&task)?
+            .into_iter()
+            .collect();
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::UpdateTask {
+                task,
+                executor_id,
+                update_time: SystemTime::now(),
+            },
+            new_state_changes,
+            state_changes_processed: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    /// Finalizes the tasks an executor ran as a bundle in a single write
+    pub async fn update_tasks(
+        &self,
+        tasks: Vec<internal_api::Task>,
+        executor_id: &str,
+    ) -> Result<()> {
+        let updates = tasks
+            .into_iter()
+            .map(|task| TaskUpdate {
+                task,
+                executor_id: Some(executor_id.to_string()),
+            })
+            .collect();
+        self.write_task_updates(updates).await
+    }
+
+    /// Finalizes tasks of any executors in a single write. The tasks leave
+    /// the executors they are assigned to.
+    pub async fn finalize_tasks(&self, tasks: Vec<internal_api::Task>) -> Result<()> {
+        let updates = tasks
+            .into_iter()
+            .map(|task| TaskUpdate {
+                task,
+                executor_id: None,
+            })
+            .collect();
+        self.write_task_updates(updates).await
+    }
+
+    async fn write_task_updates(&self, updates: Vec<TaskUpdate>) -> Result<()> {
+        let mut new_state_changes: Vec<StateChange> = Vec::new();
+        for TaskUpdate { task, .. } in &updates {
+            if let Some(change) = self.task_completed_state_change(task)? {
+                if !new_state_changes
+                    .iter()
+                    .any(|existing| existing.object_id == change.object_id)
+                {
+                    new_state_changes.push(change);
+                }
+            }
+        }
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::UpdateTasks {
+                updates,
+                update_time: SystemTime::now(),
+            },
+            new_state_changes,
+            state_changes_processed: vec![],
+        };
+        self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    /// Triggers garbage collection of the previous versions of a task's root
+    /// content once the root content has been updated
+    fn task_completed_state_change(
+        &self,
+        task: &internal_api::Task,
+    ) -> Result<Option<StateChange>> {
         let root_content_id = if let Some(root_id) = &task.content_metadata.root_content_id {
             self.state_machine
                 .get_latest_version_of_content(root_id)?
@@ -611,29 +1122,16 @@ impl App {
         } else {
             Some(task.content_metadata.id.clone())
         };
-        // Trigger garbage collection for previous content if the root content has been
-        // updated.
-        let new_state_changes = match root_content_id {
-            Some(id) if id.version > 1 => vec![StateChange::new(
+        Ok(match root_content_id {
+            Some(id) if id.version > 1 => Some(StateChange::new(
                 id.to_string(),
                 indexify_internal_api::ChangeType::TaskCompleted {
                     root_content_id: id,
                 },
                 timestamp_secs(),
-            )],
-            _ => Vec::new(),
-        };
-        let req = StateMachineUpdateRequest {
-            payload: RequestPayload::UpdateTask {
-                task,
-                executor_id,
-                update_time: SystemTime::now(),
-            },
-            new_state_changes,
-            state_changes_processed: vec![],
-        };
-        let _resp = self.forwardable_raft.client_write(req).await?;
-        Ok(())
+            )),
+            _ => None,
+        })
     }
 
     pub async fn create_gc_tasks(
@@ -688,17 +1186,118 @@ impl App {
                 .into_iter()
                 .collect_vec()
         };
-        let extraction_policies = self
-            .state_machine
-            .get_extraction_policies_from_ids(extraction_policy_ids.into_iter().collect())?
-            .unwrap_or_else(Vec::new);
-        Ok(extraction_policies)
+        let extraction_policies = self
+            .state_machine
+            .get_extraction_policies_from_ids(extraction_policy_ids.into_iter().collect())?
+            .unwrap_or_else(Vec::new);
+        Ok(extraction_policies)
+    }
+
+    pub async fn create_namespace(&self, namespace: &str) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::CreateNamespace {
+                name: namespace.to_string(),
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub fn get_content_context(
+        &self,
+        root_content_id: &str,
+    ) -> Result<HashMap<String, serde_json::Value>> {
+        self.state_machine.get_content_context(root_content_id)
+    }
+
+    pub async fn set_content_context(
+        &self,
+        root_content_id: &str,
+        key: &str,
+        value: Option<serde_json::Value>,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::SetContentContext {
+                root_content_id: root_content_id.to_string(),
+                key: key.to_string(),
+                value,
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn set_scheduler_settings(
+        &self,
+        settings: internal_api::SchedulerSettings,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::SetSchedulerSettings { settings },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub fn get_scheduler_settings(&self) -> Result<internal_api::SchedulerSettings> {
+        self.state_machine.get_scheduler_settings()
+    }
+
+    pub async fn set_maintenance_mode(
+        &self,
+        maintenance: internal_api::MaintenanceMode,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::SetMaintenanceMode { maintenance },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub fn get_maintenance_mode(&self) -> Result<internal_api::MaintenanceMode> {
+        self.state_machine.get_maintenance_mode()
+    }
+
+    pub async fn clear_task_diagnostics(&self, task_ids: Vec<TaskId>) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::ClearTaskDiagnostics { task_ids },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn update_task_progress(
+        &self,
+        executor_id: &str,
+        progress: HashMap<TaskId, internal_api::TaskProgress>,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::UpdateTaskProgress {
+                executor_id: executor_id.to_string(),
+                progress,
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        let _resp = self.forwardable_raft.client_write(req).await?;
+        Ok(())
     }
 
-    pub async fn create_namespace(&self, namespace: &str) -> Result<()> {
+    pub async fn record_egress(&self, namespace: &str, bytes: u64) -> Result<()> {
         let req = StateMachineUpdateRequest {
-            payload: RequestPayload::CreateNamespace {
-                name: namespace.to_string(),
+            payload: RequestPayload::RecordEgress {
+                namespace: namespace.to_string(),
+                bytes,
+                time: SystemTime::now(),
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
@@ -707,6 +1306,16 @@ impl App {
         Ok(())
     }
 
+    pub fn list_metering_records(
+        &self,
+        namespace: Option<&str>,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<internal_api::MeteringRecord>> {
+        self.state_machine
+            .list_metering_records(namespace, start, end)
+    }
+
     pub async fn list_namespaces(&self) -> Result<Vec<internal_api::Namespace>> {
         //  Fetch the namespaces from the db
         let namespaces: Vec<String> = self
@@ -739,6 +1348,7 @@ impl App {
         addr: &str,
         executor_id: &str,
         extractors: Vec<internal_api::ExtractorDescription>,
+        profile: internal_api::ExecutorProfile,
     ) -> Result<()> {
         let state_change = StateChange::new(
             executor_id.to_string(),
@@ -750,6 +1360,13 @@ impl App {
                 addr: addr.to_string(),
                 executor_id: executor_id.to_string(),
                 extractors,
+                cost_per_hour: profile.cost_per_hour,
+                capacity_weight: profile.capacity_weight,
+                zone: profile.zone,
+                max_payload_bytes: profile.max_payload_bytes,
+                download_bytes_per_sec: profile.download_bytes_per_sec,
+                gpu_devices: profile.gpu_devices,
+                graph_versions: profile.graph_versions,
                 ts_secs: timestamp_secs(),
             },
             new_state_changes: vec![state_change.clone()],
@@ -759,6 +1376,49 @@ impl App {
         Ok(())
     }
 
+    /// Stops giving the executor new tasks, it keeps the tasks it runs
+    pub async fn drain_executor(&self, executor_id: &str) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::DrainExecutor {
+                executor_id: executor_id.to_string(),
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn set_executor_pressure(
+        &self,
+        executor_id: &str,
+        pressure: Option<internal_api::ExecutorPressure>,
+    ) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::SetExecutorPressure {
+                executor_id: executor_id.to_string(),
+                pressure,
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
+    pub async fn renew_executor_lease(&self, executor_id: &str) -> Result<()> {
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::RenewExecutorLease {
+                executor_id: executor_id.to_string(),
+                ts_secs: timestamp_secs(),
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        self.forwardable_raft.client_write(req).await?;
+        Ok(())
+    }
+
     pub async fn list_extractors(&self) -> Result<Vec<internal_api::ExtractorDescription>> {
         let extractors: Vec<internal_api::ExtractorDescription> = self
             .state_machine
@@ -799,11 +1459,18 @@ impl App {
 
     pub async fn commit_task_assignments(
         &self,
-        assignments: HashMap<TaskId, ExecutorId>,
+        plan: TaskAllocationPlan,
         state_change_id: StateChangeId,
     ) -> Result<()> {
+        let task_ids: Vec<TaskId> = plan.assignments.keys().cloned().collect();
         let req = StateMachineUpdateRequest {
-            payload: RequestPayload::AssignTask { assignments },
+            payload: RequestPayload::AssignTask {
+                assignments: plan.assignments,
+                assigned_at: Some(SystemTime::now()),
+                gpu_devices: plan.gpu_devices,
+                burst_tasks: plan.burst_tasks,
+                content_zones: plan.content_zones,
+            },
             new_state_changes: vec![],
             state_changes_processed: vec![StateChangeProcessed {
                 state_change_id,
@@ -811,6 +1478,28 @@ impl App {
             }],
         };
         self.forwardable_raft.client_write(req).await?;
+        self.state_machine
+            .consume_rate_limit_tokens(task_ids.iter());
+        Ok(())
+    }
+
+    /// Assigns tasks outside of the processing of a state change
+    pub async fn assign_tasks(&self, plan: TaskAllocationPlan) -> Result<()> {
+        let task_ids: Vec<TaskId> = plan.assignments.keys().cloned().collect();
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::AssignTask {
+                assignments: plan.assignments,
+                assigned_at: Some(SystemTime::now()),
+                gpu_devices: plan.gpu_devices,
+                burst_tasks: plan.burst_tasks,
+                content_zones: plan.content_zones,
+            },
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        self.forwardable_raft.client_write(req).await?;
+        self.state_machine
+            .consume_rate_limit_tokens(task_ids.iter());
         Ok(())
     }
 
@@ -1078,7 +1767,10 @@ impl App {
         state_change_id: StateChangeId,
     ) -> Result<()> {
         let req = StateMachineUpdateRequest {
-            payload: RequestPayload::CreateTasks { tasks },
+            payload: RequestPayload::CreateTasks {
+                tasks,
+                ts_secs: timestamp_secs(),
+            },
             new_state_changes: vec![],
             state_changes_processed: vec![StateChangeProcessed {
                 state_change_id,
@@ -1089,6 +1781,36 @@ impl App {
         Ok(())
     }
 
+    /// Re-creates the missing tasks of a root content and resets the number
+    /// of tasks the content waits for to what is actually pending
+    pub async fn repair_content(
+        &self,
+        root_content_id: &str,
+        tasks: Vec<internal_api::Task>,
+    ) -> Result<()> {
+        let pending = self.state_machine.get_pending_work(root_content_id)?;
+        let req = StateMachineUpdateRequest {
+            payload: RequestPayload::RepairContent {
+                root_content_id: root_content_id.to_string(),
+                tasks,
+                pending_task_ids: pending.task_ids,
+                pending_state_change_ids: pending.state_change_ids,
+                ts_secs: timestamp_secs(),
+            },
+            new_state_changes: vec![StateChange::new(
+                root_content_id.to_string(),
+                internal_api::ChangeType::ContentRepaired,
+                timestamp_secs(),
+            )],
+            state_changes_processed: vec![],
+        };
+        self.forwardable_raft
+            .client_write(req)
+            .await
+            .map_err(|e| anyhow!("unable to repair content {}: {}", root_content_id, e))?;
+        Ok(())
+    }
+
     pub async fn list_tasks(
         &self,
         namespace: &str,
@@ -1115,6 +1837,64 @@ impl App {
         Ok(filtered_tasks)
     }
 
+    /// Streams the tasks of a namespace with the keys of their rows, starting
+    /// after the row with the key `cursor`
+    pub fn stream_tasks(
+        &self,
+        namespace: &str,
+        extraction_policy: Option<String>,
+        cursor: Option<String>,
+    ) -> impl Stream<Item = Result<(String, internal_api::Task)>> + Send + 'static {
+        let namespace = namespace.to_string();
+        self.stream_rows(
+            StateMachineColumns::Tasks,
+            cursor,
+            move |task: &internal_api::Task| {
+                task.namespace == namespace &&
+                    extraction_policy
+                        .as_ref()
+                        .map_or(true, |policy| policy == &task.extraction_policy_id)
+            },
+        )
+    }
+
+    /// Streams the unfinished tasks of a namespace that `predicate` matches.
+    /// The rows are filtered as they are read, so only the matching tasks are
+    /// kept.
+    pub fn stream_unfinished_tasks(
+        &self,
+        namespace: &str,
+        predicate: impl Fn(&internal_api::Task) -> bool + Send + Sync + 'static,
+    ) -> impl Stream<Item = Result<internal_api::Task>> + Send + 'static {
+        let namespace = namespace.to_string();
+        self.stream_rows(
+            StateMachineColumns::Tasks,
+            None,
+            move |task: &internal_api::Task| {
+                task.namespace == namespace && !task.terminal_state() && predicate(task)
+            },
+        )
+        .map_ok(|(_, task)| task)
+    }
+
+    /// Records a state change for running the extraction policy on the content
+    /// again. The scheduler creates the task when processing the change.
+    pub async fn rerun_extraction_policy(
+        &self,
+        content_id: &ContentMetadataId,
+        extraction_policy_id: &str,
+    ) -> Result<()> {
+        let state_change = StateChange::new(
+            content_id.id.clone(),
+            internal_api::ChangeType::RerunExtractionPolicy {
+                extraction_policy_id: extraction_policy_id.to_string(),
+            },
+            timestamp_secs(),
+        );
+        self.mark_change_events_as_processed(vec![], vec![state_change])
+            .await
+    }
+
     pub async fn update_labels(
         &self,
         namespace: &str,
@@ -1160,6 +1940,19 @@ impl App {
         Ok(())
     }
 
+    /// Thousandths of each GPU device of `executor` taken by the tasks
+    /// assigned to it
+    pub async fn gpu_occupancy(
+        &self,
+        executor: &internal_api::ExecutorMetadata,
+    ) -> Result<Vec<u32>> {
+        if executor.gpu_devices.is_empty() {
+            return Ok(Vec::new());
+        }
+        let tasks = self.tasks_for_executor(&executor.id, None).await?;
+        Ok(executor.gpu_occupancy(&tasks))
+    }
+
     pub async fn tasks_for_executor(
         &self,
         executor_id: &str,
@@ -1199,6 +1992,15 @@ impl App {
         Ok(gc_tasks)
     }
 
+    /// Whether the task is currently assigned to the executor
+    pub fn is_task_assigned_to(&self, task_id: &str, executor_id: &str) -> Result<bool> {
+        let task_ids = self
+            .state_machine
+            .get_from_cf::<HashSet<TaskId>, _>(StateMachineColumns::TaskAssignments, executor_id)?
+            .unwrap_or_default();
+        Ok(task_ids.contains(task_id))
+    }
+
     pub async fn task_with_id(&self, task_id: &str) -> Result<internal_api::Task> {
         let task = self
             .state_machine
@@ -1291,10 +2093,22 @@ impl App {
         Ok(schemas)
     }
 
-    pub async fn get_unfinished_tasks_by_extractor(
+    pub fn group_unfinished_tasks_by_extractor<'a>(
+        &self,
+        task_ids: &'a HashSet<TaskId>,
+    ) -> HashMap<store::ExtractorName, Vec<&'a TaskId>> {
+        self.state_machine
+            .group_unfinished_tasks_by_extractor(task_ids)
+    }
+
+    pub async fn unassigned_task_ids(&self) -> HashSet<TaskId> {
+        self.state_machine.get_unassigned_tasks().await
+    }
+
+    pub async fn task_queue_depths(
         &self,
-    ) -> HashMap<store::ExtractorName, HashSet<TaskId>> {
-        self.state_machine.get_unfinished_tasks_by_extractor().await
+    ) -> HashMap<store::ExtractionPolicyId, store::state_machine_objects::TaskQueueDepth> {
+        self.state_machine.get_task_queue_depths().await
     }
 
     pub async fn are_content_tasks_completed(&self, content_id: &ContentMetadataId) -> bool {
@@ -1407,17 +2221,28 @@ async fn watch_for_leader_change(
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashMap, sync::Arc, time::Duration};
+    use std::{
+        collections::{HashMap, HashSet},
+        fs,
+        path::{Path, PathBuf},
+        sync::Arc,
+        time::Duration,
+    };
 
     use indexify_internal_api::{
+        ChangeType,
         ContentMetadata,
         ContentMetadataId,
+        ContentSource,
         ExtractionGraph,
+        ExtractionGraphChange,
         StructuredDataSchema,
         TaskOutcome,
     };
 
     use crate::{
+        garbage_collector::GarbageCollector,
+        server_config::{ServerConfig, StateStoreConfig},
         state::{
             store::{
                 requests::{RequestPayload, StateMachineUpdateRequest},
@@ -1428,8 +2253,10 @@ mod tests {
         },
         test_util::db_utils::{
             create_test_extraction_graph,
+            create_test_extraction_graph_with_children,
             mock_extractor,
             test_mock_content_metadata,
+            Parent,
         },
         test_utils::RaftTestCluster,
     };
@@ -1464,6 +2291,152 @@ mod tests {
         Ok(())
     }
 
+    fn read_dir_bytes(dir: &Path, files: &mut Vec<(PathBuf, Vec<u8>)>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                read_dir_bytes(&path, files);
+            } else {
+                let bytes = fs::read(&path).unwrap();
+                files.push((path, bytes));
+            }
+        }
+        files.sort();
+    }
+
+    /// A read only node serves the state directory without changing a byte
+    /// of it
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_read_only_leaves_state_dir_unchanged() -> Result<(), anyhow::Error> {
+        let cluster = RaftTestCluster::new(1, None).await?;
+        cluster.initialize(Duration::from_secs(2)).await?;
+        let node = cluster.get_raft_node(0)?;
+        let content = ContentMetadata {
+            id: ContentMetadataId::new("content_id"),
+            ..Default::default()
+        };
+        node.create_content_batch(vec![content.clone()]).await?;
+        let state_dir = format!("/tmp/indexify-test/raft/{}/0", cluster.append);
+        node.stop().await?;
+        drop(node);
+        drop(cluster);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let mut before = Vec::new();
+        read_dir_bytes(Path::new(&state_dir), &mut before);
+
+        let config = Arc::new(ServerConfig {
+            state_store: StateStoreConfig {
+                path: Some(state_dir.clone()),
+                ..Default::default()
+            },
+            read_only: true,
+            ..Default::default()
+        });
+        let node = App::new(
+            config.clone(),
+            None,
+            GarbageCollector::new(),
+            &config.coordinator_addr,
+            Arc::new(crate::metrics::init_provider()),
+        )
+        .await?;
+        let read = node
+            .get_content_metadata_batch(vec![content.id.id.clone()])
+            .await?;
+        assert_eq!(read.len(), 1);
+        tokio::time::sleep(Duration::from_secs(4)).await;
+        assert_eq!(
+            node.forwardable_raft.raft.metrics().borrow().current_leader,
+            None
+        );
+        node.stop().await?;
+
+        let mut after = Vec::new();
+        read_dir_bytes(Path::new(&state_dir), &mut after);
+        assert_eq!(after, before);
+        Ok(())
+    }
+
+    /// Extracted content is queued for replication until its replica matches
+    /// it, and again once it changes or is deleted
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_pending_replications() -> Result<(), anyhow::Error> {
+        let cluster = RaftTestCluster::new(1, None).await?;
+        cluster.initialize(Duration::from_secs(2)).await?;
+        let node = cluster.get_raft_node(0)?;
+        let ingested = ContentMetadata {
+            id: ContentMetadataId::new("ingested"),
+            ..Default::default()
+        };
+        let output = ContentMetadata {
+            id: ContentMetadataId::new("output"),
+            source: ContentSource::ExtractionPolicyName("embed".to_string()),
+            hash: "hash_1".to_string(),
+            ..Default::default()
+        };
+        node.create_content_batch(vec![ingested, output.clone()])
+            .await?;
+        let pending = node.pending_replications(10)?;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].content_id, "output");
+        assert_eq!(pending[0].content.as_ref().unwrap().hash, "hash_1");
+        assert_eq!(pending[0].replica, None);
+
+        //  A replica of an older version leaves the new version pending
+        let replica = indexify_internal_api::ContentReplica {
+            content_id: "output".to_string(),
+            hash: "hash_1".to_string(),
+            ..Default::default()
+        };
+        node.create_content_batch(vec![ContentMetadata {
+            hash: "hash_2".to_string(),
+            ..output.clone()
+        }])
+        .await?;
+        node.update_content_replicas(
+            vec![replica.clone()],
+            vec![],
+            HashMap::from([("output".to_string(), Some("hash_1".to_string()))]),
+        )
+        .await?;
+        let pending = node.pending_replications(10)?;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].content.as_ref().unwrap().hash, "hash_2");
+        assert_eq!(pending[0].replica, Some(replica.clone()));
+
+        let replica = indexify_internal_api::ContentReplica {
+            hash: "hash_2".to_string(),
+            ..replica
+        };
+        node.update_content_replicas(
+            vec![replica.clone()],
+            vec![],
+            HashMap::from([("output".to_string(), Some("hash_2".to_string()))]),
+        )
+        .await?;
+        assert!(node.pending_replications(10)?.is_empty());
+
+        //  Deleted content is queued to remove its replica
+        node.tombstone_content_batch(&["output".to_string()])
+            .await?;
+        let pending = node.pending_replications(10)?;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].content, None);
+        assert_eq!(pending[0].replica, Some(replica));
+        node.update_content_replicas(
+            vec![],
+            vec!["output".to_string()],
+            HashMap::from([("output".to_string(), None)]),
+        )
+        .await?;
+        assert!(node.pending_replications(10)?.is_empty());
+        assert_eq!(node.get_content_replica("output")?, None);
+        Ok(())
+    }
+
     /// Test to determine that assigning a task to an executor works correctly
     #[tokio::test]
     // #[tracing_test::traced_test]
@@ -1486,6 +2459,7 @@ mod tests {
         let request = StateMachineUpdateRequest {
             payload: RequestPayload::CreateTasks {
                 tasks: vec![task.clone()],
+                ts_secs: 0,
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
@@ -1502,13 +2476,21 @@ mod tests {
         };
         cluster.read_own_write(request, read_back, true).await?;
 
-        //  assign the task to some executor
+        //  assign the task to a registered executor
+        node.register_executor("localhost:8950", "executor_id", vec![], Default::default())
+            .await?;
         let assignments: HashMap<TaskId, ExecutorId> =
             vec![("task_id".into(), "executor_id".into())]
                 .into_iter()
                 .collect();
         let request = StateMachineUpdateRequest {
-            payload: RequestPayload::AssignTask { assignments },
+            payload: RequestPayload::AssignTask {
+                assignments,
+                assigned_at: None,
+                gpu_devices: HashMap::new(),
+                burst_tasks: HashSet::new(),
+                content_zones: HashMap::new(),
+            },
             new_state_changes: vec![],
             state_changes_processed: vec![],
         };
@@ -1551,6 +2533,7 @@ mod tests {
         let request = StateMachineUpdateRequest {
             payload: RequestPayload::CreateTasks {
                 tasks: vec![task.clone()],
+                ts_secs: 0,
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
@@ -1566,13 +2549,21 @@ mod tests {
         };
         cluster.read_own_write(request, read_back, true).await?;
 
-        //  Assign the task to an executor
+        //  Assign the task to a registered executor
+        node.register_executor("localhost:8950", "executor_id", vec![], Default::default())
+            .await?;
         let assignments: HashMap<TaskId, ExecutorId> =
             vec![("task_id".into(), "executor_id".into())]
                 .into_iter()
                 .collect();
         let request = StateMachineUpdateRequest {
-            payload: RequestPayload::AssignTask { assignments },
+            payload: RequestPayload::AssignTask {
+                assignments,
+                assigned_at: None,
+                gpu_devices: HashMap::new(),
+                burst_tasks: HashSet::new(),
+                content_zones: HashMap::new(),
+            },
             new_state_changes: vec![],
             state_changes_processed: vec![],
         };
@@ -1626,8 +2617,13 @@ mod tests {
             ..Default::default()
         };
         let addr = "addr";
-        node.register_executor(addr, executor_id, vec![extractor.clone()])
-            .await?;
+        node.register_executor(
+            addr,
+            executor_id,
+            vec![extractor.clone()],
+            Default::default(),
+        )
+        .await?;
 
         //  Read the executors from multiple functions
         let executors = node.get_executors().await?;
@@ -1648,7 +2644,7 @@ mod tests {
         assert_eq!(retrieved_extractor, extractor);
 
         //  Remove the executor that was created and assert that it was removed
-        node.remove_executor(executor_id).await?;
+        node.remove_executor(executor_id, false).await?;
         let executors = node.get_executors().await?;
         assert_eq!(executors.len(), 0);
 
@@ -1718,8 +2714,13 @@ mod tests {
         let mut extractor = mock_extractor();
         extractor.input_mime_types = vec!["*/*".into()];
         let addr = "addr";
-        node.register_executor(addr, executor_id, vec![extractor.clone()])
-            .await?;
+        node.register_executor(
+            addr,
+            executor_id,
+            vec![extractor.clone()],
+            Default::default(),
+        )
+        .await?;
 
         let mut eg = create_test_extraction_graph("graph1", vec!["policy1"]);
 
@@ -1729,8 +2730,13 @@ mod tests {
             ("label3".to_string(), "value3".to_string()),
         ]);
 
-        node.create_extraction_graph(eg.clone(), StructuredDataSchema::default(), vec![])
-            .await?;
+        node.create_extraction_graph(
+            eg.clone(),
+            StructuredDataSchema::default(),
+            vec![],
+            ExtractionGraphChange::default(),
+        )
+        .await?;
 
         //  Read the policy back using namespace
         let read_policy = node.list_extraction_policy(&eg.namespace).await?;
@@ -1798,10 +2804,18 @@ mod tests {
                     ..Default::default()
                 },
             ],
+            max_active_content: None,
+            latency_tolerant: false,
+            zone_placement: Default::default(),
         };
         let structured_schema = StructuredDataSchema::new(&eg.name, &eg.namespace);
-        node.create_extraction_graph(eg, structured_schema, vec![])
-            .await?;
+        node.create_extraction_graph(
+            eg,
+            structured_schema,
+            vec![],
+            ExtractionGraphChange::default(),
+        )
+        .await?;
 
         //  Read the namespace back and expect to get the extraction policies as well
         // which will be asserted
@@ -1841,8 +2855,13 @@ mod tests {
         let executor_id = "executor_id";
         let extractor = mock_extractor();
         let addr = "addr";
-        node.register_executor(addr, executor_id, vec![extractor.clone()])
-            .await?;
+        node.register_executor(
+            addr,
+            executor_id,
+            vec![extractor.clone()],
+            Default::default(),
+        )
+        .await?;
 
         //  Create the extraction graph
         let mut eg = create_test_extraction_graph("extraction_graph", vec!["extraction_policy"]);
@@ -1853,6 +2872,7 @@ mod tests {
             eg.clone(),
             StructuredDataSchema::new(&eg.name, &eg.namespace),
             vec![], //  no indexes
+            ExtractionGraphChange::default(),
         )
         .await?;
 
@@ -1886,4 +2906,108 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_match_extraction_policies_by_output() -> Result<(), anyhow::Error> {
+        let cluster = RaftTestCluster::new(1, None).await?;
+        cluster.initialize(Duration::from_secs(2)).await?;
+        let node = cluster.get_raft_node(0)?;
+        node.register_executor(
+            "addr",
+            "executor_id",
+            vec![mock_extractor()],
+            Default::default(),
+        )
+        .await?;
+
+        let mut eg = create_test_extraction_graph_with_children(
+            "extraction_graph",
+            vec!["split", "embed_chunks", "summarize"],
+            &[Parent::Root, Parent::Child(0), Parent::Child(0)],
+        );
+        eg.extraction_policies[1].source_output = Some("chunks".to_string());
+        node.create_extraction_graph(
+            eg.clone(),
+            StructuredDataSchema::new(&eg.name, &eg.namespace),
+            vec![],
+            ExtractionGraphChange::default(),
+        )
+        .await?;
+
+        let mut content = test_mock_content_metadata("content_id", "root_id", &eg.name);
+        content.source = ContentSource::ExtractionPolicyName("split".to_string());
+        content.output = "chunks".to_string();
+        let mut policies: Vec<String> = node
+            .match_extraction_policies_for_content(&content)
+            .await?
+            .into_iter()
+            .map(|policy| policy.name)
+            .collect();
+        policies.sort();
+        assert_eq!(policies, vec!["embed_chunks", "summarize"]);
+
+        content.output = "metadata".to_string();
+        let policies: Vec<String> = node
+            .match_extraction_policies_for_content(&content)
+            .await?
+            .into_iter()
+            .map(|policy| policy.name)
+            .collect();
+        assert_eq!(policies, vec!["summarize"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_active_root_content() -> Result<(), anyhow::Error> {
+        let cluster = RaftTestCluster::new(1, None).await?;
+        cluster.initialize(Duration::from_secs(2)).await?;
+        let node = cluster.get_raft_node(0)?;
+        let graph_id = ExtractionGraph::create_id("graph", "namespace");
+
+        let content = ContentMetadata {
+            id: ContentMetadataId::new("content_id"),
+            namespace: "namespace".into(),
+            ..Default::default()
+        };
+        node.create_content_batch(vec![content.clone()]).await?;
+        //  Ingested content is not active before its tasks are created
+        assert!(node.state_machine.active_root_content(&graph_id).is_empty());
+
+        let state_change = node
+            .unprocessed_state_change_events()
+            .await?
+            .into_iter()
+            .find(|change| change.change_type == ChangeType::NewContent)
+            .unwrap();
+        let task = indexify_internal_api::Task {
+            id: "task_id".into(),
+            extraction_graph_name: "graph".into(),
+            namespace: "namespace".into(),
+            content_metadata: content.clone(),
+            ..Default::default()
+        };
+        node.create_tasks(vec![task.clone()], state_change.id)
+            .await?;
+        assert_eq!(
+            node.state_machine.active_root_content(&graph_id),
+            HashSet::from(["content_id".to_string()])
+        );
+        assert!(node
+            .state_machine
+            .active_root_content(&ExtractionGraph::create_id("other", "namespace"))
+            .is_empty());
+
+        node.update_task(
+            indexify_internal_api::Task {
+                outcome: TaskOutcome::Success,
+                ..task
+            },
+            None,
+        )
+        .await?;
+        assert!(node.state_machine.active_root_content(&graph_id).is_empty());
+
+        Ok(())
+    }
 }
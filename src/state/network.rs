@@ -26,7 +26,10 @@ use crate::{
     },
     state::{
         raft_client::RaftClient,
-        store::requests::{RequestPayload, StateMachineUpdateRequest},
+        store::{
+            requests::{RequestPayload, StateMachineUpdateRequest},
+            state_version::STATE_FORMAT_VERSION,
+        },
         typ::{InstallSnapshotError, RPCError, RaftError},
         NodeId,
         TypeConfig,
@@ -113,6 +116,7 @@ impl Network {
                 node_id,
                 address: node_addr.into(),
                 coordinator_addr: coordinator_addr.into(),
+                state_format_version: STATE_FORMAT_VERSION,
             },
             new_state_changes: vec![],
             state_changes_processed: vec![],
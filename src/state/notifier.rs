@@ -0,0 +1,184 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::sync::watch;
+
+/// How far a consumer is behind the changes sent to it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConsumerLag {
+    /// Changes sent since the consumer last took the latest change
+    pub pending: u64,
+    /// How long the oldest pending change has been waiting
+    pub oldest_pending_age: Option<Duration>,
+    /// Changes the consumer was never woken up for because a later change
+    /// was sent before it looked
+    pub coalesced: u64,
+}
+
+#[derive(Default)]
+struct Consumer {
+    seen: u64,
+    oldest_pending: Option<Instant>,
+    coalesced: u64,
+}
+
+#[derive(Default)]
+struct Counts {
+    sent: u64,
+    consumers: BTreeMap<&'static str, Consumer>,
+}
+
+/// Wakes consumers up when a value changes, like a watch channel, and keeps
+/// count of what each consumer hasn't looked at yet. A consumer that is busy
+/// while several changes are sent is woken up once for all of them. The counts
+/// are of wakeups, what a consumer still has to process is tracked by the
+/// consumer itself, like the unprocessed state changes of the scheduler.
+pub struct ChangeNotifier<T> {
+    tx: watch::Sender<T>,
+    counts: Arc<Mutex<Counts>>,
+}
+
+impl<T> ChangeNotifier<T> {
+    pub fn new(initial: T) -> Self {
+        let (tx, _) = watch::channel(initial);
+        Self {
+            tx,
+            counts: Default::default(),
+        }
+    }
+
+    /// Replaces the latest value and wakes up every consumer
+    pub fn send(&self, value: T) {
+        let mut counts = self.counts.lock().unwrap();
+        counts.sent += 1;
+        let now = Instant::now();
+        for consumer in counts.consumers.values_mut() {
+            consumer.oldest_pending.get_or_insert(now);
+        }
+        self.tx.send_replace(value);
+    }
+
+    /// Watches the changes sent from now on. The lag is kept under the name
+    /// of the consumer while the watcher lives, a second watcher of the same
+    /// name starts the counts of the name over.
+    pub fn subscribe(&self, consumer: &'static str) -> ChangeWatcher<T> {
+        let mut counts = self.counts.lock().unwrap();
+        let seen = counts.sent;
+        counts.consumers.insert(
+            consumer,
+            Consumer {
+                seen,
+                ..Default::default()
+            },
+        );
+        ChangeWatcher {
+            rx: self.tx.subscribe(),
+            counts: self.counts.clone(),
+            consumer,
+        }
+    }
+
+    /// The latest value, without counting it as seen by any consumer
+    pub fn latest(&self) -> watch::Ref<'_, T> {
+        self.tx.borrow()
+    }
+
+    /// Lag of every consumer by its name
+    pub fn lag(&self) -> BTreeMap<&'static str, ConsumerLag> {
+        let counts = self.counts.lock().unwrap();
+        let now = Instant::now();
+        counts
+            .consumers
+            .iter()
+            .map(|(name, consumer)| {
+                (
+                    *name,
+                    ConsumerLag {
+                        pending: counts.sent - consumer.seen,
+                        oldest_pending_age: consumer.oldest_pending.map(|at| now - at),
+                        coalesced: consumer.coalesced,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+/// The receiving end of a [`ChangeNotifier`] for one consumer
+pub struct ChangeWatcher<T> {
+    rx: watch::Receiver<T>,
+    counts: Arc<Mutex<Counts>>,
+    consumer: &'static str,
+}
+
+impl<T> ChangeWatcher<T> {
+    /// Waits for a change the consumer hasn't seen
+    pub async fn changed(&mut self) -> Result<(), watch::error::RecvError> {
+        self.rx.changed().await
+    }
+
+    /// Takes the latest value, which counts every change sent until now as
+    /// seen
+    pub fn borrow_and_update(&mut self) -> watch::Ref<'_, T> {
+        {
+            let mut counts = self.counts.lock().unwrap();
+            let sent = counts.sent;
+            if let Some(consumer) = counts.consumers.get_mut(self.consumer) {
+                consumer.coalesced += (sent - consumer.seen).saturating_sub(1);
+                consumer.seen = sent;
+                consumer.oldest_pending = None;
+            }
+        }
+        self.rx.borrow_and_update()
+    }
+}
+
+impl<T> Drop for ChangeWatcher<T> {
+    fn drop(&mut self) {
+        self.counts.lock().unwrap().consumers.remove(self.consumer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_coalesced_changes() {
+        let notifier = ChangeNotifier::new(0);
+        let mut watcher = notifier.subscribe("scheduler");
+        assert_eq!(notifier.lag()["scheduler"], ConsumerLag::default());
+
+        //  Three changes sent while the consumer is busy wake it up once
+        for i in 1..=3 {
+            notifier.send(i);
+        }
+        let lag = notifier.lag()["scheduler"].clone();
+        assert_eq!(lag.pending, 3);
+        assert!(lag.oldest_pending_age.is_some());
+
+        watcher.changed().await.unwrap();
+        assert_eq!(*watcher.borrow_and_update(), 3);
+        assert_eq!(
+            notifier.lag()["scheduler"],
+            ConsumerLag {
+                pending: 0,
+                oldest_pending_age: None,
+                coalesced: 2,
+            }
+        );
+
+        //  A change taken as soon as it is sent isn't coalesced
+        notifier.send(4);
+        watcher.changed().await.unwrap();
+        assert_eq!(*watcher.borrow_and_update(), 4);
+        assert_eq!(notifier.lag()["scheduler"].coalesced, 2);
+        assert_eq!(*notifier.latest(), 4);
+
+        drop(watcher);
+        assert!(notifier.lag().is_empty());
+    }
+}
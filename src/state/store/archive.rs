@@ -0,0 +1,463 @@
+use std::{
+    io::Write,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use bytes::Bytes;
+use openraft::{storage::RaftLogStorage, LogId, RaftLogReader};
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+
+use super::{
+    restore::{ArchiveManifest, ArchivedLogEntry, ArchivedObject, MANIFEST_NAME},
+    serializer::{JsonEncode, JsonEncoder},
+    state_version::StateVersion,
+    LogStore,
+    StateMachineStore,
+};
+use crate::{
+    blob_storage::{BlobStorage, BlobStorageWriter},
+    server_config::StateArchiveConfig,
+    state::NodeId,
+    utils::timestamp_secs,
+};
+
+/// Most log entries written to one segment, larger backlogs are split so a
+/// segment stays small enough to hold in memory
+const MAX_SEGMENT_ENTRIES: u64 = 10_000;
+
+/// What a call to `StateArchiver::ship` wrote
+#[derive(Debug)]
+pub struct ShipReport {
+    /// Url of the archive, the `--from` of `indexify restore`
+    pub archive_url: String,
+    /// Whether a new archive was started from the latest snapshot
+    pub checkpointed: bool,
+    pub shipped_entries: usize,
+}
+
+/// The archive log entries are currently shipped to
+struct CurrentArchive {
+    key: String,
+    manifest: ArchiveManifest,
+    snapshot_log_id: Option<LogId<NodeId>>,
+    /// Index of the first log entry that isn't archived yet
+    next_index: u64,
+    checkpointed_at: Instant,
+}
+
+/// Writes the archives `restore` rebuilds a state directory from: the raft
+/// snapshot of the server, and the log entries committed after it as they
+/// are committed. A new archive is started from the latest snapshot every
+/// checkpoint interval. The manifest is written after the objects it lists,
+/// so an archive can be restored at any time.
+pub struct StateArchiver {
+    blob_storage: Arc<BlobStorage>,
+    key_prefix: String,
+    checkpoint_interval: Duration,
+    current: Mutex<Option<CurrentArchive>>,
+}
+
+impl StateArchiver {
+    pub fn new(config: &StateArchiveConfig, blob_storage: Arc<BlobStorage>) -> Self {
+        Self {
+            blob_storage,
+            key_prefix: config.key_prefix.trim_end_matches('/').to_string(),
+            checkpoint_interval: Duration::from_secs(config.checkpoint_interval_secs),
+            current: Mutex::new(None),
+        }
+    }
+
+    /// Ships the log entries committed since the previous call, after
+    /// starting a new archive when a checkpoint is due. Returns none until
+    /// the server has taken a snapshot.
+    pub async fn ship(&self, state_machine: &StateMachineStore) -> Result<Option<ShipReport>> {
+        let mut current = self.current.lock().await;
+        let mut checkpointed = false;
+        if current.as_ref().map_or(true, |archive| {
+            archive.checkpointed_at.elapsed() >= self.checkpoint_interval
+        }) {
+            checkpointed = self.checkpoint(state_machine, &mut *current).await?;
+        }
+        let Some(archive) = current.as_mut() else {
+            return Ok(None);
+        };
+
+        let mut log_store = LogStore {
+            db: state_machine.db.clone(),
+        };
+        let committed = log_store
+            .read_committed()
+            .await
+            .map_err(|e| anyhow!("unable to read the committed log id: {}", e))?;
+        let mut shipped_entries = 0;
+        while let Some(last_index) = committed
+            .map(|log_id| log_id.index)
+            .filter(|index| *index >= archive.next_index)
+        {
+            let end = last_index.min(archive.next_index + MAX_SEGMENT_ENTRIES - 1);
+            let entries = log_store
+                .try_get_log_entries(archive.next_index..=end)
+                .await
+                .map_err(|e| anyhow!("unable to read the log: {}", e))?;
+            if entries.first().map(|entry| entry.log_id.index) != Some(archive.next_index) {
+                //  The archive can't be continued without a gap, the next call
+                //  starts a new one from the snapshot that replaced the entries
+                let next_index = archive.next_index;
+                *current = None;
+                return Err(anyhow!(
+                    "log entries from {} were purged before they were archived",
+                    next_index
+                ));
+            }
+            let mut written_at = log_store
+                .get_log_times_(archive.next_index..=end)
+                .map_err(|e| anyhow!("unable to read the log: {}", e))?;
+            let entries: Vec<ArchivedLogEntry> = entries
+                .into_iter()
+                .map(|entry| ArchivedLogEntry {
+                    written_at: written_at.remove(&entry.log_id.index),
+                    entry,
+                })
+                .collect();
+            let name = format!("wal/{:020}", archive.next_index);
+            let segment = compress(&JsonEncoder::encode(&entries)?)?;
+            let segment = self.put(&archive.key, &name, segment).await?;
+            archive.manifest.wal_segments.push(segment);
+            archive.next_index += entries.len() as u64;
+            shipped_entries += entries.len();
+        }
+        if shipped_entries > 0 || checkpointed {
+            self.put_manifest(archive).await?;
+        }
+        Ok(Some(ShipReport {
+            archive_url: self.blob_storage.url_for_key(&archive.key),
+            checkpointed,
+            shipped_entries,
+        }))
+    }
+
+    /// Starts a new archive from the current snapshot of the server, unless
+    /// the current archive already starts from it. Returns whether a new
+    /// archive was started.
+    async fn checkpoint(
+        &self,
+        state_machine: &StateMachineStore,
+        current: &mut Option<CurrentArchive>,
+    ) -> Result<bool> {
+        let Some(snapshot) = state_machine
+            .get_current_snapshot_()
+            .map_err(|e| anyhow!("unable to read the snapshot: {}", e))?
+        else {
+            return Ok(false);
+        };
+        let snapshot_log_id = snapshot.meta.last_log_id;
+        if let Some(archive) = current
+            .as_mut()
+            .filter(|archive| archive.snapshot_log_id == snapshot_log_id)
+        {
+            archive.checkpointed_at = Instant::now();
+            return Ok(false);
+        }
+
+        let created_at = timestamp_secs();
+        let key = format!("{}/{}", self.key_prefix, created_at);
+        let snapshot_object = self
+            .put(
+                &key,
+                "snapshot",
+                compress(&JsonEncoder::encode(&snapshot)?)?,
+            )
+            .await?;
+        let version = StateVersion::current();
+        let archive = CurrentArchive {
+            key,
+            manifest: ArchiveManifest {
+                format_version: version.format_version,
+                server_version: version.server_version,
+                created_at,
+                snapshot: snapshot_object,
+                wal_segments: Vec::new(),
+            },
+            snapshot_log_id,
+            next_index: snapshot_log_id.map_or(0, |log_id| log_id.index + 1),
+            checkpointed_at: Instant::now(),
+        };
+        *current = Some(archive);
+        Ok(true)
+    }
+
+    async fn put(&self, key: &str, name: &str, data: Vec<u8>) -> Result<ArchivedObject> {
+        let object = ArchivedObject {
+            name: name.to_string(),
+            size_bytes: data.len() as u64,
+            sha256: format!("{:x}", Sha256::digest(&data)),
+        };
+        self.blob_storage
+            .put(
+                &format!("{}/{}", key, name),
+                futures::stream::iter(vec![Ok(Bytes::from(data))]),
+            )
+            .await?;
+        Ok(object)
+    }
+
+    async fn put_manifest(&self, archive: &CurrentArchive) -> Result<()> {
+        let manifest = Bytes::from(serde_json::to_vec(&archive.manifest)?);
+        self.blob_storage
+            .put(
+                &format!("{}/{}", archive.key, MANIFEST_NAME),
+                futures::stream::iter(vec![Ok(manifest)]),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+/// Compresses the way `write_compressed` does, so archived objects read like
+/// the files of a state directory
+fn compress(data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use indexify_internal_api::{ContentMetadata, ContentMetadataId, ExtractorDescription, Task};
+    use openraft::{
+        storage::{RaftSnapshotBuilder, RaftStateMachine},
+        CommittedLeaderId,
+        Entry,
+        EntryPayload,
+    };
+
+    use super::*;
+    use crate::{
+        blob_storage::{BlobStorageConfig, ContentReader, DiskStorageConfig},
+        server_config::{InvariantValidation, ServerConfig},
+        state::{
+            store::{
+                id_to_bin,
+                new_storage,
+                requests::{RequestPayload, StateMachineUpdateRequest},
+                restore,
+                StateMachineColumns,
+            },
+            TypeConfig,
+        },
+    };
+
+    fn log_id(index: u64) -> LogId<NodeId> {
+        LogId::new(CommittedLeaderId::new(1, 1), index)
+    }
+
+    fn task(id: &str) -> Task {
+        Task {
+            id: id.to_string(),
+            extractor: "extractor".to_string(),
+            extraction_policy_id: "policy".to_string(),
+            namespace: "default".to_string(),
+            content_metadata: ContentMetadata {
+                id: ContentMetadataId::new(&format!("content-{}", id)),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Applies the payloads as the next log entries and commits them, as if
+    /// they were written at 1000 plus their index
+    async fn commit(
+        log_store: &LogStore,
+        store: &Arc<StateMachineStore>,
+        first_index: u64,
+        payloads: Vec<RequestPayload>,
+    ) {
+        let entries: Vec<Entry<TypeConfig>> = payloads
+            .into_iter()
+            .enumerate()
+            .map(|(i, payload)| Entry {
+                log_id: log_id(first_index + i as u64),
+                payload: EntryPayload::Normal(StateMachineUpdateRequest {
+                    payload,
+                    new_state_changes: vec![],
+                    state_changes_processed: vec![],
+                }),
+            })
+            .collect();
+        for entry in &entries {
+            log_store
+                .db
+                .put_cf(
+                    log_store.logs(),
+                    id_to_bin(entry.log_id.index),
+                    JsonEncoder::encode(entry).unwrap(),
+                )
+                .unwrap();
+            log_store
+                .db
+                .put_cf(
+                    log_store.log_times(),
+                    id_to_bin(entry.log_id.index),
+                    JsonEncoder::encode(&(1000 + entry.log_id.index)).unwrap(),
+                )
+                .unwrap();
+        }
+        let last_log_id = entries.last().map(|entry| entry.log_id);
+        store.clone().apply(entries).await.unwrap();
+        log_store.set_committed_(&last_log_id).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ship_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let (log_store, store) = new_storage(
+            dir.path().join("db"),
+            dir.path().join("sm-blob"),
+            InvariantValidation::LogOnly,
+        )
+        .await
+        .unwrap();
+        let blob_storage = Arc::new(BlobStorage::new_with_config(BlobStorageConfig {
+            s3: None,
+            disk: Some(DiskStorageConfig {
+                path: dir.path().join("blobs").to_str().unwrap().to_string(),
+            }),
+        }));
+        let archiver = StateArchiver::new(
+            &StateArchiveConfig {
+                key_prefix: "snapshots".to_string(),
+                interval_secs: 60,
+                checkpoint_interval_secs: 3600,
+            },
+            blob_storage,
+        );
+
+        //  Nothing is archived before the first snapshot
+        assert!(archiver.ship(&store).await.unwrap().is_none());
+
+        commit(
+            &log_store,
+            &store,
+            1,
+            vec![
+                RequestPayload::RegisterExecutor {
+                    addr: "localhost:8950".to_string(),
+                    executor_id: "executor".to_string(),
+                    extractors: vec![ExtractorDescription {
+                        name: "extractor".to_string(),
+                        ..Default::default()
+                    }],
+                    cost_per_hour: None,
+                    capacity_weight: None,
+                    zone: None,
+                    max_payload_bytes: None,
+                    download_bytes_per_sec: None,
+                    gpu_devices: Vec::new(),
+                    graph_versions: Vec::new(),
+                    ts_secs: 0,
+                },
+                RequestPayload::CreateTasks {
+                    tasks: vec![task("task_1")],
+                    ts_secs: 0,
+                },
+            ],
+        )
+        .await;
+        store.clone().build_snapshot().await.unwrap();
+
+        let report = archiver.ship(&store).await.unwrap().unwrap();
+        assert!(report.checkpointed);
+        assert_eq!(report.shipped_entries, 0);
+
+        //  Entries committed after the snapshot are shipped as they come
+        commit(
+            &log_store,
+            &store,
+            3,
+            vec![RequestPayload::AssignTask {
+                assignments: HashMap::from([("task_1".to_string(), "executor".to_string())]),
+                assigned_at: None,
+                gpu_devices: HashMap::new(),
+                burst_tasks: HashSet::new(),
+                content_zones: HashMap::new(),
+            }],
+        )
+        .await;
+        let report = archiver.ship(&store).await.unwrap().unwrap();
+        assert!(!report.checkpointed);
+        assert_eq!(report.shipped_entries, 1);
+        commit(
+            &log_store,
+            &store,
+            4,
+            vec![RequestPayload::CreateTasks {
+                tasks: vec![task("task_2")],
+                ts_secs: 0,
+            }],
+        )
+        .await;
+        let report = archiver.ship(&store).await.unwrap().unwrap();
+        assert_eq!(report.shipped_entries, 1);
+        assert_eq!(
+            archiver
+                .ship(&store)
+                .await
+                .unwrap()
+                .unwrap()
+                .shipped_entries,
+            0
+        );
+
+        //  The archive restores to the snapshot followed by every shipped entry
+        let reader = ContentReader::new(Arc::new(ServerConfig::default()));
+        let state_dir = dir.path().join("restored");
+        let restored = restore::restore(&reader, &report.archive_url, &state_dir, None)
+            .await
+            .unwrap();
+        assert_eq!(restored.snapshot_log_id, Some(log_id(2)));
+        assert_eq!(restored.last_log_id, Some(log_id(4)));
+        assert_eq!(restored.replayed_entries, 2);
+
+        let (mut restored_log, restored_store) = new_storage(
+            state_dir.join("db"),
+            state_dir.join("sm-blob"),
+            InvariantValidation::LogOnly,
+        )
+        .await
+        .unwrap();
+        let entries = restored_log.try_get_log_entries(3..).await.unwrap();
+        restored_store.clone().apply(entries).await.unwrap();
+        for task_id in ["task_1", "task_2"] {
+            assert!(restored_store
+                .get_from_cf::<Task, _>(StateMachineColumns::Tasks, task_id)
+                .unwrap()
+                .is_some());
+        }
+        assert_eq!(
+            restored_store
+                .data
+                .indexify_state
+                .get_all_task_assignments(&restored_store.db)
+                .unwrap(),
+            HashMap::from([("task_1".to_string(), "executor".to_string())])
+        );
+
+        //  The times the entries were written are archived with them
+        let restored = restore::restore(
+            &reader,
+            &report.archive_url,
+            &dir.path().join("restored_until"),
+            Some(1003),
+        )
+        .await
+        .unwrap();
+        assert_eq!(restored.last_log_id, Some(log_id(3)));
+        assert_eq!(restored.skipped_entries, 1);
+    }
+}
@@ -0,0 +1,99 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use rocksdb::{
+    backup::{BackupEngine, BackupEngineOptions},
+    Env,
+    OptimisticTransactionDB,
+};
+
+use crate::server_config::BackupConfig;
+
+/// A backup of the state store
+#[derive(Debug, Clone)]
+pub struct BackupReport {
+    pub backup_id: u32,
+    /// Size of the files of the backup, including the files it shares with
+    /// earlier backups
+    pub size_bytes: u64,
+    pub num_files: u32,
+    pub elapsed: Duration,
+    /// Bytes per second the backup was limited to, none when it wasn't
+    pub rate_limit_bytes_per_sec: Option<u64>,
+}
+
+impl BackupReport {
+    /// Share of the rate limit the backup used over its whole run. Close to 1
+    /// means the backup was held back by the limit for most of its run.
+    pub fn rate_limit_utilization(&self) -> Option<f64> {
+        let rate_limit = self.rate_limit_bytes_per_sec?;
+        let throughput = self.size_bytes as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON);
+        Some((throughput / rate_limit as f64).min(1.0))
+    }
+}
+
+/// Backs `db` up to the directory of the config while it keeps taking writes.
+/// The memtables are flushed so the backup holds every committed write, then
+/// the live files are copied, at most at the rate limit, without holding any
+/// lock that writes wait for. Blocks until the backup is written, so it runs
+/// off the async runtime.
+pub fn create_backup(
+    db: &Arc<OptimisticTransactionDB>,
+    config: &BackupConfig,
+) -> Result<BackupReport> {
+    let mut options = BackupEngineOptions::new(&config.path)?;
+    options.set_max_background_operations(1);
+    let rate_limit_bytes_per_sec =
+        (config.rate_limit_bytes_per_sec > 0).then_some(config.rate_limit_bytes_per_sec);
+    if let Some(rate_limit) = rate_limit_bytes_per_sec {
+        options.set_backup_rate_limit(rate_limit);
+    }
+    let env = Env::new()?;
+    let mut engine = BackupEngine::open(&options, &env)?;
+
+    let start = Instant::now();
+    engine.create_new_backup_flush(db.as_ref(), true)?;
+    let elapsed = start.elapsed();
+    engine.purge_old_backups(config.keep.max(1))?;
+
+    let info = engine
+        .get_backup_info()
+        .into_iter()
+        .max_by_key(|info| info.backup_id)
+        .ok_or_else(|| anyhow!("backup missing from {}", config.path))?;
+    Ok(BackupReport {
+        backup_id: info.backup_id,
+        size_bytes: info.size,
+        num_files: info.num_files,
+        elapsed,
+        rate_limit_bytes_per_sec,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_utilization() {
+        let report = BackupReport {
+            backup_id: 1,
+            size_bytes: 100 * 1024 * 1024,
+            num_files: 10,
+            elapsed: Duration::from_secs(10),
+            rate_limit_bytes_per_sec: Some(20 * 1024 * 1024),
+        };
+        assert_eq!(report.rate_limit_utilization(), Some(0.5));
+        assert_eq!(
+            BackupReport {
+                rate_limit_bytes_per_sec: None,
+                ..report
+            }
+            .rate_limit_utilization(),
+            None
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use rocksdb::OptimisticTransactionDB;
+
+use super::StateMachineColumns;
+
+/// Property with the size of the files of a column family
+const SST_FILES_SIZE_PROPERTY: &str = "rocksdb.total-sst-files-size";
+
+/// Keys deleted from a column family since it was last compacted
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeletedRange {
+    pub start: Vec<u8>,
+    /// Last deleted key, the range includes it
+    pub end: Vec<u8>,
+    pub deletions: u64,
+}
+
+impl DeletedRange {
+    fn new(key: &[u8]) -> Self {
+        Self {
+            start: key.to_vec(),
+            end: key.to_vec(),
+            deletions: 1,
+        }
+    }
+
+    fn add(&mut self, key: &[u8]) {
+        if key < self.start.as_slice() {
+            self.start = key.to_vec();
+        }
+        if key > self.end.as_slice() {
+            self.end = key.to_vec();
+        }
+        self.deletions += 1;
+    }
+}
+
+/// Ranges of keys the state machine deleted by column family. Deleted keys
+/// stay in the files as tombstones that iterators step over until a
+/// compaction drops them, which can take long on column families that are
+/// rarely written.
+#[derive(Debug, Default)]
+pub struct DeletionTracker {
+    ranges: Mutex<BTreeMap<String, DeletedRange>>,
+}
+
+impl DeletionTracker {
+    pub fn record(&self, column: &StateMachineColumns, key: impl AsRef<[u8]>) {
+        let key = key.as_ref();
+        self.ranges
+            .lock()
+            .unwrap()
+            .entry(column.to_string())
+            .and_modify(|range| range.add(key))
+            .or_insert_with(|| DeletedRange::new(key));
+    }
+
+    /// Takes the ranges of the column families with at least `min_deletions`
+    /// deletions, the others keep counting
+    pub fn take(&self, min_deletions: u64) -> Vec<(String, DeletedRange)> {
+        let mut ranges = self.ranges.lock().unwrap();
+        let columns: Vec<String> = ranges
+            .iter()
+            .filter(|(_, range)| range.deletions >= min_deletions)
+            .map(|(column, _)| column.clone())
+            .collect();
+        columns
+            .into_iter()
+            .filter_map(|column| ranges.remove_entry(&column))
+            .collect()
+    }
+}
+
+/// What a compaction of deleted keys got back
+#[derive(Debug, Clone)]
+pub struct CompactionReport {
+    pub column: String,
+    pub deletions: u64,
+    /// Bytes the files of the column family shrank by
+    pub reclaimed_bytes: u64,
+    pub elapsed: Duration,
+}
+
+/// Compacts the files of `column` that overlap `range`. Blocks until the
+/// compaction is done.
+pub fn compact_deleted_range(
+    db: &Arc<OptimisticTransactionDB>,
+    column: &str,
+    range: &DeletedRange,
+) -> Result<CompactionReport> {
+    let cf = db
+        .cf_handle(column)
+        .ok_or_else(|| anyhow!("column family {} not found", column))?;
+    let size_before = db.property_int_value_cf(cf, SST_FILES_SIZE_PROPERTY)?;
+    let start = Instant::now();
+    db.compact_range_cf(cf, Some(range.start.as_slice()), Some(range.end.as_slice()));
+    let size_after = db.property_int_value_cf(cf, SST_FILES_SIZE_PROPERTY)?;
+    Ok(CompactionReport {
+        column: column.to_string(),
+        deletions: range.deletions,
+        reclaimed_bytes: size_before
+            .unwrap_or_default()
+            .saturating_sub(size_after.unwrap_or_default()),
+        elapsed: start.elapsed(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deletion_tracker() {
+        let tracker = DeletionTracker::default();
+        tracker.record(&StateMachineColumns::ContentTable, "b::v1");
+        tracker.record(&StateMachineColumns::ContentTable, "a::v1");
+        tracker.record(&StateMachineColumns::ContentTable, "c::v2");
+        tracker.record(&StateMachineColumns::ContentContext, "a");
+
+        let taken = tracker.take(2);
+        assert_eq!(
+            taken,
+            vec![(
+                StateMachineColumns::ContentTable.to_string(),
+                DeletedRange {
+                    start: b"a::v1".to_vec(),
+                    end: b"c::v2".to_vec(),
+                    deletions: 3,
+                },
+            )]
+        );
+
+        //  Column families under the threshold keep their range
+        assert!(tracker.take(2).is_empty());
+        assert_eq!(tracker.take(1)[0].1.deletions, 1);
+    }
+}
@@ -0,0 +1,354 @@
+//! Applies randomized interleavings of state machine requests and checks the
+//! invariants between the task tables and the in-memory indexes after every
+//! request.
+//!
+//! Requests are planned against the state that has been applied so far, the
+//! way the coordinator plans them, and are applied in a random order. That
+//! reproduces races like an allocation planned for an executor that is
+//! removed before the allocation is applied. The invariants have to hold
+//! whether invariant violations only get logged or the requests get dropped.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use indexify_internal_api as internal_api;
+use internal_api::{ContentMetadata, ContentMetadataId, ExtractorDescription, Task, TaskOutcome};
+use rand::{rngs::StdRng, seq::IteratorRandom, Rng, SeedableRng};
+
+use super::{
+    new_storage,
+    requests::{RequestPayload, StateMachineUpdateRequest},
+    ExecutorId,
+    StateMachineColumns,
+    StateMachineStore,
+    TaskId,
+};
+use crate::{server_config::InvariantValidation, utils::timestamp_secs};
+
+const SEEDS: u64 = 32;
+const STEPS: usize = 200;
+const EXTRACTOR: &str = "extractor";
+
+struct Fuzzer {
+    store: Arc<StateMachineStore>,
+    rng: StdRng,
+    in_flight: Vec<RequestPayload>,
+    next_id: usize,
+}
+
+struct Snapshot {
+    executors: HashSet<ExecutorId>,
+    tasks: HashMap<TaskId, Task>,
+    assignments: Vec<(ExecutorId, HashSet<TaskId>)>,
+}
+
+impl Snapshot {
+    fn assigned_executor(&self, task_id: &TaskId) -> Option<ExecutorId> {
+        self.assignments
+            .iter()
+            .find(|(_, task_ids)| task_ids.contains(task_id))
+            .map(|(executor_id, _)| executor_id.clone())
+    }
+}
+
+impl Fuzzer {
+    fn snapshot(&self) -> Snapshot {
+        let state = &self.store.data.indexify_state;
+        let db = &self.store.db;
+        let executors = state
+            .get_all_rows_from_cf::<internal_api::ExecutorMetadata>(
+                StateMachineColumns::Executors,
+                db,
+            )
+            .unwrap()
+            .into_iter()
+            .map(|(executor_id, _)| executor_id)
+            .collect();
+        let tasks = state
+            .get_all_rows_from_cf::<Task>(StateMachineColumns::Tasks, db)
+            .unwrap()
+            .into_iter()
+            .collect();
+        let assignments = state
+            .get_all_rows_from_cf::<HashSet<TaskId>>(StateMachineColumns::TaskAssignments, db)
+            .unwrap();
+        Snapshot {
+            executors,
+            tasks,
+            assignments,
+        }
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}-{}", prefix, self.next_id)
+    }
+
+    /// Plans a request the coordinator could make in the current state
+    fn plan(&mut self) -> Option<RequestPayload> {
+        let snapshot = self.snapshot();
+        match self.rng.gen_range(0..8) {
+            0 => Some(RequestPayload::RegisterExecutor {
+                addr: "localhost:8950".to_string(),
+                executor_id: self.next_id("executor"),
+                extractors: vec![ExtractorDescription {
+                    name: EXTRACTOR.to_string(),
+                    ..Default::default()
+                }],
+                cost_per_hour: None,
+                capacity_weight: None,
+                zone: None,
+                max_payload_bytes: None,
+                download_bytes_per_sec: None,
+                gpu_devices: Vec::new(),
+                graph_versions: Vec::new(),
+                ts_secs: 0,
+            }),
+            1 => {
+                let executor_id = snapshot.executors.into_iter().choose(&mut self.rng)?;
+                Some(RequestPayload::RemoveExecutor {
+                    executor_id,
+                    graceful: false,
+                    ts_secs: timestamp_secs(),
+                })
+            }
+            2 => {
+                let id = self.next_id("task");
+                Some(RequestPayload::CreateTasks {
+                    tasks: vec![Task {
+                        id: id.clone(),
+                        extractor: EXTRACTOR.to_string(),
+                        extraction_policy_id: "policy".to_string(),
+                        content_metadata: ContentMetadata {
+                            id: ContentMetadataId::new(&format!("content-{}", id)),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    }],
+                    ts_secs: timestamp_secs(),
+                })
+            }
+            3 => {
+                let task_id = self
+                    .store
+                    .data
+                    .indexify_state
+                    .get_unassigned_tasks()
+                    .into_iter()
+                    .choose(&mut self.rng)?;
+                let executor_id = snapshot.executors.into_iter().choose(&mut self.rng)?;
+                Some(RequestPayload::AssignTask {
+                    assignments: HashMap::from([(task_id, executor_id)]),
+                    assigned_at: Some(SystemTime::now()),
+                    gpu_devices: HashMap::new(),
+                    burst_tasks: HashSet::new(),
+                    content_zones: HashMap::new(),
+                })
+            }
+            4 => {
+                //  The executor finishes a task it was assigned
+                let (executor_id, task_ids) = snapshot.assignments.iter().choose(&mut self.rng)?;
+                let task_id = task_ids.iter().choose(&mut self.rng)?;
+                let mut task = snapshot.tasks.get(task_id)?.clone();
+                task.outcome = if self.rng.gen_bool(0.5) {
+                    TaskOutcome::Success
+                } else {
+                    TaskOutcome::Failed
+                };
+                Some(RequestPayload::UpdateTask {
+                    task,
+                    executor_id: Some(executor_id.clone()),
+                    update_time: SystemTime::now(),
+                })
+            }
+            5 => {
+                let (finished_task_assignments, unallocated_tasks) = self
+                    .store
+                    .data
+                    .indexify_state
+                    .find_orphaned_assignments(&self.store.db)
+                    .unwrap();
+                Some(RequestPayload::ReapOrphanedAssignments {
+                    finished_task_assignments,
+                    unallocated_tasks,
+                    ts_secs: timestamp_secs(),
+                })
+            }
+            6 => {
+                //  An assigned task is moved to another executor
+                let (_, task_ids) = snapshot.assignments.iter().choose(&mut self.rng)?;
+                let task_id = task_ids.iter().choose(&mut self.rng)?.clone();
+                let executor_id = snapshot.executors.into_iter().choose(&mut self.rng)?;
+                Some(RequestPayload::AssignTask {
+                    assignments: HashMap::from([(task_id, executor_id)]),
+                    assigned_at: Some(SystemTime::now()),
+                    gpu_devices: HashMap::new(),
+                    burst_tasks: HashSet::new(),
+                    content_zones: HashMap::new(),
+                })
+            }
+            _ => {
+                //  The task is cancelled
+                let task_id = snapshot
+                    .tasks
+                    .values()
+                    .filter(|task| !task.terminal_state())
+                    .map(|task| task.id.clone())
+                    .choose(&mut self.rng)?;
+                let mut task = snapshot.tasks.get(&task_id)?.clone();
+                task.outcome = TaskOutcome::Failed;
+                Some(RequestPayload::UpdateTask {
+                    executor_id: snapshot.assigned_executor(&task_id),
+                    task,
+                    update_time: SystemTime::now(),
+                })
+            }
+        }
+    }
+
+    fn apply_one(&mut self) {
+        let index = self.rng.gen_range(0..self.in_flight.len());
+        let payload = self.in_flight.swap_remove(index);
+        let request = StateMachineUpdateRequest {
+            payload: payload.clone(),
+            new_state_changes: vec![],
+            state_changes_processed: vec![],
+        };
+        self.store
+            .data
+            .indexify_state
+            .apply_state_machine_updates(request, &self.store.db)
+            .unwrap_or_else(|e| panic!("applying {:?} failed: {}", payload, e));
+        self.check_invariants(&payload);
+    }
+
+    fn check_invariants(&self, applied: &RequestPayload) {
+        let state = &self.store.data.indexify_state;
+        let snapshot = self.snapshot();
+
+        let mut assigned = HashSet::new();
+        for (executor_id, task_ids) in &snapshot.assignments {
+            if task_ids.is_empty() {
+                continue;
+            }
+            assert!(
+                snapshot.executors.contains(executor_id),
+                "tasks {:?} are assigned to executor {} which is not registered, after {:?}",
+                task_ids,
+                executor_id,
+                applied
+            );
+            for task_id in task_ids {
+                let task = snapshot.tasks.get(task_id).unwrap_or_else(|| {
+                    panic!(
+                        "assignment to {} points at missing task {}, after {:?}",
+                        executor_id, task_id, applied
+                    )
+                });
+                assert!(
+                    !task.terminal_state(),
+                    "finished task {} is still assigned to {}, after {:?}",
+                    task_id,
+                    executor_id,
+                    applied
+                );
+                assert!(
+                    assigned.insert(task_id.clone()),
+                    "task {} is assigned to more than one executor, after {:?}",
+                    task_id,
+                    applied
+                );
+            }
+        }
+
+        let expected_task_executors: HashMap<TaskId, ExecutorId> = snapshot
+            .assignments
+            .iter()
+            .flat_map(|(executor_id, task_ids)| {
+                task_ids
+                    .iter()
+                    .map(move |task_id| (task_id.clone(), executor_id.clone()))
+            })
+            .collect();
+        assert_eq!(
+            state.task_executors.inner(),
+            expected_task_executors,
+            "task executors index diverged, after {:?}",
+            applied
+        );
+
+        let expected_unassigned: HashSet<TaskId> = snapshot
+            .tasks
+            .values()
+            .filter(|task| !task.terminal_state() && !assigned.contains(&task.id))
+            .map(|task| task.id.clone())
+            .collect();
+        assert_eq!(
+            state.get_unassigned_tasks(),
+            expected_unassigned,
+            "unassigned tasks index diverged, after {:?}",
+            applied
+        );
+
+        for executor_id in &snapshot.executors {
+            let running = snapshot
+                .assignments
+                .iter()
+                .find(|(id, _)| id == executor_id)
+                .map_or(0, |(_, task_ids)| task_ids.len() as u64);
+            assert_eq!(
+                state.executor_running_task_count.get(executor_id),
+                Some(running),
+                "running task count of {} diverged, after {:?}",
+                executor_id,
+                applied
+            );
+        }
+    }
+}
+
+async fn fuzz(seed: u64, invariant_validation: InvariantValidation) {
+    let dir = tempfile::tempdir().unwrap();
+    let (_log_store, store) = new_storage(
+        dir.path().join("db"),
+        dir.path().join("snapshot"),
+        invariant_validation,
+    )
+    .await
+    .unwrap();
+    let mut fuzzer = Fuzzer {
+        store,
+        rng: StdRng::seed_from_u64(seed),
+        in_flight: Vec::new(),
+        next_id: 0,
+    };
+    for _ in 0..STEPS {
+        if fuzzer.in_flight.is_empty() || fuzzer.rng.gen_bool(0.5) {
+            if let Some(payload) = fuzzer.plan() {
+                fuzzer.in_flight.push(payload);
+            }
+        } else {
+            fuzzer.apply_one();
+        }
+    }
+    while !fuzzer.in_flight.is_empty() {
+        fuzzer.apply_one();
+    }
+}
+
+#[tokio::test]
+async fn test_randomized_request_orderings() {
+    for seed in 0..SEEDS {
+        fuzz(seed, InvariantValidation::LogOnly).await;
+    }
+}
+
+#[tokio::test]
+async fn test_randomized_request_orderings_strict() {
+    for seed in 0..SEEDS {
+        fuzz(seed, InvariantValidation::Strict).await;
+    }
+}
@@ -3,9 +3,14 @@ use std::{
     fmt::Debug,
     fs::{self, File},
     io::{BufReader, Cursor, Read, Write},
-    ops::RangeBounds,
+    ops::{RangeBounds, RangeInclusive},
     path::{Path, PathBuf},
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+        Mutex,
+    },
+    time::{Duration, Instant, SystemTime},
 };
 
 use anyhow::{anyhow, Result};
@@ -18,6 +23,9 @@ use indexify_internal_api::{
     NamespaceName,
     StateChange,
     StructuredDataSchema,
+    StuckContent,
+    TaskTransition,
+    ZonePlacement,
 };
 use openraft::{
     storage::{LogFlushed, LogState, RaftLogStorage, RaftStateMachine, Snapshot},
@@ -37,7 +45,9 @@ use openraft::{
     StoredMembership,
     Vote,
 };
+use opentelemetry::KeyValue;
 use rocksdb::{
+    Cache,
     ColumnFamily,
     ColumnFamilyDescriptor,
     Direction,
@@ -48,7 +58,7 @@ use rocksdb::{
 use serde::{de::DeserializeOwned, Deserialize};
 use strum::{AsRefStr, IntoEnumIterator};
 use thiserror::Error;
-use tokio::sync::{broadcast, RwLock};
+use tokio::sync::{broadcast, Notify, RwLock};
 use tracing::debug;
 
 type Node = BasicNode;
@@ -58,12 +68,19 @@ use indexify_internal_api::StateChangeId;
 use self::{
     requests::RequestPayload,
     serializer::{JsonEncode, JsonEncoder},
-    state_machine_objects::{IndexifyState, IndexifyStateSnapshot},
+    state_machine_objects::{
+        IndexifyIndexesSnapshot,
+        IndexifyState,
+        IndexifyStateSnapshot,
+        PendingWork,
+    },
+    tuning::{log_read_options, scan_read_options},
 };
-use super::{typ, NodeId, SnapshotData, TypeConfig};
+use super::{notifier::ChangeNotifier, typ, NodeId, SnapshotData, TypeConfig};
 use crate::{
     metrics::{state_machine::Metrics, Timer},
-    utils::OptionInspectNone,
+    server_config::{BackupConfig, InvariantValidation},
+    utils::{timestamp_secs, OptionInspectNone},
 };
 
 pub type TaskId = String;
@@ -77,9 +94,20 @@ pub type ContentType = String;
 pub type ExtractionGraphId = String;
 pub type SchemaId = String;
 
+pub mod archive;
+pub mod backup;
+pub mod compaction;
+pub mod read_only;
 pub mod requests;
+pub mod restore;
 pub mod serializer;
+pub mod sharded;
 pub mod state_machine_objects;
+pub mod state_version;
+pub mod tuning;
+
+#[cfg(test)]
+mod fuzz_tests;
 
 #[derive(Error, Debug)]
 pub enum StateMachineError {
@@ -112,6 +140,24 @@ pub enum StateMachineColumns {
     ExtractionPoliciesAppliedOnContent, //  ContentId -> Vec<ExtractionPolicyIds>
     CoordinatorAddress,                 //  NodeId -> Coordinator address
     ExtractionGraphs,                   //  ExtractionGraphId -> ExtractionGraph
+    ContentContext,                     //  RootContentId -> HashMap<String, Value>
+    ExtractionGraphHistory,             //  ExtractionGraphId -> Vec<ExtractionGraphVersion>
+    SchedulerSettings,                  //  Scheduler settings and maintenance mode
+    Metering,                           //  Namespace and period -> MeteringRecord
+    TaskAssignmentTimes,                //  TaskId -> Time the task was assigned
+    ExtractionGraphUsage,               //  ExtractionGraphId and version -> ExtractionGraphUsage
+    TaskTransitions,                    //  TaskId -> Vec<TaskTransition>
+    Replays,                            //  ReplayId -> Replay
+    ReplayTasks,                        /*  TaskId -> ReplayId of the unfinished replay of the
+                                         * task */
+    GraphTemplates,      //  Template name -> GraphTemplate
+    ContentReplicas,     //  ContentId -> ContentReplica
+    PendingReplications, //  ContentId of content whose replica may be out of date
+    NamespaceReplays,    //  Namespace and ReplayId of the replays of a namespace
+    RunningReplayTasks,  /*  ContentId and extraction policy name -> TaskId of the
+                          * unfinished replay task */
+    CancelTasksJobs, //  JobId -> CancelTasksJob
+    ContentZones,    //  RootContentId -> HashMap<Zone, tasks of the content given to the zone>
 }
 
 impl StateMachineColumns {
@@ -137,6 +183,31 @@ pub struct StoredSnapshot {
     pub data: Vec<u8>,
 }
 
+/// The in-memory indexes together with the raft log position they reflect
+#[derive(serde::Serialize, Deserialize, Debug)]
+pub struct StoredIndexesSnapshot {
+    pub last_applied_log_id: Option<LogId<NodeId>>,
+
+    pub indexes: IndexifyIndexesSnapshot,
+}
+
+/// Number of applied log entries between two snapshots of the in-memory
+/// indexes
+const INDEXES_SNAPSHOT_INTERVAL: u64 = 1000;
+
+/// Time without applied log entries after which the indexes are snapshotted
+/// if any entry was applied since their last snapshot
+const INDEXES_SNAPSHOT_QUIET_PERIOD: Duration = Duration::from_secs(10);
+
+//  Keys of the store column family written in the transaction of every
+//  applied log entry, so they always match the state machine columns
+const LAST_APPLIED_LOG_ID_KEY: &[u8] = b"last_applied_log_id";
+const LAST_MEMBERSHIP_KEY: &[u8] = b"last_membership";
+
+//  Key of the latest snapshot of the in-memory indexes, written on its own
+//  with the log id it was taken at
+const INDEXES_SNAPSHOT_KEY: &[u8] = b"indexes_snapshot";
+
 pub struct StateMachineData {
     pub last_applied_log_id: RwLock<Option<LogId<NodeId>>>,
 
@@ -145,7 +216,8 @@ pub struct StateMachineData {
     /// State built from applying the raft log
     pub indexify_state: IndexifyState,
 
-    state_change_tx: Arc<tokio::sync::watch::Sender<StateChange>>,
+    /// Wakes up the scheduler when state changes are applied
+    pub state_changes: Arc<ChangeNotifier<StateChange>>,
 
     gc_tasks_tx: broadcast::Sender<indexify_internal_api::GarbageCollectionTask>,
 }
@@ -157,10 +229,21 @@ pub struct StateMachineStore {
 
     db: Arc<OptimisticTransactionDB>,
 
-    pub state_change_rx: tokio::sync::watch::Receiver<StateChange>,
-
     snapshot_file_path: PathBuf,
 
+    /// Log entries applied since the indexes were last written to disk
+    entries_since_indexes_snapshot: Mutex<u64>,
+
+    /// Held while a log entry is applied, so the indexes are snapshotted
+    /// together with the id of the last entry they reflect
+    applying: tokio::sync::Mutex<()>,
+
+    /// Wakes up the snapshotting of the indexes when log entries are applied
+    indexes_applied: Arc<Notify>,
+
+    /// Set while a backup of the database is written
+    backup_running: AtomicBool,
+
     metrics: Metrics,
 }
 
@@ -168,32 +251,228 @@ impl StateMachineStore {
     async fn new(
         db: Arc<OptimisticTransactionDB>,
         snapshot_file_path: PathBuf,
+        invariant_validation: InvariantValidation,
     ) -> Result<StateMachineStore, StorageError<NodeId>> {
-        let (tx, rx) = tokio::sync::watch::channel(StateChange::default());
         let (gc_tasks_tx, _) = broadcast::channel(100);
         let sm = Self {
             data: StateMachineData {
                 last_applied_log_id: RwLock::new(None),
                 last_membership: RwLock::new(StoredMembership::default()),
-                indexify_state: IndexifyState::default(),
-                state_change_tx: Arc::new(tx),
+                indexify_state: IndexifyState {
+                    invariant_validation,
+                    ..Default::default()
+                },
+                state_changes: Arc::new(ChangeNotifier::new(StateChange::default())),
                 gc_tasks_tx,
             },
             snapshot_idx: Mutex::new(0),
             db,
-            state_change_rx: rx,
             snapshot_file_path,
+            entries_since_indexes_snapshot: Mutex::new(0),
+            applying: tokio::sync::Mutex::new(()),
+            indexes_applied: Arc::new(Notify::new()),
+            backup_running: AtomicBool::new(false),
             metrics: Metrics::new(),
         };
 
-        let snapshot = sm.get_current_snapshot_()?;
-        if let Some(snap) = snapshot {
-            sm.update_state_machine_(snap).await?;
+        //  The state machine columns are committed together with the id of the
+        //  last log entry applied to them, so the database picks up where it
+        //  stopped and nothing is applied twice. The indexes come from their
+        //  snapshot when it was taken at that entry. Fencing and some of the
+        //  index updates of an entry happen together with its writes, so the
+        //  entries after an older snapshot can't be replayed onto the indexes
+        //  alone and they are rebuilt from the columns instead. Databases
+        //  written before the applied log id was recorded start over from the
+        //  full snapshot.
+        match sm.get_applied_state_()? {
+            Some((last_applied_log_id, last_membership)) => {
+                {
+                    let mut guard = sm.data.last_applied_log_id.write().await;
+                    *guard = Some(last_applied_log_id);
+                }
+                {
+                    let mut guard = sm.data.last_membership.write().await;
+                    *guard = last_membership;
+                }
+                match sm.get_indexes_snapshot_()? {
+                    Some(snapshot) if snapshot.last_applied_log_id == Some(last_applied_log_id) => {
+                        debug!(
+                            "restoring in-memory indexes at log id {}",
+                            last_applied_log_id
+                        );
+                        sm.data
+                            .indexify_state
+                            .install_indexes_snapshot(snapshot.indexes);
+                    }
+                    _ => {
+                        debug!(
+                            "rebuilding in-memory indexes at log id {}",
+                            last_applied_log_id
+                        );
+                        sm.data
+                            .indexify_state
+                            .rebuild_indexes(&sm.db)
+                            .map_err(|e| StorageError::IO {
+                                source: StorageIOError::read(&e),
+                            })?;
+                    }
+                }
+            }
+            None => {
+                let snapshot = sm.get_current_snapshot_()?;
+                if let Some(snap) = snapshot {
+                    sm.update_state_machine_(snap).await?;
+                }
+            }
         }
 
         Ok(sm)
     }
 
+    fn store_cf(&self) -> &ColumnFamily {
+        self.db.cf_handle("store").unwrap()
+    }
+
+    fn get_store_value_<T: DeserializeOwned>(&self, key: &[u8]) -> StorageResult<Option<T>> {
+        self.db
+            .get_cf(self.store_cf(), key)
+            .map_err(|e| StorageError::IO {
+                source: StorageIOError::read(&e),
+            })?
+            .map(|data| JsonEncoder::decode(&data))
+            .transpose()
+            .map_err(|e| StorageError::IO {
+                source: StorageIOError::read(&e),
+            })
+    }
+
+    /// The last applied log entry and membership, as committed with the state
+    /// machine columns
+    fn get_applied_state_(
+        &self,
+    ) -> StorageResult<Option<(LogId<NodeId>, StoredMembership<NodeId, Node>)>> {
+        let Some(last_applied_log_id) = self.get_store_value_(LAST_APPLIED_LOG_ID_KEY)? else {
+            return Ok(None);
+        };
+        let last_membership = self
+            .get_store_value_(LAST_MEMBERSHIP_KEY)?
+            .unwrap_or_default();
+        Ok(Some((last_applied_log_id, last_membership)))
+    }
+
+    fn get_indexes_snapshot_(&self) -> StorageResult<Option<StoredIndexesSnapshot>> {
+        let Some(compressed) = self
+            .db
+            .get_cf(self.store_cf(), INDEXES_SNAPSHOT_KEY)
+            .map_err(|e| StorageError::IO {
+                source: StorageIOError::read(&e),
+            })?
+        else {
+            return Ok(None);
+        };
+        let mut data = Vec::new();
+        ZlibDecoder::new(compressed.as_slice())
+            .read_to_end(&mut data)
+            .map_err(|e| StorageError::IO {
+                source: StorageIOError::read(&e),
+            })?;
+        match JsonEncoder::decode(&data) {
+            Ok(snapshot) => Ok(Some(snapshot)),
+            Err(e) => {
+                //  Written by an older version, rebuild the indexes instead
+                tracing::warn!("ignoring unreadable indexes snapshot: {}", e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Records `log_id` as applied in `txn`, along with the membership
+    fn record_applied_(
+        &self,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        log_id: LogId<NodeId>,
+        membership: &StoredMembership<NodeId, Node>,
+    ) -> Result<(), StateMachineError> {
+        txn.put_cf(
+            self.store_cf(),
+            LAST_APPLIED_LOG_ID_KEY,
+            JsonEncoder::encode(&log_id)?,
+        )
+        .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        txn.put_cf(
+            self.store_cf(),
+            LAST_MEMBERSHIP_KEY,
+            JsonEncoder::encode(membership)?,
+        )
+        .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Records a log entry that doesn't change the state machine columns
+    fn record_applied_entry_(
+        &self,
+        log_id: LogId<NodeId>,
+        membership: &StoredMembership<NodeId, Node>,
+    ) -> Result<(), StateMachineError> {
+        let txn = self.db.transaction();
+        self.record_applied_(&txn, log_id, membership)?;
+        txn.commit()
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))
+    }
+
+    /// Writes the in-memory indexes with the id of the last log entry they
+    /// reflect. They are copied between two applied entries, the copy is
+    /// encoded and written without holding up the entries applied after it.
+    async fn write_indexes_snapshot_(&self) -> Result<(), StateMachineError> {
+        let snapshot = {
+            let _applying = self.applying.lock().await;
+            *self.entries_since_indexes_snapshot.lock().unwrap() = 0;
+            StoredIndexesSnapshot {
+                last_applied_log_id: *self.data.last_applied_log_id.read().await,
+                indexes: self.data.indexify_state.build_indexes_snapshot(),
+            }
+        };
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(&JsonEncoder::encode(&snapshot)?)
+            .map_err(|e| StateMachineError::SerializationError(e.to_string()))?;
+        let compressed = encoder
+            .finish()
+            .map_err(|e| StateMachineError::SerializationError(e.to_string()))?;
+        self.db
+            .put_cf(self.store_cf(), INDEXES_SNAPSHOT_KEY, compressed)
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))
+    }
+
+    /// Snapshots the indexes in the background every
+    /// `INDEXES_SNAPSHOT_INTERVAL` applied log entries, and once applying
+    /// goes quiet after an entry, until the store is dropped
+    fn spawn_indexes_snapshots(sm: &Arc<StateMachineStore>) {
+        let store = Arc::downgrade(sm);
+        tokio::spawn(async move {
+            loop {
+                let Some(indexes_applied) = store.upgrade().map(|sm| sm.indexes_applied.clone())
+                else {
+                    return;
+                };
+                let quiet =
+                    tokio::time::timeout(INDEXES_SNAPSHOT_QUIET_PERIOD, indexes_applied.notified())
+                        .await
+                        .is_err();
+                let Some(sm) = store.upgrade() else {
+                    return;
+                };
+                let entries = *sm.entries_since_indexes_snapshot.lock().unwrap();
+                if entries >= INDEXES_SNAPSHOT_INTERVAL || (quiet && entries > 0) {
+                    if let Err(e) = sm.write_indexes_snapshot_().await {
+                        tracing::error!("failed to snapshot the in-memory indexes: {}", e);
+                    }
+                }
+            }
+        });
+    }
+
     /// This method is used to update the in-memory state machine when a new
     /// state machine is provided via the InstallSnapshot RPC
     async fn update_state_machine_(
@@ -214,7 +493,19 @@ impl StateMachineStore {
 
         self.data
             .indexify_state
-            .install_snapshot(&self.db, indexify_state_snapshot)
+            .install_snapshot(&self.db, indexify_state_snapshot, |txn| {
+                match snapshot.meta.last_log_id {
+                    Some(log_id) => {
+                        self.record_applied_(txn, log_id, &snapshot.meta.last_membership)
+                    }
+                    None => Ok(()),
+                }
+            })
+            .map_err(|e| StorageError::IO {
+                source: StorageIOError::write(&e),
+            })?;
+        self.write_indexes_snapshot_()
+            .await
             .map_err(|e| StorageError::IO {
                 source: StorageIOError::write(&e),
             })?;
@@ -228,17 +519,8 @@ impl StateMachineStore {
             return Ok(None);
         }
 
-        let file = File::open(&self.snapshot_file_path).map_err(|e| StorageError::IO {
-            source: StorageIOError::read(&e),
-        })?;
-
-        //  Decompress the data with ZLib decoder
-        let buf_reader = BufReader::new(file);
-        let mut decoder = ZlibDecoder::new(buf_reader);
-        let mut decompressed_data = Vec::new();
-        decoder
-            .read_to_end(&mut decompressed_data)
-            .map_err(|e| StorageError::IO {
+        let decompressed_data =
+            read_compressed(&self.snapshot_file_path).map_err(|e| StorageError::IO {
                 source: StorageIOError::read(&e),
             })?;
 
@@ -259,35 +541,10 @@ impl StateMachineStore {
         })?;
         let uncompressed_size = serialized_data.len();
 
-        //  Compress the serialized meta and data using Zlib
-        let mut encoder =
-            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
-        encoder
-            .write_all(&serialized_data)
-            .map_err(|e| StorageError::IO {
-                source: StorageIOError::write_snapshot(Some(snap.meta.signature()), &e),
-            })?;
-        let compressed_data = encoder.finish().map_err(|e| StorageError::IO {
-            source: StorageIOError::write_snapshot(Some(snap.meta.signature()), &e),
-        })?;
-        let compressed_size = compressed_data.len();
-
-        //  Create a temp file, write to temp file and then swap inode pointers
-        let temp_file_path = self.snapshot_file_path.with_extension("tmp");
-        let mut temp_file = File::create(&temp_file_path).map_err(|e| StorageError::IO {
-            source: StorageIOError::write_snapshot(Some(snap.meta.signature()), &e),
-        })?;
-        temp_file
-            .write_all(&compressed_data)
+        let compressed_size = write_compressed(&self.snapshot_file_path, &serialized_data)
             .map_err(|e| StorageError::IO {
                 source: StorageIOError::write_snapshot(Some(snap.meta.signature()), &e),
             })?;
-        temp_file.sync_all().map_err(|e| StorageError::IO {
-            source: StorageIOError::write_snapshot(Some(snap.meta.signature()), &e),
-        })?;
-        fs::rename(&temp_file_path, &self.snapshot_file_path).map_err(|e| StorageError::IO {
-            source: StorageIOError::write_snapshot(Some(snap.meta.signature()), &e),
-        })?;
 
         // Calculate compression ratio or percentage
         let compression_ratio = compressed_size as f64 / uncompressed_size as f64;
@@ -358,6 +615,119 @@ impl StateMachineStore {
             .map_err(|e| anyhow::anyhow!("Failed to get task assignments: {}", e))
     }
 
+    pub async fn find_expired_tasks(
+        &self,
+        now_secs: u64,
+    ) -> Result<Vec<indexify_internal_api::Task>> {
+        self.data
+            .indexify_state
+            .find_expired_tasks(&self.db, now_secs)
+            .map_err(|e| anyhow::anyhow!("Failed to find expired tasks: {}", e))
+    }
+
+    /// Keeps back the tasks of rate limited extraction policies whose token
+    /// bucket ran dry
+    pub fn admit_rate_limited_tasks(&self, task_ids: HashSet<TaskId>) -> Result<HashSet<TaskId>> {
+        self.data
+            .indexify_state
+            .admit_rate_limited_tasks(&self.db, task_ids, Instant::now())
+    }
+
+    pub fn consume_rate_limit_tokens<'a>(&self, task_ids: impl Iterator<Item = &'a TaskId>) {
+        self.data.indexify_state.consume_rate_limit_tokens(task_ids)
+    }
+
+    pub fn record_scheduling_attempts(
+        &self,
+        attempts: Vec<(TaskId, indexify_internal_api::SchedulingAttempt)>,
+    ) {
+        self.data
+            .indexify_state
+            .scheduling_attempts
+            .record(attempts)
+    }
+
+    pub fn get_scheduling_attempts(
+        &self,
+        task_id: &TaskId,
+    ) -> Vec<indexify_internal_api::SchedulingAttempt> {
+        self.data.indexify_state.scheduling_attempts.get(task_id)
+    }
+
+    pub fn get_throttled_tasks(&self) -> HashSet<TaskId> {
+        self.data.indexify_state.get_throttled_tasks()
+    }
+
+    pub fn get_starving_tasks(
+        &self,
+        min_age: std::time::Duration,
+    ) -> Vec<(TaskId, std::time::Duration)> {
+        self.data.indexify_state.get_starving_tasks(min_age)
+    }
+
+    pub fn set_starved_task_counts(
+        &self,
+        counts: HashMap<indexify_internal_api::UnschedulableReason, u64>,
+    ) {
+        *self.data.indexify_state.starved_tasks.lock().unwrap() = counts;
+    }
+
+    pub fn get_finished_task_counts(
+        &self,
+        extraction_policy_id: &ExtractionPolicyId,
+        latency: Option<std::time::Duration>,
+    ) -> (u64, u64, u64) {
+        self.data
+            .indexify_state
+            .get_finished_task_counts(extraction_policy_id, latency)
+    }
+
+    pub fn set_slo_compliance(
+        &self,
+        compliance: HashMap<ExtractionPolicyId, indexify_internal_api::SloCompliance>,
+    ) {
+        *self.data.indexify_state.slo_compliance.lock().unwrap() = compliance;
+    }
+
+    pub fn active_root_content(&self, graph_id: &str) -> HashSet<String> {
+        self.data.indexify_state.active_root_content(graph_id)
+    }
+
+    pub async fn find_stuck_content(
+        &self,
+        namespace: Option<&str>,
+        stalled_for: std::time::Duration,
+    ) -> Result<Vec<StuckContent>> {
+        self.data
+            .indexify_state
+            .find_stuck_content(&self.db, namespace, stalled_for)
+            .map_err(|e| anyhow::anyhow!("Failed to find stuck content: {}", e))
+    }
+
+    pub fn get_pending_work(&self, root_content_id: &str) -> Result<PendingWork> {
+        self.data
+            .indexify_state
+            .get_pending_work(&self.db, root_content_id)
+            .map_err(|e| anyhow::anyhow!("Failed to read pending work: {}", e))
+    }
+
+    /// Finds the orphaned task assignments and unallocated tasks. The task
+    /// assignments are scanned off the async runtime.
+    pub async fn find_orphaned_assignments(
+        &self,
+    ) -> Result<(HashMap<ExecutorId, HashSet<TaskId>>, HashSet<TaskId>)> {
+        let db = self.db.clone();
+        let (finished_task_assignments, assigned_tasks) =
+            tokio::task::spawn_blocking(move || IndexifyState::scan_task_assignments(&db))
+                .await?
+                .map_err(|e| anyhow::anyhow!("Failed to find orphaned task assignments: {}", e))?;
+        let unallocated_tasks = self
+            .data
+            .indexify_state
+            .find_unallocated_tasks(&assigned_tasks);
+        Ok((finished_task_assignments, unallocated_tasks))
+    }
+
     pub async fn get_indexes_from_ids(
         &self,
         task_ids: HashSet<String>,
@@ -416,9 +786,9 @@ impl StateMachineStore {
         parent_id: &str,
         predicate: impl Fn(&ContentMetadata) -> bool,
     ) -> Result<Vec<ContentMetadata>> {
-        let txn = self.db.transaction();
-        let iter = txn.iterator_cf(
+        let iter = self.db.iterator_cf_opt(
             StateMachineColumns::ContentTable.cf(&self.db),
+            scan_read_options(),
             IteratorMode::Start,
         );
         let mut contents = Vec::new();
@@ -499,6 +869,243 @@ impl StateMachineStore {
             .map_err(|e| anyhow::anyhow!(e))
     }
 
+    pub fn get_extraction_graph_history(
+        &self,
+        extraction_graph_id: &str,
+    ) -> Result<Vec<indexify_internal_api::ExtractionGraphVersion>> {
+        self.data
+            .indexify_state
+            .get_extraction_graph_history(&self.db, extraction_graph_id)
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    pub fn get_latest_extraction_graph_version(
+        &self,
+        extraction_graph_id: &str,
+    ) -> Result<Option<Arc<indexify_internal_api::ExtractionGraphVersion>>> {
+        self.data
+            .indexify_state
+            .get_latest_extraction_graph_version(&self.db, extraction_graph_id)
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    pub fn get_extraction_graph_usage(
+        &self,
+        extraction_graph_id: &str,
+    ) -> Result<Vec<indexify_internal_api::ExtractionGraphUsage>> {
+        self.data
+            .indexify_state
+            .get_extraction_graph_usage(&self.db, extraction_graph_id)
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    pub fn get_scheduler_settings(&self) -> Result<indexify_internal_api::SchedulerSettings> {
+        self.data
+            .indexify_state
+            .get_scheduler_settings(&self.db)
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    pub fn get_maintenance_mode(&self) -> Result<indexify_internal_api::MaintenanceMode> {
+        self.data
+            .indexify_state
+            .get_maintenance_mode(&self.db)
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    pub fn list_metering_records(
+        &self,
+        namespace: Option<&str>,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<indexify_internal_api::MeteringRecord>> {
+        self.data
+            .indexify_state
+            .list_metering_records(&self.db, namespace, start, end)
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    pub fn get_latency_tolerant_tasks(
+        &self,
+        task_ids: &HashSet<TaskId>,
+    ) -> Result<HashSet<TaskId>> {
+        self.data
+            .indexify_state
+            .get_latency_tolerant_tasks(&self.db, task_ids)
+    }
+
+    pub fn get_tasks_from_ids(
+        &self,
+        task_ids: &HashSet<TaskId>,
+    ) -> Result<HashMap<TaskId, internal_api::Task>> {
+        self.data
+            .indexify_state
+            .get_tasks_from_ids(&self.db, task_ids)
+    }
+
+    pub fn get_task_transitions(&self, task_id: &str) -> Result<Vec<TaskTransition>> {
+        self.data
+            .indexify_state
+            .get_task_transitions(&self.db, task_id)
+    }
+
+    pub fn tasks_quarantined(&self) -> u64 {
+        self.data
+            .indexify_state
+            .metrics
+            .lock()
+            .unwrap()
+            .tasks_quarantined
+    }
+
+    pub fn get_replay(&self, replay_id: &str) -> Result<Option<indexify_internal_api::Replay>> {
+        self.data.indexify_state.get_replay(&self.db, replay_id)
+    }
+
+    pub fn list_replays(&self, namespace: &str) -> Result<Vec<indexify_internal_api::Replay>> {
+        self.data.indexify_state.list_replays(&self.db, namespace)
+    }
+
+    pub fn get_graph_template(
+        &self,
+        name: &str,
+    ) -> Result<Option<indexify_internal_api::GraphTemplate>> {
+        self.data.indexify_state.get_graph_template(&self.db, name)
+    }
+
+    pub fn list_graph_templates(&self) -> Result<Vec<indexify_internal_api::GraphTemplate>> {
+        self.data.indexify_state.list_graph_templates(&self.db)
+    }
+
+    pub fn get_cancel_tasks_job(
+        &self,
+        job_id: &str,
+    ) -> Result<Option<indexify_internal_api::CancelTasksJob>> {
+        self.data
+            .indexify_state
+            .get_cancel_tasks_job(&self.db, job_id)
+    }
+
+    pub fn list_cancel_tasks_jobs(&self) -> Result<Vec<indexify_internal_api::CancelTasksJob>> {
+        self.data.indexify_state.list_cancel_tasks_jobs(&self.db)
+    }
+
+    pub fn get_content_replica(
+        &self,
+        content_id: &str,
+    ) -> Result<Option<indexify_internal_api::ContentReplica>> {
+        self.data
+            .indexify_state
+            .get_content_replica(&self.db, content_id)
+    }
+
+    pub fn pending_replications(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<indexify_internal_api::PendingReplication>> {
+        self.data
+            .indexify_state
+            .pending_replications(&self.db, limit)
+    }
+
+    /// Backs the state store up while it keeps applying log entries. Returns
+    /// none when the previous backup is still running.
+    pub async fn create_backup(
+        &self,
+        config: &BackupConfig,
+    ) -> Result<Option<backup::BackupReport>> {
+        if self.backup_running.swap(true, Ordering::AcqRel) {
+            return Ok(None);
+        }
+        let db = self.db.clone();
+        let backup_config = config.clone();
+        let result =
+            tokio::task::spawn_blocking(move || backup::create_backup(&db, &backup_config)).await;
+        self.backup_running.store(false, Ordering::Release);
+        let report = result??;
+        self.metrics
+            .backup_duration
+            .record(report.elapsed.as_secs_f64(), &[]);
+        self.metrics.backup_size.record(report.size_bytes, &[]);
+        if let Some(utilization) = report.rate_limit_utilization() {
+            self.metrics
+                .backup_rate_limit_utilization
+                .record(utilization, &[]);
+        }
+        Ok(Some(report))
+    }
+
+    /// Compacts the key ranges of the column families with at least
+    /// `min_deletions` deleted keys, so iterators stop stepping over their
+    /// tombstones. Compactions run one after the other off the async runtime.
+    pub async fn compact_deleted_ranges(
+        &self,
+        min_deletions: u64,
+    ) -> Result<Vec<compaction::CompactionReport>> {
+        let ranges = self.data.indexify_state.deletions.take(min_deletions);
+        if ranges.is_empty() {
+            return Ok(Vec::new());
+        }
+        let db = self.db.clone();
+        let reports = tokio::task::spawn_blocking(move || {
+            ranges
+                .iter()
+                .map(|(column, range)| compaction::compact_deleted_range(&db, column, range))
+                .collect::<Result<Vec<_>>>()
+        })
+        .await??;
+        for report in &reports {
+            self.metrics.compaction_reclaimed_bytes.add(
+                report.reclaimed_bytes,
+                &[KeyValue::new("column", report.column.clone())],
+            );
+        }
+        Ok(reports)
+    }
+
+    /// Finished tasks that still hold diagnostics. The tasks are scanned off
+    /// the async runtime.
+    pub async fn finished_tasks_with_diagnostics(
+        &self,
+    ) -> Result<Vec<(TaskId, String, Option<u64>)>> {
+        let db = self.db.clone();
+        tokio::task::spawn_blocking(move || IndexifyState::finished_tasks_with_diagnostics(&db))
+            .await?
+    }
+
+    pub fn get_zone_placements(
+        &self,
+        task_ids: &HashSet<TaskId>,
+    ) -> Result<HashMap<TaskId, (ZonePlacement, String)>> {
+        self.data
+            .indexify_state
+            .get_zone_placements(&self.db, task_ids)
+    }
+
+    pub fn get_content_zones<'a>(
+        &self,
+        root_content_ids: impl IntoIterator<Item = &'a String>,
+    ) -> Result<HashMap<String, HashMap<String, usize>>> {
+        self.data
+            .indexify_state
+            .get_content_zones(&self.db, root_content_ids)
+    }
+
+    /// Garbage collection tasks that didn't finish yet
+    pub fn count_unfinished_gc_tasks(&self) -> usize {
+        self.data.indexify_state.count_unfinished_gc_tasks()
+    }
+
+    pub fn get_content_context(
+        &self,
+        root_content_id: &str,
+    ) -> Result<HashMap<String, serde_json::Value>> {
+        self.data
+            .indexify_state
+            .get_content_context(&self.db, root_content_id)
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+
     pub async fn get_coordinator_addr(&self, node_id: NodeId) -> Result<Option<String>> {
         self.data
             .indexify_state
@@ -519,6 +1126,44 @@ impl StateMachineStore {
             .map_err(|e| anyhow::anyhow!("Failed to get all rows from column family: {}", e))
     }
 
+    /// Reads a page of the rows of a column family: up to `limit` rows after
+    /// the key `cursor`, or from the first row without one. Returns the rows
+    /// that match `predicate` with their keys, and the key to read the next
+    /// page after, none once the column family was read to its end.
+    pub fn read_rows_page<V: DeserializeOwned>(
+        &self,
+        column: StateMachineColumns,
+        cursor: Option<&[u8]>,
+        limit: usize,
+        predicate: impl Fn(&V) -> bool,
+    ) -> Result<(Vec<(String, V)>, Option<Vec<u8>>)> {
+        let mode = match cursor {
+            Some(cursor) => IteratorMode::From(cursor, Direction::Forward),
+            None => IteratorMode::Start,
+        };
+        let mut iter = self
+            .db
+            .iterator_cf_opt(column.cf(&self.db), scan_read_options(), mode)
+            .peekable();
+        //  The row of the cursor was in the previous page
+        if let (Some(cursor), Some(Ok((key, _)))) = (cursor, iter.peek()) {
+            if key.as_ref() == cursor {
+                iter.next();
+            }
+        }
+        let mut rows = Vec::new();
+        let mut last_key = None;
+        for row in iter.take(limit.max(1)) {
+            let (key, value) = row.map_err(|e| anyhow!("error reading {}: {}", column, e))?;
+            let value = JsonEncoder::decode::<V>(&value)?;
+            if predicate(&value) {
+                rows.push((String::from_utf8_lossy(&key).to_string(), value));
+            }
+            last_key = Some(key.to_vec());
+        }
+        Ok((rows, last_key))
+    }
+
     //  END FORWARD INDEX READER METHOD INTERFACES
 
     //  START REVERSE INDEX READER METHOD INTERFACES
@@ -530,6 +1175,28 @@ impl StateMachineStore {
         self.data.indexify_state.get_unprocessed_state_changes()
     }
 
+    /// Number of state changes the scheduler hasn't processed yet, and the
+    /// seconds the oldest of them has waited
+    pub fn state_change_backlog(&self) -> Result<(usize, u64)> {
+        let (count, oldest) = self
+            .data
+            .indexify_state
+            .get_state_change_backlog(&self.db)?;
+        let age = oldest.map_or(0, |change| {
+            timestamp_secs().saturating_sub(change.created_at)
+        });
+        Ok((count, age))
+    }
+
+    pub fn state_changes_processed(&self) -> u64 {
+        self.data
+            .indexify_state
+            .metrics
+            .lock()
+            .unwrap()
+            .state_changes_processed
+    }
+
     pub fn get_content_namespace_table(
         &self,
     ) -> HashMap<NamespaceName, HashSet<ContentMetadataId>> {
@@ -550,14 +1217,40 @@ impl StateMachineStore {
         self.data.indexify_state.get_namespace_index_table()
     }
 
-    pub async fn get_unfinished_tasks_by_extractor(
+    pub fn get_unfinished_tasks_for_extractor(&self, extractor: &str) -> HashSet<TaskId> {
+        self.data
+            .indexify_state
+            .get_unfinished_tasks_for_extractor(extractor)
+    }
+
+    pub fn group_unfinished_tasks_by_extractor<'a>(
         &self,
-    ) -> HashMap<ExtractorName, HashSet<TaskId>> {
-        self.data.indexify_state.get_unfinished_tasks_by_extractor()
+        task_ids: &'a HashSet<TaskId>,
+    ) -> HashMap<ExtractorName, Vec<&'a TaskId>> {
+        self.data
+            .indexify_state
+            .group_unfinished_tasks_by_extractor(task_ids)
+    }
+
+    pub fn get_executor_loads_for_extractor(&self, extractor: &str) -> Vec<(ExecutorId, u64)> {
+        self.data
+            .indexify_state
+            .get_executor_loads_for_extractor(extractor)
+    }
+
+    pub fn warm_executors_for_tasks(
+        &self,
+        task_ids: &HashSet<TaskId>,
+    ) -> Result<HashMap<TaskId, HashSet<ExecutorId>>> {
+        self.data
+            .indexify_state
+            .warm_executors_for_tasks(&self.db, task_ids, SystemTime::now())
     }
 
-    pub async fn get_executor_running_task_count(&self) -> HashMap<ExecutorId, u64> {
-        self.data.indexify_state.get_executor_running_task_count()
+    pub async fn get_task_queue_depths(
+        &self,
+    ) -> HashMap<ExtractionPolicyId, state_machine_objects::TaskQueueDepth> {
+        self.data.indexify_state.get_task_queue_depths()
     }
 
     pub async fn get_schemas_by_namespace(
@@ -670,19 +1363,25 @@ impl RaftStateMachine<TypeConfig> for Arc<StateMachineStore> {
         let mut change_events: Vec<StateChange> = Vec::new();
 
         for ent in entries {
+            let _applying = self.applying.lock().await;
             {
                 let mut guard = self.data.last_applied_log_id.write().await;
                 *guard = Some(ent.log_id);
             }
             let resp_value = None;
+            let membership = self.data.last_membership.read().await.clone();
             match ent.payload {
-                EntryPayload::Blank => {}
+                EntryPayload::Blank => {
+                    if let Err(e) = self.record_applied_entry_(ent.log_id, &membership) {
+                        panic!("error recording applied log entry: {}", e);
+                    }
+                }
                 EntryPayload::Normal(req) => {
-                    match self
-                        .data
-                        .indexify_state
-                        .apply_state_machine_updates(req.clone(), &self.db)
-                    {
+                    match self.data.indexify_state.apply_state_machine_updates_with(
+                        req.clone(),
+                        &self.db,
+                        |txn| self.record_applied_(txn, ent.log_id, &membership),
+                    ) {
                         Ok(changes) => {
                             change_events.extend(changes);
                         }
@@ -690,6 +1389,7 @@ impl RaftStateMachine<TypeConfig> for Arc<StateMachineStore> {
                             panic!("error applying state machine update: {}", e);
                         }
                     }
+                    *self.entries_since_indexes_snapshot.lock().unwrap() += 1;
 
                     //  if the payload is a GC task, send it via channel
                     if let RequestPayload::CreateOrAssignGarbageCollectionTask { gc_tasks } =
@@ -713,18 +1413,21 @@ impl RaftStateMachine<TypeConfig> for Arc<StateMachineStore> {
                     }
                 }
                 EntryPayload::Membership(mem) => {
+                    let membership = StoredMembership::new(Some(ent.log_id), mem.clone());
+                    if let Err(e) = self.record_applied_entry_(ent.log_id, &membership) {
+                        panic!("error recording applied log entry: {}", e);
+                    }
                     let mut guard = self.data.last_membership.write().await;
-                    *guard = StoredMembership::new(Some(ent.log_id), mem.clone());
+                    *guard = membership;
                 }
             }
 
             replies.push(Response { value: resp_value });
         }
 
+        self.indexes_applied.notify_one();
         for change_event in change_events {
-            if let Err(err) = self.data.state_change_tx.send(change_event) {
-                tracing::error!("error sending state change event: {}", err);
-            }
+            self.data.state_changes.send(change_event);
         }
         Ok(replies)
     }
@@ -775,6 +1478,36 @@ pub struct LogStore {
 }
 type StorageResult<T> = Result<T, StorageError<NodeId>>;
 
+/// Reads a file written by `write_compressed`
+fn read_compressed(path: &Path) -> std::io::Result<Vec<u8>> {
+    let file = File::open(path)?;
+
+    //  Decompress the data with ZLib decoder
+    let buf_reader = BufReader::new(file);
+    let mut decoder = ZlibDecoder::new(buf_reader);
+    let mut decompressed_data = Vec::new();
+    decoder.read_to_end(&mut decompressed_data)?;
+    Ok(decompressed_data)
+}
+
+/// Compresses `data` with Zlib and atomically replaces the file at `path`
+/// with it, returning the compressed size
+fn write_compressed(path: &Path, data: &[u8]) -> std::io::Result<usize> {
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data)?;
+    let compressed_data = encoder.finish()?;
+
+    //  Create a temp file, write to temp file and then swap inode pointers
+    let mut temp_file_path = path.as_os_str().to_owned();
+    temp_file_path.push(".tmp");
+    let temp_file_path = PathBuf::from(temp_file_path);
+    let mut temp_file = File::create(&temp_file_path)?;
+    temp_file.write_all(&compressed_data)?;
+    temp_file.sync_all()?;
+    fs::rename(&temp_file_path, path)?;
+    Ok(compressed_data.len())
+}
+
 /// converts an id to a byte vector for storing in the database.
 /// Note that we're using big endian encoding to ensure correct sorting of keys
 fn id_to_bin(id: u64) -> Vec<u8> {
@@ -796,6 +1529,31 @@ impl LogStore {
         self.db.cf_handle("logs").unwrap()
     }
 
+    fn log_times(&self) -> &ColumnFamily {
+        self.db.cf_handle("log_times").unwrap()
+    }
+
+    /// Times the log entries in `range` were appended to the log of this
+    /// server, in seconds since the epoch, by index. Entries appended before
+    /// the times were recorded have none.
+    fn get_log_times_(&self, range: RangeInclusive<u64>) -> StorageResult<HashMap<u64, u64>> {
+        let start = id_to_bin(*range.start());
+        let mut times = HashMap::new();
+        for item in self.db.iterator_cf(
+            self.log_times(),
+            IteratorMode::From(&start, Direction::Forward),
+        ) {
+            let (id, time) = item.map_err(|e| StorageIOError::read_logs(&e))?;
+            let id = bin_to_id(&id);
+            if !range.contains(&id) {
+                break;
+            }
+            let time = JsonEncoder::decode(&time).map_err(|e| StorageIOError::read_logs(&e))?;
+            times.insert(id, time);
+        }
+        Ok(times)
+    }
+
     fn flush(
         &self,
         subject: ErrorSubject<NodeId>,
@@ -885,7 +1643,11 @@ impl RaftLogReader<TypeConfig> for LogStore {
             std::ops::Bound::Unbounded => id_to_bin(0),
         };
         self.db
-            .iterator_cf(self.logs(), IteratorMode::From(&start, Direction::Forward))
+            .iterator_cf_opt(
+                self.logs(),
+                log_read_options(&range),
+                IteratorMode::From(&start, Direction::Forward),
+            )
             .map(|res| {
                 let (id, val) = res.unwrap();
                 let entry: StorageResult<Entry<_>> =
@@ -956,16 +1718,21 @@ impl RaftLogStorage<TypeConfig> for LogStore {
         I: IntoIterator<Item = Entry<TypeConfig>> + Send,
         I::IntoIter: Send,
     {
+        let written_at =
+            JsonEncoder::encode(&timestamp_secs()).map_err(|e| StorageIOError::write_logs(&e))?;
         for entry in entries {
             let id = id_to_bin(entry.log_id.index);
             assert_eq!(bin_to_id(&id), entry.log_id.index);
             self.db
                 .put_cf(
                     self.logs(),
-                    id,
+                    &id,
                     JsonEncoder::encode(&entry).map_err(|e| StorageIOError::write_logs(&e))?,
                 )
                 .map_err(|e| StorageIOError::write_logs(&e))?;
+            self.db
+                .put_cf(self.log_times(), &id, &written_at)
+                .map_err(|e| StorageIOError::write_logs(&e))?;
         }
 
         callback.log_io_completed(Ok(()));
@@ -979,6 +1746,9 @@ impl RaftLogStorage<TypeConfig> for LogStore {
 
         let from = id_to_bin(log_id.index);
         let to = id_to_bin(0xff_ff_ff_ff_ff_ff_ff_ff);
+        self.db
+            .delete_file_in_range_cf(self.log_times(), &from, &to)
+            .map_err(|e| StorageIOError::write_logs(&e))?;
         self.db
             .delete_file_in_range_cf(self.logs(), &from, &to)
             .map_err(|e| StorageIOError::write_logs(&e).into())
@@ -991,6 +1761,9 @@ impl RaftLogStorage<TypeConfig> for LogStore {
         self.set_last_purged_(log_id)?;
         let from = id_to_bin(0);
         let to = id_to_bin(log_id.index + 1);
+        self.db
+            .delete_file_in_range_cf(self.log_times(), &from, &to)
+            .map_err(|e| StorageIOError::write_logs(&e))?;
         self.db
             .delete_file_in_range_cf(self.logs(), &from, &to)
             .map_err(|e| StorageIOError::write_logs(&e).into())
@@ -1004,28 +1777,27 @@ impl RaftLogStorage<TypeConfig> for LogStore {
 pub(crate) async fn new_storage<P: AsRef<Path>>(
     db_path: P,
     snapshot_path: P,
-) -> (LogStore, Arc<StateMachineStore>) {
+    invariant_validation: InvariantValidation,
+) -> Result<(LogStore, Arc<StateMachineStore>)> {
     let mut db_opts = Options::default();
     db_opts.create_missing_column_families(true);
     db_opts.create_if_missing(true);
 
-    let store = ColumnFamilyDescriptor::new("store", Options::default());
-    let logs = ColumnFamilyDescriptor::new("logs", Options::default());
+    let block_cache = Cache::new_lru_cache(tuning::BLOCK_CACHE_SIZE);
+    let store = ColumnFamilyDescriptor::new("store", tuning::log_options(&block_cache));
+    let logs = ColumnFamilyDescriptor::new("logs", tuning::log_options(&block_cache));
+    let log_times = ColumnFamilyDescriptor::new("log_times", tuning::log_options(&block_cache));
 
     //  Create the column families for the state machine columns
-    let sm_columns: Vec<String> = StateMachineColumns::iter()
-        .map(|cf| cf.to_string())
+    let sm_column_families: Vec<ColumnFamilyDescriptor> = StateMachineColumns::iter()
+        .map(|cf| ColumnFamilyDescriptor::new(cf.to_string(), cf.options(&block_cache)))
         .collect();
-    let sm_column_families: Vec<ColumnFamilyDescriptor> = sm_columns
-        .iter()
-        .map(|name| ColumnFamilyDescriptor::new(name, Options::default()))
-        .collect();
-    let mut all_column_families = vec![store, logs];
+    let mut all_column_families = vec![store, logs, log_times];
     all_column_families.extend(sm_column_families);
 
     let db: OptimisticTransactionDB =
-        OptimisticTransactionDB::open_cf_descriptors(&db_opts, db_path, all_column_families)
-            .unwrap();
+        OptimisticTransactionDB::open_cf_descriptors(&db_opts, db_path, all_column_families)?;
+    state_version::check_and_record_state_version(&db)?;
 
     let db = Arc::new(db);
 
@@ -1033,23 +1805,954 @@ pub(crate) async fn new_storage<P: AsRef<Path>>(
 
     let snapshot_path = PathBuf::from(snapshot_path.as_ref());
 
-    let sm_store = StateMachineStore::new(db, snapshot_path).await.unwrap();
+    let sm_store = StateMachineStore::new(db, snapshot_path, invariant_validation)
+        .await
+        .map_err(|e| anyhow!("unable to open the state machine: {}", e))?;
+    let sm_store = Arc::new(sm_store);
+    StateMachineStore::spawn_indexes_snapshots(&sm_store);
 
-    (log_store, Arc::new(sm_store))
+    Ok((log_store, sm_store))
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::{
+        collections::{HashMap, HashSet},
+        time::{Duration, SystemTime},
+    };
+
+    use indexify_internal_api::{
+        ChangeType,
+        ContentMetadata,
+        ContentMetadataId,
+        ExtractionGraph,
+        ExtractorDescription,
+        Replay,
+        ReplayKind,
+        ReplayTask,
+        StateChange,
+        Task,
+        TaskOutcome,
+    };
+    use openraft::{storage::RaftStateMachine, CommittedLeaderId, EntryPayload, LogId};
+
+    use super::{
+        new_storage,
+        requests::{RequestPayload, StateChangeProcessed, StateMachineUpdateRequest},
+        serializer::{JsonEncode, JsonEncoder},
+        StateMachineColumns,
+    };
+    use crate::{
+        server_config::InvariantValidation,
+        state::RaftConfigOverrides,
+        test_utils::RaftTestCluster,
+        utils::timestamp_secs,
+    };
+
+    #[tokio::test]
+    async fn test_compact_deleted_ranges() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_log_store, store) = new_storage(
+            dir.path().join("db"),
+            dir.path().join("snapshot"),
+            InvariantValidation::default(),
+        )
+        .await
+        .unwrap();
+        let cf = StateMachineColumns::ContentTable.cf(&store.db);
+        for i in 0..10 {
+            let content = ContentMetadata {
+                id: ContentMetadataId::new(&format!("content_{}", i)),
+                ..Default::default()
+            };
+            store
+                .db
+                .put_cf(cf, content.id_key(), JsonEncoder::encode(&content).unwrap())
+                .unwrap();
+        }
+        store.db.flush_cf(cf).unwrap();
+        for i in 0..10 {
+            let key = format!("content_{}", i);
+            store.db.delete_cf(cf, &key).unwrap();
+            store
+                .data
+                .indexify_state
+                .deletions
+                .record(&StateMachineColumns::ContentTable, &key);
+        }
+        store.db.flush_cf(cf).unwrap();
+
+        //  Column families with fewer deletions are left to regular compactions
+        assert!(store.compact_deleted_ranges(11).await.unwrap().is_empty());
+        let reports = store.compact_deleted_ranges(10).await.unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(
+            reports[0].column,
+            StateMachineColumns::ContentTable.to_string()
+        );
+        assert_eq!(reports[0].deletions, 10);
+        assert!(reports[0].reclaimed_bytes > 0);
+        assert!(store.compact_deleted_ranges(1).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_log_store, store) = new_storage(
+            dir.path().join("db"),
+            dir.path().join("snapshot"),
+            InvariantValidation::default(),
+        )
+        .await
+        .unwrap();
+        let cf = StateMachineColumns::ContentTable.cf(&store.db);
+        let content = ContentMetadata::default();
+        store
+            .db
+            .put_cf(cf, content.id_key(), JsonEncoder::encode(&content).unwrap())
+            .unwrap();
 
-    use indexify_internal_api::ContentMetadataId;
+        let config = BackupConfig {
+            path: dir.path().join("backups").to_str().unwrap().to_string(),
+            interval_secs: 1,
+            rate_limit_bytes_per_sec: 0,
+            keep: 1,
+        };
+        let first = store.create_backup(&config).await.unwrap().unwrap();
+        assert!(first.size_bytes > 0);
+        assert_eq!(first.rate_limit_utilization(), None);
 
-    use crate::{state::RaftConfigOverrides, test_utils::RaftTestCluster};
+        //  Older backups are purged past `keep`
+        let second = store.create_backup(&config).await.unwrap().unwrap();
+        assert_eq!(second.backup_id, first.backup_id + 1);
+        let engine = rocksdb::backup::BackupEngine::open(
+            &rocksdb::backup::BackupEngineOptions::new(&config.path).unwrap(),
+            &rocksdb::Env::new().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(engine.get_backup_info().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_invariant_validation_modes() {
+        let processed_change =
+            StateChange::new("executor".to_string(), ChangeType::ExecutorAdded, 0);
+        for invariant_validation in [InvariantValidation::LogOnly, InvariantValidation::Strict] {
+            let dir = tempfile::tempdir().unwrap();
+            let (_log_store, store) = new_storage(
+                dir.path().join("db"),
+                dir.path().join("snapshot"),
+                invariant_validation,
+            )
+            .await
+            .unwrap();
+            let state = &store.data.indexify_state;
+            let changes = state
+                .apply_state_machine_updates(
+                    StateMachineUpdateRequest {
+                        payload: RequestPayload::MarkStateChangesProcessed {
+                            state_changes: vec![],
+                        },
+                        new_state_changes: vec![processed_change.clone()],
+                        state_changes_processed: vec![],
+                    },
+                    &store.db,
+                )
+                .unwrap();
+            let processed_change_id = changes[0].id;
+
+            //  Removing an unknown executor violates an invariant. Strict
+            //  validation drops the removal and the state change it makes,
+            //  the state change it processed is still marked processed.
+            let changes = state
+                .apply_state_machine_updates(
+                    StateMachineUpdateRequest {
+                        payload: RequestPayload::RemoveExecutor {
+                            executor_id: "unknown_executor".to_string(),
+                            graceful: false,
+                            ts_secs: 0,
+                        },
+                        new_state_changes: vec![StateChange::new(
+                            "unknown_executor".to_string(),
+                            ChangeType::ExecutorRemoved,
+                            0,
+                        )],
+                        state_changes_processed: vec![StateChangeProcessed {
+                            state_change_id: processed_change_id,
+                            processed_at: 1,
+                        }],
+                    },
+                    &store.db,
+                )
+                .unwrap();
+            let strict = invariant_validation == InvariantValidation::Strict;
+            assert_eq!(changes.len(), if strict { 0 } else { 1 });
+            let stored_changes = state
+                .get_all_rows_from_cf::<StateChange>(StateMachineColumns::StateChanges, &store.db)
+                .unwrap();
+            assert_eq!(stored_changes.len(), if strict { 1 } else { 2 });
+            assert_eq!(
+                stored_changes
+                    .iter()
+                    .any(|change| change.change_type == ChangeType::ExecutorRemoved),
+                !strict
+            );
+            assert!(!state
+                .get_unprocessed_state_changes()
+                .contains(&processed_change_id));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reap_orphaned_assignments() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_log_store, store) = new_storage(
+            dir.path().join("db"),
+            dir.path().join("snapshot"),
+            InvariantValidation::LogOnly,
+        )
+        .await
+        .unwrap();
+        let state = &store.data.indexify_state;
+        let apply = |payload| {
+            state
+                .apply_state_machine_updates(
+                    StateMachineUpdateRequest {
+                        payload,
+                        new_state_changes: vec![StateChange::new(
+                            "node".to_string(),
+                            ChangeType::OrphanedAssignmentsReaped,
+                            0,
+                        )],
+                        state_changes_processed: vec![],
+                    },
+                    &store.db,
+                )
+                .unwrap()
+        };
+        let executor_id = "executor".to_string();
+        let task = |id: &str| Task {
+            id: id.to_string(),
+            extractor: "extractor".to_string(),
+            extraction_policy_id: "policy".to_string(),
+            content_metadata: ContentMetadata {
+                id: ContentMetadataId::new(&format!("content-{}", id)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        apply(RequestPayload::RegisterExecutor {
+            addr: "localhost:8950".to_string(),
+            executor_id: executor_id.clone(),
+            extractors: vec![ExtractorDescription {
+                name: "extractor".to_string(),
+                ..Default::default()
+            }],
+            cost_per_hour: None,
+            capacity_weight: None,
+            zone: None,
+            max_payload_bytes: None,
+            download_bytes_per_sec: None,
+            gpu_devices: Vec::new(),
+            graph_versions: Vec::new(),
+            ts_secs: 0,
+        });
+        apply(RequestPayload::CreateTasks {
+            tasks: vec![task("task_1"), task("task_2")],
+            ts_secs: 0,
+        });
+        apply(RequestPayload::AssignTask {
+            assignments: HashMap::from([("task_1".to_string(), executor_id.clone())]),
+            assigned_at: None,
+            gpu_devices: HashMap::new(),
+            burst_tasks: HashSet::new(),
+            content_zones: HashMap::new(),
+        });
+
+        //  task_1 finishes without its assignment being dropped and task_2
+        //  falls out of the unassigned tasks without being assigned
+        let mut finished_task = task("task_1");
+        finished_task.outcome = TaskOutcome::Success;
+        store
+            .db
+            .put_cf(
+                StateMachineColumns::Tasks.cf(&store.db),
+                "task_1",
+                JsonEncoder::encode(&finished_task).unwrap(),
+            )
+            .unwrap();
+        state.unassigned_tasks.remove(&"task_2".to_string());
+
+        let (finished_task_assignments, unallocated_tasks) =
+            state.find_orphaned_assignments(&store.db).unwrap();
+        assert_eq!(
+            finished_task_assignments,
+            HashMap::from([(executor_id.clone(), HashSet::from(["task_1".to_string()]))])
+        );
+        assert_eq!(unallocated_tasks, HashSet::from(["task_2".to_string()]));
+
+        let reap = RequestPayload::ReapOrphanedAssignments {
+            finished_task_assignments,
+            unallocated_tasks,
+            ts_secs: 0,
+        };
+        assert_eq!(apply(reap.clone()).len(), 1);
+        assert!(state
+            .get_all_task_assignments(&store.db)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            state.get_unassigned_tasks(),
+            HashSet::from(["task_2".to_string()])
+        );
+        assert_eq!(state.executor_running_task_count.get(&executor_id), Some(0));
+
+        //  Reaping the same orphans again is a no-op
+        assert!(apply(reap).is_empty());
+        assert_eq!(state.executor_running_task_count.get(&executor_id), Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_fence_task_assignments() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_log_store, store) = new_storage(
+            dir.path().join("db"),
+            dir.path().join("snapshot"),
+            InvariantValidation::LogOnly,
+        )
+        .await
+        .unwrap();
+        let state = &store.data.indexify_state;
+        let apply = |payload| {
+            state
+                .apply_state_machine_updates(
+                    StateMachineUpdateRequest {
+                        payload,
+                        new_state_changes: vec![],
+                        state_changes_processed: vec![],
+                    },
+                    &store.db,
+                )
+                .unwrap()
+        };
+        let assign = |task_id: &str, executor_id: &str| {
+            apply(RequestPayload::AssignTask {
+                assignments: HashMap::from([(task_id.to_string(), executor_id.to_string())]),
+                assigned_at: None,
+                gpu_devices: HashMap::new(),
+                burst_tasks: HashSet::new(),
+                content_zones: HashMap::new(),
+            })
+        };
+        let task = |id: &str| Task {
+            id: id.to_string(),
+            extractor: "extractor".to_string(),
+            extraction_policy_id: "policy".to_string(),
+            content_metadata: ContentMetadata {
+                id: ContentMetadataId::new(&format!("content-{}", id)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        for executor_id in ["executor_a", "executor_b"] {
+            apply(RequestPayload::RegisterExecutor {
+                addr: "localhost:8950".to_string(),
+                executor_id: executor_id.to_string(),
+                extractors: vec![ExtractorDescription {
+                    name: "extractor".to_string(),
+                    ..Default::default()
+                }],
+                cost_per_hour: None,
+                capacity_weight: None,
+                zone: None,
+                max_payload_bytes: None,
+                download_bytes_per_sec: None,
+                gpu_devices: Vec::new(),
+                graph_versions: Vec::new(),
+                ts_secs: 0,
+            });
+        }
+        apply(RequestPayload::CreateTasks {
+            tasks: vec![task("task_1"), task("task_2"), task("task_3")],
+            ts_secs: 0,
+        });
+        assign("task_1", "executor_a");
+        let running = |executor_id: &str| {
+            state
+                .executor_running_task_count
+                .get(&executor_id.to_string())
+        };
+
+        //  A task held by another registered executor isn't taken away from
+        //  it, and assigning it to its executor again doesn't count it twice
+        assign("task_1", "executor_b");
+        assign("task_1", "executor_a");
+        assert_eq!(running("executor_a"), Some(1));
+        assert_eq!(running("executor_b"), None);
+
+        //  An unfinished task nobody holds can be assigned
+        state.unassigned_tasks.remove(&"task_2".to_string());
+        assign("task_2", "executor_b");
+        assert_eq!(running("executor_b"), Some(1));
+
+        //  A task can be moved away from an executor that is gone
+        store
+            .db
+            .put_cf(
+                StateMachineColumns::TaskAssignments.cf(&store.db),
+                "executor_gone",
+                JsonEncoder::encode(&HashSet::from(["task_3".to_string()])).unwrap(),
+            )
+            .unwrap();
+        state.rebuild_indexes(&store.db).unwrap();
+        assert!(state.get_unassigned_tasks().is_empty());
+        assign("task_3", "executor_b");
+        assert_eq!(running("executor_b"), Some(2));
+        assert_eq!(running("executor_gone"), Some(0));
+
+        let assignments = state.get_all_task_assignments(&store.db).unwrap();
+        assert_eq!(
+            assignments,
+            HashMap::from([
+                ("task_1".to_string(), "executor_a".to_string()),
+                ("task_2".to_string(), "executor_b".to_string()),
+                ("task_3".to_string(), "executor_b".to_string()),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_content_zones() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_log_store, store) = new_storage(
+            dir.path().join("db"),
+            dir.path().join("snapshot"),
+            InvariantValidation::LogOnly,
+        )
+        .await
+        .unwrap();
+        let state = &store.data.indexify_state;
+        let apply = |payload| {
+            state
+                .apply_state_machine_updates(
+                    StateMachineUpdateRequest {
+                        payload,
+                        new_state_changes: vec![],
+                        state_changes_processed: vec![],
+                    },
+                    &store.db,
+                )
+                .unwrap()
+        };
+        let task = |id: &str| Task {
+            id: id.to_string(),
+            extractor: "extractor".to_string(),
+            extraction_policy_id: "policy".to_string(),
+            content_metadata: ContentMetadata {
+                id: ContentMetadataId::new(&format!("content-{}", id)),
+                root_content_id: Some("root".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        apply(RequestPayload::RegisterExecutor {
+            addr: "localhost:8950".to_string(),
+            executor_id: "executor".to_string(),
+            extractors: vec![ExtractorDescription {
+                name: "extractor".to_string(),
+                ..Default::default()
+            }],
+            cost_per_hour: None,
+            capacity_weight: None,
+            zone: Some("zone_a".to_string()),
+            max_payload_bytes: None,
+            download_bytes_per_sec: None,
+            gpu_devices: Vec::new(),
+            graph_versions: Vec::new(),
+            ts_secs: 0,
+        });
+        apply(RequestPayload::CreateTasks {
+            tasks: vec![task("task_1"), task("task_2")],
+            ts_secs: 0,
+        });
+
+        //  The zones the tasks of a content are given to survive in the store,
+        //  so a new leader keeps placing the content in the same zone
+        let root = "root".to_string();
+        for task_id in ["task_1", "task_2"] {
+            apply(RequestPayload::AssignTask {
+                assignments: HashMap::from([(task_id.to_string(), "executor".to_string())]),
+                assigned_at: None,
+                gpu_devices: HashMap::new(),
+                burst_tasks: HashSet::new(),
+                content_zones: HashMap::from([(
+                    task_id.to_string(),
+                    (root.clone(), "zone_a".to_string()),
+                )]),
+            });
+        }
+        assert_eq!(
+            state.get_content_zones(&store.db, [&root]).unwrap(),
+            HashMap::from([(root.clone(), HashMap::from([("zone_a".to_string(), 2)]))])
+        );
+        assert!(state
+            .get_content_zones(&store.db, [&"other".to_string()])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replays() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_log_store, store) = new_storage(
+            dir.path().join("db"),
+            dir.path().join("snapshot"),
+            InvariantValidation::LogOnly,
+        )
+        .await
+        .unwrap();
+        let state = &store.data.indexify_state;
+        let apply = |payload| {
+            state
+                .apply_state_machine_updates(
+                    StateMachineUpdateRequest {
+                        payload,
+                        new_state_changes: vec![],
+                        state_changes_processed: vec![],
+                    },
+                    &store.db,
+                )
+                .unwrap()
+        };
+        let task = |id: &str| Task {
+            id: id.to_string(),
+            extractor: "extractor".to_string(),
+            extraction_policy_id: "policy".to_string(),
+            namespace: "default".to_string(),
+            content_metadata: ContentMetadata {
+                id: ContentMetadataId::new(&format!("content-{}", id)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let replay = |id: &str, initiator: &str, started_at: u64, task_ids: &[&str]| Replay {
+            id: id.to_string(),
+            namespace: "default".to_string(),
+            kind: ReplayKind::FailedTasks,
+            content_id: "root".to_string(),
+            initiator: initiator.to_string(),
+            started_at,
+            finished_at: None,
+            tasks: task_ids
+                .iter()
+                .map(|task_id| ReplayTask {
+                    task_id: task_id.to_string(),
+                    content_id: format!("content-{}", task_id),
+                    extraction_graph: "graph".to_string(),
+                    extraction_policy: "policy".to_string(),
+                    graph_version: 1,
+                    outcome: TaskOutcome::Unknown,
+                    replaced_by: None,
+                    outputs: Vec::new(),
+                })
+                .collect(),
+        };
+        let finish = |id: &str, outcome: TaskOutcome, at: u64| {
+            apply(RequestPayload::UpdateTask {
+                task: Task {
+                    outcome,
+                    ..task(id)
+                },
+                executor_id: None,
+                update_time: SystemTime::UNIX_EPOCH + Duration::from_secs(at),
+            })
+        };
+        let get_replay = |id: &str| store.get_replay(id).unwrap().unwrap();
+        apply(RequestPayload::CreateTasks {
+            tasks: vec![task("task_1"), task("task_2")],
+            ts_secs: 0,
+        });
+
+        //  A replay starts with its parameters and unfinished tasks
+        apply(RequestPayload::CreateReplay {
+            replay: replay("replay_1", "alice", 100, &["task_1", "task_2"]),
+        });
+        assert_eq!(
+            get_replay("replay_1"),
+            replay("replay_1", "alice", 100, &["task_1", "task_2"])
+        );
+
+        //  It isn't finished while one of its tasks runs
+        finish("task_1", TaskOutcome::Success, 200);
+        let replay_1 = get_replay("replay_1");
+        assert_eq!(replay_1.tasks[0].outcome, TaskOutcome::Success);
+        assert_eq!(replay_1.finished_at, None);
+
+        //  Replaying a task that is still running finishes it in the earlier
+        //  replay, as replaced by the later one
+        apply(RequestPayload::CreateReplay {
+            replay: replay("replay_2", "bob", 300, &["task_2"]),
+        });
+        let replay_1 = get_replay("replay_1");
+        assert_eq!(replay_1.tasks[1].replaced_by, Some("replay_2".to_string()));
+        assert_eq!(replay_1.tasks[1].outcome, TaskOutcome::Unknown);
+        assert_eq!(replay_1.finished_at, Some(300));
+        assert_eq!(replay_1.failed_tasks(), 0);
+
+        //  The outcome of the task is recorded in the replay that ran it last
+        finish("task_2", TaskOutcome::Failed, 400);
+        let replay_2 = get_replay("replay_2");
+        assert_eq!(replay_2.tasks[0].outcome, TaskOutcome::Failed);
+        assert_eq!(replay_2.finished_at, Some(400));
+        assert_eq!(replay_2.failed_tasks(), 1);
+        assert_eq!(get_replay("replay_1"), replay_1);
+
+        //  Replays of a namespace are listed latest first
+        assert_eq!(
+            store.list_replays("default").unwrap(),
+            vec![replay_2, replay_1]
+        );
+        assert!(store.list_replays("other").unwrap().is_empty());
+        assert!(store.get_replay("unknown").unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_transitions_take_request_time() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_log_store, store) = new_storage(
+            dir.path().join("db"),
+            dir.path().join("snapshot"),
+            InvariantValidation::LogOnly,
+        )
+        .await
+        .unwrap();
+        let state = &store.data.indexify_state;
+        let apply = |payload| {
+            state
+                .apply_state_machine_updates(
+                    StateMachineUpdateRequest {
+                        payload,
+                        new_state_changes: vec![],
+                        state_changes_processed: vec![],
+                    },
+                    &store.db,
+                )
+                .unwrap()
+        };
+        apply(RequestPayload::RegisterExecutor {
+            addr: "localhost:8950".to_string(),
+            executor_id: "executor".to_string(),
+            extractors: vec![ExtractorDescription {
+                name: "extractor".to_string(),
+                ..Default::default()
+            }],
+            cost_per_hour: None,
+            capacity_weight: None,
+            zone: None,
+            max_payload_bytes: None,
+            download_bytes_per_sec: None,
+            gpu_devices: Vec::new(),
+            graph_versions: Vec::new(),
+            ts_secs: 100,
+        });
+        apply(RequestPayload::CreateTasks {
+            tasks: vec![Task {
+                id: "task".to_string(),
+                extractor: "extractor".to_string(),
+                extraction_policy_id: "policy".to_string(),
+                ..Default::default()
+            }],
+            ts_secs: 100,
+        });
+        apply(RequestPayload::AssignTask {
+            assignments: HashMap::from([("task".to_string(), "executor".to_string())]),
+            assigned_at: Some(SystemTime::UNIX_EPOCH + Duration::from_secs(200)),
+            gpu_devices: HashMap::new(),
+            burst_tasks: HashSet::new(),
+            content_zones: HashMap::new(),
+        });
+        apply(RequestPayload::RemoveExecutor {
+            executor_id: "executor".to_string(),
+            graceful: true,
+            ts_secs: 300,
+        });
+
+        //  Every node applying the requests records the same times, whatever
+        //  its clock
+        let at: Vec<u64> = store
+            .get_task_transitions("task")
+            .unwrap()
+            .iter()
+            .map(|transition| transition.at)
+            .collect();
+        assert_eq!(at, vec![100, 200, 300]);
+        //  The task waits from the time its executor was removed
+        let waiting = state.task_queues_by_extraction_policy.unallocated_for(
+            Duration::ZERO,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(360),
+        );
+        assert_eq!(waiting, vec![("task".to_string(), Duration::from_secs(60))]);
+    }
+
+    #[tokio::test]
+    async fn test_state_change_backlog() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_log_store, store) = new_storage(
+            dir.path().join("db"),
+            dir.path().join("snapshot"),
+            InvariantValidation::LogOnly,
+        )
+        .await
+        .unwrap();
+        let state = &store.data.indexify_state;
+        let now = timestamp_secs();
+        let changes = state
+            .apply_state_machine_updates(
+                StateMachineUpdateRequest {
+                    payload: RequestPayload::MarkStateChangesProcessed {
+                        state_changes: vec![],
+                    },
+                    new_state_changes: vec![
+                        StateChange::new(
+                            "executor_1".to_string(),
+                            ChangeType::ExecutorAdded,
+                            now - 120,
+                        ),
+                        StateChange::new(
+                            "executor_2".to_string(),
+                            ChangeType::ExecutorAdded,
+                            now - 10,
+                        ),
+                    ],
+                    state_changes_processed: vec![],
+                },
+                &store.db,
+            )
+            .unwrap();
+        let (count, age) = store.state_change_backlog().unwrap();
+        assert_eq!(count, 2);
+        assert!(age >= 120);
+        assert_eq!(store.state_changes_processed(), 0);
+
+        //  The scheduler marks the changes it handled processed, marking one
+        //  again doesn't count it twice
+        let mark_processed = || {
+            state
+                .apply_state_machine_updates(
+                    StateMachineUpdateRequest {
+                        payload: RequestPayload::MarkStateChangesProcessed {
+                            state_changes: vec![StateChangeProcessed {
+                                state_change_id: changes[0].id,
+                                processed_at: now,
+                            }],
+                        },
+                        new_state_changes: vec![],
+                        state_changes_processed: vec![],
+                    },
+                    &store.db,
+                )
+                .unwrap()
+        };
+        mark_processed();
+        mark_processed();
+        let (count, age) = store.state_change_backlog().unwrap();
+        assert_eq!(count, 1);
+        assert!((10..120).contains(&age));
+        assert_eq!(store.state_changes_processed(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tasks_for_executor_by_priority() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_log_store, store) = new_storage(
+            dir.path().join("db"),
+            dir.path().join("snapshot"),
+            InvariantValidation::LogOnly,
+        )
+        .await
+        .unwrap();
+        let executor_id = "executor".to_string();
+        let task = |id: &str, priority: i32| Task {
+            id: id.to_string(),
+            extractor: "extractor".to_string(),
+            extraction_policy_id: "policy".to_string(),
+            content_metadata: ContentMetadata {
+                id: ContentMetadataId::new(&format!("content-{}", id)),
+                priority,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        for payload in [
+            RequestPayload::RegisterExecutor {
+                addr: "localhost:8950".to_string(),
+                executor_id: executor_id.clone(),
+                extractors: vec![ExtractorDescription {
+                    name: "extractor".to_string(),
+                    ..Default::default()
+                }],
+                cost_per_hour: None,
+                capacity_weight: None,
+                zone: None,
+                max_payload_bytes: None,
+                download_bytes_per_sec: None,
+                gpu_devices: Vec::new(),
+                graph_versions: Vec::new(),
+                ts_secs: 0,
+            },
+            RequestPayload::CreateTasks {
+                tasks: vec![
+                    task("backfill_1", 0),
+                    task("interactive", 10),
+                    task("backfill_2", 0),
+                ],
+                ts_secs: 0,
+            },
+            RequestPayload::AssignTask {
+                assignments: ["backfill_1", "interactive", "backfill_2"]
+                    .into_iter()
+                    .map(|task_id| (task_id.to_string(), executor_id.clone()))
+                    .collect(),
+                assigned_at: None,
+                gpu_devices: HashMap::new(),
+                burst_tasks: HashSet::new(),
+                content_zones: HashMap::new(),
+            },
+        ] {
+            store
+                .data
+                .indexify_state
+                .apply_state_machine_updates(
+                    StateMachineUpdateRequest {
+                        payload,
+                        new_state_changes: vec![],
+                        state_changes_processed: vec![],
+                    },
+                    &store.db,
+                )
+                .unwrap();
+        }
+
+        let tasks = store
+            .get_tasks_for_executor(&executor_id, Some(1))
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, "interactive");
+
+        let tasks = store
+            .get_tasks_for_executor(&executor_id, None)
+            .await
+            .unwrap();
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].id, "interactive");
+    }
 
     /// This is a dummy test which forces building a snapshot on the cluster by
     /// passing in some overrides Manually check that the snapshot file was
     /// actually created. Still need to find a way to force reading and
     /// deserialization
+    #[tokio::test]
+    async fn test_restart_resumes_from_applied_log_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let open = || {
+            new_storage(
+                dir.path().join("db"),
+                dir.path().join("snapshot"),
+                InvariantValidation::LogOnly,
+            )
+        };
+        let log_id = |index| LogId::new(CommittedLeaderId::new(1, 1), index);
+        let task = |id: &str| Task {
+            id: id.to_string(),
+            extractor: "extractor".to_string(),
+            extraction_policy_id: "policy".to_string(),
+            namespace: "namespace".to_string(),
+            extraction_graph_name: "graph".to_string(),
+            content_metadata: ContentMetadata {
+                id: ContentMetadataId::new("root"),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        //  The content an extraction graph extracts, for its cap of active
+        //  content
+        let graph_id = ExtractionGraph::create_id("graph", "namespace");
+
+        let (log_store, mut store) = open().await.unwrap();
+        store
+            .apply(vec![openraft::Entry::<crate::state::TypeConfig> {
+                log_id: log_id(1),
+                payload: EntryPayload::Normal(StateMachineUpdateRequest {
+                    payload: RequestPayload::CreateTasks {
+                        tasks: vec![task("task_1"), task("task_2")],
+                        ts_secs: 0,
+                    },
+                    new_state_changes: vec![],
+                    state_changes_processed: vec![],
+                }),
+            }])
+            .await
+            .unwrap();
+        drop(log_store);
+        drop(store);
+
+        //  The indexes weren't snapshotted yet, so they are rebuilt from the
+        //  columns, and the applied entry isn't applied again
+        let (log_store, mut store) = open().await.unwrap();
+        let (last_applied_log_id, _) = store.applied_state().await.unwrap();
+        assert_eq!(last_applied_log_id, Some(log_id(1)));
+        let state = &store.data.indexify_state;
+        assert_eq!(
+            state.get_unassigned_tasks(),
+            HashSet::from(["task_1".to_string(), "task_2".to_string()])
+        );
+        assert!(!state.are_content_tasks_completed(&ContentMetadataId::new("root")));
+        assert_eq!(
+            state.active_root_content(&graph_id),
+            HashSet::from(["root".to_string()])
+        );
+
+        //  Indexes snapshotted at the last applied entry are restored as is,
+        //  rather than rebuilt
+        state.unassigned_tasks.remove(&"task_2".to_string());
+        store.write_indexes_snapshot_().await.unwrap();
+        drop(log_store);
+        drop(store);
+
+        let (log_store, mut store) = open().await.unwrap();
+        let state = &store.data.indexify_state;
+        assert_eq!(
+            state.get_unassigned_tasks(),
+            HashSet::from(["task_1".to_string()])
+        );
+        assert!(!state.are_content_tasks_completed(&ContentMetadataId::new("root")));
+        assert_eq!(
+            state.active_root_content(&graph_id),
+            HashSet::from(["root".to_string()])
+        );
+
+        //  A snapshot taken before the last applied entry is ignored and the
+        //  indexes are rebuilt
+        store
+            .apply(vec![openraft::Entry::<crate::state::TypeConfig> {
+                log_id: log_id(2),
+                payload: EntryPayload::Blank,
+            }])
+            .await
+            .unwrap();
+        drop(log_store);
+        drop(store);
+
+        let (_log_store, mut store) = open().await.unwrap();
+        let (last_applied_log_id, _) = store.applied_state().await.unwrap();
+        assert_eq!(last_applied_log_id, Some(log_id(2)));
+        assert_eq!(
+            store.data.indexify_state.get_unassigned_tasks(),
+            HashSet::from(["task_1".to_string(), "task_2".to_string()])
+        );
+    }
+
     #[tokio::test]
     #[tracing_test::traced_test]
     async fn test_install_snapshot() -> anyhow::Result<()> {
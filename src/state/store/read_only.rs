@@ -0,0 +1,66 @@
+//! Reads the state directory of a stopped server without writing to it, for
+//! commands that inspect a copy of the state of a cluster.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use indexify_internal_api as internal_api;
+use rocksdb::{IteratorMode, Options, DB};
+use serde::de::DeserializeOwned;
+
+use super::{
+    serializer::{JsonEncode, JsonEncoder},
+    state_machine_objects::SCHEDULER_SETTINGS_KEY,
+    tuning::scan_read_options,
+    StateMachineColumns,
+};
+
+/// The database of a state directory opened read only. Nothing is created or
+/// recorded in the directory, and the column families missing from it read as
+/// empty.
+pub struct ReadOnlyState {
+    db: DB,
+}
+
+impl ReadOnlyState {
+    pub fn open(db_path: &Path) -> Result<Self> {
+        let opts = Options::default();
+        let column_families = DB::list_cf(&opts, db_path)
+            .map_err(|e| anyhow!("unable to list the column families: {}", e))?;
+        let db = DB::open_cf_for_read_only(&opts, db_path, column_families, false)?;
+        Ok(Self { db })
+    }
+
+    /// Rows of a column family, with their keys as strings
+    pub fn rows<V: DeserializeOwned>(
+        &self,
+        column: StateMachineColumns,
+    ) -> Result<Vec<(String, V)>> {
+        let Some(cf) = self.db.cf_handle(column.as_ref()) else {
+            return Ok(Vec::new());
+        };
+        self.db
+            .iterator_cf_opt(cf, scan_read_options(), IteratorMode::Start)
+            .map(|item| {
+                let (key, value) = item?;
+                let value = JsonEncoder::decode(&value)
+                    .map_err(|e| anyhow!("unable to decode a row of {}: {}", column, e))?;
+                Ok((String::from_utf8_lossy(&key).to_string(), value))
+            })
+            .collect()
+    }
+
+    pub fn scheduler_settings(&self) -> Result<internal_api::SchedulerSettings> {
+        let Some(cf) = self
+            .db
+            .cf_handle(StateMachineColumns::SchedulerSettings.as_ref())
+        else {
+            return Ok(Default::default());
+        };
+        match self.db.get_cf(cf, SCHEDULER_SETTINGS_KEY)? {
+            Some(value) => JsonEncoder::decode(&value)
+                .map_err(|e| anyhow!("unable to decode the scheduler settings: {}", e)),
+            None => Ok(Default::default()),
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use std::{collections::HashMap, time::SystemTime};
+use std::{
+    collections::{HashMap, HashSet},
+    time::SystemTime,
+};
 
 use indexify_internal_api as internal_api;
 use internal_api::{StateChange, StateChangeId};
@@ -14,6 +17,58 @@ pub struct StateMachineUpdateRequest {
     pub state_changes_processed: Vec<StateChangeProcessed>,
 }
 
+impl StateMachineUpdateRequest {
+    /// Latest time the request carries, in seconds since the epoch, from the
+    /// state changes it creates or processes and the times in its payload.
+    /// Requests without state changes or times in their payload have none.
+    pub fn timestamp(&self) -> Option<u64> {
+        let secs = |time: &SystemTime| {
+            time.duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs())
+        };
+        let payload_times: Vec<u64> = match &self.payload {
+            RequestPayload::RegisterExecutor { ts_secs, .. } |
+            RequestPayload::RenewExecutorLease { ts_secs, .. } |
+            RequestPayload::RemoveExecutor { ts_secs, .. } |
+            RequestPayload::CreateTasks { ts_secs, .. } |
+            RequestPayload::RepairContent { ts_secs, .. } |
+            RequestPayload::ReapOrphanedAssignments { ts_secs, .. } |
+            RequestPayload::ShedBurstTasks { ts_secs, .. } => vec![*ts_secs],
+            RequestPayload::AssignTask { assigned_at, .. } => {
+                assigned_at.iter().map(secs).collect()
+            }
+            RequestPayload::UpdateTask { update_time, .. } |
+            RequestPayload::UpdateTasks { update_time, .. } => vec![secs(update_time)],
+            RequestPayload::RecordEgress { time, .. } => vec![secs(time)],
+            RequestPayload::CreateReplay { replay } => vec![replay.started_at],
+            RequestPayload::SetGraphTemplate { template } => vec![template.created_at],
+            RequestPayload::SetCancelTasksJob { job } => vec![job.updated_at],
+            RequestPayload::UpdateContentReplicas { added, .. } => {
+                added.iter().map(|replica| replica.replicated_at).collect()
+            }
+            RequestPayload::UpdateTaskProgress { progress, .. } => progress
+                .values()
+                .map(|progress| progress.updated_at)
+                .collect(),
+            RequestPayload::MarkStateChangesProcessed { state_changes } => state_changes
+                .iter()
+                .map(|change| change.processed_at)
+                .collect(),
+            _ => vec![],
+        };
+        self.new_state_changes
+            .iter()
+            .map(|change| change.created_at)
+            .chain(
+                self.state_changes_processed
+                    .iter()
+                    .map(|change| change.processed_at),
+            )
+            .chain(payload_times)
+            .max()
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct StateChangeProcessed {
     pub state_change_id: StateChangeId,
@@ -26,6 +81,13 @@ pub struct CreateOrUpdateContentEntry {
     pub previous_parent: Option<internal_api::ContentMetadataId>,
 }
 
+/// A task an executor finished, with the executor whose slot it releases
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TaskUpdate {
+    pub task: internal_api::Task,
+    pub executor_id: Option<ExecutorId>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum RequestPayload {
     //  NOTE: This isn't strictly a state machine update. It's used to change cluster membership.
@@ -33,25 +95,112 @@ pub enum RequestPayload {
         node_id: NodeId,
         address: String,
         coordinator_addr: String,
+        /// State format version of the joining node
+        #[serde(default = "super::state_version::unversioned_state_format")]
+        state_format_version: u32,
     },
     // TODO: edwin
     RegisterExecutor {
         addr: String,
         executor_id: String,
         extractors: Vec<internal_api::ExtractorDescription>,
+        #[serde(default)]
+        cost_per_hour: Option<f64>,
+        #[serde(default)]
+        capacity_weight: Option<u32>,
+        #[serde(default)]
+        zone: Option<String>,
+        #[serde(default)]
+        max_payload_bytes: Option<u64>,
+        #[serde(default)]
+        download_bytes_per_sec: Option<u64>,
+        #[serde(default)]
+        gpu_devices: Vec<u32>,
+        #[serde(default)]
+        graph_versions: Vec<internal_api::GraphVersionRange>,
         ts_secs: u64,
     },
     RemoveExecutor {
         executor_id: String,
+        /// The executor left on its own, its tasks aren't counted as lost
+        #[serde(default)]
+        graceful: bool,
+        /// Time the executor was removed, which its tasks are requeued at
+        #[serde(default)]
+        ts_secs: u64,
+    },
+    DrainExecutor {
+        executor_id: String,
+    },
+    /// Sets the pressure an executor reported, or clears it
+    SetExecutorPressure {
+        executor_id: String,
+        pressure: Option<internal_api::ExecutorPressure>,
+    },
+    RenewExecutorLease {
+        executor_id: String,
+        ts_secs: u64,
     },
     CreateNamespace {
         name: String,
     },
     CreateTasks {
         tasks: Vec<internal_api::Task>,
+        /// Time the tasks were created, which they are queued at
+        #[serde(default)]
+        ts_secs: u64,
+    },
+    /// Re-creates the missing tasks of a root content and resets its
+    /// outstanding task count to the work that is actually pending
+    RepairContent {
+        root_content_id: String,
+        tasks: Vec<internal_api::Task>,
+        /// Unfinished tasks and unprocessed state changes of the content
+        /// found before the request was sent, counted if they are still
+        /// pending when it's applied
+        #[serde(default)]
+        pending_task_ids: Vec<TaskId>,
+        #[serde(default)]
+        pending_state_change_ids: Vec<StateChangeId>,
+        /// Time the tasks were re-created, which they are queued at
+        #[serde(default)]
+        ts_secs: u64,
     },
     AssignTask {
         assignments: HashMap<TaskId, ExecutorId>,
+        /// Task seconds are metered from this time to the time the task
+        /// finishes
+        #[serde(default)]
+        assigned_at: Option<SystemTime>,
+        /// GPU device the tasks taking a share of a GPU are packed onto,
+        /// picked by the planner
+        #[serde(default)]
+        gpu_devices: HashMap<TaskId, Option<u32>>,
+        /// Tasks assigned above the limit of tasks per executor, shed first
+        /// when their executor has to give tasks back
+        #[serde(default)]
+        burst_tasks: HashSet<TaskId>,
+        /// Root content and zone of the executor of the tasks of content
+        /// placed by zone, counted to keep placing the content by zone
+        #[serde(default)]
+        content_zones: HashMap<TaskId, (String, String)>,
+    },
+    /// Drops the assignments of finished or deleted tasks and puts unfinished
+    /// tasks that no executor holds back into the unassigned tasks
+    ReapOrphanedAssignments {
+        finished_task_assignments: HashMap<ExecutorId, HashSet<TaskId>>,
+        unallocated_tasks: HashSet<TaskId>,
+        /// Time the orphans were reaped, which the tasks are requeued at
+        #[serde(default)]
+        ts_secs: u64,
+    },
+    /// Takes burst tasks back from executors that hold more tasks than they
+    /// may, and puts them back into the unassigned tasks
+    ShedBurstTasks {
+        tasks: HashMap<ExecutorId, HashSet<TaskId>>,
+        /// Time the tasks were shed, which they are requeued at
+        #[serde(default)]
+        ts_secs: u64,
     },
     CreateOrAssignGarbageCollectionTask {
         gc_tasks: Vec<internal_api::GarbageCollectionTask>,
@@ -64,6 +213,9 @@ pub enum RequestPayload {
         extraction_graph: internal_api::ExtractionGraph,
         structured_data_schema: internal_api::StructuredDataSchema,
         indexes: Vec<internal_api::Index>,
+        /// Recorded with the definition in the history of the graph
+        #[serde(default)]
+        change: internal_api::ExtractionGraphChange,
     },
     CreateOrUpdateContent {
         entries: Vec<CreateOrUpdateContentEntry>,
@@ -79,9 +231,71 @@ pub enum RequestPayload {
         executor_id: Option<String>,
         update_time: SystemTime,
     },
+    /// Finalizes the tasks of a bundle in one write
+    UpdateTasks {
+        updates: Vec<TaskUpdate>,
+        update_time: SystemTime,
+    },
     MarkStateChangesProcessed {
         state_changes: Vec<StateChangeProcessed>,
     },
+    /// Sets a key of the context of a root content, or removes it when
+    /// `value` is unset
+    SetContentContext {
+        root_content_id: String,
+        key: String,
+        value: Option<serde_json::Value>,
+    },
+    SetSchedulerSettings {
+        settings: internal_api::SchedulerSettings,
+    },
+    SetMaintenanceMode {
+        maintenance: internal_api::MaintenanceMode,
+    },
+    /// Adds bytes of content downloaded from a namespace to its metering
+    /// record
+    RecordEgress {
+        namespace: String,
+        bytes: u64,
+        time: SystemTime,
+    },
+    /// Drops the diagnostics of finished tasks past their retention
+    ClearTaskDiagnostics {
+        task_ids: Vec<TaskId>,
+    },
+    UpdateTaskProgress {
+        executor_id: ExecutorId,
+        progress: HashMap<TaskId, internal_api::TaskProgress>,
+    },
+    /// Records a replay before its tasks run again
+    CreateReplay {
+        replay: internal_api::Replay,
+    },
+    /// Creates a graph template or replaces the template with its name
+    SetGraphTemplate {
+        template: internal_api::GraphTemplate,
+    },
+    DeleteGraphTemplate {
+        name: String,
+    },
+    /// Records a cancel tasks job or its progress
+    SetCancelTasksJob {
+        job: internal_api::CancelTasksJob,
+    },
+    /// Forgets cancel tasks jobs past their retention
+    DeleteCancelTasksJobs {
+        job_ids: Vec<String>,
+    },
+    /// Records copies of content verified in the blob store of another
+    /// region, and forgets the copies removed from it
+    UpdateContentReplicas {
+        added: Vec<internal_api::ContentReplica>,
+        removed: Vec<String>,
+        /// Content id -> hash of the content the replicator read, none when
+        /// the content was deleted. The content is no longer pending unless
+        /// it changed since.
+        processed: HashMap<String, Option<String>>,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -0,0 +1,606 @@
+use std::{fs, io::Read, path::Path};
+
+use anyhow::{anyhow, Result};
+use flate2::bufread::ZlibDecoder;
+use openraft::{storage::RaftLogStorage, Entry, LogId};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use super::{
+    id_to_bin,
+    new_storage,
+    serializer::{JsonEncode, JsonEncoder},
+    state_machine_objects::IndexifyStateSnapshot,
+    state_version::{self, StateVersion},
+    StoredSnapshot,
+};
+use crate::{
+    blob_storage::ContentReader,
+    server_config::InvariantValidation,
+    state::{NodeId, TypeConfig},
+};
+
+/// Name of the manifest at the root of an archive
+pub const MANIFEST_NAME: &str = "manifest.json";
+
+/// Describes an archived checkpoint of a server: its raft snapshot file and
+/// the segments of the raft log committed after it, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub format_version: u32,
+    pub server_version: String,
+    #[serde(default)]
+    pub created_at: u64,
+    pub snapshot: ArchivedObject,
+    #[serde(default)]
+    pub wal_segments: Vec<ArchivedObject>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedObject {
+    /// Path of the object relative to the root of the archive
+    pub name: String,
+    pub size_bytes: u64,
+    /// Hex encoded sha256 of the object
+    pub sha256: String,
+}
+
+/// A log entry of a segment, with the time it was written to the log of the
+/// server that archived it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedLogEntry {
+    #[serde(flatten)]
+    pub entry: Entry<TypeConfig>,
+    /// Seconds since the epoch, none in segments of servers that didn't
+    /// record it
+    #[serde(default)]
+    pub written_at: Option<u64>,
+}
+
+/// A task an executor was running when the snapshot was taken
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InFlightTask {
+    pub task_id: String,
+    pub executor_id: String,
+    pub namespace: String,
+    pub extraction_policy_id: String,
+    pub content_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestoreReport {
+    pub snapshot_log_id: Option<LogId<NodeId>>,
+    pub last_log_id: Option<LogId<NodeId>>,
+    /// Log entries after the snapshot that are applied when the server starts
+    pub replayed_entries: usize,
+    /// Archived log entries after the restore point that were left out
+    pub skipped_entries: usize,
+    pub in_flight_tasks: Vec<InFlightTask>,
+}
+
+/// Downloads the archive at `from` and rebuilds the state directory from it.
+/// Every object is checked against the manifest before anything is written.
+/// With `until`, only the log entries written up to that time, in seconds
+/// since the epoch, are kept.
+pub async fn restore(
+    reader: &ContentReader,
+    from: &str,
+    state_dir: &Path,
+    until: Option<u64>,
+) -> Result<RestoreReport> {
+    let from = from.trim_end_matches('/');
+    let manifest = reader.bytes(&format!("{}/{}", from, MANIFEST_NAME)).await?;
+    let manifest: ArchiveManifest = serde_json::from_slice(&manifest)
+        .map_err(|e| anyhow!("unable to read the manifest of {}: {}", from, e))?;
+    info!(
+        "restoring archive of indexify {} created at {} with {} log segments",
+        manifest.server_version,
+        manifest.created_at,
+        manifest.wal_segments.len()
+    );
+    let snapshot = download(reader, from, &manifest.snapshot).await?;
+    let mut segments = Vec::with_capacity(manifest.wal_segments.len());
+    for segment in &manifest.wal_segments {
+        segments.push(download(reader, from, segment).await?);
+    }
+    restore_state(&manifest, &snapshot, &segments, state_dir, until).await
+}
+
+async fn download(reader: &ContentReader, from: &str, object: &ArchivedObject) -> Result<Vec<u8>> {
+    let bytes = reader.bytes(&format!("{}/{}", from, object.name)).await?;
+    verify(object, &bytes)?;
+    Ok(bytes.to_vec())
+}
+
+fn verify(object: &ArchivedObject, bytes: &[u8]) -> Result<()> {
+    if bytes.len() as u64 != object.size_bytes {
+        return Err(anyhow!(
+            "{} is {} bytes, the manifest expects {}",
+            object.name,
+            bytes.len(),
+            object.size_bytes
+        ));
+    }
+    let sha256 = format!("{:x}", Sha256::digest(bytes));
+    if !sha256.eq_ignore_ascii_case(&object.sha256) {
+        return Err(anyhow!(
+            "checksum of {} doesn't match the manifest",
+            object.name
+        ));
+    }
+    Ok(())
+}
+
+/// Inverse of `write_compressed` for bytes already in memory
+fn decompress(bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    ZlibDecoder::new(bytes).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+/// Decodes the log segments and keeps the entries that follow the snapshot.
+/// Segments may overlap but must not leave a gap after the snapshot.
+fn log_entries_after(
+    snapshot_log_id: Option<LogId<NodeId>>,
+    segments: &[Vec<u8>],
+) -> Result<Vec<ArchivedLogEntry>> {
+    let mut next_index = snapshot_log_id.map_or(0, |log_id| log_id.index + 1);
+    let mut entries = Vec::new();
+    for segment in segments {
+        let segment: Vec<ArchivedLogEntry> = JsonEncoder::decode(&decompress(segment)?)?;
+        for entry in segment {
+            let index = entry.entry.log_id.index;
+            if index < next_index {
+                continue;
+            }
+            if index > next_index {
+                return Err(anyhow!(
+                    "log entries {} to {} are missing from the archive",
+                    next_index,
+                    index - 1
+                ));
+            }
+            next_index += 1;
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// Keeps the log entries written at or before `until`. Segments archived
+/// without the times the entries were written can't be restored to a point in
+/// time.
+fn entries_until(mut entries: Vec<ArchivedLogEntry>, until: u64) -> Result<Vec<ArchivedLogEntry>> {
+    let mut keep = 0;
+    for entry in &entries {
+        match entry.written_at {
+            Some(written_at) if written_at > until => break,
+            Some(_) => keep += 1,
+            None => {
+                return Err(anyhow!(
+                    "log entry {} was archived without the time it was written, restore it without --until",
+                    entry.entry.log_id.index
+                ))
+            }
+        }
+    }
+    entries.truncate(keep);
+    Ok(entries)
+}
+
+/// Rebuilds the state directory from downloaded and verified archive objects
+async fn restore_state(
+    manifest: &ArchiveManifest,
+    snapshot: &[u8],
+    segments: &[Vec<u8>],
+    state_dir: &Path,
+    until: Option<u64>,
+) -> Result<RestoreReport> {
+    state_version::check_state_version(&StateVersion {
+        format_version: manifest.format_version,
+        server_version: manifest.server_version.clone(),
+    })?;
+    let snapshot: StoredSnapshot = JsonEncoder::decode(&decompress(snapshot)?)
+        .map_err(|e| anyhow!("unable to decode the snapshot: {}", e))?;
+    let state: IndexifyStateSnapshot = JsonEncoder::decode(&snapshot.data)
+        .map_err(|e| anyhow!("unable to decode the state in the snapshot: {}", e))?;
+    let snapshot_log_id = snapshot.meta.last_log_id;
+    let mut entries = log_entries_after(snapshot_log_id, segments)?;
+    let archived_entries = entries.len();
+    if let Some(until) = until {
+        if let Some(changed_at) = state.latest_change_time().filter(|time| *time > until) {
+            return Err(anyhow!(
+                "the snapshot holds state changes from {}, after {}, restore from an older archive",
+                changed_at,
+                until
+            ));
+        }
+        entries = entries_until(entries, until)?;
+    }
+
+    let db_path = state_dir.join("db");
+    let snapshot_path = state_dir.join("sm-blob");
+    if db_path.exists() || snapshot_path.exists() {
+        return Err(anyhow!(
+            "{} already holds state, restore into an empty state directory",
+            state_dir.display()
+        ));
+    }
+    fs::create_dir_all(state_dir)?;
+    let last_log_id = match write_state(&db_path, &snapshot_path, snapshot, &entries).await {
+        Ok(last_log_id) => last_log_id,
+        Err(e) => {
+            //  Don't leave a partial state behind for a server to start from
+            let _ = fs::remove_dir_all(&db_path);
+            let _ = fs::remove_file(&snapshot_path);
+            return Err(e);
+        }
+    };
+
+    let in_flight_tasks = state
+        .in_flight_tasks()
+        .into_iter()
+        .map(|(task, executor_id)| InFlightTask {
+            task_id: task.id.clone(),
+            executor_id: executor_id.clone(),
+            namespace: task.namespace.clone(),
+            extraction_policy_id: task.extraction_policy_id.clone(),
+            content_id: task.content_metadata.id.id.clone(),
+        })
+        .collect();
+    Ok(RestoreReport {
+        snapshot_log_id,
+        last_log_id,
+        replayed_entries: entries.len(),
+        skipped_entries: archived_entries - entries.len(),
+        in_flight_tasks,
+    })
+}
+
+/// Installs the snapshot and writes the log entries after it as committed, so
+/// that the server applies them when it starts
+async fn write_state(
+    db_path: &Path,
+    snapshot_path: &Path,
+    snapshot: StoredSnapshot,
+    entries: &[ArchivedLogEntry],
+) -> Result<Option<LogId<NodeId>>> {
+    let (mut log_store, state_machine) =
+        new_storage(db_path, snapshot_path, InvariantValidation::default()).await?;
+    let snapshot_log_id = snapshot.meta.last_log_id;
+    state_machine
+        .update_state_machine_(snapshot.clone())
+        .await
+        .map_err(|e| anyhow!("unable to install the snapshot: {}", e))?;
+    state_machine
+        .set_current_snapshot_(snapshot)
+        .map_err(|e| anyhow!("unable to write the snapshot: {}", e))?;
+
+    for ArchivedLogEntry { entry, written_at } in entries {
+        let id = id_to_bin(entry.log_id.index);
+        log_store
+            .db
+            .put_cf(log_store.logs(), &id, JsonEncoder::encode(entry)?)?;
+        if let Some(written_at) = written_at {
+            log_store
+                .db
+                .put_cf(log_store.log_times(), &id, JsonEncoder::encode(written_at)?)?;
+        }
+    }
+    if let Some(log_id) = snapshot_log_id {
+        log_store
+            .set_last_purged_(log_id)
+            .map_err(|e| anyhow!("unable to write the log: {}", e))?;
+    }
+    let last_log_id = entries
+        .last()
+        .map(|entry| entry.entry.log_id)
+        .or(snapshot_log_id);
+    log_store
+        .set_committed_(&last_log_id)
+        .map_err(|e| anyhow!("unable to write the log: {}", e))?;
+
+    //  Read the log back to check that it was written as a server will see it
+    let log_state = log_store
+        .get_log_state()
+        .await
+        .map_err(|e| anyhow!("unable to read the restored log: {}", e))?;
+    if log_state.last_log_id != last_log_id {
+        return Err(anyhow!(
+            "restored log ends at {:?} instead of {:?}",
+            log_state.last_log_id,
+            last_log_id
+        ));
+    }
+    Ok(last_log_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, HashSet},
+        path::PathBuf,
+        sync::Arc,
+    };
+
+    use indexify_internal_api::{ContentMetadata, ContentMetadataId, ExtractorDescription, Task};
+    use openraft::{
+        CommittedLeaderId,
+        EntryPayload,
+        RaftLogReader,
+        SnapshotMeta,
+        StoredMembership,
+    };
+
+    use super::*;
+    use crate::{
+        server_config::ServerConfig,
+        state::store::{
+            requests::{RequestPayload, StateMachineUpdateRequest},
+            write_compressed,
+            StateMachineColumns,
+        },
+    };
+
+    fn log_id(index: u64) -> LogId<NodeId> {
+        LogId::new(CommittedLeaderId::new(1, 1), index)
+    }
+
+    fn archive_object(archive: &Path, name: &str, data: &[u8]) -> ArchivedObject {
+        let path = archive.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        write_compressed(&path, data).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        ArchivedObject {
+            name: name.to_string(),
+            size_bytes: bytes.len() as u64,
+            sha256: format!("{:x}", Sha256::digest(&bytes)),
+        }
+    }
+
+    /// Archives the state of a server with one task running and one waiting,
+    /// snapshotted at log index 5 and followed by log entries 4 to 7, written
+    /// at 100 plus their index
+    async fn create_archive(archive: &Path) {
+        let source = tempfile::tempdir().unwrap();
+        let (_log_store, store) = new_storage(
+            source.path().join("db"),
+            source.path().join("sm-blob"),
+            InvariantValidation::LogOnly,
+        )
+        .await
+        .unwrap();
+        let apply = |payload| {
+            store
+                .data
+                .indexify_state
+                .apply_state_machine_updates(
+                    StateMachineUpdateRequest {
+                        payload,
+                        new_state_changes: vec![],
+                        state_changes_processed: vec![],
+                    },
+                    &store.db,
+                )
+                .unwrap();
+        };
+        let task = |id: &str| Task {
+            id: id.to_string(),
+            extractor: "extractor".to_string(),
+            extraction_policy_id: "policy".to_string(),
+            namespace: "default".to_string(),
+            content_metadata: ContentMetadata {
+                id: ContentMetadataId::new(&format!("content-{}", id)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        apply(RequestPayload::RegisterExecutor {
+            addr: "localhost:8950".to_string(),
+            executor_id: "executor".to_string(),
+            extractors: vec![ExtractorDescription {
+                name: "extractor".to_string(),
+                ..Default::default()
+            }],
+            cost_per_hour: None,
+            capacity_weight: None,
+            zone: None,
+            max_payload_bytes: None,
+            download_bytes_per_sec: None,
+            gpu_devices: Vec::new(),
+            graph_versions: Vec::new(),
+            ts_secs: 0,
+        });
+        apply(RequestPayload::CreateTasks {
+            tasks: vec![task("task_1"), task("task_2")],
+            ts_secs: 0,
+        });
+        apply(RequestPayload::AssignTask {
+            assignments: HashMap::from([("task_1".to_string(), "executor".to_string())]),
+            assigned_at: None,
+            gpu_devices: HashMap::new(),
+            burst_tasks: HashSet::new(),
+            content_zones: HashMap::new(),
+        });
+
+        let state = store.data.indexify_state.build_snapshot(&store.db).unwrap();
+        let snapshot = StoredSnapshot {
+            meta: SnapshotMeta {
+                last_log_id: Some(log_id(5)),
+                last_membership: StoredMembership::default(),
+                snapshot_id: "1-1-5".to_string(),
+            },
+            data: JsonEncoder::encode(&state).unwrap(),
+        };
+        let entries: Vec<ArchivedLogEntry> = (4..=7)
+            .map(|index| ArchivedLogEntry {
+                entry: Entry {
+                    log_id: log_id(index),
+                    payload: EntryPayload::Blank,
+                },
+                written_at: Some(100 + index),
+            })
+            .collect();
+        let manifest = ArchiveManifest {
+            format_version: state_version::STATE_FORMAT_VERSION,
+            server_version: "0.0.1".to_string(),
+            created_at: 0,
+            snapshot: archive_object(
+                archive,
+                "snapshot",
+                &JsonEncoder::encode(&snapshot).unwrap(),
+            ),
+            wal_segments: vec![archive_object(
+                archive,
+                "wal/00000000000000000004",
+                &JsonEncoder::encode(&entries).unwrap(),
+            )],
+        };
+        fs::write(
+            archive.join(MANIFEST_NAME),
+            serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore() {
+        let archive = tempfile::tempdir().unwrap();
+        create_archive(archive.path()).await;
+        let from = format!("file://{}", archive.path().display());
+        let reader = ContentReader::new(Arc::new(ServerConfig::default()));
+        let state_dir = tempfile::tempdir().unwrap();
+        let state_dir: PathBuf = state_dir.path().join("state");
+
+        let report = restore(&reader, &from, &state_dir, None).await.unwrap();
+        assert_eq!(report.snapshot_log_id, Some(log_id(5)));
+        assert_eq!(report.last_log_id, Some(log_id(7)));
+        assert_eq!(report.replayed_entries, 2);
+        assert_eq!(
+            report.in_flight_tasks,
+            vec![InFlightTask {
+                task_id: "task_1".to_string(),
+                executor_id: "executor".to_string(),
+                namespace: "default".to_string(),
+                extraction_policy_id: "policy".to_string(),
+                content_id: "content-task_1".to_string(),
+            }]
+        );
+
+        //  A server opening the state finds the snapshot and the log after it
+        let (mut log_store, store) = new_storage(
+            state_dir.join("db"),
+            state_dir.join("sm-blob"),
+            InvariantValidation::LogOnly,
+        )
+        .await
+        .unwrap();
+        assert!(store
+            .get_from_cf::<Task, _>(StateMachineColumns::Tasks, "task_2")
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            *store.data.last_applied_log_id.read().await,
+            Some(log_id(5))
+        );
+        let entries = log_store.try_get_log_entries(0..).await.unwrap();
+        assert_eq!(
+            entries.iter().map(|e| e.log_id.index).collect::<Vec<_>>(),
+            vec![6, 7]
+        );
+        assert_eq!(log_store.read_committed().await.unwrap(), Some(log_id(7)));
+        drop((log_store, store));
+
+        //  Existing state is never overwritten
+        assert!(restore(&reader, &from, &state_dir, None).await.is_err());
+
+        //  A restore to a point in time keeps the entries written up to it,
+        //  along with the times they were written
+        let state_dir = tempfile::tempdir().unwrap();
+        let report = restore(&reader, &from, state_dir.path(), Some(106))
+            .await
+            .unwrap();
+        assert_eq!(report.last_log_id, Some(log_id(6)));
+        assert_eq!(report.replayed_entries, 1);
+        assert_eq!(report.skipped_entries, 1);
+        let (log_store, _store) = new_storage(
+            state_dir.path().join("db"),
+            state_dir.path().join("sm-blob"),
+            InvariantValidation::LogOnly,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            log_store.get_log_times_(0..=10).unwrap(),
+            HashMap::from([(6, 106)])
+        );
+    }
+
+    #[test]
+    fn test_entries_until() {
+        let entry = |index, written_at| ArchivedLogEntry {
+            entry: Entry {
+                log_id: log_id(index),
+                payload: EntryPayload::Blank,
+            },
+            written_at,
+        };
+        let entries = vec![
+            entry(1, Some(100)),
+            entry(2, Some(100)),
+            entry(3, Some(200)),
+            entry(4, Some(300)),
+        ];
+        let indexes = |entries: Result<Vec<ArchivedLogEntry>>| {
+            entries
+                .unwrap()
+                .iter()
+                .map(|entry| entry.entry.log_id.index)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(indexes(entries_until(entries.clone(), 250)), vec![1, 2, 3]);
+        assert_eq!(indexes(entries_until(entries.clone(), 300)).len(), 4);
+        assert!(indexes(entries_until(entries.clone(), 50)).is_empty());
+
+        //  Segments without write times can only be restored entirely
+        let mut untimed = entries;
+        untimed.push(entry(5, None));
+        assert_eq!(indexes(entries_until(untimed.clone(), 250)), vec![1, 2, 3]);
+        assert!(entries_until(untimed, 300).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_restore_corrupt_archive() {
+        let archive = tempfile::tempdir().unwrap();
+        create_archive(archive.path()).await;
+        let from = format!("file://{}", archive.path().display());
+        let reader = ContentReader::new(Arc::new(ServerConfig::default()));
+
+        //  A segment altered after it was archived
+        let segment = archive.path().join("wal/00000000000000000004");
+        let mut bytes = fs::read(&segment).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        fs::write(&segment, bytes).unwrap();
+        let state_dir = tempfile::tempdir().unwrap();
+        let err = restore(&reader, &from, state_dir.path(), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("checksum"));
+        assert!(!state_dir.path().join("db").exists());
+
+        //  Log entries missing between the snapshot and the segments
+        let entries: Vec<Entry<TypeConfig>> = vec![Entry {
+            log_id: log_id(8),
+            payload: EntryPayload::Blank,
+        }];
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut encoder, &JsonEncoder::encode(&entries).unwrap()).unwrap();
+        let segment = encoder.finish().unwrap();
+        let err = log_entries_after(Some(log_id(5)), &[segment]).unwrap_err();
+        assert!(err.to_string().contains("6 to 7"));
+    }
+}
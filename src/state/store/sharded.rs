@@ -0,0 +1,132 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+const SHARD_COUNT: usize = 16;
+
+/// A collection split into shards by the hash of its keys. Every shard has its
+/// own lock, so updates of different keys don't wait on each other and readers
+/// of one key don't block writers of another.
+#[derive(Debug)]
+pub struct Sharded<T> {
+    shards: Vec<RwLock<T>>,
+}
+
+impl<T: Default> Default for Sharded<T> {
+    fn default() -> Self {
+        Self {
+            shards: (0..SHARD_COUNT)
+                .map(|_| RwLock::new(T::default()))
+                .collect(),
+        }
+    }
+}
+
+impl<T> Sharded<T> {
+    fn shard_index<K: Hash + ?Sized>(key: &K) -> usize {
+        let mut s = DefaultHasher::new();
+        key.hash(&mut s);
+        (s.finish() % SHARD_COUNT as u64) as usize
+    }
+
+    /// Locks the shard holding `key` for reading
+    pub fn read<K: Hash + ?Sized>(&self, key: &K) -> RwLockReadGuard<'_, T> {
+        self.shards[Self::shard_index(key)].read().unwrap()
+    }
+
+    /// Locks the shard holding `key` for writing
+    pub fn write<K: Hash + ?Sized>(&self, key: &K) -> RwLockWriteGuard<'_, T> {
+        self.shards[Self::shard_index(key)].write().unwrap()
+    }
+
+    pub fn shards(&self) -> impl Iterator<Item = &RwLock<T>> {
+        self.shards.iter()
+    }
+}
+
+impl<T> Sharded<T>
+where
+    T: Default + Clone + IntoIterator + Extend<<T as IntoIterator>::Item>,
+{
+    /// Returns a copy of the whole collection. Shards are locked one at a
+    /// time, so the copy is not a point in time snapshot across shards.
+    pub fn inner(&self) -> T {
+        let mut all = T::default();
+        for shard in &self.shards {
+            all.extend(shard.read().unwrap().clone());
+        }
+        all
+    }
+}
+
+impl<K: Hash + Eq> Sharded<HashSet<K>> {
+    pub fn set(&self, items: HashSet<K>) {
+        let mut shards: Vec<HashSet<K>> = (0..SHARD_COUNT).map(|_| HashSet::new()).collect();
+        for item in items {
+            shards[Self::shard_index(&item)].insert(item);
+        }
+        for (shard, items) in self.shards.iter().zip(shards) {
+            *shard.write().unwrap() = items;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|shard| shard.read().unwrap().len())
+            .sum()
+    }
+}
+
+impl<K: Hash + Eq, V> Sharded<HashMap<K, V>> {
+    pub fn set(&self, entries: HashMap<K, V>) {
+        let mut shards: Vec<HashMap<K, V>> = (0..SHARD_COUNT).map(|_| HashMap::new()).collect();
+        for (key, value) in entries {
+            shards[Self::shard_index(&key)].insert(key, value);
+        }
+        for (shard, entries) in self.shards.iter().zip(shards) {
+            *shard.write().unwrap() = entries;
+        }
+    }
+}
+
+impl<K: Hash + Eq> From<HashSet<K>> for Sharded<HashSet<K>> {
+    fn from(items: HashSet<K>) -> Self {
+        let sharded = Self::default();
+        sharded.set(items);
+        sharded
+    }
+}
+
+impl<K: Hash + Eq, V> From<HashMap<K, V>> for Sharded<HashMap<K, V>> {
+    fn from(entries: HashMap<K, V>) -> Self {
+        let sharded = Self::default();
+        sharded.set(entries);
+        sharded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sharded_set() {
+        let sharded: Sharded<HashSet<String>> = Sharded::default();
+        for i in 0..100 {
+            let key = format!("task-{}", i);
+            sharded.write(&key).insert(key.clone());
+        }
+        assert_eq!(sharded.len(), 100);
+        assert!(sharded.read("task-42").contains("task-42"));
+
+        sharded.write("task-42").remove("task-42");
+        assert!(!sharded.inner().contains("task-42"));
+        assert_eq!(sharded.inner().len(), 99);
+
+        sharded.set(HashSet::from(["a".to_string()]));
+        assert_eq!(sharded.inner(), HashSet::from(["a".to_string()]));
+    }
+}
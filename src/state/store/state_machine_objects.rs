@@ -1,8 +1,8 @@
 use core::fmt;
 use std::{
-    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
+    collections::{hash_map::Entry, BTreeMap, HashMap, HashSet, VecDeque},
     sync::{Arc, RwLock},
-    time::SystemTime,
+    time::{Duration, Instant, SystemTime},
 };
 
 use anyhow::{anyhow, Result};
@@ -14,7 +14,12 @@ use internal_api::{
     ExtractionPolicyName,
     ExtractorDescription,
     StateChange,
+    StuckContent,
     TaskOutcome,
+    TaskState,
+    TaskTransition,
+    UnschedulableReason,
+    ZonePlacement,
 };
 use itertools::Itertools;
 use opentelemetry::metrics::AsyncInstrument;
@@ -24,8 +29,17 @@ use tokio::sync::broadcast;
 use tracing::{error, warn};
 
 use super::{
-    requests::{RequestPayload, StateChangeProcessed, StateMachineUpdateRequest},
+    compaction::DeletionTracker,
+    requests::{
+        CreateOrUpdateContentEntry,
+        RequestPayload,
+        StateChangeProcessed,
+        StateMachineUpdateRequest,
+        TaskUpdate,
+    },
     serializer::JsonEncode,
+    sharded::Sharded,
+    tuning::{prefix_read_options, scan_read_options},
     ExecutorId,
     ExtractionGraphId,
     ExtractionPolicyId,
@@ -38,47 +52,93 @@ use super::{
     StateMachineError,
     TaskId,
 };
-use crate::state::NodeId;
+use crate::{server_config::InvariantValidation, state::NodeId};
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+/// Task ids are spread over lock shards, so marking one task as assigned does
+/// not contend with the scheduler reading or updating other tasks.
+#[derive(Clone, Debug, Default)]
 pub struct UnassignedTasks {
-    unassigned_tasks: Arc<RwLock<HashSet<TaskId>>>,
+    unassigned_tasks: Arc<Sharded<HashSet<TaskId>>>,
 }
 
 impl UnassignedTasks {
     pub fn insert(&self, task_id: &TaskId) {
-        let mut guard = self.unassigned_tasks.write().unwrap();
-        guard.insert(task_id.into());
+        self.unassigned_tasks.write(task_id).insert(task_id.into());
+    }
+
+    pub fn contains(&self, task_id: &TaskId) -> bool {
+        self.unassigned_tasks.read(task_id).contains(task_id)
     }
 
     pub fn remove(&self, task_id: &TaskId) {
-        let mut guard = self.unassigned_tasks.write().unwrap();
-        guard.remove(task_id);
+        self.unassigned_tasks.write(task_id).remove(task_id);
     }
 
     pub fn inner(&self) -> HashSet<TaskId> {
-        let guard = self.unassigned_tasks.read().unwrap();
-        guard.clone()
+        self.unassigned_tasks.inner()
     }
 
     pub fn set(&self, tasks: HashSet<TaskId>) {
-        let mut guard = self.unassigned_tasks.write().unwrap();
-        *guard = tasks;
+        self.unassigned_tasks.set(tasks);
     }
 
     pub fn count(&self) -> usize {
-        let guard = self.unassigned_tasks.read().unwrap();
-        guard.len()
+        self.unassigned_tasks.len()
     }
 }
 
 impl From<HashSet<TaskId>> for UnassignedTasks {
     fn from(tasks: HashSet<TaskId>) -> Self {
-        let unassigned_tasks = Arc::new(RwLock::new(tasks));
+        let unassigned_tasks = Arc::new(Sharded::from(tasks));
         Self { unassigned_tasks }
     }
 }
 
+/// Executor each assigned task is assigned to, the reverse of the task
+/// assignments column family. It is updated along with the column family, so
+/// looking up the owner of a task doesn't scan all the assignments.
+#[derive(Clone, Debug, Default)]
+pub struct TaskExecutors {
+    task_executors: Arc<RwLock<HashMap<TaskId, ExecutorId>>>,
+}
+
+impl TaskExecutors {
+    pub fn get(&self, task_id: &TaskId) -> Option<ExecutorId> {
+        self.task_executors.read().unwrap().get(task_id).cloned()
+    }
+
+    pub fn insert(&self, task_id: &TaskId, executor_id: &ExecutorId) {
+        self.task_executors
+            .write()
+            .unwrap()
+            .insert(task_id.clone(), executor_id.clone());
+    }
+
+    /// Removes the task only if it is still assigned to `executor_id`, a task
+    /// that moved to another executor keeps its new owner
+    pub fn remove(&self, task_id: &TaskId, executor_id: &str) {
+        let mut guard = self.task_executors.write().unwrap();
+        if guard
+            .get(task_id)
+            .map_or(false, |owner| owner == executor_id)
+        {
+            guard.remove(task_id);
+        }
+    }
+
+    pub fn contains(&self, task_id: &TaskId) -> bool {
+        self.task_executors.read().unwrap().contains_key(task_id)
+    }
+
+    pub fn inner(&self) -> HashMap<TaskId, ExecutorId> {
+        self.task_executors.read().unwrap().clone()
+    }
+
+    pub fn set(&self, task_executors: HashMap<TaskId, ExecutorId>) {
+        *self.task_executors.write().unwrap() = task_executors;
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
 pub struct UnprocessedStateChanges {
     unprocessed_state_changes: Arc<RwLock<HashSet<StateChangeId>>>,
@@ -90,15 +150,33 @@ impl UnprocessedStateChanges {
         guard.insert(state_change_id);
     }
 
-    pub fn remove(&self, state_change_id: &StateChangeId) {
+    /// Returns whether the state change was still unprocessed
+    pub fn remove(&self, state_change_id: &StateChangeId) -> bool {
         let mut guard = self.unprocessed_state_changes.write().unwrap();
-        guard.remove(state_change_id);
+        guard.remove(state_change_id)
     }
 
     pub fn inner(&self) -> HashSet<StateChangeId> {
         let guard = self.unprocessed_state_changes.read().unwrap();
         guard.clone()
     }
+
+    pub fn count(&self) -> usize {
+        self.unprocessed_state_changes.read().unwrap().len()
+    }
+
+    pub fn contains(&self, state_change_id: &StateChangeId) -> bool {
+        self.unprocessed_state_changes
+            .read()
+            .unwrap()
+            .contains(state_change_id)
+    }
+
+    /// Ids are assigned in order, so the smallest one was created first
+    pub fn oldest(&self) -> Option<StateChangeId> {
+        let guard = self.unprocessed_state_changes.read().unwrap();
+        guard.iter().min().copied()
+    }
 }
 
 impl From<HashSet<StateChangeId>> for UnprocessedStateChanges {
@@ -279,9 +357,51 @@ impl UnfinishedTasksByExtractor {
         guard.entry(extractor.clone()).or_default().remove(task_id);
     }
 
-    pub fn inner(&self) -> HashMap<ExtractorName, HashSet<TaskId>> {
+    pub fn get(&self, extractor: &str) -> HashSet<TaskId> {
         let guard = self.unfinished_tasks_by_extractor.read().unwrap();
-        guard.clone()
+        guard.get(extractor).cloned().unwrap_or_default()
+    }
+
+    pub fn contains(&self, extractor: &str, task_id: &TaskId) -> bool {
+        let guard = self.unfinished_tasks_by_extractor.read().unwrap();
+        guard
+            .get(extractor)
+            .map_or(false, |task_ids| task_ids.contains(task_id))
+    }
+
+    /// Returns the unfinished tasks of every extractor
+    pub fn task_ids(&self) -> HashSet<TaskId> {
+        let guard = self.unfinished_tasks_by_extractor.read().unwrap();
+        guard.values().flatten().cloned().collect()
+    }
+
+    /// Groups the given task ids by the extractor of each unfinished task.
+    /// Task ids are borrowed from the input and the index is walked under the
+    /// read lock, so nothing is copied apart from the grouping itself.
+    pub fn group_tasks<'a>(
+        &self,
+        task_ids: &'a HashSet<TaskId>,
+    ) -> HashMap<ExtractorName, Vec<&'a TaskId>> {
+        let guard = self.unfinished_tasks_by_extractor.read().unwrap();
+        let mut result: HashMap<ExtractorName, Vec<&'a TaskId>> = HashMap::new();
+        for (extractor, extractor_task_ids) in guard.iter() {
+            // Walk whichever set is smaller and probe the other one
+            let grouped: Vec<&'a TaskId> = if extractor_task_ids.len() < task_ids.len() {
+                extractor_task_ids
+                    .iter()
+                    .filter_map(|task_id| task_ids.get(task_id))
+                    .collect()
+            } else {
+                task_ids
+                    .iter()
+                    .filter(|task_id| extractor_task_ids.contains(*task_id))
+                    .collect()
+            };
+            if !grouped.is_empty() {
+                result.insert(extractor.clone(), grouped);
+            }
+        }
+        result
     }
 
     pub fn observe_task_counts(&self, observer: &dyn AsyncInstrument<u64>) {
@@ -375,6 +495,531 @@ impl From<HashMap<ExecutorId, u64>> for ExecutorRunningTaskCount {
     }
 }
 
+/// Window over which completed tasks are counted in the queue depths
+const COMPLETED_TASKS_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct TaskQueueDepth {
+    pub unallocated: u64,
+    pub running: u64,
+    pub completed_last_hour: u64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+struct PolicyTaskQueue {
+    unallocated: HashSet<TaskId>,
+    running: HashSet<TaskId>,
+    /// Completion times of the tasks finished within the window, oldest first
+    completed_at: VecDeque<SystemTime>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+struct TaskQueues {
+    /// Task id -> extraction policy id, for unfinished tasks
+    task_policies: HashMap<TaskId, ExtractionPolicyId>,
+    queues: HashMap<ExtractionPolicyId, PolicyTaskQueue>,
+    /// Task id -> when the unallocated task was last put into its queue
+    #[serde(default)]
+    unallocated_since: HashMap<TaskId, SystemTime>,
+    /// Task id -> when the task was first put into its queue
+    #[serde(default)]
+    queued_at: HashMap<TaskId, SystemTime>,
+    /// Task id -> priority of the content of the unfinished task, when it
+    /// isn't the default
+    #[serde(default)]
+    priorities: HashMap<TaskId, i32>,
+}
+
+/// Unfinished and recently completed tasks of every extraction policy.
+/// Completion times are not persisted, so completed counts start from zero
+/// when the state is rebuilt from a snapshot.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+pub struct TaskQueuesByExtractionPolicy {
+    task_queues: Arc<RwLock<TaskQueues>>,
+}
+
+impl TaskQueuesByExtractionPolicy {
+    pub fn insert_unallocated(
+        &self,
+        extraction_policy_id: &ExtractionPolicyId,
+        task_id: &TaskId,
+        priority: i32,
+        at: SystemTime,
+    ) {
+        let mut guard = self.task_queues.write().unwrap();
+        guard
+            .task_policies
+            .insert(task_id.clone(), extraction_policy_id.clone());
+        if priority != 0 {
+            guard.priorities.insert(task_id.clone(), priority);
+        } else {
+            guard.priorities.remove(task_id);
+        }
+        let queue = guard
+            .queues
+            .entry(extraction_policy_id.clone())
+            .or_default();
+        queue.running.remove(task_id);
+        queue.unallocated.insert(task_id.clone());
+        guard.unallocated_since.insert(task_id.clone(), at);
+        guard.queued_at.entry(task_id.clone()).or_insert(at);
+    }
+
+    pub fn mark_running(&self, task_id: &TaskId) {
+        let mut guard = self.task_queues.write().unwrap();
+        let Some(extraction_policy_id) = guard.task_policies.get(task_id).cloned() else {
+            return;
+        };
+        guard.unallocated_since.remove(task_id);
+        let queue = guard.queues.entry(extraction_policy_id).or_default();
+        queue.unallocated.remove(task_id);
+        queue.running.insert(task_id.clone());
+    }
+
+    pub fn mark_unallocated(&self, task_id: &TaskId, at: SystemTime) {
+        let mut guard = self.task_queues.write().unwrap();
+        let Some(extraction_policy_id) = guard.task_policies.get(task_id).cloned() else {
+            return;
+        };
+        let queue = guard.queues.entry(extraction_policy_id).or_default();
+        queue.running.remove(task_id);
+        queue.unallocated.insert(task_id.clone());
+        guard.unallocated_since.insert(task_id.clone(), at);
+    }
+
+    /// Marks a task completed, returning how long it took since it was first
+    /// queued
+    pub fn mark_completed(&self, task_id: &TaskId, completed_at: SystemTime) -> Option<Duration> {
+        let mut guard = self.task_queues.write().unwrap();
+        let Some(extraction_policy_id) = guard.task_policies.remove(task_id) else {
+            return None;
+        };
+        guard.unallocated_since.remove(task_id);
+        guard.priorities.remove(task_id);
+        let latency = guard
+            .queued_at
+            .remove(task_id)
+            .map(|queued_at| completed_at.duration_since(queued_at).unwrap_or_default());
+        let queue = guard.queues.entry(extraction_policy_id).or_default();
+        queue.unallocated.remove(task_id);
+        queue.running.remove(task_id);
+        queue.completed_at.push_back(completed_at);
+        while let Some(oldest) = queue.completed_at.front() {
+            match completed_at.duration_since(*oldest) {
+                Ok(age) if age > COMPLETED_TASKS_WINDOW => {
+                    queue.completed_at.pop_front();
+                }
+                _ => break,
+            }
+        }
+        latency
+    }
+
+    pub fn extraction_policy_of(&self, task_id: &TaskId) -> Option<ExtractionPolicyId> {
+        let guard = self.task_queues.read().unwrap();
+        guard.task_policies.get(task_id).cloned()
+    }
+
+    /// Orders task ids by the priority of their content, highest first, and
+    /// by id within a priority
+    pub fn sort_by_priority(&self, task_ids: &mut [TaskId]) {
+        let guard = self.task_queues.read().unwrap();
+        let priority = |task_id: &TaskId| guard.priorities.get(task_id).copied().unwrap_or(0);
+        task_ids.sort_by(|a, b| priority(b).cmp(&priority(a)).then_with(|| a.cmp(b)));
+    }
+
+    /// Unallocated tasks that have been waiting in their queue for at least
+    /// `min_age`, with how long they have been waiting
+    pub fn unallocated_for(&self, min_age: Duration, now: SystemTime) -> Vec<(TaskId, Duration)> {
+        let guard = self.task_queues.read().unwrap();
+        guard
+            .unallocated_since
+            .iter()
+            .filter_map(|(task_id, since)| {
+                let age = now.duration_since(*since).unwrap_or_default();
+                (age >= min_age).then(|| (task_id.clone(), age))
+            })
+            .collect()
+    }
+
+    pub fn queue_depths(&self, now: SystemTime) -> HashMap<ExtractionPolicyId, TaskQueueDepth> {
+        let guard = self.task_queues.read().unwrap();
+        guard
+            .queues
+            .iter()
+            .map(|(extraction_policy_id, queue)| {
+                let completed_last_hour = queue
+                    .completed_at
+                    .iter()
+                    .filter(|completed_at| {
+                        now.duration_since(**completed_at)
+                            .map_or(true, |age| age <= COMPLETED_TASKS_WINDOW)
+                    })
+                    .count() as u64;
+                (
+                    extraction_policy_id.clone(),
+                    TaskQueueDepth {
+                        unallocated: queue.unallocated.len() as u64,
+                        running: queue.running.len() as u64,
+                        completed_last_hour,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+/// Unfinished tasks and unprocessed state changes of a root content
+#[derive(Debug, Default)]
+pub struct PendingWork {
+    pub task_ids: Vec<TaskId>,
+    pub state_change_ids: Vec<StateChangeId>,
+}
+
+impl PendingWork {
+    pub fn count(&self) -> u64 {
+        (self.task_ids.len() + self.state_change_ids.len()) as u64
+    }
+}
+
+#[derive(Debug)]
+struct FinishedTask {
+    finished_at: SystemTime,
+    latency: Option<Duration>,
+    failed: bool,
+}
+
+/// Tasks of every extraction policy finished within the completed tasks
+/// window, with how long they took and whether they failed. Used to compute
+/// the compliance of policies with their objectives, only kept in memory.
+#[derive(Debug, Default)]
+pub struct FinishedTasks {
+    by_extraction_policy: RwLock<HashMap<ExtractionPolicyId, VecDeque<FinishedTask>>>,
+}
+
+impl FinishedTasks {
+    pub fn record(
+        &self,
+        extraction_policy_id: &ExtractionPolicyId,
+        finished_at: SystemTime,
+        latency: Option<Duration>,
+        failed: bool,
+    ) {
+        let mut by_extraction_policy = self.by_extraction_policy.write().unwrap();
+        let finished = by_extraction_policy
+            .entry(extraction_policy_id.clone())
+            .or_default();
+        finished.push_back(FinishedTask {
+            finished_at,
+            latency,
+            failed,
+        });
+        while finished.front().map_or(false, |task| {
+            finished_at
+                .duration_since(task.finished_at)
+                .map_or(false, |age| age > COMPLETED_TASKS_WINDOW)
+        }) {
+            finished.pop_front();
+        }
+    }
+
+    /// Tasks of the extraction policy finished within the window before
+    /// `now`, how many of them took longer than `latency` and how many failed
+    pub fn counts(
+        &self,
+        extraction_policy_id: &ExtractionPolicyId,
+        latency: Option<Duration>,
+        now: SystemTime,
+    ) -> (u64, u64, u64) {
+        let by_extraction_policy = self.by_extraction_policy.read().unwrap();
+        let Some(finished) = by_extraction_policy.get(extraction_policy_id) else {
+            return (0, 0, 0);
+        };
+        finished
+            .iter()
+            .filter(|task| {
+                now.duration_since(task.finished_at)
+                    .map_or(true, |age| age <= COMPLETED_TASKS_WINDOW)
+            })
+            .fold((0, 0, 0), |(tasks, slow, failed), task| {
+                let is_slow =
+                    matches!((task.latency, latency), (Some(took), Some(max)) if took > max);
+                (
+                    tasks + 1,
+                    slow + is_slow as u64,
+                    failed + task.failed as u64,
+                )
+            })
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Token buckets limiting how many tasks of the extraction policies with a
+/// `max_tasks_per_second` are allocated. A bucket holds up to one second
+/// worth of tokens. Tokens are only taken once the allocation of tasks is
+/// committed, so tasks that a plan admitted but didn't place don't use up the
+/// rate of their policy. The buckets are only kept in memory and start out
+/// full on a new leader.
+#[derive(Debug, Default)]
+pub struct TaskRateLimiters {
+    buckets: RwLock<HashMap<ExtractionPolicyId, TokenBucket>>,
+}
+
+impl TaskRateLimiters {
+    /// Number of tasks, up to `wanted`, the bucket of an extraction policy
+    /// that allows `tasks_per_second` has tokens for. No token is taken.
+    pub fn available(
+        &self,
+        extraction_policy_id: &ExtractionPolicyId,
+        tasks_per_second: u32,
+        wanted: usize,
+        now: Instant,
+    ) -> usize {
+        let capacity = tasks_per_second as f64;
+        let mut buckets = self.buckets.write().unwrap();
+        let bucket = buckets
+            .entry(extraction_policy_id.clone())
+            .or_insert(TokenBucket {
+                tokens: capacity,
+                refilled_at: now,
+            });
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * capacity).min(capacity);
+        bucket.refilled_at = now;
+        (bucket.tokens.max(0.0).floor() as usize).min(wanted)
+    }
+
+    /// Takes the tokens of `tasks` allocated tasks of an extraction policy.
+    /// Policies without a bucket aren't rate limited.
+    pub fn consume(&self, extraction_policy_id: &ExtractionPolicyId, tasks: usize) {
+        if let Some(bucket) = self.buckets.write().unwrap().get_mut(extraction_policy_id) {
+            bucket.tokens -= tasks as f64;
+        }
+    }
+
+    pub fn extraction_policy_ids(&self) -> HashSet<ExtractionPolicyId> {
+        self.buckets.read().unwrap().keys().cloned().collect()
+    }
+}
+
+/// Placement attempts kept for each task, and tasks attempts are kept for
+const SCHEDULING_ATTEMPTS_PER_TASK: usize = 5;
+const TASKS_WITH_SCHEDULING_ATTEMPTS: usize = 10_000;
+
+#[derive(Debug, Default)]
+struct TaskSchedulingAttempts {
+    /// Attempts of each task, with the sequence number of its latest attempt
+    by_task: HashMap<TaskId, (u64, VecDeque<internal_api::SchedulingAttempt>)>,
+    /// Tasks with attempts by the sequence number of their latest attempt
+    by_latest_attempt: BTreeMap<u64, TaskId>,
+    next_sequence: u64,
+}
+
+/// The most recent placement attempts of the allocator for each task, with
+/// the executors it considered. The attempts of the tasks attempted least
+/// recently are dropped once too many tasks have attempts, so tasks that keep
+/// failing to be placed keep their history. Only kept in memory by the leader.
+#[derive(Debug, Default)]
+pub struct SchedulingAttempts {
+    attempts: RwLock<TaskSchedulingAttempts>,
+}
+
+impl SchedulingAttempts {
+    pub fn record(&self, attempts: Vec<(TaskId, internal_api::SchedulingAttempt)>) {
+        let mut guard = self.attempts.write().unwrap();
+        let TaskSchedulingAttempts {
+            by_task,
+            by_latest_attempt,
+            next_sequence,
+        } = &mut *guard;
+        for (task_id, attempt) in attempts {
+            let sequence = *next_sequence;
+            *next_sequence += 1;
+            let (latest, task_attempts) = by_task.entry(task_id.clone()).or_default();
+            if !task_attempts.is_empty() {
+                by_latest_attempt.remove(&*latest);
+            }
+            *latest = sequence;
+            by_latest_attempt.insert(sequence, task_id);
+            if task_attempts.len() == SCHEDULING_ATTEMPTS_PER_TASK {
+                task_attempts.pop_front();
+            }
+            task_attempts.push_back(attempt);
+        }
+        while by_task.len() > TASKS_WITH_SCHEDULING_ATTEMPTS {
+            if let Some((_, task_id)) = by_latest_attempt.pop_first() {
+                by_task.remove(&task_id);
+            }
+        }
+    }
+
+    /// Attempts to place the task, oldest first
+    pub fn get(&self, task_id: &TaskId) -> Vec<internal_api::SchedulingAttempt> {
+        let guard = self.attempts.read().unwrap();
+        guard
+            .by_task
+            .get(task_id)
+            .map(|(_, attempts)| attempts.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Extraction graphs the latest version is kept in memory for
+const CACHED_EXTRACTION_GRAPH_VERSIONS: usize = 1_000;
+
+#[derive(Debug, Default)]
+struct CachedExtractionGraphVersions {
+    /// Latest version of each graph, with the sequence number of its last use
+    by_graph: HashMap<ExtractionGraphId, (u64, Arc<internal_api::ExtractionGraphVersion>)>,
+    /// Graphs by the sequence number of their last use
+    by_last_use: BTreeMap<u64, ExtractionGraphId>,
+    next_sequence: u64,
+}
+
+impl CachedExtractionGraphVersions {
+    fn touch(
+        &mut self,
+        extraction_graph_id: &str,
+    ) -> Option<Arc<internal_api::ExtractionGraphVersion>> {
+        let (last_use, version) = self.by_graph.get_mut(extraction_graph_id)?;
+        self.by_last_use.remove(last_use);
+        *last_use = self.next_sequence;
+        self.by_last_use
+            .insert(self.next_sequence, extraction_graph_id.to_string());
+        self.next_sequence += 1;
+        Some(version.clone())
+    }
+
+    fn insert(&mut self, version: Arc<internal_api::ExtractionGraphVersion>) {
+        let extraction_graph_id = version.extraction_graph.id.clone();
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        if let Some((last_use, _)) = self
+            .by_graph
+            .insert(extraction_graph_id.clone(), (sequence, version))
+        {
+            self.by_last_use.remove(&last_use);
+        }
+        self.by_last_use.insert(sequence, extraction_graph_id);
+        while self.by_graph.len() > CACHED_EXTRACTION_GRAPH_VERSIONS {
+            if let Some((_, extraction_graph_id)) = self.by_last_use.pop_first() {
+                self.by_graph.remove(&extraction_graph_id);
+            }
+        }
+    }
+}
+
+/// The latest version of the extraction graphs used recently. The version of
+/// a graph is loaded from its history on first use rather than for every task
+/// created, and the graphs used least recently are evicted once too many are
+/// cached, so graphs with a long history don't stay in memory.
+#[derive(Debug, Default)]
+pub struct ExtractionGraphVersions {
+    cache: std::sync::Mutex<CachedExtractionGraphVersions>,
+}
+
+impl ExtractionGraphVersions {
+    /// The latest version of a graph, loaded with `load` when it isn't cached.
+    /// The lock is held while loading so that a version loaded before a new
+    /// one was added can't replace it.
+    pub fn get_or_load<E>(
+        &self,
+        extraction_graph_id: &str,
+        load: impl FnOnce() -> Result<Option<internal_api::ExtractionGraphVersion>, E>,
+    ) -> Result<Option<Arc<internal_api::ExtractionGraphVersion>>, E> {
+        let mut cache = self.cache.lock().unwrap();
+        if let Some(version) = cache.touch(extraction_graph_id) {
+            return Ok(Some(version));
+        }
+        let Some(version) = load()?.map(Arc::new) else {
+            return Ok(None);
+        };
+        cache.insert(version.clone());
+        Ok(Some(version))
+    }
+
+    /// Makes `version` the latest version of its graph
+    pub fn insert(&self, version: internal_api::ExtractionGraphVersion) {
+        self.cache.lock().unwrap().insert(Arc::new(version));
+    }
+
+    pub fn clear(&self) {
+        *self.cache.lock().unwrap() = CachedExtractionGraphVersions::default();
+    }
+}
+
+/// Keys of the scheduler settings and of the maintenance mode in the scheduler
+/// settings column family
+pub(super) const SCHEDULER_SETTINGS_KEY: &str = "scheduler_settings";
+const MAINTENANCE_MODE_KEY: &str = "maintenance_mode";
+
+/// How long an executor counts as warm for an extraction policy after it
+/// finished a task of the policy
+const WARM_EXECUTOR_PERIOD: Duration = Duration::from_secs(10 * 60);
+
+/// Extraction policies each executor finished tasks of, and when it last did.
+/// Used to place tasks of policies with an expensive warm up on executors
+/// that likely still have the extractor loaded. Entries are kept past the
+/// warm period for policies that pin warm executors.
+#[derive(Debug, Default)]
+pub struct RecentExtractionPolicies {
+    by_executor: RwLock<HashMap<ExecutorId, HashMap<ExtractionPolicyId, SystemTime>>>,
+}
+
+impl RecentExtractionPolicies {
+    pub fn record(
+        &self,
+        executor_id: &ExecutorId,
+        extraction_policy_id: &ExtractionPolicyId,
+        now: SystemTime,
+    ) {
+        let mut by_executor = self.by_executor.write().unwrap();
+        by_executor
+            .entry(executor_id.clone())
+            .or_default()
+            .insert(extraction_policy_id.clone(), now);
+    }
+
+    pub fn remove_executor(&self, executor_id: &ExecutorId) {
+        self.by_executor.write().unwrap().remove(executor_id);
+    }
+
+    /// Executors that finished a task of the extraction policy within the
+    /// warm period before `now`, and the `pinned` executors that finished one
+    /// last even if that was before the warm period
+    pub fn warm_executors(
+        &self,
+        extraction_policy_id: &ExtractionPolicyId,
+        now: SystemTime,
+        pinned: usize,
+    ) -> HashSet<ExecutorId> {
+        let by_executor = self.by_executor.read().unwrap();
+        let mut finished: Vec<(&SystemTime, &ExecutorId)> = by_executor
+            .iter()
+            .filter_map(|(executor_id, recent)| {
+                recent
+                    .get(extraction_policy_id)
+                    .map(|finished_at| (finished_at, executor_id))
+            })
+            .collect();
+        finished.sort_by(|a, b| b.cmp(a));
+        finished
+            .into_iter()
+            .enumerate()
+            .filter(|(i, (finished_at, _))| {
+                *i < pinned ||
+                    now.duration_since(**finished_at).unwrap_or_default() < WARM_EXECUTOR_PERIOD
+            })
+            .map(|(_, (_, executor_id))| executor_id.clone())
+            .collect()
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
 pub struct ExtractionGraphTable {
     eg_by_namespace: Arc<RwLock<HashMap<NamespaceName, HashSet<ExtractionGraphId>>>>,
@@ -510,10 +1155,12 @@ impl From<HashMap<ContentMetadataId, HashSet<ContentMetadataId>>> for ContentChi
     }
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+/// Sharded by content id, so task updates for different content don't serialize
+/// on a single lock.
+#[derive(Clone, Debug, Default)]
 pub struct PendingTasksForContent {
     pending_tasks_for_content:
-        Arc<RwLock<HashMap<ContentMetadataId, HashMap<ExtractionPolicyId, HashSet<TaskId>>>>>,
+        Arc<Sharded<HashMap<ContentMetadataId, HashMap<ExtractionPolicyId, HashSet<TaskId>>>>>,
 }
 
 impl PendingTasksForContent {
@@ -523,7 +1170,7 @@ impl PendingTasksForContent {
         extraction_policy_id: &ExtractionPolicyId,
         task_id: &TaskId,
     ) {
-        let mut guard = self.pending_tasks_for_content.write().unwrap();
+        let mut guard = self.pending_tasks_for_content.write(content_id);
         let policies_map = guard.entry(content_id.clone()).or_default();
         let tasks_set = policies_map
             .entry(extraction_policy_id.clone())
@@ -537,7 +1184,7 @@ impl PendingTasksForContent {
         extraction_policy_id: &ExtractionPolicyId,
         task_id: &TaskId,
     ) {
-        let mut guard = self.pending_tasks_for_content.write().unwrap();
+        let mut guard = self.pending_tasks_for_content.write(content_id);
         if let Some(extraction_policies_map) = guard.get_mut(content_id) {
             if let Some(task_ids) = extraction_policies_map.get_mut(extraction_policy_id) {
                 task_ids.remove(task_id);
@@ -552,15 +1199,25 @@ impl PendingTasksForContent {
     }
 
     pub fn are_content_tasks_completed(&self, content_id: &ContentMetadataId) -> bool {
-        let guard = self.pending_tasks_for_content.read().unwrap();
+        let guard = self.pending_tasks_for_content.read(content_id);
         guard.get(content_id).is_none()
     }
 
     pub fn inner(
         &self,
     ) -> HashMap<ContentMetadataId, HashMap<ExtractionPolicyId, HashSet<TaskId>>> {
-        let guard = self.pending_tasks_for_content.read().unwrap();
-        guard.clone()
+        self.pending_tasks_for_content.inner()
+    }
+
+    pub fn set(
+        &self,
+        pending_tasks_for_content: HashMap<
+            ContentMetadataId,
+            HashMap<ExtractionPolicyId, HashSet<TaskId>>,
+        >,
+    ) {
+        self.pending_tasks_for_content
+            .set(pending_tasks_for_content);
     }
 }
 
@@ -573,7 +1230,7 @@ impl From<HashMap<ContentMetadataId, HashMap<ExtractionPolicyId, HashSet<TaskId>
             HashMap<ExtractionPolicyId, HashSet<TaskId>>,
         >,
     ) -> Self {
-        let pending_tasks_for_content = Arc::new(RwLock::new(pending_tasks_for_content));
+        let pending_tasks_for_content = Arc::new(Sharded::from(pending_tasks_for_content));
         Self {
             pending_tasks_for_content,
         }
@@ -599,22 +1256,40 @@ pub struct Metrics {
 
     /// Total number of bytes in extracted contents
     pub content_extracted_bytes: u64,
+
+    /// Number of tasks quarantined
+    #[serde(default)]
+    pub tasks_quarantined: u64,
+
+    /// Number of state changes marked processed
+    #[serde(default)]
+    pub state_changes_processed: u64,
 }
 
 impl Metrics {
     pub fn update_task_completion(&mut self, outcome: TaskOutcome) {
         match outcome {
             TaskOutcome::Success => self.tasks_completed += 1,
-            TaskOutcome::Failed => self.tasks_completed_with_errors += 1,
+            TaskOutcome::Failed | TaskOutcome::Quarantined | TaskOutcome::Expired => {
+                self.tasks_completed_with_errors += 1
+            }
             _ => (),
         }
+        if outcome == TaskOutcome::Quarantined {
+            self.tasks_quarantined += 1;
+        }
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 struct TaskCount {
     count: u64,
     notify: Option<broadcast::Sender<()>>,
+    /// When a task of the content was last created or finished
+    last_progress: SystemTime,
+    /// Extraction graphs that have created tasks for the content. The content
+    /// is active in these graphs until all of its tasks are finished.
+    graphs: HashSet<ExtractionGraphId>,
 }
 
 #[derive(thiserror::Error, Debug, Default)]
@@ -623,6 +1298,9 @@ pub struct IndexifyState {
     /// The tasks that are currently unassigned
     pub unassigned_tasks: UnassignedTasks,
 
+    /// Task id -> executor the task is assigned to
+    pub task_executors: TaskExecutors,
+
     /// State changes that have not been processed yet
     pub unprocessed_state_changes: UnprocessedStateChanges,
 
@@ -647,6 +1325,21 @@ pub struct IndexifyState {
     /// Executor id -> number of tasks running on executor
     pub executor_running_task_count: ExecutorRunningTaskCount,
 
+    /// Extraction policy id -> unfinished and recently completed tasks
+    pub task_queues_by_extraction_policy: TaskQueuesByExtractionPolicy,
+
+    /// Extraction policy id -> tokens left for allocating its tasks
+    pub task_rate_limiters: TaskRateLimiters,
+
+    /// Task id -> recent placement attempts of the allocator
+    pub scheduling_attempts: SchedulingAttempts,
+
+    /// Executor id -> extraction policies it recently finished tasks of
+    pub recent_extraction_policies: RecentExtractionPolicies,
+
+    /// Extraction policy id -> tasks finished within the last hour
+    pub finished_tasks: FinishedTasks,
+
     /// Namespace -> Schemas
     pub schemas_by_namespace: SchemasByNamespace,
 
@@ -659,14 +1352,34 @@ pub struct IndexifyState {
     /// Number of tasks pending for root content
     root_task_counts: RwLock<HashMap<String, TaskCount>>,
 
+    /// Garbage collection tasks that didn't finish yet
+    unfinished_gc_tasks: RwLock<HashSet<internal_api::GarbageCollectionTaskId>>,
+
     /// Metrics
     pub metrics: std::sync::Mutex<Metrics>,
 
+    /// Unschedulable reason -> tasks unallocated past the starvation
+    /// threshold, as of the last check of the leader
+    pub starved_tasks: std::sync::Mutex<HashMap<UnschedulableReason, u64>>,
+
+    /// Compliance of the extraction policies with objectives, as of the last
+    /// check of the leader
+    pub slo_compliance: std::sync::Mutex<HashMap<ExtractionPolicyId, internal_api::SloCompliance>>,
+
     /// Namespace -> Extraction Graph ID
     extraction_graphs_by_ns: ExtractionGraphTable,
 
+    /// Extraction Graph ID -> latest version, for the graphs used recently
+    extraction_graph_versions: ExtractionGraphVersions,
+
     /// Next change id
     pub change_id: std::sync::Mutex<u64>,
+
+    /// What to do with requests that violate the invariants below
+    pub invariant_validation: InvariantValidation,
+
+    /// Keys deleted since their column family was last compacted
+    pub deletions: DeletionTracker,
 }
 
 impl fmt::Display for IndexifyState {
@@ -688,26 +1401,133 @@ impl fmt::Display for IndexifyState {
     }
 }
 
+/// Key of a replay in the replays of its namespace
+fn namespace_replay_key(namespace: &str, replay_id: &str) -> String {
+    format!("{}/{}", namespace, replay_id)
+}
+
+/// Key of the unfinished replay task running an extraction policy on a
+/// content
+fn running_replay_task_key(content_id: &str, extraction_policy: &str) -> String {
+    format!("{}/{}", content_id, extraction_policy)
+}
+
+/// Commits `txn` after letting `before_commit` add its writes to it
+fn commit(
+    txn: rocksdb::Transaction<OptimisticTransactionDB>,
+    before_commit: impl FnOnce(
+        &rocksdb::Transaction<OptimisticTransactionDB>,
+    ) -> Result<(), StateMachineError>,
+) -> Result<(), StateMachineError> {
+    before_commit(&txn)?;
+    txn.commit()
+        .map_err(|e| StateMachineError::TransactionError(e.to_string()))
+}
+
 impl IndexifyState {
+    /// Writes an extraction graph with its policies and schema. Policies of
+    /// the previous definition of the graph that it no longer has are deleted
+    /// and returned.
     fn set_extraction_graph(
         &self,
         db: &Arc<OptimisticTransactionDB>,
         txn: &rocksdb::Transaction<OptimisticTransactionDB>,
         extraction_graph: &ExtractionGraph,
         structured_data_schema: &internal_api::StructuredDataSchema,
-    ) -> Result<(), StateMachineError> {
-        let serialized_eg = JsonEncoder::encode(extraction_graph)?;
-        let _ = txn
-            .put_cf(
-                &StateMachineColumns::ExtractionGraphs.cf(db),
+    ) -> Result<Vec<ExtractionPolicy>, StateMachineError> {
+        let previous = txn
+            .get_cf(
+                StateMachineColumns::ExtractionGraphs.cf(db),
                 &extraction_graph.id,
-                serialized_eg,
+            )
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+            .map(|bytes| JsonEncoder::decode::<ExtractionGraph>(&bytes))
+            .transpose()?;
+        let policy_ids: HashSet<&String> = extraction_graph
+            .extraction_policies
+            .iter()
+            .map(|policy| &policy.id)
+            .collect();
+        let removed_policies: Vec<ExtractionPolicy> = previous
+            .map(|previous| previous.extraction_policies)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|policy| !policy_ids.contains(&policy.id))
+            .collect();
+        for policy in &removed_policies {
+            txn.delete_cf(StateMachineColumns::ExtractionPolicies.cf(db), &policy.id)
+                .map_err(|e| {
+                    StateMachineError::TransactionError(format!(
+                        "error in txn while trying to delete extraction policy: {}",
+                        e
+                    ))
+                })?;
+            self.deletions
+                .record(&StateMachineColumns::ExtractionPolicies, &policy.id);
+        }
+
+        let serialized_eg = JsonEncoder::encode(extraction_graph)?;
+        let _ = txn
+            .put_cf(
+                &StateMachineColumns::ExtractionGraphs.cf(db),
+                &extraction_graph.id,
+                serialized_eg,
             )
             .map_err(|e| StateMachineError::DatabaseError(e.to_string()));
         for ep in extraction_graph.extraction_policies.to_owned() {
             self.set_extraction_policy(db, txn, &ep)?;
         }
         self.set_schema(db, txn, structured_data_schema)?;
+        Ok(removed_policies)
+    }
+
+    /// Appends the definition of a graph to its history. Graphs created before
+    /// the history was kept get their previous definition as first version.
+    fn add_extraction_graph_version(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        extraction_graph: &ExtractionGraph,
+        change: &internal_api::ExtractionGraphChange,
+    ) -> Result<(), StateMachineError> {
+        let mut history = self.get_extraction_graph_history(db, &extraction_graph.id)?;
+        if history.is_empty() {
+            let previous = self.get_from_cf::<ExtractionGraph, _>(
+                db,
+                StateMachineColumns::ExtractionGraphs,
+                &extraction_graph.id,
+            )?;
+            if let Some(previous) = previous {
+                history.push(internal_api::ExtractionGraphVersion {
+                    version: 1,
+                    extraction_graph: previous,
+                    author: String::new(),
+                    summary: "definition before the history was recorded".to_string(),
+                    created_at: 0,
+                });
+            }
+        }
+        history.push(internal_api::ExtractionGraphVersion {
+            version: history.last().map_or(1, |version| version.version + 1),
+            extraction_graph: extraction_graph.clone(),
+            author: change.author.clone(),
+            summary: change.summary.clone(),
+            created_at: change.created_at,
+        });
+        txn.put_cf(
+            &StateMachineColumns::ExtractionGraphHistory.cf(db),
+            &extraction_graph.id,
+            JsonEncoder::encode(&history)?,
+        )
+        .map_err(|e| {
+            StateMachineError::DatabaseError(format!(
+                "error writing extraction graph history: {}",
+                e
+            ))
+        })?;
+        if let Some(latest) = history.pop() {
+            self.extraction_graph_versions.insert(latest);
+        }
         Ok(())
     }
 
@@ -716,11 +1536,12 @@ impl IndexifyState {
         db: &Arc<OptimisticTransactionDB>,
         txn: &rocksdb::Transaction<OptimisticTransactionDB>,
         state_changes: &mut Vec<StateChange>,
+        at: SystemTime,
     ) -> Result<(), StateMachineError> {
         let mut change_id = self.get_next_change_ids(state_changes.len());
         for change in state_changes {
             if let Some(refcnt_object_id) = change.refcnt_object_id.as_ref() {
-                self.inc_root_ref_count(refcnt_object_id);
+                self.inc_root_ref_count(refcnt_object_id, at);
             }
             change.id = StateChangeId::new(change_id);
             change_id += 1;
@@ -829,6 +1650,50 @@ impl IndexifyState {
         Ok(())
     }
 
+    /// Writes a task an executor reported on. Once the task finished, its
+    /// usage is recorded and the slot of its executor and the reference on
+    /// its root content are released.
+    fn finalize_task(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        task: &internal_api::Task,
+        executor_id: Option<&ExecutorId>,
+        update_time: SystemTime,
+        finalized: TaskTransition,
+    ) -> Result<(), StateMachineError> {
+        self.update_tasks(db, txn, vec![task], update_time)?;
+        if !task.terminal_state() {
+            return Ok(());
+        }
+        let finished_at = finalized.at;
+        self.record_task_transitions(db, txn, [&task.id], finalized)?;
+        self.record_replay_outcome(db, txn, task, finished_at)?;
+        self.meter_finished_task(db, txn, task, update_time)?;
+        if let Some(usage) = &task.usage {
+            self.add_extraction_graph_usage(db, txn, task, usage)?;
+        }
+        self.metrics
+            .lock()
+            .unwrap()
+            .update_task_completion(task.outcome);
+
+        //  If the task is meant to be marked finished and has an executor id, remove it
+        // from the list of tasks assigned to an executor
+        if let Some(executor_id) = executor_id {
+            let mut existing_tasks =
+                self.get_task_assignments_for_executor(db, txn, executor_id)?;
+            existing_tasks.remove(&task.id);
+            let new_task_assignment = HashMap::from([(executor_id.to_string(), existing_tasks)]);
+            self.set_task_assignments(db, txn, &new_task_assignment)?;
+        }
+        self.dec_root_ref_count(
+            task.content_metadata.get_root_id(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(finished_at),
+        );
+        Ok(())
+    }
+
     fn set_garbage_collection_tasks(
         &self,
         db: &Arc<OptimisticTransactionDB>,
@@ -865,6 +1730,278 @@ impl IndexifyState {
         Ok(())
     }
 
+    /// Returns the invariants `payload` would violate: allocating a task held
+    /// by a registered executor, allocating a finished or unknown task or
+    /// allocating to an executor that isn't registered, updating an unknown
+    /// task and removing an executor that isn't registered.
+    fn invariant_violations(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        payload: &RequestPayload,
+    ) -> Result<Vec<String>, StateMachineError> {
+        let mut violations = Vec::new();
+        match payload {
+            RequestPayload::AssignTask { assignments, .. } => {
+                for (task_id, executor_id) in assignments {
+                    match self.read_task(db, txn, task_id)? {
+                        None => violations.push(format!(
+                            "assignment of unknown task {} to executor {}",
+                            task_id, executor_id
+                        )),
+                        Some(task) if task.terminal_state() => violations.push(format!(
+                            "assignment of finished task {} to executor {}",
+                            task_id, executor_id
+                        )),
+                        Some(_) => {
+                            //  Moving a task away from an executor that is gone is fine
+                            if let Some(owner) = self.task_executors.get(task_id) {
+                                if owner == *executor_id ||
+                                    self.is_executor_registered(db, txn, &owner)?
+                                {
+                                    violations.push(format!(
+                                        "task {} is already allocated to executor {}, assigning it to executor {} allocates it twice",
+                                        task_id, owner, executor_id
+                                    ))
+                                }
+                            }
+                        }
+                    }
+                    if !self.is_executor_registered(db, txn, executor_id)? {
+                        violations.push(format!(
+                            "assignment of task {} to unknown executor {}",
+                            task_id, executor_id
+                        ));
+                    }
+                }
+            }
+            RequestPayload::UpdateTask { task, .. } => {
+                if self.read_task(db, txn, &task.id)?.is_none() {
+                    violations.push(format!("update of unknown task {}", task.id));
+                }
+            }
+            RequestPayload::UpdateTasks { updates, .. } => {
+                for TaskUpdate { task, .. } in updates {
+                    if self.read_task(db, txn, &task.id)?.is_none() {
+                        violations.push(format!("update of unknown task {}", task.id));
+                    }
+                }
+            }
+            RequestPayload::RemoveExecutor { executor_id, .. } => {
+                if !self.is_executor_registered(db, txn, executor_id)? {
+                    violations.push(format!("removal of unknown executor {}", executor_id));
+                }
+            }
+            _ => {}
+        }
+        Ok(violations)
+    }
+
+    fn read_task(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        task_id: &str,
+    ) -> Result<Option<internal_api::Task>, StateMachineError> {
+        txn.get_cf(StateMachineColumns::Tasks.cf(db), task_id)
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+            .map(|bytes| JsonEncoder::decode::<internal_api::Task>(&bytes))
+            .transpose()
+    }
+
+    fn is_executor_registered(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        executor_id: &str,
+    ) -> Result<bool, StateMachineError> {
+        Ok(txn
+            .get_cf(StateMachineColumns::Executors.cf(db), executor_id)
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+            .is_some())
+    }
+
+    /// Decides whether a task update still applies. Finishing a task twice
+    /// would release its executor slot and its root content reference twice,
+    /// so updates of finished tasks are dropped, as are updates from an
+    /// executor the task was taken away from. Updates that don't name an
+    /// executor release the slot of the executor holding the task.
+    fn fence_task_update(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        task: &internal_api::Task,
+        executor_id: &mut Option<ExecutorId>,
+    ) -> Result<bool, StateMachineError> {
+        if self.is_task_finished(db, txn, &task.id)? {
+            warn!("ignoring update of finished task: {}", task.id);
+            return Ok(false);
+        }
+        if let Some(id) = executor_id {
+            if self
+                .get_task_assignments_for_executor(db, txn, id)?
+                .contains(&task.id)
+            {
+                return Ok(true);
+            }
+        }
+        if self.unassigned_tasks.contains(&task.id) {
+            //  Nobody holds a slot for an unassigned task
+            *executor_id = None;
+            return Ok(true);
+        }
+        if let Some(id) = executor_id {
+            warn!(
+                "ignoring update of task {} from executor {}, the task is assigned to another executor",
+                task.id, id
+            );
+            return Ok(false);
+        }
+        *executor_id = self.task_executors.get(&task.id);
+        Ok(true)
+    }
+
+    /// Drops the assignments that can't be applied anymore: the ones of
+    /// finished or deleted tasks, the ones to executors that were removed after
+    /// the allocation was planned, and the ones of tasks that a different
+    /// registered executor holds. Tasks can be moved away from executors that
+    /// are gone, and assigning a task to its current executor is a no-op. The
+    /// dropped tasks stay with the allocator.
+    fn fence_task_assignments(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        assignments: &mut HashMap<TaskId, ExecutorId>,
+    ) -> Result<(), StateMachineError> {
+        let mut registered: HashMap<ExecutorId, bool> = HashMap::new();
+        let mut is_registered = |executor_id: &ExecutorId| -> Result<bool, StateMachineError> {
+            if let Some(is_registered) = registered.get(executor_id) {
+                return Ok(*is_registered);
+            }
+            let is_registered = self.is_executor_registered(db, txn, executor_id)?;
+            registered.insert(executor_id.clone(), is_registered);
+            Ok(is_registered)
+        };
+        let mut stale = Vec::new();
+        for (task_id, executor_id) in assignments.iter() {
+            if self.unassigned_tasks.contains(task_id) {
+                if !is_registered(executor_id)? {
+                    stale.push(task_id.clone());
+                }
+                continue;
+            }
+            let unfinished = self
+                .read_task(db, txn, task_id)?
+                .map_or(false, |task| !task.terminal_state());
+            let owner = self.task_executors.get(task_id);
+            let held_elsewhere = match &owner {
+                Some(owner) => owner != executor_id && is_registered(owner)?,
+                None => false,
+            };
+            if !unfinished ||
+                held_elsewhere ||
+                owner.as_ref() == Some(executor_id) ||
+                !is_registered(executor_id)?
+            {
+                stale.push(task_id.clone());
+            }
+        }
+        for task_id in stale {
+            if let Some(executor_id) = assignments.remove(&task_id) {
+                warn!(
+                    "dropping stale assignment of task {} to executor {}",
+                    task_id, executor_id
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Keeps the parts of a reap that are still orphaned: assignments of tasks
+    /// that are finished or deleted, and unfinished tasks that are neither
+    /// assigned nor unassigned. Returns false when nothing is left to reap.
+    fn fence_reaped_assignments(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        finished_task_assignments: &mut HashMap<ExecutorId, HashSet<TaskId>>,
+        unallocated_tasks: &mut HashSet<TaskId>,
+    ) -> Result<bool, StateMachineError> {
+        for (executor_id, task_ids) in finished_task_assignments.iter_mut() {
+            let assigned = self.get_task_assignments_for_executor(db, txn, executor_id)?;
+            for task_id in std::mem::take(task_ids) {
+                let finished = self
+                    .read_task(db, txn, &task_id)?
+                    .map_or(true, |task| task.terminal_state());
+                if finished && assigned.contains(&task_id) {
+                    task_ids.insert(task_id);
+                }
+            }
+        }
+        finished_task_assignments.retain(|_, task_ids| !task_ids.is_empty());
+
+        for task_id in std::mem::take(unallocated_tasks) {
+            let unfinished = self
+                .read_task(db, txn, &task_id)?
+                .map_or(false, |task| !task.terminal_state());
+            if unfinished &&
+                !self.task_executors.contains(&task_id) &&
+                !self.unassigned_tasks.contains(&task_id)
+            {
+                unallocated_tasks.insert(task_id);
+            }
+        }
+        Ok(!finished_task_assignments.is_empty() || !unallocated_tasks.is_empty())
+    }
+
+    /// Keeps the burst tasks of a shed that their executor still holds and
+    /// that haven't finished. Returns false when nothing is left to shed.
+    fn fence_shed_burst_tasks(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        tasks: &mut HashMap<ExecutorId, HashSet<TaskId>>,
+    ) -> Result<bool, StateMachineError> {
+        for (executor_id, task_ids) in tasks.iter_mut() {
+            let assigned = self.get_task_assignments_for_executor(db, txn, executor_id)?;
+            for task_id in std::mem::take(task_ids) {
+                let burst = self
+                    .read_task(db, txn, &task_id)?
+                    .map_or(false, |task| task.burst && !task.terminal_state());
+                if burst && assigned.contains(&task_id) {
+                    task_ids.insert(task_id);
+                }
+            }
+        }
+        tasks.retain(|_, task_ids| !task_ids.is_empty());
+        Ok(!tasks.is_empty())
+    }
+
+    /// Drops the tasks of a repair that are already pending, so they aren't
+    /// counted twice
+    fn fence_repaired_tasks(&self, tasks: &mut Vec<internal_api::Task>) {
+        tasks.retain(|task| {
+            let pending = self
+                .unfinished_tasks_by_extractor
+                .contains(&task.extractor, &task.id);
+            if pending {
+                warn!("not re-creating pending task {}", task.id);
+            }
+            !pending
+        });
+    }
+
+    fn is_task_finished(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        task_id: &str,
+    ) -> Result<bool, StateMachineError> {
+        Ok(self
+            .read_task(db, txn, task_id)?
+            .map_or(false, |task| task.terminal_state()))
+    }
+
     fn get_task_assignments_for_executor(
         &self,
         db: &Arc<OptimisticTransactionDB>,
@@ -891,7 +2028,8 @@ impl IndexifyState {
         }
     }
 
-    /// Set the list of tasks that have been assigned to some executor
+    /// Set the list of tasks that have been assigned to some executor, and
+    /// the executor of the tasks in `task_executors`
     fn set_task_assignments(
         &self,
         db: &Arc<OptimisticTransactionDB>,
@@ -900,6 +2038,13 @@ impl IndexifyState {
     ) -> Result<(), StateMachineError> {
         let task_assignment_cf = StateMachineColumns::TaskAssignments.cf(db);
         for (executor_id, task_ids) in task_assignments {
+            let previous = self.get_task_assignments_for_executor(db, txn, executor_id)?;
+            for task_id in previous.difference(task_ids) {
+                self.task_executors.remove(task_id, executor_id);
+            }
+            for task_id in task_ids {
+                self.task_executors.insert(task_id, executor_id);
+            }
             txn.put_cf(
                 task_assignment_cf,
                 executor_id,
@@ -945,10 +2090,45 @@ impl IndexifyState {
                     e
                 ))
             })?;
+        for task_id in &task_ids {
+            self.task_executors.remove(task_id, executor_id);
+        }
 
         Ok(task_ids)
     }
 
+    /// Records on the unfinished tasks of a removed executor that the
+    /// executor was lost while running them, unless it left on its own
+    fn record_lost_task_attempts(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        executor_id: &str,
+        task_ids: &[TaskId],
+        lost: bool,
+    ) -> Result<(), StateMachineError> {
+        for task_id in task_ids {
+            let Some(mut task) = self.read_task(db, txn, task_id)? else {
+                continue;
+            };
+            if task.terminal_state() || task.lost_by_executors.iter().any(|id| id == executor_id) {
+                continue;
+            }
+            if lost {
+                task.lost_by_executors.push(executor_id.to_string());
+            }
+            //  The next executor starts the task over
+            task.progress = None;
+            txn.put_cf(
+                StateMachineColumns::Tasks.cf(db),
+                task_id,
+                JsonEncoder::encode(&task)?,
+            )
+            .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
     fn set_content<'a>(
         &self,
         db: &Arc<OptimisticTransactionDB>,
@@ -965,10 +2145,73 @@ impl IndexifyState {
             .map_err(|e| {
                 StateMachineError::DatabaseError(format!("error writing content: {}", e))
             })?;
+            //  Extracted content may have to be copied to another region
+            if content.latest &&
+                !content.tombstoned &&
+                matches!(
+                    content.source,
+                    internal_api::ContentSource::ExtractionPolicyName(_)
+                )
+            {
+                self.mark_pending_replication(db, txn, &content.id.id)?;
+            }
+            self.record_replay_output(db, txn, content)?;
+        }
+        Ok(())
+    }
+
+    /// Queues a content for the replicator, which copies it to another region
+    /// or removes its copy once it's deleted
+    fn mark_pending_replication(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        content_id: &str,
+    ) -> Result<(), StateMachineError> {
+        txn.put_cf(
+            StateMachineColumns::PendingReplications.cf(db),
+            content_id,
+            JsonEncoder::encode(&())?,
+        )
+        .map_err(|e| {
+            StateMachineError::DatabaseError(format!("error writing pending replication: {}", e))
+        })
+    }
+
+    /// Queues the removal of the copy of a deleted content in another region
+    fn mark_replica_orphaned(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        content_id: &str,
+    ) -> Result<(), StateMachineError> {
+        let replica = txn
+            .get_cf(StateMachineColumns::ContentReplicas.cf(db), content_id)
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
+        if replica.is_some() {
+            self.mark_pending_replication(db, txn, content_id)?;
         }
         Ok(())
     }
 
+    /// Hash of the latest version of a content, none once it's deleted or
+    /// tombstoned
+    fn live_content_hash(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        content_id: &str,
+    ) -> Result<Option<String>, StateMachineError> {
+        let content = txn
+            .get_cf(StateMachineColumns::ContentTable.cf(db), content_id)
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+            .map(|value| JsonEncoder::decode::<internal_api::ContentMetadata>(&value))
+            .transpose()?;
+        Ok(content
+            .filter(|content| content.latest && !content.tombstoned)
+            .map(|content| content.hash))
+    }
+
     fn tombstone_content_tree(
         &self,
         db: &Arc<OptimisticTransactionDB>,
@@ -977,12 +2220,15 @@ impl IndexifyState {
     ) -> Result<(), StateMachineError> {
         for content in content_metadata {
             let cf = StateMachineColumns::ContentTable.cf(db);
+            self.mark_replica_orphaned(db, txn, &content.id.id)?;
             let mut content = content.clone();
             // If updating latest version of root node, the key will change so delete from
             // previous location.
             if content.latest && content.parent_id.is_none() {
                 txn.delete_cf(cf, &content.id.id)
                     .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
+                self.deletions
+                    .record(&StateMachineColumns::ContentTable, &content.id.id);
                 content.latest = false;
             }
             let serialized_content = JsonEncoder::encode(&content)?;
@@ -1003,16 +2249,36 @@ impl IndexifyState {
         content_ids: Vec<ContentMetadataId>,
     ) -> Result<(), StateMachineError> {
         for content_id in content_ids {
-            txn.delete_cf(
-                StateMachineColumns::ContentTable.cf(db),
-                &format!("{}::v{}", content_id.id, content_id.version),
-            )
-            .map_err(|e| {
-                StateMachineError::TransactionError(format!(
-                    "error in txn while trying to delete content: {}",
-                    e
-                ))
-            })?;
+            self.mark_replica_orphaned(db, txn, &content_id.id)?;
+            let key = format!("{}::v{}", content_id.id, content_id.version);
+            txn.delete_cf(StateMachineColumns::ContentTable.cf(db), &key)
+                .map_err(|e| {
+                    StateMachineError::TransactionError(format!(
+                        "error in txn while trying to delete content: {}",
+                        e
+                    ))
+                })?;
+            self.deletions
+                .record(&StateMachineColumns::ContentTable, &key);
+            //  Only root content has a context
+            txn.delete_cf(StateMachineColumns::ContentContext.cf(db), &content_id.id)
+                .map_err(|e| {
+                    StateMachineError::TransactionError(format!(
+                        "error in txn while trying to delete content context: {}",
+                        e
+                    ))
+                })?;
+            self.deletions
+                .record(&StateMachineColumns::ContentContext, &content_id.id);
+            txn.delete_cf(StateMachineColumns::ContentZones.cf(db), &content_id.id)
+                .map_err(|e| {
+                    StateMachineError::TransactionError(format!(
+                        "error in txn while trying to delete content zones: {}",
+                        e
+                    ))
+                })?;
+            self.deletions
+                .record(&StateMachineColumns::ContentZones, &content_id.id);
         }
         Ok(())
     }
@@ -1021,21 +2287,67 @@ impl IndexifyState {
         &self,
         db: &Arc<OptimisticTransactionDB>,
         txn: &rocksdb::Transaction<OptimisticTransactionDB>,
-        addr: String,
+        executor: &internal_api::ExecutorMetadata,
+    ) -> Result<(), StateMachineError> {
+        let serialized_executor = JsonEncoder::encode(executor)?;
+        txn.put_cf(
+            StateMachineColumns::Executors.cf(db),
+            &executor.id,
+            serialized_executor,
+        )
+        .map_err(|e| StateMachineError::DatabaseError(format!("Error writing executor: {}", e)))?;
+        Ok(())
+    }
+
+    /// Moves the executor's last_seen time forward. Executors that were removed
+    /// in the meantime are not brought back.
+    fn renew_executor_lease(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
         executor_id: &str,
-        extractors: &Vec<ExtractorDescription>,
-        ts_secs: &u64,
+        ts_secs: u64,
     ) -> Result<(), StateMachineError> {
-        let serialized_executor = JsonEncoder::encode(&internal_api::ExecutorMetadata {
-            id: executor_id.into(),
-            last_seen: *ts_secs,
-            addr: addr.clone(),
-            extractors: extractors.clone(),
+        let executors_cf = StateMachineColumns::Executors.cf(db);
+        let executor = txn.get_cf(executors_cf, executor_id).map_err(|e| {
+            StateMachineError::DatabaseError(format!("Error reading executor: {}", e))
         })?;
+        let Some(executor) = executor else {
+            return Ok(());
+        };
+        let mut executor_meta = JsonEncoder::decode::<internal_api::ExecutorMetadata>(&executor)?;
+        executor_meta.last_seen = executor_meta.last_seen.max(ts_secs);
         txn.put_cf(
-            StateMachineColumns::Executors.cf(db),
+            executors_cf,
             executor_id,
-            serialized_executor,
+            JsonEncoder::encode(&executor_meta)?,
+        )
+        .map_err(|e| StateMachineError::DatabaseError(format!("Error writing executor: {}", e)))?;
+        Ok(())
+    }
+
+    /// Changes the metadata of a registered executor, executors that were
+    /// removed in the meantime are left alone
+    fn update_executor(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        executor_id: &str,
+        update: impl FnOnce(&mut internal_api::ExecutorMetadata),
+    ) -> Result<(), StateMachineError> {
+        let executors_cf = StateMachineColumns::Executors.cf(db);
+        let executor = txn.get_cf(executors_cf, executor_id).map_err(|e| {
+            StateMachineError::DatabaseError(format!("Error reading executor: {}", e))
+        })?;
+        let Some(executor) = executor else {
+            return Ok(());
+        };
+        let mut executor_meta = JsonEncoder::decode::<internal_api::ExecutorMetadata>(&executor)?;
+        update(&mut executor_meta);
+        txn.put_cf(
+            executors_cf,
+            executor_id,
+            JsonEncoder::encode(&executor_meta)?,
         )
         .map_err(|e| StateMachineError::DatabaseError(format!("Error writing executor: {}", e)))?;
         Ok(())
@@ -1118,27 +2430,612 @@ impl IndexifyState {
         Ok(())
     }
 
-    fn set_schema(
+    fn set_content_context(
         &self,
         db: &Arc<OptimisticTransactionDB>,
         txn: &rocksdb::Transaction<OptimisticTransactionDB>,
-        schema: &internal_api::StructuredDataSchema,
+        root_content_id: &str,
+        key: &str,
+        value: &Option<serde_json::Value>,
     ) -> Result<(), StateMachineError> {
-        let serialized_schema = JsonEncoder::encode(schema)?;
-        txn.put_cf(
-            &StateMachineColumns::StructuredDataSchemas.cf(db),
-            schema.id.clone(),
-            serialized_schema,
-        )
-        .map_err(|e| StateMachineError::DatabaseError(format!("Error writing schema: {}", e)))?;
-        Ok(())
+        let mut context = self.get_content_context(db, root_content_id)?;
+        match value {
+            Some(value) => context.insert(key.to_string(), value.clone()),
+            None => context.remove(key),
+        };
+        let cf = StateMachineColumns::ContentContext.cf(db);
+        if context.is_empty() {
+            txn.delete_cf(cf, root_content_id)
+        } else {
+            txn.put_cf(cf, root_content_id, JsonEncoder::encode(&context)?)
+        }
+        .map_err(|e| {
+            StateMachineError::DatabaseError(format!("error writing content context: {}", e))
+        })
     }
 
-    pub fn update_content_extraction_policy_state(
+    fn clear_task_diagnostics(
         &self,
         db: &Arc<OptimisticTransactionDB>,
         txn: &rocksdb::Transaction<OptimisticTransactionDB>,
-        content_id: &ContentMetadataId,
+        task_ids: &[TaskId],
+    ) -> Result<(), StateMachineError> {
+        for task_id in task_ids {
+            let mut task = match self
+                .get_from_cf::<internal_api::Task, _>(db, StateMachineColumns::Tasks, task_id)
+                .map_err(|e| StateMachineError::DatabaseError(e.to_string()))?
+            {
+                Some(task) if task.diagnostics.is_some() => task,
+                _ => continue,
+            };
+            task.diagnostics = None;
+            txn.put_cf(
+                StateMachineColumns::Tasks.cf(db),
+                task_id,
+                JsonEncoder::encode(&task)?,
+            )
+            .map_err(|e| StateMachineError::DatabaseError(format!("error writing task: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Stores the progress an executor reported on the unfinished tasks
+    /// assigned to it. Reports of tasks the executor no longer holds are
+    /// dropped.
+    fn update_task_progress(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        executor_id: &str,
+        progress: &HashMap<TaskId, internal_api::TaskProgress>,
+    ) -> Result<(), StateMachineError> {
+        let assigned = self.get_task_assignments_for_executor(db, txn, executor_id)?;
+        for (task_id, progress) in progress {
+            if !assigned.contains(task_id) {
+                continue;
+            }
+            let Some(mut task) = self.read_task(db, txn, task_id)? else {
+                continue;
+            };
+            if task.terminal_state() {
+                continue;
+            }
+            task.progress = Some(*progress);
+            txn.put_cf(
+                StateMachineColumns::Tasks.cf(db),
+                task_id,
+                JsonEncoder::encode(&task)?,
+            )
+            .map_err(|e| StateMachineError::DatabaseError(format!("error writing task: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Sets the GPU device the planner packed each assigned task taking a
+    /// share of a GPU onto. A task that didn't get a device runs without one
+    /// rather than keep the one of an earlier executor.
+    fn set_gpu_devices(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        assignments: &HashMap<TaskId, ExecutorId>,
+        gpu_devices: &HashMap<TaskId, Option<u32>>,
+    ) -> Result<(), StateMachineError> {
+        for (task_id, device) in gpu_devices {
+            //  Assignments dropped by the fencing keep their device
+            if !assignments.contains_key(task_id) {
+                continue;
+            }
+            let Some(mut task) = self.read_task(db, txn, task_id)? else {
+                continue;
+            };
+            task.gpu_device = *device;
+            txn.put_cf(
+                StateMachineColumns::Tasks.cf(db),
+                task_id,
+                JsonEncoder::encode(&task)?,
+            )
+            .map_err(|e| StateMachineError::DatabaseError(format!("error writing task: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Marks the assigned tasks that took burst capacity of their executor,
+    /// and clears the mark of the others, which may have taken burst capacity
+    /// of an earlier executor
+    fn set_burst_tasks(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        assignments: &HashMap<TaskId, ExecutorId>,
+        burst_tasks: &HashSet<TaskId>,
+    ) -> Result<(), StateMachineError> {
+        for task_id in assignments.keys() {
+            let Some(mut task) = self.read_task(db, txn, task_id)? else {
+                continue;
+            };
+            let burst = burst_tasks.contains(task_id);
+            if task.burst == burst {
+                continue;
+            }
+            task.burst = burst;
+            txn.put_cf(
+                StateMachineColumns::Tasks.cf(db),
+                task_id,
+                JsonEncoder::encode(&task)?,
+            )
+            .map_err(|e| StateMachineError::DatabaseError(format!("error writing task: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Counts the assigned tasks of content placed by zone against the zone
+    /// of their executor, by root content
+    fn add_content_zones(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        assignments: &HashMap<TaskId, ExecutorId>,
+        content_zones: &HashMap<TaskId, (String, String)>,
+    ) -> Result<(), StateMachineError> {
+        let mut added: HashMap<&String, HashMap<&String, usize>> = HashMap::new();
+        for (root_content_id, zone) in assignments
+            .keys()
+            .filter_map(|task_id| content_zones.get(task_id))
+        {
+            *added
+                .entry(root_content_id)
+                .or_default()
+                .entry(zone)
+                .or_default() += 1;
+        }
+        for (root_content_id, added) in added {
+            let mut zones: HashMap<String, usize> = self
+                .get_from_cf(db, StateMachineColumns::ContentZones, root_content_id)?
+                .unwrap_or_default();
+            for (zone, count) in added {
+                *zones.entry(zone.clone()).or_default() += count;
+            }
+            txn.put_cf(
+                StateMachineColumns::ContentZones.cf(db),
+                root_content_id,
+                JsonEncoder::encode(&zones)?,
+            )
+            .map_err(|e| {
+                StateMachineError::DatabaseError(format!("error writing content zones: {}", e))
+            })?;
+        }
+        Ok(())
+    }
+
+    fn set_scheduler_settings(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        settings: &internal_api::SchedulerSettings,
+    ) -> Result<(), StateMachineError> {
+        txn.put_cf(
+            StateMachineColumns::SchedulerSettings.cf(db),
+            SCHEDULER_SETTINGS_KEY,
+            JsonEncoder::encode(settings)?,
+        )
+        .map_err(|e| {
+            StateMachineError::DatabaseError(format!("error writing scheduler settings: {}", e))
+        })
+    }
+
+    fn set_maintenance_mode(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        maintenance: &internal_api::MaintenanceMode,
+    ) -> Result<(), StateMachineError> {
+        txn.put_cf(
+            StateMachineColumns::SchedulerSettings.cf(db),
+            MAINTENANCE_MODE_KEY,
+            JsonEncoder::encode(maintenance)?,
+        )
+        .map_err(|e| {
+            StateMachineError::DatabaseError(format!("error writing maintenance mode: {}", e))
+        })
+    }
+
+    /// Applies `update` to the metering record of `namespace` for the period
+    /// `time` falls into
+    fn update_metering_record(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        namespace: &str,
+        time: SystemTime,
+        update: impl FnOnce(&mut internal_api::MeteringRecord),
+    ) -> Result<(), StateMachineError> {
+        let ts_secs = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let period_start = internal_api::MeteringRecord::period_of(ts_secs);
+        let key = internal_api::MeteringRecord::key(namespace, period_start);
+        let cf = StateMachineColumns::Metering.cf(db);
+        let mut record = txn
+            .get_cf(cf, &key)
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+            .map(|bytes| JsonEncoder::decode::<internal_api::MeteringRecord>(&bytes))
+            .transpose()?
+            .unwrap_or_else(|| internal_api::MeteringRecord::new(namespace, period_start));
+        update(&mut record);
+        txn.put_cf(cf, key, JsonEncoder::encode(&record)?)
+            .map_err(|e| {
+                StateMachineError::DatabaseError(format!("error writing metering record: {}", e))
+            })
+    }
+
+    /// Meters the content of `entries` that isn't stored yet. Updates of
+    /// stored content, like label changes, are not metered again.
+    fn meter_new_content(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        entries: &[CreateOrUpdateContentEntry],
+    ) -> Result<(), StateMachineError> {
+        for entry in entries {
+            let content = &entry.content;
+            let stored = txn
+                .get_cf(StateMachineColumns::ContentTable.cf(db), content.id_key())
+                .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+                .map(|bytes| JsonEncoder::decode::<internal_api::ContentMetadata>(&bytes))
+                .transpose()?;
+            if stored.map_or(false, |stored| stored.id == content.id) {
+                continue;
+            }
+            let created_at =
+                SystemTime::UNIX_EPOCH + Duration::from_secs(content.created_at.max(0) as u64);
+            self.update_metering_record(db, txn, &content.namespace, created_at, |record| {
+                if content.parent_id.is_none() {
+                    record.invocations += 1;
+                }
+                record.stored_bytes += content.size_bytes;
+            })?;
+        }
+        Ok(())
+    }
+
+    fn set_task_assignment_times(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        task_ids: impl IntoIterator<Item = &'_ TaskId>,
+        assigned_at: SystemTime,
+    ) -> Result<(), StateMachineError> {
+        let serialized = JsonEncoder::encode(&assigned_at)?;
+        for task_id in task_ids {
+            txn.put_cf(
+                StateMachineColumns::TaskAssignmentTimes.cf(db),
+                task_id,
+                &serialized,
+            )
+            .map_err(|e| {
+                StateMachineError::DatabaseError(format!(
+                    "error writing task assignment time: {}",
+                    e
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Appends a transition to the history of each task of `task_ids`
+    fn record_task_transitions<'a>(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        task_ids: impl IntoIterator<Item = &'a TaskId>,
+        transition: TaskTransition,
+    ) -> Result<(), StateMachineError> {
+        let cf = StateMachineColumns::TaskTransitions.cf(db);
+        for task_id in task_ids {
+            let mut transitions = txn
+                .get_cf(cf, task_id)
+                .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+                .map(|bytes| JsonEncoder::decode::<Vec<TaskTransition>>(&bytes))
+                .transpose()?
+                .unwrap_or_default();
+            transitions.push(transition.clone());
+            txn.put_cf(cf, task_id, JsonEncoder::encode(&transitions)?)
+                .map_err(|e| {
+                    StateMachineError::DatabaseError(format!(
+                        "error writing task transitions: {}",
+                        e
+                    ))
+                })?;
+        }
+        Ok(())
+    }
+
+    fn read_replay(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        replay_id: &str,
+    ) -> Result<Option<internal_api::Replay>, StateMachineError> {
+        txn.get_cf(StateMachineColumns::Replays.cf(db), replay_id)
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+            .map(|bytes| JsonEncoder::decode::<internal_api::Replay>(&bytes))
+            .transpose()
+    }
+
+    fn write_replay(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        replay: &internal_api::Replay,
+    ) -> Result<(), StateMachineError> {
+        txn.put_cf(
+            StateMachineColumns::Replays.cf(db),
+            &replay.id,
+            JsonEncoder::encode(replay)?,
+        )
+        .map_err(|e| StateMachineError::DatabaseError(format!("error writing replay: {}", e)))
+    }
+
+    /// Records a replay and points its tasks at it. A task still running for
+    /// an earlier replay is marked as replaced in that replay.
+    fn create_replay(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        replay: &internal_api::Replay,
+    ) -> Result<(), StateMachineError> {
+        txn.put_cf(
+            StateMachineColumns::NamespaceReplays.cf(db),
+            namespace_replay_key(&replay.namespace, &replay.id),
+            JsonEncoder::encode(&())?,
+        )
+        .map_err(|e| StateMachineError::DatabaseError(format!("error writing replay: {}", e)))?;
+        let cf = StateMachineColumns::ReplayTasks.cf(db);
+        for task in &replay.tasks {
+            txn.put_cf(
+                StateMachineColumns::RunningReplayTasks.cf(db),
+                running_replay_task_key(&task.content_id, &task.extraction_policy),
+                JsonEncoder::encode(&task.task_id)?,
+            )
+            .map_err(|e| {
+                StateMachineError::DatabaseError(format!("error writing replay task: {}", e))
+            })?;
+            let previous = txn
+                .get_cf(cf, &task.task_id)
+                .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+                .map(|bytes| JsonEncoder::decode::<String>(&bytes))
+                .transpose()?;
+            if let Some(mut previous) = previous
+                .map(|replay_id| self.read_replay(db, txn, &replay_id))
+                .transpose()?
+                .flatten()
+            {
+                previous.finish_task(
+                    &task.task_id,
+                    internal_api::TaskOutcome::Unknown,
+                    Some(&replay.id),
+                    replay.started_at,
+                );
+                self.write_replay(db, txn, &previous)?;
+            }
+            txn.put_cf(cf, &task.task_id, JsonEncoder::encode(&replay.id)?)
+                .map_err(|e| {
+                    StateMachineError::DatabaseError(format!("error writing replay task: {}", e))
+                })?;
+        }
+        self.write_replay(db, txn, replay)
+    }
+
+    /// Records the outcome of a finished task in the replay that ran it
+    fn record_replay_outcome(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        task: &internal_api::Task,
+        finished_at: u64,
+    ) -> Result<(), StateMachineError> {
+        let cf = StateMachineColumns::ReplayTasks.cf(db);
+        let Some(replay_id) = txn
+            .get_cf(cf, &task.id)
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+            .map(|bytes| JsonEncoder::decode::<String>(&bytes))
+            .transpose()?
+        else {
+            return Ok(());
+        };
+        txn.delete_cf(cf, &task.id)
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
+        if let Some(mut replay) = self.read_replay(db, txn, &replay_id)? {
+            replay.finish_task(&task.id, task.outcome, None, finished_at);
+            for replay_task in replay.tasks.iter().filter(|t| t.task_id == task.id) {
+                txn.delete_cf(
+                    StateMachineColumns::RunningReplayTasks.cf(db),
+                    running_replay_task_key(
+                        &replay_task.content_id,
+                        &replay_task.extraction_policy,
+                    ),
+                )
+                .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
+            }
+            self.write_replay(db, txn, &replay)?;
+        }
+        Ok(())
+    }
+
+    /// Records content extracted by a task of a replay in the replay, so its
+    /// outputs are told apart from content the policy extracts later
+    fn record_replay_output(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        content: &internal_api::ContentMetadata,
+    ) -> Result<(), StateMachineError> {
+        let (Some(parent_id), internal_api::ContentSource::ExtractionPolicyName(policy)) =
+            (&content.parent_id, &content.source)
+        else {
+            return Ok(());
+        };
+        let Some(task_id) = txn
+            .get_cf(
+                StateMachineColumns::RunningReplayTasks.cf(db),
+                running_replay_task_key(&parent_id.id, policy),
+            )
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+            .map(|bytes| JsonEncoder::decode::<String>(&bytes))
+            .transpose()?
+        else {
+            return Ok(());
+        };
+        let Some(replay_id) = txn
+            .get_cf(StateMachineColumns::ReplayTasks.cf(db), &task_id)
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+            .map(|bytes| JsonEncoder::decode::<String>(&bytes))
+            .transpose()?
+        else {
+            return Ok(());
+        };
+        let Some(mut replay) = self.read_replay(db, txn, &replay_id)? else {
+            return Ok(());
+        };
+        let mut recorded = false;
+        for task in replay
+            .tasks
+            .iter_mut()
+            .filter(|task| task.task_id == task_id)
+        {
+            if !task.outputs.contains(&content.id.id) {
+                task.outputs.push(content.id.id.clone());
+                recorded = true;
+            }
+        }
+        if recorded {
+            self.write_replay(db, txn, &replay)?;
+        }
+        Ok(())
+    }
+
+    /// Name of the extraction policy of a task, or its id when the policy
+    /// doesn't exist anymore
+    fn task_extraction_policy_name(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        task: &internal_api::Task,
+    ) -> Result<String, StateMachineError> {
+        Ok(txn
+            .get_cf(
+                StateMachineColumns::ExtractionPolicies.cf(db),
+                &task.extraction_policy_id,
+            )
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+            .map(|bytes| JsonEncoder::decode::<ExtractionPolicy>(&bytes))
+            .transpose()?
+            .map_or_else(|| task.extraction_policy_id.clone(), |policy| policy.name))
+    }
+
+    /// Meters the time a finished task spent between being assigned and
+    /// finishing under the name of its extraction policy. Tasks that finish
+    /// without having been assigned, like cancelled tasks, are not metered.
+    fn meter_finished_task(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        task: &internal_api::Task,
+        finished_at: SystemTime,
+    ) -> Result<(), StateMachineError> {
+        let cf = StateMachineColumns::TaskAssignmentTimes.cf(db);
+        let Some(assigned_at) = txn
+            .get_cf(cf, &task.id)
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+            .map(|bytes| JsonEncoder::decode::<SystemTime>(&bytes))
+            .transpose()?
+        else {
+            return Ok(());
+        };
+        txn.delete_cf(cf, &task.id)
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
+        let task_seconds = finished_at
+            .duration_since(assigned_at)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let extraction_policy_name = self.task_extraction_policy_name(db, txn, task)?;
+        self.update_metering_record(db, txn, &task.namespace, finished_at, |record| {
+            *record
+                .task_seconds
+                .entry(format!(
+                    "{}/{}",
+                    task.extraction_graph_name, extraction_policy_name
+                ))
+                .or_default() += task_seconds;
+        })
+    }
+
+    /// Adds the usage a finished task reported to the usage of the current
+    /// version of its extraction graph
+    fn add_extraction_graph_usage(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        task: &internal_api::Task,
+        usage: &internal_api::TaskUsage,
+    ) -> Result<(), StateMachineError> {
+        let extraction_graph_id =
+            ExtractionGraph::create_id(&task.extraction_graph_name, &task.namespace);
+        let version = self
+            .get_latest_extraction_graph_version(db, &extraction_graph_id)?
+            .map_or(1, |version| version.version);
+        let extraction_policy_name = self.task_extraction_policy_name(db, txn, task)?;
+
+        let key = internal_api::ExtractionGraphUsage::key(&extraction_graph_id, version);
+        let cf = StateMachineColumns::ExtractionGraphUsage.cf(db);
+        let mut graph_usage = txn
+            .get_cf(cf, &key)
+            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+            .map(|bytes| JsonEncoder::decode::<internal_api::ExtractionGraphUsage>(&bytes))
+            .transpose()?
+            .unwrap_or_else(|| internal_api::ExtractionGraphUsage {
+                namespace: task.namespace.clone(),
+                extraction_graph: task.extraction_graph_name.clone(),
+                version,
+                ..Default::default()
+            });
+        graph_usage
+            .extraction_policies
+            .entry(extraction_policy_name)
+            .or_default()
+            .add(usage);
+        txn.put_cf(cf, key, JsonEncoder::encode(&graph_usage)?)
+            .map_err(|e| {
+                StateMachineError::DatabaseError(format!(
+                    "error writing extraction graph usage: {}",
+                    e
+                ))
+            })
+    }
+
+    fn set_schema(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        schema: &internal_api::StructuredDataSchema,
+    ) -> Result<(), StateMachineError> {
+        let serialized_schema = JsonEncoder::encode(schema)?;
+        txn.put_cf(
+            &StateMachineColumns::StructuredDataSchemas.cf(db),
+            schema.id.clone(),
+            serialized_schema,
+        )
+        .map_err(|e| StateMachineError::DatabaseError(format!("Error writing schema: {}", e)))?;
+        Ok(())
+    }
+
+    pub fn update_content_extraction_policy_state(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        txn: &rocksdb::Transaction<OptimisticTransactionDB>,
+        content_id: &ContentMetadataId,
         extraction_policy_id: &str,
         policy_completion_time: SystemTime,
     ) -> Result<(), StateMachineError> {
@@ -1212,8 +3109,14 @@ impl IndexifyState {
         state_change: &StateChangeProcessed,
         _processed_at: u64,
     ) {
-        self.unprocessed_state_changes
-            .remove(&state_change.state_change_id);
+        //  Changes are marked both by the requests that handle them and by the
+        //  scheduler, only count them the first time
+        if self
+            .unprocessed_state_changes
+            .remove(&state_change.state_change_id)
+        {
+            self.metrics.lock().unwrap().state_changes_processed += 1;
+        }
     }
 
     fn update_extraction_graph_reverse_idx(
@@ -1234,13 +3137,105 @@ impl IndexifyState {
 
     /// This method will make all state machine forward index writes to RocksDB
     pub fn apply_state_machine_updates(
+        &self,
+        request: StateMachineUpdateRequest,
+        db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<Vec<StateChange>, StateMachineError> {
+        self.apply_state_machine_updates_with(request, db, |_| Ok(()))
+    }
+
+    /// Applies `request` like `apply_state_machine_updates`. `before_commit`
+    /// is called with the transaction of the request once the reverse indexes
+    /// reflect it, so whatever it writes is committed together with the
+    /// request, including when the request turns out to be a no-op.
+    pub fn apply_state_machine_updates_with(
         &self,
         mut request: StateMachineUpdateRequest,
         db: &Arc<OptimisticTransactionDB>,
+        before_commit: impl FnOnce(
+            &rocksdb::Transaction<OptimisticTransactionDB>,
+        ) -> Result<(), StateMachineError>,
     ) -> Result<Vec<StateChange>, StateMachineError> {
         let txn = db.transaction();
 
-        self.set_new_state_changes(db, &txn, &mut request.new_state_changes)?;
+        let violations = self.invariant_violations(db, &txn, &request.payload)?;
+        for violation in &violations {
+            error!("state machine invariant violation: {}", violation);
+        }
+        if !violations.is_empty() && self.invariant_validation == InvariantValidation::Strict {
+            //  The payload is dropped together with the state changes it
+            //  would have made. The state changes the request processed are
+            //  still marked processed, so that they aren't processed again.
+            request.payload = RequestPayload::MarkStateChangesProcessed {
+                state_changes: Vec::new(),
+            };
+            request.new_state_changes.clear();
+        }
+
+        match &mut request.payload {
+            RequestPayload::UpdateTask {
+                task, executor_id, ..
+            } => {
+                if !self.fence_task_update(db, &txn, task, executor_id)? {
+                    commit(txn, before_commit)?;
+                    return Ok(Vec::new());
+                }
+            }
+            RequestPayload::UpdateTasks { updates, .. } => {
+                //  A task listed twice would be finalized twice
+                let mut task_ids = HashSet::new();
+                let mut fenced_updates = Vec::with_capacity(updates.len());
+                for mut update in updates.drain(..) {
+                    if task_ids.insert(update.task.id.clone()) &&
+                        self.fence_task_update(db, &txn, &update.task, &mut update.executor_id)?
+                    {
+                        fenced_updates.push(update);
+                    }
+                }
+                *updates = fenced_updates;
+            }
+            RequestPayload::AssignTask { assignments, .. } => {
+                self.fence_task_assignments(db, &txn, assignments)?;
+            }
+            RequestPayload::ReapOrphanedAssignments {
+                finished_task_assignments,
+                unallocated_tasks,
+                ..
+            } => {
+                if !self.fence_reaped_assignments(
+                    db,
+                    &txn,
+                    finished_task_assignments,
+                    unallocated_tasks,
+                )? {
+                    commit(txn, before_commit)?;
+                    return Ok(Vec::new());
+                }
+            }
+            RequestPayload::ShedBurstTasks { tasks, .. } => {
+                if !self.fence_shed_burst_tasks(db, &txn, tasks)? {
+                    commit(txn, before_commit)?;
+                    return Ok(Vec::new());
+                }
+            }
+            RequestPayload::RepairContent { tasks, .. } => {
+                self.fence_repaired_tasks(tasks);
+            }
+            _ => {}
+        }
+
+        //  Transitions take the time the request carries, never the clock of
+        //  the node applying it, so that every node records the same history.
+        //  Requests logged before they carried a time record 0.
+        let at = request.timestamp().unwrap_or_default();
+        let at_time = SystemTime::UNIX_EPOCH + Duration::from_secs(at);
+        let transition = |state, executor_id: Option<&String>, outcome| TaskTransition {
+            state,
+            executor_id: executor_id.cloned(),
+            outcome,
+            at,
+        };
+        self.set_new_state_changes(db, &txn, &mut request.new_state_changes, at_time)?;
         let mut state_changes_processed =
             self.set_processed_state_changes(db, &txn, &request.state_changes_processed)?;
 
@@ -1250,10 +3245,50 @@ impl IndexifyState {
                     self.set_index(db, &txn, index, &index.id)?;
                 }
             }
-            RequestPayload::CreateTasks { tasks } => {
+            RequestPayload::CreateTasks { tasks, .. } => {
                 self.set_tasks(db, &txn, tasks)?;
+                self.record_task_transitions(
+                    db,
+                    &txn,
+                    tasks.iter().map(|task| &task.id),
+                    transition(TaskState::Pending, None, None),
+                )?;
                 for task in tasks {
-                    self.inc_root_ref_count(task.content_metadata.get_root_id());
+                    self.inc_root_ref_count(task.content_metadata.get_root_id(), at_time);
+                    self.mark_root_active(task);
+                }
+            }
+            RequestPayload::RepairContent {
+                root_content_id,
+                tasks,
+                pending_task_ids,
+                pending_state_change_ids,
+                ..
+            } => {
+                self.set_tasks(db, &txn, tasks)?;
+                self.record_task_transitions(
+                    db,
+                    &txn,
+                    tasks.iter().map(|task| &task.id),
+                    transition(TaskState::Pending, None, None),
+                )?;
+                //  The outstanding count is what is still pending for the
+                //  content plus the tasks re-created for it
+                let mut pending = pending_state_change_ids
+                    .iter()
+                    .filter(|id| self.unprocessed_state_changes.contains(id))
+                    .count();
+                for task_id in pending_task_ids {
+                    if self
+                        .read_task(db, &txn, task_id)?
+                        .is_some_and(|task| !task.terminal_state())
+                    {
+                        pending += 1;
+                    }
+                }
+                self.set_root_ref_count(root_content_id, (pending + tasks.len()) as u64, at_time);
+                for task in tasks {
+                    self.mark_root_active(task);
                 }
             }
             RequestPayload::CreateOrAssignGarbageCollectionTask { gc_tasks } => {
@@ -1269,7 +3304,49 @@ impl IndexifyState {
                     self.delete_content(db, &txn, vec![gc_task.content_id.clone()])?;
                 }
             }
-            RequestPayload::AssignTask { assignments } => {
+            RequestPayload::AssignTask {
+                assignments,
+                assigned_at,
+                gpu_devices,
+                burst_tasks,
+                content_zones,
+            } => {
+                if let Some(assigned_at) = assigned_at {
+                    self.set_task_assignment_times(db, &txn, assignments.keys(), *assigned_at)?;
+                }
+                self.set_gpu_devices(db, &txn, assignments, gpu_devices)?;
+                self.set_burst_tasks(db, &txn, assignments, burst_tasks)?;
+                self.add_content_zones(db, &txn, assignments, content_zones)?;
+
+                //  NOTE: Special case where forward and reverse indexes are updated together,
+                //  tasks moved away from an executor that is gone release its slots
+                let mut moved: HashMap<ExecutorId, HashSet<TaskId>> = HashMap::new();
+                for (task_id, executor_id) in assignments.iter() {
+                    if let Some(owner) = self.task_executors.get(task_id) {
+                        if owner != *executor_id {
+                            moved.entry(owner).or_default().insert(task_id.clone());
+                        }
+                    }
+                }
+                for (owner, task_ids) in moved {
+                    let mut existing_tasks =
+                        self.get_task_assignments_for_executor(db, &txn, &owner)?;
+                    existing_tasks.retain(|task_id| !task_ids.contains(task_id));
+                    for _ in &task_ids {
+                        self.executor_running_task_count
+                            .decrement_running_task_count(&owner);
+                    }
+                    self.set_task_assignments(db, &txn, &HashMap::from([(owner, existing_tasks)]))?;
+                }
+
+                for (task_id, executor_id) in assignments {
+                    self.record_task_transitions(
+                        db,
+                        &txn,
+                        [task_id],
+                        transition(TaskState::Running, Some(executor_id), None),
+                    )?;
+                }
                 let assignments: HashMap<&String, HashSet<TaskId>> =
                     assignments
                         .iter()
@@ -1290,45 +3367,136 @@ impl IndexifyState {
                     self.set_task_assignments(db, &txn, &task_assignment)?;
                 }
             }
+            RequestPayload::ReapOrphanedAssignments {
+                finished_task_assignments,
+                unallocated_tasks,
+                ..
+            } => {
+                self.record_task_transitions(
+                    db,
+                    &txn,
+                    unallocated_tasks,
+                    transition(TaskState::Pending, None, None),
+                )?;
+                for (executor_id, task_ids) in finished_task_assignments {
+                    let mut existing_tasks =
+                        self.get_task_assignments_for_executor(db, &txn, executor_id)?;
+                    existing_tasks.retain(|task_id| !task_ids.contains(task_id));
+                    let task_assignment = HashMap::from([(executor_id.clone(), existing_tasks)]);
+                    self.set_task_assignments(db, &txn, &task_assignment)?;
+                }
+            }
+            RequestPayload::ShedBurstTasks { tasks, .. } => {
+                for (executor_id, task_ids) in tasks {
+                    let mut existing_tasks =
+                        self.get_task_assignments_for_executor(db, &txn, executor_id)?;
+                    existing_tasks.retain(|task_id| !task_ids.contains(task_id));
+                    let task_assignment = HashMap::from([(executor_id.clone(), existing_tasks)]);
+                    self.set_task_assignments(db, &txn, &task_assignment)?;
+                    //  The executor gave the tasks back, it didn't lose them
+                    let task_ids: Vec<TaskId> = task_ids.iter().cloned().collect();
+                    self.record_lost_task_attempts(db, &txn, executor_id, &task_ids, false)?;
+                    self.record_task_transitions(
+                        db,
+                        &txn,
+                        &task_ids,
+                        transition(TaskState::Pending, Some(executor_id), None),
+                    )?;
+                }
+            }
             RequestPayload::UpdateTask {
                 task,
                 executor_id,
                 update_time,
             } => {
-                self.update_tasks(db, &txn, vec![task], *update_time)?;
-
-                if task.terminal_state() {
-                    self.metrics
-                        .lock()
-                        .unwrap()
-                        .update_task_completion(task.outcome);
-
-                    //  If the task is meant to be marked finished and has an executor id, remove it
-                    // from the list of tasks assigned to an executor
-                    if let Some(executor_id) = executor_id {
-                        let mut existing_tasks =
-                            self.get_task_assignments_for_executor(db, &txn, executor_id)?;
-                        existing_tasks.remove(&task.id);
-                        let new_task_assignment =
-                            HashMap::from([(executor_id.to_string(), existing_tasks)]);
-                        self.set_task_assignments(db, &txn, &new_task_assignment)?;
-                    }
-                    self.dec_root_ref_count(task.content_metadata.get_root_id());
+                self.finalize_task(
+                    db,
+                    &txn,
+                    task,
+                    executor_id.as_ref(),
+                    *update_time,
+                    transition(
+                        TaskState::Finalized,
+                        executor_id.as_ref(),
+                        Some(task.outcome),
+                    ),
+                )?;
+            }
+            RequestPayload::UpdateTasks {
+                updates,
+                update_time,
+            } => {
+                for TaskUpdate { task, executor_id } in updates {
+                    self.finalize_task(
+                        db,
+                        &txn,
+                        task,
+                        executor_id.as_ref(),
+                        *update_time,
+                        transition(
+                            TaskState::Finalized,
+                            executor_id.as_ref(),
+                            Some(task.outcome),
+                        ),
+                    )?;
                 }
             }
             RequestPayload::RegisterExecutor {
                 addr,
                 executor_id,
                 extractors,
+                cost_per_hour,
+                capacity_weight,
+                zone,
+                max_payload_bytes,
+                download_bytes_per_sec,
+                gpu_devices,
+                graph_versions,
                 ts_secs,
             } => {
                 //  Insert the executor
-                self.set_executor(db, &txn, addr.into(), executor_id, extractors, ts_secs)?;
+                let executor = internal_api::ExecutorMetadata {
+                    id: executor_id.clone(),
+                    last_seen: *ts_secs,
+                    addr: addr.clone(),
+                    extractors: extractors.clone(),
+                    cost_per_hour: *cost_per_hour,
+                    capacity_weight: *capacity_weight,
+                    zone: zone.clone(),
+                    max_payload_bytes: *max_payload_bytes,
+                    download_bytes_per_sec: *download_bytes_per_sec,
+                    draining: false,
+                    pressure: None,
+                    gpu_devices: gpu_devices.clone(),
+                    graph_versions: graph_versions.clone(),
+                };
+                self.set_executor(db, &txn, &executor)?;
 
                 //  Insert the associated extractors
                 self.set_extractors(db, &txn, extractors)?;
             }
-            RequestPayload::RemoveExecutor { executor_id } => {
+            RequestPayload::RenewExecutorLease {
+                executor_id,
+                ts_secs,
+            } => {
+                self.renew_executor_lease(db, &txn, executor_id, *ts_secs)?;
+            }
+            RequestPayload::DrainExecutor { executor_id } => {
+                self.update_executor(db, &txn, executor_id, |executor| executor.draining = true)?;
+            }
+            RequestPayload::SetExecutorPressure {
+                executor_id,
+                pressure,
+            } => {
+                self.update_executor(db, &txn, executor_id, |executor| {
+                    executor.pressure = pressure.clone()
+                })?;
+            }
+            RequestPayload::RemoveExecutor {
+                executor_id,
+                graceful,
+                ..
+            } => {
                 //  NOTE: Special case where forward and reverse indexes are updated together
 
                 //  Get a handle on the executor before deleting it from the DB
@@ -1336,9 +3504,13 @@ impl IndexifyState {
 
                 // Remove all tasks assigned to this executor and get a handle on the task ids
                 let task_ids = self.delete_task_assignments_for_executor(db, &txn, executor_id)?;
-
-                txn.commit()
-                    .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
+                self.record_lost_task_attempts(db, &txn, executor_id, &task_ids, !graceful)?;
+                self.record_task_transitions(
+                    db,
+                    &txn,
+                    &task_ids,
+                    transition(TaskState::Pending, Some(executor_id), None),
+                )?;
 
                 //  Remove the extractors from the executor -> extractor mapping table
                 if let Some(executor_meta) = executor_meta {
@@ -1351,14 +3523,19 @@ impl IndexifyState {
                 //  Put the tasks of the deleted executor into the unassigned tasks list
                 for task_id in task_ids {
                     self.unassigned_tasks.insert(&task_id);
+                    self.task_queues_by_extraction_policy
+                        .mark_unallocated(&task_id, at_time);
                 }
 
                 // Remove from the executor load table
                 self.executor_running_task_count.remove(executor_id);
+                self.recent_extraction_policies.remove_executor(executor_id);
 
+                commit(txn, before_commit)?;
                 return Ok(request.new_state_changes);
             }
             RequestPayload::CreateOrUpdateContent { entries } => {
+                self.meter_new_content(db, &txn, entries)?;
                 self.set_content(db, &txn, entries.iter().map(|e| &e.content))?;
             }
             RequestPayload::TombstoneContentTree { content_metadata } => {
@@ -1367,6 +3544,104 @@ impl IndexifyState {
             RequestPayload::CreateNamespace { name } => {
                 self.set_namespace(db, &txn, name)?;
             }
+            RequestPayload::SetContentContext {
+                root_content_id,
+                key,
+                value,
+            } => {
+                self.set_content_context(db, &txn, root_content_id, key, value)?;
+            }
+            RequestPayload::SetSchedulerSettings { settings } => {
+                self.set_scheduler_settings(db, &txn, settings)?;
+            }
+            RequestPayload::SetMaintenanceMode { maintenance } => {
+                self.set_maintenance_mode(db, &txn, maintenance)?;
+            }
+            RequestPayload::RecordEgress {
+                namespace,
+                bytes,
+                time,
+            } => {
+                self.update_metering_record(db, &txn, namespace, *time, |record| {
+                    record.egress_bytes += bytes;
+                })?;
+            }
+            RequestPayload::ClearTaskDiagnostics { task_ids } => {
+                self.clear_task_diagnostics(db, &txn, task_ids)?;
+            }
+            RequestPayload::UpdateTaskProgress {
+                executor_id,
+                progress,
+            } => {
+                self.update_task_progress(db, &txn, executor_id, progress)?;
+            }
+            RequestPayload::CreateReplay { replay } => {
+                self.create_replay(db, &txn, replay)?;
+            }
+            RequestPayload::SetGraphTemplate { template } => {
+                txn.put_cf(
+                    StateMachineColumns::GraphTemplates.cf(db),
+                    &template.name,
+                    JsonEncoder::encode(template)?,
+                )
+                .map_err(|e| {
+                    StateMachineError::DatabaseError(format!("error writing graph template: {}", e))
+                })?;
+            }
+            RequestPayload::DeleteGraphTemplate { name } => {
+                txn.delete_cf(StateMachineColumns::GraphTemplates.cf(db), name)
+                    .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
+            }
+            RequestPayload::SetCancelTasksJob { job } => {
+                txn.put_cf(
+                    StateMachineColumns::CancelTasksJobs.cf(db),
+                    &job.id,
+                    JsonEncoder::encode(job)?,
+                )
+                .map_err(|e| {
+                    StateMachineError::DatabaseError(format!(
+                        "error writing cancel tasks job: {}",
+                        e
+                    ))
+                })?;
+            }
+            RequestPayload::DeleteCancelTasksJobs { job_ids } => {
+                for job_id in job_ids {
+                    txn.delete_cf(StateMachineColumns::CancelTasksJobs.cf(db), job_id)
+                        .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
+                }
+            }
+            RequestPayload::UpdateContentReplicas {
+                added,
+                removed,
+                processed,
+            } => {
+                for replica in added {
+                    txn.put_cf(
+                        StateMachineColumns::ContentReplicas.cf(db),
+                        &replica.content_id,
+                        JsonEncoder::encode(replica)?,
+                    )
+                    .map_err(|e| {
+                        StateMachineError::DatabaseError(format!(
+                            "error writing content replica: {}",
+                            e
+                        ))
+                    })?;
+                }
+                for content_id in removed {
+                    txn.delete_cf(StateMachineColumns::ContentReplicas.cf(db), content_id)
+                        .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
+                }
+                for (content_id, hash) in processed {
+                    //  Content that changed since the replicator read it stays
+                    //  pending
+                    if self.live_content_hash(db, &txn, content_id)? == *hash {
+                        txn.delete_cf(StateMachineColumns::PendingReplications.cf(db), content_id)
+                            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
+                    }
+                }
+            }
             RequestPayload::MarkStateChangesProcessed { state_changes } => {
                 let payload_changes_processed =
                     self.set_processed_state_changes(db, &txn, state_changes)?;
@@ -1374,8 +3649,8 @@ impl IndexifyState {
             }
             RequestPayload::JoinCluster {
                 node_id,
-                address: _,
                 coordinator_addr,
+                ..
             } => {
                 self.set_coordinator_addr(db, &txn, *node_id, coordinator_addr)?;
             }
@@ -1383,8 +3658,18 @@ impl IndexifyState {
                 extraction_graph,
                 structured_data_schema,
                 indexes,
+                change,
             } => {
-                self.set_extraction_graph(db, &txn, extraction_graph, structured_data_schema)?;
+                self.add_extraction_graph_version(db, &txn, extraction_graph, change)?;
+                let removed_policies =
+                    self.set_extraction_graph(db, &txn, extraction_graph, structured_data_schema)?;
+                //  NOTE: The request doesn't carry the removed policies, so
+                //  their reverse index is updated together with the forward
+                //  index
+                for policy in removed_policies {
+                    self.extraction_policies_table
+                        .remove(&policy.namespace, &policy.id);
+                }
                 for index in indexes {
                     self.set_index(db, &txn, index, &index.id)?;
                 }
@@ -1395,7 +3680,7 @@ impl IndexifyState {
         for state_change in state_changes_processed {
             if unprocessed_changes.contains(&state_change.id) {
                 if let Some(refcnt_object_id) = &state_change.refcnt_object_id {
-                    self.dec_root_ref_count(&refcnt_object_id);
+                    self.dec_root_ref_count(&refcnt_object_id, at_time);
                 }
             }
         }
@@ -1409,15 +3694,55 @@ impl IndexifyState {
             ))
         })?;
 
-        txn.commit()
-            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
+        commit(txn, before_commit)?;
 
         Ok(new_state_changes)
     }
 
-    /// This method handles all reverse index writes. All reverse indexes are
-    /// written in memory
+    /// This method handles all reverse index writes. All reverse indexes are
+    /// written in memory
+    fn finalize_task_reverse_indexes(
+        &self,
+        task: &internal_api::Task,
+        executor_id: Option<&ExecutorId>,
+        update_time: SystemTime,
+    ) {
+        if !task.terminal_state() {
+            return;
+        }
+        self.unassigned_tasks.remove(&task.id);
+        self.unfinished_tasks_by_extractor
+            .remove(&task.extractor, &task.id);
+        let latency = self
+            .task_queues_by_extraction_policy
+            .mark_completed(&task.id, update_time);
+        self.finished_tasks.record(
+            &task.extraction_policy_id,
+            update_time,
+            latency,
+            matches!(task.outcome, TaskOutcome::Failed | TaskOutcome::Quarantined),
+        );
+        if let Some(executor_id) = executor_id {
+            self.executor_running_task_count
+                .decrement_running_task_count(executor_id);
+            self.recent_extraction_policies.record(
+                executor_id,
+                &task.extraction_policy_id,
+                update_time,
+            );
+        }
+        self.pending_tasks_for_content.remove(
+            &task.content_metadata.id,
+            &task.extraction_policy_id,
+            &task.id,
+        );
+    }
+
     pub fn update_reverse_indexes(&self, request: StateMachineUpdateRequest) -> Result<()> {
+        //  Tasks are queued at the time the request carries, like their
+        //  transitions
+        let at =
+            SystemTime::UNIX_EPOCH + Duration::from_secs(request.timestamp().unwrap_or_default());
         for change in request.new_state_changes {
             self.unprocessed_state_changes.insert(change.id);
         }
@@ -1429,6 +3754,13 @@ impl IndexifyState {
                 addr,
                 executor_id,
                 extractors,
+                cost_per_hour,
+                capacity_weight,
+                zone,
+                max_payload_bytes,
+                download_bytes_per_sec,
+                gpu_devices,
+                graph_versions,
                 ts_secs,
             } => {
                 // Inserts the executor list of extractors to the executor -> extractor mapping
@@ -1443,16 +3775,31 @@ impl IndexifyState {
                     last_seen: ts_secs,
                     addr: addr.clone(),
                     extractors: extractors.clone(),
+                    cost_per_hour,
+                    capacity_weight,
+                    zone,
+                    max_payload_bytes,
+                    download_bytes_per_sec,
+                    draining: false,
+                    pressure: None,
+                    gpu_devices,
+                    graph_versions,
                 };
                 // initialize executor load at 0
                 self.executor_running_task_count.insert(&executor_id, 0);
                 Ok(())
             }
-            RequestPayload::CreateTasks { tasks } => {
+            RequestPayload::CreateTasks { tasks } | RequestPayload::RepairContent { tasks, .. } => {
                 for task in tasks {
                     self.unassigned_tasks.insert(&task.id);
                     self.unfinished_tasks_by_extractor
                         .insert(&task.extractor, &task.id);
+                    self.task_queues_by_extraction_policy.insert_unallocated(
+                        &task.extraction_policy_id,
+                        &task.id,
+                        task.content_metadata.priority,
+                        at,
+                    );
                     self.pending_tasks_for_content.insert(
                         &task.content_metadata.id,
                         &task.extraction_policy_id,
@@ -1461,20 +3808,67 @@ impl IndexifyState {
                 }
                 Ok(())
             }
-            RequestPayload::AssignTask { assignments } => {
+            RequestPayload::AssignTask { assignments, .. } => {
                 for (task_id, executor_id) in assignments {
                     self.unassigned_tasks.remove(&task_id);
+                    self.task_queues_by_extraction_policy.mark_running(&task_id);
 
                     self.executor_running_task_count
                         .increment_running_task_count(&executor_id);
                 }
                 Ok(())
             }
-            RequestPayload::CreateOrAssignGarbageCollectionTask { gc_tasks: _ } => Ok(()),
+            RequestPayload::ReapOrphanedAssignments {
+                finished_task_assignments,
+                unallocated_tasks,
+                ..
+            } => {
+                for (executor_id, task_ids) in finished_task_assignments {
+                    for _ in task_ids {
+                        self.executor_running_task_count
+                            .decrement_running_task_count(&executor_id);
+                    }
+                }
+                for task_id in unallocated_tasks {
+                    self.unassigned_tasks.insert(&task_id);
+                    self.task_queues_by_extraction_policy
+                        .mark_unallocated(&task_id, at);
+                }
+                Ok(())
+            }
+            RequestPayload::ShedBurstTasks { tasks, .. } => {
+                for (executor_id, task_ids) in tasks {
+                    for task_id in task_ids {
+                        self.executor_running_task_count
+                            .decrement_running_task_count(&executor_id);
+                        self.unassigned_tasks.insert(&task_id);
+                        self.task_queues_by_extraction_policy
+                            .mark_unallocated(&task_id, at);
+                    }
+                }
+                Ok(())
+            }
+            RequestPayload::CreateOrAssignGarbageCollectionTask { gc_tasks } => {
+                let mut unfinished_gc_tasks = self.unfinished_gc_tasks.write().unwrap();
+                for gc_task in gc_tasks {
+                    if gc_task.outcome == TaskOutcome::Unknown {
+                        unfinished_gc_tasks.insert(gc_task.id);
+                    } else {
+                        unfinished_gc_tasks.remove(&gc_task.id);
+                    }
+                }
+                Ok(())
+            }
             RequestPayload::UpdateGarbageCollectionTask {
                 gc_task,
                 mark_finished,
             } => {
+                if mark_finished {
+                    self.unfinished_gc_tasks
+                        .write()
+                        .unwrap()
+                        .remove(&gc_task.id);
+                }
                 if mark_finished && gc_task.task_type == ServerTaskType::Delete {
                     self.content_children_table.remove_all(&gc_task.content_id);
                 }
@@ -1505,6 +3899,7 @@ impl IndexifyState {
                 extraction_graph,
                 structured_data_schema,
                 indexes,
+                ..
             } => {
                 self.update_extraction_graph_reverse_idx(&extraction_graph, structured_data_schema);
                 for index in indexes {
@@ -1517,22 +3912,17 @@ impl IndexifyState {
             RequestPayload::UpdateTask {
                 task,
                 executor_id,
-                update_time: _,
+                update_time,
             } => {
-                if task.terminal_state() {
-                    self.unassigned_tasks.remove(&task.id);
-                    self.unfinished_tasks_by_extractor
-                        .remove(&task.extractor, &task.id);
-                    if let Some(ref executor_id) = executor_id {
-                        self.executor_running_task_count
-                            .decrement_running_task_count(executor_id);
-                    }
-                    let content_id = task.content_metadata.id;
-                    self.pending_tasks_for_content.remove(
-                        &content_id,
-                        &task.extraction_policy_id,
-                        &task.id,
-                    );
+                self.finalize_task_reverse_indexes(&task, executor_id.as_ref(), update_time);
+                Ok(())
+            }
+            RequestPayload::UpdateTasks {
+                updates,
+                update_time,
+            } => {
+                for TaskUpdate { task, executor_id } in &updates {
+                    self.finalize_task_reverse_indexes(task, executor_id.as_ref(), update_time);
                 }
                 Ok(())
             }
@@ -1581,6 +3971,29 @@ impl IndexifyState {
         Ok(Some(result))
     }
 
+    /// Fetches the values of `keys` from a column family with a single
+    /// multi_get. The results are in the same order as the keys and are
+    /// `None` for keys that are not found.
+    fn multi_get_from_cf<T, K>(
+        db: &Arc<OptimisticTransactionDB>,
+        column: StateMachineColumns,
+        keys: impl IntoIterator<Item = K>,
+    ) -> Result<Vec<Option<T>>, StateMachineError>
+    where
+        T: DeserializeOwned,
+        K: AsRef<[u8]>,
+    {
+        let cf = column.cf(db);
+        db.multi_get_cf(keys.into_iter().map(|key| (cf, key)))
+            .into_iter()
+            .map(|res| {
+                res.map_err(|e| StateMachineError::TransactionError(e.to_string()))?
+                    .map(|bytes| JsonEncoder::decode::<T>(&bytes).map_err(StateMachineError::from))
+                    .transpose()
+            })
+            .collect()
+    }
+
     /// This method is used to get the tasks assigned to an executor
     /// It does this by looking up the TaskAssignments CF to get the task id's
     /// and then using those id's to look up tasks via Tasks CF
@@ -1596,7 +4009,7 @@ impl IndexifyState {
             .get_cf(StateMachineColumns::TaskAssignments.cf(db), executor_id)
             .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
 
-        let task_ids: Vec<String> = task_ids_bytes
+        let mut task_ids: Vec<String> = task_ids_bytes
             .map(|task_id_bytes| {
                 JsonEncoder::decode(&task_id_bytes)
                     .map_err(StateMachineError::from)
@@ -1607,23 +4020,24 @@ impl IndexifyState {
             })
             .unwrap_or_else(Vec::new);
 
-        // FIXME Use MULTIGET
-        let limit = limit.unwrap_or(task_ids.len() as u64) as usize;
-
-        let tasks: Result<Vec<indexify_internal_api::Task>, StateMachineError> = task_ids
-            .into_iter()
-            .take(limit)
-            .map(|task_id| {
-                let task_bytes = txn
-                    .get_cf(StateMachineColumns::Tasks.cf(db), task_id.as_bytes())
-                    .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
-                    .ok_or_else(|| {
-                        StateMachineError::DatabaseError(format!("Task {} not found", task_id))
-                    })?;
-                JsonEncoder::decode(&task_bytes).map_err(StateMachineError::from)
+        //  Higher priority tasks go first, so they are the ones handed out when
+        //  the executor asks for fewer tasks than it was assigned. Only the
+        //  tasks handed out are read.
+        self.task_queues_by_extraction_policy
+            .sort_by_priority(&mut task_ids);
+        if let Some(limit) = limit {
+            task_ids.truncate(limit as usize);
+        }
+        let tasks = Self::multi_get_from_cf(db, StateMachineColumns::Tasks, task_ids.iter())?;
+        task_ids
+            .iter()
+            .zip(tasks)
+            .map(|(task_id, task)| {
+                task.ok_or_else(|| {
+                    StateMachineError::DatabaseError(format!("Task {} not found", task_id))
+                })
             })
-            .collect();
-        tasks
+            .collect()
     }
 
     /// This method will fetch indexes based on the id's of the indexes provided
@@ -1632,20 +4046,18 @@ impl IndexifyState {
         task_ids: HashSet<TaskId>,
         db: &Arc<OptimisticTransactionDB>,
     ) -> Result<Vec<indexify_internal_api::Index>, StateMachineError> {
-        let txn = db.transaction();
-        let indexes: Result<Vec<indexify_internal_api::Index>, StateMachineError> = task_ids
-            .into_iter()
-            .map(|task_id| {
-                let index_bytes = txn
-                    .get_cf(StateMachineColumns::IndexTable.cf(db), task_id.as_bytes())
-                    .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
-                    .ok_or_else(|| {
-                        StateMachineError::DatabaseError(format!("Index {} not found", task_id))
-                    })?;
-                JsonEncoder::decode(&index_bytes).map_err(StateMachineError::from)
+        let task_ids = task_ids.into_iter().collect_vec();
+        let indexes =
+            Self::multi_get_from_cf(db, StateMachineColumns::IndexTable, task_ids.iter())?;
+        task_ids
+            .iter()
+            .zip(indexes)
+            .map(|(task_id, index)| {
+                index.ok_or_else(|| {
+                    StateMachineError::DatabaseError(format!("Index {} not found", task_id))
+                })
             })
-            .collect();
-        indexes
+            .collect()
     }
 
     /// This method will fetch the executors from RocksDB CF based on the
@@ -1655,27 +4067,18 @@ impl IndexifyState {
         executor_ids: HashSet<String>,
         db: &Arc<OptimisticTransactionDB>,
     ) -> Result<Vec<indexify_internal_api::ExecutorMetadata>, StateMachineError> {
-        let txn = db.transaction();
-        let executors: Result<Vec<indexify_internal_api::ExecutorMetadata>, StateMachineError> =
-            executor_ids
-                .into_iter()
-                .map(|executor_id| {
-                    let executor_bytes = txn
-                        .get_cf(
-                            StateMachineColumns::Executors.cf(db),
-                            executor_id.as_bytes(),
-                        )
-                        .map_err(|e| StateMachineError::TransactionError(e.to_string()))?
-                        .ok_or_else(|| {
-                            StateMachineError::DatabaseError(format!(
-                                "Executor {} not found",
-                                executor_id
-                            ))
-                        })?;
-                    JsonEncoder::decode(&executor_bytes).map_err(StateMachineError::from)
+        let executor_ids = executor_ids.into_iter().collect_vec();
+        let executors =
+            Self::multi_get_from_cf(db, StateMachineColumns::Executors, executor_ids.iter())?;
+        executor_ids
+            .iter()
+            .zip(executors)
+            .map(|(executor_id, executor)| {
+                executor.ok_or_else(|| {
+                    StateMachineError::DatabaseError(format!("Executor {} not found", executor_id))
                 })
-                .collect();
-        executors
+            })
+            .collect()
     }
 
     pub fn get_content_by_id_and_version(
@@ -1778,130 +4181,773 @@ impl IndexifyState {
             }
         }
 
-        Ok(collected_content_metadata)
+        Ok(collected_content_metadata)
+    }
+
+    /// This method will fetch all pieces of content metadata for the tree
+    /// rooted at latest version of content_id.
+    pub fn get_content_tree_metadata(
+        &self,
+        content_id: &str,
+        db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<Vec<indexify_internal_api::ContentMetadata>, StateMachineError> {
+        self.get_content_tree_metadata_inner(content_id, None, db)
+    }
+
+    /// This method will fetch all pieces of content metadata for the tree
+    /// rooted at overwritten/deleted content_id. It will look for a specfic
+    /// version of the node.
+    pub fn get_content_tree_metadata_with_version(
+        &self,
+        content_id: &ContentMetadataId,
+        db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<Vec<indexify_internal_api::ContentMetadata>, StateMachineError> {
+        self.get_content_tree_metadata_inner(&content_id.id, Some(content_id.version), db)
+    }
+
+    /// This method tries to retrieve all policies based on id's. If it cannot
+    /// find any, it skips them. If it encounters an error at any point
+    /// during the transaction, it returns out immediately
+    pub fn get_extraction_policies_from_ids(
+        &self,
+        extraction_policy_ids: HashSet<String>,
+        db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<Option<Vec<ExtractionPolicy>>, StateMachineError> {
+        // Policies that are not found are skipped
+        let policies: Vec<ExtractionPolicy> = Self::multi_get_from_cf(
+            db,
+            StateMachineColumns::ExtractionPolicies,
+            extraction_policy_ids.iter(),
+        )?
+        .into_iter()
+        .flatten()
+        .collect();
+
+        if policies.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(policies))
+        }
+    }
+
+    pub fn get_extraction_policy_by_names(
+        &self,
+        namespace: &str,
+        graph_name: &str,
+        policy_names: &HashSet<ExtractionPolicyName>,
+        db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<Vec<Option<ExtractionPolicy>>, StateMachineError> {
+        let mut policies = Vec::new();
+        for policy_name in policy_names {
+            let extraction_policy_id =
+                ExtractionPolicy::create_id(graph_name, policy_name, namespace);
+            let extraction_policy_bytes = db
+                .get_cf(
+                    StateMachineColumns::ExtractionPolicies.cf(db),
+                    extraction_policy_id.as_bytes(),
+                )
+                .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
+            match extraction_policy_bytes {
+                Some(bytes) => {
+                    let extraction_policy = JsonEncoder::decode::<ExtractionPolicy>(&bytes)?;
+                    policies.push(Some(extraction_policy))
+                }
+                None => policies.push(None),
+            }
+        }
+        Ok(policies)
+    }
+
+    /// This method gets all task assignments stored in the relevant CF
+    pub fn get_all_task_assignments(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<HashMap<TaskId, ExecutorId>, StateMachineError> {
+        let mut assignments = HashMap::new();
+        let iter = db.iterator_cf(
+            StateMachineColumns::TaskAssignments.cf(db),
+            rocksdb::IteratorMode::Start,
+        );
+        for item in iter {
+            let (key, value) = item.map_err(|e| {
+                StateMachineError::DatabaseError(format!(
+                    "unable to get values from task assignment {}",
+                    e
+                ))
+            })?;
+            let executor_id = String::from_utf8(key.to_vec()).map_err(|e| {
+                StateMachineError::DatabaseError(format!(
+                    "unable to get executor id from task assignment {}",
+                    e
+                ))
+            })?;
+            let task_ids: HashSet<TaskId> = JsonEncoder::decode(&value).map_err(|e| {
+                StateMachineError::DatabaseError(format!(
+                    "unable to decoded task hashset from task assignment {}",
+                    e
+                ))
+            })?;
+            for task_id in task_ids {
+                assignments.insert(task_id, executor_id.clone());
+            }
+        }
+        Ok(assignments)
+    }
+
+    /// Returns the unfinished tasks whose content expired at or before
+    /// `now_secs`
+    pub fn find_expired_tasks(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        now_secs: u64,
+    ) -> Result<Vec<internal_api::Task>, StateMachineError> {
+        let task_ids = self.unfinished_tasks_by_extractor.task_ids();
+        Ok(Self::multi_get_from_cf::<internal_api::Task, _>(
+            db,
+            StateMachineColumns::Tasks,
+            &task_ids,
+        )?
+        .into_iter()
+        .flatten()
+        .filter(|task| {
+            let expires_at = task.content_metadata.expires_at;
+            !task.terminal_state() && expires_at != 0 && expires_at <= now_secs
+        })
+        .collect())
+    }
+
+    /// Finished tasks that still hold diagnostics, with their namespace and
+    /// the time they finished. The time is unknown for tasks that finished
+    /// before their transitions were kept. Reads the database only, so it can
+    /// run off the async runtime.
+    pub fn finished_tasks_with_diagnostics(
+        db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<Vec<(TaskId, String, Option<u64>)>> {
+        let mut tasks = Vec::new();
+        let iter = db.iterator_cf_opt(
+            StateMachineColumns::Tasks.cf(db),
+            scan_read_options(),
+            rocksdb::IteratorMode::Start,
+        );
+        for item in iter {
+            let (_, value) = item?;
+            let task: internal_api::Task = JsonEncoder::decode(&value)?;
+            if task.terminal_state() && task.diagnostics.is_some() {
+                tasks.push((task.id, task.namespace));
+            }
+        }
+        let task_ids: Vec<&TaskId> = tasks.iter().map(|(task_id, _)| task_id).collect();
+        let transitions = Self::multi_get_from_cf::<Vec<TaskTransition>, _>(
+            db,
+            StateMachineColumns::TaskTransitions,
+            &task_ids,
+        )?;
+        Ok(tasks
+            .into_iter()
+            .zip(transitions)
+            .map(|((task_id, namespace), transitions)| {
+                let finished_at = transitions
+                    .and_then(|transitions| transitions.last().map(|transition| transition.at));
+                (task_id, namespace, finished_at)
+            })
+            .collect())
+    }
+
+    /// Returns the tasks of `task_ids` that can be allocated at `now`. Tasks
+    /// of extraction policies with a rate limit are admitted while the bucket
+    /// of the policy has tokens, and stay unassigned otherwise. The tokens
+    /// are taken by `consume_rate_limit_tokens` once the allocation is
+    /// committed.
+    pub fn admit_rate_limited_tasks(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        task_ids: HashSet<TaskId>,
+        now: Instant,
+    ) -> Result<HashSet<TaskId>> {
+        let mut admitted = HashSet::new();
+        let mut tasks_by_policy: HashMap<ExtractionPolicyId, Vec<TaskId>> = HashMap::new();
+        for task_id in task_ids {
+            match self
+                .task_queues_by_extraction_policy
+                .extraction_policy_of(&task_id)
+            {
+                Some(extraction_policy_id) => tasks_by_policy
+                    .entry(extraction_policy_id)
+                    .or_default()
+                    .push(task_id),
+                None => {
+                    admitted.insert(task_id);
+                }
+            }
+        }
+        for (extraction_policy_id, mut task_ids) in tasks_by_policy {
+            let max_tasks_per_second = self
+                .get_from_cf::<ExtractionPolicy, _>(
+                    db,
+                    StateMachineColumns::ExtractionPolicies,
+                    &extraction_policy_id,
+                )?
+                .and_then(|policy| policy.max_tasks_per_second);
+            let Some(max_tasks_per_second) = max_tasks_per_second else {
+                admitted.extend(task_ids);
+                continue;
+            };
+            task_ids.sort();
+            let available = self.task_rate_limiters.available(
+                &extraction_policy_id,
+                max_tasks_per_second,
+                task_ids.len(),
+                now,
+            );
+            admitted.extend(task_ids.into_iter().take(available));
+        }
+        Ok(admitted)
+    }
+
+    /// Takes the rate limit tokens of tasks whose allocation was committed
+    pub fn consume_rate_limit_tokens<'a>(&self, task_ids: impl Iterator<Item = &'a TaskId>) {
+        let mut tasks_by_policy: HashMap<ExtractionPolicyId, usize> = HashMap::new();
+        for task_id in task_ids {
+            if let Some(extraction_policy_id) = self
+                .task_queues_by_extraction_policy
+                .extraction_policy_of(task_id)
+            {
+                *tasks_by_policy.entry(extraction_policy_id).or_default() += 1;
+            }
+        }
+        for (extraction_policy_id, tasks) in tasks_by_policy {
+            self.task_rate_limiters
+                .consume(&extraction_policy_id, tasks);
+        }
+    }
+
+    /// Executors that recently finished tasks of the extraction policy of
+    /// each task of `task_ids`, for the tasks whose policy has an expensive
+    /// warm up. Tasks without warm executors are left out, and so are tasks
+    /// of policies with fewer warm executors than they want, so that their
+    /// tasks spread to other executors and warm them up.
+    pub fn warm_executors_for_tasks(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        task_ids: &HashSet<TaskId>,
+        now: SystemTime,
+    ) -> Result<HashMap<TaskId, HashSet<ExecutorId>>> {
+        let mut warm_executors_by_policy: HashMap<ExtractionPolicyId, HashSet<ExecutorId>> =
+            HashMap::new();
+        let mut warm_executors = HashMap::new();
+        for task_id in task_ids {
+            let Some(extraction_policy_id) = self
+                .task_queues_by_extraction_policy
+                .extraction_policy_of(task_id)
+            else {
+                continue;
+            };
+            let executors = match warm_executors_by_policy.entry(extraction_policy_id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let policy = self.get_from_cf::<ExtractionPolicy, _>(
+                        db,
+                        StateMachineColumns::ExtractionPolicies,
+                        entry.key(),
+                    )?;
+                    let prefers_warm = policy.as_ref().map_or(false, |policy| {
+                        policy.expensive_warmup || policy.min_warm_executors.is_some()
+                    });
+                    let min_warm = policy
+                        .and_then(|policy| policy.min_warm_executors)
+                        .unwrap_or_default() as usize;
+                    let executors = if prefers_warm {
+                        self.recent_extraction_policies
+                            .warm_executors(entry.key(), now, min_warm)
+                    } else {
+                        HashSet::new()
+                    };
+                    if executors.len() < min_warm {
+                        entry.insert(HashSet::new())
+                    } else {
+                        entry.insert(executors)
+                    }
+                }
+            };
+            if !executors.is_empty() {
+                warm_executors.insert(task_id.clone(), executors.clone());
+            }
+        }
+        Ok(warm_executors)
+    }
+
+    /// The tasks of `task_ids` that exist, by id
+    pub fn get_tasks_from_ids(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        task_ids: &HashSet<TaskId>,
+    ) -> Result<HashMap<TaskId, internal_api::Task>> {
+        let mut tasks = HashMap::new();
+        for task_id in task_ids {
+            if let Some(task) =
+                self.get_from_cf::<internal_api::Task, _>(db, StateMachineColumns::Tasks, task_id)?
+            {
+                tasks.insert(task_id.clone(), task);
+            }
+        }
+        Ok(tasks)
+    }
+
+    /// Tasks of `task_ids` whose extraction graph is latency tolerant
+    pub fn get_latency_tolerant_tasks(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        task_ids: &HashSet<TaskId>,
+    ) -> Result<HashSet<TaskId>> {
+        let mut tolerant_by_policy: HashMap<ExtractionPolicyId, bool> = HashMap::new();
+        let mut tolerant = HashSet::new();
+        for task_id in task_ids {
+            let Some(extraction_policy_id) = self
+                .task_queues_by_extraction_policy
+                .extraction_policy_of(task_id)
+            else {
+                continue;
+            };
+            let is_tolerant = match tolerant_by_policy.entry(extraction_policy_id) {
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => {
+                    let graph = match self.get_from_cf::<ExtractionPolicy, _>(
+                        db,
+                        StateMachineColumns::ExtractionPolicies,
+                        entry.key(),
+                    )? {
+                        Some(policy) => self.get_from_cf::<ExtractionGraph, _>(
+                            db,
+                            StateMachineColumns::ExtractionGraphs,
+                            ExtractionGraph::create_id(&policy.graph_name, &policy.namespace),
+                        )?,
+                        None => None,
+                    };
+                    *entry.insert(graph.map_or(false, |graph| graph.latency_tolerant))
+                }
+            };
+            if is_tolerant {
+                tolerant.insert(task_id.clone());
+            }
+        }
+        Ok(tolerant)
+    }
+
+    /// States a task moved through, oldest first
+    pub fn get_task_transitions(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        task_id: &str,
+    ) -> Result<Vec<TaskTransition>> {
+        Ok(self
+            .get_from_cf::<Vec<TaskTransition>, _>(
+                db,
+                StateMachineColumns::TaskTransitions,
+                task_id,
+            )?
+            .unwrap_or_default())
+    }
+
+    pub fn get_replay(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        replay_id: &str,
+    ) -> Result<Option<internal_api::Replay>> {
+        self.get_from_cf(db, StateMachineColumns::Replays, replay_id)
+    }
+
+    /// Replays of a namespace, latest first
+    pub fn list_replays(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        namespace: &str,
+    ) -> Result<Vec<internal_api::Replay>> {
+        let prefix = namespace_replay_key(namespace, "");
+        let mode = rocksdb::IteratorMode::From(prefix.as_bytes(), rocksdb::Direction::Forward);
+        let mut replays = Vec::new();
+        for item in db.iterator_cf_opt(
+            StateMachineColumns::NamespaceReplays.cf(db),
+            prefix_read_options(),
+            mode,
+        ) {
+            let (key, _) = item?;
+            let Some(replay_id) = key.strip_prefix(prefix.as_bytes()) else {
+                break;
+            };
+            let replay_id = String::from_utf8_lossy(replay_id);
+            if let Some(replay) = self.get_replay(db, &replay_id)? {
+                replays.push(replay);
+            }
+        }
+        replays.sort_by(|a, b| b.started_at.cmp(&a.started_at).then(a.id.cmp(&b.id)));
+        Ok(replays)
+    }
+
+    pub fn get_graph_template(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        name: &str,
+    ) -> Result<Option<internal_api::GraphTemplate>> {
+        self.get_from_cf(db, StateMachineColumns::GraphTemplates, name)
+    }
+
+    /// Graph templates ordered by name
+    pub fn list_graph_templates(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<Vec<internal_api::GraphTemplate>> {
+        Ok(self
+            .get_all_rows_from_cf::<internal_api::GraphTemplate>(
+                StateMachineColumns::GraphTemplates,
+                db,
+            )?
+            .into_iter()
+            .map(|(_, template)| template)
+            .collect())
+    }
+
+    pub fn get_cancel_tasks_job(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        job_id: &str,
+    ) -> Result<Option<internal_api::CancelTasksJob>> {
+        self.get_from_cf(db, StateMachineColumns::CancelTasksJobs, job_id)
     }
 
-    /// This method will fetch all pieces of content metadata for the tree
-    /// rooted at latest version of content_id.
-    pub fn get_content_tree_metadata(
+    pub fn list_cancel_tasks_jobs(
         &self,
-        content_id: &str,
         db: &Arc<OptimisticTransactionDB>,
-    ) -> Result<Vec<indexify_internal_api::ContentMetadata>, StateMachineError> {
-        self.get_content_tree_metadata_inner(content_id, None, db)
+    ) -> Result<Vec<internal_api::CancelTasksJob>> {
+        Ok(self
+            .get_all_rows_from_cf::<internal_api::CancelTasksJob>(
+                StateMachineColumns::CancelTasksJobs,
+                db,
+            )?
+            .into_iter()
+            .map(|(_, job)| job)
+            .collect())
     }
 
-    /// This method will fetch all pieces of content metadata for the tree
-    /// rooted at overwritten/deleted content_id. It will look for a specfic
-    /// version of the node.
-    pub fn get_content_tree_metadata_with_version(
+    pub fn get_content_replica(
         &self,
-        content_id: &ContentMetadataId,
         db: &Arc<OptimisticTransactionDB>,
-    ) -> Result<Vec<indexify_internal_api::ContentMetadata>, StateMachineError> {
-        self.get_content_tree_metadata_inner(&content_id.id, Some(content_id.version), db)
+        content_id: &str,
+    ) -> Result<Option<internal_api::ContentReplica>> {
+        self.get_from_cf(db, StateMachineColumns::ContentReplicas, content_id)
     }
 
-    /// This method tries to retrieve all policies based on id's. If it cannot
-    /// find any, it skips them. If it encounters an error at any point
-    /// during the transaction, it returns out immediately
-    pub fn get_extraction_policies_from_ids(
+    /// Content whose copy in another region may be out of date, up to
+    /// `limit`, with its latest version and its replica
+    pub fn pending_replications(
         &self,
-        extraction_policy_ids: HashSet<String>,
         db: &Arc<OptimisticTransactionDB>,
-    ) -> Result<Option<Vec<ExtractionPolicy>>, StateMachineError> {
-        let txn = db.transaction();
+        limit: usize,
+    ) -> Result<Vec<internal_api::PendingReplication>> {
+        let content_cf = StateMachineColumns::ContentTable.cf(db);
+        let iter = db.iterator_cf_opt(
+            StateMachineColumns::PendingReplications.cf(db),
+            scan_read_options(),
+            rocksdb::IteratorMode::Start,
+        );
+        let mut pending = Vec::new();
+        for item in iter.take(limit) {
+            let (key, _) = item?;
+            let content_id = String::from_utf8(key.to_vec())?;
+            let content = db
+                .get_cf(content_cf, &content_id)?
+                .map(|value| JsonEncoder::decode::<internal_api::ContentMetadata>(&value))
+                .transpose()?
+                .filter(|content| content.latest && !content.tombstoned);
+            let replica = self.get_content_replica(db, &content_id)?;
+            pending.push(internal_api::PendingReplication {
+                content_id,
+                content,
+                replica,
+            });
+        }
+        Ok(pending)
+    }
 
-        let mut policies = Vec::new();
-        for id in extraction_policy_ids.iter() {
-            let bytes_opt = txn
-                .get_cf(
-                    StateMachineColumns::ExtractionPolicies.cf(db),
-                    id.as_bytes(),
-                )
-                .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
+    /// Zone placement and root content id of the tasks of `task_ids` whose
+    /// extraction graph places them by zone
+    pub fn get_zone_placements(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        task_ids: &HashSet<TaskId>,
+    ) -> Result<HashMap<TaskId, (ZonePlacement, String)>> {
+        let mut placement_by_policy: HashMap<ExtractionPolicyId, ZonePlacement> = HashMap::new();
+        let mut placements = HashMap::new();
+        for task_id in task_ids {
+            let Some(extraction_policy_id) = self
+                .task_queues_by_extraction_policy
+                .extraction_policy_of(task_id)
+            else {
+                continue;
+            };
+            let placement = match placement_by_policy.entry(extraction_policy_id) {
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => {
+                    let graph = match self.get_from_cf::<ExtractionPolicy, _>(
+                        db,
+                        StateMachineColumns::ExtractionPolicies,
+                        entry.key(),
+                    )? {
+                        Some(policy) => self.get_from_cf::<ExtractionGraph, _>(
+                            db,
+                            StateMachineColumns::ExtractionGraphs,
+                            ExtractionGraph::create_id(&policy.graph_name, &policy.namespace),
+                        )?,
+                        None => None,
+                    };
+                    *entry.insert(graph.map(|graph| graph.zone_placement).unwrap_or_default())
+                }
+            };
+            if placement == ZonePlacement::Any {
+                continue;
+            }
+            if let Some(task) =
+                self.get_from_cf::<internal_api::Task, _>(db, StateMachineColumns::Tasks, task_id)?
+            {
+                placements.insert(
+                    task_id.clone(),
+                    (placement, task.content_metadata.get_root_id().to_string()),
+                );
+            }
+        }
+        Ok(placements)
+    }
 
-            if let Some(bytes) = bytes_opt {
-                let policy = serde_json::from_slice::<ExtractionPolicy>(&bytes).map_err(|e| {
-                    StateMachineError::SerializationError(format!(
-                        "get_extraction_policies from id: unable to deserialize json, {}",
-                        e
-                    ))
-                })?;
-                policies.push(policy);
+    /// Tasks given to each zone by root content, for the root contents of
+    /// `root_content_ids` whose tasks were placed by zone
+    pub fn get_content_zones<'a>(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        root_content_ids: impl IntoIterator<Item = &'a String>,
+    ) -> Result<HashMap<String, HashMap<String, usize>>> {
+        let mut content_zones = HashMap::new();
+        for root_content_id in root_content_ids {
+            if let Some(zones) =
+                self.get_from_cf(db, StateMachineColumns::ContentZones, root_content_id)?
+            {
+                content_zones.insert(root_content_id.clone(), zones);
             }
-            // If None, the policy is not found; we simply skip it.
         }
+        Ok(content_zones)
+    }
 
-        if policies.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(policies))
+    /// Unassigned tasks that have been waiting for an executor for at least
+    /// `min_age`
+    pub fn get_starving_tasks(&self, min_age: Duration) -> Vec<(TaskId, Duration)> {
+        self.task_queues_by_extraction_policy
+            .unallocated_for(min_age, SystemTime::now())
+    }
+
+    /// Tasks of the extraction policy finished within the last hour, how many
+    /// of them took longer than `latency` and how many failed
+    pub fn get_finished_task_counts(
+        &self,
+        extraction_policy_id: &ExtractionPolicyId,
+        latency: Option<Duration>,
+    ) -> (u64, u64, u64) {
+        self.finished_tasks
+            .counts(extraction_policy_id, latency, SystemTime::now())
+    }
+
+    /// Unassigned tasks of the extraction policies that are rate limited
+    pub fn get_throttled_tasks(&self) -> HashSet<TaskId> {
+        let rate_limited = self.task_rate_limiters.extraction_policy_ids();
+        if rate_limited.is_empty() {
+            return HashSet::new();
         }
+        self.get_unassigned_tasks()
+            .into_iter()
+            .filter(|task_id| {
+                self.task_queues_by_extraction_policy
+                    .extraction_policy_of(task_id)
+                    .map_or(false, |policy_id| rate_limited.contains(&policy_id))
+            })
+            .collect()
     }
 
-    pub fn get_extraction_policy_by_names(
+    /// The unfinished tasks and unprocessed state changes that exist for each
+    /// root content
+    fn pending_work_by_root(
         &self,
-        namespace: &str,
-        graph_name: &str,
-        policy_names: &HashSet<ExtractionPolicyName>,
         db: &Arc<OptimisticTransactionDB>,
-    ) -> Result<Vec<Option<ExtractionPolicy>>, StateMachineError> {
-        let mut policies = Vec::new();
-        for policy_name in policy_names {
-            let extraction_policy_id =
-                ExtractionPolicy::create_id(graph_name, policy_name, namespace);
-            let extraction_policy_bytes = db
-                .get_cf(
-                    StateMachineColumns::ExtractionPolicies.cf(db),
-                    extraction_policy_id.as_bytes(),
-                )
-                .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
-            match extraction_policy_bytes {
-                Some(bytes) => {
-                    let extraction_policy = JsonEncoder::decode::<ExtractionPolicy>(&bytes)?;
-                    policies.push(Some(extraction_policy))
-                }
-                None => policies.push(None),
+    ) -> Result<HashMap<String, PendingWork>, StateMachineError> {
+        let mut pending: HashMap<String, PendingWork> = HashMap::new();
+        let task_ids = self.unfinished_tasks_by_extractor.task_ids();
+        for task in Self::multi_get_from_cf::<internal_api::Task, _>(
+            db,
+            StateMachineColumns::Tasks,
+            &task_ids,
+        )?
+        .into_iter()
+        .flatten()
+        {
+            if !task.terminal_state() {
+                pending
+                    .entry(task.content_metadata.get_root_id().to_string())
+                    .or_default()
+                    .task_ids
+                    .push(task.id);
             }
         }
-        Ok(policies)
+        let change_keys = self
+            .unprocessed_state_changes
+            .inner()
+            .into_iter()
+            .map(|id| id.to_key());
+        for change in Self::multi_get_from_cf::<StateChange, _>(
+            db,
+            StateMachineColumns::StateChanges,
+            change_keys,
+        )?
+        .into_iter()
+        .flatten()
+        {
+            if let Some(root_content_id) = change.refcnt_object_id {
+                pending
+                    .entry(root_content_id)
+                    .or_default()
+                    .state_change_ids
+                    .push(change.id);
+            }
+        }
+        Ok(pending)
     }
 
-    /// This method gets all task assignments stored in the relevant CF
-    pub fn get_all_task_assignments(
+    /// The unfinished tasks and unprocessed state changes of a root content,
+    /// read before a repair of the content is sent
+    pub fn get_pending_work(
         &self,
         db: &Arc<OptimisticTransactionDB>,
-    ) -> Result<HashMap<TaskId, ExecutorId>, StateMachineError> {
-        let mut assignments = HashMap::new();
-        let iter = db.iterator_cf(
+        root_content_id: &str,
+    ) -> Result<PendingWork, StateMachineError> {
+        Ok(self
+            .pending_work_by_root(db)?
+            .remove(root_content_id)
+            .unwrap_or_default())
+    }
+
+    /// Finds the root content that is still waiting for tasks but has nothing
+    /// pending that could finish them, or whose tasks made no progress for
+    /// `stalled_for`
+    pub fn find_stuck_content(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        namespace: Option<&str>,
+        stalled_for: Duration,
+    ) -> Result<Vec<StuckContent>, StateMachineError> {
+        let now = SystemTime::now();
+        let outstanding: Vec<(String, u64, Duration)> = self
+            .root_task_counts
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(content_id, task_count)| {
+                let stalled = now
+                    .duration_since(task_count.last_progress)
+                    .unwrap_or_default();
+                (content_id.clone(), task_count.count, stalled)
+            })
+            .collect();
+        if outstanding.is_empty() {
+            return Ok(Vec::new());
+        }
+        let pending = self.pending_work_by_root(db)?;
+        let mut stuck: HashMap<String, StuckContent> = outstanding
+            .into_iter()
+            .filter_map(|(content_id, outstanding_tasks, stalled)| {
+                let pending_tasks = pending.get(&content_id).map_or(0, PendingWork::count);
+                (pending_tasks == 0 || stalled >= stalled_for).then(|| {
+                    (
+                        content_id.clone(),
+                        StuckContent {
+                            content_id,
+                            outstanding_tasks,
+                            pending_tasks,
+                            stalled_secs: stalled.as_secs(),
+                        },
+                    )
+                })
+            })
+            .collect();
+        if let Some(namespace) = namespace {
+            let in_namespace: HashSet<String> = self
+                .get_content_from_ids(stuck.keys().cloned().collect_vec(), db)?
+                .into_iter()
+                .filter(|content| content.namespace == namespace)
+                .map(|content| content.id.id)
+                .collect();
+            stuck.retain(|content_id, _| in_namespace.contains(content_id));
+        }
+        Ok(stuck.into_values().collect())
+    }
+
+    /// Finds the assignments of tasks that are finished or no longer exist,
+    /// and the unfinished tasks that are neither assigned to an executor nor
+    /// waiting for one. The indexes are read one after the other, so requests
+    /// applied in between can show up as false positives; applying the
+    /// `ReapOrphanedAssignments` request checks every entry again.
+    pub fn find_orphaned_assignments(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<(HashMap<ExecutorId, HashSet<TaskId>>, HashSet<TaskId>), StateMachineError> {
+        let (finished_task_assignments, assigned_tasks) = Self::scan_task_assignments(db)?;
+        let unallocated_tasks = self.find_unallocated_tasks(&assigned_tasks);
+        Ok((finished_task_assignments, unallocated_tasks))
+    }
+
+    /// Splits the task assignments into the assignments of tasks that are
+    /// finished or no longer exist, by executor, and the unfinished assigned
+    /// tasks. Reads the database only, so it can run off the async runtime.
+    pub fn scan_task_assignments(
+        db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<(HashMap<ExecutorId, HashSet<TaskId>>, HashSet<TaskId>), StateMachineError> {
+        let mut assigned_tasks = HashSet::new();
+        let mut finished_task_assignments: HashMap<ExecutorId, HashSet<TaskId>> = HashMap::new();
+        let iter = db.iterator_cf_opt(
             StateMachineColumns::TaskAssignments.cf(db),
+            scan_read_options(),
             rocksdb::IteratorMode::Start,
         );
         for item in iter {
-            let (key, value) = item.map_err(|e| {
-                StateMachineError::DatabaseError(format!(
-                    "unable to get values from task assignment {}",
-                    e
-                ))
-            })?;
-            let executor_id = String::from_utf8(key.to_vec()).map_err(|e| {
-                StateMachineError::DatabaseError(format!(
-                    "unable to get executor id from task assignment {}",
-                    e
-                ))
-            })?;
-            let task_ids: HashSet<TaskId> = JsonEncoder::decode(&value).map_err(|e| {
-                StateMachineError::DatabaseError(format!(
-                    "unable to decoded task hashset from task assignment {}",
-                    e
-                ))
-            })?;
-            for task_id in task_ids {
-                assignments.insert(task_id, executor_id.clone());
+            let (key, value) = item.map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            let executor_id = String::from_utf8_lossy(&key).to_string();
+            let task_ids: Vec<TaskId> = JsonEncoder::decode::<HashSet<TaskId>>(&value)?
+                .into_iter()
+                .collect();
+            let tasks = Self::multi_get_from_cf::<internal_api::Task, _>(
+                db,
+                StateMachineColumns::Tasks,
+                &task_ids,
+            )?;
+            for (task_id, task) in task_ids.into_iter().zip(tasks) {
+                if task.map_or(true, |task| task.terminal_state()) {
+                    finished_task_assignments
+                        .entry(executor_id.clone())
+                        .or_default()
+                        .insert(task_id);
+                } else {
+                    assigned_tasks.insert(task_id);
+                }
             }
         }
-        Ok(assignments)
+        Ok((finished_task_assignments, assigned_tasks))
+    }
+
+    /// Unfinished tasks that are neither in `assigned_tasks` nor waiting for
+    /// an executor
+    pub fn find_unallocated_tasks(&self, assigned_tasks: &HashSet<TaskId>) -> HashSet<TaskId> {
+        let unassigned_tasks = self.unassigned_tasks.inner();
+        self.unfinished_tasks_by_extractor
+            .task_ids()
+            .into_iter()
+            .filter(|task_id| {
+                !assigned_tasks.contains(task_id) && !unassigned_tasks.contains(task_id)
+            })
+            .collect()
     }
 
     /// Allocate a block of state change ids
@@ -2053,7 +5099,7 @@ impl IndexifyState {
                 "Failed to get column family {}",
                 column
             )))?;
-        let iter = db.iterator_cf(cf_handle, rocksdb::IteratorMode::Start);
+        let iter = db.iterator_cf_opt(cf_handle, scan_read_options(), rocksdb::IteratorMode::Start);
 
         iter.map(|item| {
             item.map_err(|e| StateMachineError::DatabaseError(e.to_string()))
@@ -2085,6 +5131,141 @@ impl IndexifyState {
         .collect::<Result<Vec<(String, V)>, _>>()
     }
 
+    /// Definitions of an extraction graph, oldest first
+    pub fn get_extraction_graph_history(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        extraction_graph_id: &str,
+    ) -> Result<Vec<internal_api::ExtractionGraphVersion>, StateMachineError> {
+        Ok(self
+            .get_from_cf(
+                db,
+                StateMachineColumns::ExtractionGraphHistory,
+                extraction_graph_id,
+            )?
+            .unwrap_or_default())
+    }
+
+    /// The latest definition of an extraction graph, cached in memory once
+    /// loaded from its history
+    pub fn get_latest_extraction_graph_version(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        extraction_graph_id: &str,
+    ) -> Result<Option<Arc<internal_api::ExtractionGraphVersion>>, StateMachineError> {
+        self.extraction_graph_versions
+            .get_or_load(extraction_graph_id, || {
+                Ok(self
+                    .get_extraction_graph_history(db, extraction_graph_id)?
+                    .pop())
+            })
+    }
+
+    /// The scheduler settings last set, or the defaults
+    pub fn get_scheduler_settings(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<internal_api::SchedulerSettings, StateMachineError> {
+        Ok(self
+            .get_from_cf(
+                db,
+                StateMachineColumns::SchedulerSettings,
+                SCHEDULER_SETTINGS_KEY,
+            )?
+            .unwrap_or_default())
+    }
+
+    /// Whether the cluster is in maintenance, it isn't unless it was put in
+    /// maintenance
+    pub fn get_maintenance_mode(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<internal_api::MaintenanceMode, StateMachineError> {
+        Ok(self
+            .get_from_cf(
+                db,
+                StateMachineColumns::SchedulerSettings,
+                MAINTENANCE_MODE_KEY,
+            )?
+            .unwrap_or_default())
+    }
+
+    /// Metering records of the periods starting from `start` up to `end`, of
+    /// one namespace or of all namespaces, ordered by namespace and period
+    pub fn list_metering_records(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        namespace: Option<&str>,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<internal_api::MeteringRecord>, StateMachineError> {
+        let prefix = namespace.map(|namespace| format!("{}/", namespace));
+        let (opts, mode) = match &prefix {
+            Some(prefix) => (
+                prefix_read_options(),
+                rocksdb::IteratorMode::From(prefix.as_bytes(), rocksdb::Direction::Forward),
+            ),
+            None => (scan_read_options(), rocksdb::IteratorMode::Start),
+        };
+        let mut records = Vec::new();
+        for item in db.iterator_cf_opt(StateMachineColumns::Metering.cf(db), opts, mode) {
+            let (key, value) = item.map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            if let Some(prefix) = &prefix {
+                if !key.starts_with(prefix.as_bytes()) {
+                    break;
+                }
+            }
+            let record = JsonEncoder::decode::<internal_api::MeteringRecord>(&value)?;
+            if record.period_start >= start && record.period_start <= end {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Usage of every version of an extraction graph that ran tasks, oldest
+    /// version first
+    pub fn get_extraction_graph_usage(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        extraction_graph_id: &str,
+    ) -> Result<Vec<internal_api::ExtractionGraphUsage>, StateMachineError> {
+        let prefix = format!("{}/", extraction_graph_id);
+        let mode = rocksdb::IteratorMode::From(prefix.as_bytes(), rocksdb::Direction::Forward);
+        let mut usage = Vec::new();
+        for item in db.iterator_cf_opt(
+            StateMachineColumns::ExtractionGraphUsage.cf(db),
+            prefix_read_options(),
+            mode,
+        ) {
+            let (key, value) = item.map_err(|e| StateMachineError::DatabaseError(e.to_string()))?;
+            if !key.starts_with(prefix.as_bytes()) {
+                break;
+            }
+            usage.push(JsonEncoder::decode::<internal_api::ExtractionGraphUsage>(
+                &value,
+            )?);
+        }
+        Ok(usage)
+    }
+
+    /// Key value context of a root content, shared by the tasks of the content
+    /// and of the content extracted from it
+    pub fn get_content_context(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        root_content_id: &str,
+    ) -> Result<HashMap<String, serde_json::Value>, StateMachineError> {
+        Ok(self
+            .get_from_cf(db, StateMachineColumns::ContentContext, root_content_id)?
+            .unwrap_or_default())
+    }
+
+    /// Garbage collection tasks that didn't finish yet
+    pub fn count_unfinished_gc_tasks(&self) -> usize {
+        self.unfinished_gc_tasks.read().unwrap().len()
+    }
+
     pub fn list_active_contents(
         &self,
         db: &Arc<OptimisticTransactionDB>,
@@ -2113,6 +5294,19 @@ impl IndexifyState {
         self.unprocessed_state_changes.inner()
     }
 
+    /// Number of unprocessed state changes, and the oldest of them
+    pub fn get_state_change_backlog(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<(usize, Option<StateChange>)> {
+        let count = self.unprocessed_state_changes.count();
+        let oldest = match self.unprocessed_state_changes.oldest() {
+            Some(id) => self.get_from_cf(db, StateMachineColumns::StateChanges, id.to_key())?,
+            None => None,
+        };
+        Ok((count, oldest))
+    }
+
     pub fn get_content_namespace_table(
         &self,
     ) -> HashMap<NamespaceName, HashSet<ContentMetadataId>> {
@@ -2131,12 +5325,45 @@ impl IndexifyState {
         self.namespace_index_table.inner()
     }
 
-    pub fn get_unfinished_tasks_by_extractor(&self) -> HashMap<ExtractorName, HashSet<TaskId>> {
-        self.unfinished_tasks_by_extractor.inner()
+    pub fn get_unfinished_tasks_for_extractor(&self, extractor: &str) -> HashSet<TaskId> {
+        self.unfinished_tasks_by_extractor.get(extractor)
+    }
+
+    pub fn group_unfinished_tasks_by_extractor<'a>(
+        &self,
+        task_ids: &'a HashSet<TaskId>,
+    ) -> HashMap<ExtractorName, Vec<&'a TaskId>> {
+        self.unfinished_tasks_by_extractor.group_tasks(task_ids)
+    }
+
+    /// Running task counts of the executors that run an extractor. Both
+    /// tables are kept up to date as tasks are assigned and finished, so this
+    /// is a lookup per executor rather than a scan of the task assignments.
+    pub fn get_executor_loads_for_extractor(&self, extractor: &str) -> Vec<(ExecutorId, u64)> {
+        let executors = self
+            .extractor_executors_table
+            .extractor_executors_table
+            .read()
+            .unwrap();
+        let running_task_count = self
+            .executor_running_task_count
+            .executor_running_task_count
+            .read()
+            .unwrap();
+        executors
+            .get(extractor)
+            .into_iter()
+            .flatten()
+            .map(|executor_id| {
+                let count = running_task_count.get(executor_id).copied().unwrap_or(0);
+                (executor_id.clone(), count)
+            })
+            .collect()
     }
 
-    pub fn get_executor_running_task_count(&self) -> HashMap<ExecutorId, u64> {
-        self.executor_running_task_count.inner()
+    pub fn get_task_queue_depths(&self) -> HashMap<ExtractionPolicyId, TaskQueueDepth> {
+        self.task_queues_by_extraction_policy
+            .queue_depths(SystemTime::now())
     }
 
     pub fn get_schemas_by_namespace(&self) -> HashMap<NamespaceName, HashSet<SchemaId>> {
@@ -2155,22 +5382,79 @@ impl IndexifyState {
         self.pending_tasks_for_content.inner()
     }
 
-    fn inc_root_ref_count(&self, content_id: &str) {
+    fn inc_root_ref_count(&self, content_id: &str, at: SystemTime) {
         let mut root_task_counts = self.root_task_counts.write().unwrap();
         root_task_counts
             .entry(content_id.to_string())
-            .and_modify(|c| c.count += 1)
+            .and_modify(|c| {
+                c.count += 1;
+                c.last_progress = at;
+            })
             .or_insert(TaskCount {
                 count: 1,
                 notify: None,
+                last_progress: at,
+                graphs: HashSet::new(),
             });
     }
 
-    fn dec_root_ref_count(&self, content_id: &str) {
+    /// Marks the root content of `task` as active in the task's extraction
+    /// graph
+    fn mark_root_active(&self, task: &internal_api::Task) {
+        let graph_id = ExtractionGraph::create_id(&task.extraction_graph_name, &task.namespace);
+        if let Some(task_count) = self
+            .root_task_counts
+            .write()
+            .unwrap()
+            .get_mut(task.content_metadata.get_root_id())
+        {
+            task_count.graphs.insert(graph_id);
+        }
+    }
+
+    /// Ids of the root content that has unfinished tasks in the extraction
+    /// graph
+    pub fn active_root_content(&self, graph_id: &str) -> HashSet<String> {
+        self.root_task_counts
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_, task_count)| task_count.graphs.contains(graph_id))
+            .map(|(content_id, _)| content_id.clone())
+            .collect()
+    }
+
+    /// Overwrites the outstanding task count of a root content, waking up the
+    /// waiters of the content if nothing is outstanding anymore
+    fn set_root_ref_count(&self, content_id: &str, count: u64, at: SystemTime) {
+        let mut root_task_counts = self.root_task_counts.write().unwrap();
+        if count > 0 {
+            let task_count = root_task_counts
+                .entry(content_id.to_string())
+                .or_insert(TaskCount {
+                    count,
+                    notify: None,
+                    last_progress: at,
+                    graphs: HashSet::new(),
+                });
+            task_count.count = count;
+            task_count.last_progress = at;
+            return;
+        }
+        if let Some(task_count) = root_task_counts.remove(content_id) {
+            drop(root_task_counts);
+            if let Some(tx) = task_count.notify {
+                let _ = tx.send(());
+            }
+        }
+    }
+
+    fn dec_root_ref_count(&self, content_id: &str, at: SystemTime) {
         let mut root_task_counts = self.root_task_counts.write().unwrap();
         match root_task_counts.entry(content_id.to_string()) {
             Entry::Occupied(mut entry) => {
                 entry.get_mut().count -= 1;
+                entry.get_mut().last_progress = at;
                 if entry.get().count == 0 {
                     let notify = entry.get().notify.clone();
                     entry.remove_entry();
@@ -2233,6 +5517,17 @@ impl IndexifyState {
     pub fn build_snapshot(
         &self,
         db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<IndexifyStateSnapshot, StateMachineError> {
+        self.build_snapshot_(db, true)
+    }
+
+    /// Reads the column families into a snapshot. The history of the
+    /// extraction graphs is left out when `with_graph_history` is false, as
+    /// no reverse index is built from it.
+    fn build_snapshot_(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+        with_graph_history: bool,
     ) -> Result<IndexifyStateSnapshot, StateMachineError> {
         let executors = self.get_all_rows_from_cf::<internal_api::ExecutorMetadata>(
             StateMachineColumns::Executors,
@@ -2294,6 +5589,65 @@ impl IndexifyState {
             .collect::<Result<_, StateMachineError>>()?;
         let extraction_graphs = self
             .get_all_rows_from_cf::<ExtractionGraph>(StateMachineColumns::ExtractionGraphs, db)?;
+        let content_context = self.get_all_rows_from_cf::<HashMap<String, serde_json::Value>>(
+            StateMachineColumns::ContentContext,
+            db,
+        )?;
+        let extraction_graph_history = if with_graph_history {
+            self.get_all_rows_from_cf::<Vec<internal_api::ExtractionGraphVersion>>(
+                StateMachineColumns::ExtractionGraphHistory,
+                db,
+            )?
+        } else {
+            Vec::new()
+        };
+        let scheduler_settings = self.get_from_cf::<internal_api::SchedulerSettings, _>(
+            db,
+            StateMachineColumns::SchedulerSettings,
+            SCHEDULER_SETTINGS_KEY,
+        )?;
+        let maintenance_mode = self.get_from_cf::<internal_api::MaintenanceMode, _>(
+            db,
+            StateMachineColumns::SchedulerSettings,
+            MAINTENANCE_MODE_KEY,
+        )?;
+        let metering = self.get_all_rows_from_cf::<internal_api::MeteringRecord>(
+            StateMachineColumns::Metering,
+            db,
+        )?;
+        let task_assignment_times =
+            self.get_all_rows_from_cf::<SystemTime>(StateMachineColumns::TaskAssignmentTimes, db)?;
+        let extraction_graph_usage = self
+            .get_all_rows_from_cf::<internal_api::ExtractionGraphUsage>(
+                StateMachineColumns::ExtractionGraphUsage,
+                db,
+            )?;
+        let task_transitions = self.get_all_rows_from_cf::<Vec<TaskTransition>>(
+            StateMachineColumns::TaskTransitions,
+            db,
+        )?;
+        let replays =
+            self.get_all_rows_from_cf::<internal_api::Replay>(StateMachineColumns::Replays, db)?;
+        let replay_tasks =
+            self.get_all_rows_from_cf::<String>(StateMachineColumns::ReplayTasks, db)?;
+        let graph_templates = self.get_all_rows_from_cf::<internal_api::GraphTemplate>(
+            StateMachineColumns::GraphTemplates,
+            db,
+        )?;
+        let content_replicas = self.get_all_rows_from_cf::<internal_api::ContentReplica>(
+            StateMachineColumns::ContentReplicas,
+            db,
+        )?;
+        let pending_replications =
+            self.get_all_rows_from_cf::<()>(StateMachineColumns::PendingReplications, db)?;
+        let cancel_tasks_jobs = self.get_all_rows_from_cf::<internal_api::CancelTasksJob>(
+            StateMachineColumns::CancelTasksJobs,
+            db,
+        )?;
+        let content_zones = self.get_all_rows_from_cf::<HashMap<String, usize>>(
+            StateMachineColumns::ContentZones,
+            db,
+        )?;
         let metrics = self.metrics.lock().unwrap().clone();
 
         let snapshot = IndexifyStateSnapshot {
@@ -2310,15 +5664,39 @@ impl IndexifyState {
             structured_data_schemas: structured_data_schemas.into_iter().collect(),
             coordinator_address: coordinator_address.into_iter().collect(),
             extraction_graphs: extraction_graphs.into_iter().collect(),
+            content_context: content_context.into_iter().collect(),
+            extraction_graph_history: extraction_graph_history.into_iter().collect(),
+            scheduler_settings,
+            maintenance_mode,
+            metering: metering.into_iter().collect(),
+            task_assignment_times: task_assignment_times.into_iter().collect(),
+            extraction_graph_usage: extraction_graph_usage.into_iter().collect(),
+            task_transitions: task_transitions.into_iter().collect(),
+            replays: replays.into_iter().collect(),
+            replay_tasks: replay_tasks.into_iter().collect(),
+            graph_templates: graph_templates.into_iter().collect(),
+            content_replicas: content_replicas.into_iter().collect(),
+            pending_replications: pending_replications
+                .into_iter()
+                .map(|(content_id, _)| content_id)
+                .collect(),
+            cancel_tasks_jobs: cancel_tasks_jobs.into_iter().collect(),
+            content_zones: content_zones.into_iter().collect(),
             metrics,
         };
         Ok(snapshot)
     }
 
+    /// Writes the column families of a snapshot and rebuilds the reverse
+    /// indexes from it. `before_commit` is called like in
+    /// `apply_state_machine_updates_with`.
     pub fn install_snapshot(
         &self,
         db: &Arc<OptimisticTransactionDB>,
         snapshot: IndexifyStateSnapshot,
+        before_commit: impl FnOnce(
+            &rocksdb::Transaction<OptimisticTransactionDB>,
+        ) -> Result<(), StateMachineError>,
     ) -> Result<(), StateMachineError> {
         fn put_cf<K, T>(
             txn: &rocksdb::Transaction<OptimisticTransactionDB>,
@@ -2370,9 +5748,9 @@ impl IndexifyState {
             let cf = StateMachineColumns::Extractors.cf(db);
             put_cf(&txn, cf, extractor_name, &extractor_description)?;
         }
-        for namespace in snapshot.namespaces {
+        for namespace in &snapshot.namespaces {
             let cf = StateMachineColumns::Namespaces.cf(db);
-            put_cf(&txn, cf, &namespace, &namespace)?;
+            put_cf(&txn, cf, namespace, namespace)?;
         }
         for (index_name, index) in &snapshot.index_table {
             let cf = StateMachineColumns::IndexTable.cf(db);
@@ -2386,77 +5764,155 @@ impl IndexifyState {
             let cf = StateMachineColumns::CoordinatorAddress.cf(db);
             put_cf(&txn, cf, &node_id.to_string(), &addr)?;
         }
+        for (root_content_id, context) in &snapshot.content_context {
+            let cf = StateMachineColumns::ContentContext.cf(db);
+            put_cf(&txn, cf, root_content_id, &context)?;
+        }
+        for (extraction_graph_id, history) in &snapshot.extraction_graph_history {
+            let cf = StateMachineColumns::ExtractionGraphHistory.cf(db);
+            put_cf(&txn, cf, extraction_graph_id, &history)?;
+        }
+        if let Some(settings) = &snapshot.scheduler_settings {
+            let cf = StateMachineColumns::SchedulerSettings.cf(db);
+            put_cf(&txn, cf, SCHEDULER_SETTINGS_KEY, settings)?;
+        }
+        if let Some(maintenance) = &snapshot.maintenance_mode {
+            let cf = StateMachineColumns::SchedulerSettings.cf(db);
+            put_cf(&txn, cf, MAINTENANCE_MODE_KEY, maintenance)?;
+        }
+        for (key, record) in &snapshot.metering {
+            let cf = StateMachineColumns::Metering.cf(db);
+            put_cf(&txn, cf, key, &record)?;
+        }
+        for (task_id, assigned_at) in &snapshot.task_assignment_times {
+            let cf = StateMachineColumns::TaskAssignmentTimes.cf(db);
+            put_cf(&txn, cf, task_id, &assigned_at)?;
+        }
+        for (key, usage) in &snapshot.extraction_graph_usage {
+            let cf = StateMachineColumns::ExtractionGraphUsage.cf(db);
+            put_cf(&txn, cf, key, &usage)?;
+        }
+        for (task_id, transitions) in &snapshot.task_transitions {
+            let cf = StateMachineColumns::TaskTransitions.cf(db);
+            put_cf(&txn, cf, task_id, &transitions)?;
+        }
+        //  The indexes of the replays are built from the replays
+        for (replay_id, replay) in &snapshot.replays {
+            let cf = StateMachineColumns::Replays.cf(db);
+            put_cf(&txn, cf, replay_id, &replay)?;
+            let cf = StateMachineColumns::NamespaceReplays.cf(db);
+            put_cf(
+                &txn,
+                cf,
+                namespace_replay_key(&replay.namespace, replay_id),
+                &(),
+            )?;
+        }
+        for (task_id, replay_id) in &snapshot.replay_tasks {
+            let cf = StateMachineColumns::ReplayTasks.cf(db);
+            put_cf(&txn, cf, task_id, &replay_id)?;
+            let tasks = snapshot
+                .replays
+                .get(replay_id)
+                .into_iter()
+                .flat_map(|replay| &replay.tasks)
+                .filter(|task| &task.task_id == task_id);
+            for task in tasks {
+                let cf = StateMachineColumns::RunningReplayTasks.cf(db);
+                let key = running_replay_task_key(&task.content_id, &task.extraction_policy);
+                put_cf(&txn, cf, key, task_id)?;
+            }
+        }
+        for (name, template) in &snapshot.graph_templates {
+            let cf = StateMachineColumns::GraphTemplates.cf(db);
+            put_cf(&txn, cf, name, &template)?;
+        }
+        for (content_id, replica) in &snapshot.content_replicas {
+            let cf = StateMachineColumns::ContentReplicas.cf(db);
+            put_cf(&txn, cf, content_id, &replica)?;
+        }
+        for content_id in &snapshot.pending_replications {
+            let cf = StateMachineColumns::PendingReplications.cf(db);
+            put_cf(&txn, cf, content_id, &())?;
+        }
+        for (job_id, job) in &snapshot.cancel_tasks_jobs {
+            let cf = StateMachineColumns::CancelTasksJobs.cf(db);
+            put_cf(&txn, cf, job_id, &job)?;
+        }
+        for (root_content_id, zones) in &snapshot.content_zones {
+            let cf = StateMachineColumns::ContentZones.cf(db);
+            put_cf(&txn, cf, root_content_id, &zones)?;
+        }
 
-        //  Build the in-memory reverse indexes
-        let mut unassigned_tasks = self.unassigned_tasks.unassigned_tasks.write().unwrap();
-        let mut unprocessed_state_changes_guard = self
-            .unprocessed_state_changes
-            .unprocessed_state_changes
-            .write()
-            .unwrap();
-        let mut content_namespace_table_guard = self
-            .content_namespace_table
-            .content_namespace_table
-            .write()
-            .unwrap();
-        let mut extraction_policies_table = self
-            .extraction_policies_table
-            .extraction_policies_table
-            .write()
-            .unwrap();
-        let mut extractor_executors_table = self
-            .extractor_executors_table
-            .extractor_executors_table
-            .write()
-            .unwrap();
-        let mut namespace_index_table = self
-            .namespace_index_table
-            .namespace_index_table
-            .write()
-            .unwrap();
-        let mut unfinished_tasks_by_extractor = self
-            .unfinished_tasks_by_extractor
-            .unfinished_tasks_by_extractor
-            .write()
-            .unwrap();
-        let mut executor_running_task_count = self
-            .executor_running_task_count
-            .executor_running_task_count
-            .write()
-            .unwrap();
-        let mut schemas_by_namespace = self
-            .schemas_by_namespace
-            .schemas_by_namespace
-            .write()
-            .unwrap();
-        let mut content_children_table = self
-            .content_children_table
-            .content_children_table
-            .write()
-            .unwrap();
-        let mut pending_tasks_for_content = self
-            .pending_tasks_for_content
-            .pending_tasks_for_content
-            .write()
-            .unwrap();
+        self.rebuild_reverse_indexes(&snapshot);
+        *self.metrics.lock().unwrap() = snapshot.metrics;
+
+        commit(txn, before_commit)
+    }
+
+    /// Rebuilds the reverse indexes from the column families, for when the
+    /// database is ahead of the last snapshot of the indexes
+    pub fn rebuild_indexes(
+        &self,
+        db: &Arc<OptimisticTransactionDB>,
+    ) -> Result<(), StateMachineError> {
+        let snapshot = self.build_snapshot_(db, false)?;
+        self.rebuild_reverse_indexes(&snapshot);
+        Ok(())
+    }
+
+    /// Replaces every reverse index with one built from `snapshot`
+    fn rebuild_reverse_indexes(&self, snapshot: &IndexifyStateSnapshot) {
+        let mut unassigned_tasks = HashSet::new();
+        let mut content_namespace_table: HashMap<NamespaceName, HashSet<ContentMetadataId>> =
+            HashMap::new();
+        let mut extraction_policies_table: HashMap<NamespaceName, HashSet<String>> = HashMap::new();
+        let mut extractor_executors_table: HashMap<ExtractorName, HashSet<ExecutorId>> =
+            HashMap::new();
+        let mut namespace_index_table: HashMap<NamespaceName, HashSet<String>> = HashMap::new();
+        let mut unfinished_tasks_by_extractor: HashMap<ExtractorName, HashSet<TaskId>> =
+            HashMap::new();
+        let mut executor_running_task_count: HashMap<ExecutorId, u64> = HashMap::new();
+        let mut schemas_by_namespace: HashMap<NamespaceName, HashSet<SchemaId>> = HashMap::new();
+        let mut content_children_table: HashMap<ContentMetadataId, HashSet<ContentMetadataId>> =
+            HashMap::new();
+        let mut pending_tasks_for_content: HashMap<
+            ContentMetadataId,
+            HashMap<ExtractionPolicyId, HashSet<TaskId>>,
+        > = HashMap::new();
+        let mut extraction_graphs_by_ns: HashMap<NamespaceName, HashSet<ExtractionGraphId>> =
+            HashMap::new();
+        let mut root_task_counts: HashMap<String, RootTaskCount> = HashMap::new();
+        let now = SystemTime::now();
+        let unfinished_gc_tasks = snapshot
+            .gc_tasks
+            .iter()
+            .filter(|(_, gc_task)| gc_task.outcome == TaskOutcome::Unknown)
+            .map(|(id, _)| id.clone())
+            .collect();
 
         for (task_id, task) in &snapshot.tasks {
             if !task.terminal_state() {
                 unassigned_tasks.insert(task_id.clone());
             }
         }
-        for task_ids in snapshot.task_assignments.values() {
+        let mut task_executors = HashMap::new();
+        for (executor_id, task_ids) in &snapshot.task_assignments {
             for task_id in task_ids {
                 unassigned_tasks.remove(task_id);
+                task_executors.insert(task_id.clone(), executor_id.clone());
             }
         }
 
-        for state_change_id in snapshot.state_changes.keys() {
-            unprocessed_state_changes_guard.insert(*state_change_id);
-        }
+        let unprocessed_state_changes: HashSet<StateChangeId> = snapshot
+            .state_changes
+            .iter()
+            .filter(|(_, state_change)| state_change.processed_at.is_none())
+            .map(|(id, _)| *id)
+            .collect();
 
         for (content_id, content) in &snapshot.content_table {
-            content_namespace_table_guard
+            content_namespace_table
                 .entry(content.namespace.clone())
                 .or_default()
                 .insert(content_id.clone());
@@ -2485,12 +5941,64 @@ impl IndexifyState {
                 .insert(index_id.clone());
         }
 
+        for (graph_id, graph) in &snapshot.extraction_graphs {
+            extraction_graphs_by_ns
+                .entry(graph.namespace.clone())
+                .or_default()
+                .insert(graph_id.clone());
+        }
+
+        let mut task_queues = TaskQueues::default();
         for (task_id, task) in &snapshot.tasks {
-            if !task.terminal_state() {
-                unfinished_tasks_by_extractor
-                    .entry(task.extractor.clone())
+            if task.terminal_state() {
+                continue;
+            }
+            unfinished_tasks_by_extractor
+                .entry(task.extractor.clone())
+                .or_default()
+                .insert(task_id.clone());
+
+            task_queues
+                .task_policies
+                .insert(task_id.clone(), task.extraction_policy_id.clone());
+            let queue = task_queues
+                .queues
+                .entry(task.extraction_policy_id.clone())
+                .or_default();
+            if unassigned_tasks.contains(task_id) {
+                queue.unallocated.insert(task_id.clone());
+                task_queues.unallocated_since.insert(task_id.clone(), now);
+            } else {
+                queue.running.insert(task_id.clone());
+            }
+
+            pending_tasks_for_content
+                .entry(task.content_metadata.id.clone())
+                .or_default()
+                .entry(task.extraction_policy_id.clone())
+                .or_default()
+                .insert(task_id.clone());
+
+            //  A root content is outstanding until its unfinished tasks and the
+            //  state changes of its finished tasks are processed
+            let task_count = root_task_counts
+                .entry(task.content_metadata.get_root_id().to_string())
+                .or_default();
+            task_count.count += 1;
+            task_count.graphs.insert(ExtractionGraph::create_id(
+                &task.extraction_graph_name,
+                &task.namespace,
+            ));
+        }
+        for state_change in snapshot.state_changes.values() {
+            if state_change.processed_at.is_some() {
+                continue;
+            }
+            if let Some(root_content_id) = &state_change.refcnt_object_id {
+                root_task_counts
+                    .entry(root_content_id.clone())
                     .or_default()
-                    .insert(task_id.clone());
+                    .count += 1;
             }
         }
 
@@ -2516,31 +6024,227 @@ impl IndexifyState {
             }
         }
 
-        for (task_id, task) in &snapshot.tasks {
-            if !task.terminal_state() {
-                let content_id = &task.content_metadata.id;
-                let extraction_policy_id = &task.extraction_policy_id;
+        let next_change_id = snapshot
+            .state_changes
+            .keys()
+            .map(|id| u64::from(*id) + 1)
+            .max()
+            .unwrap_or_default();
 
-                let policies_map = pending_tasks_for_content
-                    .entry(content_id.clone())
-                    .or_default();
-                let tasks_set = policies_map
-                    .entry(extraction_policy_id.clone())
-                    .or_default();
-                tasks_set.insert(task_id.clone());
-            }
+        self.install_indexes_snapshot(IndexifyIndexesSnapshot {
+            unassigned_tasks,
+            task_executors,
+            unprocessed_state_changes,
+            content_namespace_table,
+            extraction_policies_table,
+            extractor_executors_table,
+            namespace_index_table,
+            unfinished_tasks_by_extractor,
+            executor_running_task_count,
+            task_queues,
+            schemas_by_namespace,
+            content_children_table,
+            pending_tasks_for_content,
+            extraction_graphs_by_ns,
+            root_task_counts,
+            unfinished_gc_tasks,
+            metrics: self.metrics.lock().unwrap().clone(),
+            change_id: next_change_id.max(*self.change_id.lock().unwrap()),
+        });
+    }
+
+    /// Copies the reverse indexes without touching the database
+    pub fn build_indexes_snapshot(&self) -> IndexifyIndexesSnapshot {
+        IndexifyIndexesSnapshot {
+            unassigned_tasks: self.unassigned_tasks.inner(),
+            task_executors: self.task_executors.inner(),
+            unprocessed_state_changes: self.unprocessed_state_changes.inner(),
+            content_namespace_table: self.content_namespace_table.inner(),
+            extraction_policies_table: self.extraction_policies_table.inner(),
+            extractor_executors_table: self.extractor_executors_table.inner(),
+            namespace_index_table: self.namespace_index_table.inner(),
+            unfinished_tasks_by_extractor: self
+                .unfinished_tasks_by_extractor
+                .unfinished_tasks_by_extractor
+                .read()
+                .unwrap()
+                .clone(),
+            executor_running_task_count: self.executor_running_task_count.inner(),
+            task_queues: self
+                .task_queues_by_extraction_policy
+                .task_queues
+                .read()
+                .unwrap()
+                .clone(),
+            schemas_by_namespace: self.schemas_by_namespace.inner(),
+            content_children_table: self.content_children_table.inner(),
+            pending_tasks_for_content: self.pending_tasks_for_content.inner(),
+            extraction_graphs_by_ns: self.extraction_graphs_by_ns.inner(),
+            root_task_counts: self
+                .root_task_counts
+                .read()
+                .unwrap()
+                .iter()
+                .map(|(content_id, task_count)| {
+                    (
+                        content_id.clone(),
+                        RootTaskCount {
+                            count: task_count.count,
+                            graphs: task_count.graphs.clone(),
+                        },
+                    )
+                })
+                .collect(),
+            unfinished_gc_tasks: self.unfinished_gc_tasks.read().unwrap().clone(),
+            metrics: self.metrics.lock().unwrap().clone(),
+            change_id: *self.change_id.lock().unwrap(),
         }
+    }
 
-        //  set the metrics
+    /// Replaces the reverse indexes with a copy taken by
+    /// `build_indexes_snapshot`. The database is expected to already hold the
+    /// state the indexes were built from.
+    pub fn install_indexes_snapshot(&self, snapshot: IndexifyIndexesSnapshot) {
+        //  The cached graph versions are loaded again from the history the
+        //  indexes were built with
+        self.extraction_graph_versions.clear();
+        self.unassigned_tasks.set(snapshot.unassigned_tasks);
+        self.task_executors.set(snapshot.task_executors);
+        *self
+            .unprocessed_state_changes
+            .unprocessed_state_changes
+            .write()
+            .unwrap() = snapshot.unprocessed_state_changes;
+        *self
+            .content_namespace_table
+            .content_namespace_table
+            .write()
+            .unwrap() = snapshot.content_namespace_table;
+        *self
+            .extraction_policies_table
+            .extraction_policies_table
+            .write()
+            .unwrap() = snapshot.extraction_policies_table;
+        *self
+            .extractor_executors_table
+            .extractor_executors_table
+            .write()
+            .unwrap() = snapshot.extractor_executors_table;
+        *self
+            .namespace_index_table
+            .namespace_index_table
+            .write()
+            .unwrap() = snapshot.namespace_index_table;
+        *self
+            .unfinished_tasks_by_extractor
+            .unfinished_tasks_by_extractor
+            .write()
+            .unwrap() = snapshot.unfinished_tasks_by_extractor;
+        *self
+            .executor_running_task_count
+            .executor_running_task_count
+            .write()
+            .unwrap() = snapshot.executor_running_task_count;
+        *self
+            .task_queues_by_extraction_policy
+            .task_queues
+            .write()
+            .unwrap() = snapshot.task_queues;
+        *self
+            .schemas_by_namespace
+            .schemas_by_namespace
+            .write()
+            .unwrap() = snapshot.schemas_by_namespace;
+        *self
+            .content_children_table
+            .content_children_table
+            .write()
+            .unwrap() = snapshot.content_children_table;
+        self.pending_tasks_for_content
+            .set(snapshot.pending_tasks_for_content);
+        *self
+            .extraction_graphs_by_ns
+            .eg_by_namespace
+            .write()
+            .unwrap() = snapshot.extraction_graphs_by_ns;
+        *self.unfinished_gc_tasks.write().unwrap() = snapshot.unfinished_gc_tasks;
+        //  Waiters only exist in memory, the ones of content that is still
+        //  outstanding keep waiting
+        let now = SystemTime::now();
+        let mut root_task_counts = self.root_task_counts.write().unwrap();
+        let previous = std::mem::take(&mut *root_task_counts);
+        *root_task_counts = snapshot
+            .root_task_counts
+            .into_iter()
+            .map(|(content_id, count)| {
+                let notify = previous
+                    .get(&content_id)
+                    .and_then(|task_count| task_count.notify.clone());
+                (
+                    content_id,
+                    TaskCount {
+                        count: count.count,
+                        notify,
+                        last_progress: now,
+                        graphs: count.graphs,
+                    },
+                )
+            })
+            .collect();
+        drop(root_task_counts);
+        for (content_id, task_count) in previous {
+            if let Some(tx) = task_count.notify {
+                if !self
+                    .root_task_counts
+                    .read()
+                    .unwrap()
+                    .contains_key(&content_id)
+                {
+                    let _ = tx.send(());
+                }
+            }
+        }
         *self.metrics.lock().unwrap() = snapshot.metrics;
-
-        txn.commit()
-            .map_err(|e| StateMachineError::TransactionError(e.to_string()))?;
-        Ok(())
+        *self.change_id.lock().unwrap() = snapshot.change_id;
     }
     //  END SNAPSHOT METHODS
 }
 
+/// Outstanding work of a root content, see `TaskCount`
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug, Clone, PartialEq)]
+struct RootTaskCount {
+    count: u64,
+    graphs: HashSet<ExtractionGraphId>,
+}
+
+/// The reverse indexes of `IndexifyState`, written periodically in the
+/// transaction of the log entry they reflect, so a restart can restore them
+/// instead of rebuilding them from the column families.
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug)]
+pub struct IndexifyIndexesSnapshot {
+    unassigned_tasks: HashSet<TaskId>,
+    task_executors: HashMap<TaskId, ExecutorId>,
+    unprocessed_state_changes: HashSet<StateChangeId>,
+    content_namespace_table: HashMap<NamespaceName, HashSet<ContentMetadataId>>,
+    extraction_policies_table: HashMap<NamespaceName, HashSet<String>>,
+    extractor_executors_table: HashMap<ExtractorName, HashSet<ExecutorId>>,
+    namespace_index_table: HashMap<NamespaceName, HashSet<String>>,
+    unfinished_tasks_by_extractor: HashMap<ExtractorName, HashSet<TaskId>>,
+    executor_running_task_count: HashMap<ExecutorId, u64>,
+    task_queues: TaskQueues,
+    schemas_by_namespace: HashMap<NamespaceName, HashSet<SchemaId>>,
+    content_children_table: HashMap<ContentMetadataId, HashSet<ContentMetadataId>>,
+    pending_tasks_for_content:
+        HashMap<ContentMetadataId, HashMap<ExtractionPolicyId, HashSet<TaskId>>>,
+    extraction_graphs_by_ns: HashMap<NamespaceName, HashSet<ExtractionGraphId>>,
+    #[serde(default)]
+    root_task_counts: HashMap<String, RootTaskCount>,
+    #[serde(default)]
+    unfinished_gc_tasks: HashSet<internal_api::GarbageCollectionTaskId>,
+    metrics: Metrics,
+    change_id: u64,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Default, Debug)]
 pub struct IndexifyStateSnapshot {
     executors: HashMap<ExecutorId, internal_api::ExecutorMetadata>,
@@ -2556,9 +6260,68 @@ pub struct IndexifyStateSnapshot {
     structured_data_schemas: HashMap<String, internal_api::StructuredDataSchema>,
     coordinator_address: HashMap<NodeId, String>,
     extraction_graphs: HashMap<ExtractionGraphId, ExtractionGraph>,
+    #[serde(default)]
+    content_context: HashMap<String, HashMap<String, serde_json::Value>>,
+    #[serde(default)]
+    extraction_graph_history: HashMap<ExtractionGraphId, Vec<internal_api::ExtractionGraphVersion>>,
+    #[serde(default)]
+    scheduler_settings: Option<internal_api::SchedulerSettings>,
+    #[serde(default)]
+    maintenance_mode: Option<internal_api::MaintenanceMode>,
+    #[serde(default)]
+    metering: HashMap<String, internal_api::MeteringRecord>,
+    #[serde(default)]
+    task_assignment_times: HashMap<TaskId, SystemTime>,
+    #[serde(default)]
+    extraction_graph_usage: HashMap<String, internal_api::ExtractionGraphUsage>,
+    #[serde(default)]
+    task_transitions: HashMap<TaskId, Vec<TaskTransition>>,
+    #[serde(default)]
+    replays: HashMap<String, internal_api::Replay>,
+    #[serde(default)]
+    replay_tasks: HashMap<TaskId, String>,
+    #[serde(default)]
+    graph_templates: HashMap<String, internal_api::GraphTemplate>,
+    #[serde(default)]
+    content_replicas: HashMap<String, internal_api::ContentReplica>,
+    #[serde(default)]
+    pending_replications: HashSet<String>,
+    #[serde(default)]
+    cancel_tasks_jobs: HashMap<String, internal_api::CancelTasksJob>,
+    #[serde(default)]
+    content_zones: HashMap<String, HashMap<String, usize>>,
     metrics: Metrics,
 }
 
+impl IndexifyStateSnapshot {
+    /// Unfinished tasks that were assigned to an executor when the snapshot
+    /// was taken, together with the executor running them
+    pub fn in_flight_tasks(&self) -> Vec<(&internal_api::Task, &ExecutorId)> {
+        let mut in_flight: Vec<_> = self
+            .task_assignments
+            .iter()
+            .flat_map(|(executor_id, task_ids)| {
+                task_ids
+                    .iter()
+                    .filter_map(|task_id| self.tasks.get(task_id))
+                    .filter(|task| !task.terminal_state())
+                    .map(move |task| (task, executor_id))
+            })
+            .collect();
+        in_flight.sort_by(|a, b| a.0.id.cmp(&b.0.id));
+        in_flight
+    }
+
+    /// Latest time a state change in the snapshot was created or processed.
+    /// The snapshot was taken no earlier than that.
+    pub fn latest_change_time(&self) -> Option<u64> {
+        self.state_changes
+            .values()
+            .map(|change| change.processed_at.unwrap_or(change.created_at))
+            .max()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2584,4 +6347,340 @@ mod tests {
         executor_running_task_count.decrement_running_task_count(&executor_id);
         assert_eq!(executor_running_task_count.get(&executor_id).unwrap(), 0);
     }
+
+    #[test]
+    fn test_unprocessed_state_changes() {
+        let unprocessed = UnprocessedStateChanges::default();
+        assert_eq!(unprocessed.count(), 0);
+        assert_eq!(unprocessed.oldest(), None);
+        for id in [3, 1, 2] {
+            unprocessed.insert(StateChangeId::new(id));
+        }
+        assert_eq!(unprocessed.count(), 3);
+        assert_eq!(unprocessed.oldest(), Some(StateChangeId::new(1)));
+
+        //  Only the first removal of a change finds it unprocessed
+        assert!(unprocessed.remove(&StateChangeId::new(1)));
+        assert!(!unprocessed.remove(&StateChangeId::new(1)));
+        assert_eq!(unprocessed.count(), 2);
+        assert_eq!(unprocessed.oldest(), Some(StateChangeId::new(2)));
+    }
+
+    #[test]
+    fn test_task_queue_depths() {
+        let task_queues = TaskQueuesByExtractionPolicy::default();
+        let policy_id = "policy_id".to_string();
+        let now = SystemTime::now();
+        for task_id in ["task1", "task2", "task3"] {
+            task_queues.insert_unallocated(&policy_id, &task_id.to_string(), 0, now);
+        }
+        task_queues.mark_running(&"task1".to_string());
+        task_queues.mark_running(&"task2".to_string());
+        task_queues.mark_completed(&"task1".to_string(), now - Duration::from_secs(2 * 60 * 60));
+        task_queues.mark_completed(&"task2".to_string(), now);
+        assert_eq!(
+            task_queues.queue_depths(now).get(&policy_id),
+            Some(&TaskQueueDepth {
+                unallocated: 1,
+                running: 0,
+                completed_last_hour: 1,
+            })
+        );
+
+        // Tasks of a removed executor go back to the queue
+        task_queues.mark_running(&"task3".to_string());
+        task_queues.mark_unallocated(&"task3".to_string(), now);
+        assert_eq!(task_queues.queue_depths(now)[&policy_id].unallocated, 1);
+
+        // Tasks wait from the time they were last put back into the queue
+        let later = now + Duration::from_secs(60);
+        let waiting = task_queues.unallocated_for(Duration::from_secs(30), later);
+        assert_eq!(waiting.len(), 1);
+        assert_eq!(waiting[0].0, "task3");
+        assert!(task_queues
+            .unallocated_for(Duration::from_secs(120), later)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_finished_tasks() {
+        let finished_tasks = FinishedTasks::default();
+        let policy_id = "policy_id".to_string();
+        let now = SystemTime::now();
+        let second = Some(Duration::from_secs(1));
+        finished_tasks.record(
+            &policy_id,
+            now - Duration::from_secs(2 * 60 * 60),
+            None,
+            true,
+        );
+        finished_tasks.record(&policy_id, now, Some(Duration::from_millis(10)), false);
+        finished_tasks.record(&policy_id, now, Some(Duration::from_secs(5)), false);
+        finished_tasks.record(&policy_id, now, Some(Duration::from_millis(10)), true);
+        assert_eq!(finished_tasks.counts(&policy_id, second, now), (3, 1, 1));
+        assert_eq!(finished_tasks.counts(&policy_id, None, now), (3, 0, 1));
+        assert_eq!(
+            finished_tasks.counts(&"other".to_string(), second, now),
+            (0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_scheduling_attempts() {
+        let scheduling_attempts = SchedulingAttempts::default();
+        let attempt = |attempted_at| internal_api::SchedulingAttempt {
+            attempted_at,
+            executor_id: None,
+            candidates: Vec::new(),
+        };
+        scheduling_attempts.record(
+            (0..SCHEDULING_ATTEMPTS_PER_TASK as u64 + 2)
+                .map(|attempted_at| ("task1".to_string(), attempt(attempted_at)))
+                .collect(),
+        );
+        let attempts = scheduling_attempts.get(&"task1".to_string());
+        assert_eq!(attempts.len(), SCHEDULING_ATTEMPTS_PER_TASK);
+        assert_eq!(attempts[0].attempted_at, 2);
+
+        // The tasks attempted least recently make room for others, a task
+        // attempted again keeps its attempts
+        scheduling_attempts.record(
+            (0..TASKS_WITH_SCHEDULING_ATTEMPTS - 1)
+                .map(|i| (format!("task{}", i + 2), attempt(0)))
+                .collect(),
+        );
+        scheduling_attempts.record(vec![("task1".to_string(), attempt(10))]);
+        scheduling_attempts.record(vec![("other".to_string(), attempt(0))]);
+        let attempts = scheduling_attempts.get(&"task1".to_string());
+        assert_eq!(attempts.len(), SCHEDULING_ATTEMPTS_PER_TASK);
+        assert_eq!(attempts.last().unwrap().attempted_at, 10);
+        assert!(scheduling_attempts.get(&"task2".to_string()).is_empty());
+        assert_eq!(scheduling_attempts.get(&"task3".to_string()).len(), 1);
+        assert_eq!(scheduling_attempts.get(&"other".to_string()).len(), 1);
+    }
+
+    #[test]
+    fn test_extraction_graph_versions() {
+        let version = |id: &str, version| internal_api::ExtractionGraphVersion {
+            version,
+            extraction_graph: ExtractionGraph {
+                id: id.to_string(),
+                name: id.to_string(),
+                namespace: "namespace".to_string(),
+                extraction_policies: Vec::new(),
+                max_active_content: None,
+                latency_tolerant: false,
+                zone_placement: ZonePlacement::default(),
+            },
+            author: String::new(),
+            summary: String::new(),
+            created_at: 0,
+        };
+        let versions = ExtractionGraphVersions::default();
+        let latest = |id: &str| {
+            versions
+                .get_or_load(id, || Ok::<_, StateMachineError>(Some(version(id, 1))))
+                .unwrap()
+                .map(|version| version.version)
+        };
+
+        //  Versions are loaded on first use only, and replaced by the ones
+        //  added
+        assert_eq!(latest("graph0"), Some(1));
+        versions.insert(version("graph0", 2));
+        assert_eq!(latest("graph0"), Some(2));
+        let missing = versions
+            .get_or_load("missing", || Ok::<_, StateMachineError>(None))
+            .unwrap();
+        assert!(missing.is_none());
+
+        //  The graphs used least recently make room for others
+        for i in 1..CACHED_EXTRACTION_GRAPH_VERSIONS {
+            versions.insert(version(&format!("graph{}", i), 2));
+        }
+        assert_eq!(latest("graph0"), Some(2));
+        versions.insert(version("other", 2));
+        assert_eq!(latest("graph0"), Some(2));
+        assert_eq!(latest("graph2"), Some(2));
+        assert_eq!(latest("graph1"), Some(1));
+
+        versions.clear();
+        assert_eq!(latest("graph0"), Some(1));
+    }
+
+    #[test]
+    fn test_task_rate_limiters() {
+        let limiters = TaskRateLimiters::default();
+        let policy_id = "policy_id".to_string();
+        let start = Instant::now();
+        // A new bucket is full, and only allocated tasks take tokens
+        assert_eq!(limiters.available(&policy_id, 5, 8, start), 5);
+        assert_eq!(limiters.available(&policy_id, 5, 8, start), 5);
+        limiters.consume(&policy_id, 5);
+        assert_eq!(limiters.available(&policy_id, 5, 8, start), 0);
+        // Tokens come back at the allowed rate, up to a second worth of them
+        let later = start + Duration::from_millis(500);
+        assert_eq!(limiters.available(&policy_id, 5, 8, later), 2);
+        limiters.consume(&policy_id, 2);
+        let much_later = later + Duration::from_secs(60);
+        assert_eq!(limiters.available(&policy_id, 5, 3, much_later), 3);
+        limiters.consume(&policy_id, 3);
+        assert_eq!(limiters.available(&policy_id, 5, 8, much_later), 2);
+        // Policies without a bucket aren't limited
+        limiters.consume(&"other".to_string(), 1);
+        assert_eq!(
+            limiters.extraction_policy_ids(),
+            HashSet::from([policy_id.clone()])
+        );
+    }
+
+    #[test]
+    fn test_recent_extraction_policies() {
+        let recent = RecentExtractionPolicies::default();
+        let policy_id = "policy_id".to_string();
+        let start = SystemTime::now();
+        recent.record(&"executor1".to_string(), &policy_id, start);
+        recent.record(&"executor2".to_string(), &"other".to_string(), start);
+        assert_eq!(
+            recent.warm_executors(&policy_id, start, 0),
+            HashSet::from(["executor1".to_string()])
+        );
+        // Executors cool down after the warm period, unless they are pinned
+        assert!(recent
+            .warm_executors(&policy_id, start + WARM_EXECUTOR_PERIOD, 0)
+            .is_empty());
+        assert_eq!(
+            recent.warm_executors(&policy_id, start + WARM_EXECUTOR_PERIOD, 1),
+            HashSet::from(["executor1".to_string()])
+        );
+
+        recent.record(
+            &"executor2".to_string(),
+            &policy_id,
+            start + Duration::from_secs(1),
+        );
+        assert_eq!(
+            recent.warm_executors(&policy_id, start + WARM_EXECUTOR_PERIOD, 1),
+            HashSet::from(["executor2".to_string()])
+        );
+        recent.remove_executor(&"executor1".to_string());
+        assert_eq!(
+            recent.warm_executors(&policy_id, start, 0),
+            HashSet::from(["executor2".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_executor_loads_for_extractor() {
+        let state = IndexifyState::default();
+        let extractor = "extractor".to_string();
+        for executor_id in ["executor1", "executor2"] {
+            let executor_id = executor_id.to_string();
+            state
+                .extractor_executors_table
+                .insert(&extractor, &executor_id);
+            state.executor_running_task_count.insert(&executor_id, 0);
+        }
+        state
+            .executor_running_task_count
+            .increment_running_task_count(&"executor2".to_string());
+
+        let mut loads = state.get_executor_loads_for_extractor(&extractor);
+        loads.sort();
+        assert_eq!(
+            loads,
+            vec![("executor1".to_string(), 0), ("executor2".to_string(), 1)]
+        );
+        assert!(state.get_executor_loads_for_extractor("unknown").is_empty());
+    }
+
+    #[test]
+    fn test_indexes_snapshot_roundtrip() {
+        let state = IndexifyState::default();
+        let content_id = ContentMetadataId::new("content_id");
+        state.unassigned_tasks.insert(&"task1".to_string());
+        state
+            .content_namespace_table
+            .insert(&"namespace".to_string(), &content_id);
+        state.pending_tasks_for_content.insert(
+            &content_id,
+            &"policy".to_string(),
+            &"task1".to_string(),
+        );
+        state.task_queues_by_extraction_policy.insert_unallocated(
+            &"policy".to_string(),
+            &"task1".to_string(),
+            0,
+            SystemTime::now(),
+        );
+        state.get_next_change_ids(3);
+        state.inc_root_ref_count("content_id", SystemTime::now());
+
+        let snapshot = JsonEncoder::encode(&state.build_indexes_snapshot()).unwrap();
+        let restored = IndexifyState::default();
+        restored.install_indexes_snapshot(JsonEncoder::decode(&snapshot).unwrap());
+
+        assert_eq!(
+            restored.get_unassigned_tasks(),
+            state.get_unassigned_tasks()
+        );
+        assert_eq!(
+            restored.get_content_namespace_table(),
+            state.get_content_namespace_table()
+        );
+        assert_eq!(
+            restored.get_pending_tasks_for_content(),
+            state.get_pending_tasks_for_content()
+        );
+        assert_eq!(restored.get_task_queue_depths()["policy"].unallocated, 1);
+        assert_eq!(restored.get_next_change_ids(1), 3);
+        assert!(!restored.are_content_tasks_completed(&content_id));
+    }
+
+    /// Compares grouping a small allocation batch against 100k queued tasks
+    /// with the previous approach of copying the whole index first. Timings
+    /// depend on the machine, run it with `cargo test -- --ignored`.
+    #[test]
+    #[ignore]
+    fn test_group_tasks_benchmark() {
+        let total_tasks = 100_000;
+        let extractors = ["MockTextExtractor", "MockJsonExtractor"];
+        let mut index: HashMap<ExtractorName, HashSet<TaskId>> = HashMap::new();
+        for i in 0..total_tasks {
+            index
+                .entry(extractors[i % 2].to_string())
+                .or_default()
+                .insert(format!("task-{}", i));
+        }
+        let unfinished_tasks = UnfinishedTasksByExtractor::from(index.clone());
+        let batch: HashSet<TaskId> = (0..100).map(|i| format!("task-{}", i * 7)).collect();
+
+        let start = std::time::Instant::now();
+        let mut cloned: HashMap<ExtractorName, HashSet<TaskId>> = HashMap::new();
+        for (extractor, task_ids) in index.clone().iter() {
+            let filtered: HashSet<TaskId> = task_ids.intersection(&batch).cloned().collect();
+            if !filtered.is_empty() {
+                cloned.insert(extractor.clone(), filtered);
+            }
+        }
+        let clone_duration = start.elapsed();
+
+        let start = std::time::Instant::now();
+        let grouped = unfinished_tasks.group_tasks(&batch);
+        let borrow_duration = start.elapsed();
+
+        assert_eq!(grouped.len(), cloned.len());
+        for (extractor, task_ids) in grouped {
+            let task_ids: HashSet<TaskId> = task_ids.into_iter().cloned().collect();
+            assert_eq!(task_ids, cloned[&extractor]);
+        }
+        assert!(
+            borrow_duration < clone_duration,
+            "grouping {} of {} tasks: {:?} copying the index, {:?} borrowing",
+            batch.len(),
+            total_tasks,
+            clone_duration,
+            borrow_duration
+        );
+    }
 }
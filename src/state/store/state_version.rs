@@ -0,0 +1,221 @@
+use anyhow::{anyhow, Result};
+use rocksdb::{IteratorMode, OptimisticTransactionDB};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::serializer::{JsonEncode, JsonEncoder};
+
+/// Version of the format of the state in the database and of the messages
+/// servers exchange. Raise it when a release writes state or messages the
+/// previous release can't read. Every release reads the previous format, so
+/// servers can be upgraded one release at a time.
+pub const STATE_FORMAT_VERSION: u32 = 2;
+
+/// Format of databases written before the format was recorded
+pub const UNVERSIONED_STATE_FORMAT: u32 = 1;
+
+const STATE_VERSION_KEY: &[u8] = b"state_version";
+
+/// The format of the state in a database and the release that last opened it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateVersion {
+    pub format_version: u32,
+    pub server_version: String,
+}
+
+impl StateVersion {
+    pub fn current() -> Self {
+        Self {
+            format_version: STATE_FORMAT_VERSION,
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// Servers of adjacent formats can be in the same cluster while it is
+/// upgraded, servers further apart can't read each other's messages
+pub fn can_join_cluster(format_version: u32) -> bool {
+    format_version.abs_diff(STATE_FORMAT_VERSION) <= 1
+}
+
+pub fn unversioned_state_format() -> u32 {
+    UNVERSIONED_STATE_FORMAT
+}
+
+/// Reads the version recorded in the database. Databases with raft state but
+/// no version were written before versions were recorded.
+fn read_state_version(db: &OptimisticTransactionDB) -> Result<Option<StateVersion>> {
+    let store = db
+        .cf_handle("store")
+        .ok_or_else(|| anyhow!("store column family not found"))?;
+    if let Some(bytes) = db.get_cf(store, STATE_VERSION_KEY)? {
+        return Ok(Some(JsonEncoder::decode(&bytes)?));
+    }
+    let has_state = ["store", "logs"].into_iter().any(|name| {
+        db.cf_handle(name).map_or(false, |cf| {
+            db.iterator_cf(cf, IteratorMode::Start).next().is_some()
+        })
+    });
+    Ok(has_state.then(|| StateVersion {
+        format_version: UNVERSIONED_STATE_FORMAT,
+        server_version: "unknown".to_string(),
+    }))
+}
+
+/// Checks that this release can read state of the given version. State of a
+/// newer format is refused so that a downgraded server doesn't misread it, and
+/// state older than the previous format has to be opened by an intermediate
+/// release first.
+pub(super) fn check_state_version(stored: &StateVersion) -> Result<()> {
+    let current = StateVersion::current();
+    if stored.format_version > current.format_version {
+        return Err(anyhow!(
+            "state was written by indexify {} in format {}, indexify {} reads formats up to {}. \
+            run indexify {} or newer with this state",
+            stored.server_version,
+            stored.format_version,
+            current.server_version,
+            current.format_version,
+            stored.server_version
+        ));
+    }
+    if stored.format_version + 1 < current.format_version {
+        return Err(anyhow!(
+            "state was written by indexify {} in format {}, indexify {} reads formats {} and {}. \
+            upgrade through an intermediate release first",
+            stored.server_version,
+            stored.format_version,
+            current.server_version,
+            current.format_version - 1,
+            current.format_version
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that this release can read the state in the database and records
+/// the current version in it
+pub fn check_and_record_state_version(db: &OptimisticTransactionDB) -> Result<()> {
+    let current = StateVersion::current();
+    if let Some(stored) = read_state_version(db)? {
+        check_state_version(&stored)?;
+        if stored == current {
+            return Ok(());
+        }
+        info!(
+            "upgrading state of indexify {} in format {} to indexify {} in format {}",
+            stored.server_version,
+            stored.format_version,
+            current.server_version,
+            current.format_version
+        );
+    }
+    let store = db
+        .cf_handle("store")
+        .ok_or_else(|| anyhow!("store column family not found"))?;
+    db.put_cf(store, STATE_VERSION_KEY, JsonEncoder::encode(&current)?)?;
+    db.flush_wal(true)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rocksdb::{ColumnFamilyDescriptor, Options};
+
+    use super::*;
+    use crate::state::store::requests::RequestPayload;
+
+    fn open_db(path: &std::path::Path) -> OptimisticTransactionDB {
+        let mut db_opts = Options::default();
+        db_opts.create_missing_column_families(true);
+        db_opts.create_if_missing(true);
+        let column_families = ["store", "logs"]
+            .into_iter()
+            .map(|name| ColumnFamilyDescriptor::new(name, Options::default()));
+        OptimisticTransactionDB::open_cf_descriptors(&db_opts, path, column_families).unwrap()
+    }
+
+    fn set_state_version(db: &OptimisticTransactionDB, format_version: u32) {
+        let version = StateVersion {
+            format_version,
+            server_version: "0.0.1".to_string(),
+        };
+        db.put_cf(
+            db.cf_handle("store").unwrap(),
+            STATE_VERSION_KEY,
+            JsonEncoder::encode(&version).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_new_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = open_db(dir.path());
+        check_and_record_state_version(&db).unwrap();
+        assert_eq!(
+            read_state_version(&db).unwrap(),
+            Some(StateVersion::current())
+        );
+    }
+
+    #[test]
+    fn test_upgrade_unversioned_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = open_db(dir.path());
+        //  A database a release without versions left behind
+        db.put_cf(db.cf_handle("store").unwrap(), b"committed", b"null")
+            .unwrap();
+        assert_eq!(
+            read_state_version(&db).unwrap().unwrap().format_version,
+            UNVERSIONED_STATE_FORMAT
+        );
+        check_and_record_state_version(&db).unwrap();
+        assert_eq!(
+            read_state_version(&db).unwrap(),
+            Some(StateVersion::current())
+        );
+
+        //  Raft log entries of the previous release still decode
+        let payload: RequestPayload = serde_json::from_str(
+            r#"{"CreateExtractionGraph": {
+                "extraction_graph": {"id": "1", "name": "graph", "namespace": "default", "extraction_policies": []},
+                "structured_data_schema": {"id": "1", "extraction_graph_name": "graph", "namespace": "default", "columns": {}},
+                "indexes": []
+            }}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            payload,
+            RequestPayload::CreateExtractionGraph { change, .. } if change.author.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_incompatible_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = open_db(dir.path());
+        set_state_version(&db, STATE_FORMAT_VERSION + 1);
+        assert!(check_and_record_state_version(&db).is_err());
+        //  The state is left as it was
+        assert_eq!(
+            read_state_version(&db).unwrap().unwrap().format_version,
+            STATE_FORMAT_VERSION + 1
+        );
+
+        set_state_version(&db, STATE_FORMAT_VERSION - 2);
+        assert!(check_and_record_state_version(&db).is_err());
+
+        set_state_version(&db, STATE_FORMAT_VERSION - 1);
+        check_and_record_state_version(&db).unwrap();
+    }
+
+    #[test]
+    fn test_can_join_cluster() {
+        assert!(can_join_cluster(STATE_FORMAT_VERSION));
+        assert!(can_join_cluster(STATE_FORMAT_VERSION - 1));
+        assert!(can_join_cluster(STATE_FORMAT_VERSION + 1));
+        assert!(!can_join_cluster(STATE_FORMAT_VERSION + 2));
+        assert!(!can_join_cluster(STATE_FORMAT_VERSION - 2));
+    }
+}
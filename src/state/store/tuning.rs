@@ -0,0 +1,177 @@
+use std::ops::{Bound, RangeBounds};
+
+use rocksdb::{BlockBasedOptions, Cache, Options, ReadOptions, SliceTransform};
+
+use super::{id_to_bin, StateMachineColumns};
+
+/// Block cache shared by every column family of the database
+pub const BLOCK_CACHE_SIZE: usize = 256 * 1024 * 1024;
+
+/// Bits per key of the bloom filters, about 1% false positives
+const BLOOM_FILTER_BITS_PER_KEY: f64 = 10.0;
+
+/// Share of a memtable given to the bloom filter of its keys or prefixes
+const MEMTABLE_BLOOM_RATIO: f64 = 0.1;
+
+/// Bytes prefetched ahead of iterators scanning a whole column family
+const SCAN_READAHEAD_SIZE: usize = 2 * 1024 * 1024;
+
+/// Separates the id of a content from its version in the keys of the
+/// versions of a root content that were replaced, e.g. `id::v2`
+const CONTENT_VERSION_SEPARATOR: &[u8] = b"::v";
+
+/// How the keys of a column family are read, which decides the filters that
+/// are kept for it
+enum KeyAccess {
+    /// Keys are read one at a time, the filters skip the files that don't
+    /// hold a key
+    Point,
+    /// Keys are read one at a time, and with the other versions of the same
+    /// content
+    ContentVersions,
+    /// Keys are scanned by the part before their first `/`, like the
+    /// namespace of metering records
+    SlashPrefix,
+    /// Keys are scanned in order or read rarely, filters don't pay off
+    Scan,
+}
+
+impl StateMachineColumns {
+    fn key_access(&self) -> KeyAccess {
+        match self {
+            StateMachineColumns::ContentTable => KeyAccess::ContentVersions,
+            StateMachineColumns::Tasks |
+            StateMachineColumns::TaskTransitions |
+            StateMachineColumns::TaskAssignmentTimes |
+            StateMachineColumns::ExtractionPoliciesAppliedOnContent |
+            StateMachineColumns::RunningReplayTasks => KeyAccess::Point,
+            StateMachineColumns::Metering |
+            StateMachineColumns::ExtractionGraphUsage |
+            StateMachineColumns::NamespaceReplays => KeyAccess::SlashPrefix,
+            _ => KeyAccess::Scan,
+        }
+    }
+
+    /// Options of the column family, with filters fitting how its keys are
+    /// read and its blocks cached in `cache`
+    pub fn options(&self, cache: &Cache) -> Options {
+        let mut table_options = BlockBasedOptions::default();
+        table_options.set_block_cache(cache);
+        let mut options = Options::default();
+        match self.key_access() {
+            KeyAccess::Point => {
+                table_options.set_bloom_filter(BLOOM_FILTER_BITS_PER_KEY, false);
+                table_options.set_cache_index_and_filter_blocks(true);
+                table_options.set_pin_l0_filter_and_index_blocks_in_cache(true);
+                options.set_memtable_whole_key_filtering(true);
+                options.set_memtable_prefix_bloom_ratio(MEMTABLE_BLOOM_RATIO);
+            }
+            KeyAccess::ContentVersions => {
+                //  Whole keys are filtered as well as content ids, so reads of
+                //  the latest version don't lose the precision of the filter
+                table_options.set_bloom_filter(BLOOM_FILTER_BITS_PER_KEY, false);
+                table_options.set_whole_key_filtering(true);
+                table_options.set_cache_index_and_filter_blocks(true);
+                table_options.set_pin_l0_filter_and_index_blocks_in_cache(true);
+                options.set_prefix_extractor(SliceTransform::create(
+                    "content_id",
+                    content_id_prefix,
+                    None,
+                ));
+                options.set_memtable_whole_key_filtering(true);
+                options.set_memtable_prefix_bloom_ratio(MEMTABLE_BLOOM_RATIO);
+            }
+            KeyAccess::SlashPrefix => {
+                table_options.set_bloom_filter(BLOOM_FILTER_BITS_PER_KEY, false);
+                table_options.set_whole_key_filtering(false);
+                options.set_prefix_extractor(SliceTransform::create(
+                    "slash_prefix",
+                    slash_prefix,
+                    Some(has_slash),
+                ));
+                options.set_memtable_prefix_bloom_ratio(MEMTABLE_BLOOM_RATIO);
+            }
+            KeyAccess::Scan => {}
+        }
+        options.set_block_based_table_factory(&table_options);
+        options
+    }
+}
+
+/// Options of the column families of the raft log and of its vote, which are
+/// read in order of their keys
+pub fn log_options(cache: &Cache) -> Options {
+    let mut table_options = BlockBasedOptions::default();
+    table_options.set_block_cache(cache);
+    let mut options = Options::default();
+    options.set_block_based_table_factory(&table_options);
+    options
+}
+
+fn content_id_prefix(key: &[u8]) -> &[u8] {
+    key.windows(CONTENT_VERSION_SEPARATOR.len())
+        .position(|window| window == CONTENT_VERSION_SEPARATOR)
+        .map_or(key, |end| &key[..end])
+}
+
+fn slash_prefix(key: &[u8]) -> &[u8] {
+    key.iter()
+        .position(|byte| *byte == b'/')
+        .map_or(key, |end| &key[..=end])
+}
+
+fn has_slash(key: &[u8]) -> bool {
+    key.contains(&b'/')
+}
+
+/// Read options for full scans of a column family. Prefetches ahead of the
+/// iterator instead of reading one block at a time and keeps the scanned
+/// blocks out of the block cache used by point reads. Scans ignore the prefix
+/// filters, they would end the scan at the end of the first prefix.
+pub fn scan_read_options() -> ReadOptions {
+    let mut opts = ReadOptions::default();
+    opts.set_readahead_size(SCAN_READAHEAD_SIZE);
+    opts.fill_cache(false);
+    opts.set_total_order_seek(true);
+    opts
+}
+
+/// Read options for scans of the keys with the prefix of the key they start
+/// from, which skip the files the prefix filters rule out
+pub fn prefix_read_options() -> ReadOptions {
+    let mut opts = ReadOptions::default();
+    opts.set_prefix_same_as_start(true);
+    opts
+}
+
+/// Read options for replaying a range of the raft log. The iterator stops at
+/// the end of the range instead of reading the entry after it, or the
+/// deleted entries after a truncation.
+pub fn log_read_options(range: &impl RangeBounds<u64>) -> ReadOptions {
+    let mut opts = ReadOptions::default();
+    let end = match range.end_bound() {
+        Bound::Included(end) => end.checked_add(1),
+        Bound::Excluded(end) => Some(*end),
+        Bound::Unbounded => None,
+    };
+    if let Some(end) = end {
+        opts.set_iterate_upper_bound(id_to_bin(end));
+    }
+    opts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_prefixes() {
+        assert_eq!(content_id_prefix(b"abc"), b"abc");
+        assert_eq!(content_id_prefix(b"abc::v2"), b"abc");
+        assert_eq!(content_id_prefix(b"a:b::v12"), b"a:b");
+
+        assert_eq!(slash_prefix(b"default/1718000000"), b"default/");
+        assert!(has_slash(b"default/1718000000"));
+        assert!(!has_slash(b"default"));
+    }
+}
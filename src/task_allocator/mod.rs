@@ -6,6 +6,7 @@ use self::planner::plan::TaskAllocationPlan;
 use crate::state::{store::TaskId, SharedState};
 
 pub mod planner;
+pub mod simulation;
 
 #[allow(dead_code)] // until scheduler integration
 pub struct TaskAllocator {
@@ -24,20 +25,29 @@ impl TaskAllocator {
         }
     }
 
+    /// Plans the allocation of tasks. Tasks of rate limited extraction
+    /// policies over their limit are left out of the plan and stay unassigned.
     pub async fn allocate_tasks(&self, task_ids: HashSet<TaskId>) -> Result<TaskAllocationPlan> {
+        let task_ids = self
+            .shared_state
+            .state_machine
+            .admit_rate_limited_tasks(task_ids)?;
         self.planner.plan_allocations(task_ids).await
     }
 
-    /// Reschedule all tasks that match an extractor, even if they are already
-    /// assigned to a different executor.
-    pub async fn reallocate_all_tasks_matching_extractor(
+    /// Schedule the unassigned tasks that match an extractor. Tasks that are
+    /// already assigned stay with their executor, assigning them again would
+    /// allocate them twice.
+    pub async fn allocate_unassigned_tasks_matching_extractor(
         &self,
         extractor_name: &str,
     ) -> Result<TaskAllocationPlan> {
-        let task_ids = self
+        let mut task_ids = self
             .shared_state
             .unfinished_tasks_by_extractor(extractor_name)
             .await?;
-        self.planner.plan_allocations(task_ids).await
+        let unassigned = self.shared_state.unassigned_task_ids().await;
+        task_ids.retain(|task_id| unassigned.contains(task_id));
+        self.allocate_tasks(task_ids).await
     }
 }
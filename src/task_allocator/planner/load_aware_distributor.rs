@@ -1,15 +1,29 @@
 use std::{
     cmp::{Ordering, Reverse},
-    collections::{BinaryHeap, HashMap, HashSet},
+    collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque},
 };
 
-use indexify_internal_api::ExecutorMetadata;
-use tracing::error;
+use indexify_internal_api::{
+    CapacityReservation,
+    ExecutorMetadata,
+    PlacementCandidate,
+    PlacementRejection,
+    PlacementStrategy,
+    PressureLevel,
+    SchedulerSettings,
+    SchedulingAttempt,
+    Task,
+    ZonePlacement,
+};
+use tracing::{debug, error};
 
 use super::{plan::TaskAllocationPlan, AllocationPlanner, AllocationPlannerResult};
-use crate::state::{
-    store::{ExecutorId, ExtractorName, StateMachineColumns, TaskId},
-    SharedState,
+use crate::{
+    state::{
+        store::{ExecutorId, ExtractorName, TaskId},
+        SharedState,
+    },
+    utils::timestamp_secs,
 };
 
 type MinHeap<T> = BinaryHeap<Reverse<T>>;
@@ -22,6 +36,9 @@ type MinHeap<T> = BinaryHeap<Reverse<T>>;
 /// let load = ExecutorLoad {
 ///   executor_id: "executor1".to_string(),
 ///   running_task_count: 5,
+///   capacity_weight: 1,
+///   throttled: false,
+///   cost_order: None,
 /// };
 /// let mut heap = BinaryHeap::new();
 /// heap.push(Reverse(load));
@@ -32,6 +49,63 @@ struct ExecutorLoad {
     executor_id: ExecutorId,
     // Current count of tasks being processed by the executor.
     running_task_count: usize,
+    // Multiple of the tasks per executor limit the executor takes.
+    capacity_weight: usize,
+    // Whether the executor reported elevated pressure.
+    throttled: bool,
+    // Cost of the executor, set when executors are ordered cheapest first for
+    // latency tolerant tasks.
+    cost_order: Option<CostOrder>,
+}
+
+/// Orders executors with room for another task by their cost before their
+/// load, for the tasks that go to the cheapest executors. Executors without
+/// room keep the load order behind them, so the least loaded one still takes
+/// burst tasks first.
+#[derive(Debug, Clone, Copy)]
+struct CostOrder {
+    // Cost per hour of the executor, executors that didn't register a cost
+    // count as free.
+    cost: f64,
+    // Tasks per unit of capacity weight the executor has room for.
+    limit: Option<usize>,
+}
+
+impl ExecutorLoad {
+    /// Running tasks counted against the capacity of the executor. Tasks of
+    /// executors under elevated pressure count twice, so they take half their
+    /// share of tasks and reach their limit at half the tasks.
+    fn load(&self) -> usize {
+        if self.throttled {
+            self.running_task_count * 2
+        } else {
+            self.running_task_count
+        }
+    }
+
+    /// Whether the executor runs `max` tasks for each unit of its capacity
+    /// weight
+    fn is_full(&self, max: Option<usize>) -> bool {
+        max.map_or(false, |max| self.load() >= max * self.capacity_weight)
+    }
+
+    /// Cost the executor is ordered by, none when it isn't ordered by cost or
+    /// has no room for another task
+    fn cost_rank(&self) -> Option<f64> {
+        let order = self.cost_order?;
+        (!self.is_full(order.limit)).then_some(order.cost)
+    }
+
+    /// Tasks the executor takes before it runs `max` tasks for each unit of
+    /// its capacity weight
+    fn free_slots(&self, max: usize) -> usize {
+        let free = (max * self.capacity_weight).saturating_sub(self.load());
+        if self.throttled {
+            free / 2
+        } else {
+            free
+        }
+    }
 }
 
 impl Ord for ExecutorLoad {
@@ -49,14 +123,30 @@ impl Ord for ExecutorLoad {
     /// `running_task_count` (or however the executor load ranking is defined)
     /// are prioritized for receiving new tasks.
     ///
+    /// The running task count is relative to the capacity weight, so an
+    /// executor with twice the weight is as loaded as another one when it
+    /// runs twice the tasks.
+    ///
+    /// Executors ordered by cost go by their cost first, the ones with room
+    /// for another task before the others.
+    ///
     /// Keep this method aligned with the load balancing strategy. If
     /// additional factors should be considered in the future, incorporate them
     /// here, keeping in mind the inverted logic due to the `Reverse`
     /// wrapper.
     fn cmp(&self, other: &Self) -> Ordering {
-        // Compare the running task count to establish the ordering. `.cmp` is
-        // equivalent to <=>, and it returns a corresponding Ordering.
-        self.running_task_count.cmp(&other.running_task_count)
+        // Compare the running task count per unit of capacity weight to establish
+        // the ordering, cross multiplied to stay in integers. `.cmp` is equivalent
+        // to <=>, and it returns a corresponding Ordering.
+        let by_cost = match (self.cost_rank(), other.cost_rank()) {
+            (Some(cost), Some(other_cost)) => cost.total_cmp(&other_cost),
+            (Some(_), None) if other.cost_order.is_some() => Ordering::Less,
+            (None, Some(_)) if self.cost_order.is_some() => Ordering::Greater,
+            _ => Ordering::Equal,
+        };
+        by_cost.then_with(|| {
+            (self.load() * other.capacity_weight).cmp(&(other.load() * self.capacity_weight))
+        })
     }
 }
 
@@ -70,8 +160,852 @@ impl Eq for ExecutorLoad {}
 
 impl PartialEq for ExecutorLoad {
     fn eq(&self, other: &Self) -> bool {
-        self.running_task_count == other.running_task_count && self.executor_id == other.executor_id
+        self.running_task_count == other.running_task_count &&
+            self.capacity_weight == other.capacity_weight &&
+            self.throttled == other.throttled &&
+            self.executor_id == other.executor_id
+    }
+}
+
+/// Pops the least loaded executor of `warm` that has room for another task,
+/// or the least loaded executor overall when none of `warm` serves the
+/// extractor of the heap or has room. Room is counted against the nominal
+/// limit, so warm executors don't take burst tasks while other executors
+/// have nominal room.
+fn pop_preferring_warm(
+    heap: &mut MinHeap<ExecutorLoad>,
+    warm: &HashSet<ExecutorId>,
+    max_tasks_per_executor: Option<usize>,
+) -> Option<Reverse<ExecutorLoad>> {
+    let mut skipped = Vec::new();
+    let mut preferred = None;
+    while let Some(load) = heap.pop() {
+        if load.0.is_full(max_tasks_per_executor) {
+            //  Executors after this one are at least as loaded
+            skipped.push(load);
+            break;
+        }
+        if warm.contains(&load.0.executor_id) {
+            preferred = Some(load);
+            break;
+        }
+        skipped.push(load);
+    }
+    heap.extend(skipped);
+    preferred.or_else(|| heap.pop())
+}
+
+/// Orders the executors of the heap cheapest first. Executors that didn't
+/// register a cost count as free.
+fn order_by_cost(
+    heap: &mut MinHeap<ExecutorLoad>,
+    costs: &HashMap<ExecutorId, f64>,
+    max_tasks_per_executor: Option<usize>,
+) {
+    *heap = std::mem::take(heap)
+        .into_iter()
+        .map(|Reverse(mut load)| {
+            load.cost_order = Some(CostOrder {
+                cost: costs.get(&load.executor_id).copied().unwrap_or_default(),
+                limit: max_tasks_per_executor,
+            });
+            Reverse(load)
+        })
+        .collect();
+}
+
+/// Takes the executors that can't run `task` out of the heap, so they sit out
+/// the pick of an executor for it, with the first reason each one can't: the
+/// input is over its payload limit or takes too long to download, none of its
+/// GPU devices has room for the task, or it doesn't run the graph version of
+/// the task. `occupancy` holds the occupancy of the devices of the executors
+/// with GPUs.
+fn take_unfit(
+    heap: &mut MinHeap<ExecutorLoad>,
+    executors: &BTreeMap<ExecutorId, ExecutorMetadata>,
+    occupancy: &HashMap<ExecutorId, Vec<u32>>,
+    task: &Task,
+    max_download_secs: Option<u32>,
+) -> Vec<(Reverse<ExecutorLoad>, PlacementRejection)> {
+    let rejection = |executor_id: &ExecutorId| {
+        let fits_gpu = |executor: &ExecutorMetadata| {
+            task.gpu_millis.map_or(true, |millis| {
+                occupancy.get(executor_id).map_or(false, |occupancy| {
+                    executor.pack_gpu(occupancy, millis).is_some()
+                })
+            })
+        };
+        let Some(executor) = executors.get(executor_id) else {
+            return task.gpu_millis.map(|_| PlacementRejection::NoGpu);
+        };
+        if !executor.takes_payload(task.content_metadata.size_bytes, max_download_secs) {
+            Some(PlacementRejection::InputTooLarge)
+        } else if !fits_gpu(executor) {
+            Some(PlacementRejection::NoGpu)
+        } else if task.graph_version.is_some_and(|version| {
+            !executor.runs_graph_version(&task.namespace, &task.extraction_graph_name, version)
+        }) {
+            Some(PlacementRejection::GraphVersion)
+        } else {
+            None
+        }
+    };
+    let mut unfit = Vec::new();
+    for load in std::mem::take(heap).into_vec() {
+        match rejection(&load.0.executor_id) {
+            Some(rejection) => unfit.push((load, rejection)),
+            None => heap.push(load),
+        }
+    }
+    unfit
+}
+
+/// What decides which executors can run a task: how many of the input
+/// limits of the executors its input is over, the share of a GPU it takes and
+/// the graph version it was made by, when executors declare the versions they
+/// run. Consecutive tasks of one class are kept off the same executors, so
+/// the executors that can't run them are taken out of the heap once for all
+/// of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FitClass<'a> {
+    over_payload_limits: usize,
+    gpu_millis: Option<u32>,
+    graph_version: Option<(&'a str, &'a str, u64)>,
+}
+
+/// Sorts the tasks of an extractor into classes by what its executors limit
+struct FitClasses {
+    payload_limits: Vec<u64>,
+    versioned: bool,
+}
+
+impl FitClasses {
+    fn new(
+        executors: &BTreeMap<ExecutorId, ExecutorMetadata>,
+        max_download_secs: Option<u32>,
+    ) -> Self {
+        let mut payload_limits: Vec<u64> = executors
+            .values()
+            .filter_map(|executor| payload_limit(executor, max_download_secs))
+            .collect();
+        payload_limits.sort_unstable();
+        payload_limits.dedup();
+        FitClasses {
+            payload_limits,
+            versioned: executors
+                .values()
+                .any(|executor| !executor.graph_versions.is_empty()),
+        }
+    }
+
+    fn of<'a>(&self, task: &'a Task) -> FitClass<'a> {
+        let size_bytes = task.content_metadata.size_bytes;
+        FitClass {
+            over_payload_limits: self
+                .payload_limits
+                .partition_point(|limit| *limit < size_bytes),
+            gpu_millis: task.gpu_millis,
+            graph_version: task
+                .graph_version
+                .filter(|_| self.versioned)
+                .map(|version| {
+                    (
+                        task.namespace.as_str(),
+                        task.extraction_graph_name.as_str(),
+                        version,
+                    )
+                }),
+        }
+    }
+}
+
+/// Largest input an executor takes, see `ExecutorMetadata::takes_payload`
+fn payload_limit(executor: &ExecutorMetadata, max_download_secs: Option<u32>) -> Option<u64> {
+    let download_limit = executor
+        .download_bytes_per_sec
+        .zip(max_download_secs)
+        .map(|(bandwidth, max_secs)| bandwidth * max_secs as u64);
+    match (executor.max_payload_bytes, download_limit) {
+        (Some(max_payload), Some(download_limit)) => Some(max_payload.min(download_limit)),
+        (max_payload, download_limit) => max_payload.or(download_limit),
+    }
+}
+
+/// Adds the executors the allocator passed over for a task to the candidates
+/// of its placement attempt
+fn reject<'a>(
+    candidates: &mut Vec<PlacementCandidate>,
+    loads: impl IntoIterator<Item = &'a Reverse<ExecutorLoad>>,
+    rejection: PlacementRejection,
+) {
+    candidates.extend(loads.into_iter().map(|load| PlacementCandidate {
+        executor_id: load.0.executor_id.clone(),
+        rejection: Some(rejection),
+    }));
+}
+
+/// Adds the executors left in the heap after the pick of an executor for a
+/// task to the candidates of its placement attempt, the full ones rejected
+/// for being full and the others for `rejection`
+fn reject_rest(
+    candidates: &mut Vec<PlacementCandidate>,
+    heap: &MinHeap<ExecutorLoad>,
+    limit: Option<usize>,
+    rejection: PlacementRejection,
+) {
+    candidates.extend(heap.iter().map(|load| PlacementCandidate {
+        executor_id: load.0.executor_id.clone(),
+        rejection: Some(
+            if load.0.is_full(limit) {
+                PlacementRejection::Full
+            } else {
+                rejection
+            },
+        ),
+    }));
+}
+
+/// Pops an executor for a task whose content is placed by zone. `placed`
+/// counts the tasks of the content each zone was given so far.
+///
+/// Spread picks the zone with room that was given the fewest tasks of the
+/// content, same zone the zone that was given the most. The least loaded
+/// executor of the zone is popped, or the least loaded executor overall when
+/// the zone has no room. Without a zone to pick, executors that registered a
+/// zone go first. Returns `None` when a same zone task has to wait for
+/// room in its zone.
+fn pop_by_zone(
+    heap: &mut MinHeap<ExecutorLoad>,
+    zones: &HashMap<ExecutorId, String>,
+    placement: ZonePlacement,
+    placed: &HashMap<String, usize>,
+    max_tasks_per_executor: Option<usize>,
+) -> Option<Reverse<ExecutorLoad>> {
+    let zone = match placement {
+        ZonePlacement::Any => return heap.pop(),
+        ZonePlacement::Spread => heap
+            .iter()
+            .filter(|load| !load.0.is_full(max_tasks_per_executor))
+            .filter_map(|load| zones.get(&load.0.executor_id))
+            .min_by_key(|zone| (placed.get(*zone).copied().unwrap_or_default(), *zone))
+            .cloned(),
+        ZonePlacement::SameZone => placed
+            .iter()
+            .max_by_key(|(zone, count)| (**count, Reverse(*zone)))
+            .map(|(zone, _)| zone.clone()),
+    };
+    let Some(zone) = zone else {
+        //  Executors of any zone go before executors without one
+        let zoned = zones.keys().cloned().collect();
+        return pop_preferring_warm(heap, &zoned, max_tasks_per_executor);
+    };
+    let in_zone: HashSet<ExecutorId> = zones
+        .iter()
+        .filter(|(_, executor_zone)| **executor_zone == zone)
+        .map(|(executor_id, _)| executor_id.clone())
+        .collect();
+    let load = pop_preferring_warm(heap, &in_zone, max_tasks_per_executor)?;
+    if placement == ZonePlacement::SameZone &&
+        !in_zone.is_empty() &&
+        !in_zone.contains(&load.0.executor_id)
+    {
+        heap.push(load);
+        return None;
+    }
+    Some(load)
+}
+
+/// Orders the tasks of `task_ids` with a smooth weighted round robin over
+/// their namespaces, so that when executors run out of room for tasks each
+/// namespace got a share of the assigned tasks proportional to its weight.
+/// The tasks of a namespace keep their order in `task_ids`.
+fn order_by_namespace_weight<'a>(
+    task_ids: &[&'a TaskId],
+    task_namespaces: &HashMap<TaskId, String>,
+    settings: &SchedulerSettings,
+) -> Vec<&'a TaskId> {
+    let mut queues: BTreeMap<&str, VecDeque<&'a TaskId>> = BTreeMap::new();
+    for &task_id in task_ids {
+        let namespace = task_namespaces.get(task_id).map_or("", |n| n.as_str());
+        queues.entry(namespace).or_default().push_back(task_id);
+    }
+    let mut credits: HashMap<&str, i64> = HashMap::new();
+    let mut ordered = Vec::with_capacity(task_ids.len());
+    while ordered.len() < task_ids.len() {
+        let mut total = 0;
+        let mut next: Option<(&str, i64)> = None;
+        for (&namespace, _) in queues.iter().filter(|(_, queue)| !queue.is_empty()) {
+            let weight = settings.namespace_weight(namespace) as i64;
+            total += weight;
+            let credit = credits.entry(namespace).or_default();
+            *credit += weight;
+            if next.map_or(true, |(_, max)| *credit > max) {
+                next = Some((namespace, *credit));
+            }
+        }
+        let Some((namespace, _)) = next else {
+            break;
+        };
+        *credits.get_mut(namespace).unwrap() -= total;
+        ordered.extend(queues.get_mut(namespace).unwrap().pop_front());
+    }
+    ordered
+}
+
+/// Slots of an active capacity reservation its tasks haven't taken yet, held
+/// on the executors of the extractors its graphs run
+#[derive(Debug, Clone)]
+struct HeldSlots {
+    reservation: CapacityReservation,
+    extractors: HashSet<ExtractorName>,
+    unused: usize,
+}
+
+/// Slots other tasks must leave free on the executors of an extractor. A
+/// task doesn't have to leave the slots of reservations it belongs to.
+fn held_slots(
+    held: &[HeldSlots],
+    extractor_name: &str,
+    tenant: Option<&(String, String)>,
+) -> usize {
+    held.iter()
+        .filter(|held| held.extractors.contains(extractor_name))
+        .filter(|held| {
+            !tenant.is_some_and(|(namespace, graph)| held.reservation.matches(namespace, graph))
+        })
+        .map(|held| held.unused)
+        .sum()
+}
+
+/// Whether a task belongs to a reservation that holds slots on the executors
+/// of an extractor
+fn is_reserved(
+    held: &[HeldSlots],
+    extractor_name: &str,
+    tenant: Option<&(String, String)>,
+) -> bool {
+    tenant.is_some_and(|(namespace, graph)| {
+        held.iter().any(|held| {
+            held.extractors.contains(extractor_name) && held.reservation.matches(namespace, graph)
+        })
+    })
+}
+
+/// What the planner reads from the state to plan the allocation of tasks:
+/// the tasks, the executors of their extractors and everything their
+/// placement depends on. It is read once for a plan, and placing tasks
+/// updates it the way the state changes once the plan is committed, so the
+/// scheduling simulation can place tasks again on a copy of it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PlacementState {
+    pub(crate) settings: SchedulerSettings,
+    // Tasks to place, by id and by extractor
+    pub(crate) tasks: HashMap<TaskId, Task>,
+    pub(crate) tasks_by_extractor: HashMap<ExtractorName, Vec<TaskId>>,
+    pub(crate) executors_by_extractor:
+        HashMap<ExtractorName, BTreeMap<ExecutorId, ExecutorMetadata>>,
+    // Tasks each executor runs
+    pub(crate) running_tasks: HashMap<ExecutorId, usize>,
+    // Occupancy of the devices of the executors with GPUs
+    pub(crate) gpu_occupancy: HashMap<ExecutorId, Vec<u32>>,
+    // Executors that recently ran the policy of a task, for the tasks placed
+    // on warm executors
+    pub(crate) warm_executors: HashMap<TaskId, HashSet<ExecutorId>>,
+    pub(crate) latency_tolerant: HashSet<TaskId>,
+    // How a task is placed across zones, with its root content
+    pub(crate) zone_placements: HashMap<TaskId, (ZonePlacement, String)>,
+    // Tasks given to each zone by root content
+    pub(crate) content_zones: HashMap<String, HashMap<String, usize>>,
+    held: Vec<HeldSlots>,
+    tenants: HashMap<TaskId, (String, String)>,
+}
+
+impl PlacementState {
+    /// Adds a task to place
+    pub(crate) fn add_task(
+        &mut self,
+        task: Task,
+        latency_tolerant: bool,
+        zone_placement: ZonePlacement,
+    ) {
+        if latency_tolerant {
+            self.latency_tolerant.insert(task.id.clone());
+        }
+        if zone_placement != ZonePlacement::Any {
+            self.zone_placements.insert(
+                task.id.clone(),
+                (
+                    zone_placement,
+                    task.content_metadata.get_root_id().to_string(),
+                ),
+            );
+        }
+        self.tenants.insert(
+            task.id.clone(),
+            (task.namespace.clone(), task.extraction_graph_name.clone()),
+        );
+        self.tasks_by_extractor
+            .entry(task.extractor.clone())
+            .or_default()
+            .push(task.id.clone());
+        self.tasks.insert(task.id.clone(), task);
+    }
+
+    /// Adds an idle executor of `extractors`
+    pub(crate) fn add_executor(
+        &mut self,
+        executor: ExecutorMetadata,
+        extractors: &[ExtractorName],
+    ) {
+        self.running_tasks.insert(executor.id.clone(), 0);
+        if !executor.gpu_devices.is_empty() {
+            self.gpu_occupancy
+                .insert(executor.id.clone(), vec![0; executor.gpu_devices.len()]);
+        }
+        for extractor in extractors {
+            self.executors_by_extractor
+                .entry(extractor.clone())
+                .or_default()
+                .insert(executor.id.clone(), executor.clone());
+        }
+    }
+
+    /// Frees the slot and the share of a GPU device a task took on its
+    /// executor once it finished
+    pub(crate) fn finish_task(&mut self, executor_id: &ExecutorId, task: &Task) {
+        if let Some(running) = self.running_tasks.get_mut(executor_id) {
+            *running = running.saturating_sub(1);
+        }
+        if let (Some(millis), Some(device), Some(occupancy)) = (
+            task.gpu_millis,
+            task.gpu_device,
+            self.gpu_occupancy.get_mut(executor_id),
+        ) {
+            if let Some(occupied) = occupancy.get_mut(device as usize) {
+                *occupied = occupied.saturating_sub(millis);
+            }
+        }
+        for held in self.held.iter_mut().filter(|held| {
+            held.reservation
+                .matches(&task.namespace, &task.extraction_graph_name)
+        }) {
+            held.unused = (held.unused + 1).min(held.reservation.slots as usize);
+        }
+    }
+}
+
+/// Creates a mapping from extractor names to min-heaps
+/// (priority queues) of executors, sorted by their current load.
+///
+/// The load of an executor is determined by the number of tasks it is
+/// currently running, allowing for efficient selection of the least loaded
+/// executor for task allocation. "Pop"-ing from the heap will yield the
+/// executor with the least load, and "push"-ing an updated
+/// load back into the heap will maintain the min-heap property.
+///
+/// Loads come from the running task counters of the placement state,
+/// weighted by the capacity the executors registered with, and heaps are
+/// only built for the extractors being allocated, from their executors
+/// loaded for the plan.
+///
+/// # Returns
+/// Returns a `HashMap` where each key is a `String` representing the
+/// extractor name, and each value is a `BinaryHeap<Reverse<ExecutorLoad>>`
+/// representing the priority queue of executors by their load for that
+/// extractor. Extractors without executors have no entry.
+fn initialize_executor_load_min_heaps_by_extractor(
+    state: &PlacementState,
+) -> HashMap<ExtractorName, MinHeap<ExecutorLoad>> {
+    let mut executors_load_min_heap: HashMap<ExtractorName, MinHeap<ExecutorLoad>> = HashMap::new();
+    for extractor_name in state.tasks_by_extractor.keys() {
+        let Some(executors) = state.executors_by_extractor.get(extractor_name) else {
+            continue;
+        };
+        let heap: MinHeap<ExecutorLoad> = executors
+            .values()
+            //  Draining executors and executors under critical pressure finish
+            //  their tasks without taking new ones
+            .filter(|executor| {
+                !executor.draining &&
+                    executor.pressure.as_ref().map(|pressure| pressure.level) !=
+                        Some(PressureLevel::Critical)
+            })
+            // use `Reverse` here to make it a min-heap
+            .map(|executor| {
+                Reverse(ExecutorLoad {
+                    executor_id: executor.id.clone(),
+                    running_task_count: state
+                        .running_tasks
+                        .get(&executor.id)
+                        .copied()
+                        .unwrap_or_default(),
+                    capacity_weight: executor.capacity_weight() as usize,
+                    throttled: executor.pressure.as_ref().map(|pressure| pressure.level) ==
+                        Some(PressureLevel::Elevated),
+                    cost_order: None,
+                })
+            })
+            .collect();
+        if !heap.is_empty() {
+            executors_load_min_heap.insert(extractor_name.clone(), heap);
+        }
     }
+    executors_load_min_heap
+}
+
+/// Places the tasks of the placement state on its executors, and updates
+/// the state with the placed tasks. Returns the plan with a placement attempt
+/// for every task, made at `attempted_at`. See `plan_allocations` for how
+/// tasks are placed.
+pub(crate) fn place(
+    state: &mut PlacementState,
+    attempted_at: u64,
+) -> (TaskAllocationPlan, Vec<(TaskId, SchedulingAttempt)>) {
+    // Initialize a mapping from extractor names to priority queues (min-heaps) of
+    // executors based on their load.
+    let mut executor_load_min_heaps_by_extractor: HashMap<ExtractorName, MinHeap<ExecutorLoad>> =
+        initialize_executor_load_min_heaps_by_extractor(state);
+    let PlacementState {
+        settings,
+        tasks,
+        tasks_by_extractor,
+        executors_by_extractor,
+        running_tasks,
+        gpu_occupancy: gpus,
+        warm_executors,
+        latency_tolerant,
+        zone_placements,
+        content_zones,
+        held,
+        tenants,
+    } = state;
+    let max_tasks_per_executor = settings.max_tasks_per_executor.map(|max| max as usize);
+    let task_namespaces: HashMap<TaskId, String> = if settings.namespace_weights.is_empty() {
+        HashMap::new()
+    } else {
+        tasks
+            .values()
+            .map(|task| (task.id.clone(), task.namespace.clone()))
+            .collect()
+    };
+    let no_executors = BTreeMap::new();
+
+    // Prepare the allocation plan structure to record task assignments.
+    let mut plan = TaskAllocationPlan::default();
+    let mut attempts = Vec::new();
+
+    for (extractor_name, task_ids) in tasks_by_extractor.iter() {
+        let executors = executors_by_extractor
+            .get(extractor_name)
+            .unwrap_or(&no_executors);
+        // Executors of the extractor left out of the heap because they are
+        // draining or under critical pressure
+        let in_heap: HashSet<&ExecutorId> = executor_load_min_heaps_by_extractor
+            .get(extractor_name)
+            .map(|heap| heap.iter().map(|load| &load.0.executor_id).collect())
+            .unwrap_or_default();
+        let paused: Vec<PlacementCandidate> = executors
+            .keys()
+            .filter(|executor_id| !in_heap.contains(executor_id))
+            .map(|executor_id| PlacementCandidate {
+                executor_id: executor_id.clone(),
+                rejection: Some(PlacementRejection::Paused),
+            })
+            .collect();
+        // Attempt to retrieve the min-heap of executor loads for the current extractor.
+        // If no heap is found (an invariant violation), log an error and skip to the
+        // next extractor.
+        let heap = match executor_load_min_heaps_by_extractor.get_mut(extractor_name) {
+            Some(heap) => heap,
+            None => {
+                // Logging at error level because this situation indicates a logic error
+                // that should be investigated.
+                error!(
+                    "No matching executor found for extractor '{}'. This shouldn't be possible.",
+                    extractor_name
+                );
+                attempts.extend(task_ids.iter().map(|task_id| {
+                    (
+                        task_id.clone(),
+                        SchedulingAttempt {
+                            attempted_at,
+                            executor_id: None,
+                            candidates: paused.clone(),
+                        },
+                    )
+                }));
+                continue;
+            }
+        };
+        // Tasks of content with a higher priority are placed first, so they
+        // are the ones placed when executors run out of room for tasks.
+        let mut task_ids: Vec<&TaskId> = task_ids.iter().collect();
+        task_ids.sort_by_key(|task_id| {
+            let priority = tasks
+                .get(*task_id)
+                .map_or(0, |task| task.content_metadata.priority);
+            (Reverse(priority), *task_id)
+        });
+        // Namespaces take turns by weight, which only matters when executors
+        // run out of room for tasks.
+        if !task_namespaces.is_empty() {
+            task_ids = order_by_namespace_weight(&task_ids, &task_namespaces, settings);
+        }
+        // Executors of overcommitted extractors take burst tasks once every
+        // executor is at the nominal limit. Least loaded executors are picked
+        // first, so no executor bursts while another one has nominal room.
+        let burst_limit = settings
+            .burst_tasks_per_executor(extractor_name)
+            .map(|max| max as usize);
+        // Tasks of reservations go first so they take the slots held for them
+        // before other tasks take what is left.
+        let mut free_slots = 0;
+        if !held.is_empty() {
+            task_ids
+                .sort_by_key(|task_id| !is_reserved(held, extractor_name, tenants.get(*task_id)));
+            free_slots = heap
+                .iter()
+                .map(|load| {
+                    load.0
+                        .free_slots(max_tasks_per_executor.unwrap_or_default())
+                })
+                .sum();
+        }
+        let costs: HashMap<ExecutorId, f64> = executors
+            .values()
+            .filter_map(|executor| Some((executor.id.clone(), executor.cost_per_hour?)))
+            .collect();
+        // Tasks of latency tolerant graphs go to the cheapest executors after
+        // the other tasks are placed, so the expensive executors stay reserved
+        // for the tasks that can't wait and the others take what is left.
+        let mut cheapest_first = false;
+        if !costs.is_empty() {
+            task_ids.sort_by_key(|task_id| latency_tolerant.contains(*task_id));
+        }
+        let zones: HashMap<ExecutorId, String> = executors
+            .values()
+            .filter_map(|executor| Some((executor.id.clone(), executor.zone.clone()?)))
+            .collect();
+        // Executors that can't run the tasks of `unfit_class` sit out of the
+        // heap until a task of another class comes up.
+        let fit_classes = FitClasses::new(executors, settings.max_input_download_secs);
+        let mut unfit: Vec<(Reverse<ExecutorLoad>, PlacementRejection)> = Vec::new();
+        let mut unfit_class: Option<FitClass> = None;
+        // Iterate over each task ID assigned to the current extractor. Tasks
+        // taking a share of a GPU only go to executors with a device that has
+        // room for them, the occupancy of the devices grows as tasks are
+        // placed.
+        for (i, task_id) in task_ids.iter().enumerate() {
+            let task_id = *task_id;
+            if !cheapest_first && latency_tolerant.contains(task_id) && !costs.is_empty() {
+                //  The other tasks are placed, the rest go cheapest first
+                heap.extend(unfit.drain(..).map(|(load, _)| load));
+                unfit_class = None;
+                order_by_cost(heap, &costs, max_tasks_per_executor);
+                cheapest_first = true;
+            }
+            let mut candidates = paused.clone();
+            let tenant = tenants.get(task_id);
+            if !held.is_empty() && free_slots <= held_slots(held, extractor_name, tenant) {
+                // The free slots are reserved for other tasks, the task stays unassigned
+                // until the reservation ends or other tasks finish.
+                reject(&mut candidates, heap.iter(), PlacementRejection::Reserved);
+                attempts.push((
+                    task_id.clone(),
+                    SchedulingAttempt {
+                        attempted_at,
+                        executor_id: None,
+                        candidates,
+                    },
+                ));
+                continue;
+            }
+            // Executors that can't run the task sit out the pick
+            let task = tasks.get(task_id);
+            let class = task.map(|task| fit_classes.of(task));
+            if class != unfit_class {
+                heap.extend(unfit.drain(..).map(|(load, _)| load));
+                if let Some(task) = task {
+                    unfit = take_unfit(
+                        heap,
+                        executors,
+                        gpus,
+                        task,
+                        settings.max_input_download_secs,
+                    );
+                }
+                unfit_class = class;
+            }
+            candidates.extend(unfit.iter().map(|(load, rejection)| PlacementCandidate {
+                executor_id: load.0.executor_id.clone(),
+                rejection: Some(*rejection),
+            }));
+            if !unfit.is_empty() && heap.is_empty() {
+                debug!(
+                    "no executor of extractor '{}' can run task {}",
+                    extractor_name, task_id
+                );
+                attempts.push((
+                    task_id.clone(),
+                    SchedulingAttempt {
+                        attempted_at,
+                        executor_id: None,
+                        candidates,
+                    },
+                ));
+                continue;
+            }
+            let filtered = !unfit.is_empty();
+            let zone_placement = zone_placements.get(task_id).filter(|_| !zones.is_empty());
+            // Attempt to pop the executor with the least load from the heap.
+            let executor_load = if let Some((placement, content_id)) = zone_placement {
+                let placed = content_zones.get(content_id).cloned().unwrap_or_default();
+                match pop_by_zone(heap, &zones, *placement, &placed, max_tasks_per_executor) {
+                    Some(executor_load) => Some(executor_load),
+                    // The zone of the content is full, the task waits for room in it
+                    None => {
+                        reject_rest(
+                            &mut candidates,
+                            heap,
+                            max_tasks_per_executor,
+                            PlacementRejection::Zone,
+                        );
+                        attempts.push((
+                            task_id.clone(),
+                            SchedulingAttempt {
+                                attempted_at,
+                                executor_id: None,
+                                candidates,
+                            },
+                        ));
+                        continue;
+                    }
+                }
+            } else if !costs.is_empty() && latency_tolerant.contains(task_id) {
+                heap.pop()
+            } else {
+                match warm_executors.get(task_id) {
+                    Some(warm) => pop_preferring_warm(heap, warm, max_tasks_per_executor),
+                    None => heap.pop(),
+                }
+            };
+            if let Some(executor_load) = &executor_load {
+                candidates.push(PlacementCandidate {
+                    executor_id: executor_load.0.executor_id.clone(),
+                    rejection: executor_load
+                        .0
+                        .is_full(burst_limit)
+                        .then_some(PlacementRejection::Full),
+                });
+            }
+            reject_rest(
+                &mut candidates,
+                heap,
+                burst_limit,
+                PlacementRejection::NotPicked,
+            );
+            let attempt = SchedulingAttempt {
+                attempted_at,
+                executor_id: executor_load
+                    .as_ref()
+                    .filter(|executor_load| !executor_load.0.is_full(burst_limit))
+                    .map(|executor_load| executor_load.0.executor_id.clone()),
+                candidates,
+            };
+            attempts.push((task_id.clone(), attempt.clone()));
+            match executor_load {
+                Some(executor_load) if executor_load.0.is_full(burst_limit) && filtered => {
+                    // The executors that take the input are full, others may still
+                    // take the tasks after this one
+                    heap.push(executor_load);
+                }
+                Some(executor_load) if executor_load.0.is_full(burst_limit) => {
+                    // The least loaded executor is full, so are all the others. The
+                    // remaining tasks stay unassigned until executors finish tasks.
+                    debug!(
+                        "executors of extractor '{}' are at their limit of {} tasks",
+                        extractor_name, executor_load.0.running_task_count
+                    );
+                    heap.push(executor_load);
+                    // The remaining tasks are passed over by the same executors
+                    attempts.extend(
+                        task_ids[i + 1..]
+                            .iter()
+                            .map(|task_id| ((*task_id).clone(), attempt.clone())),
+                    );
+                    break;
+                }
+                Some(executor_load) => {
+                    // If an executor is found, assign the task to it and increment its load.
+                    // Then, push the updated load back into the heap to maintain the min-heap
+                    // property.
+                    plan.assignments
+                        .insert(task_id.clone(), executor_load.0.executor_id.clone());
+                    if executor_load.0.is_full(max_tasks_per_executor) {
+                        plan.burst_tasks.insert(task_id.clone());
+                    }
+                    free_slots = free_slots.saturating_sub(1);
+                    let executor_id = &executor_load.0.executor_id;
+                    *running_tasks.entry(executor_id.clone()).or_default() += 1;
+                    // The executor stops fitting the tasks of the class once
+                    // its devices have no room left for their share of a GPU
+                    let mut fits_class = true;
+                    if let (Some(millis), Some(executor), Some(occupancy)) = (
+                        task.and_then(|task| task.gpu_millis),
+                        executors.get(executor_id),
+                        gpus.get_mut(executor_id),
+                    ) {
+                        let device = executor.pack_gpu(occupancy, millis);
+                        if let Some(device) = device {
+                            occupancy[device as usize] += millis;
+                        }
+                        plan.gpu_devices.insert(task_id.clone(), device);
+                        fits_class = executor.pack_gpu(occupancy, millis).is_some();
+                    }
+                    if let Some((namespace, graph)) = tenant {
+                        for held in held
+                            .iter_mut()
+                            .filter(|held| held.reservation.matches(namespace, graph))
+                        {
+                            held.unused = held.unused.saturating_sub(1);
+                        }
+                    }
+                    if let Some((_, content_id)) = zone_placement {
+                        if let Some(zone) = zones.get(&executor_load.0.executor_id) {
+                            *content_zones
+                                .entry(content_id.clone())
+                                .or_default()
+                                .entry(zone.clone())
+                                .or_default() += 1;
+                            plan.content_zones
+                                .insert(task_id.clone(), (content_id.clone(), zone.clone()));
+                        }
+                    }
+                    let mut load = executor_load.0;
+                    load.running_task_count += 1;
+                    if fits_class {
+                        heap.push(Reverse(load));
+                    } else {
+                        unfit.push((Reverse(load), PlacementRejection::NoGpu));
+                    }
+                }
+                None => {
+                    // If no executor is available for this task, log an error.
+                    // This case might require attention to ensure tasks are not left unhandled.
+                    error!("No matching executor found for task: {}", task_id);
+                }
+            }
+        }
+        heap.extend(unfit.drain(..).map(|(load, _)| load));
+    }
+
+    // The placed tasks leave the tasks to place
+    for task_ids in tasks_by_extractor.values_mut() {
+        task_ids.retain(|task_id| !plan.assignments.contains_key(task_id));
+    }
+    tasks_by_extractor.retain(|_, task_ids| !task_ids.is_empty());
+    tasks.retain(|task_id, _| !plan.assignments.contains_key(task_id));
+    (plan, attempts)
 }
 
 /// See comment for `plan_allocations` method for more details.
@@ -91,115 +1025,164 @@ impl LoadAwareDistributor {
     /// Only extractors with at least one matching task ID are included in
     /// the result.
     ///
+    /// The grouping borrows the task IDs from `task_ids` rather than copying
+    /// the unfinished task index, which matters when many tasks are queued.
+    ///
     /// # Parameters
     /// - `task_ids`: A set of `TaskId` representing the task IDs to be grouped.
     ///
     /// # Returns
-    /// A `HashMap` where each key is an `ExtractorName` associated with a
-    /// `HashSet` of `TaskId` that represents the grouped task IDs for that
-    /// extractor.
-    async fn group_tasks_by_extractor<'a>(
+    /// A `HashMap` where each key is an `ExtractorName` associated with the
+    /// task IDs for that extractor.
+    fn group_tasks_by_extractor<'a>(
         &self,
         task_ids: &'a HashSet<TaskId>,
-    ) -> HashMap<ExtractorName, HashSet<TaskId>> {
-        // let sm = self.shared_state.indexify_state.read().await;
-
-        // Initialize the result HashMap to collect the filtered task IDs by extractor.
-        let mut result = HashMap::new();
-        for (extractor, extractor_task_ids) in self
-            .shared_state
-            .get_unfinished_tasks_by_extractor()
-            .await
-            .iter()
-        {
-            let filtered_task_ids: HashSet<TaskId> =
-                extractor_task_ids.intersection(task_ids).cloned().collect();
-            if !filtered_task_ids.is_empty() {
-                // Only insert if there are actually task IDs to avoid empty entries.
-                result.insert(extractor.clone(), filtered_task_ids);
-            }
-        }
-        result
+    ) -> HashMap<ExtractorName, Vec<&'a TaskId>> {
+        self.shared_state
+            .group_unfinished_tasks_by_extractor(task_ids)
     }
 
-    /// This method creates a mapping from extractor names to min-heaps
-    /// (priority queues) of executors, sorted by their current load.
-    ///
-    /// The load of an executor is determined by the number of tasks it is
-    /// currently running, allowing for efficient selection of the least loaded
-    /// executor for task allocation. "Pop"-ing from the heap will yield the
-    /// executor with the least load, and "push"-ing an updated
-    /// load back into the heap will maintain the min-heap property.
-    ///
-    /// # Returns
-    /// Returns a `HashMap` where each key is a `String` representing the
-    /// extractor name, and each value is a `BinaryHeap<Reverse<ExecutorLoad>>`
-    /// representing the priority queue of executors by their load for that
-    /// extractor.
-    ///
-    /// # Errors
-    /// Logs an error if an executor referenced in the running task count is not
-    /// found in the executors table, indicating a potential inconsistency
-    /// in the application's state management.
-    async fn initialize_executor_load_min_heaps_by_extractor(
+    /// Reads the placement state of the tasks of `task_ids`, with the
+    /// executors of their extractors and of `extractors`, for a plan made at
+    /// `now`
+    pub(crate) async fn placement_state(
         &self,
-    ) -> HashMap<ExtractorName, MinHeap<ExecutorLoad>> {
-        let mut executors_load_min_heap: HashMap<ExtractorName, MinHeap<ExecutorLoad>> =
-            HashMap::new();
-        // Retrieve the current running task count for each executor from the shared
-        // state.
-        let executor_running_task_count = self.shared_state.get_executor_running_task_count().await;
-
-        // Populate the executors' load heap for each extractor based on the current
-        // running tasks.
-        for executor_id in executor_running_task_count.keys() {
-            let executor = self
+        task_ids: &HashSet<TaskId>,
+        extractors: &HashSet<ExtractorName>,
+        now: u64,
+    ) -> anyhow::Result<PlacementState> {
+        // Group tasks by their required extractor. This allows targeting a subset of
+        // executors rather than iterating over all of them.
+        let tasks_by_extractor: HashMap<ExtractorName, Vec<TaskId>> = self
+            .group_tasks_by_extractor(task_ids)
+            .into_iter()
+            .map(|(extractor_name, task_ids)| {
+                (extractor_name, task_ids.into_iter().cloned().collect())
+            })
+            .collect();
+        let settings = self.shared_state.get_scheduler_settings()?;
+        let warm_executors = match settings.placement_strategy {
+            PlacementStrategy::PreferWarm => {
+                self.shared_state.warm_executors_for_tasks(task_ids)?
+            }
+            PlacementStrategy::LeastLoaded => HashMap::new(),
+        };
+        // The tasks and the executors of their extractors are read once for the
+        // plan
+        let tasks = self.shared_state.get_tasks_from_ids(task_ids)?;
+        let gpu_extractors: HashSet<&ExtractorName> = tasks
+            .values()
+            .filter(|task| task.gpu_millis.is_some())
+            .map(|task| &task.extractor)
+            .collect();
+        let mut executors_by_extractor = HashMap::new();
+        let mut running_tasks = HashMap::new();
+        let mut gpu_occupancy = HashMap::new();
+        for extractor_name in tasks_by_extractor.keys().chain(extractors) {
+            if executors_by_extractor.contains_key(extractor_name) {
+                continue;
+            }
+            let executors: BTreeMap<ExecutorId, ExecutorMetadata> = self
                 .shared_state
-                .state_machine
-                .get_from_cf::<ExecutorMetadata, _>(StateMachineColumns::Executors, executor_id)
-                .map(|opt| {
-                    if opt.is_none() {
-                        error!("Executor with id {} not found", executor_id);
-                    }
-                    opt
-                })
-                .unwrap_or(None);
-            match executor {
-                Some(executor) => {
-                    let extractor_names = executor
-                        .extractors
-                        .into_iter()
-                        .map(|e| e.name)
-                        .collect::<Vec<String>>();
-
-                    let running_task_count = executor_running_task_count
-                        .get(executor_id)
-                        .cloned()
-                        .unwrap_or_default() as usize;
-
-                    // Update or create the heap for the extractor and add the executor's load.
-                    for extractor_name in extractor_names {
-                        executors_load_min_heap
-                            .entry(extractor_name)
-                            .or_default()
-                            // use `Reverse` here to make it a min-heap
-                            .push(Reverse(ExecutorLoad {
-                                executor_id: executor_id.clone(),
-                                running_task_count,
-                            }));
+                .get_executors_for_extractor(extractor_name)
+                .await?
+                .into_iter()
+                .map(|executor| (executor.id.clone(), executor))
+                .collect();
+            running_tasks.extend(
+                self.shared_state
+                    .get_executor_loads_for_extractor(extractor_name)
+                    .into_iter()
+                    .map(|(executor_id, running)| (executor_id, running as usize)),
+            );
+            // The occupancy of GPU devices is only read for the extractors of
+            // tasks taking a share of a GPU
+            if gpu_extractors.contains(extractor_name) || extractors.contains(extractor_name) {
+                for executor in executors.values() {
+                    if !executor.gpu_devices.is_empty() && !gpu_occupancy.contains_key(&executor.id)
+                    {
+                        let occupancy = self.shared_state.gpu_occupancy(executor).await?;
+                        gpu_occupancy.insert(executor.id.clone(), occupancy);
                     }
                 }
-                None => {
-                    // Inconsistency: an executor is in the running task count but not in
-                    // the executors table.
-                    error!(
-                        "Executor '{}' not found in executors table - this shouldn't be possible.",
-                        executor_id
-                    );
-                }
             }
+            executors_by_extractor.insert(extractor_name.clone(), executors);
+        }
+        let (held, tenants) = self.reserved_slots(&settings, now, &tasks).await?;
+        let latency_tolerant = self.shared_state.get_latency_tolerant_tasks(task_ids)?;
+        let zone_placements = self.shared_state.get_zone_placements(task_ids)?;
+        // Tasks of content placed by zone committed with the plans before,
+        // read back from the state so a new leader keeps placing them by zone
+        let root_content_ids: HashSet<&String> = zone_placements
+            .values()
+            .map(|(_, content_id)| content_id)
+            .collect();
+        let content_zones = self.shared_state.get_content_zones(root_content_ids)?;
+        Ok(PlacementState {
+            settings,
+            tasks,
+            tasks_by_extractor,
+            executors_by_extractor,
+            running_tasks,
+            gpu_occupancy,
+            warm_executors,
+            latency_tolerant,
+            zone_placements,
+            content_zones,
+            held,
+            tenants,
+        })
+    }
+
+    /// Slots of the reservations active at `now`, with the namespace and
+    /// graph of the planned `tasks` to match them with the reservations
+    async fn reserved_slots(
+        &self,
+        settings: &SchedulerSettings,
+        now: u64,
+        tasks: &HashMap<TaskId, Task>,
+    ) -> anyhow::Result<(Vec<HeldSlots>, HashMap<TaskId, (String, String)>)> {
+        let reservations: Vec<&CapacityReservation> = settings.active_reservations(now).collect();
+        if reservations.is_empty() || settings.max_tasks_per_executor.is_none() {
+            return Ok((Vec::new(), HashMap::new()));
         }
-        executors_load_min_heap
+        let assigned_ids: HashSet<TaskId> = self
+            .shared_state
+            .task_assignments()
+            .await?
+            .into_keys()
+            .collect();
+        let assigned = self.shared_state.get_tasks_from_ids(&assigned_ids)?;
+        let mut held = Vec::new();
+        for reservation in reservations {
+            let extractors = self
+                .shared_state
+                .list_extraction_policy(&reservation.namespace)
+                .await?
+                .into_iter()
+                .filter(|policy| reservation.matches(&policy.namespace, &policy.graph_name))
+                .map(|policy| policy.extractor)
+                .collect();
+            let used = assigned
+                .values()
+                .filter(|task| reservation.matches(&task.namespace, &task.extraction_graph_name))
+                .count();
+            held.push(HeldSlots {
+                reservation: reservation.clone(),
+                extractors,
+                unused: (reservation.slots as usize).saturating_sub(used),
+            });
+        }
+        let tenants = tasks
+            .values()
+            .map(|task| {
+                (
+                    task.id.clone(),
+                    (task.namespace.clone(), task.extraction_graph_name.clone()),
+                )
+            })
+            .collect();
+        Ok((held, tenants))
     }
 }
 
@@ -216,6 +1199,29 @@ impl AllocationPlanner for LoadAwareDistributor {
     /// `Ord` for `ExecutorLoad`. A min-heap is used to keep track of the
     /// running task count for each executor, updated as tasks are allocated.
     ///
+    /// Tasks of extraction policies with an expensive warm up go to the least
+    /// loaded executor that recently ran the policy instead, and fall back to
+    /// the least loaded executor when no such executor is registered.
+    ///
+    /// While a capacity reservation is active, its tasks are allocated first
+    /// and other tasks leave the slots it doesn't use yet free.
+    ///
+    /// Tasks of latency tolerant graphs go to the cheapest executor with room
+    /// when executors registered a cost, which leaves the expensive executors
+    /// to the tasks of other graphs.
+    ///
+    /// Tasks of graphs placed by zone are spread across the zones executors
+    /// registered with, or kept in the zone the first task of their root
+    /// content went to. Zone placement goes before cost and warm executors.
+    ///
+    /// Executors that registered a payload limit or download bandwidth only
+    /// get the tasks whose input is within the limit, or is downloaded within
+    /// `max_input_download_secs`. Tasks no executor takes wait for one.
+    ///
+    /// Every task the plan is made for gets a placement attempt recorded,
+    /// with the executors of its extractor and why the ones that didn't get
+    /// the task were passed over.
+    ///
     /// # Parameters
     /// - `task_ids`: A `HashSet` of `TaskId` representing the tasks to be
     ///   allocated. Each `TaskId` is unique and corresponds to a specific task
@@ -223,66 +1229,26 @@ impl AllocationPlanner for LoadAwareDistributor {
     ///
     /// # Returns
     /// Returns an `AllocationPlannerResult`, which is a result type that wraps
-    /// a `TaskAllocationPlan`. The `TaskAllocationPlan` itself is a
-    /// `HashMap` of `TaskId` to `ExecutorId`. If no tasks are provided (i.e.,
+    /// a `TaskAllocationPlan`. The `TaskAllocationPlan` holds the `ExecutorId`
+    /// of each `TaskId`, and the GPU device of the tasks taking a share of a
+    /// GPU. If no tasks are provided (i.e.,
     /// the `HashSet` is empty), the method returns an empty
     /// `TaskAllocationPlan`.
     async fn plan_allocations(&self, task_ids: HashSet<TaskId>) -> AllocationPlannerResult {
         // Early return if there are no tasks to allocate
         if task_ids.is_empty() {
-            return Ok(TaskAllocationPlan(HashMap::new()));
+            return Ok(TaskAllocationPlan::default());
         }
 
-        // Group tasks by their required extractor. This allows targeting a subset of
-        // executors rather than iterating over all of them.
-        let tasks_by_extractor = self.group_tasks_by_extractor(&task_ids).await;
-
-        // Initialize a mapping from extractor names to priority queues (min-heaps) of
-        // executors based on their load.
-        let mut executor_load_min_heaps_by_extractor: HashMap<
-            ExtractorName,
-            MinHeap<ExecutorLoad>,
-        > = self.initialize_executor_load_min_heaps_by_extractor().await;
-
-        // Prepare the allocation plan structure to record task assignments.
-        let mut plan = TaskAllocationPlan(HashMap::new());
-
-        for (extractor_name, task_ids) in tasks_by_extractor.iter() {
-            // Attempt to retrieve the min-heap of executor loads for the current extractor.
-            // If no heap is found (an invariant violation), log an error and skip to the
-            // next extractor.
-            let heap = match executor_load_min_heaps_by_extractor.get_mut(extractor_name) {
-                Some(heap) => heap,
-                None => {
-                    // Logging at error level because this situation indicates a logic error
-                    // that should be investigated.
-                    error!("No matching executor found for extractor '{}'. This shouldn't be possible.", extractor_name);
-                    continue;
-                }
-            };
-            // Iterate over each task ID assigned to the current extractor.
-            for task_id in task_ids.iter() {
-                // Attempt to pop the executor with the least load from the heap.
-                match heap.pop() {
-                    Some(executor_load) => {
-                        // If an executor is found, assign the task to it and increment its load.
-                        // Then, push the updated load back into the heap to maintain the min-heap
-                        // property.
-                        plan.0
-                            .insert(task_id.clone(), executor_load.0.executor_id.clone());
-                        let mut load = executor_load.0;
-                        load.running_task_count += 1;
-                        heap.push(Reverse(load));
-                    }
-                    None => {
-                        // If no executor is available for this task, log an error.
-                        // This case might require attention to ensure tasks are not left unhandled.
-                        error!("No matching executor found for task: {}", task_id);
-                    }
-                }
-            }
-        }
+        let attempted_at = timestamp_secs();
+        let mut state = self
+            .placement_state(&task_ids, &HashSet::new(), attempted_at)
+            .await?;
+        let (plan, attempts) = place(&mut state, attempted_at);
 
+        self.shared_state
+            .state_machine
+            .record_scheduling_attempts(attempts);
         Ok(plan)
     }
 }
@@ -298,8 +1264,13 @@ mod tests {
     use super::*;
     use crate::{
         server_config::ServerConfig,
-        state::App,
-        test_util::db_utils::{mock_extractor, mock_extractors},
+        state::{store::StateMachineColumns, App},
+        test_util::db_utils::{
+            create_test_extraction_graph,
+            mock_extractor,
+            mock_extractors,
+            DEFAULT_TEST_NAMESPACE,
+        },
     };
 
     fn create_task(
@@ -320,6 +1291,14 @@ mod tests {
             input_params: json!(null),
             outcome: internal_api::TaskOutcome::Unknown,
             index_tables: vec![],
+            lost_by_executors: vec![],
+            diagnostics: None,
+            usage: None,
+            progress: None,
+            gpu_millis: None,
+            gpu_device: None,
+            burst: false,
+            graph_version: None,
         }
     }
 
@@ -329,18 +1308,30 @@ mod tests {
         let load1 = ExecutorLoad {
             executor_id: "executor1".to_string(),
             running_task_count: 1,
+            capacity_weight: 1,
+            throttled: false,
+            cost_order: None,
         };
         let load2 = ExecutorLoad {
             executor_id: "executor2".to_string(),
             running_task_count: 2,
+            capacity_weight: 1,
+            throttled: false,
+            cost_order: None,
         };
         let load3 = ExecutorLoad {
             executor_id: "executor3".to_string(),
             running_task_count: 13,
+            capacity_weight: 1,
+            throttled: false,
+            cost_order: None,
         };
         let load4 = ExecutorLoad {
             executor_id: "executor4".to_string(),
             running_task_count: 4,
+            capacity_weight: 1,
+            throttled: false,
+            cost_order: None,
         };
         let mut heap = BinaryHeap::new();
         heap.push(Reverse(load1));
@@ -361,6 +1352,9 @@ mod tests {
             ExecutorLoad {
                 executor_id: "executor4".to_string(),
                 running_task_count: 4,
+                capacity_weight: 1,
+                throttled: false,
+                cost_order: None,
             }
         );
         assert_eq!(
@@ -368,6 +1362,9 @@ mod tests {
             ExecutorLoad {
                 executor_id: "executor2".to_string(),
                 running_task_count: 6,
+                capacity_weight: 1,
+                throttled: false,
+                cost_order: None,
             }
         );
         assert_eq!(
@@ -375,6 +1372,9 @@ mod tests {
             ExecutorLoad {
                 executor_id: "executor1".to_string(),
                 running_task_count: 11,
+                capacity_weight: 1,
+                throttled: false,
+                cost_order: None,
             }
         );
         assert_eq!(
@@ -382,10 +1382,638 @@ mod tests {
             ExecutorLoad {
                 executor_id: "executor3".to_string(),
                 running_task_count: 13,
+                capacity_weight: 1,
+                throttled: false,
+                cost_order: None,
             }
         );
     }
 
+    #[test]
+    fn test_pop_preferring_warm() {
+        let mut heap: MinHeap<ExecutorLoad> =
+            [("executor1", 1), ("executor2", 5), ("executor3", 3)]
+                .into_iter()
+                .map(|(executor_id, running_task_count)| {
+                    Reverse(ExecutorLoad {
+                        executor_id: executor_id.to_string(),
+                        running_task_count,
+                        capacity_weight: 1,
+                        throttled: false,
+                        cost_order: None,
+                    })
+                })
+                .collect();
+
+        let warm = HashSet::from(["executor2".to_string()]);
+        let load = pop_preferring_warm(&mut heap, &warm, None).unwrap().0;
+        assert_eq!(load.executor_id, "executor2");
+        assert_eq!(heap.len(), 2);
+
+        //  None of the warm executors is left, the least loaded one is used
+        let load = pop_preferring_warm(&mut heap, &warm, None).unwrap().0;
+        assert_eq!(load.executor_id, "executor1");
+        assert_eq!(heap.pop().unwrap().0.executor_id, "executor3");
+        assert!(pop_preferring_warm(&mut heap, &warm, None).is_none());
+    }
+
+    #[test]
+    fn test_pop_preferring_warm_at_limit() {
+        let mut heap: MinHeap<ExecutorLoad> = [("executor1", 1), ("executor2", 2)]
+            .into_iter()
+            .map(|(executor_id, running_task_count)| {
+                Reverse(ExecutorLoad {
+                    executor_id: executor_id.to_string(),
+                    running_task_count,
+                    capacity_weight: 1,
+                    throttled: false,
+                    cost_order: None,
+                })
+            })
+            .collect();
+
+        //  The warm executor is full, the least loaded one is used
+        let warm = HashSet::from(["executor2".to_string()]);
+        let load = pop_preferring_warm(&mut heap, &warm, Some(2)).unwrap().0;
+        assert_eq!(load.executor_id, "executor1");
+        assert_eq!(heap.len(), 1);
+    }
+
+    #[test]
+    fn test_weighted_executor_load() {
+        let big = ExecutorLoad {
+            executor_id: "big".to_string(),
+            running_task_count: 4,
+            capacity_weight: 4,
+            throttled: false,
+            cost_order: None,
+        };
+        let small = ExecutorLoad {
+            executor_id: "small".to_string(),
+            running_task_count: 2,
+            capacity_weight: 1,
+            throttled: false,
+            cost_order: None,
+        };
+        //  The big executor runs more tasks but is less loaded for its size
+        let mut heap: MinHeap<ExecutorLoad> = [Reverse(big.clone()), Reverse(small.clone())]
+            .into_iter()
+            .collect();
+        assert_eq!(heap.pop().unwrap().0.executor_id, "big");
+
+        assert!(!big.is_full(Some(2)));
+        assert!(small.is_full(Some(2)));
+        assert!(!small.is_full(None));
+    }
+
+    #[test]
+    fn test_throttled_executor_load() {
+        let throttled = ExecutorLoad {
+            executor_id: "throttled".to_string(),
+            running_task_count: 2,
+            capacity_weight: 1,
+            throttled: true,
+            cost_order: None,
+        };
+        let busy = ExecutorLoad {
+            executor_id: "busy".to_string(),
+            running_task_count: 3,
+            capacity_weight: 1,
+            throttled: false,
+            cost_order: None,
+        };
+        //  An executor under pressure counts its tasks twice
+        let mut heap: MinHeap<ExecutorLoad> = [Reverse(throttled.clone()), Reverse(busy.clone())]
+            .into_iter()
+            .collect();
+        assert_eq!(heap.pop().unwrap().0.executor_id, "busy");
+
+        assert!(throttled.is_full(Some(4)));
+        assert!(!busy.is_full(Some(4)));
+        assert_eq!(throttled.free_slots(6), 1);
+        assert_eq!(busy.free_slots(6), 3);
+    }
+
+    #[test]
+    fn test_place() {
+        let mut state = PlacementState {
+            settings: SchedulerSettings {
+                max_tasks_per_executor: Some(2),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        //  Room for 1 task on the first executor and 4 on the bigger one
+        let extractors = ["extractor".to_string()];
+        for (id, running, capacity_weight) in [("small", 1, 1), ("big", 4, 4)] {
+            state.add_executor(
+                ExecutorMetadata {
+                    id: id.to_string(),
+                    capacity_weight: Some(capacity_weight),
+                    ..Default::default()
+                },
+                &extractors,
+            );
+            state.running_tasks.insert(id.to_string(), running);
+        }
+        for i in 0..10 {
+            let task = Task {
+                id: format!("task-{}", i),
+                extractor: "extractor".to_string(),
+                ..Default::default()
+            };
+            state.add_task(task, false, ZonePlacement::Any);
+        }
+
+        let (plan, attempts) = place(&mut state, 1);
+        assert_eq!(plan.len(), 5);
+        assert_eq!(attempts.len(), 10);
+        assert_eq!(state.tasks.len(), 5);
+        assert_eq!(state.tasks_by_extractor["extractor"].len(), 5);
+        assert_eq!(state.running_tasks["small"], 2);
+        assert_eq!(state.running_tasks["big"], 8);
+
+        //  The executors are full until one of them finishes a task
+        let (plan, _) = place(&mut state, 2);
+        assert!(plan.is_empty());
+        state.finish_task(&"small".to_string(), &Task::default());
+        let (plan, _) = place(&mut state, 3);
+        assert_eq!(plan.assignments.values().collect::<Vec<_>>(), vec!["small"]);
+        assert_eq!(state.tasks.len(), 4);
+    }
+
+    #[test]
+    fn test_place_by_fit_class() {
+        let mut state = PlacementState::default();
+        let extractors = ["extractor".to_string()];
+        for (id, gpu_devices) in [
+            ("gpu_small", vec![500]),
+            ("gpu_big", vec![1000]),
+            ("cpu", vec![]),
+        ] {
+            state.add_executor(
+                ExecutorMetadata {
+                    id: id.to_string(),
+                    gpu_devices,
+                    ..Default::default()
+                },
+                &extractors,
+            );
+        }
+        //  Half a GPU for the first tasks, the last one runs on a CPU
+        for i in 0..5 {
+            let task = Task {
+                id: format!("task-{}", i),
+                extractor: "extractor".to_string(),
+                gpu_millis: (i < 4).then_some(500),
+                ..Default::default()
+            };
+            state.add_task(task, false, ZonePlacement::Any);
+        }
+
+        let (plan, attempts) = place(&mut state, 1);
+        let placed_on = |executor_id: &str| {
+            plan.assignments
+                .values()
+                .filter(|id| id.as_str() == executor_id)
+                .count()
+        };
+        //  GPU executors sit out the tasks of the class once their devices
+        //  are full, the executor without GPUs only takes the CPU task
+        assert_eq!(placed_on("gpu_small"), 1);
+        assert_eq!(placed_on("gpu_big"), 2);
+        assert_eq!(plan.assignments["task-4"], "cpu");
+        assert!(!plan.assignments.contains_key("task-3"));
+        let (_, attempt) = attempts
+            .iter()
+            .find(|(task_id, _)| task_id == "task-3")
+            .unwrap();
+        assert!(attempt.executor_id.is_none());
+        assert_eq!(attempt.candidates.len(), 3);
+        assert!(attempt
+            .candidates
+            .iter()
+            .all(|candidate| candidate.rejection == Some(PlacementRejection::NoGpu)));
+
+        //  Inputs under the same limits of the executors are of one class
+        let executors: BTreeMap<ExecutorId, ExecutorMetadata> =
+            [(Some(1024), None), (None, Some(100))]
+                .into_iter()
+                .enumerate()
+                .map(|(i, (max_payload_bytes, download_bytes_per_sec))| {
+                    let executor = ExecutorMetadata {
+                        id: format!("executor-{}", i),
+                        max_payload_bytes,
+                        download_bytes_per_sec,
+                        ..Default::default()
+                    };
+                    (executor.id.clone(), executor)
+                })
+                .collect();
+        let classes = FitClasses::new(&executors, Some(40));
+        let task = |size_bytes| Task {
+            content_metadata: ContentMetadata {
+                size_bytes,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (small, smaller, medium, large) = (task(100), task(10), task(2000), task(5000));
+        assert_eq!(classes.of(&small), classes.of(&smaller));
+        assert_ne!(classes.of(&small), classes.of(&medium));
+        assert_ne!(classes.of(&medium), classes.of(&large));
+    }
+
+    #[test]
+    fn test_order_by_cost() {
+        let loads = [("spot", 3), ("on_demand", 0), ("gpu", 1)];
+        let costs = HashMap::from([
+            ("spot".to_string(), 0.3),
+            ("on_demand".to_string(), 1.0),
+            ("gpu".to_string(), 4.0),
+        ]);
+        let heap_with = |max_tasks_per_executor| {
+            let mut heap: MinHeap<ExecutorLoad> = loads
+                .into_iter()
+                .map(|(executor_id, running_task_count)| {
+                    Reverse(ExecutorLoad {
+                        executor_id: executor_id.to_string(),
+                        running_task_count,
+                        capacity_weight: 1,
+                        throttled: false,
+                        cost_order: None,
+                    })
+                })
+                .collect();
+            order_by_cost(&mut heap, &costs, max_tasks_per_executor);
+            heap
+        };
+
+        assert_eq!(heap_with(None).pop().unwrap().0.executor_id, "spot");
+
+        //  The spot executor is full, the next cheapest one is used until it
+        //  is full too
+        let mut heap = heap_with(Some(3));
+        let mut load = heap.pop().unwrap().0;
+        assert_eq!(load.executor_id, "on_demand");
+        load.running_task_count = 3;
+        heap.push(Reverse(load));
+        assert_eq!(heap.pop().unwrap().0.executor_id, "gpu");
+
+        //  Only the on demand executor has room, the full ones follow by load
+        let mut heap = heap_with(Some(1));
+        assert_eq!(heap.pop().unwrap().0.executor_id, "on_demand");
+        assert_eq!(heap.pop().unwrap().0.executor_id, "gpu");
+        assert_eq!(heap.pop().unwrap().0.executor_id, "spot");
+    }
+
+    #[test]
+    fn test_pop_by_zone() {
+        let mut heap: MinHeap<ExecutorLoad> = [("a1", 0), ("a2", 1), ("b1", 1)]
+            .into_iter()
+            .map(|(executor_id, running_task_count)| {
+                Reverse(ExecutorLoad {
+                    executor_id: executor_id.to_string(),
+                    running_task_count,
+                    capacity_weight: 1,
+                    throttled: false,
+                    cost_order: None,
+                })
+            })
+            .collect();
+        let zones: HashMap<ExecutorId, String> = [("a1", "a"), ("a2", "a"), ("b1", "b")]
+            .into_iter()
+            .map(|(executor_id, zone)| (executor_id.to_string(), zone.to_string()))
+            .collect();
+
+        //  Zone a was given a task of the content already, so zone b is next
+        let placed = HashMap::from([("a".to_string(), 1)]);
+        let load = pop_by_zone(&mut heap, &zones, ZonePlacement::Spread, &placed, Some(2))
+            .unwrap()
+            .0;
+        assert_eq!(load.executor_id, "b1");
+        heap.push(Reverse(load));
+
+        //  The content stays in zone b, where the executor is full
+        let placed = HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)]);
+        assert!(
+            pop_by_zone(&mut heap, &zones, ZonePlacement::SameZone, &placed, Some(1)).is_none()
+        );
+        assert_eq!(heap.len(), 3);
+        let load = pop_by_zone(&mut heap, &zones, ZonePlacement::SameZone, &placed, Some(2))
+            .unwrap()
+            .0;
+        assert_eq!(load.executor_id, "b1");
+        heap.push(Reverse(load));
+
+        //  The first task of a content goes to the least loaded executor
+        let load = pop_by_zone(
+            &mut heap,
+            &zones,
+            ZonePlacement::SameZone,
+            &HashMap::new(),
+            Some(2),
+        )
+        .unwrap()
+        .0;
+        assert_eq!(load.executor_id, "a1");
+    }
+
+    fn loads(executor_ids: &[&str]) -> MinHeap<ExecutorLoad> {
+        executor_ids
+            .iter()
+            .map(|executor_id| {
+                Reverse(ExecutorLoad {
+                    executor_id: executor_id.to_string(),
+                    running_task_count: 0,
+                    capacity_weight: 1,
+                    throttled: false,
+                    cost_order: None,
+                })
+            })
+            .collect()
+    }
+
+    fn unfit_ids(
+        unfit: Vec<(Reverse<ExecutorLoad>, PlacementRejection)>,
+    ) -> Vec<(ExecutorId, PlacementRejection)> {
+        let mut unfit: Vec<_> = unfit
+            .into_iter()
+            .map(|(load, rejection)| (load.0.executor_id, rejection))
+            .collect();
+        unfit.sort_by(|a, b| a.0.cmp(&b.0));
+        unfit
+    }
+
+    #[test]
+    fn test_take_unfit() {
+        let executors: BTreeMap<ExecutorId, ExecutorMetadata> = [
+            ExecutorMetadata {
+                id: "edge".to_string(),
+                max_payload_bytes: Some(1024),
+                ..Default::default()
+            },
+            ExecutorMetadata {
+                id: "slow".to_string(),
+                download_bytes_per_sec: Some(100),
+                ..Default::default()
+            },
+            ExecutorMetadata {
+                id: "large".to_string(),
+                ..Default::default()
+            },
+        ]
+        .into_iter()
+        .map(|executor| (executor.id.clone(), executor))
+        .collect();
+        let task = |size_bytes| Task {
+            content_metadata: ContentMetadata {
+                size_bytes,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut heap = loads(&["edge", "slow", "large"]);
+
+        //  Inputs within every limit go to any executor
+        assert!(
+            take_unfit(&mut heap, &executors, &HashMap::new(), &task(512), Some(10)).is_empty()
+        );
+        assert_eq!(heap.len(), 3);
+
+        //  The input takes too long to download on the slow executor and is over
+        //  the limit of the edge executor
+        let unfit = take_unfit(
+            &mut heap,
+            &executors,
+            &HashMap::new(),
+            &task(2048),
+            Some(10),
+        );
+        assert_eq!(
+            unfit_ids(unfit),
+            vec![
+                ("edge".to_string(), PlacementRejection::InputTooLarge),
+                ("slow".to_string(), PlacementRejection::InputTooLarge),
+            ]
+        );
+        assert_eq!(heap.pop().unwrap().0.executor_id, "large");
+
+        //  Without a download limit the bandwidth doesn't matter
+        let mut heap = loads(&["slow"]);
+        assert!(take_unfit(&mut heap, &executors, &HashMap::new(), &task(2048), None).is_empty());
+    }
+
+    #[test]
+    fn test_take_unfit_graph_version() {
+        let range = |min_version, max_version| internal_api::GraphVersionRange {
+            namespace: "default".to_string(),
+            graph_name: "graph".to_string(),
+            min_version,
+            max_version,
+        };
+        let executors: BTreeMap<ExecutorId, ExecutorMetadata> = [
+            ExecutorMetadata {
+                id: "old".to_string(),
+                graph_versions: vec![range(None, Some(2))],
+                ..Default::default()
+            },
+            ExecutorMetadata {
+                id: "new".to_string(),
+                graph_versions: vec![range(Some(3), None)],
+                ..Default::default()
+            },
+            ExecutorMetadata {
+                id: "any".to_string(),
+                ..Default::default()
+            },
+        ]
+        .into_iter()
+        .map(|executor| (executor.id.clone(), executor))
+        .collect();
+        let task = |graph_name: &str, version| Task {
+            namespace: "default".to_string(),
+            extraction_graph_name: graph_name.to_string(),
+            graph_version: Some(version),
+            ..Default::default()
+        };
+        let mut heap = loads(&["old", "new", "any"]);
+
+        //  Tasks of the new version stay off the executors of the old release
+        let unfit = take_unfit(
+            &mut heap,
+            &executors,
+            &HashMap::new(),
+            &task("graph", 3),
+            None,
+        );
+        assert_eq!(
+            unfit_ids(unfit.clone()),
+            vec![("old".to_string(), PlacementRejection::GraphVersion)]
+        );
+        heap.extend(unfit.into_iter().map(|(load, _)| load));
+
+        let unfit = take_unfit(
+            &mut heap,
+            &executors,
+            &HashMap::new(),
+            &task("graph", 2),
+            None,
+        );
+        assert_eq!(
+            unfit_ids(unfit.clone()),
+            vec![("new".to_string(), PlacementRejection::GraphVersion)]
+        );
+        heap.extend(unfit.into_iter().map(|(load, _)| load));
+
+        //  Versions of other graphs run anywhere
+        assert!(take_unfit(
+            &mut heap,
+            &executors,
+            &HashMap::new(),
+            &task("other", 7),
+            None
+        )
+        .is_empty());
+        assert_eq!(heap.len(), 3);
+    }
+
+    #[test]
+    fn test_take_unfit_gpu() {
+        let executor = ExecutorMetadata {
+            id: "gpu".to_string(),
+            //  A whole GPU and half of a partitioned one
+            gpu_devices: vec![1000, 500],
+            //  Executors that can't take the input are passed over for it first
+            max_payload_bytes: Some(1024),
+            ..Default::default()
+        };
+
+        //  Tasks go to the fullest device they fit on
+        assert_eq!(executor.pack_gpu(&[0, 0], 250), Some(1));
+        assert_eq!(executor.pack_gpu(&[0, 250], 250), Some(1));
+        assert_eq!(executor.pack_gpu(&[0, 500], 250), Some(0));
+        assert_eq!(executor.pack_gpu(&[0, 250], 500), Some(0));
+        assert_eq!(executor.pack_gpu(&[750, 500], 500), None);
+
+        let executors: BTreeMap<ExecutorId, ExecutorMetadata> = [
+            executor,
+            ExecutorMetadata {
+                id: "cpu".to_string(),
+                ..Default::default()
+            },
+        ]
+        .into_iter()
+        .map(|executor| (executor.id.clone(), executor))
+        .collect();
+        let mut occupancy = HashMap::from([("gpu".to_string(), vec![750, 250])]);
+        let task = |millis, size_bytes| Task {
+            gpu_millis: Some(millis),
+            content_metadata: ContentMetadata {
+                size_bytes,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut heap = loads(&["gpu", "cpu"]);
+
+        //  Executors without GPUs never take the task
+        let unfit = take_unfit(&mut heap, &executors, &occupancy, &task(250, 0), None);
+        assert_eq!(
+            unfit_ids(unfit),
+            vec![("cpu".to_string(), PlacementRejection::NoGpu)]
+        );
+        assert_eq!(heap.len(), 1);
+
+        //  No device of the GPU executor has room for half a GPU
+        let unfit = take_unfit(&mut heap, &executors, &occupancy, &task(500, 0), None);
+        assert_eq!(
+            unfit_ids(unfit),
+            vec![("gpu".to_string(), PlacementRejection::NoGpu)]
+        );
+        assert!(heap.is_empty());
+
+        //  Until its tasks finish
+        let mut heap = loads(&["gpu", "cpu"]);
+        occupancy.insert("gpu".to_string(), vec![0, 250]);
+        let unfit = take_unfit(&mut heap, &executors, &occupancy, &task(500, 0), None);
+        assert_eq!(
+            unfit_ids(unfit),
+            vec![("cpu".to_string(), PlacementRejection::NoGpu)]
+        );
+        assert_eq!(heap.pop().unwrap().0.executor_id, "gpu");
+
+        //  Each executor is passed over for the first reason it can't run the
+        //  task
+        let mut heap = loads(&["gpu", "cpu"]);
+        let unfit = take_unfit(&mut heap, &executors, &occupancy, &task(500, 2048), None);
+        assert_eq!(
+            unfit_ids(unfit),
+            vec![
+                ("cpu".to_string(), PlacementRejection::NoGpu),
+                ("gpu".to_string(), PlacementRejection::InputTooLarge),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_order_by_namespace_weight() {
+        let task_ids: Vec<TaskId> = (0..6).map(|i| format!("task{}", i)).collect();
+        let task_namespaces: HashMap<TaskId, String> = task_ids
+            .iter()
+            .enumerate()
+            .map(|(i, task_id)| {
+                let namespace = if i < 4 { "batch" } else { "interactive" };
+                (task_id.clone(), namespace.to_string())
+            })
+            .collect();
+        let task_id_refs: Vec<&TaskId> = task_ids.iter().collect();
+
+        //  Without weights the namespaces take turns
+        let ordered = order_by_namespace_weight(
+            &task_id_refs,
+            &task_namespaces,
+            &SchedulerSettings::default(),
+        );
+        let namespaces: Vec<&str> = ordered
+            .iter()
+            .map(|task_id| task_namespaces[*task_id].as_str())
+            .collect();
+        assert_eq!(
+            namespaces,
+            vec![
+                "batch",
+                "interactive",
+                "batch",
+                "interactive",
+                "batch",
+                "batch"
+            ]
+        );
+
+        let settings = SchedulerSettings {
+            namespace_weights: HashMap::from([("batch".to_string(), 3)]),
+            ..Default::default()
+        };
+        let ordered = order_by_namespace_weight(&task_id_refs, &task_namespaces, &settings);
+        let namespaces: Vec<&str> = ordered
+            .iter()
+            .map(|task_id| task_namespaces[*task_id].as_str())
+            .collect();
+        assert_eq!(
+            namespaces,
+            vec![
+                "batch",
+                "batch",
+                "interactive",
+                "batch",
+                "batch",
+                "interactive"
+            ]
+        );
+        assert_eq!(ordered[0], "task0");
+    }
+
     #[tokio::test]
     #[tracing_test::traced_test]
     async fn test_plan_allocations_empty() -> Result<(), anyhow::Error> {
@@ -417,7 +2045,7 @@ mod tests {
         let result = distributor.plan_allocations(task_ids).await;
         assert!(result.is_ok());
         // should be empty
-        assert_eq!(result.unwrap().0.len(), 0);
+        assert_eq!(result.unwrap().len(), 0);
         Ok(())
     }
 
@@ -440,7 +2068,12 @@ mod tests {
 
         // Add extractors and extractor bindings and ensure that we are creating tasks
         shared_state
-            .register_executor("localhost:8956", "test_executor_id", mock_extractors())
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractors(),
+                Default::default(),
+            )
             .await?;
 
         let content = ContentMetadata {
@@ -469,8 +2102,11 @@ mod tests {
             .await?;
 
         // Verify that the tasks are allocated
-        assert_eq!(result.0.len(), 1);
-        assert_eq!(result.0.get(&task.id).unwrap(), "test_executor_id");
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result.assignments.get(&task.id).unwrap(),
+            "test_executor_id"
+        );
 
         Ok(())
     }
@@ -513,6 +2149,7 @@ mod tests {
                     format!("localhost:{}", 8955 + i).as_str(),
                     format!("text_executor{}", i).as_str(),
                     vec![text_extractor.clone()],
+                    Default::default(),
                 )
                 .await?;
             shared_state
@@ -520,6 +2157,7 @@ mod tests {
                     format!("localhost:{}", 8965 + i).as_str(),
                     format!("json_executor{}", i).as_str(),
                     vec![json_extractor.clone()],
+                    Default::default(),
                 )
                 .await?;
         }
@@ -592,6 +2230,172 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_max_tasks_per_executor() -> Result<(), anyhow::Error> {
+        let config = Arc::new(ServerConfig::default());
+        std::fs::remove_dir_all(config.state_store.clone().path.unwrap()).unwrap();
+        let garbage_collector = crate::garbage_collector::GarbageCollector::new();
+        let shared_state = App::new(
+            config.clone(),
+            None,
+            Arc::clone(&garbage_collector),
+            &config.coordinator_addr,
+            Arc::new(crate::metrics::init_provider()),
+        )
+        .await
+        .unwrap();
+        shared_state.initialize_raft().await.unwrap();
+        shared_state
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractors(),
+                Default::default(),
+            )
+            .await?;
+
+        let mut tasks = Vec::new();
+        let mut content = Vec::new();
+        for i in 1..=3 {
+            //  The last task is of content with a higher priority
+            let content_metadata = ContentMetadata {
+                id: ContentMetadataId::new(&format!("content_id_{}", i)),
+                priority: if i == 3 { 10 } else { 0 },
+                ..Default::default()
+            };
+            tasks.push(create_task(
+                &format!("test-task-{}", i),
+                &mock_extractor().name,
+                "mock-extraction-graph",
+                "test-binding",
+                content_metadata.clone(),
+            ));
+            content.push(content_metadata);
+        }
+        shared_state.create_content_batch(content).await?;
+        let state_changes = shared_state.unprocessed_state_change_events().await?;
+        shared_state
+            .create_tasks(tasks.clone(), state_changes.first().unwrap().id)
+            .await?;
+        shared_state
+            .set_scheduler_settings(internal_api::SchedulerSettings {
+                max_tasks_per_executor: Some(2),
+                ..Default::default()
+            })
+            .await?;
+
+        let distributor = LoadAwareDistributor::new(shared_state.clone());
+        let result = distributor
+            .plan_allocations(tasks.iter().map(|t| t.id.clone()).collect())
+            .await?;
+        assert_eq!(result.len(), 2);
+        assert!(result.assignments.contains_key("test-task-3"));
+
+        //  Overcommitted executors take a burst task over the limit
+        shared_state
+            .set_scheduler_settings(internal_api::SchedulerSettings {
+                max_tasks_per_executor: Some(2),
+                overcommit_factors: HashMap::from([(mock_extractor().name, 1.5)]),
+                ..Default::default()
+            })
+            .await?;
+        let result = distributor
+            .plan_allocations(tasks.iter().map(|t| t.id.clone()).collect())
+            .await?;
+        assert_eq!(result.len(), 3);
+        //  The task of the lowest priority content is the one over the limit
+        assert_eq!(result.burst_tasks.len(), 1);
+        assert!(result.burst_tasks.contains("test-task-2"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_capacity_reservation() -> Result<(), anyhow::Error> {
+        let config = Arc::new(ServerConfig::default());
+        std::fs::remove_dir_all(config.state_store.clone().path.unwrap()).unwrap();
+        let garbage_collector = crate::garbage_collector::GarbageCollector::new();
+        let shared_state = App::new(
+            config.clone(),
+            None,
+            Arc::clone(&garbage_collector),
+            &config.coordinator_addr,
+            Arc::new(crate::metrics::init_provider()),
+        )
+        .await
+        .unwrap();
+        shared_state.initialize_raft().await.unwrap();
+        shared_state
+            .register_executor(
+                "localhost:8956",
+                "test_executor_id",
+                mock_extractors(),
+                Default::default(),
+            )
+            .await?;
+        shared_state
+            .create_extraction_graph(
+                create_test_extraction_graph("reserved-graph", vec!["reserved-policy"]),
+                internal_api::StructuredDataSchema::default(),
+                vec![],
+                internal_api::ExtractionGraphChange::default(),
+            )
+            .await?;
+
+        let mut tasks = Vec::new();
+        let mut content = Vec::new();
+        for i in 1..=4 {
+            let content_metadata = ContentMetadata {
+                id: ContentMetadataId::new(&format!("content_id_{}", i)),
+                ..Default::default()
+            };
+            tasks.push(create_task(
+                &format!("test-task-{}", i),
+                &mock_extractor().name,
+                "mock-extraction-graph",
+                "test-binding",
+                content_metadata.clone(),
+            ));
+            content.push(content_metadata);
+        }
+        tasks[3].namespace = DEFAULT_TEST_NAMESPACE.to_string();
+        tasks[3].extraction_graph_name = "reserved-graph".to_string();
+        shared_state.create_content_batch(content).await?;
+        let state_changes = shared_state.unprocessed_state_change_events().await?;
+        shared_state
+            .create_tasks(tasks.clone(), state_changes.first().unwrap().id)
+            .await?;
+        shared_state
+            .set_scheduler_settings(internal_api::SchedulerSettings {
+                max_tasks_per_executor: Some(2),
+                capacity_reservations: vec![internal_api::CapacityReservation {
+                    namespace: DEFAULT_TEST_NAMESPACE.to_string(),
+                    extraction_graph: Some("reserved-graph".to_string()),
+                    slots: 1,
+                    start: 0,
+                    end: u64::MAX,
+                }],
+                ..Default::default()
+            })
+            .await?;
+
+        //  One of the two slots is held for the reserved graph
+        let distributor = LoadAwareDistributor::new(shared_state.clone());
+        let result = distributor
+            .plan_allocations(tasks[..3].iter().map(|t| t.id.clone()).collect())
+            .await?;
+        assert_eq!(result.len(), 1);
+
+        //  The task of the reserved graph takes the held slot
+        let result = distributor
+            .plan_allocations(tasks.iter().map(|t| t.id.clone()).collect())
+            .await?;
+        assert_eq!(result.len(), 2);
+        assert!(result.assignments.contains_key("test-task-4"));
+        Ok(())
+    }
+
     //  NOTE: This test has been temporarily commented out because there is no good
     // way to call a mut method  on the App state because that returns data
     // wrapped in an Arc. However, here we are calling
@@ -676,7 +2480,7 @@ mod tests {
     //         .await?;
 
     //     // Verify that the tasks are allocated
-    //     assert_eq!(result.clone().0.len(), 200);
+    //     assert_eq!(result.len(), 200);
     //     let mapped_result = result.into_tasks_by_executor();
 
     //     // every executor should have 24 tasks
@@ -756,6 +2560,7 @@ mod tests {
                 text_executors[i - 1].0.as_str(),
                 text_executors[i - 1].1.as_str(),
                 vec![text_extractor.clone()],
+                Default::default(),
             )
         }))
         .await;
@@ -773,6 +2578,7 @@ mod tests {
                 json_executors[i - 1].0.as_str(),
                 json_executors[i - 1].1.as_str(),
                 vec![json_extractor.clone()],
+                Default::default(),
             )
         }))
         .await;
@@ -825,7 +2631,7 @@ mod tests {
         let duration = start.elapsed();
 
         // Verify that the tasks are allocated
-        assert_eq!(result.clone().0.len(), total_tasks);
+        assert_eq!(result.len(), total_tasks);
         println!(
             "Time elapsed in round_robin_distribution() is: {:?}",
             duration
@@ -2,19 +2,40 @@ use std::collections::{HashMap, HashSet};
 
 use crate::state::store::{ExecutorId, TaskId};
 
-#[derive(Debug, Clone)]
-pub struct TaskAllocationPlan(pub HashMap<TaskId, ExecutorId>);
-
-impl From<TaskAllocationPlan> for HashMap<TaskId, ExecutorId> {
-    fn from(plan: TaskAllocationPlan) -> Self {
-        plan.0
-    }
+#[derive(Debug, Clone, Default)]
+pub struct TaskAllocationPlan {
+    pub assignments: HashMap<TaskId, ExecutorId>,
+    /// GPU device of their executor the assigned tasks taking a share of a
+    /// GPU are packed onto, none when no device has room for the task
+    pub gpu_devices: HashMap<TaskId, Option<u32>>,
+    /// Assigned tasks that took burst capacity of their executor, above the
+    /// limit of tasks per executor
+    pub burst_tasks: HashSet<TaskId>,
+    /// Root content and zone of the executor of the assigned tasks of content
+    /// placed by zone
+    pub content_zones: HashMap<TaskId, (String, String)>,
 }
 
 impl TaskAllocationPlan {
+    pub fn is_empty(&self) -> bool {
+        self.assignments.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.assignments.len()
+    }
+
+    /// Adds the assignments of another plan
+    pub fn merge(&mut self, other: TaskAllocationPlan) {
+        self.assignments.extend(other.assignments);
+        self.gpu_devices.extend(other.gpu_devices);
+        self.burst_tasks.extend(other.burst_tasks);
+        self.content_zones.extend(other.content_zones);
+    }
+
     pub fn into_tasks_by_executor(self) -> HashMap<ExecutorId, HashSet<TaskId>> {
         let mut tasks_by_executor: HashMap<ExecutorId, HashSet<TaskId>> = HashMap::new();
-        for (task_id, executor_id) in self.0 {
+        for (task_id, executor_id) in self.assignments {
             tasks_by_executor
                 .entry(executor_id)
                 .or_default()
@@ -0,0 +1,294 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use indexify_internal_api::{Task, ZonePlacement};
+
+use super::planner::load_aware_distributor::{place, PlacementState};
+use crate::state::store::{ExecutorId, ExtractorName, TaskId};
+
+/// Horizon of a simulation when the request has none
+pub const DEFAULT_HORIZON_SECS: u64 = 3600;
+
+/// Seconds between two rounds of placement of a simulation
+const STEP_SECS: u64 = 60;
+
+/// Tasks of an extraction policy created while the scheduler is simulated
+#[derive(Debug, Clone)]
+pub struct Arrivals {
+    /// Task the created tasks are copies of
+    pub task: Task,
+    pub latency_tolerant: bool,
+    pub zone_placement: ZonePlacement,
+    pub per_hour: f64,
+}
+
+/// The predicted queue of the tasks of an extractor
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatedQueue {
+    pub extractor: ExtractorName,
+    pub executors: usize,
+    pub unallocated: u64,
+    pub queued: u64,
+    pub predicted_queued: Option<u64>,
+    pub predicted_wait_secs: Option<u64>,
+}
+
+/// How long the tasks of an extractor run, from the tasks its executors run
+/// and the tasks they finished in the last hour. Unknown when they finished
+/// none.
+pub fn run_secs(running: u64, completed_last_hour: u64) -> Option<f64> {
+    (completed_last_hour > 0).then(|| running.max(1) as f64 * 3600.0 / completed_last_hour as f64)
+}
+
+/// A run of the planner over time on a copy of the placement state, with the
+/// executors and tasks a hypothetical change adds to the cluster
+pub(crate) struct Simulation {
+    pub(crate) state: PlacementState,
+    /// Tasks running on each executor
+    pub(crate) running: Vec<(ExecutorId, Task)>,
+    pub(crate) arrivals: Vec<Arrivals>,
+    /// How long the tasks of each extractor run
+    pub(crate) run_secs: HashMap<ExtractorName, f64>,
+    pub(crate) horizon_secs: u64,
+}
+
+impl Simulation {
+    /// Places the tasks every minute of the horizon the way the planner
+    /// places them, with the tasks created since the last round. Running
+    /// tasks finish after the run time of their extractor, the ones running
+    /// now spread over one run time.
+    ///
+    /// Tasks of extractors whose run time is unknown never finish, and their
+    /// queue is only predicted right after the first round.
+    pub fn run(self) -> Vec<SimulatedQueue> {
+        let Simulation {
+            mut state,
+            running,
+            arrivals,
+            run_secs,
+            horizon_secs,
+        } = self;
+        let mut extractors: BTreeSet<ExtractorName> =
+            state.executors_by_extractor.keys().cloned().collect();
+        extractors.extend(state.tasks_by_extractor.keys().cloned());
+        extractors.extend(
+            arrivals
+                .iter()
+                .map(|arrivals| arrivals.task.extractor.clone()),
+        );
+        let unallocated = queue_lengths(&state);
+
+        // Tasks finishing at each second, with their executor
+        let mut finishing: BTreeMap<u64, Vec<(ExecutorId, Task)>> = BTreeMap::new();
+        let mut running_by_extractor: HashMap<ExtractorName, Vec<(ExecutorId, Task)>> =
+            HashMap::new();
+        for (executor_id, task) in running {
+            running_by_extractor
+                .entry(task.extractor.clone())
+                .or_default()
+                .push((executor_id, task));
+        }
+        for (extractor, running) in running_by_extractor {
+            let Some(secs) = run_secs.get(&extractor) else {
+                continue;
+            };
+            let count = running.len();
+            for (i, task) in running.into_iter().enumerate() {
+                let at = (secs * (i + 1) as f64 / count as f64).ceil() as u64;
+                finishing.entry(at).or_default().push(task);
+            }
+        }
+
+        // Tasks waiting for an executor with the time they were created, and
+        // the waits of the placed tasks by extractor
+        let mut waiting: HashMap<TaskId, (Task, u64)> = state
+            .tasks
+            .values()
+            .map(|task| (task.id.clone(), (task.clone(), 0)))
+            .collect();
+        let mut waits: HashMap<ExtractorName, (u64, u64)> = HashMap::new();
+        let mut due = vec![0.0; arrivals.len()];
+        let mut created_tasks = 0;
+        let mut queued = None;
+        let mut now = 0;
+        loop {
+            while let Some(entry) = finishing.first_entry() {
+                if *entry.key() > now {
+                    break;
+                }
+                for (executor_id, task) in entry.remove() {
+                    state.finish_task(&executor_id, &task);
+                }
+            }
+            if now > 0 {
+                for (stream, due) in arrivals.iter().zip(due.iter_mut()) {
+                    *due += stream.per_hour * STEP_SECS as f64 / 3600.0;
+                    while *due >= 1.0 {
+                        *due -= 1.0;
+                        created_tasks += 1;
+                        let mut task = stream.task.clone();
+                        task.id = format!("simulated-task-{}", created_tasks);
+                        task.content_metadata.id.id = task.id.clone();
+                        waiting.insert(task.id.clone(), (task.clone(), now));
+                        state.add_task(task, stream.latency_tolerant, stream.zone_placement);
+                    }
+                }
+            }
+            let (plan, _) = place(&mut state, now);
+            for (task_id, executor_id) in plan.assignments {
+                let Some((mut task, created_at)) = waiting.remove(&task_id) else {
+                    continue;
+                };
+                let wait = waits.entry(task.extractor.clone()).or_default();
+                wait.0 += now - created_at;
+                wait.1 += 1;
+                if let Some(secs) = run_secs.get(&task.extractor) {
+                    task.gpu_device = plan.gpu_devices.get(&task_id).copied().flatten();
+                    finishing
+                        .entry(now + secs.ceil().max(1.0) as u64)
+                        .or_default()
+                        .push((executor_id, task));
+                }
+            }
+            if queued.is_none() {
+                queued = Some(queue_lengths(&state));
+            }
+            if now >= horizon_secs {
+                break;
+            }
+            now = (now + STEP_SECS).min(horizon_secs);
+        }
+
+        // Tasks still waiting count with the time they waited so far
+        for (task, created_at) in waiting.values() {
+            let wait = waits.entry(task.extractor.clone()).or_default();
+            wait.0 += horizon_secs - created_at;
+            wait.1 += 1;
+        }
+        let queued = queued.unwrap_or_default();
+        let predicted_queued = queue_lengths(&state);
+        extractors
+            .into_iter()
+            .map(|extractor| {
+                let known = run_secs.contains_key(&extractor);
+                SimulatedQueue {
+                    executors: state
+                        .executors_by_extractor
+                        .get(&extractor)
+                        .map_or(0, |executors| executors.len()),
+                    unallocated: unallocated.get(&extractor).copied().unwrap_or_default(),
+                    queued: queued.get(&extractor).copied().unwrap_or_default(),
+                    predicted_queued: known.then(|| {
+                        predicted_queued
+                            .get(&extractor)
+                            .copied()
+                            .unwrap_or_default()
+                    }),
+                    predicted_wait_secs: known.then(|| {
+                        waits
+                            .get(&extractor)
+                            .filter(|(_, count)| *count > 0)
+                            .map_or(0, |(total, count)| total / count)
+                    }),
+                    extractor,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Tasks waiting for an executor by extractor
+fn queue_lengths(state: &PlacementState) -> HashMap<ExtractorName, u64> {
+    state
+        .tasks_by_extractor
+        .iter()
+        .map(|(extractor, task_ids)| (extractor.clone(), task_ids.len() as u64))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use indexify_internal_api::{ExecutorMetadata, SchedulerSettings};
+
+    use super::*;
+
+    fn simulation(executors: &[(&str, u32)], queued: usize) -> Simulation {
+        let mut state = PlacementState {
+            settings: SchedulerSettings {
+                max_tasks_per_executor: Some(2),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let extractors = ["extractor".to_string()];
+        let mut running = Vec::new();
+        for (id, capacity_weight) in executors {
+            state.add_executor(
+                ExecutorMetadata {
+                    id: id.to_string(),
+                    capacity_weight: Some(*capacity_weight),
+                    ..Default::default()
+                },
+                &extractors,
+            );
+        }
+        //  The first executor is full
+        state.running_tasks.insert(executors[0].0.to_string(), 2);
+        for i in 0..2 {
+            let task = Task {
+                id: format!("running-{}", i),
+                extractor: "extractor".to_string(),
+                ..Default::default()
+            };
+            running.push((executors[0].0.to_string(), task));
+        }
+        let task = Task {
+            extractor: "extractor".to_string(),
+            ..Default::default()
+        };
+        for i in 0..queued {
+            let mut task = task.clone();
+            task.id = format!("queued-{}", i);
+            state.add_task(task, false, ZonePlacement::Any);
+        }
+        Simulation {
+            state,
+            running,
+            arrivals: vec![Arrivals {
+                task,
+                latency_tolerant: false,
+                zone_placement: ZonePlacement::Any,
+                per_hour: 100.0,
+            }],
+            //  2 running tasks and 100 finished in the last hour
+            run_secs: HashMap::from([("extractor".to_string(), run_secs(2, 100).unwrap())]),
+            horizon_secs: DEFAULT_HORIZON_SECS,
+        }
+    }
+
+    #[test]
+    fn test_simulate() {
+        //  Without a change the executor is full and the queue stays
+        let queues = simulation(&[("executor", 1)], 10).run();
+        assert_eq!(queues.len(), 1);
+        assert_eq!(queues[0].executors, 1);
+        assert_eq!(queues[0].unallocated, 10);
+        assert_eq!(queues[0].queued, 10);
+        assert!(queues[0].predicted_queued.unwrap() >= 10);
+        assert!(queues[0].predicted_wait_secs.unwrap() > 0);
+
+        //  An added executor of twice the size takes 4 tasks and drains the
+        //  queue
+        let queues = simulation(&[("executor", 1), ("added", 2)], 10).run();
+        assert_eq!(queues[0].executors, 2);
+        assert_eq!(queues[0].queued, 6);
+        assert_eq!(queues[0].predicted_queued, Some(0));
+
+        //  The queue of an extractor whose run time is unknown isn't predicted
+        let mut simulation = simulation(&[("executor", 1)], 10);
+        simulation.run_secs.clear();
+        let queues = simulation.run();
+        assert_eq!(queues[0].queued, 10);
+        assert_eq!(queues[0].predicted_queued, None);
+        assert_eq!(queues[0].predicted_wait_secs, None);
+    }
+}
@@ -0,0 +1,269 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use indexify_internal_api as internal_api;
+use indexify_proto::indexify_coordinator;
+use internal_api::SchedulerSettings;
+
+use crate::state::store::{ExtractionPolicyId, TaskId};
+
+/// Tasks sent to an executor in one heartbeat, on their own or in bundles of
+/// tasks of the same extraction policy
+#[derive(Debug, Default)]
+pub struct TaskDispatch {
+    pub tasks: Vec<internal_api::Task>,
+    pub bundles: Vec<Vec<internal_api::Task>>,
+}
+
+impl TaskDispatch {
+    pub fn into_response(self, executor_id: &str) -> indexify_coordinator::HeartbeatResponse {
+        indexify_coordinator::HeartbeatResponse {
+            executor_id: executor_id.to_string(),
+            tasks: self.tasks.into_iter().map(Into::into).collect(),
+            bundles: self
+                .bundles
+                .into_iter()
+                .map(|tasks| indexify_coordinator::TaskBundle {
+                    extraction_policy_id: tasks[0].extraction_policy_id.clone(),
+                    tasks: tasks.into_iter().map(Into::into).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A bundle that may still take more tasks of its extraction policy
+#[derive(Debug)]
+struct OpenBundle {
+    tasks: Vec<internal_api::Task>,
+    bytes: u64,
+    /// When the bundle is sent even if it isn't full
+    flush_at: Instant,
+}
+
+/// Groups the small tasks of an extraction policy assigned to an executor
+/// into bundles, so that executors of lightweight extractors don't pay the
+/// overhead of a task for each of them. Kept for the lifetime of a heartbeat
+/// stream: a task joins a bundle once and stays in it, a bundle is sent once
+/// it's full or at its flush deadline, and sent bundles are sent again in
+/// every heartbeat while their tasks are assigned to the executor.
+#[derive(Debug, Default)]
+pub struct TaskBundler {
+    open: BTreeMap<ExtractionPolicyId, OpenBundle>,
+    sent: Vec<Vec<internal_api::Task>>,
+}
+
+impl TaskBundler {
+    /// Takes the tasks assigned to the executor. New tasks join the open
+    /// bundle of their extraction policy, tasks no longer assigned are
+    /// dropped from their bundles.
+    pub fn add(
+        &mut self,
+        tasks: Vec<internal_api::Task>,
+        settings: &SchedulerSettings,
+        now: Instant,
+    ) {
+        let task_ids: HashSet<&TaskId> = tasks.iter().map(|task| &task.id).collect();
+        for bundle in &mut self.sent {
+            bundle.retain(|task| task_ids.contains(&task.id));
+        }
+        self.sent.retain(|bundle| !bundle.is_empty());
+        for bundle in self.open.values_mut() {
+            bundle.tasks.retain(|task| task_ids.contains(&task.id));
+            bundle.bytes = bundle
+                .tasks
+                .iter()
+                .map(|task| task.content_metadata.size_bytes)
+                .sum();
+        }
+        self.open.retain(|_, bundle| !bundle.tasks.is_empty());
+
+        let known: HashSet<TaskId> = self
+            .sent
+            .iter()
+            .flatten()
+            .chain(self.open.values().flat_map(|bundle| &bundle.tasks))
+            .map(|task| task.id.clone())
+            .collect();
+        let new_tasks = tasks.into_iter().filter(|task| !known.contains(&task.id));
+        let Some(max_tasks) = settings.max_bundle_tasks.filter(|max| *max > 1) else {
+            self.sent.extend(new_tasks.map(|task| vec![task]));
+            return;
+        };
+        let max_bytes = settings.max_bundle_bytes.unwrap_or(u64::MAX);
+        let latency = Duration::from_millis(settings.max_bundle_latency_ms.unwrap_or(0) as u64);
+        for task in new_tasks {
+            let size_bytes = task.content_metadata.size_bytes;
+            if size_bytes > max_bytes {
+                self.sent.push(vec![task]);
+                continue;
+            }
+            let policy_id = task.extraction_policy_id.clone();
+            if self
+                .open
+                .get(&policy_id)
+                .is_some_and(|bundle| bundle.bytes + size_bytes > max_bytes)
+            {
+                self.seal(&policy_id);
+            }
+            let bundle = self.open.entry(policy_id.clone()).or_insert(OpenBundle {
+                tasks: Vec::new(),
+                bytes: 0,
+                flush_at: now + latency,
+            });
+            bundle.bytes += size_bytes;
+            bundle.tasks.push(task);
+            if bundle.tasks.len() == max_tasks as usize {
+                self.seal(&policy_id);
+            }
+        }
+        self.flush(now);
+    }
+
+    /// Sends the open bundles whose flush deadline passed, returns whether
+    /// there were any
+    pub fn flush(&mut self, now: Instant) -> bool {
+        let due: Vec<ExtractionPolicyId> = self
+            .open
+            .iter()
+            .filter(|(_, bundle)| bundle.flush_at <= now)
+            .map(|(policy_id, _)| policy_id.clone())
+            .collect();
+        for policy_id in &due {
+            self.seal(policy_id);
+        }
+        !due.is_empty()
+    }
+
+    /// The earliest flush deadline of the open bundles
+    pub fn next_flush(&self) -> Option<Instant> {
+        self.open.values().map(|bundle| bundle.flush_at).min()
+    }
+
+    /// Tasks and bundles to send to the executor
+    pub fn dispatch(&self) -> TaskDispatch {
+        let mut dispatch = TaskDispatch::default();
+        for bundle in &self.sent {
+            match bundle.as_slice() {
+                [task] => dispatch.tasks.push(task.clone()),
+                _ => dispatch.bundles.push(bundle.clone()),
+            }
+        }
+        dispatch
+    }
+
+    fn seal(&mut self, policy_id: &ExtractionPolicyId) {
+        if let Some(bundle) = self.open.remove(policy_id) {
+            self.sent.push(bundle.tasks);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: &str, extraction_policy_id: &str, size_bytes: u64) -> internal_api::Task {
+        internal_api::Task {
+            id: id.to_string(),
+            extraction_policy_id: extraction_policy_id.to_string(),
+            content_metadata: internal_api::ContentMetadata {
+                size_bytes,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn ids(tasks: &[internal_api::Task]) -> Vec<&str> {
+        tasks.iter().map(|task| task.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_bundle_tasks() {
+        let settings = SchedulerSettings {
+            max_bundle_tasks: Some(3),
+            max_bundle_bytes: Some(1000),
+            max_bundle_latency_ms: Some(100),
+            ..Default::default()
+        };
+        let tasks = vec![
+            task("chunk_1", "chunking", 10),
+            task("chunk_2", "chunking", 10),
+            task("embed_1", "embedding", 10),
+            task("chunk_3", "chunking", 10),
+            task("chunk_4", "chunking", 10),
+            task("chunk_large", "chunking", 5000),
+        ];
+        let mut bundler = TaskBundler::default();
+        let start = Instant::now();
+
+        //  Full bundles and large tasks go right away, the others wait for more
+        //  tasks
+        bundler.add(tasks.clone(), &settings, start);
+        let dispatch = bundler.dispatch();
+        assert_eq!(ids(&dispatch.tasks), vec!["chunk_large"]);
+        assert_eq!(dispatch.bundles.len(), 1);
+        assert_eq!(
+            ids(&dispatch.bundles[0]),
+            vec!["chunk_1", "chunk_2", "chunk_3"]
+        );
+        assert_eq!(
+            bundler.next_flush(),
+            Some(start + Duration::from_millis(100))
+        );
+
+        //  New tasks join the open bundle of their policy, tasks that finished
+        //  leave their bundle
+        bundler.add(
+            vec![
+                task("chunk_4", "chunking", 10),
+                task("embed_1", "embedding", 10),
+                task("chunk_large", "chunking", 5000),
+                task("chunk_5", "chunking", 10),
+            ],
+            &settings,
+            start + Duration::from_millis(50),
+        );
+        let dispatch = bundler.dispatch();
+        assert_eq!(ids(&dispatch.tasks), vec!["chunk_large"]);
+        assert!(dispatch.bundles.is_empty());
+
+        //  Open bundles are sent at their flush deadline, between heartbeats
+        assert!(!bundler.flush(start + Duration::from_millis(99)));
+        assert!(bundler.flush(start + Duration::from_millis(100)));
+        assert_eq!(bundler.next_flush(), None);
+        let dispatch = bundler.dispatch();
+        assert_eq!(ids(&dispatch.tasks), vec!["chunk_large", "embed_1"]);
+        assert_eq!(ids(&dispatch.bundles[0]), vec!["chunk_4", "chunk_5"]);
+
+        //  Sent bundles keep their tasks, new tasks open a new bundle
+        bundler.add(
+            vec![
+                task("chunk_4", "chunking", 10),
+                task("chunk_5", "chunking", 10),
+                task("chunk_6", "chunking", 10),
+            ],
+            &settings,
+            start + Duration::from_millis(150),
+        );
+        let dispatch = bundler.dispatch();
+        assert!(dispatch.tasks.is_empty());
+        assert_eq!(dispatch.bundles.len(), 1);
+        assert_eq!(ids(&dispatch.bundles[0]), vec!["chunk_4", "chunk_5"]);
+        assert_eq!(
+            bundler.next_flush(),
+            Some(start + Duration::from_millis(250))
+        );
+
+        //  Without a bundle size tasks go on their own
+        let mut bundler = TaskBundler::default();
+        bundler.add(tasks, &Default::default(), start);
+        let dispatch = bundler.dispatch();
+        assert_eq!(dispatch.tasks.len(), 6);
+        assert!(dispatch.bundles.is_empty());
+        assert_eq!(bundler.next_flush(), None);
+    }
+}
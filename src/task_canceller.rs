@@ -0,0 +1,266 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Result;
+use futures::{StreamExt, TryStreamExt};
+use indexify_internal_api::{self as internal_api, CancelTasksJob, CancelTasksJobState};
+use tracing::{error, info};
+
+use crate::{state::SharedState, utils::timestamp_secs};
+
+/// Number of tasks cancelled by a single raft write
+const CANCEL_PAGE_SIZE: usize = 100;
+
+/// Number of pages of tasks being cancelled at the same time. The raft writes
+/// of a page overlap with the writes of the other pages in flight instead of
+/// waiting for every earlier page to finish.
+const MAX_PAGES_IN_FLIGHT: usize = 4;
+
+/// Time after which a running job whose progress isn't recorded anymore is
+/// taken for the job of a coordinator that stopped. A running job records its
+/// progress after every page.
+pub const STALE_JOB_SECS: u64 = 10 * 60;
+
+/// Selects the unfinished tasks of a namespace that a bulk cancellation
+/// applies to. Unset fields match every task.
+#[derive(Debug, Clone, Default)]
+pub struct CancelTasksFilter {
+    pub extraction_graph: Option<String>,
+    pub labels_eq: HashMap<String, String>,
+    /// Inclusive lower bound on the creation time of the task's content
+    pub created_after: Option<i64>,
+    /// Exclusive upper bound on the creation time of the task's content
+    pub created_before: Option<i64>,
+}
+
+impl CancelTasksFilter {
+    pub fn matches(&self, task: &internal_api::Task) -> bool {
+        if let Some(extraction_graph) = &self.extraction_graph {
+            if &task.extraction_graph_name != extraction_graph {
+                return false;
+            }
+        }
+        if !self
+            .labels_eq
+            .iter()
+            .all(|(key, value)| task.content_metadata.labels.get(key) == Some(value))
+        {
+            return false;
+        }
+        let created_at = task.content_metadata.created_at;
+        self.created_after.map_or(true, |after| created_at >= after) &&
+            self.created_before
+                .map_or(true, |before| created_at < before)
+    }
+}
+
+/// Time a cancel tasks job is kept once its progress was last recorded. A
+/// running job whose coordinator stopped is aborted first, see
+/// [`TaskCanceller::abort_stale_jobs`].
+pub const JOB_RETENTION_SECS: u64 = 24 * 60 * 60;
+
+/// Runs bulk cancellations in the background. Matching tasks are marked as
+/// cancelled and removed from the executors they were assigned to. Jobs and
+/// their progress are recorded in the state, they can be read from any
+/// coordinator.
+pub struct TaskCanceller {
+    shared_state: SharedState,
+    /// Jobs this coordinator is running
+    running: Mutex<HashSet<String>>,
+}
+
+impl TaskCanceller {
+    pub fn new(shared_state: SharedState) -> Arc<Self> {
+        Arc::new(Self {
+            shared_state,
+            running: Mutex::new(HashSet::new()),
+        })
+    }
+
+    pub async fn start(
+        self: &Arc<Self>,
+        namespace: &str,
+        filter: CancelTasksFilter,
+    ) -> Result<String> {
+        let now = timestamp_secs();
+        let job = CancelTasksJob {
+            id: nanoid::nanoid!(16),
+            namespace: namespace.to_string(),
+            state: CancelTasksJobState::Running,
+            matched_tasks: 0,
+            cancelled_tasks: 0,
+            error: None,
+            started_at: now,
+            updated_at: now,
+        };
+        self.shared_state.set_cancel_tasks_job(job.clone()).await?;
+        let job_id = job.id.clone();
+        self.running.lock().unwrap().insert(job_id.clone());
+
+        info!(
+            "starting cancel tasks job {} in namespace {}: {:?}",
+            job_id, namespace, filter
+        );
+        let canceller = self.clone();
+        tokio::spawn(async move {
+            let mut job = job;
+            let result = canceller.run(&mut job, &filter).await;
+            match result {
+                Ok(()) => job.state = CancelTasksJobState::Completed,
+                Err(e) => {
+                    error!("cancel tasks job {} aborted: {}", job.id, e);
+                    job.state = CancelTasksJobState::Aborted;
+                    job.error = Some(e.to_string());
+                }
+            }
+            job.updated_at = timestamp_secs();
+            let job_id = job.id.clone();
+            if let Err(e) = canceller.shared_state.set_cancel_tasks_job(job).await {
+                error!(
+                    "unable to record the end of cancel tasks job {}: {}",
+                    job_id, e
+                );
+            }
+            canceller.running.lock().unwrap().remove(&job_id);
+        });
+        Ok(job_id)
+    }
+
+    /// Aborts the running jobs that no coordinator recorded progress of for
+    /// [`STALE_JOB_SECS`] and that this coordinator isn't running. Their
+    /// coordinator stopped, the tasks they didn't get to are left as they
+    /// are. Returns the number of jobs aborted.
+    pub async fn abort_stale_jobs(&self, now: u64) -> Result<usize> {
+        let stale_jobs: Vec<CancelTasksJob> = {
+            let running = self.running.lock().unwrap();
+            self.shared_state
+                .list_cancel_tasks_jobs()?
+                .into_iter()
+                .filter(|job| {
+                    job.state == CancelTasksJobState::Running &&
+                        job.updated_at.saturating_add(STALE_JOB_SECS) <= now &&
+                        !running.contains(&job.id)
+                })
+                .collect()
+        };
+        let aborted = stale_jobs.len();
+        for mut job in stale_jobs {
+            info!(
+                "aborting cancel tasks job {}, its coordinator stopped",
+                job.id
+            );
+            job.state = CancelTasksJobState::Aborted;
+            job.error = Some("the coordinator running the job stopped".to_string());
+            job.updated_at = now;
+            self.shared_state.set_cancel_tasks_job(job).await?;
+        }
+        Ok(aborted)
+    }
+
+    /// Forgets the jobs whose progress was last recorded longer ago than
+    /// [`JOB_RETENTION_SECS`]. Returns the number of jobs forgotten.
+    pub async fn evict_jobs(&self, now: u64) -> Result<usize> {
+        let job_ids: Vec<String> = self
+            .shared_state
+            .list_cancel_tasks_jobs()?
+            .into_iter()
+            .filter(|job| job.updated_at.saturating_add(JOB_RETENTION_SECS) <= now)
+            .map(|job| job.id)
+            .collect();
+        if !job_ids.is_empty() {
+            info!("evicting {} cancel tasks jobs", job_ids.len());
+            self.shared_state
+                .delete_cancel_tasks_jobs(job_ids.clone())
+                .await?;
+        }
+        Ok(job_ids.len())
+    }
+
+    async fn run(&self, job: &mut CancelTasksJob, filter: &CancelTasksFilter) -> Result<()> {
+        let matches = {
+            let filter = filter.clone();
+            move |task: &internal_api::Task| filter.matches(task)
+        };
+        let tasks: Vec<internal_api::Task> = self
+            .shared_state
+            .stream_unfinished_tasks(&job.namespace, matches)
+            .try_collect()
+            .await?;
+        job.matched_tasks = tasks.len() as u64;
+        job.updated_at = timestamp_secs();
+        self.shared_state.set_cancel_tasks_job(job.clone()).await?;
+
+        let mut pages = futures::stream::iter(
+            tasks
+                .chunks(CANCEL_PAGE_SIZE)
+                .map(|page| self.cancel_page(page)),
+        )
+        .buffer_unordered(MAX_PAGES_IN_FLIGHT);
+        while let Some(cancelled) = pages.next().await {
+            job.cancelled_tasks += cancelled?;
+            job.updated_at = timestamp_secs();
+            self.shared_state.set_cancel_tasks_job(job.clone()).await?;
+        }
+        Ok(())
+    }
+
+    /// Cancels a page of tasks in a single raft write
+    async fn cancel_page(&self, tasks: &[internal_api::Task]) -> Result<u64> {
+        let tasks: Vec<internal_api::Task> = tasks
+            .iter()
+            .cloned()
+            .map(|mut task| {
+                task.outcome = internal_api::TaskOutcome::Cancelled;
+                task
+            })
+            .collect();
+        let cancelled = tasks.len() as u64;
+        self.shared_state.finalize_tasks(tasks).await?;
+        Ok(cancelled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use indexify_internal_api::{ContentMetadata, Task};
+
+    use super::CancelTasksFilter;
+
+    fn task(graph: &str, labels: &[(&str, &str)], created_at: i64) -> Task {
+        Task {
+            extraction_graph_name: graph.to_string(),
+            content_metadata: ContentMetadata {
+                labels: labels
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                created_at,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cancel_tasks_filter() {
+        let filter = CancelTasksFilter::default();
+        assert!(filter.matches(&task("graph1", &[], 10)));
+
+        let filter = CancelTasksFilter {
+            extraction_graph: Some("graph1".to_string()),
+            labels_eq: HashMap::from([("tier".to_string(), "gold".to_string())]),
+            created_after: Some(10),
+            created_before: Some(20),
+        };
+        assert!(filter.matches(&task("graph1", &[("tier", "gold")], 10)));
+        assert!(!filter.matches(&task("graph2", &[("tier", "gold")], 10)));
+        assert!(!filter.matches(&task("graph1", &[("tier", "silver")], 10)));
+        assert!(!filter.matches(&task("graph1", &[], 10)));
+        assert!(!filter.matches(&task("graph1", &[("tier", "gold")], 9)));
+        assert!(!filter.matches(&task("graph1", &[("tier", "gold")], 20)));
+    }
+}
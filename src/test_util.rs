@@ -73,6 +73,13 @@ pub mod db_utils {
                     "test_table".to_string(),
                 )]),
                 content_source: internal_api::ExtractionPolicyContentSource::Ingestion,
+                output_schema: None,
+                source_output: None,
+                max_tasks_per_second: None,
+                expensive_warmup: false,
+                min_warm_executors: None,
+                gpu_millis: None,
+                slo: None,
             };
             extraction_policies.push(ep);
         }
@@ -81,6 +88,9 @@ pub mod db_utils {
             namespace: DEFAULT_TEST_NAMESPACE.to_string(),
             name: graph_name.to_string(),
             extraction_policies,
+            max_active_content: None,
+            latency_tolerant: false,
+            zone_placement: Default::default(),
         }
     }
 
@@ -120,6 +130,13 @@ pub mod db_utils {
                         )
                     }
                 },
+                output_schema: None,
+                source_output: None,
+                max_tasks_per_second: None,
+                expensive_warmup: false,
+                min_warm_executors: None,
+                gpu_millis: None,
+                slo: None,
             };
             extraction_policies.push(ep);
         }
@@ -128,6 +145,9 @@ pub mod db_utils {
             namespace: DEFAULT_TEST_NAMESPACE.to_string(),
             name: graph_name.to_string(),
             extraction_policies,
+            max_active_content: None,
+            latency_tolerant: false,
+            zone_placement: Default::default(),
         }
     }
     pub fn mock_extractor() -> internal_api::ExtractorDescription {
@@ -52,6 +52,7 @@ impl RaftTestCluster {
                 raft_port: port + 1,
                 state_store: StateStoreConfig {
                     path: Some(format!("/tmp/indexify-test/raft/{}/{}", append, i)),
+                    ..Default::default()
                 },
                 seed_node: seed_node.clone(),
                 ..Default::default()
@@ -82,6 +83,7 @@ impl RaftTestCluster {
                     "/tmp/indexify-test/raft/{}/{}",
                     self.append, new_node_id
                 )),
+                ..Default::default()
             },
             seed_node,
             ..Default::default()
@@ -97,7 +99,13 @@ impl RaftTestCluster {
         )
         .await?;
         let coordinator_client = CoordinatorClient::new(Arc::clone(&new_node_config));
-        let coordinator = Coordinator::new(shared_state, coordinator_client, garbage_collector);
+        let coordinator = Coordinator::new(
+            shared_state,
+            coordinator_client,
+            garbage_collector,
+            None,
+            Default::default(),
+        );
 
         // Add the new node to the map
         self.nodes.insert(new_node_id as u64, coordinator);
@@ -231,7 +239,13 @@ impl RaftTestCluster {
             .await?;
             let coordinator_client = CoordinatorClient::new(Arc::clone(&config));
             let garbage_collector = GarbageCollector::new();
-            let coordinator = Coordinator::new(shared_state, coordinator_client, garbage_collector);
+            let coordinator = Coordinator::new(
+                shared_state,
+                coordinator_client,
+                garbage_collector,
+                None,
+                Default::default(),
+            );
             nodes.insert(config.node_id, coordinator);
         }
         Ok(Self {
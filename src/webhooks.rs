@@ -0,0 +1,102 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::utils::timestamp_secs;
+
+/// Headers of a delivery. The id is the same for every attempt of a delivery,
+/// so receivers can drop retries they already got.
+const DELIVERY_HEADER: &str = "X-Indexify-Delivery";
+const ATTEMPT_HEADER: &str = "X-Indexify-Attempt";
+const SIGNATURE_HEADER: &str = "X-Indexify-Signature";
+
+/// Adds a JSON body to a request to an endpoint the coordinator delivers to,
+/// with the id and attempt of the delivery, signed with the secret of the
+/// endpoint when it has one
+pub fn delivery(
+    request: reqwest::RequestBuilder,
+    delivery_id: &str,
+    attempt: u32,
+    secret: Option<&str>,
+    body: Vec<u8>,
+) -> reqwest::RequestBuilder {
+    let mut request = request
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(DELIVERY_HEADER, delivery_id)
+        .header(ATTEMPT_HEADER, attempt.to_string());
+    if let Some(secret) = secret {
+        request = request.header(SIGNATURE_HEADER, sign(secret, timestamp_secs(), &body));
+    }
+    request.body(body)
+}
+
+/// Signature header of a delivery: the time it was signed at and the
+/// HMAC-SHA256 of the time and body, joined by a dot, with the secret of the
+/// endpoint. Receivers recompute it and reject old times to stop replays.
+fn sign(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac takes keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!(
+        "t={},v1={}",
+        timestamp,
+        hex::encode(mac.finalize().into_bytes())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_delivery() {
+        //  The time and the body are signed, joined by a dot
+        let signature = sign("Jefe", 1700000000, b"what do ya want for nothing?");
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"Jefe").unwrap();
+        mac.update(b"1700000000.what do ya want for nothing?");
+        assert_eq!(
+            signature,
+            format!(
+                "t=1700000000,v1={}",
+                hex::encode(mac.finalize().into_bytes())
+            )
+        );
+        assert_ne!(
+            signature,
+            sign("other", 1700000000, b"what do ya want for nothing?")
+        );
+    }
+
+    #[test]
+    fn test_delivery_headers() {
+        let client = reqwest::Client::new();
+        let request = delivery(
+            client.post("http://localhost:8080/usage"),
+            "metering-0-3600",
+            2,
+            Some("secret"),
+            b"[]".to_vec(),
+        )
+        .build()
+        .unwrap();
+        let headers = request.headers();
+        assert_eq!(headers[DELIVERY_HEADER], "metering-0-3600");
+        assert_eq!(headers[ATTEMPT_HEADER], "2");
+        assert!(headers[SIGNATURE_HEADER]
+            .to_str()
+            .unwrap()
+            .starts_with("t="));
+
+        let request = delivery(
+            client.post("http://localhost:8080/usage"),
+            "metering-0-3600",
+            1,
+            None,
+            b"[]".to_vec(),
+        )
+        .build()
+        .unwrap();
+        assert!(request.headers().get(SIGNATURE_HEADER).is_none());
+    }
+}